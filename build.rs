@@ -1,11 +1,11 @@
-#![cfg_attr(feature = "cargo-clippy", allow(clippy::all))]
+#![allow(clippy::all)]
 
 use std::env;
 use std::process::Command;
 
 fn main() {
     static PACKAGE: &'static str = "hyperx";
-    let msrv = vec![1, 46];
+    let msrv = vec![1, 70];
 
     static VERSION: &'static str = env!("CARGO_PKG_VERSION");
     static M_V: &'static str = "minimum supported rust version (MSRV)";
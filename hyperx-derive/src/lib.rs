@@ -0,0 +1,305 @@
+#![deny(missing_docs)]
+
+//! Derive macro for the hyper*x* `Header` trait.
+//!
+//! This crate is not intended to be used directly. Enable the `derive`
+//! feature of _hyperx_ and use the re-exported `hyperx::header::Header`
+//! derive instead.
+
+extern crate proc_macro;
+extern crate proc_macro2;
+extern crate quote;
+extern crate syn;
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DataEnum, DeriveInput, Fields, Ident, LitStr};
+
+/// Derive `Header`, `Display`, `FromStr` and conversions from and to
+/// `HeaderValue` for a newtype (single field tuple struct) header, or an enum
+/// of unit variants.
+///
+/// The `#[header(..)]` attribute of the type supports:
+///
+/// * `name = "X-My-Header"`: the header name (required).
+/// * `csv`: the field is a `Vec<T>` of comma delimited values, parsed from
+///   all lines of the header. Otherwise the field is parsed via `FromStr`
+///   from the single line, and formatted via `Display`.
+/// * `from_str`: the type implements `FromStr` and `Display` itself, and
+///   is parsed from the single line and formatted via these. Any struct or
+///   enum may be derived with this flag.
+///
+/// Each variant of an enum is its own value, given by the
+/// `#[header(value = "...")]` attribute of the variant, or otherwise its
+/// name. Values are parsed ignoring ASCII case.
+#[proc_macro_derive(Header, attributes(header))]
+pub fn derive_header(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    match expand(&input) {
+        Ok(tokens) => tokens.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+struct Attrs {
+    name: LitStr,
+    csv: bool,
+    from_str: bool,
+}
+
+fn parse_attrs(input: &DeriveInput) -> syn::Result<Attrs> {
+    let mut name = None;
+    let mut csv = false;
+    let mut from_str = false;
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("header")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("name") {
+                let lit: LitStr = meta.value()?.parse()?;
+                if !is_token(&lit.value()) {
+                    return Err(syn::Error::new(lit.span(), "invalid header name"));
+                }
+                name = Some(lit);
+                Ok(())
+            } else if meta.path.is_ident("csv") {
+                csv = true;
+                Ok(())
+            } else if meta.path.is_ident("from_str") {
+                from_str = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported header attribute"))
+            }
+        })?;
+    }
+    if csv && from_str {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "csv and from_str can not be combined",
+        ));
+    }
+    match name {
+        Some(name) => Ok(Attrs {
+            name,
+            csv,
+            from_str,
+        }),
+        None => Err(syn::Error::new_spanned(
+            &input.ident,
+            "missing #[header(name = \"...\")] attribute",
+        )),
+    }
+}
+
+// The value of each unit variant, from `#[header(value = "...")]` or its name
+fn parse_variants(data: &DataEnum) -> syn::Result<Vec<(&Ident, LitStr)>> {
+    let mut variants = Vec::with_capacity(data.variants.len());
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(syn::Error::new_spanned(
+                variant,
+                "Header can only be derived for an enum of unit variants, without from_str",
+            ));
+        }
+        let mut value = None;
+        for attr in variant.attrs.iter().filter(|a| a.path().is_ident("header")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("value") {
+                    let lit: LitStr = meta.value()?.parse()?;
+                    if lit.value().is_empty() {
+                        return Err(syn::Error::new(lit.span(), "empty header value"));
+                    }
+                    value = Some(lit);
+                    Ok(())
+                } else {
+                    Err(meta.error("unsupported header variant attribute"))
+                }
+            })?;
+        }
+        let value =
+            value.unwrap_or_else(|| LitStr::new(&variant.ident.to_string(), variant.ident.span()));
+        variants.push((&variant.ident, value));
+    }
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &data.variants,
+            "Header can not be derived for an enum without variants",
+        ));
+    }
+    Ok(variants)
+}
+
+// RFC 7230 token characters
+fn is_token(s: &str) -> bool {
+    !s.is_empty()
+        && s.bytes().all(|b| {
+            matches!(b,
+                b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+                b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' |
+                b'^' | b'_' | b'`' | b'|' | b'~')
+        })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let attrs = parse_attrs(input)?;
+
+    let id = &input.ident;
+    let name = &attrs.name;
+    let lower_name = LitStr::new(&name.value().to_ascii_lowercase(), name.span());
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let newtype = match input.data {
+        Data::Struct(ref data) => match data.fields {
+            Fields::Unnamed(ref fields) => fields.unnamed.len() == 1,
+            _ => false,
+        },
+        _ => false,
+    };
+
+    // The parse expression, and the Display body if not implemented by the
+    // type itself
+    let (parse, display) = if attrs.from_str {
+        (
+            quote!(::hyperx::header::parsing::from_one_raw_str(raw)),
+            None,
+        )
+    } else if let Data::Enum(ref data) = input.data {
+        if attrs.csv {
+            return Err(syn::Error::new_spanned(
+                id,
+                "csv is not supported for an enum",
+            ));
+        }
+        let variants = parse_variants(data)?;
+        let idents: Vec<_> = variants.iter().map(|v| v.0).collect();
+        let values: Vec<_> = variants.iter().map(|v| &v.1).collect();
+        (
+            quote!(::hyperx::header::parsing::from_one_raw_str::<_, ::std::string::String>(raw)
+            .and_then(|s| {
+                #(
+                    if s.eq_ignore_ascii_case(#values) {
+                        return Ok(#id::#idents);
+                    }
+                )*
+                Err(::hyperx::Error::header(::hyperx::HeaderErrorKind::Syntax)
+                    .with_header_value(s.as_bytes()))
+            })),
+            Some(quote!(f.write_str(match *self {
+                #(#id::#idents => #values,)*
+            }))),
+        )
+    } else if !newtype {
+        return Err(syn::Error::new_spanned(
+            id,
+            "Header can only be derived for a single field tuple struct or an enum \
+             of unit variants, without from_str",
+        ));
+    } else if attrs.csv {
+        (
+            quote!(::hyperx::header::parsing::from_comma_delimited(raw).map(#id)),
+            Some(quote!(::hyperx::header::parsing::fmt_comma_delimited(
+                f,
+                &self.0[..]
+            ))),
+        )
+    } else {
+        (
+            quote!(::hyperx::header::parsing::from_one_raw_str(raw).map(#id)),
+            Some(quote!(::std::fmt::Display::fmt(&self.0, f))),
+        )
+    };
+
+    // Display and FromStr, unless implemented by the type itself
+    let display_from_str = display.map(|display| {
+        quote! {
+            impl #impl_generics ::std::fmt::Display for #id #ty_generics #where_clause {
+                #[inline]
+                fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+                    #display
+                }
+            }
+
+            impl #impl_generics ::std::str::FromStr for #id #ty_generics #where_clause {
+                type Err = ::hyperx::Error;
+
+                fn from_str(s: &str) -> ::hyperx::Result<Self> {
+                    <Self as ::hyperx::header::Header>::parse_header(
+                        &::hyperx::header::Raw::from(s)
+                    )
+                }
+            }
+        }
+    });
+
+    // The serde impls are generated by hyperx itself, depending on its
+    // `serde` feature, and only for non-generic headers.
+    let serde = if input.generics.params.is_empty() {
+        quote!(::hyperx::__hyper__serde!(#id);)
+    } else {
+        quote!()
+    };
+
+    Ok(quote! {
+        #serde
+
+        impl #impl_generics ::hyperx::header::Header for #id #ty_generics #where_clause {
+            #[inline]
+            fn header_name() -> &'static str {
+                #name
+            }
+            #[inline]
+            fn http_name() -> ::hyperx::header::__private::HeaderName {
+                ::hyperx::header::__private::HeaderName::from_static(#lower_name)
+            }
+            #[inline]
+            fn parse_header<'a, T>(raw: &'a T) -> ::hyperx::Result<Self>
+            where T: ::hyperx::header::RawLike<'a>
+            {
+                #parse.map_err(|e| e.with_header_name(#name))
+            }
+            #[inline]
+            fn fmt_header(&self, f: &mut ::hyperx::header::Formatter) -> ::std::fmt::Result {
+                f.fmt_line(self)
+            }
+        }
+
+        #display_from_str
+
+        impl #impl_generics ::std::convert::TryFrom<&::hyperx::header::__private::HeaderValue>
+            for #id #ty_generics #where_clause
+        {
+            type Error = ::hyperx::Error;
+
+            #[inline]
+            fn try_from(
+                value: &::hyperx::header::__private::HeaderValue
+            ) -> ::hyperx::Result<Self> {
+                <Self as ::hyperx::header::Header>::parse_header(
+                    &::hyperx::header::Raw::from(value)
+                )
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<&#id #ty_generics>
+            for ::hyperx::header::__private::HeaderValue #where_clause
+        {
+            type Error = ::hyperx::header::__private::InvalidHeaderValue;
+
+            #[inline]
+            fn try_from(header: &#id #ty_generics) -> ::std::result::Result<Self, Self::Error> {
+                ::hyperx::header::__private::to_value(header)
+            }
+        }
+
+        impl #impl_generics ::std::convert::TryFrom<#id #ty_generics>
+            for ::hyperx::header::__private::HeaderValue #where_clause
+        {
+            type Error = ::hyperx::header::__private::InvalidHeaderValue;
+
+            #[inline]
+            fn try_from(header: #id #ty_generics) -> ::std::result::Result<Self, Self::Error> {
+                ::hyperx::header::__private::to_value(&header)
+            }
+        }
+    })
+}
@@ -0,0 +1,383 @@
+//! A client cache of alternative services, per [RFC 7838].
+//!
+//! An [`AltSvcCache`](struct.AltSvcCache.html) records the typed `AltSvc`
+//! headers received from each origin, and answers which alternative
+//! endpoints of the origin, such as HTTP/3 over QUIC, are currently valid.
+//! Each header replaces the alternatives previously advertised by the
+//! origin, and `clear` removes them. Alternatives expire after their `ma`
+//! (24 hours by default), and are dropped on network changes unless
+//! advertised with `persist=1`.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, SystemTime};
+//! use http::Uri;
+//! use hyperx::alt_svc::AltSvcCache;
+//! use hyperx::header::{AltService, AltSvc};
+//!
+//! let now = SystemTime::now();
+//! let mut cache = AltSvcCache::new();
+//! let origin: Uri = "https://example.com/".parse().unwrap();
+//! cache.store(
+//!     &AltSvc::Services(vec![AltService::new("h3", 443).max_age(3600)]),
+//!     &origin,
+//!     now,
+//! );
+//!
+//! let alternatives = cache.alternatives(&origin, now);
+//! assert_eq!(alternatives[0].protocol(), "h3");
+//! assert_eq!(alternatives[0].host(), "example.com");
+//! assert_eq!(alternatives[0].port(), 443);
+//!
+//! let later = now + Duration::from_secs(3600);
+//! assert!(cache.alternatives(&origin, later).is_empty());
+//! ```
+//!
+//! [RFC 7838]: https://www.rfc-editor.org/rfc/rfc7838
+
+use std::collections::HashMap;
+use std::time::{Duration, SystemTime};
+
+use http::header::ALT_SVC;
+use http::uri::{Scheme, Uri};
+use http::HeaderMap;
+
+use header::{AltSvc, Header};
+
+// The freshness of an alternative service without `ma`, per RFC 7838,
+// Section 3.1.
+const DEFAULT_MAX_AGE: u64 = 24 * 60 * 60;
+
+/// A cached alternative service of an origin.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Alternative {
+    protocol: String,
+    host: String,
+    port: u16,
+    persist: bool,
+    expires: SystemTime,
+}
+
+impl Alternative {
+    /// The ALPN protocol identifier, such as `h3`.
+    pub fn protocol(&self) -> &str {
+        &self.protocol
+    }
+
+    /// The host, in lowercase: that of the origin, unless the alternative
+    /// service names another.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// The port.
+    pub fn port(&self) -> u16 {
+        self.port
+    }
+
+    /// Whether the alternative survives network changes.
+    pub fn persist(&self) -> bool {
+        self.persist
+    }
+
+    /// The time at which the alternative expires.
+    pub fn expires(&self) -> SystemTime {
+        self.expires
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires <= now
+    }
+}
+
+// An origin: scheme, lowercase host and port.
+type Origin = (Scheme, String, u16);
+
+/// A cache of the alternative services advertised by origins.
+#[derive(Clone, Debug, Default)]
+pub struct AltSvcCache {
+    origins: HashMap<Origin, Vec<Alternative>>,
+}
+
+impl AltSvcCache {
+    /// Create a new, empty cache.
+    pub fn new() -> AltSvcCache {
+        AltSvcCache::default()
+    }
+
+    /// Store an `AltSvc` header, received at `now` from the origin of `url`.
+    ///
+    /// The advertised alternatives replace any cached for the origin, and
+    /// `clear` removes them. Alternatives with a `ma` of zero are not
+    /// stored. The header is ignored for URLs without an origin.
+    pub fn store(&mut self, alt_svc: &AltSvc, url: &Uri, now: SystemTime) {
+        let origin = match origin(url) {
+            Some(origin) => origin,
+            None => return,
+        };
+        let services = match *alt_svc {
+            AltSvc::Clear => {
+                self.origins.remove(&origin);
+                return;
+            }
+            AltSvc::Services(ref services) => services,
+        };
+        let alternatives: Vec<Alternative> = services
+            .iter()
+            .filter_map(|service| {
+                let max_age = service.max_age.unwrap_or(DEFAULT_MAX_AGE);
+                let expires = now.checked_add(Duration::from_secs(max_age))?;
+                Some(Alternative {
+                    protocol: service.protocol.clone(),
+                    host: match service.host {
+                        Some(ref host) => host.to_ascii_lowercase(),
+                        None => origin.1.clone(),
+                    },
+                    port: service.port,
+                    persist: service.persist,
+                    expires,
+                })
+            })
+            .filter(|alt| !alt.is_expired(now))
+            .collect();
+        if alternatives.is_empty() {
+            self.origins.remove(&origin);
+        } else {
+            self.origins.insert(origin, alternatives);
+        }
+    }
+
+    /// Store any `Alt-Svc` header of a response.
+    ///
+    /// An invalid header is ignored.
+    pub fn store_response(&mut self, headers: &HeaderMap, url: &Uri, now: SystemTime) {
+        if !headers.contains_key(ALT_SVC) {
+            return;
+        }
+        if let Ok(alt_svc) = AltSvc::parse_header(&headers.get_all(ALT_SVC)) {
+            self.store(&alt_svc, url, now);
+        }
+    }
+
+    /// Returns the alternatives of the origin of `url` which are valid at
+    /// `now`, in order of preference.
+    pub fn alternatives(&self, url: &Uri, now: SystemTime) -> Vec<&Alternative> {
+        origin(url)
+            .and_then(|origin| self.origins.get(&origin))
+            .map(|alts| alts.iter().filter(|alt| !alt.is_expired(now)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Remove the alternatives which do not persist across network changes,
+    /// for use when the network configuration of the client changes.
+    pub fn network_changed(&mut self) {
+        self.retain(|alt| alt.persist);
+    }
+
+    /// Remove the alternatives of the origin of `url`, if any.
+    pub fn remove(&mut self, url: &Uri) -> Option<Vec<Alternative>> {
+        self.origins.remove(&origin(url)?)
+    }
+
+    /// Remove all alternatives which have expired at `now`.
+    pub fn remove_expired(&mut self, now: SystemTime) {
+        self.retain(|alt| !alt.is_expired(now));
+    }
+
+    /// Remove all alternatives.
+    pub fn clear(&mut self) {
+        self.origins.clear();
+    }
+
+    /// The number of origins with cached alternatives.
+    pub fn len(&self) -> usize {
+        self.origins.len()
+    }
+
+    /// Returns true if no alternatives are cached.
+    pub fn is_empty(&self) -> bool {
+        self.origins.is_empty()
+    }
+
+    fn retain<F: FnMut(&Alternative) -> bool>(&mut self, mut f: F) {
+        self.origins.retain(|_, alts| {
+            alts.retain(&mut f);
+            !alts.is_empty()
+        });
+    }
+}
+
+fn origin(url: &Uri) -> Option<Origin> {
+    let scheme = url.scheme()?.clone();
+    let port = match url.port_u16() {
+        Some(port) => port,
+        None if scheme == Scheme::HTTPS => 443,
+        None if scheme == Scheme::HTTP => 80,
+        None => return None,
+    };
+    let host = url.host()?.trim_end_matches('.').to_ascii_lowercase();
+    Some((scheme, host, port))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::AltService;
+    use std::time::UNIX_EPOCH;
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000_000)
+    }
+
+    fn protocols(alts: Vec<&Alternative>) -> Vec<(&str, &str, u16)> {
+        alts.into_iter()
+            .map(|alt| (alt.protocol(), alt.host(), alt.port()))
+            .collect()
+    }
+
+    #[test]
+    fn test_store() {
+        let mut cache = AltSvcCache::new();
+        let alt_svc = AltSvc::Services(vec![
+            AltService::new("h3", 8443).host("Alt.Example.com"),
+            AltService::new("h2", 443).max_age(60),
+        ]);
+        cache.store(&alt_svc, &uri("https://Example.com./a"), now());
+        assert_eq!(cache.len(), 1);
+
+        let origin = uri("https://example.com:443/b");
+        assert_eq!(
+            protocols(cache.alternatives(&origin, now())),
+            vec![("h3", "alt.example.com", 8443), ("h2", "example.com", 443)]
+        );
+        let alts = cache.alternatives(&origin, now());
+        assert_eq!(alts[0].expires(), now() + Duration::from_secs(86400));
+        assert!(!alts[0].persist());
+
+        let later = now() + Duration::from_secs(60);
+        assert_eq!(
+            protocols(cache.alternatives(&origin, later)),
+            vec![("h3", "alt.example.com", 8443)]
+        );
+
+        // Other origins are distinct
+        assert!(cache
+            .alternatives(&uri("http://example.com/"), now())
+            .is_empty());
+        assert!(cache
+            .alternatives(&uri("https://example.com:8443/"), now())
+            .is_empty());
+        assert!(cache.alternatives(&uri("/relative"), now()).is_empty());
+    }
+
+    #[test]
+    fn test_replace_and_clear() {
+        let mut cache = AltSvcCache::new();
+        let origin = uri("https://example.com/");
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443)]),
+            &origin,
+            now(),
+        );
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h2", 8443)]),
+            &origin,
+            now(),
+        );
+        assert_eq!(
+            protocols(cache.alternatives(&origin, now())),
+            vec![("h2", "example.com", 8443)]
+        );
+
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443).max_age(0)]),
+            &origin,
+            now(),
+        );
+        assert!(cache.is_empty());
+
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443)]),
+            &origin,
+            now(),
+        );
+        cache.store(&AltSvc::Clear, &origin, now());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_network_changed() {
+        let mut cache = AltSvcCache::new();
+        let a = uri("https://a.example/");
+        let b = uri("https://b.example/");
+        cache.store(
+            &AltSvc::Services(vec![
+                AltService::new("h3", 443).persist(),
+                AltService::new("h2", 8443),
+            ]),
+            &a,
+            now(),
+        );
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443)]),
+            &b,
+            now(),
+        );
+
+        cache.network_changed();
+        assert_eq!(cache.len(), 1);
+        assert_eq!(
+            protocols(cache.alternatives(&a, now())),
+            vec![("h3", "a.example", 443)]
+        );
+        assert!(cache.remove(&a).is_some());
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_remove_expired() {
+        let mut cache = AltSvcCache::new();
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443).max_age(10)]),
+            &uri("https://a.example/"),
+            now(),
+        );
+        cache.store(
+            &AltSvc::Services(vec![AltService::new("h3", 443).max_age(20)]),
+            &uri("https://b.example/"),
+            now(),
+        );
+        cache.remove_expired(now() + Duration::from_secs(10));
+        assert_eq!(cache.len(), 1);
+        cache.clear();
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn test_store_response() {
+        let mut cache = AltSvcCache::new();
+        let origin = uri("https://example.com/");
+        let mut headers = HeaderMap::new();
+        headers.append("alt-svc", "h3=\":443\"; ma=60".parse().unwrap());
+        headers.append("alt-svc", "h2=\":8443\"".parse().unwrap());
+        cache.store_response(&headers, &origin, now());
+        assert_eq!(cache.alternatives(&origin, now()).len(), 2);
+
+        let mut headers = HeaderMap::new();
+        headers.insert("alt-svc", "bogus".parse().unwrap());
+        cache.store_response(&headers, &origin, now());
+        assert_eq!(cache.alternatives(&origin, now()).len(), 2);
+
+        cache.store_response(&HeaderMap::new(), &origin, now());
+        assert_eq!(cache.len(), 1);
+
+        headers.insert("alt-svc", "clear".parse().unwrap());
+        cache.store_response(&headers, &origin, now());
+        assert!(cache.is_empty());
+    }
+}
@@ -0,0 +1,524 @@
+//! Linting of a full set of message headers.
+//!
+//! [`audit`](fn.audit.html) inspects a `http::HeaderMap` and reports each
+//! problem found as a [`Finding`](struct.Finding.html), with a
+//! [`Severity`](enum.Severity.html) and a [`FindingKind`](enum.FindingKind.html),
+//! for use in proxies (e.g. to reject or sanitize a message) or in API tests
+//! (e.g. to assert that a response has no findings of `Severity::Error`).
+//!
+//! The checks are:
+//!
+//! * conflicting message framing: `Content-Length` together with
+//!   `Transfer-Encoding`, or differing `Content-Length` values;
+//! * duplicate singleton headers, such as `Host` or `Content-Type`;
+//! * invalid values of the typed headers of this crate;
+//! * deprecated headers, such as `X-XSS-Protection`;
+//! * for responses, missing recommended security headers;
+//! * for forwarded messages, hop-by-hop headers which should have been
+//!   removed by the forwarding intermediary.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::audit::{audit, AuditOptions, FindingKind, Severity};
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("content-length", "42".parse().unwrap());
+//! headers.insert("transfer-encoding", "chunked".parse().unwrap());
+//! headers.insert("x-xss-protection", "1; mode=block".parse().unwrap());
+//!
+//! let options = AuditOptions { response: false, ..AuditOptions::default() };
+//! let findings = audit(&headers, &options);
+//! assert_eq!(findings.len(), 2);
+//! assert_eq!(findings[0].kind, FindingKind::ConflictingFraming);
+//! assert_eq!(findings[0].severity, Severity::Error);
+//! assert_eq!(findings[1].kind, FindingKind::Deprecated);
+//! assert_eq!(findings[1].name, "x-xss-protection");
+//! ```
+
+use std::fmt;
+use std::sync::OnceLock;
+
+use http::header::{HeaderMap, HeaderName};
+
+use header::{FieldInfo, FieldKind, HeaderRegistry, Raw};
+use proxy::is_hop_by_hop;
+
+/// The severity of a [`Finding`](struct.Finding.html), ordered from least
+/// to most severe.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// A recommendation, such as a missing security header.
+    Info,
+    /// A likely problem, which recipients usually tolerate.
+    Warning,
+    /// A message which recipients must or should reject, or which is
+    /// ambiguous between recipients.
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Severity::Info => "info",
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        })
+    }
+}
+
+/// The kind of check which produced a [`Finding`](struct.Finding.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FindingKind {
+    /// `Content-Length` with `Transfer-Encoding`, or differing
+    /// `Content-Length` values.
+    ConflictingFraming,
+    /// A header which may occur only once occurs multiple times.
+    DuplicateSingleton,
+    /// The value of a typed header of this crate fails to parse.
+    InvalidValue,
+    /// A deprecated or obsolete header.
+    Deprecated,
+    /// A recommended security response header is missing.
+    MissingSecurityHeader,
+    /// A hop-by-hop header in a forwarded message.
+    HopByHop,
+}
+
+/// A problem found by [`audit`](fn.audit.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The severity of the problem.
+    pub severity: Severity,
+    /// The check which found the problem.
+    pub kind: FindingKind,
+    /// The name of the offending (or missing) header.
+    pub name: HeaderName,
+    /// A human readable description of the problem.
+    pub message: String,
+}
+
+impl Finding {
+    fn new<M: Into<String>>(
+        severity: Severity,
+        kind: FindingKind,
+        name: HeaderName,
+        message: M,
+    ) -> Finding {
+        Finding {
+            severity,
+            kind,
+            name,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}: {}", self.severity, self.name, self.message)
+    }
+}
+
+/// Options of [`audit`](fn.audit.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AuditOptions {
+    /// Whether the headers are of a response, rather than of a request,
+    /// enabling the check for missing security headers. Default: true.
+    pub response: bool,
+    /// Whether the message was forwarded by an intermediary, enabling the
+    /// check for hop-by-hop headers. Default: false.
+    pub forwarded: bool,
+}
+
+impl Default for AuditOptions {
+    fn default() -> AuditOptions {
+        AuditOptions {
+            response: true,
+            forwarded: false,
+        }
+    }
+}
+
+// Deprecated headers, with the reason. Includes each field deprecated or
+// obsoleted in `FieldInfo`.
+static DEPRECATED: &[(&str, &str)] = &[
+    (
+        "accept-charset",
+        "deprecated, as UTF-8 is nearly ubiquitous (RFC 9110)",
+    ),
+    (
+        "digest",
+        "obsoleted by Content-Digest and Repr-Digest (RFC 9530)",
+    ),
+    (
+        "expect-ct",
+        "obsolete, Certificate Transparency is enforced by default",
+    ),
+    ("feature-policy", "replaced by Permissions-Policy"),
+    ("pragma", "deprecated in favor of Cache-Control (RFC 9111)"),
+    ("proxy-connection", "obsolete, use Connection"),
+    ("public-key-pins", "removed from user agents"),
+    (
+        "want-digest",
+        "obsoleted by Want-Content-Digest and Want-Repr-Digest (RFC 9530)",
+    ),
+    ("warning", "obsoleted by RFC 9111"),
+    (
+        "x-xss-protection",
+        "removed from user agents, use Content-Security-Policy",
+    ),
+];
+
+static SECURITY: &[&str] = &[
+    "strict-transport-security",
+    "x-content-type-options",
+    "content-security-policy",
+    "referrer-policy",
+];
+
+// Headers defined with a singular field value, which must not be repeated.
+// A `Cookie` may be split into multiple fields in HTTP/2 (RFC 9113 §8.2.3).
+fn is_singleton(name: &HeaderName) -> bool {
+    *name != http::header::COOKIE
+        && FieldInfo::lookup(name.as_str()).is_some_and(|f| f.kind == FieldKind::Singleton)
+}
+
+fn registry() -> &'static HeaderRegistry {
+    static REGISTRY: OnceLock<HeaderRegistry> = OnceLock::new();
+    REGISTRY.get_or_init(HeaderRegistry::standard)
+}
+
+/// Audit the headers of a message, returning all findings in the order of
+/// the checks, and of the headers within each check.
+pub fn audit(headers: &HeaderMap, options: &AuditOptions) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_framing(headers, &mut findings);
+    check_singletons(headers, &mut findings);
+    check_values(headers, &mut findings);
+    check_deprecated(headers, &mut findings);
+    if options.response {
+        check_security(headers, &mut findings);
+    }
+    if options.forwarded {
+        check_hop_by_hop(headers, &mut findings);
+    }
+    findings
+}
+
+fn check_framing(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    let mut lengths = headers.get_all(http::header::CONTENT_LENGTH).iter();
+    if let Some(first) = lengths.next() {
+        if headers.contains_key(http::header::TRANSFER_ENCODING) {
+            findings.push(Finding::new(
+                Severity::Error,
+                FindingKind::ConflictingFraming,
+                http::header::TRANSFER_ENCODING,
+                "Transfer-Encoding with Content-Length",
+            ));
+        }
+        if lengths.any(|v| v != first) {
+            findings.push(Finding::new(
+                Severity::Error,
+                FindingKind::ConflictingFraming,
+                http::header::CONTENT_LENGTH,
+                "differing Content-Length values",
+            ));
+        }
+    }
+}
+
+fn check_singletons(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    for key in headers.keys() {
+        if !is_singleton(key) {
+            continue;
+        }
+        let count = headers.get_all(key).iter().count();
+        if count < 2 {
+            continue;
+        }
+        if *key == http::header::CONTENT_LENGTH {
+            let first = &headers[key];
+            if headers.get_all(key).iter().any(|v| v != first) {
+                // Already reported as conflicting framing
+                continue;
+            }
+        }
+        // A repeated Host is ambiguous between recipients (RFC 9112 §3.2)
+        let severity = if *key == http::header::HOST {
+            Severity::Error
+        } else {
+            Severity::Warning
+        };
+        findings.push(Finding::new(
+            severity,
+            FindingKind::DuplicateSingleton,
+            key.clone(),
+            format!("occurs {} times", count),
+        ));
+    }
+}
+
+fn check_values(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    for key in headers.keys() {
+        let raw: Raw = headers
+            .get_all(key)
+            .iter()
+            .map(|v| v.as_bytes().to_vec())
+            .collect::<Vec<_>>()
+            .into();
+        if let Some(Err(e)) = registry().parse(key.as_str(), &raw) {
+            let multiple = e
+                .header_error()
+                .is_some_and(|h| h.kind() == ::HeaderErrorKind::Multiple);
+            if multiple && is_singleton(key) {
+                // Already reported as a duplicate
+                continue;
+            }
+            findings.push(Finding::new(
+                Severity::Error,
+                FindingKind::InvalidValue,
+                key.clone(),
+                e.to_string(),
+            ));
+        }
+    }
+}
+
+fn check_deprecated(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    for key in headers.keys() {
+        if let Some(&(_, reason)) = DEPRECATED.iter().find(|d| d.0 == key.as_str()) {
+            findings.push(Finding::new(
+                Severity::Warning,
+                FindingKind::Deprecated,
+                key.clone(),
+                reason,
+            ));
+        }
+    }
+}
+
+fn check_security(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    for &header in SECURITY {
+        if !headers.contains_key(header) {
+            findings.push(Finding::new(
+                Severity::Info,
+                FindingKind::MissingSecurityHeader,
+                HeaderName::from_static(header),
+                "recommended security header is missing",
+            ));
+        }
+    }
+    // Framing may be restricted by either header
+    let frame_ancestors = headers
+        .get_all(http::header::CONTENT_SECURITY_POLICY)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .any(|v| v.to_ascii_lowercase().contains("frame-ancestors"));
+    if !frame_ancestors && !headers.contains_key(http::header::X_FRAME_OPTIONS) {
+        findings.push(Finding::new(
+            Severity::Info,
+            FindingKind::MissingSecurityHeader,
+            http::header::X_FRAME_OPTIONS,
+            "framing is not restricted by X-Frame-Options or CSP frame-ancestors",
+        ));
+    }
+}
+
+fn check_hop_by_hop(headers: &HeaderMap, findings: &mut Vec<Finding>) {
+    let options: Vec<String> = headers
+        .get_all(http::header::CONNECTION)
+        .iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(','))
+        .map(|o| o.trim().to_ascii_lowercase())
+        .filter(|o| !o.is_empty())
+        .collect();
+    for key in headers.keys() {
+        let listed = is_hop_by_hop(key);
+        if listed || options.iter().any(|o| o == key.as_str()) {
+            let message = if listed {
+                "hop-by-hop header must not be forwarded"
+            } else {
+                "header listed in Connection must not be forwarded"
+            };
+            findings.push(Finding::new(
+                Severity::Warning,
+                FindingKind::HopByHop,
+                key.clone(),
+                message,
+            ));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::FieldStatus;
+
+    fn headers(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn request() -> AuditOptions {
+        AuditOptions {
+            response: false,
+            forwarded: false,
+        }
+    }
+
+    fn kinds(findings: &[Finding]) -> Vec<(FindingKind, &str)> {
+        findings.iter().map(|f| (f.kind, f.name.as_str())).collect()
+    }
+
+    #[test]
+    fn test_deprecated_consistent() {
+        for info in FieldInfo::all() {
+            let name = info.name.to_ascii_lowercase();
+            let deprecated = matches!(
+                info.status,
+                FieldStatus::Deprecated | FieldStatus::Obsoleted
+            );
+            assert_eq!(
+                deprecated,
+                DEPRECATED.iter().any(|d| d.0 == name),
+                "{}",
+                info.name
+            );
+        }
+    }
+
+    #[test]
+    fn test_clean_request() {
+        let map = headers(&[
+            ("host", "example.com"),
+            ("accept", "text/html"),
+            ("content-length", "3"),
+        ]);
+        assert_eq!(audit(&map, &request()), vec![]);
+    }
+
+    #[test]
+    fn test_framing() {
+        let map = headers(&[
+            ("content-length", "3"),
+            ("content-length", "4"),
+            ("transfer-encoding", "chunked"),
+        ]);
+        let findings = audit(&map, &request());
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::ConflictingFraming, "transfer-encoding"),
+                (FindingKind::ConflictingFraming, "content-length"),
+            ]
+        );
+        assert!(findings.iter().all(|f| f.severity == Severity::Error));
+    }
+
+    #[test]
+    fn test_duplicates() {
+        let map = headers(&[
+            ("host", "a.example"),
+            ("host", "b.example"),
+            ("content-length", "3"),
+            ("content-length", "3"),
+            ("accept", "text/html"),
+            ("accept", "text/plain"),
+        ]);
+        let findings = audit(&map, &request());
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::DuplicateSingleton, "host"),
+                (FindingKind::DuplicateSingleton, "content-length"),
+            ]
+        );
+        assert_eq!(findings[0].severity, Severity::Error);
+        assert_eq!(findings[1].severity, Severity::Warning);
+        assert_eq!(
+            findings[1].to_string(),
+            "warning: content-length: occurs 2 times"
+        );
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        let map = headers(&[
+            ("content-length", "nope"),
+            ("date", "yesterday"),
+            ("date", "today"),
+            ("x-custom", "anything"),
+        ]);
+        let findings = audit(&map, &request());
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::DuplicateSingleton, "date"),
+                (FindingKind::InvalidValue, "content-length"),
+            ]
+        );
+        assert!(findings[1].message.contains("Content-Length"));
+    }
+
+    #[test]
+    fn test_security() {
+        let map = headers(&[
+            ("content-security-policy", "default-src 'self'"),
+            ("x-content-type-options", "nosniff"),
+            ("x-xss-protection", "0"),
+        ]);
+        let findings = audit(&map, &AuditOptions::default());
+        assert_eq!(
+            kinds(&findings),
+            vec![
+                (FindingKind::Deprecated, "x-xss-protection"),
+                (
+                    FindingKind::MissingSecurityHeader,
+                    "strict-transport-security"
+                ),
+                (FindingKind::MissingSecurityHeader, "referrer-policy"),
+                (FindingKind::MissingSecurityHeader, "x-frame-options"),
+            ]
+        );
+
+        let map = headers(&[
+            ("strict-transport-security", "max-age=31536000"),
+            ("x-content-type-options", "nosniff"),
+            ("content-security-policy", "frame-ancestors 'none'"),
+            ("referrer-policy", "no-referrer"),
+        ]);
+        assert_eq!(audit(&map, &AuditOptions::default()), vec![]);
+    }
+
+    #[test]
+    fn test_hop_by_hop() {
+        let map = headers(&[
+            ("connection", "close, x-trace"),
+            ("keep-alive", "timeout=5"),
+            ("x-trace", "1"),
+            ("x-other", "1"),
+            ("proxy-authorization", "Basic Zm9vOmJhcg=="),
+        ]);
+        let options = AuditOptions {
+            response: false,
+            forwarded: true,
+        };
+        assert_eq!(
+            kinds(&audit(&map, &options)),
+            vec![
+                (FindingKind::HopByHop, "connection"),
+                (FindingKind::HopByHop, "keep-alive"),
+                (FindingKind::HopByHop, "x-trace"),
+                (FindingKind::HopByHop, "proxy-authorization"),
+            ]
+        );
+        assert_eq!(audit(&map, &request()), vec![]);
+    }
+}
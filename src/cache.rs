@@ -0,0 +1,913 @@
+//! HTTP cache freshness and revalidation, per [RFC 9111].
+//!
+//! A [`StoredResponse`](struct.StoredResponse.html) holds the cache related
+//! headers of a response stored by a cache, along with the times of its
+//! request and receipt. From these it computes the freshness lifetime
+//! (explicit or heuristic) and current age of the response, and decides
+//! whether it may be served for a new request, or must first be
+//! revalidated with the origin server.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use hyperx::cache::{CacheDecision, CacheOptions, StoredResponse};
+//! use hyperx::header::{CacheControl, HttpDate};
+//!
+//! let received = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("date", HttpDate::from(received).to_string().parse().unwrap());
+//! headers.insert("cache-control", "max-age=60, stale-while-revalidate=30".parse().unwrap());
+//!
+//! let stored = StoredResponse::from_headers(&headers, received, received);
+//! let options = CacheOptions::default();
+//! assert_eq!(stored.freshness_lifetime(&options), Some(Duration::from_secs(60)));
+//!
+//! let later = received + Duration::from_secs(70);
+//! assert_eq!(stored.current_age(later), Duration::from_secs(70));
+//! assert_eq!(stored.decide(None, &options, later), CacheDecision::ServeWhileRevalidate);
+//!
+//! let request = "no-cache".parse::<CacheControl>().unwrap();
+//! assert_eq!(
+//!     stored.decide(Some(&request), &options, later),
+//!     CacheDecision::Revalidate { stale_if_error: false }
+//! );
+//! ```
+//!
+//! Of several responses stored for the same URI, those which may be served
+//! for a request are selected by their secondary cache key, computed from
+//! the `Vary` header by [`VaryKeys`](struct.VaryKeys.html).
+//!
+//! Whether a response may be stored at all (e.g. `no-store` or `private` in
+//! a shared cache), and whether its status code is heuristically cacheable,
+//! is left to the caller.
+//!
+//! [RFC 9111]: https://www.rfc-editor.org/rfc/rfc9111
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use http::header::{self as h, HeaderMap, HeaderName};
+
+use canonical::{canonical_value, CanonicalOptions};
+use header::parsing;
+use header::{
+    Age, CacheControl, CacheDirective, Date, ETag, EntityTag, Expires, IfModifiedSince,
+    IfNoneMatch, LastModified, Quality, TypedHeaders, Vary,
+};
+
+/// Options of a cache affecting freshness.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CacheOptions {
+    /// Whether the cache is shared (e.g. a proxy), rather than private (e.g.
+    /// of a user agent). Default: false.
+    pub shared: bool,
+    /// The percentage of the time since `Last-Modified` used as the
+    /// heuristic freshness lifetime. Default: 10.
+    pub heuristic_percent: u32,
+    /// The maximum heuristic freshness lifetime. Default: 1 day.
+    pub heuristic_max: Duration,
+}
+
+impl Default for CacheOptions {
+    fn default() -> CacheOptions {
+        CacheOptions {
+            shared: false,
+            heuristic_percent: 10,
+            heuristic_max: Duration::from_secs(24 * 60 * 60),
+        }
+    }
+}
+
+/// How a stored response may be used for a new request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CacheDecision {
+    /// The response is fresh, and may be served.
+    Serve,
+    /// The response is stale, but may be served, as allowed by the
+    /// `max-stale` request directive.
+    ServeStale,
+    /// The response is stale, but may be served while it is revalidated in
+    /// the background, per the `stale-while-revalidate` response directive.
+    ServeWhileRevalidate,
+    /// The response must be revalidated (or replaced) before it is served.
+    /// If `stale_if_error` is true, it may still be served if revalidation
+    /// fails with an error, per the `stale-if-error` directive.
+    Revalidate {
+        /// Whether the stale response may be served on error.
+        stale_if_error: bool,
+    },
+}
+
+/// The cache related headers of a stored response, and the times of its
+/// request and receipt.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StoredResponse {
+    /// The `Cache-Control` header.
+    pub cache_control: Option<CacheControl>,
+    /// The `Expires` header.
+    pub expires: Option<Expires>,
+    /// The `Date` header.
+    pub date: Option<Date>,
+    /// The `Age` header.
+    pub age: Option<Age>,
+    /// The `Last-Modified` header.
+    pub last_modified: Option<LastModified>,
+    /// The `ETag` header.
+    pub etag: Option<ETag>,
+    /// The time at which the request was sent.
+    pub request_time: SystemTime,
+    /// The time at which the response was received.
+    pub response_time: SystemTime,
+}
+
+impl StoredResponse {
+    /// Decode a stored response from its headers, and the times of its
+    /// request and receipt.
+    ///
+    /// Headers which fail to parse are ignored, except for an invalid
+    /// `Expires`, which represents a time in the past.
+    pub fn from_headers(
+        headers: &HeaderMap,
+        request_time: SystemTime,
+        response_time: SystemTime,
+    ) -> StoredResponse {
+        let expires = headers
+            .try_decode::<Expires>()
+            .map(|r| r.unwrap_or_else(|_| Expires(UNIX_EPOCH.into())));
+        StoredResponse {
+            cache_control: headers.try_decode().and_then(|r| r.ok()),
+            expires,
+            date: headers.try_decode().and_then(|r| r.ok()),
+            age: headers.try_decode().and_then(|r| r.ok()),
+            last_modified: headers.try_decode().and_then(|r| r.ok()),
+            etag: headers.try_decode().and_then(|r| r.ok()),
+            request_time,
+            response_time,
+        }
+    }
+
+    /// The explicit freshness lifetime, from the `s-maxage` (for shared
+    /// caches) or `max-age` directives, or otherwise `Expires` relative to
+    /// `Date`. Returns `None` if no explicit lifetime is given.
+    pub fn freshness_lifetime(&self, options: &CacheOptions) -> Option<Duration> {
+        if options.shared {
+            if let Some(secs) = self.directive(|d| match *d {
+                CacheDirective::SMaxAge(s) => Some(s),
+                _ => None,
+            }) {
+                return Some(Duration::from_secs(secs.into()));
+            }
+        }
+        if let Some(secs) = self.directive(|d| match *d {
+            CacheDirective::MaxAge(s) => Some(s),
+            _ => None,
+        }) {
+            return Some(Duration::from_secs(secs.into()));
+        }
+        self.expires.as_ref().map(|expires| {
+            SystemTime::from(expires.0)
+                .duration_since(self.date_value())
+                .unwrap_or_default()
+        })
+    }
+
+    /// The heuristic freshness lifetime, as a percentage of the time since
+    /// `Last-Modified`, if given, and limited to `heuristic_max`.
+    pub fn heuristic_freshness(&self, options: &CacheOptions) -> Option<Duration> {
+        self.last_modified.as_ref().map(|lm| {
+            let since = self
+                .date_value()
+                .duration_since(lm.0.into())
+                .unwrap_or_default();
+            (since * options.heuristic_percent / 100).min(options.heuristic_max)
+        })
+    }
+
+    /// The current age of the response at `now`, accounting for the `Age`
+    /// header, clock skew via `Date`, network delay and resident time. See
+    /// [`current_age`](fn.current_age.html).
+    pub fn current_age(&self, now: SystemTime) -> Duration {
+        current_age(
+            self.date.as_ref().map(|d| d.0.into()),
+            self.age.as_ref().map(|a| Duration::from_secs(a.0.into())),
+            self.request_time,
+            self.response_time,
+            now,
+        )
+    }
+
+    /// The `Age` header to send when forwarding or serving the response
+    /// from the cache at `now`, in whole seconds.
+    pub fn age_header(&self, now: SystemTime) -> Age {
+        Age(age_secs(self.current_age(now)))
+    }
+
+    /// The apparent offset of the clock of the origin server from the local
+    /// clock, from `Date` (adjusted by `Age`) relative to the interval from
+    /// `request_time` to `response_time`, allowing for the one second
+    /// resolution of `Date`. Returns `None` without a `Date`, or if `Date`
+    /// falls within the interval.
+    pub fn clock_skew(&self) -> Option<ClockSkew> {
+        let date = SystemTime::from(self.date.as_ref()?.0);
+        let age = Duration::from_secs(self.age.as_ref().map_or(0, |a| a.0.into()));
+        let generated = date + age;
+        if let Ok(ahead) = generated.duration_since(self.response_time) {
+            if ahead > Duration::ZERO {
+                return Some(ClockSkew::Ahead(ahead));
+            }
+        }
+        match self
+            .request_time
+            .duration_since(generated + Duration::from_secs(1))
+        {
+            Ok(behind) if behind > Duration::ZERO => Some(ClockSkew::Behind(behind)),
+            _ => None,
+        }
+    }
+
+    /// The staleness of the response at `now`, or `None` if fresh. The
+    /// heuristic freshness lifetime is used if no explicit lifetime is given.
+    pub fn staleness(&self, options: &CacheOptions, now: SystemTime) -> Option<Duration> {
+        let lifetime = self.lifetime(options);
+        let age = self.current_age(now);
+        if age < lifetime {
+            None
+        } else {
+            Some(age - lifetime)
+        }
+    }
+
+    /// Decide how the response may be used at `now` for a request with the
+    /// given `Cache-Control` header, if any.
+    pub fn decide(
+        &self,
+        request: Option<&CacheControl>,
+        options: &CacheOptions,
+        now: SystemTime,
+    ) -> CacheDecision {
+        let req = |f: &dyn Fn(&CacheDirective) -> Option<u32>| {
+            request.and_then(|cc| cc.iter().find_map(f))
+        };
+        let res_no_cache = self.has(&CacheDirective::NoCache);
+        let must_revalidate = self.has(&CacheDirective::MustRevalidate)
+            || (options.shared
+                && (self.has(&CacheDirective::ProxyRevalidate)
+                    || self
+                        .directive(|d| match *d {
+                            CacheDirective::SMaxAge(s) => Some(s),
+                            _ => None,
+                        })
+                        .is_some()));
+
+        let lifetime = self.lifetime(options);
+        let age = self.current_age(now);
+        let staleness = if age < lifetime {
+            Duration::default()
+        } else {
+            age - lifetime
+        };
+        let stale_if_error = !must_revalidate
+            && !res_no_cache
+            && age >= lifetime
+            && (self.extension_secs("stale-if-error") >= Some(staleness.as_secs())
+                || req(&|d| extension_secs(d, "stale-if-error"))
+                    .map(u64::from)
+                    .is_some_and(|s| s >= staleness.as_secs()));
+        let revalidate = CacheDecision::Revalidate {
+            stale_if_error: stale_if_error || (!must_revalidate && !res_no_cache && age < lifetime),
+        };
+
+        let req_no_cache = request.is_some_and(|cc| cc.contains(&CacheDirective::NoCache));
+        if res_no_cache || req_no_cache {
+            return revalidate;
+        }
+        if let Some(max_age) = req(&|d| match *d {
+            CacheDirective::MaxAge(s) => Some(s),
+            _ => None,
+        }) {
+            if age > Duration::from_secs(max_age.into()) {
+                return revalidate;
+            }
+        }
+        let min_fresh = req(&|d| match *d {
+            CacheDirective::MinFresh(s) => Some(s),
+            _ => None,
+        })
+        .map_or(Duration::default(), |s| Duration::from_secs(s.into()));
+        if age + min_fresh < lifetime {
+            return CacheDecision::Serve;
+        }
+        if must_revalidate {
+            return revalidate;
+        }
+
+        let any_stale = request.is_some_and(|cc| {
+            cc.iter().any(|d| match *d {
+                CacheDirective::Extension(ref n, None) => n.as_str() == "max-stale",
+                _ => false,
+            })
+        });
+        let max_stale = req(&|d| match *d {
+            CacheDirective::MaxStale(s) => Some(s),
+            _ => None,
+        });
+        if any_stale || max_stale.is_some_and(|s| staleness <= Duration::from_secs(s.into())) {
+            return CacheDecision::ServeStale;
+        }
+        if self
+            .extension_secs("stale-while-revalidate")
+            .is_some_and(|s| staleness <= Duration::from_secs(s))
+        {
+            return CacheDecision::ServeWhileRevalidate;
+        }
+        revalidate
+    }
+
+    /// Encode the conditional request headers for revalidation, from the
+    /// `ETag` and `Last-Modified` validators of the response, if any.
+    pub fn encode_validators(&self, headers: &mut HeaderMap) {
+        if let Some(ETag(ref tag)) = self.etag {
+            headers.encode(&IfNoneMatch::Items(smallvec![EntityTag::clone(tag)]));
+        }
+        if let Some(LastModified(date)) = self.last_modified {
+            headers.encode(&IfModifiedSince(date));
+        }
+    }
+
+    fn lifetime(&self, options: &CacheOptions) -> Duration {
+        self.freshness_lifetime(options)
+            .or_else(|| self.heuristic_freshness(options))
+            .unwrap_or_default()
+    }
+
+    fn date_value(&self) -> SystemTime {
+        self.date
+            .as_ref()
+            .map_or(self.response_time, |d| SystemTime::from(d.0))
+    }
+
+    fn has(&self, directive: &CacheDirective) -> bool {
+        self.cache_control
+            .as_ref()
+            .is_some_and(|cc| cc.contains(directive))
+    }
+
+    fn directive<F>(&self, f: F) -> Option<u32>
+    where
+        F: Fn(&CacheDirective) -> Option<u32>,
+    {
+        self.cache_control
+            .as_ref()
+            .and_then(|cc| cc.iter().find_map(f))
+    }
+
+    fn extension_secs(&self, name: &str) -> Option<u64> {
+        self.directive(|d| extension_secs(d, name)).map(u64::from)
+    }
+}
+
+/// The offset of a remote clock from the local clock.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClockSkew {
+    /// The remote clock is ahead of the local clock.
+    Ahead(Duration),
+    /// The remote clock is behind the local clock.
+    Behind(Duration),
+}
+
+/// Compute the current age of a response at `now`, per the age calculation
+/// of [RFC 9111 §4.2.3](https://www.rfc-editor.org/rfc/rfc9111#section-4.2.3).
+///
+/// `date` and `age` are the values of the `Date` and `Age` headers of the
+/// response, if any, and `request_time` and `response_time` the local times
+/// at which the request was sent and the response received. Clock skew is
+/// corrected for by taking the larger of the apparent age, from `Date`, and
+/// the `Age` value plus the response delay; a `Date` in the future
+/// contributes nothing. Without a `Date`, `response_time` is used.
+///
+/// ```
+/// use std::time::{Duration, UNIX_EPOCH};
+/// use hyperx::cache::current_age;
+///
+/// let sent = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+/// let received = sent + Duration::from_secs(2);
+/// // An origin clock 60s ahead of the local clock, and a hop reporting Age
+/// let date = received + Duration::from_secs(60);
+/// let age = current_age(Some(date), Some(Duration::from_secs(10)), sent, received, received);
+/// assert_eq!(age, Duration::from_secs(12));
+/// ```
+pub fn current_age(
+    date: Option<SystemTime>,
+    age: Option<Duration>,
+    request_time: SystemTime,
+    response_time: SystemTime,
+    now: SystemTime,
+) -> Duration {
+    let date_value = date.unwrap_or(response_time);
+    let apparent_age = response_time.duration_since(date_value).unwrap_or_default();
+    let response_delay = response_time
+        .duration_since(request_time)
+        .unwrap_or_default();
+    let age_value = age.unwrap_or_default();
+    let corrected_initial_age = apparent_age.max(age_value + response_delay);
+    let resident_time = now.duration_since(response_time).unwrap_or_default();
+    corrected_initial_age + resident_time
+}
+
+/// A normalization of the combined value of a request header, e.g. to
+/// ignore the order of `Accept-Encoding` codings in a secondary cache key.
+pub type Normalizer = Box<dyn Fn(&str) -> String + Send + Sync>;
+
+/// Computes secondary cache keys, from the request headers nominated by the
+/// `Vary` header of a stored response, per
+/// [RFC 9111 §4.1](https://www.rfc-editor.org/rfc/rfc9111#section-4.1).
+///
+/// The values of each nominated header are combined, with whitespace
+/// trimmed and collapsed, then normalized by the `Normalizer` registered
+/// for the header, if any. Requests with equal keys may be served the same
+/// stored response.
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::cache::VaryKeys;
+///
+/// let vary = "Accept-Encoding, Accept-Language".parse().unwrap();
+/// let keys = VaryKeys::standard();
+///
+/// let mut stored = http::HeaderMap::new();
+/// stored.insert("accept-encoding", "gzip, br".parse().unwrap());
+/// let key = keys.key(&vary, &stored).unwrap();
+///
+/// let mut request = http::HeaderMap::new();
+/// request.insert("accept-encoding", "BR,gzip;q=1".parse().unwrap());
+/// assert!(keys.matches(&vary, &key, &request));
+///
+/// request.insert("accept-language", "en".parse().unwrap());
+/// assert!(!keys.matches(&vary, &key, &request));
+/// ```
+#[derive(Default)]
+pub struct VaryKeys {
+    normalizers: Vec<(HeaderName, Normalizer)>,
+}
+
+impl VaryKeys {
+    /// Create without any normalizers.
+    pub fn new() -> VaryKeys {
+        VaryKeys::default()
+    }
+
+    /// Create with normalizers for the content negotiation headers:
+    /// [`normalize_accept_encoding`](fn.normalize_accept_encoding.html) for
+    /// `Accept-Encoding`, and [`normalize_list`](fn.normalize_list.html) for
+    /// `Accept`, `Accept-Charset` and `Accept-Language`.
+    pub fn standard() -> VaryKeys {
+        VaryKeys::new()
+            .normalizer(h::ACCEPT, normalize_list)
+            .normalizer(h::ACCEPT_CHARSET, normalize_list)
+            .normalizer(h::ACCEPT_ENCODING, normalize_accept_encoding)
+            .normalizer(h::ACCEPT_LANGUAGE, normalize_list)
+    }
+
+    /// Register the normalizer of a header, replacing any previous one.
+    pub fn normalizer<F>(mut self, name: HeaderName, f: F) -> VaryKeys
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.normalizers.retain(|n| n.0 != name);
+        self.normalizers.push((name, Box::new(f)));
+        self
+    }
+
+    /// Compute the secondary key of a request, for a stored response with
+    /// the given `Vary` header. Returns `None` for `Vary: *`, which matches
+    /// no request.
+    pub fn key(&self, vary: &Vary, request: &HeaderMap) -> Option<SecondaryKey> {
+        let items = match *vary {
+            Vary::Any => return None,
+            Vary::Items(ref items) => items,
+        };
+        let mut names: Vec<String> = items.iter().map(|n| n.to_ascii_lowercase()).collect();
+        names.sort();
+        names.dedup();
+        let options = CanonicalOptions {
+            separator: ", ",
+            collapse_whitespace: true,
+        };
+        let mut key = String::new();
+        for name in names {
+            key.push_str(&name);
+            if request.contains_key(&name[..]) {
+                let values: Vec<String> = request
+                    .get_all(&name[..])
+                    .iter()
+                    .map(|v| canonical_value(v.as_bytes(), &options))
+                    .filter(|v| !v.is_empty())
+                    .collect();
+                let value = values.join(options.separator);
+                key.push(':');
+                match self.normalizers.iter().find(|n| n.0 == name[..]) {
+                    Some(n) => key.push_str(&(n.1)(&value)),
+                    None => key.push_str(&value),
+                }
+            }
+            key.push('\n');
+        }
+        Some(SecondaryKey(key))
+    }
+
+    /// Returns true if a request matches the secondary key of a stored
+    /// response with the given `Vary` header.
+    pub fn matches(&self, vary: &Vary, key: &SecondaryKey, request: &HeaderMap) -> bool {
+        self.key(vary, request).as_ref() == Some(key)
+    }
+}
+
+impl fmt::Debug for VaryKeys {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("VaryKeys")
+            .field(
+                "normalizers",
+                &self.normalizers.iter().map(|n| &n.0).collect::<Vec<_>>(),
+            )
+            .finish()
+    }
+}
+
+/// A secondary cache key, computed by [`VaryKeys`](struct.VaryKeys.html).
+///
+/// The key lists each nominated header as `name:value`, or only `name` if
+/// absent from the request, one per line.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SecondaryKey(String);
+
+impl SecondaryKey {
+    /// The key as a string, e.g. to store with a response.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl fmt::Display for SecondaryKey {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+/// Normalize a comma separated list, lowercased and without whitespace
+/// around items and parameters. The order of items is retained.
+///
+/// ```
+/// use hyperx::cache::normalize_list;
+///
+/// assert_eq!(normalize_list("en-US, fr ; q=0.5"), "en-us,fr;q=0.5");
+/// ```
+pub fn normalize_list(value: &str) -> String {
+    let items: Vec<String> = parsing::split_comma_delimited(value)
+        .map(|item| {
+            let params: Vec<&str> = parsing::split_delimited(item, ';').collect();
+            params.join(";").to_ascii_lowercase()
+        })
+        .collect();
+    items.join(",")
+}
+
+/// Normalize an `Accept-Encoding` value: codings are lowercased and
+/// sorted, codings with `q=0` removed, and `q=1` implied.
+///
+/// ```
+/// use hyperx::cache::normalize_accept_encoding;
+///
+/// assert_eq!(
+///     normalize_accept_encoding("gzip;q=1.0, BR, deflate;q=0, zstd; q=0.5"),
+///     "br,gzip,zstd;q=0.5"
+/// );
+/// ```
+pub fn normalize_accept_encoding(value: &str) -> String {
+    let mut codings: Vec<String> = Vec::new();
+    for item in parsing::split_comma_delimited(value) {
+        let mut params = parsing::split_delimited(item, ';');
+        let coding = match params.next() {
+            Some(coding) => coding.to_ascii_lowercase(),
+            None => continue,
+        };
+        let quality = params
+            .filter_map(|p| parsing::parse_parameter(p).ok())
+            .find(|p| p.0.eq_ignore_ascii_case("q"))
+            .and_then(|p| p.1)
+            .map(|q| parsing::parse_quality(&q));
+        match quality.map(|q| q.map(Quality::thousandths)) {
+            None | Some(Ok(1000)) => codings.push(coding),
+            Some(Ok(0)) => (),
+            Some(Ok(q)) => codings.push(format!(
+                "{};q=0.{}",
+                coding,
+                format!("{:03}", q).trim_end_matches('0')
+            )),
+            Some(Err(_)) => codings.push(normalize_list(item)),
+        }
+    }
+    codings.sort();
+    codings.dedup();
+    codings.join(",")
+}
+
+// Age values are limited to 2^31 seconds (RFC 9111 §1.2.2)
+fn age_secs(age: Duration) -> u32 {
+    age.as_secs().min(1 << 31) as u32
+}
+
+fn extension_secs(directive: &CacheDirective, name: &str) -> Option<u32> {
+    match *directive {
+        CacheDirective::Extension(ref n, Some(ref v)) if n.as_str() == name => v.parse().ok(),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::HeaderVec;
+    use header::HttpDate;
+
+    fn secs(s: u64) -> Duration {
+        Duration::from_secs(s)
+    }
+
+    fn stored(headers: &[(&str, &str)], received: SystemTime) -> StoredResponse {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            let name = ::http::header::HeaderName::from_bytes(name.as_bytes()).unwrap();
+            map.append(name, value.parse().unwrap());
+        }
+        StoredResponse::from_headers(&map, received - secs(1), received)
+    }
+
+    fn date(t: SystemTime) -> String {
+        HttpDate::from(t).to_string()
+    }
+
+    fn t0() -> SystemTime {
+        UNIX_EPOCH + secs(1_600_000_000)
+    }
+
+    #[test]
+    fn test_freshness_lifetime() {
+        let opts = CacheOptions::default();
+        let shared = CacheOptions {
+            shared: true,
+            ..opts
+        };
+        let r = stored(&[("cache-control", "max-age=60, s-maxage=120")], t0());
+        assert_eq!(r.freshness_lifetime(&opts), Some(secs(60)));
+        assert_eq!(r.freshness_lifetime(&shared), Some(secs(120)));
+
+        let r = stored(
+            &[
+                ("date", &date(t0() - secs(10))),
+                ("expires", &date(t0() + secs(290))),
+            ],
+            t0(),
+        );
+        assert_eq!(r.freshness_lifetime(&opts), Some(secs(300)));
+
+        let r = stored(&[("expires", "0")], t0());
+        assert_eq!(r.freshness_lifetime(&opts), Some(secs(0)));
+
+        let r = stored(&[("last-modified", &date(t0() - secs(1000)))], t0());
+        assert_eq!(r.freshness_lifetime(&opts), None);
+        assert_eq!(r.heuristic_freshness(&opts), Some(secs(100)));
+        assert_eq!(r.staleness(&opts, t0() + secs(150)), Some(secs(51)));
+        assert_eq!(r.staleness(&opts, t0() + secs(50)), None);
+
+        let r = stored(&[("last-modified", &date(t0() - secs(10_000_000)))], t0());
+        assert_eq!(r.heuristic_freshness(&opts), Some(opts.heuristic_max));
+    }
+
+    #[test]
+    fn test_current_age() {
+        // Date 5s before receipt, Age 2 plus 1s response delay
+        let r = stored(&[("date", &date(t0() - secs(5))), ("age", "2")], t0());
+        assert_eq!(r.current_age(t0()), secs(5));
+        assert_eq!(r.current_age(t0() + secs(10)), secs(15));
+
+        let r = stored(&[("date", &date(t0())), ("age", "30")], t0());
+        assert_eq!(r.current_age(t0() + secs(10)), secs(41));
+        assert_eq!(r.age_header(t0() + secs(10)), Age(41));
+    }
+
+    #[test]
+    fn test_clock_skew() {
+        // Received at t0, requested at t0 - 1s
+        let r = stored(&[("date", &date(t0()))], t0());
+        assert_eq!(r.clock_skew(), None);
+        let r = stored(&[("date", &date(t0() - secs(2)))], t0());
+        assert_eq!(r.clock_skew(), None);
+        let r = stored(&[("date", &date(t0() + secs(30)))], t0());
+        assert_eq!(r.clock_skew(), Some(ClockSkew::Ahead(secs(30))));
+        assert_eq!(r.current_age(t0()), secs(1));
+        let r = stored(&[("date", &date(t0() - secs(100))), ("age", "40")], t0());
+        assert_eq!(r.clock_skew(), Some(ClockSkew::Behind(secs(58))));
+        assert_eq!(stored(&[], t0()).clock_skew(), None);
+    }
+
+    #[test]
+    fn test_current_age_fn() {
+        let sent = t0() - secs(3);
+        assert_eq!(current_age(None, None, sent, t0(), t0()), secs(3));
+        assert_eq!(
+            current_age(Some(t0() - secs(60)), Some(secs(5)), sent, t0(), t0()),
+            secs(60)
+        );
+        assert_eq!(
+            current_age(None, Some(secs(5)), t0() + secs(1), t0(), t0() + secs(2)),
+            secs(7)
+        );
+        assert_eq!(age_secs(secs(u64::MAX)), 1 << 31);
+    }
+
+    #[test]
+    fn test_decide() {
+        let opts = CacheOptions::default();
+        let cc = |s: &str| s.parse::<CacheControl>().unwrap();
+        let revalidate = CacheDecision::Revalidate {
+            stale_if_error: false,
+        };
+
+        let r = stored(
+            &[("date", &date(t0())), ("cache-control", "max-age=60")],
+            t0(),
+        );
+        assert_eq!(r.decide(None, &opts, t0() + secs(30)), CacheDecision::Serve);
+        assert_eq!(r.decide(None, &opts, t0() + secs(60)), revalidate);
+        let req = cc("max-stale=10");
+        assert_eq!(
+            r.decide(Some(&req), &opts, t0() + secs(65)),
+            CacheDecision::ServeStale
+        );
+        assert_eq!(r.decide(Some(&req), &opts, t0() + secs(75)), revalidate);
+        let req = cc("max-stale");
+        assert_eq!(
+            r.decide(Some(&req), &opts, t0() + secs(1000)),
+            CacheDecision::ServeStale
+        );
+        let req = cc("max-age=10");
+        assert_ne!(
+            r.decide(Some(&req), &opts, t0() + secs(30)),
+            CacheDecision::Serve
+        );
+        let req = cc("min-fresh=40");
+        assert_ne!(
+            r.decide(Some(&req), &opts, t0() + secs(30)),
+            CacheDecision::Serve
+        );
+        let req = cc("no-cache");
+        assert_eq!(
+            r.decide(Some(&req), &opts, t0()),
+            CacheDecision::Revalidate {
+                stale_if_error: true
+            }
+        );
+
+        let r = stored(
+            &[
+                ("date", &date(t0())),
+                (
+                    "cache-control",
+                    "max-age=60, stale-while-revalidate=30, stale-if-error=600",
+                ),
+            ],
+            t0(),
+        );
+        assert_eq!(
+            r.decide(None, &opts, t0() + secs(80)),
+            CacheDecision::ServeWhileRevalidate
+        );
+        assert_eq!(
+            r.decide(None, &opts, t0() + secs(100)),
+            CacheDecision::Revalidate {
+                stale_if_error: true
+            }
+        );
+        assert_eq!(r.decide(None, &opts, t0() + secs(1000)), revalidate);
+
+        let r = stored(
+            &[
+                ("date", &date(t0())),
+                (
+                    "cache-control",
+                    "max-age=60, must-revalidate, stale-if-error=600",
+                ),
+            ],
+            t0(),
+        );
+        let req = cc("max-stale=100");
+        assert_eq!(r.decide(Some(&req), &opts, t0() + secs(80)), revalidate);
+
+        let r = stored(
+            &[("date", &date(t0())), ("cache-control", "no-cache")],
+            t0(),
+        );
+        assert_eq!(r.decide(None, &opts, t0()), revalidate);
+    }
+
+    #[test]
+    fn test_encode_validators() {
+        let r = stored(
+            &[
+                ("etag", "\"xyzzy\""),
+                ("last-modified", "Sun, 06 Nov 1994 08:49:37 GMT"),
+            ],
+            t0(),
+        );
+        let mut headers = HeaderMap::new();
+        r.encode_validators(&mut headers);
+        assert_eq!(headers["if-none-match"], "\"xyzzy\"");
+        assert_eq!(
+            headers["if-modified-since"],
+            "Sun, 06 Nov 1994 08:49:37 GMT"
+        );
+    }
+
+    fn vary(s: &str) -> Vary {
+        s.parse().unwrap()
+    }
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_vary_key() {
+        let keys = VaryKeys::new();
+        let headers = request(&[
+            ("accept-language", " en,  fr "),
+            ("accept-language", "de"),
+            ("x-empty", ""),
+        ]);
+        let key = keys
+            .key(
+                &vary("X-Missing, Accept-Language, x-empty, accept-language"),
+                &headers,
+            )
+            .unwrap();
+        assert_eq!(
+            key.as_str(),
+            "accept-language:en, fr, de\nx-empty:\nx-missing\n"
+        );
+        assert_eq!(key.to_string(), key.as_str());
+        assert_eq!(keys.key(&Vary::Any, &headers), None);
+        assert!(!keys.matches(&Vary::Any, &key, &headers));
+        assert_eq!(
+            keys.key(&Vary::Items(HeaderVec::new()), &headers)
+                .unwrap()
+                .as_str(),
+            ""
+        );
+
+        // Absent and empty headers differ
+        let v = vary("x-empty");
+        let empty = keys.key(&v, &headers).unwrap();
+        assert!(!keys.matches(&v, &empty, &HeaderMap::new()));
+    }
+
+    #[test]
+    fn test_vary_normalizers() {
+        let v = vary("accept-encoding, x-id");
+        let stored = request(&[("accept-encoding", "gzip, br"), ("x-id", "A")]);
+        let same = request(&[("accept-encoding", "br;q=1.0,GZIP"), ("x-id", "a")]);
+
+        let keys = VaryKeys::standard();
+        let key = keys.key(&v, &stored).unwrap();
+        assert!(!keys.matches(&v, &key, &same));
+
+        let keys = keys.normalizer(HeaderName::from_static("x-id"), |v: &str| v.to_lowercase());
+        let key = keys.key(&v, &stored).unwrap();
+        assert!(keys.matches(&v, &key, &same));
+        assert!(!VaryKeys::new().matches(&v, &key, &same));
+        assert_eq!(
+            format!("{:?}", keys),
+            "VaryKeys { normalizers: [\"accept\", \"accept-charset\", \"accept-encoding\", \"accept-language\", \"x-id\"] }"
+        );
+    }
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize_accept_encoding(""), "");
+        assert_eq!(normalize_accept_encoding("gzip, gzip;q=1, *;q=0"), "gzip");
+        assert_eq!(
+            normalize_accept_encoding("br;q=0.50, gzip;q=x"),
+            "br;q=0.5,gzip;q=x"
+        );
+        assert_eq!(
+            normalize_list("text/html ;level=1 , */*;Q=0.1"),
+            "text/html;level=1,*/*;q=0.1"
+        );
+    }
+}
@@ -0,0 +1,269 @@
+//! Canonical header serialization, for request signing.
+//!
+//! Signing schemes such as AWS Signature Version 4 sign a canonical form of
+//! the request headers, so that the signature survives harmless changes in
+//! transit: names are lowercased, values trimmed with inner whitespace
+//! collapsed, headers sorted by name, and the values of a repeated header
+//! joined in order. [`canonicalize`](fn.canonicalize.html) produces this
+//! form as [`CanonicalHeaders`](struct.CanonicalHeaders.html), from a
+//! `http::HeaderMap` (or a `Headers` map, with the _headers_ feature).
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::canonical::{canonicalize, CanonicalOptions};
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("host", "iam.amazonaws.com".parse().unwrap());
+//! headers.insert("x-amz-date", "20150830T123600Z".parse().unwrap());
+//! headers.insert("content-type", "application/x-www-form-urlencoded;  charset=utf-8".parse().unwrap());
+//! headers.append("my-header1", "  a   b   c  ".parse().unwrap());
+//! headers.append("my-header1", "\"a   b   c\"".parse().unwrap());
+//! headers.insert("user-agent", "unsigned".parse().unwrap());
+//!
+//! let canonical = canonicalize(&headers, &CanonicalOptions::default())
+//!     .only(&["content-type", "host", "my-header1", "x-amz-date"]);
+//! assert_eq!(canonical.signed_headers(), "content-type;host;my-header1;x-amz-date");
+//! assert_eq!(
+//!     canonical.to_string(),
+//!     "content-type:application/x-www-form-urlencoded; charset=utf-8\n\
+//!      host:iam.amazonaws.com\n\
+//!      my-header1:a b c,\"a b c\"\n\
+//!      x-amz-date:20150830T123600Z\n"
+//! );
+//! ```
+//!
+//! Values which are not valid UTF-8 are converted lossily. Empty values of a
+//! repeated header are skipped when joining.
+
+use std::fmt;
+
+use http::HeaderMap;
+
+#[cfg(feature = "headers")]
+use header::{Headers, RawLike};
+
+/// Options of [`canonicalize`](fn.canonicalize.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CanonicalOptions {
+    /// The separator joining the values of a repeated header. Default: `,`.
+    pub separator: &'static str,
+    /// Whether runs of spaces and tabs within a value are collapsed to a
+    /// single space. Default: true.
+    pub collapse_whitespace: bool,
+}
+
+impl Default for CanonicalOptions {
+    fn default() -> CanonicalOptions {
+        CanonicalOptions {
+            separator: ",",
+            collapse_whitespace: true,
+        }
+    }
+}
+
+/// Headers in canonical form, sorted by lowercase name, with one joined
+/// value per name.
+///
+/// The `Display` form is the canonical headers block of AWS Signature
+/// Version 4: a `name:value` line per header, each terminated by `\n`.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CanonicalHeaders {
+    entries: Vec<(String, String)>,
+}
+
+impl CanonicalHeaders {
+    /// Retain only the headers with the given names (case-insensitive), e.g.
+    /// the headers to sign.
+    pub fn only(mut self, names: &[&str]) -> CanonicalHeaders {
+        self.entries
+            .retain(|e| names.iter().any(|n| n.eq_ignore_ascii_case(&e.0)));
+        self
+    }
+
+    /// Retain only the headers for which the predicate on the lowercase
+    /// name returns true.
+    pub fn retain<F: FnMut(&str) -> bool>(mut self, mut f: F) -> CanonicalHeaders {
+        self.entries.retain(|e| f(&e.0));
+        self
+    }
+
+    /// Returns the canonical value of a header, if present.
+    pub fn get(&self, name: &str) -> Option<&str> {
+        let name = name.to_ascii_lowercase();
+        self.entries
+            .binary_search_by(|e| e.0.cmp(&name))
+            .ok()
+            .map(|i| &self.entries[i].1[..])
+    }
+
+    /// The semicolon-separated lowercase names, e.g. the `SignedHeaders` of
+    /// AWS Signature Version 4.
+    pub fn signed_headers(&self) -> String {
+        let names: Vec<&str> = self.entries.iter().map(|e| &e.0[..]).collect();
+        names.join(";")
+    }
+
+    /// Returns an iterator over the lowercase names and canonical values,
+    /// in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> {
+        self.entries.iter().map(|e| (&e.0[..], &e.1[..]))
+    }
+
+    /// Returns the number of headers.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if there are no headers.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    // Append a value of a header, keeping the entries sorted by name.
+    fn push(&mut self, name: &str, value: &[u8], options: &CanonicalOptions) {
+        let name = name.to_ascii_lowercase();
+        let i = match self.entries.binary_search_by(|e| e.0.cmp(&name)) {
+            Ok(i) => i,
+            Err(i) => {
+                self.entries.insert(i, (name, String::new()));
+                i
+            }
+        };
+        let value = canonical_value(value, options);
+        if !value.is_empty() {
+            let joined = &mut self.entries[i].1;
+            if !joined.is_empty() {
+                joined.push_str(options.separator);
+            }
+            joined.push_str(&value);
+        }
+    }
+}
+
+impl fmt::Display for CanonicalHeaders {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (name, value) in &self.entries {
+            writeln!(f, "{}:{}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Canonicalize all headers of a `http::HeaderMap`.
+pub fn canonicalize(headers: &HeaderMap, options: &CanonicalOptions) -> CanonicalHeaders {
+    let mut canonical = CanonicalHeaders::default();
+    for (name, value) in headers {
+        canonical.push(name.as_str(), value.as_bytes(), options);
+    }
+    canonical
+}
+
+/// Canonicalize all headers of a `Headers` map.
+#[cfg(feature = "headers")]
+pub fn canonicalize_headers(headers: &Headers, options: &CanonicalOptions) -> CanonicalHeaders {
+    let mut canonical = CanonicalHeaders::default();
+    for view in headers.iter() {
+        for value in view.raw().iter() {
+            canonical.push(view.name(), value, options);
+        }
+    }
+    canonical
+}
+
+/// Canonicalize a single header value: trim leading and trailing
+/// whitespace, and collapse inner runs of spaces and tabs to a single space
+/// if enabled by the options.
+///
+/// ```
+/// use hyperx::canonical::{canonical_value, CanonicalOptions};
+///
+/// let options = CanonicalOptions::default();
+/// assert_eq!(canonical_value(b" a \t b  ", &options), "a b");
+/// ```
+pub fn canonical_value(value: &[u8], options: &CanonicalOptions) -> String {
+    let value = String::from_utf8_lossy(value);
+    let value = value.trim_matches(|c| c == ' ' || c == '\t');
+    if !options.collapse_whitespace {
+        return value.to_owned();
+    }
+    let mut out = String::with_capacity(value.len());
+    let mut space = false;
+    for c in value.chars() {
+        if c == ' ' || c == '\t' {
+            space = true;
+        } else {
+            if space {
+                out.push(' ');
+                space = false;
+            }
+            out.push(c);
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_canonicalize() {
+        let headers = map(&[
+            ("x-b", "2"),
+            ("x-a", " 1\t\t1 "),
+            ("x-b", ""),
+            ("x-b", "3"),
+            ("x-empty", ""),
+        ]);
+        let canonical = canonicalize(&headers, &CanonicalOptions::default());
+        assert_eq!(canonical.len(), 3);
+        assert_eq!(canonical.signed_headers(), "x-a;x-b;x-empty");
+        assert_eq!(canonical.get("X-B"), Some("2,3"));
+        assert_eq!(canonical.to_string(), "x-a:1 1\nx-b:2,3\nx-empty:\n");
+
+        let options = CanonicalOptions {
+            separator: ", ",
+            collapse_whitespace: false,
+        };
+        let canonical = canonicalize(&headers, &options);
+        assert_eq!(canonical.get("x-a"), Some("1\t\t1"));
+        assert_eq!(canonical.get("x-b"), Some("2, 3"));
+    }
+
+    #[test]
+    fn test_select() {
+        let headers = map(&[("host", "a"), ("x-amz-date", "b"), ("accept", "c")]);
+        let canonical = canonicalize(&headers, &CanonicalOptions::default());
+        let signed = canonical.clone().only(&["Host", "X-Amz-Date", "missing"]);
+        assert_eq!(signed.signed_headers(), "host;x-amz-date");
+        let amz = canonical.retain(|n| n.starts_with("x-amz-"));
+        assert_eq!(amz.iter().collect::<Vec<_>>(), vec![("x-amz-date", "b")]);
+        assert!(CanonicalHeaders::default().is_empty());
+    }
+
+    #[test]
+    fn test_non_utf8() {
+        let options = CanonicalOptions::default();
+        assert_eq!(canonical_value(b"a\xffb", &options), "a\u{fffd}b");
+    }
+
+    #[cfg(feature = "headers")]
+    #[test]
+    fn test_headers() {
+        let mut headers = Headers::new();
+        headers.set_raw("Host", "example.com");
+        headers.append_raw("X-Multi", "a  b");
+        headers.append_raw("X-Multi", "c");
+        let canonical = canonicalize_headers(&headers, &CanonicalOptions::default());
+        assert_eq!(canonical.to_string(), "host:example.com\nx-multi:a b,c\n");
+    }
+}
@@ -0,0 +1,239 @@
+//! Client hints negotiation, per [RFC 8942] and [Client Hint Reliability].
+//!
+//! A server declares the client hints it would like to receive with a
+//! [`ClientHints`](struct.ClientHints.html) set, some of which may be
+//! critical: hints without which the response would differ. Evaluating the
+//! set against a request yields a [`HintsOutcome`](struct.HintsOutcome.html):
+//! the hints which arrived, those which did not, the `Accept-CH`,
+//! `Critical-CH` and `Vary` headers of the response, and whether the client
+//! should retry the request with a missing critical hint.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::client_hints::ClientHints;
+//!
+//! let hints = ClientHints::new()
+//!     .hint("Sec-CH-UA-Platform")
+//!     .critical("Sec-CH-Prefers-Color-Scheme");
+//!
+//! let mut request = http::HeaderMap::new();
+//! request.insert("sec-ch-ua-platform", "\"Linux\"".parse().unwrap());
+//!
+//! let outcome = hints.evaluate(&request);
+//! assert_eq!(outcome.received, vec!["Sec-CH-UA-Platform"]);
+//! assert_eq!(outcome.missing, vec!["Sec-CH-Prefers-Color-Scheme"]);
+//! assert!(outcome.retry);
+//!
+//! let mut response = http::HeaderMap::new();
+//! outcome.encode(&mut response);
+//! assert_eq!(
+//!     response["accept-ch"],
+//!     "Sec-CH-UA-Platform, Sec-CH-Prefers-Color-Scheme"
+//! );
+//! assert_eq!(response["critical-ch"], "Sec-CH-Prefers-Color-Scheme");
+//! assert_eq!(
+//!     response["vary"],
+//!     "Sec-CH-UA-Platform, Sec-CH-Prefers-Color-Scheme"
+//! );
+//! ```
+//!
+//! [RFC 8942]: https://www.rfc-editor.org/rfc/rfc8942
+//! [Client Hint Reliability]: https://datatracker.ietf.org/doc/html/draft-davidben-http-client-hint-reliability
+
+use http::HeaderMap;
+use unicase::Ascii;
+
+use header::{AcceptCh, CriticalCh, TypedHeaders, Vary};
+
+/// The client hints a server would like to receive, constructed via builder
+/// methods.
+///
+/// Hints are header field names, such as `Sec-CH-UA-Platform`, compared
+/// case-insensitively.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ClientHints {
+    hints: Vec<Ascii<String>>,
+    critical: Vec<Ascii<String>>,
+}
+
+impl ClientHints {
+    /// Create a new, empty set of hints.
+    pub fn new() -> ClientHints {
+        ClientHints::default()
+    }
+
+    /// Request a hint, to be listed in `Accept-CH`.
+    pub fn hint<S: Into<String>>(mut self, name: S) -> ClientHints {
+        let name = Ascii::new(name.into());
+        if !self.hints.contains(&name) {
+            self.hints.push(name);
+        }
+        self
+    }
+
+    /// Request a critical hint, to be listed in both `Accept-CH` and
+    /// `Critical-CH`.
+    pub fn critical<S: Into<String>>(self, name: S) -> ClientHints {
+        let name = name.into();
+        let mut hints = self.hint(name.clone());
+        let name = Ascii::new(name);
+        if !hints.critical.contains(&name) {
+            hints.critical.push(name);
+        }
+        hints
+    }
+
+    /// The requested hints, including the critical hints, in order.
+    pub fn hints(&self) -> &[Ascii<String>] {
+        &self.hints
+    }
+
+    /// The critical hints, in order.
+    pub fn critical_hints(&self) -> &[Ascii<String>] {
+        &self.critical
+    }
+
+    /// Evaluate the hints of a request.
+    pub fn evaluate(&self, request: &HeaderMap) -> HintsOutcome {
+        let (received, missing): (Vec<_>, Vec<_>) = self
+            .hints
+            .iter()
+            .cloned()
+            .partition(|name| request.contains_key(name.as_str()));
+        let retry = self.critical.iter().any(|name| missing.contains(name));
+        HintsOutcome {
+            accept_ch: if self.hints.is_empty() {
+                None
+            } else {
+                Some(AcceptCh(self.hints.iter().cloned().collect()))
+            },
+            critical_ch: if self.critical.is_empty() {
+                None
+            } else {
+                Some(CriticalCh(self.critical.iter().cloned().collect()))
+            },
+            // The response depends on each hint, whether sent or not
+            vary: if self.hints.is_empty() {
+                None
+            } else {
+                Some(Vary::Items(self.hints.iter().cloned().collect()))
+            },
+            received,
+            missing,
+            retry,
+        }
+    }
+}
+
+/// The result of evaluating [`ClientHints`](struct.ClientHints.html)
+/// against a request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HintsOutcome {
+    /// The requested hints present in the request.
+    pub received: Vec<Ascii<String>>,
+    /// The requested hints absent from the request.
+    pub missing: Vec<Ascii<String>>,
+    /// True if a critical hint is missing. The response should then still
+    /// include `Accept-CH` and `Critical-CH`, so that a client supporting
+    /// the hint retries the request with it.
+    pub retry: bool,
+    /// The `Accept-CH` header, if any hints are requested.
+    pub accept_ch: Option<AcceptCh>,
+    /// The `Critical-CH` header, if any critical hints are requested.
+    pub critical_ch: Option<CriticalCh>,
+    /// The `Vary` header additions, listing each requested hint.
+    pub vary: Option<Vary>,
+}
+
+impl HintsOutcome {
+    /// Encode the headers into a response `http::HeaderMap`, replacing any
+    /// existing `Accept-CH` and `Critical-CH` headers. `Vary` is appended to
+    /// any existing value.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if let Some(ref h) = self.accept_ch {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.critical_ch {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.vary {
+            headers.encode_append(h);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_builder() {
+        let hints = ClientHints::new()
+            .hint("Sec-CH-UA-Model")
+            .critical("sec-ch-ua-model")
+            .critical("Sec-CH-UA-Model")
+            .hint("DPR");
+        assert_eq!(hints.hints().len(), 2);
+        assert_eq!(hints.critical_hints(), &[Ascii::new("sec-ch-ua-model")]);
+    }
+
+    #[test]
+    fn test_all_received() {
+        let hints = ClientHints::new()
+            .hint("Sec-CH-UA-Platform")
+            .critical("Sec-CH-UA-Mobile");
+        let outcome = hints.evaluate(&request(&[
+            ("sec-ch-ua-platform", "\"Windows\""),
+            ("sec-ch-ua-mobile", "?0"),
+        ]));
+        assert_eq!(outcome.received.len(), 2);
+        assert!(outcome.missing.is_empty());
+        assert!(!outcome.retry);
+    }
+
+    #[test]
+    fn test_missing_non_critical() {
+        let hints = ClientHints::new().hint("Sec-CH-UA-Platform");
+        let outcome = hints.evaluate(&HeaderMap::new());
+        assert_eq!(outcome.missing, vec![Ascii::new("Sec-CH-UA-Platform")]);
+        assert!(!outcome.retry);
+        assert_eq!(outcome.critical_ch, None);
+
+        let mut response = HeaderMap::new();
+        outcome.encode(&mut response);
+        assert_eq!(response["accept-ch"], "Sec-CH-UA-Platform");
+        assert!(!response.contains_key("critical-ch"));
+    }
+
+    #[test]
+    fn test_empty() {
+        let outcome = ClientHints::new().evaluate(&request(&[("sec-ch-ua", "x")]));
+        assert!(outcome.received.is_empty());
+        assert!(!outcome.retry);
+
+        let mut response = HeaderMap::new();
+        outcome.encode(&mut response);
+        assert!(response.is_empty());
+    }
+
+    #[test]
+    fn test_vary_appended() {
+        let hints = ClientHints::new().critical("Sec-CH-Prefers-Reduced-Motion");
+        let mut response = request(&[("vary", "Accept-Encoding")]);
+        hints.evaluate(&HeaderMap::new()).encode(&mut response);
+        let vary: Vec<_> = response.get_all("vary").iter().collect();
+        assert_eq!(
+            vary,
+            vec!["Accept-Encoding", "Sec-CH-Prefers-Reduced-Motion"]
+        );
+    }
+}
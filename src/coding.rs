@@ -0,0 +1,394 @@
+//! Validation of the content-coding and transfer-coding pipeline of a
+//! message, per [RFC 9110 §8.4] and [RFC 9112 §6.1].
+//!
+//! The `Content-Encoding` and `Transfer-Encoding` headers each list the
+//! codings applied to a message, in the order they were applied, content
+//! codings first. A [`CodingPlan`](struct.CodingPlan.html) validates that
+//! combination and lists the steps to undo it for a received message, or
+//! to apply it for a response, checked against the codings the client
+//! accepts via `Accept-Encoding` and `TE`.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::coding::{CodingPlan, Layer, Step};
+//! use hyperx::header::Encoding;
+//!
+//! let mut request = http::HeaderMap::new();
+//! request.insert("accept-encoding", "gzip, br;q=0.5".parse().unwrap());
+//!
+//! let mut response = http::HeaderMap::new();
+//! response.insert("content-encoding", "gzip".parse().unwrap());
+//! response.insert("transfer-encoding", "chunked".parse().unwrap());
+//!
+//! let plan = CodingPlan::encode(&response, &request).unwrap();
+//! assert_eq!(
+//!     plan.steps,
+//!     vec![
+//!         Step::new(Layer::Content, Encoding::Gzip),
+//!         Step::new(Layer::Transfer, Encoding::Chunked),
+//!     ]
+//! );
+//!
+//! response.insert("content-encoding", "deflate".parse().unwrap());
+//! assert!(CodingPlan::encode(&response, &request).is_err());
+//! ```
+//!
+//! [RFC 9110 §8.4]: https://www.rfc-editor.org/rfc/rfc9110#section-8.4
+//! [RFC 9112 §6.1]: https://www.rfc-editor.org/rfc/rfc9112#section-6.1
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use http::HeaderMap;
+
+use header::{
+    AcceptEncoding, ContentEncoding, Encoding, HeaderVec, Te, TransferEncoding, TypedHeaders,
+};
+use negotiate::encoding_quality;
+
+/// The layer of a coding.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Layer {
+    /// A content coding, of the `Content-Encoding` header, which is a
+    /// property of the representation.
+    Content,
+    /// A transfer coding, of the `Transfer-Encoding` header, which applies
+    /// to a single hop.
+    Transfer,
+}
+
+/// A step of a [`CodingPlan`](struct.CodingPlan.html): a coding to apply or
+/// to undo.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Step {
+    /// The layer of the coding.
+    pub layer: Layer,
+    /// The coding, which is never `identity`.
+    pub coding: Encoding,
+}
+
+impl Step {
+    /// Create a new step.
+    pub fn new(layer: Layer, coding: Encoding) -> Step {
+        Step { layer, coding }
+    }
+}
+
+/// Whether a [`CodingPlan`](struct.CodingPlan.html) applies or undoes its
+/// codings.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Direction {
+    /// Apply each coding, to send a message.
+    Encode,
+    /// Undo each coding, to read a received message.
+    Decode,
+}
+
+/// The validated codings of a message, as the steps to execute in order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CodingPlan {
+    /// Whether the steps apply or undo their codings.
+    pub direction: Direction,
+    /// The steps, in the order to execute them.
+    pub steps: Vec<Step>,
+}
+
+impl CodingPlan {
+    /// Validate the codings of a received message, returning the steps to
+    /// decode its content: the transfer codings in reverse order, then the
+    /// content codings in reverse order.
+    ///
+    /// Each coding other than `chunked` must be one of `supported`.
+    pub fn decode(headers: &HeaderMap, supported: &[Encoding]) -> Result<CodingPlan, CodingError> {
+        let mut steps = codings(headers)?;
+        for step in &steps {
+            if step.coding != Encoding::Chunked && !supported.contains(&step.coding) {
+                return Err(CodingError::Unsupported(step.layer, step.coding.clone()));
+            }
+        }
+        steps.reverse();
+        Ok(CodingPlan {
+            direction: Direction::Decode,
+            steps,
+        })
+    }
+
+    /// Validate the codings of a response to a request, returning the steps
+    /// to encode its content: the content codings in order, then the
+    /// transfer codings in order.
+    ///
+    /// Each content coding must be acceptable per the `Accept-Encoding`
+    /// header of the request, if any, and each transfer coding other than
+    /// `chunked` must be listed in the `TE` header of the request.
+    pub fn encode(response: &HeaderMap, request: &HeaderMap) -> Result<CodingPlan, CodingError> {
+        let steps = codings(response)?;
+        let accept_encoding = match request.try_decode::<AcceptEncoding>() {
+            Some(Ok(a)) => Some(a),
+            Some(Err(_)) => return Err(CodingError::Invalid("Accept-Encoding")),
+            None => None,
+        };
+        let te = match request.try_decode::<Te>() {
+            Some(Ok(te)) => te.0,
+            Some(Err(_)) => return Err(CodingError::Invalid("TE")),
+            None => HeaderVec::new(),
+        };
+        for step in &steps {
+            let accepted = match step.layer {
+                Layer::Content => {
+                    encoding_quality(accept_encoding.as_ref(), Some(&step.coding)) > 0
+                }
+                Layer::Transfer => {
+                    step.coding == Encoding::Chunked
+                        || te
+                            .iter()
+                            .any(|qi| qi.item == step.coding && qi.quality.thousandths() > 0)
+                }
+            };
+            if !accepted {
+                return Err(CodingError::NotAccepted(step.layer, step.coding.clone()));
+            }
+        }
+        Ok(CodingPlan {
+            direction: Direction::Encode,
+            steps,
+        })
+    }
+
+    /// Returns true if there are no steps, as for unencoded content.
+    pub fn is_identity(&self) -> bool {
+        self.steps.is_empty()
+    }
+
+    /// Returns true if the message is delimited by the `chunked` transfer
+    /// coding.
+    pub fn is_chunked(&self) -> bool {
+        self.steps
+            .iter()
+            .any(|s| s.layer == Layer::Transfer && s.coding == Encoding::Chunked)
+    }
+}
+
+// The codings of a message, in the order applied, without `identity`.
+fn codings(headers: &HeaderMap) -> Result<Vec<Step>, CodingError> {
+    let content = match headers.try_decode::<ContentEncoding>() {
+        Some(Ok(ce)) => ce.0,
+        Some(Err(_)) => return Err(CodingError::Invalid("Content-Encoding")),
+        None => HeaderVec::new(),
+    };
+    let transfer = match headers.try_decode::<TransferEncoding>() {
+        Some(Ok(ref te)) if te.is_empty() => return Err(CodingError::Invalid("Transfer-Encoding")),
+        Some(Ok(te)) => te.0,
+        Some(Err(_)) => return Err(CodingError::Invalid("Transfer-Encoding")),
+        None => HeaderVec::new(),
+    };
+    let mut steps = Vec::with_capacity(content.len() + transfer.len());
+    for coding in content {
+        match coding {
+            Encoding::Identity => {}
+            Encoding::Chunked | Encoding::Trailers => {
+                return Err(CodingError::Misplaced(Layer::Content, coding))
+            }
+            coding => steps.push(Step::new(Layer::Content, coding)),
+        }
+    }
+    if transfer.iter().any(|c| *c != Encoding::Identity)
+        && transfer.last() != Some(&Encoding::Chunked)
+    {
+        return Err(CodingError::ChunkedNotLast);
+    }
+    let last = transfer.len().saturating_sub(1);
+    for (i, coding) in transfer.into_iter().enumerate() {
+        match coding {
+            Encoding::Identity => {}
+            Encoding::Chunked if i != last => return Err(CodingError::ChunkedNotLast),
+            Encoding::Trailers => return Err(CodingError::Misplaced(Layer::Transfer, coding)),
+            coding => steps.push(Step::new(Layer::Transfer, coding)),
+        }
+    }
+    Ok(steps)
+}
+
+/// The reason the codings of a message are invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CodingError {
+    /// A coding header is invalid, such as an empty `Transfer-Encoding`.
+    Invalid(&'static str),
+    /// Transfer codings are applied, but `chunked` is not the final one,
+    /// or is applied more than once.
+    ChunkedNotLast,
+    /// A coding is not valid in the layer, such as `chunked` as a content
+    /// coding, or `trailers` other than in `TE`.
+    Misplaced(Layer, Encoding),
+    /// A received coding is not supported.
+    Unsupported(Layer, Encoding),
+    /// A coding of a response is not accepted by the request.
+    NotAccepted(Layer, Encoding),
+}
+
+impl fmt::Display for CodingError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CodingError::Invalid(name) => write!(f, "invalid {} header", name),
+            CodingError::ChunkedNotLast => f.write_str("chunked is not the final transfer coding"),
+            CodingError::Misplaced(layer, ref c) => {
+                write!(f, "{} is not a {}", c, layer_name(layer))
+            }
+            CodingError::Unsupported(layer, ref c) => {
+                write!(f, "unsupported {} {}", layer_name(layer), c)
+            }
+            CodingError::NotAccepted(layer, ref c) => {
+                write!(f, "{} {} not accepted", layer_name(layer), c)
+            }
+        }
+    }
+}
+
+impl StdError for CodingError {}
+
+fn layer_name(layer: Layer) -> &'static str {
+    match layer {
+        Layer::Content => "content coding",
+        Layer::Transfer => "transfer coding",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headers(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn content(c: Encoding) -> Step {
+        Step::new(Layer::Content, c)
+    }
+
+    fn transfer(c: Encoding) -> Step {
+        Step::new(Layer::Transfer, c)
+    }
+
+    #[test]
+    fn test_decode() {
+        let message = headers(&[
+            ("content-encoding", "gzip, identity"),
+            ("content-encoding", "br"),
+            ("transfer-encoding", "deflate, chunked"),
+        ]);
+        let plan = CodingPlan::decode(
+            &message,
+            &[Encoding::Gzip, Encoding::Brotli, Encoding::Deflate],
+        )
+        .unwrap();
+        assert_eq!(plan.direction, Direction::Decode);
+        assert_eq!(
+            plan.steps,
+            vec![
+                transfer(Encoding::Chunked),
+                transfer(Encoding::Deflate),
+                content(Encoding::Brotli),
+                content(Encoding::Gzip),
+            ]
+        );
+        assert!(plan.is_chunked());
+
+        assert_eq!(
+            CodingPlan::decode(&message, &[Encoding::Gzip, Encoding::Deflate]),
+            Err(CodingError::Unsupported(Layer::Content, Encoding::Brotli))
+        );
+
+        let plan = CodingPlan::decode(&HeaderMap::new(), &[]).unwrap();
+        assert!(plan.is_identity());
+        assert!(!plan.is_chunked());
+    }
+
+    #[test]
+    fn test_chunked_not_last() {
+        for te in &["chunked, gzip", "gzip", "chunked, chunked"] {
+            let message = headers(&[("transfer-encoding", te)]);
+            assert_eq!(
+                CodingPlan::decode(&message, &[Encoding::Gzip]),
+                Err(CodingError::ChunkedNotLast),
+                "{}",
+                te
+            );
+        }
+    }
+
+    #[test]
+    fn test_misplaced() {
+        let message = headers(&[("content-encoding", "gzip, chunked")]);
+        assert_eq!(
+            CodingPlan::decode(&message, &[Encoding::Gzip]),
+            Err(CodingError::Misplaced(Layer::Content, Encoding::Chunked))
+        );
+        let message = headers(&[("transfer-encoding", "trailers, chunked")]);
+        assert_eq!(
+            CodingPlan::decode(&message, &[]),
+            Err(CodingError::Misplaced(Layer::Transfer, Encoding::Trailers))
+        );
+        let message = headers(&[("transfer-encoding", "")]);
+        assert_eq!(
+            CodingPlan::decode(&message, &[]),
+            Err(CodingError::Invalid("Transfer-Encoding"))
+        );
+    }
+
+    #[test]
+    fn test_encode_accept_encoding() {
+        let response = headers(&[("content-encoding", "br")]);
+        assert!(CodingPlan::encode(&response, &HeaderMap::new()).is_ok());
+        for accept in &["*", "br;q=0.1", "gzip, *;q=0.5"] {
+            let request = headers(&[("accept-encoding", accept)]);
+            assert!(
+                CodingPlan::encode(&response, &request).is_ok(),
+                "{}",
+                accept
+            );
+        }
+        for accept in &["", "gzip", "br;q=0", "*, br;q=0"] {
+            let request = headers(&[("accept-encoding", accept)]);
+            assert_eq!(
+                CodingPlan::encode(&response, &request),
+                Err(CodingError::NotAccepted(Layer::Content, Encoding::Brotli)),
+                "{}",
+                accept
+            );
+        }
+
+        let request = headers(&[("accept-encoding", "identity;q=0")]);
+        let plan = CodingPlan::encode(&headers(&[]), &request).unwrap();
+        assert!(plan.is_identity());
+    }
+
+    #[test]
+    fn test_encode_te() {
+        let response = headers(&[("transfer-encoding", "gzip, chunked")]);
+        let request = headers(&[("te", "trailers, gzip;q=0.5")]);
+        let plan = CodingPlan::encode(&response, &request).unwrap();
+        assert_eq!(plan.direction, Direction::Encode);
+        assert_eq!(
+            plan.steps,
+            vec![transfer(Encoding::Gzip), transfer(Encoding::Chunked)]
+        );
+
+        for te in &["trailers", "gzip;q=0"] {
+            let request = headers(&[("te", te)]);
+            assert_eq!(
+                CodingPlan::encode(&response, &request),
+                Err(CodingError::NotAccepted(Layer::Transfer, Encoding::Gzip)),
+                "{}",
+                te
+            );
+        }
+
+        let response = headers(&[("transfer-encoding", "chunked")]);
+        assert!(CodingPlan::encode(&response, &HeaderMap::new()).is_ok());
+    }
+}
@@ -0,0 +1,301 @@
+//! Evaluation of conditional requests, per [RFC 9110 §13.2].
+//!
+//! [`Conditions`](struct.Conditions.html) holds the precondition headers of
+//! a request, which are evaluated against the current
+//! [`Validators`](struct.Validators.html) of the selected representation,
+//! in the order mandated by the specification, to determine the
+//! [`Outcome`](enum.Outcome.html) of the request.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use http::{HeaderMap, Method, StatusCode};
+//! use hyperx::conditional::{Conditions, Outcome, Validators};
+//! use hyperx::header::EntityTag;
+//!
+//! let validators = Validators {
+//!     etag: Some(EntityTag::strong("xyzzy".to_owned())),
+//!     last_modified: None,
+//! };
+//!
+//! let mut headers = HeaderMap::new();
+//! headers.insert("if-none-match", "\"xyzzy\"".parse().unwrap());
+//! let conditions = Conditions::from_headers(&headers);
+//!
+//! let outcome = conditions.evaluate(&Method::GET, &validators);
+//! assert_eq!(outcome, Outcome::NotModified);
+//! assert_eq!(outcome.status(), Some(StatusCode::NOT_MODIFIED));
+//!
+//! let outcome = conditions.evaluate(&Method::PUT, &validators);
+//! assert_eq!(outcome.status(), Some(StatusCode::PRECONDITION_FAILED));
+//! ```
+//!
+//! [RFC 9110 §13.2]: https://www.rfc-editor.org/rfc/rfc9110#section-13.2
+
+use http::header::RANGE;
+use http::{HeaderMap, Method, StatusCode};
+
+use header::{
+    EntityTag, HttpDate, IfMatch, IfModifiedSince, IfNoneMatch, IfRange, IfUnmodifiedSince,
+    TypedHeaders,
+};
+
+/// The current validators of the selected representation of a resource.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Validators {
+    /// The entity-tag, as sent in `ETag`.
+    pub etag: Option<EntityTag>,
+    /// The last modification date, as sent in `Last-Modified`.
+    pub last_modified: Option<HttpDate>,
+}
+
+/// The outcome of evaluating the preconditions of a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Outcome {
+    /// All preconditions passed: perform the request, honouring any `Range`.
+    Proceed,
+    /// The `If-Range` condition failed: perform the request, ignoring
+    /// `Range` and responding with the full representation.
+    IgnoreRange,
+    /// Respond with `304 Not Modified`.
+    NotModified,
+    /// Respond with `412 Precondition Failed`.
+    PreconditionFailed,
+}
+
+impl Outcome {
+    /// The status code to respond with, if the request is not to be
+    /// performed.
+    pub fn status(&self) -> Option<StatusCode> {
+        match *self {
+            Outcome::Proceed | Outcome::IgnoreRange => None,
+            Outcome::NotModified => Some(StatusCode::NOT_MODIFIED),
+            Outcome::PreconditionFailed => Some(StatusCode::PRECONDITION_FAILED),
+        }
+    }
+}
+
+/// The precondition headers of a request.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Conditions {
+    /// The `If-Match` header.
+    pub if_match: Option<IfMatch>,
+    /// The `If-None-Match` header.
+    pub if_none_match: Option<IfNoneMatch>,
+    /// The `If-Modified-Since` header.
+    pub if_modified_since: Option<IfModifiedSince>,
+    /// The `If-Unmodified-Since` header.
+    pub if_unmodified_since: Option<IfUnmodifiedSince>,
+    /// The `If-Range` header.
+    pub if_range: Option<IfRange>,
+    /// Whether the request has a `Range` header, to which `If-Range`
+    /// applies.
+    pub has_range: bool,
+}
+
+impl Conditions {
+    /// Decode the precondition headers of a request.
+    ///
+    /// Headers which fail to parse are ignored, as the specification
+    /// requires for invalid dates. An invalid `If-Range` is treated as
+    /// failing, so that any `Range` is ignored.
+    pub fn from_headers(headers: &HeaderMap) -> Conditions {
+        let if_range = headers
+            .try_decode::<IfRange>()
+            .map(|r| r.unwrap_or_else(|_| IfRange::EntityTag(EntityTag::weak(String::new()))));
+        Conditions {
+            if_match: headers.try_decode().and_then(|r| r.ok()),
+            if_none_match: headers.try_decode().and_then(|r| r.ok()),
+            if_modified_since: headers.try_decode().and_then(|r| r.ok()),
+            if_unmodified_since: headers.try_decode().and_then(|r| r.ok()),
+            if_range,
+            has_range: headers.contains_key(RANGE),
+        }
+    }
+
+    /// Evaluate the preconditions for a request `method` against the
+    /// current `validators` of the selected representation, which is
+    /// assumed to exist.
+    ///
+    /// The steps of RFC 9110 §13.2.2 are applied in order: `If-Match`, else
+    /// `If-Unmodified-Since`; then `If-None-Match`, else `If-Modified-Since`
+    /// for `GET` and `HEAD`; and finally `If-Range` for a `GET` with
+    /// `Range`.
+    pub fn evaluate(&self, method: &Method, validators: &Validators) -> Outcome {
+        let get_or_head = *method == Method::GET || *method == Method::HEAD;
+
+        if let Some(ref if_match) = self.if_match {
+            let pass = match *if_match {
+                IfMatch::Any => true,
+                IfMatch::Items(ref tags) => validators
+                    .etag
+                    .as_ref()
+                    .is_some_and(|etag| tags.iter().any(|t| t.strong_eq(etag))),
+            };
+            if !pass {
+                return Outcome::PreconditionFailed;
+            }
+        } else if let Some(IfUnmodifiedSince(since)) = self.if_unmodified_since {
+            if validators.last_modified.is_some_and(|lm| lm > since) {
+                return Outcome::PreconditionFailed;
+            }
+        }
+
+        if let Some(ref if_none_match) = self.if_none_match {
+            let matched = match *if_none_match {
+                IfNoneMatch::Any => true,
+                IfNoneMatch::Items(ref tags) => validators
+                    .etag
+                    .as_ref()
+                    .is_some_and(|etag| tags.iter().any(|t| t.weak_eq(etag))),
+            };
+            if matched {
+                return if get_or_head {
+                    Outcome::NotModified
+                } else {
+                    Outcome::PreconditionFailed
+                };
+            }
+        } else if let Some(IfModifiedSince(since)) = self.if_modified_since {
+            if get_or_head && validators.last_modified.is_some_and(|lm| lm <= since) {
+                return Outcome::NotModified;
+            }
+        }
+
+        if *method == Method::GET && self.has_range {
+            if let Some(ref if_range) = self.if_range {
+                let pass = match *if_range {
+                    IfRange::EntityTag(ref tag) => validators
+                        .etag
+                        .as_ref()
+                        .is_some_and(|etag| tag.strong_eq(etag)),
+                    IfRange::Date(date) => validators.last_modified == Some(date),
+                };
+                if !pass {
+                    return Outcome::IgnoreRange;
+                }
+            }
+        }
+
+        Outcome::Proceed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::{Duration, UNIX_EPOCH};
+
+    fn date(secs: u64) -> HttpDate {
+        (UNIX_EPOCH + Duration::from_secs(secs)).into()
+    }
+
+    fn conditions(headers: &[(&'static str, &str)]) -> Conditions {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        Conditions::from_headers(&map)
+    }
+
+    fn validators() -> Validators {
+        Validators {
+            etag: Some(EntityTag::strong("v2".to_owned())),
+            last_modified: Some(date(1_000_000)),
+        }
+    }
+
+    #[test]
+    fn test_none() {
+        let c = conditions(&[]);
+        assert_eq!(c.evaluate(&Method::GET, &validators()), Outcome::Proceed);
+        assert_eq!(
+            c.evaluate(&Method::GET, &Validators::default()),
+            Outcome::Proceed
+        );
+    }
+
+    #[test]
+    fn test_if_match() {
+        let v = validators();
+        let c = conditions(&[("if-match", "\"v1\", \"v2\"")]);
+        assert_eq!(c.evaluate(&Method::PUT, &v), Outcome::Proceed);
+        let c = conditions(&[("if-match", "W/\"v2\"")]);
+        assert_eq!(c.evaluate(&Method::PUT, &v), Outcome::PreconditionFailed);
+        let c = conditions(&[("if-match", "*")]);
+        assert_eq!(c.evaluate(&Method::PUT, &v), Outcome::Proceed);
+        let c = conditions(&[("if-match", "\"v2\"")]);
+        assert_eq!(
+            c.evaluate(&Method::PUT, &Validators::default()),
+            Outcome::PreconditionFailed
+        );
+
+        // If-Unmodified-Since is ignored when If-Match is present
+        let ius = date(999_000).to_string();
+        let c = conditions(&[("if-match", "\"v2\""), ("if-unmodified-since", &ius)]);
+        assert_eq!(c.evaluate(&Method::PUT, &v), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_unmodified_since() {
+        let v = validators();
+        let c = conditions(&[("if-unmodified-since", &date(1_000_000).to_string())]);
+        assert_eq!(c.evaluate(&Method::DELETE, &v), Outcome::Proceed);
+        let c = conditions(&[("if-unmodified-since", &date(999_999).to_string())]);
+        assert_eq!(c.evaluate(&Method::DELETE, &v), Outcome::PreconditionFailed);
+        let c = conditions(&[("if-unmodified-since", "not a date")]);
+        assert_eq!(c.evaluate(&Method::DELETE, &v), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_none_match() {
+        let v = validators();
+        let c = conditions(&[("if-none-match", "W/\"v2\"")]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::NotModified);
+        assert_eq!(c.evaluate(&Method::HEAD, &v), Outcome::NotModified);
+        assert_eq!(c.evaluate(&Method::POST, &v), Outcome::PreconditionFailed);
+        let c = conditions(&[("if-none-match", "*")]);
+        assert_eq!(c.evaluate(&Method::PUT, &v), Outcome::PreconditionFailed);
+
+        // If-Modified-Since is ignored when If-None-Match is present
+        let ims = date(2_000_000).to_string();
+        let c = conditions(&[("if-none-match", "\"v1\""), ("if-modified-since", &ims)]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_modified_since() {
+        let v = validators();
+        let c = conditions(&[("if-modified-since", &date(1_000_000).to_string())]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::NotModified);
+        assert_eq!(c.evaluate(&Method::POST, &v), Outcome::Proceed);
+        let c = conditions(&[("if-modified-since", &date(999_999).to_string())]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::Proceed);
+    }
+
+    #[test]
+    fn test_if_range() {
+        let v = validators();
+        let c = conditions(&[("range", "bytes=0-10"), ("if-range", "\"v2\"")]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::Proceed);
+        let c = conditions(&[("range", "bytes=0-10"), ("if-range", "\"v1\"")]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::IgnoreRange);
+        assert_eq!(c.evaluate(&Method::HEAD, &v), Outcome::Proceed);
+        let c = conditions(&[("if-range", "\"v1\"")]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::Proceed);
+
+        let lm = date(1_000_000).to_string();
+        let c = conditions(&[("range", "bytes=0-10"), ("if-range", &lm)]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::Proceed);
+        let c = conditions(&[("range", "bytes=0-10"), ("if-range", "garbage")]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::IgnoreRange);
+
+        // A 304 takes precedence over If-Range
+        let c = conditions(&[
+            ("range", "bytes=0-10"),
+            ("if-range", "\"v1\""),
+            ("if-none-match", "\"v2\""),
+        ]);
+        assert_eq!(c.evaluate(&Method::GET, &v), Outcome::NotModified);
+    }
+}
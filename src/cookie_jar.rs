@@ -0,0 +1,468 @@
+//! A client cookie store, per [RFC 6265].
+//!
+//! A [`CookieJar`](struct.CookieJar.html) stores the cookies of typed
+//! `SetCookie` response headers, applying the domain, path, secure and
+//! expiry rules of the specification, and produces the `Cookie` request
+//! header for a given URL.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::SystemTime;
+//! use http::Uri;
+//! use hyperx::cookie_jar::CookieJar;
+//! use hyperx::header::{ResponseCookie, SetCookie};
+//!
+//! let now = SystemTime::now();
+//! let mut jar = CookieJar::new();
+//! let login: Uri = "https://www.example.com/account/login".parse().unwrap();
+//! jar.store(
+//!     &SetCookie(vec![
+//!         ResponseCookie::new("session", "abc").path("/").secure(true).http_only(true),
+//!         ResponseCookie::new("lang", "en").domain("example.com").path("/"),
+//!         ResponseCookie::new("step", "2"),
+//!     ]),
+//!     &login,
+//!     now,
+//! );
+//!
+//! let home: Uri = "https://example.com/".parse().unwrap();
+//! let cookie = jar.cookie_header(&home, now).unwrap();
+//! assert_eq!(cookie.to_string(), "lang=en");
+//!
+//! let settings: Uri = "https://www.example.com/account/settings".parse().unwrap();
+//! let cookie = jar.cookie_header(&settings, now).unwrap();
+//! assert_eq!(cookie.to_string(), "step=2; session=abc; lang=en");
+//! ```
+//!
+//! The public suffix list is not consulted, so a `Domain` attribute naming
+//! a public suffix (e.g. `co.uk`) is accepted if the request host
+//! domain-matches it.
+//!
+//! [RFC 6265]: https://www.rfc-editor.org/rfc/rfc6265
+
+use std::net::IpAddr;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use http::{HeaderMap, Uri};
+
+use header::{Cookie, ResponseCookie, SetCookie, TypedHeaders};
+
+/// A cookie stored in a `CookieJar`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StoredCookie {
+    name: String,
+    value: String,
+    domain: String,
+    host_only: bool,
+    path: String,
+    secure: bool,
+    http_only: bool,
+    expires: Option<SystemTime>,
+    created: SystemTime,
+}
+
+impl StoredCookie {
+    /// The cookie name.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The cookie value.
+    pub fn value(&self) -> &str {
+        &self.value
+    }
+
+    /// The (lowercase) domain of the cookie.
+    pub fn domain(&self) -> &str {
+        &self.domain
+    }
+
+    /// True if the cookie is only sent to exactly its domain, rather than
+    /// also its subdomains, as when set without a `Domain` attribute.
+    pub fn host_only(&self) -> bool {
+        self.host_only
+    }
+
+    /// The path of the cookie.
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    /// True if the cookie is only sent over secure (`https`) connections.
+    pub fn secure(&self) -> bool {
+        self.secure
+    }
+
+    /// True if the cookie is not exposed to non-HTTP APIs.
+    pub fn http_only(&self) -> bool {
+        self.http_only
+    }
+
+    /// The expiry time of the cookie, or `None` for a session cookie.
+    pub fn expires(&self) -> Option<SystemTime> {
+        self.expires
+    }
+
+    /// The time at which the cookie was first created.
+    pub fn created(&self) -> SystemTime {
+        self.created
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires.is_some_and(|e| e <= now)
+    }
+
+    fn matches(&self, host: &str, path: &str, secure: bool) -> bool {
+        let domain = if self.host_only {
+            host == self.domain
+        } else {
+            domain_match(host, &self.domain)
+        };
+        domain && path_match(path, &self.path) && (secure || !self.secure)
+    }
+}
+
+/// A store of cookies received from servers.
+#[derive(Clone, Debug, Default)]
+pub struct CookieJar {
+    cookies: Vec<StoredCookie>,
+}
+
+impl CookieJar {
+    /// Create a new, empty jar.
+    pub fn new() -> CookieJar {
+        CookieJar::default()
+    }
+
+    /// Store the cookies of a `SetCookie` header, received at `now` in
+    /// response to a request for `url`.
+    ///
+    /// Invalid cookies, and cookies whose `Domain` doesn't match the host of
+    /// `url`, are ignored. A cookie with the same name, domain and path as
+    /// a stored cookie replaces it, keeping its creation time. A cookie
+    /// which has already expired removes any such stored cookie.
+    pub fn store(&mut self, set_cookie: &SetCookie, url: &Uri, now: SystemTime) {
+        for cookie in set_cookie.iter() {
+            if let Some(cookie) = to_stored(cookie, url, now) {
+                self.insert(cookie, now);
+            }
+        }
+    }
+
+    /// Store the cookies of any `Set-Cookie` headers of a response.
+    pub fn store_response(&mut self, headers: &HeaderMap, url: &Uri, now: SystemTime) {
+        if let Ok(set_cookie) = headers.decode::<SetCookie>() {
+            self.store(&set_cookie, url, now);
+        }
+    }
+
+    /// Returns the `Cookie` header to send at `now` with a request for
+    /// `url`, or `None` if no stored cookies apply.
+    ///
+    /// Cookies with longer paths are listed first, then those created
+    /// earlier.
+    pub fn cookie_header(&self, url: &Uri, now: SystemTime) -> Option<Cookie> {
+        let host = url.host()?.to_ascii_lowercase();
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let secure = url.scheme_str() == Some("https");
+        let path = url.path();
+        let mut matching: Vec<&StoredCookie> = self
+            .cookies
+            .iter()
+            .filter(|c| !c.is_expired(now) && c.matches(host, path, secure))
+            .collect();
+        if matching.is_empty() {
+            return None;
+        }
+        matching.sort_by(|a, b| {
+            b.path
+                .len()
+                .cmp(&a.path.len())
+                .then(a.created.cmp(&b.created))
+        });
+        let mut cookie = Cookie::new();
+        for c in matching {
+            cookie.append(c.name.clone(), c.value.clone());
+        }
+        Some(cookie)
+    }
+
+    /// Remove all cookies which have expired at `now`.
+    pub fn remove_expired(&mut self, now: SystemTime) {
+        self.cookies.retain(|c| !c.is_expired(now));
+    }
+
+    /// Remove all session cookies, those without an expiry time.
+    pub fn remove_session(&mut self) {
+        self.cookies.retain(|c| c.expires.is_some());
+    }
+
+    /// Remove all cookies.
+    pub fn clear(&mut self) {
+        self.cookies.clear();
+    }
+
+    /// Returns an iterator over the stored cookies, including any which
+    /// have expired but not yet been removed.
+    pub fn iter(&self) -> ::std::slice::Iter<'_, StoredCookie> {
+        self.cookies.iter()
+    }
+
+    /// The number of stored cookies.
+    pub fn len(&self) -> usize {
+        self.cookies.len()
+    }
+
+    /// Returns true if no cookies are stored.
+    pub fn is_empty(&self) -> bool {
+        self.cookies.is_empty()
+    }
+
+    fn insert(&mut self, mut cookie: StoredCookie, now: SystemTime) {
+        let existing = self.cookies.iter().position(|c| {
+            c.name == cookie.name && c.domain == cookie.domain && c.path == cookie.path
+        });
+        match existing {
+            Some(i) if cookie.is_expired(now) => {
+                self.cookies.remove(i);
+            }
+            Some(i) => {
+                cookie.created = self.cookies[i].created;
+                self.cookies[i] = cookie;
+            }
+            None if cookie.is_expired(now) => {}
+            None => self.cookies.push(cookie),
+        }
+    }
+}
+
+fn to_stored(cookie: &ResponseCookie, url: &Uri, now: SystemTime) -> Option<StoredCookie> {
+    let host = url.host()?.to_ascii_lowercase();
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_owned();
+
+    // A secure cookie may only be set over a secure connection
+    if cookie.secure && url.scheme_str() != Some("https") {
+        return None;
+    }
+    let (domain, host_only) = match cookie.domain {
+        Some(ref d) => {
+            let d = d.to_ascii_lowercase();
+            if !domain_match(&host, &d) {
+                return None;
+            }
+            (d, false)
+        }
+        None => (host, true),
+    };
+    // Max-Age takes precedence over Expires
+    let expires = match cookie.max_age {
+        Some(max_age) if max_age.as_secs() == 0 => Some(UNIX_EPOCH),
+        Some(max_age) => now.checked_add(max_age),
+        None => cookie.expires.map(SystemTime::from),
+    };
+    Some(StoredCookie {
+        name: cookie.name.clone(),
+        value: cookie.value.clone(),
+        domain,
+        host_only,
+        path: cookie
+            .path
+            .clone()
+            .unwrap_or_else(|| default_path(url.path())),
+        secure: cookie.secure,
+        http_only: cookie.http_only,
+        expires,
+        created: now,
+    })
+}
+
+fn domain_match(host: &str, domain: &str) -> bool {
+    if host == domain {
+        return true;
+    }
+    host.len() > domain.len()
+        && host.ends_with(domain)
+        && host.as_bytes()[host.len() - domain.len() - 1] == b'.'
+        && host.parse::<IpAddr>().is_err()
+}
+
+fn path_match(request: &str, cookie: &str) -> bool {
+    request == cookie
+        || (request.starts_with(cookie)
+            && (cookie.ends_with('/') || request.as_bytes()[cookie.len()] == b'/'))
+}
+
+fn default_path(request: &str) -> String {
+    match request.rfind('/') {
+        Some(i) if i > 0 && request.starts_with('/') => request[..i].to_owned(),
+        _ => "/".to_owned(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn store(jar: &mut CookieJar, url: &str, lines: &[&str], now: SystemTime) {
+        let sc = SetCookie(lines.iter().map(|l| l.parse().unwrap()).collect());
+        jar.store(&sc, &uri(url), now);
+    }
+
+    fn header(jar: &CookieJar, url: &str, now: SystemTime) -> Option<String> {
+        jar.cookie_header(&uri(url), now).map(|c| c.to_string())
+    }
+
+    #[test]
+    fn test_domain() {
+        let now = SystemTime::now();
+        let mut jar = CookieJar::new();
+        store(
+            &mut jar,
+            "http://a.example.com/",
+            &[
+                "host=1",
+                "dom=2; Domain=.Example.COM",
+                "other=3; Domain=other.com",
+                "sub=4; Domain=b.a.example.com",
+            ],
+            now,
+        );
+        assert_eq!(jar.len(), 2);
+        assert_eq!(
+            header(&jar, "http://a.example.com/", now).unwrap(),
+            "host=1; dom=2"
+        );
+        assert_eq!(
+            header(&jar, "http://b.a.example.com/", now).unwrap(),
+            "dom=2"
+        );
+        assert_eq!(header(&jar, "http://example.com/", now).unwrap(), "dom=2");
+        assert_eq!(header(&jar, "http://notexample.com/", now), None);
+
+        let mut jar = CookieJar::new();
+        store(&mut jar, "http://127.0.0.1/", &["ip=1; Domain=0.0.1"], now);
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn test_path() {
+        let now = SystemTime::now();
+        let mut jar = CookieJar::new();
+        store(
+            &mut jar,
+            "http://example.com/docs/guide/intro",
+            &["default=1", "root=2; Path=/", "bad=3; Path=relative"],
+            now,
+        );
+        let paths: Vec<_> = jar.iter().map(|c| (c.name(), c.path())).collect();
+        assert_eq!(
+            paths,
+            vec![
+                ("default", "/docs/guide"),
+                ("root", "/"),
+                ("bad", "/docs/guide")
+            ]
+        );
+        assert_eq!(
+            header(&jar, "http://example.com/docs/guide/", now).unwrap(),
+            "default=1; bad=3; root=2"
+        );
+        assert_eq!(
+            header(&jar, "http://example.com/docs/guidebook", now).unwrap(),
+            "root=2"
+        );
+        assert_eq!(default_path("/"), "/");
+        assert_eq!(default_path("/file"), "/");
+        assert_eq!(default_path(""), "/");
+    }
+
+    #[test]
+    fn test_secure() {
+        let now = SystemTime::now();
+        let mut jar = CookieJar::new();
+        store(&mut jar, "http://example.com/", &["a=1; Secure"], now);
+        assert!(jar.is_empty());
+        store(
+            &mut jar,
+            "https://example.com/",
+            &["a=1; Secure; HttpOnly"],
+            now,
+        );
+        assert!(jar.iter().next().unwrap().http_only());
+        assert_eq!(header(&jar, "http://example.com/", now), None);
+        assert_eq!(header(&jar, "https://example.com/", now).unwrap(), "a=1");
+    }
+
+    #[test]
+    fn test_expiry() {
+        let now = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let later = now + Duration::from_secs(120);
+        let mut jar = CookieJar::new();
+        store(
+            &mut jar,
+            "http://example.com/",
+            &[
+                "session=1",
+                "short=2; Max-Age=60; Expires=Wed, 21 Oct 2099 07:28:00 GMT",
+                "long=3; Expires=Wed, 21-Oct-2099 07:28:00 GMT",
+                "gone=4; Expires=Thu, 01 Jan 1970 00:00:00 GMT",
+            ],
+            now,
+        );
+        assert_eq!(jar.len(), 3);
+        assert_eq!(
+            header(&jar, "http://example.com/", now).unwrap(),
+            "session=1; short=2; long=3"
+        );
+        assert_eq!(
+            header(&jar, "http://example.com/", later).unwrap(),
+            "session=1; long=3"
+        );
+        jar.remove_expired(later);
+        assert_eq!(jar.len(), 2);
+        jar.remove_session();
+        assert_eq!(jar.len(), 1);
+
+        // Expired cookie deletes the stored one
+        store(
+            &mut jar,
+            "http://example.com/",
+            &["long=; Max-Age=0"],
+            later,
+        );
+        assert!(jar.is_empty());
+    }
+
+    #[test]
+    fn test_replace_keeps_creation() {
+        let t0 = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+        let t1 = t0 + Duration::from_secs(1);
+        let mut jar = CookieJar::new();
+        store(&mut jar, "http://example.com/", &["a=1", "b=2"], t0);
+        store(&mut jar, "http://example.com/", &["a=3"], t1);
+        assert_eq!(jar.len(), 2);
+        assert_eq!(header(&jar, "http://example.com/", t1).unwrap(), "a=3; b=2");
+        assert_eq!(jar.iter().find(|c| c.name() == "a").unwrap().created(), t0);
+    }
+
+    #[test]
+    fn test_store_response() {
+        let now = SystemTime::now();
+        let mut headers = HeaderMap::new();
+        headers.append("set-cookie", "a=1".parse().unwrap());
+        headers.append("set-cookie", "=nameless".parse().unwrap());
+        headers.append("set-cookie", "novalue".parse().unwrap());
+        let mut jar = CookieJar::new();
+        jar.store_response(&headers, &uri("http://example.com/"), now);
+        assert_eq!(jar.len(), 1);
+    }
+}
@@ -0,0 +1,510 @@
+//! Cross-Origin Resource Sharing (CORS) policy evaluation, per the
+//! [Fetch standard].
+//!
+//! A [`CorsPolicy`](struct.CorsPolicy.html) describes the cross-origin
+//! requests a server allows. Evaluating it against the `Origin`,
+//! `Access-Control-Request-Method` and `Access-Control-Request-Headers` of a
+//! request produces either the typed [`CorsHeaders`](struct.CorsHeaders.html)
+//! to include in the response, or a [`CorsRejection`](enum.CorsRejection.html).
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use http::{HeaderMap, Method};
+//! use hyperx::cors::CorsPolicy;
+//! use hyperx::header::Origin;
+//!
+//! let policy = CorsPolicy::new()
+//!     .allow_origin(Origin::new("https", "app.example", None))
+//!     .allow_methods(vec![hyperx::Method::Put, hyperx::Method::Delete])
+//!     .allow_headers(vec!["content-type", "x-request-id"])
+//!     .allow_credentials(true)
+//!     .max_age(600);
+//!
+//! let mut request = HeaderMap::new();
+//! request.insert("origin", "https://app.example".parse().unwrap());
+//! request.insert("access-control-request-method", "PUT".parse().unwrap());
+//! request.insert("access-control-request-headers", "X-Request-Id".parse().unwrap());
+//!
+//! let cors = policy.evaluate(&Method::OPTIONS, &request).unwrap().unwrap();
+//! let mut response = HeaderMap::new();
+//! cors.encode(&mut response);
+//! assert_eq!(response["access-control-allow-origin"], "https://app.example");
+//! assert_eq!(response["access-control-allow-credentials"], "true");
+//! assert_eq!(response["access-control-allow-methods"], "PUT, DELETE");
+//! assert_eq!(response["access-control-allow-headers"], "content-type, x-request-id");
+//! assert_eq!(response["access-control-max-age"], "600");
+//!
+//! request.insert("origin", "https://evil.example".parse().unwrap());
+//! assert!(policy.evaluate(&Method::OPTIONS, &request).unwrap().is_err());
+//! ```
+//!
+//! [Fetch standard]: https://fetch.spec.whatwg.org/#http-cors-protocol
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use http::HeaderMap;
+use unicase::Ascii;
+
+use header::{
+    AccessControlAllowCredentials, AccessControlAllowHeaders, AccessControlAllowMethods,
+    AccessControlAllowOrigin, AccessControlAllowPrivateNetwork, AccessControlExposeHeaders,
+    AccessControlMaxAge, AccessControlRequestHeaders, AccessControlRequestMethod,
+    AccessControlRequestPrivateNetwork, HeaderVec, Origin, TypedHeaders, Vary,
+};
+use method::Method;
+
+/// A CORS policy, constructed via builder methods.
+///
+/// By default no origin is allowed; the CORS-safelisted methods `GET`,
+/// `HEAD` and `POST` are always allowed.
+#[derive(Clone, Debug, Default)]
+pub struct CorsPolicy {
+    any_origin: bool,
+    origins: Vec<Origin>,
+    methods: Vec<Method>,
+    any_header: bool,
+    headers: Vec<Ascii<String>>,
+    expose_headers: Vec<Ascii<String>>,
+    credentials: bool,
+    max_age: Option<u32>,
+    private_network: bool,
+}
+
+impl CorsPolicy {
+    /// Create a new policy, allowing no origins.
+    pub fn new() -> CorsPolicy {
+        CorsPolicy::default()
+    }
+
+    /// Allow requests from the given origin.
+    pub fn allow_origin(mut self, origin: Origin) -> CorsPolicy {
+        self.origins.push(origin);
+        self
+    }
+
+    /// Allow requests from any (non-`null`) origin.
+    ///
+    /// If credentials are also allowed, the request origin is reflected in
+    /// `Access-Control-Allow-Origin`, since `*` is not permitted with
+    /// credentials.
+    pub fn allow_any_origin(mut self) -> CorsPolicy {
+        self.any_origin = true;
+        self
+    }
+
+    /// Allow the given methods, in addition to the CORS-safelisted methods.
+    pub fn allow_methods<I>(mut self, methods: I) -> CorsPolicy
+    where
+        I: IntoIterator<Item = Method>,
+    {
+        self.methods.extend(methods);
+        self
+    }
+
+    /// Allow the given request header names.
+    pub fn allow_headers<I, S>(mut self, headers: I) -> CorsPolicy
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.headers
+            .extend(headers.into_iter().map(|h| Ascii::new(h.into())));
+        self
+    }
+
+    /// Allow any request header names, which are then reflected in
+    /// `Access-Control-Allow-Headers`.
+    pub fn allow_any_header(mut self) -> CorsPolicy {
+        self.any_header = true;
+        self
+    }
+
+    /// Expose the given response header names to the client.
+    pub fn expose_headers<I, S>(mut self, headers: I) -> CorsPolicy
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.expose_headers
+            .extend(headers.into_iter().map(|h| Ascii::new(h.into())));
+        self
+    }
+
+    /// Set whether requests with credentials are allowed.
+    pub fn allow_credentials(mut self, allow: bool) -> CorsPolicy {
+        self.credentials = allow;
+        self
+    }
+
+    /// Set the number of seconds a preflight response may be cached.
+    pub fn max_age(mut self, seconds: u32) -> CorsPolicy {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Set whether preflight requests for private network access are
+    /// allowed.
+    pub fn allow_private_network(mut self, allow: bool) -> CorsPolicy {
+        self.private_network = allow;
+        self
+    }
+
+    /// Evaluate a request given its method and headers.
+    ///
+    /// Returns `None` if the request has no `Origin` header, so is not a
+    /// CORS request. An `OPTIONS` request with an
+    /// `Access-Control-Request-Method` header is evaluated as a preflight
+    /// request, otherwise as an actual request. Invalid CORS request headers
+    /// result in a rejection.
+    pub fn evaluate(
+        &self,
+        method: &::http::Method,
+        headers: &HeaderMap,
+    ) -> Option<Result<CorsHeaders, CorsRejection>> {
+        let origin = match headers.try_decode::<Origin>() {
+            None => return None,
+            Some(Ok(origin)) => origin,
+            Some(Err(_)) => return Some(Err(CorsRejection::OriginNotAllowed)),
+        };
+        let request_method = match headers.try_decode::<AccessControlRequestMethod>() {
+            Some(Ok(m)) => Some(m),
+            Some(Err(_)) => return Some(Err(CorsRejection::InvalidRequest)),
+            None => None,
+        };
+        match request_method {
+            Some(request_method) if *method == ::http::Method::OPTIONS => {
+                let request_headers = match headers.try_decode::<AccessControlRequestHeaders>() {
+                    Some(Ok(h)) => Some(h),
+                    Some(Err(_)) => return Some(Err(CorsRejection::InvalidRequest)),
+                    None => None,
+                };
+                let private_network = headers
+                    .decode::<AccessControlRequestPrivateNetwork>()
+                    .is_ok();
+                Some(self.preflight(
+                    &origin,
+                    &request_method,
+                    request_headers.as_ref(),
+                    private_network,
+                ))
+            }
+            _ => Some(self.actual(&origin)),
+        }
+    }
+
+    /// Evaluate a preflight request.
+    pub fn preflight(
+        &self,
+        origin: &Origin,
+        method: &AccessControlRequestMethod,
+        headers: Option<&AccessControlRequestHeaders>,
+        private_network: bool,
+    ) -> Result<CorsHeaders, CorsRejection> {
+        let mut cors = self.actual(origin)?;
+        cors.expose_headers = None;
+
+        let method = &method.0;
+        if !is_safelisted(method) && !self.methods.contains(method) {
+            return Err(CorsRejection::MethodNotAllowed(method.clone()));
+        }
+        let requested = headers.map_or(&[][..], |h| &h.0[..]);
+        if !self.any_header {
+            if let Some(h) = requested.iter().find(|h| !self.headers.contains(h)) {
+                return Err(CorsRejection::HeaderNotAllowed(h.to_string()));
+            }
+        }
+        if private_network && !self.private_network {
+            return Err(CorsRejection::PrivateNetworkNotAllowed);
+        }
+
+        if !self.methods.is_empty() {
+            cors.allow_methods = Some(AccessControlAllowMethods(
+                self.methods.iter().cloned().collect(),
+            ));
+        }
+        let allow_headers: HeaderVec<_> = if self.any_header {
+            requested.iter().cloned().collect()
+        } else {
+            self.headers.iter().cloned().collect()
+        };
+        if !allow_headers.is_empty() {
+            cors.allow_headers = Some(AccessControlAllowHeaders(allow_headers));
+        }
+        cors.max_age = self.max_age.map(AccessControlMaxAge);
+        if private_network {
+            cors.allow_private_network = Some(AccessControlAllowPrivateNetwork);
+        }
+        if let Some(Vary::Items(ref mut items)) = cors.vary {
+            items.push(Ascii::new("Access-Control-Request-Method".to_owned()));
+            items.push(Ascii::new("Access-Control-Request-Headers".to_owned()));
+        }
+        Ok(cors)
+    }
+
+    /// Evaluate an actual (non-preflight) request.
+    pub fn actual(&self, origin: &Origin) -> Result<CorsHeaders, CorsRejection> {
+        let allowed = self.origins.contains(origin) || (self.any_origin && !origin.is_null());
+        if !allowed {
+            return Err(CorsRejection::OriginNotAllowed);
+        }
+        let reflect = !self.any_origin || self.credentials;
+        let allow_origin = if reflect {
+            if origin.is_null() {
+                AccessControlAllowOrigin::Null
+            } else {
+                AccessControlAllowOrigin::Value(origin.to_string().into())
+            }
+        } else {
+            AccessControlAllowOrigin::Any
+        };
+        Ok(CorsHeaders {
+            allow_origin,
+            allow_credentials: if self.credentials {
+                Some(AccessControlAllowCredentials)
+            } else {
+                None
+            },
+            allow_methods: None,
+            allow_headers: None,
+            max_age: None,
+            expose_headers: if self.expose_headers.is_empty() {
+                None
+            } else {
+                Some(AccessControlExposeHeaders(
+                    self.expose_headers.iter().cloned().collect(),
+                ))
+            },
+            allow_private_network: None,
+            vary: if reflect {
+                Some(Vary::Items(smallvec![Ascii::new("Origin".to_owned())]))
+            } else {
+                None
+            },
+        })
+    }
+}
+
+fn is_safelisted(method: &Method) -> bool {
+    matches!(*method, Method::Get | Method::Head | Method::Post)
+}
+
+/// The CORS response headers for an allowed request.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CorsHeaders {
+    /// The `Access-Control-Allow-Origin` header.
+    pub allow_origin: AccessControlAllowOrigin,
+    /// The `Access-Control-Allow-Credentials` header.
+    pub allow_credentials: Option<AccessControlAllowCredentials>,
+    /// The `Access-Control-Allow-Methods` header, for preflight requests.
+    pub allow_methods: Option<AccessControlAllowMethods>,
+    /// The `Access-Control-Allow-Headers` header, for preflight requests.
+    pub allow_headers: Option<AccessControlAllowHeaders>,
+    /// The `Access-Control-Max-Age` header, for preflight requests.
+    pub max_age: Option<AccessControlMaxAge>,
+    /// The `Access-Control-Expose-Headers` header, for actual requests.
+    pub expose_headers: Option<AccessControlExposeHeaders>,
+    /// The `Access-Control-Allow-Private-Network` header, for preflight
+    /// requests.
+    pub allow_private_network: Option<AccessControlAllowPrivateNetwork>,
+    /// The `Vary` header, listing the request headers the response depends
+    /// on.
+    pub vary: Option<Vary>,
+}
+
+impl CorsHeaders {
+    /// Encode the headers into a response `http::HeaderMap`, replacing any
+    /// existing CORS headers. `Vary` is appended to any existing value.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        headers.encode(&self.allow_origin);
+        if let Some(ref h) = self.allow_credentials {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.allow_methods {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.allow_headers {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.max_age {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.expose_headers {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.allow_private_network {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.vary {
+            headers.encode_append(h);
+        }
+    }
+}
+
+/// The reason a CORS request was rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CorsRejection {
+    /// The `Origin` is not allowed, or invalid.
+    OriginNotAllowed,
+    /// The requested method is not allowed.
+    MethodNotAllowed(Method),
+    /// A requested header is not allowed.
+    HeaderNotAllowed(String),
+    /// Private network access is requested, but not allowed.
+    PrivateNetworkNotAllowed,
+    /// A CORS request header is invalid.
+    InvalidRequest,
+}
+
+impl fmt::Display for CorsRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            CorsRejection::OriginNotAllowed => f.write_str("origin not allowed"),
+            CorsRejection::MethodNotAllowed(ref m) => write!(f, "method {} not allowed", m),
+            CorsRejection::HeaderNotAllowed(ref h) => write!(f, "header {} not allowed", h),
+            CorsRejection::PrivateNetworkNotAllowed => {
+                f.write_str("private network access not allowed")
+            }
+            CorsRejection::InvalidRequest => f.write_str("invalid CORS request header"),
+        }
+    }
+}
+
+impl StdError for CorsRejection {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn encoded(cors: &CorsHeaders) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        cors.encode(&mut map);
+        map
+    }
+
+    #[test]
+    fn test_not_cors() {
+        let policy = CorsPolicy::new().allow_any_origin();
+        assert!(policy
+            .evaluate(&::http::Method::GET, &HeaderMap::new())
+            .is_none());
+    }
+
+    #[test]
+    fn test_actual_any_origin() {
+        let policy = CorsPolicy::new()
+            .allow_any_origin()
+            .expose_headers(vec!["ETag"]);
+        let req = request(&[("origin", "https://a.example")]);
+        let cors = policy
+            .evaluate(&::http::Method::GET, &req)
+            .unwrap()
+            .unwrap();
+        let res = encoded(&cors);
+        assert_eq!(res["access-control-allow-origin"], "*");
+        assert_eq!(res["access-control-expose-headers"], "ETag");
+        assert!(!res.contains_key("vary"));
+        assert!(!res.contains_key("access-control-allow-credentials"));
+
+        // null is not matched by any origin
+        let req = request(&[("origin", "null")]);
+        assert_eq!(
+            policy.evaluate(&::http::Method::GET, &req),
+            Some(Err(CorsRejection::OriginNotAllowed))
+        );
+    }
+
+    #[test]
+    fn test_actual_credentials() {
+        let policy = CorsPolicy::new().allow_any_origin().allow_credentials(true);
+        let cors = policy
+            .actual(&Origin::new("http", "a.example", Some(8080)))
+            .unwrap();
+        let res = encoded(&cors);
+        assert_eq!(res["access-control-allow-origin"], "http://a.example:8080");
+        assert_eq!(res["access-control-allow-credentials"], "true");
+        assert_eq!(res["vary"], "Origin");
+    }
+
+    #[test]
+    fn test_preflight() {
+        let policy = CorsPolicy::new()
+            .allow_origin(Origin::new("https", "a.example", None))
+            .allow_methods(vec![Method::Put])
+            .allow_headers(vec!["X-Token"]);
+        let origin = Origin::new("https", "a.example", None);
+        let put = AccessControlRequestMethod(Method::Put);
+
+        let cors = policy.preflight(&origin, &put, None, false).unwrap();
+        let res = encoded(&cors);
+        assert_eq!(res["access-control-allow-methods"], "PUT");
+        assert_eq!(res["access-control-allow-headers"], "X-Token");
+        assert_eq!(
+            res["vary"],
+            "Origin, Access-Control-Request-Method, Access-Control-Request-Headers"
+        );
+        assert!(!res.contains_key("access-control-max-age"));
+
+        let get = AccessControlRequestMethod(Method::Get);
+        assert!(policy.preflight(&origin, &get, None, false).is_ok());
+        let delete = AccessControlRequestMethod(Method::Delete);
+        assert_eq!(
+            policy.preflight(&origin, &delete, None, false),
+            Err(CorsRejection::MethodNotAllowed(Method::Delete))
+        );
+
+        let headers = AccessControlRequestHeaders(smallvec![
+            Ascii::new("x-token".to_owned()),
+            Ascii::new("x-other".to_owned()),
+        ]);
+        let err = policy
+            .preflight(&origin, &put, Some(&headers), false)
+            .unwrap_err();
+        assert_eq!(err, CorsRejection::HeaderNotAllowed("x-other".to_owned()));
+        assert_eq!(err.to_string(), "header x-other not allowed");
+
+        let any = policy.clone().allow_any_header();
+        let cors = any.preflight(&origin, &put, Some(&headers), false).unwrap();
+        assert_eq!(
+            encoded(&cors)["access-control-allow-headers"],
+            "x-token, x-other"
+        );
+    }
+
+    #[test]
+    fn test_preflight_private_network() {
+        let policy = CorsPolicy::new()
+            .allow_origin(Origin::new("https", "a.example", None))
+            .max_age(60);
+        let req = request(&[
+            ("origin", "https://a.example"),
+            ("access-control-request-method", "GET"),
+            ("access-control-request-private-network", "true"),
+        ]);
+        assert_eq!(
+            policy.evaluate(&::http::Method::OPTIONS, &req),
+            Some(Err(CorsRejection::PrivateNetworkNotAllowed))
+        );
+        // Not a preflight, without OPTIONS
+        assert!(policy.evaluate(&::http::Method::GET, &req).unwrap().is_ok());
+
+        let policy = policy.allow_private_network(true);
+        let cors = policy
+            .evaluate(&::http::Method::OPTIONS, &req)
+            .unwrap()
+            .unwrap();
+        let res = encoded(&cors);
+        assert_eq!(res["access-control-allow-private-network"], "true");
+        assert_eq!(res["access-control-max-age"], "60");
+    }
+}
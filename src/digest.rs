@@ -0,0 +1,769 @@
+//! Digest Access Authentication, per [RFC 7616].
+//!
+//! This module parses and formats the `Digest` challenge of a
+//! `WWW-Authenticate` header as a [`Challenge`](struct.Challenge.html),
+//! computes the credentials of a client for a challenge via a
+//! [`ClientSession`](struct.ClientSession.html), which tracks the nonce
+//! count, and [`verify`](fn.verify.html)s received credentials on a server.
+//! The credentials are sent as the typed
+//! [`Authorization<Digest>`](../header/struct.Digest.html) header.
+//!
+//! The `MD5`, `SHA-256` and `SHA-512-256` algorithms, their `-sess`
+//! variants, and the `auth` and `auth-int` qualities of protection are
+//! supported. Hash functions are provided by a pluggable
+//! [`HashBackend`](trait.HashBackend.html) implementation, e.g. backed by the
+//! _md5_ and _sha2_ crates.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! # extern crate md5;
+//! use hyperx::digest::{self, Algorithm, Challenge, ClientSession, HashBackend};
+//!
+//! struct Md5;
+//!
+//! impl HashBackend for Md5 {
+//!     fn hash(&self, algorithm: Algorithm, data: &[u8]) -> hyperx::Result<Vec<u8>> {
+//!         match algorithm {
+//!             Algorithm::Md5 | Algorithm::Md5Sess => Ok(md5::compute(data).to_vec()),
+//!             _ => Err(hyperx::HeaderErrorKind::Conversion.into()),
+//!         }
+//!     }
+//! }
+//!
+//! # fn run() -> hyperx::Result<()> {
+//! // Server: challenge the client
+//! let challenge = Challenge::new("api@example.org", "dcd98b7102dd2f0e")
+//!     .with_algorithm(Algorithm::Md5);
+//! let www_authenticate = challenge.to_string();
+//!
+//! // Client: respond to the challenge
+//! let mut session = ClientSession::new(www_authenticate.parse()?, "Mufasa", "Circle of Life");
+//! let credentials = session.authorize(&http::Method::GET, "/dir/index.html", b"", "0a4f113b", &Md5)?;
+//! assert_eq!(credentials.nc, Some(1));
+//!
+//! // Server: verify with the stored H(username:realm:password)
+//! let ha1 = digest::ha1(&Md5, Algorithm::Md5, "Mufasa", "api@example.org", "Circle of Life")?;
+//! assert!(digest::verify(&Md5, &credentials, &http::Method::GET, b"", &ha1)?);
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+//!
+//! Generating nonces, and rejecting a replayed nonce count (see
+//! [`NonceCounter`](struct.NonceCounter.html)), is left to the server.
+//!
+//! [RFC 7616]: https://www.rfc-editor.org/rfc/rfc7616
+
+use std::collections::HashMap;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use http::Method;
+
+use header::parsing;
+use header::Digest;
+use {Error, HeaderErrorKind, Result};
+
+/// A Digest algorithm.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Algorithm {
+    /// `MD5`, the default if not specified.
+    Md5,
+    /// `MD5-sess`
+    Md5Sess,
+    /// `SHA-256`
+    Sha256,
+    /// `SHA-256-sess`
+    Sha256Sess,
+    /// `SHA-512-256`
+    Sha512_256,
+    /// `SHA-512-256-sess`
+    Sha512_256Sess,
+}
+
+impl Algorithm {
+    /// The name of the algorithm, as sent in the `algorithm` parameter.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Algorithm::Md5 => "MD5",
+            Algorithm::Md5Sess => "MD5-sess",
+            Algorithm::Sha256 => "SHA-256",
+            Algorithm::Sha256Sess => "SHA-256-sess",
+            Algorithm::Sha512_256 => "SHA-512-256",
+            Algorithm::Sha512_256Sess => "SHA-512-256-sess",
+        }
+    }
+
+    /// Returns true for a `-sess` variant, which hashes the client and
+    /// server nonces into `HA1`.
+    pub fn is_session(&self) -> bool {
+        match *self {
+            Algorithm::Md5Sess | Algorithm::Sha256Sess | Algorithm::Sha512_256Sess => true,
+            Algorithm::Md5 | Algorithm::Sha256 | Algorithm::Sha512_256 => false,
+        }
+    }
+}
+
+impl fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Algorithm {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Algorithm> {
+        [
+            Algorithm::Md5,
+            Algorithm::Md5Sess,
+            Algorithm::Sha256,
+            Algorithm::Sha256Sess,
+            Algorithm::Sha512_256,
+            Algorithm::Sha512_256Sess,
+        ]
+        .iter()
+        .find(|a| a.name().eq_ignore_ascii_case(s))
+        .cloned()
+        .ok_or_else(|| Error::header(HeaderErrorKind::Token))
+    }
+}
+
+/// A quality of protection.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Qop {
+    /// `auth`: authentication only.
+    Auth,
+    /// `auth-int`: authentication with integrity protection of the body.
+    AuthInt,
+}
+
+impl Qop {
+    /// The name of the quality of protection.
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Qop::Auth => "auth",
+            Qop::AuthInt => "auth-int",
+        }
+    }
+}
+
+impl fmt::Display for Qop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl FromStr for Qop {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Qop> {
+        if s.eq_ignore_ascii_case("auth") {
+            Ok(Qop::Auth)
+        } else if s.eq_ignore_ascii_case("auth-int") {
+            Ok(Qop::AuthInt)
+        } else {
+            Err(Error::header(HeaderErrorKind::Token))
+        }
+    }
+}
+
+/// Hash functions of the Digest algorithms.
+pub trait HashBackend {
+    /// Returns the hash of `data` by the hash function of `algorithm`, where
+    /// a `-sess` variant uses the same function as its base algorithm, or
+    /// an error if the algorithm is not supported.
+    fn hash(&self, algorithm: Algorithm, data: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A `Digest` challenge of a `WWW-Authenticate` header.
+///
+/// Parsing finds the first `Digest` challenge among possibly multiple
+/// challenges of a header value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Challenge {
+    /// The protection space.
+    pub realm: String,
+    /// The server nonce.
+    pub nonce: String,
+    /// An opaque value, to be returned unchanged by the client.
+    pub opaque: Option<String>,
+    /// The algorithm.
+    pub algorithm: Algorithm,
+    /// The qualities of protection supported by the server. Empty for the
+    /// legacy [RFC 2069](https://www.rfc-editor.org/rfc/rfc2069) mode.
+    pub qop: Vec<Qop>,
+    /// Whether the previous request was rejected due to a stale nonce only.
+    pub stale: bool,
+    /// Whether the server supports hashed user names.
+    pub userhash: bool,
+}
+
+impl Challenge {
+    /// Create a new challenge, with the `SHA-256` algorithm and `qop=auth`.
+    pub fn new<R, N>(realm: R, nonce: N) -> Challenge
+    where
+        R: Into<String>,
+        N: Into<String>,
+    {
+        Challenge {
+            realm: realm.into(),
+            nonce: nonce.into(),
+            opaque: None,
+            algorithm: Algorithm::Sha256,
+            qop: vec![Qop::Auth],
+            stale: false,
+            userhash: false,
+        }
+    }
+
+    /// Set the algorithm.
+    pub fn with_algorithm(mut self, algorithm: Algorithm) -> Challenge {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the opaque value.
+    pub fn with_opaque<O: Into<String>>(mut self, opaque: O) -> Challenge {
+        self.opaque = Some(opaque.into());
+        self
+    }
+
+    /// Set the supported qualities of protection.
+    pub fn with_qop<I: IntoIterator<Item = Qop>>(mut self, qop: I) -> Challenge {
+        self.qop = qop.into_iter().collect();
+        self
+    }
+
+    /// Set the `stale` flag.
+    pub fn with_stale(mut self, stale: bool) -> Challenge {
+        self.stale = stale;
+        self
+    }
+
+    /// Set the `userhash` flag.
+    pub fn with_userhash(mut self, userhash: bool) -> Challenge {
+        self.userhash = userhash;
+        self
+    }
+
+    fn from_params<'a, I>(params: I) -> Result<Challenge>
+    where
+        I: IntoIterator<Item = &'a str>,
+    {
+        let syntax = || Error::header(HeaderErrorKind::Syntax);
+        let mut realm = None;
+        let mut nonce = None;
+        let mut challenge = Challenge::new("", "").with_algorithm(Algorithm::Md5);
+        challenge.qop.clear();
+        for param in params {
+            let (name, value) = match parsing::parse_parameter(param)? {
+                (name, Some(value)) => (name, value),
+                _ => return Err(syntax()),
+            };
+            match name {
+                n if n.eq_ignore_ascii_case("realm") => realm = Some(value.into_owned()),
+                n if n.eq_ignore_ascii_case("nonce") => nonce = Some(value.into_owned()),
+                n if n.eq_ignore_ascii_case("opaque") => {
+                    challenge.opaque = Some(value.into_owned())
+                }
+                n if n.eq_ignore_ascii_case("algorithm") => challenge.algorithm = value.parse()?,
+                n if n.eq_ignore_ascii_case("qop") => {
+                    // Unknown qualities of protection are ignored
+                    challenge.qop = parsing::split_comma_delimited(&value)
+                        .filter_map(|q| q.parse().ok())
+                        .collect();
+                }
+                n if n.eq_ignore_ascii_case("stale") => {
+                    challenge.stale = value.eq_ignore_ascii_case("true")
+                }
+                n if n.eq_ignore_ascii_case("userhash") => {
+                    challenge.userhash = value.eq_ignore_ascii_case("true")
+                }
+                _ => {}
+            }
+        }
+        challenge.realm = realm.ok_or_else(syntax)?;
+        challenge.nonce = nonce.ok_or_else(syntax)?;
+        Ok(challenge)
+    }
+}
+
+impl fmt::Display for Challenge {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Digest realm=")?;
+        parsing::fmt_quoted(f, &self.realm)?;
+        if !self.qop.is_empty() {
+            f.write_str(", qop=\"")?;
+            for (i, qop) in self.qop.iter().enumerate() {
+                if i > 0 {
+                    f.write_str(", ")?;
+                }
+                f.write_str(qop.name())?;
+            }
+            f.write_str("\"")?;
+        }
+        write!(f, ", algorithm={}, nonce=", self.algorithm)?;
+        parsing::fmt_quoted(f, &self.nonce)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            parsing::fmt_quoted(f, opaque)?;
+        }
+        if self.stale {
+            f.write_str(", stale=true")?;
+        }
+        if self.userhash {
+            f.write_str(", userhash=true")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Challenge {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Challenge> {
+        // Challenges and their parameters are both comma delimited; an item
+        // starts a new challenge if it begins with a scheme token.
+        let mut params = Vec::new();
+        let mut digest = false;
+        for item in parsing::split_comma_delimited(s) {
+            let (first, rest) = match item.find(char::is_whitespace) {
+                Some(i) => (&item[..i], item[i..].trim_start()),
+                None => (item, ""),
+            };
+            if !first.contains('=') && !rest.starts_with('=') {
+                if digest {
+                    break;
+                }
+                digest = first.eq_ignore_ascii_case("Digest");
+                if digest && !rest.is_empty() {
+                    params.push(rest);
+                }
+            } else if digest {
+                params.push(item);
+            }
+        }
+        if !digest {
+            return Err(Error::header(HeaderErrorKind::Token));
+        }
+        Challenge::from_params(params)
+    }
+}
+
+/// The client state for responding to a `Digest` challenge, with the
+/// nonce count of the challenge nonce.
+#[derive(Clone, Debug)]
+pub struct ClientSession {
+    challenge: Challenge,
+    username: String,
+    password: String,
+    nc: u32,
+}
+
+impl ClientSession {
+    /// Create a new session for a challenge and the user credentials.
+    pub fn new<U, P>(challenge: Challenge, username: U, password: P) -> ClientSession
+    where
+        U: Into<String>,
+        P: Into<String>,
+    {
+        ClientSession {
+            challenge,
+            username: username.into(),
+            password: password.into(),
+            nc: 0,
+        }
+    }
+
+    /// The challenge responded to.
+    pub fn challenge(&self) -> &Challenge {
+        &self.challenge
+    }
+
+    /// The nonce count of the last `authorize` call, or 0 if none.
+    pub fn nonce_count(&self) -> u32 {
+        self.nc
+    }
+
+    /// Replace the challenge, e.g. of a subsequent `stale=true` response,
+    /// resetting the nonce count if the nonce changed.
+    pub fn update(&mut self, challenge: Challenge) {
+        if challenge.nonce != self.challenge.nonce {
+            self.nc = 0;
+        }
+        self.challenge = challenge;
+    }
+
+    /// Compute the credentials of a request, incrementing the nonce count.
+    ///
+    /// The `uri` is the request-target, `body` the request body (hashed
+    /// only for `auth-int`), and `cnonce` a fresh client nonce, e.g. of
+    /// random bytes in hex or base64. `auth` is preferred when the server
+    /// offers both qualities of protection.
+    pub fn authorize<H: HashBackend>(
+        &mut self,
+        method: &Method,
+        uri: &str,
+        body: &[u8],
+        cnonce: &str,
+        backend: &H,
+    ) -> Result<Digest> {
+        let c = &self.challenge;
+        let qop = if c.qop.contains(&Qop::Auth) {
+            Some(Qop::Auth)
+        } else {
+            c.qop.first().cloned()
+        };
+        let nc = self.nc.checked_add(1).ok_or(HeaderErrorKind::TooLarge)?;
+        let ha1 = ha1(
+            backend,
+            c.algorithm,
+            &self.username,
+            &c.realm,
+            &self.password,
+        )?;
+        let (nc_opt, cnonce_opt) = match qop {
+            Some(_) => (Some(nc), Some(cnonce)),
+            None => (None, None),
+        };
+        let input = ResponseInput {
+            nonce: &c.nonce,
+            nc: nc_opt,
+            cnonce: cnonce_opt,
+            qop,
+            method,
+            uri,
+            body,
+        };
+        let response = response(backend, c.algorithm, &ha1, &input)?;
+        let username = if c.userhash {
+            hash_hex(
+                backend,
+                c.algorithm,
+                format!("{}:{}", self.username, c.realm).as_bytes(),
+            )?
+        } else {
+            self.username.clone()
+        };
+        self.nc = nc;
+        Ok(Digest {
+            username,
+            realm: c.realm.clone(),
+            nonce: c.nonce.clone(),
+            uri: uri.to_owned(),
+            response,
+            algorithm: Some(c.algorithm.name().to_owned()),
+            cnonce: cnonce_opt.map(ToOwned::to_owned),
+            opaque: c.opaque.clone(),
+            qop: qop.map(|q| q.name().to_owned()),
+            nc: nc_opt,
+            userhash: c.userhash,
+        })
+    }
+}
+
+/// Compute the hex-encoded `H(username:realm:password)`, as stored by
+/// servers in place of the password and passed to
+/// [`verify`](fn.verify.html). For a `-sess` algorithm, the nonces are
+/// hashed in by `verify`.
+pub fn ha1<H: HashBackend>(
+    backend: &H,
+    algorithm: Algorithm,
+    username: &str,
+    realm: &str,
+    password: &str,
+) -> Result<String> {
+    let a1 = format!("{}:{}:{}", username, realm, password);
+    hash_hex(backend, algorithm, a1.as_bytes())
+}
+
+/// Verify the `response` of received credentials, given the method and
+/// body of the request and the stored `ha1` of the user (see
+/// [`ha1`](fn.ha1.html)).
+///
+/// Checking that the realm, nonce, opaque and uri are as expected, and that
+/// the nonce count was not seen before, is left to the caller. An error is
+/// returned for an unsupported algorithm or quality of protection, or if
+/// `qop` is sent without `nc` and `cnonce`.
+pub fn verify<H: HashBackend>(
+    backend: &H,
+    credentials: &Digest,
+    method: &Method,
+    body: &[u8],
+    ha1: &str,
+) -> Result<bool> {
+    let algorithm = match credentials.algorithm {
+        Some(ref a) => a.parse()?,
+        None => Algorithm::Md5,
+    };
+    let qop = match credentials.qop {
+        Some(ref q) => Some(q.parse::<Qop>()?),
+        None => None,
+    };
+    if qop.is_some() && (credentials.nc.is_none() || credentials.cnonce.is_none()) {
+        return Err(Error::header(HeaderErrorKind::Syntax));
+    }
+    let input = ResponseInput {
+        nonce: &credentials.nonce,
+        nc: credentials.nc,
+        cnonce: credentials.cnonce.as_ref().map(|c| &c[..]),
+        qop,
+        method,
+        uri: &credentials.uri,
+        body,
+    };
+    let expected = response(backend, algorithm, ha1, &input)?;
+    Ok(constant_time_eq(
+        expected.as_bytes(),
+        credentials.response.to_ascii_lowercase().as_bytes(),
+    ))
+}
+
+/// Tracks the highest nonce count seen per nonce on a server, to reject
+/// replayed credentials.
+#[derive(Clone, Debug, Default)]
+pub struct NonceCounter {
+    counts: HashMap<String, u32>,
+}
+
+impl NonceCounter {
+    /// Create a new, empty counter.
+    pub fn new() -> NonceCounter {
+        NonceCounter::default()
+    }
+
+    /// Record the nonce count of credentials for a nonce, returning false
+    /// if it is not greater than that of all previous credentials for the
+    /// nonce.
+    pub fn check(&mut self, nonce: &str, nc: u32) -> bool {
+        match self.counts.get_mut(nonce) {
+            Some(last) if *last >= nc => false,
+            Some(last) => {
+                *last = nc;
+                true
+            }
+            None => {
+                self.counts.insert(nonce.to_owned(), nc);
+                true
+            }
+        }
+    }
+
+    /// Forget a nonce, e.g. once it expires.
+    pub fn remove(&mut self, nonce: &str) {
+        self.counts.remove(nonce);
+    }
+}
+
+struct ResponseInput<'a> {
+    nonce: &'a str,
+    nc: Option<u32>,
+    cnonce: Option<&'a str>,
+    qop: Option<Qop>,
+    method: &'a Method,
+    uri: &'a str,
+    body: &'a [u8],
+}
+
+fn response<H: HashBackend>(
+    backend: &H,
+    algorithm: Algorithm,
+    ha1: &str,
+    input: &ResponseInput,
+) -> Result<String> {
+    let cnonce = input.cnonce.unwrap_or("");
+    let ha1 = if algorithm.is_session() {
+        let a1 = format!("{}:{}:{}", ha1, input.nonce, cnonce);
+        hash_hex(backend, algorithm, a1.as_bytes())?
+    } else {
+        ha1.to_owned()
+    };
+    let a2 = match input.qop {
+        Some(Qop::AuthInt) => format!(
+            "{}:{}:{}",
+            input.method,
+            input.uri,
+            hash_hex(backend, algorithm, input.body)?
+        ),
+        _ => format!("{}:{}", input.method, input.uri),
+    };
+    let ha2 = hash_hex(backend, algorithm, a2.as_bytes())?;
+    let kd = match (input.qop, input.nc) {
+        (Some(qop), Some(nc)) => format!(
+            "{}:{}:{:08x}:{}:{}:{}",
+            ha1, input.nonce, nc, cnonce, qop, ha2
+        ),
+        _ => format!("{}:{}:{}", ha1, input.nonce, ha2),
+    };
+    hash_hex(backend, algorithm, kd.as_bytes())
+}
+
+fn hash_hex<H: HashBackend>(backend: &H, algorithm: Algorithm, data: &[u8]) -> Result<String> {
+    let hash = backend.hash(algorithm, data)?;
+    let mut hex = String::with_capacity(hash.len() * 2);
+    for b in hash {
+        write!(hex, "{:02x}", b).unwrap();
+    }
+    Ok(hex)
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use md5;
+    use sha2::{Digest as _, Sha256, Sha512_256};
+
+    struct Backend;
+
+    impl HashBackend for Backend {
+        fn hash(&self, algorithm: Algorithm, data: &[u8]) -> Result<Vec<u8>> {
+            Ok(match algorithm {
+                Algorithm::Md5 | Algorithm::Md5Sess => md5::compute(data).to_vec(),
+                Algorithm::Sha256 | Algorithm::Sha256Sess => Sha256::digest(data).to_vec(),
+                Algorithm::Sha512_256 | Algorithm::Sha512_256Sess => {
+                    Sha512_256::digest(data).to_vec()
+                }
+            })
+        }
+    }
+
+    // The example of RFC 7616, section 3.9.1
+    const CHALLENGE: &str = "Digest realm=\"http-auth@example.org\", qop=\"auth, auth-int\", \
+        algorithm=SHA-256, nonce=\"7ypf/xlj9XXwfDPEoM4URrv/xwf94BcCAzFZH4GiTo0v\", \
+        opaque=\"FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS\"";
+    const CNONCE: &str = "f2/wE4q74E6zIJEtWaHKaf5wv/H5QzzpXusqGemxURZJ";
+
+    fn authorize(algorithm: Algorithm) -> Digest {
+        let challenge = CHALLENGE.parse::<Challenge>().unwrap();
+        let mut session = ClientSession::new(
+            challenge.with_algorithm(algorithm),
+            "Mufasa",
+            "Circle of Life",
+        );
+        session
+            .authorize(&Method::GET, "/dir/index.html", b"", CNONCE, &Backend)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_challenge() {
+        let challenge = CHALLENGE.parse::<Challenge>().unwrap();
+        assert_eq!(challenge.realm, "http-auth@example.org");
+        assert_eq!(challenge.algorithm, Algorithm::Sha256);
+        assert_eq!(challenge.qop, vec![Qop::Auth, Qop::AuthInt]);
+        assert_eq!(
+            challenge.opaque.as_ref().map(|o| &o[..]),
+            Some("FQhe/qaU925kfnzjCev0ciny7QMkPqMAFRtzCUYo5tdS")
+        );
+        assert_eq!(
+            challenge.to_string().parse::<Challenge>().unwrap(),
+            challenge
+        );
+
+        let multiple = "Basic realm=\"a\", Bearer, Digest nonce=\"n\", realm=\"b\", \
+                        Newauth realm=\"c\"";
+        let challenge = multiple.parse::<Challenge>().unwrap();
+        assert_eq!(challenge.realm, "b");
+        assert_eq!(challenge.algorithm, Algorithm::Md5);
+        assert!(challenge.qop.is_empty());
+
+        assert!("Basic realm=\"a\"".parse::<Challenge>().is_err());
+        assert!("Digest realm=\"a\"".parse::<Challenge>().is_err());
+        assert!("Digest realm=\"a\", nonce=\"n\", algorithm=SHA-1"
+            .parse::<Challenge>()
+            .is_err());
+    }
+
+    #[test]
+    fn test_rfc_example() {
+        let md5 = authorize(Algorithm::Md5);
+        assert_eq!(md5.response, "8ca523f5e9506fed4657c9700eebdbec");
+        assert_eq!(md5.nc, Some(1));
+        assert_eq!(md5.qop.as_ref().map(|q| &q[..]), Some("auth"));
+
+        let sha256 = authorize(Algorithm::Sha256);
+        assert_eq!(
+            sha256.response,
+            "753927fa0e85d155564e2e272a28d1802ca10daf4496794697cf8db5856cb6c1"
+        );
+    }
+
+    #[test]
+    fn test_verify() {
+        for &algorithm in &[
+            Algorithm::Md5,
+            Algorithm::Md5Sess,
+            Algorithm::Sha256Sess,
+            Algorithm::Sha512_256,
+        ] {
+            let credentials = authorize(algorithm);
+            let ha1 = ha1(
+                &Backend,
+                algorithm,
+                "Mufasa",
+                "http-auth@example.org",
+                "Circle of Life",
+            )
+            .unwrap();
+            assert!(verify(&Backend, &credentials, &Method::GET, b"", &ha1).unwrap());
+            assert!(!verify(&Backend, &credentials, &Method::POST, b"", &ha1).unwrap());
+
+            let wrong = super::ha1(&Backend, algorithm, "Mufasa", "x", "y").unwrap();
+            assert!(!verify(&Backend, &credentials, &Method::GET, b"", &wrong).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_auth_int_and_nonce_count() {
+        let challenge = Challenge::new("r", "n1").with_qop(vec![Qop::AuthInt]);
+        let mut session = ClientSession::new(challenge.clone(), "u", "p");
+        let ha1 = ha1(&Backend, Algorithm::Sha256, "u", "r", "p").unwrap();
+        let mut counter = NonceCounter::new();
+        for nc in 1..=3 {
+            let credentials = session
+                .authorize(&Method::PUT, "/x", b"body", "c", &Backend)
+                .unwrap();
+            assert_eq!(credentials.nc, Some(nc));
+            assert_eq!(credentials.qop.as_ref().map(|q| &q[..]), Some("auth-int"));
+            assert!(verify(&Backend, &credentials, &Method::PUT, b"body", &ha1).unwrap());
+            assert!(!verify(&Backend, &credentials, &Method::PUT, b"other", &ha1).unwrap());
+            assert!(counter.check(&credentials.nonce, nc));
+            assert!(!counter.check(&credentials.nonce, nc));
+        }
+
+        session.update(challenge.clone());
+        assert_eq!(session.nonce_count(), 3);
+        session.update(Challenge::new("r", "n2").with_stale(true));
+        assert_eq!(session.nonce_count(), 0);
+    }
+
+    #[test]
+    fn test_legacy_and_userhash() {
+        let challenge = Challenge::new("r", "n")
+            .with_algorithm(Algorithm::Md5)
+            .with_qop(vec![])
+            .with_userhash(true);
+        let mut session = ClientSession::new(challenge, "u", "p");
+        let credentials = session
+            .authorize(&Method::GET, "/", b"", "c", &Backend)
+            .unwrap();
+        assert_eq!(credentials.nc, None);
+        assert_eq!(credentials.cnonce, None);
+        assert!(credentials.userhash);
+        assert_eq!(credentials.username, format!("{:x}", md5::compute(b"u:r")));
+        let ha1 = ha1(&Backend, Algorithm::Md5, "u", "r", "p").unwrap();
+        assert!(verify(&Backend, &credentials, &Method::GET, b"", &ha1).unwrap());
+
+        let mut invalid = credentials.clone();
+        invalid.qop = Some("auth".to_owned());
+        assert!(verify(&Backend, &invalid, &Method::GET, b"", &ha1).is_err());
+    }
+}
@@ -0,0 +1,352 @@
+//! The `Link` headers of `103 Early Hints` responses, per [RFC 8297].
+//!
+//! An [`EarlyHints`](struct.EarlyHints.html) set collects the `preload`
+//! and `preconnect` [`Hint`](struct.Hint.html)s a server sends ahead of its
+//! final response, dropping duplicates, and encodes them as a `Link`
+//! header. Since clients may ignore hints which the final response does not
+//! confirm, [`EarlyHints::diff`](struct.EarlyHints.html#method.diff)
+//! compares the set against the `Link` header of the final response.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::early_hints::{CrossOrigin, EarlyHints, Hint};
+//!
+//! let hints = EarlyHints::new()
+//!     .hint(Hint::preload("/style.css", "style"))
+//!     .hint(Hint::preload("/font.woff2", "font").crossorigin(CrossOrigin::Anonymous))
+//!     .hint(Hint::preconnect("https://cdn.example"))
+//!     .hint(Hint::preload("/style.css", "style"));
+//!
+//! let mut headers = http::HeaderMap::new();
+//! hints.encode(&mut headers);
+//! assert_eq!(
+//!     headers["link"],
+//!     "</style.css>; rel=\"preload\"; as=style, \
+//!      </font.woff2>; rel=\"preload\"; as=font; crossorigin, \
+//!      <https://cdn.example>; rel=\"preconnect\""
+//! );
+//!
+//! let diff = hints.diff(&"</style.css>; rel=preload; as=style".parse().unwrap());
+//! assert_eq!(diff.unconfirmed.len(), 2);
+//! assert!(diff.unhinted.is_empty());
+//! ```
+//!
+//! [RFC 8297]: https://www.rfc-editor.org/rfc/rfc8297
+
+use std::fmt;
+
+use http::HeaderMap;
+
+use header::{Link, LinkValue, RelationType, TypedHeaders};
+
+/// The relation of a [`Hint`](struct.Hint.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum HintRel {
+    /// `preload`: fetch a resource required by the final response.
+    Preload,
+    /// `preconnect`: open a connection to an origin.
+    Preconnect,
+}
+
+impl HintRel {
+    fn as_str(self) -> &'static str {
+        match self {
+            HintRel::Preload => "preload",
+            HintRel::Preconnect => "preconnect",
+        }
+    }
+}
+
+impl fmt::Display for HintRel {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// The CORS settings of a [`Hint`](struct.Hint.html), its `crossorigin`
+/// parameter.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum CrossOrigin {
+    /// `anonymous`, also implied by a `crossorigin` parameter without a
+    /// value (or with an invalid one).
+    Anonymous,
+    /// `use-credentials`.
+    UseCredentials,
+}
+
+/// A `preload` or `preconnect` hint, as a `link-value` of the `Link`
+/// header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Hint {
+    /// The relation.
+    pub rel: HintRel,
+    /// The target URI: the resource to preload, or the origin to connect
+    /// to.
+    pub uri: String,
+    /// The destination of a preloaded resource, its `as` parameter, such
+    /// as `style`, `script` or `font`.
+    pub destination: Option<String>,
+    /// The `crossorigin` parameter.
+    pub crossorigin: Option<CrossOrigin>,
+    /// The `nopush` parameter, asking a server not to push the resource.
+    pub nopush: bool,
+}
+
+impl Hint {
+    /// Create a `preload` hint, of the given destination (`as`).
+    pub fn preload<U, D>(uri: U, destination: D) -> Hint
+    where
+        U: Into<String>,
+        D: Into<String>,
+    {
+        Hint {
+            rel: HintRel::Preload,
+            uri: uri.into(),
+            destination: Some(destination.into()),
+            crossorigin: None,
+            nopush: false,
+        }
+    }
+
+    /// Create a `preconnect` hint.
+    pub fn preconnect<U: Into<String>>(uri: U) -> Hint {
+        Hint {
+            rel: HintRel::Preconnect,
+            uri: uri.into(),
+            destination: None,
+            crossorigin: None,
+            nopush: false,
+        }
+    }
+
+    /// Set the `crossorigin` parameter.
+    pub fn crossorigin(mut self, crossorigin: CrossOrigin) -> Hint {
+        self.crossorigin = Some(crossorigin);
+        self
+    }
+
+    /// Set the `nopush` parameter.
+    pub fn nopush(mut self) -> Hint {
+        self.nopush = true;
+        self
+    }
+
+    /// Convert a `link-value` into a hint, if it has a `preload` or
+    /// `preconnect` relation. Other parameters are ignored.
+    pub fn from_link_value(value: &LinkValue) -> Option<Hint> {
+        let rel = value.rel()?.iter().find_map(|rel| match *rel {
+            RelationType::ExtRelType(ref r) if r.eq_ignore_ascii_case("preload") => {
+                Some(HintRel::Preload)
+            }
+            RelationType::ExtRelType(ref r) if r.eq_ignore_ascii_case("preconnect") => {
+                Some(HintRel::Preconnect)
+            }
+            _ => None,
+        })?;
+        let crossorigin = value.param("crossorigin").map(|v| match v {
+            Some(v) if v.eq_ignore_ascii_case("use-credentials") => CrossOrigin::UseCredentials,
+            _ => CrossOrigin::Anonymous,
+        });
+        Some(Hint {
+            rel,
+            uri: value.link().to_owned(),
+            destination: value.param("as").and_then(|v| v).map(str::to_owned),
+            crossorigin,
+            nopush: value.param("nopush").is_some(),
+        })
+    }
+
+    /// Convert the hint into a `link-value`.
+    pub fn to_link_value(&self) -> LinkValue {
+        let mut value = LinkValue::new(self.uri.clone())
+            .push_rel(RelationType::ExtRelType(self.rel.as_str().to_owned()));
+        if let Some(ref destination) = self.destination {
+            value = value.push_param("as", destination.clone());
+        }
+        match self.crossorigin {
+            Some(CrossOrigin::Anonymous) => value = value.push_flag("crossorigin"),
+            Some(CrossOrigin::UseCredentials) => {
+                value = value.push_param("crossorigin", "use-credentials")
+            }
+            None => {}
+        }
+        if self.nopush {
+            value = value.push_flag("nopush");
+        }
+        value
+    }
+
+    // Hints of the same relation, target and CORS settings are duplicates,
+    // so fetch or connect only once.
+    fn is_duplicate(&self, other: &Hint) -> bool {
+        self.rel == other.rel && self.uri == other.uri && self.crossorigin == other.crossorigin
+    }
+}
+
+/// A set of hints for a `103 Early Hints` response, constructed via
+/// builder methods.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct EarlyHints {
+    hints: Vec<Hint>,
+}
+
+impl EarlyHints {
+    /// Create a new, empty set.
+    pub fn new() -> EarlyHints {
+        EarlyHints::default()
+    }
+
+    /// Add a hint, unless a hint of the same relation, target URI and
+    /// `crossorigin` parameter was already added.
+    pub fn hint(mut self, hint: Hint) -> EarlyHints {
+        if !self.hints.iter().any(|h| h.is_duplicate(&hint)) {
+            self.hints.push(hint);
+        }
+        self
+    }
+
+    /// The hints, in order.
+    pub fn hints(&self) -> &[Hint] {
+        &self.hints
+    }
+
+    /// Returns true if the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.hints.is_empty()
+    }
+
+    /// The `Link` header of the hints.
+    pub fn link(&self) -> Link {
+        Link::new(self.hints.iter().map(Hint::to_link_value).collect())
+    }
+
+    /// Encode the `Link` header into the headers of a `103 Early Hints`
+    /// response, replacing any existing value. Nothing is encoded if the
+    /// set is empty.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if !self.is_empty() {
+            headers.encode(&self.link());
+        }
+    }
+
+    /// Compare the hints against the `Link` header of the final response.
+    pub fn diff(&self, link: &Link) -> HintsDiff {
+        let mut confirmed: Vec<Hint> = Vec::new();
+        for hint in link.values().iter().filter_map(Hint::from_link_value) {
+            if !confirmed.iter().any(|h| h.is_duplicate(&hint)) {
+                confirmed.push(hint);
+            }
+        }
+        HintsDiff {
+            unconfirmed: self
+                .hints
+                .iter()
+                .filter(|h| !confirmed.iter().any(|c| c.is_duplicate(h)))
+                .cloned()
+                .collect(),
+            unhinted: confirmed
+                .into_iter()
+                .filter(|c| !self.hints.iter().any(|h| h.is_duplicate(c)))
+                .collect(),
+        }
+    }
+}
+
+/// The differences between [`EarlyHints`](struct.EarlyHints.html) and the
+/// `Link` header of the final response.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct HintsDiff {
+    /// The early hints absent from the final response.
+    pub unconfirmed: Vec<Hint>,
+    /// The hints of the final response which were not sent early.
+    pub unhinted: Vec<Hint>,
+}
+
+impl HintsDiff {
+    /// Returns true if the hints match those of the final response.
+    pub fn is_empty(&self) -> bool {
+        self.unconfirmed.is_empty() && self.unhinted.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(s: &str) -> Link {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_dedup() {
+        let hints = EarlyHints::new()
+            .hint(Hint::preconnect("https://cdn.example"))
+            .hint(Hint::preconnect("https://cdn.example").crossorigin(CrossOrigin::Anonymous))
+            .hint(Hint::preconnect("https://cdn.example"))
+            .hint(Hint::preload("/app.js", "script").nopush())
+            .hint(Hint::preload("/app.js", "script"));
+        assert_eq!(hints.hints().len(), 3);
+        assert_eq!(
+            hints.link().to_string(),
+            "<https://cdn.example>; rel=\"preconnect\", \
+             <https://cdn.example>; rel=\"preconnect\"; crossorigin, \
+             </app.js>; rel=\"preload\"; as=script; nopush"
+        );
+    }
+
+    #[test]
+    fn test_link_value_round_trip() {
+        let hint = Hint::preload("/data.json", "fetch")
+            .crossorigin(CrossOrigin::UseCredentials)
+            .nopush();
+        assert_eq!(Hint::from_link_value(&hint.to_link_value()), Some(hint));
+
+        let link = link(
+            "</a.css>; rel=\"stylesheet preload\"; AS=style; crossorigin=bogus, \
+             </next>; rel=next",
+        );
+        assert_eq!(
+            Hint::from_link_value(&link.values()[0]),
+            Some(Hint::preload("/a.css", "style").crossorigin(CrossOrigin::Anonymous))
+        );
+        assert_eq!(Hint::from_link_value(&link.values()[1]), None);
+    }
+
+    #[test]
+    fn test_diff() {
+        let hints = EarlyHints::new()
+            .hint(Hint::preload("/style.css", "style"))
+            .hint(Hint::preload("/font.woff2", "font").crossorigin(CrossOrigin::Anonymous));
+
+        let diff = hints.diff(&link(
+            "</style.css>; rel=preload; as=style; nopush, \
+             </font.woff2>; rel=preload; as=font, \
+             </app.js>; rel=preload; as=script, \
+             </app.js>; rel=preload; as=script, \
+             </about>; rel=author",
+        ));
+        assert_eq!(
+            diff.unconfirmed,
+            vec![Hint::preload("/font.woff2", "font").crossorigin(CrossOrigin::Anonymous)]
+        );
+        assert_eq!(
+            diff.unhinted,
+            vec![
+                Hint::preload("/font.woff2", "font"),
+                Hint::preload("/app.js", "script"),
+            ]
+        );
+        assert!(!diff.is_empty());
+
+        let diff = hints.diff(&hints.link());
+        assert!(diff.is_empty());
+    }
+
+    #[test]
+    fn test_encode_empty() {
+        let mut headers = HeaderMap::new();
+        EarlyHints::new().encode(&mut headers);
+        assert!(headers.is_empty());
+    }
+}
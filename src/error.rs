@@ -4,6 +4,7 @@ use std::error::Error as StdError;
 use std::fmt;
 use std::str::Utf8Error;
 use std::string::FromUtf8Error;
+use std::sync::Arc;
 
 use self::Error::{Header, Method, Status, TooLarge, Utf8, Version};
 
@@ -17,8 +18,8 @@ pub enum Error {
     Method,
     /// An invalid `HttpVersion`, such as `HTP/1.1`
     Version,
-    /// An invalid `Header`.
-    Header,
+    /// An invalid `Header`, with details of the failure.
+    Header(HeaderError),
     /// A message head is too large to be reasonable.
     TooLarge,
     /// An invalid `Status`, such as `1337 ELITE`.
@@ -30,6 +31,142 @@ pub enum Error {
     __Nonexhaustive(Void),
 }
 
+/// Details of a failure to parse a header.
+#[derive(Clone, Debug)]
+pub struct HeaderError {
+    kind: HeaderErrorKind,
+    name: Option<&'static str>,
+    value: Option<Box<[u8]>>,
+    truncated: bool,
+    source: Option<Arc<dyn StdError + Send + Sync>>,
+}
+
+/// A broad category of `Error`, for example to select a response status.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum ErrorKind {
+    /// Malformed input, such as an invalid method, status or header value as
+    /// received from a peer. When parsing a request this typically warrants
+    /// a `400 Bad Request` response.
+    Invalid,
+    /// A message head is too large. When parsing a request this typically
+    /// warrants a `431 Request Header Fields Too Large` response.
+    TooLarge,
+    /// A value provided by the application can not be converted, such as a
+    /// header value with a newline. This typically indicates a bug in the
+    /// application (i.e. a `500 Internal Server Error`).
+    Conversion,
+}
+
+/// The kind of failure to parse a header.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum HeaderErrorKind {
+    /// No value (or an empty value) where one is required.
+    Empty,
+    /// More than one value (line) for a single-valued header.
+    Multiple,
+    /// A value that is not valid UTF-8.
+    Utf8,
+    /// An invalid or unknown token.
+    Token,
+    /// An invalid number.
+    Number,
+    /// An invalid HTTP date.
+    Date,
+    /// Otherwise malformed syntax.
+    Syntax,
+    /// A name or value which can not be converted to the target type, e.g.
+    /// to an `http::HeaderValue`.
+    Conversion,
+    /// A value exceeding a configured parser limit, see
+    /// `header::parsing::ParseConfig::limit`.
+    TooLarge,
+    /// A message signature which failed verification, see the `signature`
+    /// module.
+    Signature,
+}
+
+// Maximum length of the offending value retained in a `HeaderError`.
+const MAX_VALUE_LEN: usize = 64;
+
+impl HeaderError {
+    /// Construct a new error of the given kind, without further context.
+    pub fn new(kind: HeaderErrorKind) -> HeaderError {
+        HeaderError {
+            kind,
+            name: None,
+            value: None,
+            truncated: false,
+            source: None,
+        }
+    }
+
+    /// The kind of failure.
+    pub fn kind(&self) -> HeaderErrorKind {
+        self.kind
+    }
+
+    /// The name of the header which failed to parse, if known.
+    pub fn name(&self) -> Option<&'static str> {
+        self.name
+    }
+
+    /// The offending raw value, if known. This is truncated to the first 64
+    /// bytes of long values.
+    pub fn value(&self) -> Option<&[u8]> {
+        self.value.as_ref().map(|v| &v[..])
+    }
+}
+
+impl StdError for HeaderError {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        match self.source {
+            Some(ref e) => Some(&**e),
+            None => None,
+        }
+    }
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("invalid ")?;
+        f.write_str(self.name.unwrap_or("header"))?;
+        write!(f, " ({})", self.kind.description())?;
+        if let Some(ref value) = self.value {
+            f.write_str(": \"")?;
+            for &b in value.iter() {
+                if b == b'"' || b == b'\\' {
+                    write!(f, "\\{}", b as char)?;
+                } else if (0x20..0x7f).contains(&b) {
+                    write!(f, "{}", b as char)?;
+                } else {
+                    write!(f, "\\x{:02x}", b)?;
+                }
+            }
+            f.write_str(if self.truncated { "\"..." } else { "\"" })?;
+        }
+        Ok(())
+    }
+}
+
+impl HeaderErrorKind {
+    fn description(self) -> &'static str {
+        match self {
+            HeaderErrorKind::Empty => "empty value",
+            HeaderErrorKind::Multiple => "multiple values",
+            HeaderErrorKind::Utf8 => "invalid UTF-8",
+            HeaderErrorKind::Token => "invalid token",
+            HeaderErrorKind::Number => "invalid number",
+            HeaderErrorKind::Date => "invalid date",
+            HeaderErrorKind::Syntax => "invalid syntax",
+            HeaderErrorKind::Conversion => "not convertible",
+            HeaderErrorKind::TooLarge => "limit exceeded",
+            HeaderErrorKind::Signature => "signature mismatch",
+        }
+    }
+}
+
 #[doc(hidden)]
 pub struct Void(());
 
@@ -42,6 +179,7 @@ impl fmt::Debug for Void {
 impl fmt::Display for Error {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
+            Header(ref e) => fmt::Display::fmt(e, f),
             Utf8(ref e) => fmt::Display::fmt(e, f),
             ref e => f.write_str(e.static_description()),
         }
@@ -49,11 +187,83 @@ impl fmt::Display for Error {
 }
 
 impl Error {
+    /// Construct a new `Error::Header` of the given kind.
+    pub fn header(kind: HeaderErrorKind) -> Error {
+        Header(HeaderError::new(kind))
+    }
+
+    /// Construct a new `Error::Header` of the given kind, caused by the given
+    /// error, which is returned by `Error::source`.
+    pub fn header_with_source<E>(kind: HeaderErrorKind, source: E) -> Error
+    where
+        E: StdError + Send + Sync + 'static,
+    {
+        let mut e = HeaderError::new(kind);
+        e.source = Some(Arc::new(source));
+        Header(e)
+    }
+
+    /// Return the broad category of this error.
+    pub fn kind(&self) -> ErrorKind {
+        match *self {
+            Header(ref e) if e.kind == HeaderErrorKind::Conversion => ErrorKind::Conversion,
+            Header(ref e) if e.kind == HeaderErrorKind::TooLarge => ErrorKind::TooLarge,
+            TooLarge => ErrorKind::TooLarge,
+            _ => ErrorKind::Invalid,
+        }
+    }
+
+    /// Return the header error details, if this is an `Error::Header`.
+    pub fn header_error(&self) -> Option<&HeaderError> {
+        match *self {
+            Header(ref e) => Some(e),
+            _ => None,
+        }
+    }
+
+    /// Record the name of the header which failed to parse, if not already
+    /// recorded.
+    ///
+    /// A `Utf8` error is converted to an `Error::Header` of kind
+    /// `HeaderErrorKind::Utf8`.
+    pub fn with_header_name(self, name: &'static str) -> Error {
+        match self {
+            Header(mut e) => {
+                if e.name.is_none() {
+                    e.name = Some(name);
+                }
+                Header(e)
+            }
+            Utf8(e) => Error::header_with_source(HeaderErrorKind::Utf8, e).with_header_name(name),
+            e => e,
+        }
+    }
+
+    /// Record the offending raw value of the header which failed to parse,
+    /// if not already recorded. Long values are truncated.
+    ///
+    /// A `Utf8` error is converted to an `Error::Header` of kind
+    /// `HeaderErrorKind::Utf8`.
+    pub fn with_header_value(self, value: &[u8]) -> Error {
+        match self {
+            Header(mut e) => {
+                if e.value.is_none() {
+                    let len = value.len().min(MAX_VALUE_LEN);
+                    e.value = Some(value[..len].into());
+                    e.truncated = len < value.len();
+                }
+                Header(e)
+            }
+            Utf8(e) => Error::header_with_source(HeaderErrorKind::Utf8, e).with_header_value(value),
+            e => e,
+        }
+    }
+
     fn static_description(&self) -> &str {
         match *self {
             Method => "invalid Method specified",
             Version => "invalid HTTP version specified",
-            Header => "invalid Header provided",
+            Header(_) => "invalid Header provided",
             TooLarge => "message head is too large",
             Status => "invalid Status provided",
             Utf8(_) => "invalid UTF-8 string",
@@ -67,8 +277,9 @@ impl StdError for Error {
         self.static_description()
     }
 
-    fn cause(&self) -> Option<&dyn StdError> {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
         match *self {
+            Header(ref error) => error.source(),
             Utf8(ref error) => Some(error),
             Error::__Nonexhaustive(..) => unreachable!(),
             _ => None,
@@ -76,6 +287,12 @@ impl StdError for Error {
     }
 }
 
+impl From<HeaderErrorKind> for Error {
+    fn from(kind: HeaderErrorKind) -> Error {
+        Error::header(kind)
+    }
+}
+
 impl From<Utf8Error> for Error {
     fn from(err: Utf8Error) -> Error {
         Utf8(err)
@@ -89,6 +306,76 @@ impl From<FromUtf8Error> for Error {
 }
 
 #[doc(hidden)]
+#[allow(dead_code)]
 trait AssertSendSync: Send + Sync + 'static {}
 #[doc(hidden)]
 impl AssertSendSync for Error {}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, ErrorKind, HeaderErrorKind};
+
+    #[test]
+    fn test_header_context() {
+        let e = Error::header(HeaderErrorKind::Number)
+            .with_header_value(b"12a")
+            .with_header_name("Content-Length")
+            .with_header_name("Other");
+        let he = e.header_error().unwrap();
+        assert_eq!(he.kind(), HeaderErrorKind::Number);
+        assert_eq!(he.name(), Some("Content-Length"));
+        assert_eq!(he.value(), Some(&b"12a"[..]));
+        assert_eq!(
+            e.to_string(),
+            "invalid Content-Length (invalid number): \"12a\""
+        );
+    }
+
+    #[test]
+    fn test_header_value_truncated() {
+        let long = vec![b'a'; 100];
+        let e = Error::header(HeaderErrorKind::Token).with_header_value(&long);
+        assert_eq!(e.header_error().unwrap().value().unwrap().len(), 64);
+        assert!(e.to_string().ends_with("aaa\"..."));
+        assert_eq!(
+            Error::header(HeaderErrorKind::Syntax)
+                .with_header_value(b"\xFF\"")
+                .to_string(),
+            "invalid header (invalid syntax): \"\\xff\\\"\""
+        );
+    }
+
+    #[test]
+    fn test_source() {
+        use std::error::Error as StdError;
+
+        let bad = vec![b'a', 0xFF];
+        let utf8 = ::std::str::from_utf8(&bad).unwrap_err();
+        let e = Error::header_with_source(HeaderErrorKind::Utf8, utf8);
+        assert_eq!(e.source().unwrap().to_string(), utf8.to_string());
+        assert_eq!(e.kind(), ErrorKind::Invalid);
+        assert!(Error::header(HeaderErrorKind::Token).source().is_none());
+        assert!(Error::from(utf8)
+            .with_header_name("Host")
+            .source()
+            .is_some());
+
+        assert_eq!(
+            Error::header(HeaderErrorKind::Conversion).kind(),
+            ErrorKind::Conversion
+        );
+        assert_eq!(Error::TooLarge.kind(), ErrorKind::TooLarge);
+        assert_eq!(
+            Error::header(HeaderErrorKind::TooLarge).kind(),
+            ErrorKind::TooLarge
+        );
+    }
+
+    #[test]
+    fn test_utf8_converted() {
+        let bad = vec![b'a', 0xFF];
+        let e = Error::from(::std::str::from_utf8(&bad).unwrap_err());
+        let e = e.with_header_name("Host");
+        assert_eq!(e.header_error().unwrap().kind(), HeaderErrorKind::Utf8);
+    }
+}
@@ -0,0 +1,188 @@
+//! Generation of entity-tags, for the `ETag` header.
+//!
+//! Strong entity-tags are derived from a hash of the representation
+//! content, via a pluggable [`ContentHasher`](trait.ContentHasher.html);
+//! weak entity-tags from the content length and modification time, e.g. of
+//! a file, without reading the content. Derived tags for variants of a
+//! representation (e.g. per content-coding) and tags of composite
+//! representations are built from existing tags with
+//! [`with_suffix`](fn.with_suffix.html) and [`combine`](fn.combine.html).
+//!
+//! The generated tags pair with the
+//! [`conditional`](../conditional/index.html) module, as the `etag` of the
+//! `Validators` of a representation.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, UNIX_EPOCH};
+//!
+//! use hyperx::etag::{self, Fnv1a};
+//! use hyperx::header::{ETag, TypedHeaders};
+//!
+//! let tag = etag::strong(&Fnv1a, b"hello world");
+//! assert_eq!(tag.to_string(), "\"d5pl5wI80uc\"");
+//! assert_eq!(etag::with_suffix(&tag, "gzip").to_string(), "\"d5pl5wI80uc-gzip\"");
+//!
+//! let modified = UNIX_EPOCH + Duration::from_secs(1_600_000_000);
+//! let tag = etag::weak(11, modified);
+//! assert_eq!(tag.to_string(), "W/\"b-5f5e1000.0\"");
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.encode(&ETag(tag));
+//! ```
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+
+use header::EntityTag;
+
+/// A hash function for strong entity-tags.
+///
+/// The hash should be stable across processes and releases, so that tags
+/// remain valid for clients and caches; it need not be cryptographic. Any
+/// `Fn(&[u8]) -> Vec<u8>` closure is a `ContentHasher`, e.g. for a SHA-256
+/// implementation of another crate.
+pub trait ContentHasher {
+    /// Returns the hash of the content.
+    fn hash(&self, content: &[u8]) -> Vec<u8>;
+}
+
+impl<F> ContentHasher for F
+where
+    F: Fn(&[u8]) -> Vec<u8>,
+{
+    fn hash(&self, content: &[u8]) -> Vec<u8> {
+        self(content)
+    }
+}
+
+/// The 64-bit FNV-1a hash function, a fast, stable and non-cryptographic
+/// `ContentHasher`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Fnv1a;
+
+impl ContentHasher for Fnv1a {
+    fn hash(&self, content: &[u8]) -> Vec<u8> {
+        let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+        for &b in content {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+        hash.to_be_bytes().to_vec()
+    }
+}
+
+/// A strong entity-tag of the URL-safe base64 encoded hash of the content.
+pub fn strong<H: ContentHasher>(hasher: &H, content: &[u8]) -> EntityTag {
+    EntityTag::strong(URL_SAFE_NO_PAD.encode(hasher.hash(content)))
+}
+
+/// A weak entity-tag of the content length and modification time, as
+/// `<length>-<seconds>.<nanoseconds>` since the epoch, in hex.
+pub fn weak(len: u64, modified: SystemTime) -> EntityTag {
+    let since = modified.duration_since(UNIX_EPOCH).unwrap_or_default();
+    EntityTag::weak(format!(
+        "{:x}-{:x}.{:x}",
+        len,
+        since.as_secs(),
+        since.subsec_nanos()
+    ))
+}
+
+/// An entity-tag for a variant of a representation, such as a content
+/// coding, as `<tag>-<suffix>` with the same weakness. Distinct variants
+/// must have distinct strong entity-tags.
+///
+/// # Panics
+///
+/// If the suffix contains characters invalid in an entity-tag.
+pub fn with_suffix(tag: &EntityTag, suffix: &str) -> EntityTag {
+    EntityTag::new(tag.weak, format!("{}-{}", tag.tag(), suffix))
+}
+
+/// An entity-tag for a representation composed of parts, from the hash of
+/// the tags of the parts in order. The tag is weak if any part is weak.
+pub fn combine<'a, H, I>(hasher: &H, tags: I) -> EntityTag
+where
+    H: ContentHasher,
+    I: IntoIterator<Item = &'a EntityTag>,
+{
+    let mut weak = false;
+    let mut content = String::new();
+    for tag in tags {
+        weak |= tag.weak;
+        content.push_str(&tag.to_string());
+        content.push(',');
+    }
+    EntityTag::new(
+        weak,
+        URL_SAFE_NO_PAD.encode(hasher.hash(content.as_bytes())),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn hex(bytes: Vec<u8>) -> String {
+        bytes.iter().map(|b| format!("{:02x}", b)).collect()
+    }
+
+    #[test]
+    fn test_fnv1a() {
+        assert_eq!(hex(Fnv1a.hash(b"")), "cbf29ce484222325");
+        assert_eq!(hex(Fnv1a.hash(b"a")), "af63dc4c8601ec8c");
+        assert_eq!(hex(Fnv1a.hash(b"foobar")), "85944171f73967e8");
+    }
+
+    #[test]
+    fn test_strong() {
+        let a = strong(&Fnv1a, b"content");
+        assert!(!a.weak);
+        assert_eq!(a, strong(&Fnv1a, b"content"));
+        assert_ne!(a, strong(&Fnv1a, b"Content"));
+
+        let reverse = |c: &[u8]| c.iter().rev().cloned().collect::<Vec<u8>>();
+        assert_eq!(strong(&reverse, b"abc").tag(), "Y2Jh");
+    }
+
+    #[test]
+    fn test_weak() {
+        let modified = UNIX_EPOCH + Duration::new(1_600_000_000, 500);
+        let tag = weak(1024, modified);
+        assert!(tag.weak);
+        assert_eq!(tag.tag(), "400-5f5e1000.1f4");
+        assert_ne!(tag, weak(1025, modified));
+        assert_eq!(weak(0, UNIX_EPOCH - Duration::from_secs(1)).tag(), "0-0.0");
+    }
+
+    #[test]
+    fn test_suffix_and_combine() {
+        let strong_tag = EntityTag::strong("abc".to_owned());
+        let weak_tag = EntityTag::weak("def".to_owned());
+        assert_eq!(
+            with_suffix(&weak_tag, "br"),
+            EntityTag::weak("def-br".to_owned())
+        );
+
+        let both = combine(&Fnv1a, &[strong_tag.clone(), weak_tag.clone()]);
+        assert!(both.weak);
+        assert_ne!(
+            both.tag(),
+            combine(&Fnv1a, &[weak_tag, strong_tag.clone()]).tag()
+        );
+        let one = combine(&Fnv1a, Some(&strong_tag));
+        assert!(!one.weak);
+        assert_eq!(one, combine(&Fnv1a, vec![&strong_tag]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_suffix() {
+        with_suffix(&EntityTag::strong("abc".to_owned()), "\"");
+    }
+}
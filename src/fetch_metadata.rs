@@ -0,0 +1,288 @@
+//! Fetch metadata request classification, per [Fetch Metadata Request
+//! Headers].
+//!
+//! Browsers describe the context of each request with the `Sec-Fetch-Site`,
+//! `Sec-Fetch-Mode` and `Sec-Fetch-Dest` headers. An
+//! [`IsolationPolicy`](struct.IsolationPolicy.html) implements the common
+//! resource isolation policy on top of them: allow same-origin and
+//! same-site requests, requests initiated by the user, and simple
+//! cross-site navigations, but reject cross-site subresource requests, such
+//! as those of cross-site request forgery (CSRF), cross-site script
+//! inclusion (XSSI) or timing attacks. Requests of clients which do not
+//! send fetch metadata are allowed.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use http::{HeaderMap, Method};
+//! use hyperx::fetch_metadata::{IsolationPolicy, Reason};
+//!
+//! let policy = IsolationPolicy::new();
+//!
+//! let mut request = HeaderMap::new();
+//! request.insert("sec-fetch-site", "cross-site".parse().unwrap());
+//! request.insert("sec-fetch-mode", "no-cors".parse().unwrap());
+//! request.insert("sec-fetch-dest", "script".parse().unwrap());
+//! let decision = policy.evaluate(&Method::GET, &request);
+//! assert!(!decision.is_allowed());
+//! assert_eq!(decision.reason(), Reason::CrossSite);
+//!
+//! request.insert("sec-fetch-mode", "navigate".parse().unwrap());
+//! request.insert("sec-fetch-dest", "document".parse().unwrap());
+//! assert!(policy.evaluate(&Method::GET, &request).is_allowed());
+//! assert!(!policy.evaluate(&Method::POST, &request).is_allowed());
+//! ```
+//!
+//! [Fetch Metadata Request Headers]: https://w3c.github.io/webappsec-fetch-metadata/
+
+use std::fmt;
+
+use http::HeaderMap;
+
+use header::{SecFetchDest, SecFetchMode, SecFetchSite, TypedHeaders};
+
+/// Why a request is allowed or denied by an
+/// [`IsolationPolicy`](struct.IsolationPolicy.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Reason {
+    /// `Sec-Fetch-Site` is absent or unrecognized, as sent by clients which
+    /// do not support fetch metadata.
+    NoMetadata,
+    /// The request is same-origin.
+    SameOrigin,
+    /// The request is same-site, from another origin.
+    SameSite,
+    /// The request was initiated by the user, such as by typing a URL or
+    /// following a bookmark.
+    UserInitiated,
+    /// A cross-site `GET` navigation, other than into an `object` or
+    /// `embed` element.
+    Navigation,
+    /// A cross-site navigation with another method, such as a form `POST`,
+    /// or into an `object` or `embed` element.
+    UnsafeNavigation,
+    /// A cross-site subresource request.
+    CrossSite,
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            Reason::NoMetadata => "no fetch metadata",
+            Reason::SameOrigin => "same-origin request",
+            Reason::SameSite => "same-site request",
+            Reason::UserInitiated => "user initiated request",
+            Reason::Navigation => "cross-site navigation",
+            Reason::UnsafeNavigation => "unsafe cross-site navigation",
+            Reason::CrossSite => "cross-site subresource request",
+        })
+    }
+}
+
+/// The decision of an [`IsolationPolicy`](struct.IsolationPolicy.html) on
+/// a request.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Decision {
+    /// The request may be processed.
+    Allow(Reason),
+    /// The request should be rejected, e.g. with `403 Forbidden`.
+    Deny(Reason),
+}
+
+impl Decision {
+    /// Returns true if the request is allowed.
+    pub fn is_allowed(&self) -> bool {
+        matches!(*self, Decision::Allow(_))
+    }
+
+    /// The reason for the decision.
+    pub fn reason(&self) -> Reason {
+        match *self {
+            Decision::Allow(reason) | Decision::Deny(reason) => reason,
+        }
+    }
+}
+
+impl fmt::Display for Decision {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Decision::Allow(reason) => write!(f, "allowed: {}", reason),
+            Decision::Deny(reason) => write!(f, "denied: {}", reason),
+        }
+    }
+}
+
+/// A resource isolation policy, constructed via builder methods.
+///
+/// By default, same-site requests are allowed.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IsolationPolicy {
+    same_site: bool,
+}
+
+impl Default for IsolationPolicy {
+    fn default() -> IsolationPolicy {
+        IsolationPolicy { same_site: true }
+    }
+}
+
+impl IsolationPolicy {
+    /// Create the default policy.
+    pub fn new() -> IsolationPolicy {
+        IsolationPolicy::default()
+    }
+
+    /// Whether to allow same-site requests from other origins, as opposed
+    /// to same-origin requests only.
+    pub fn allow_same_site(mut self, allow: bool) -> IsolationPolicy {
+        self.same_site = allow;
+        self
+    }
+
+    /// Evaluate the policy against the method and headers of a request.
+    pub fn evaluate(&self, method: &::http::Method, headers: &HeaderMap) -> Decision {
+        let site = match headers.try_decode::<SecFetchSite>().and_then(Result::ok) {
+            Some(site) => site,
+            None => return Decision::Allow(Reason::NoMetadata),
+        };
+        match site {
+            SecFetchSite::SameOrigin => return Decision::Allow(Reason::SameOrigin),
+            SecFetchSite::SameSite if self.same_site => return Decision::Allow(Reason::SameSite),
+            SecFetchSite::None => return Decision::Allow(Reason::UserInitiated),
+            SecFetchSite::SameSite | SecFetchSite::CrossSite => {}
+        }
+        if headers.try_decode::<SecFetchMode>().and_then(Result::ok) != Some(SecFetchMode::Navigate)
+        {
+            return Decision::Deny(Reason::CrossSite);
+        }
+        let dest = headers.try_decode::<SecFetchDest>().and_then(Result::ok);
+        if *method == ::http::Method::GET
+            && dest != Some(SecFetchDest::Object)
+            && dest != Some(SecFetchDest::Embed)
+        {
+            Decision::Allow(Reason::Navigation)
+        } else {
+            Decision::Deny(Reason::UnsafeNavigation)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use http::Method;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn evaluate(method: Method, site: &str, mode: &str, dest: &str) -> Decision {
+        IsolationPolicy::new().evaluate(
+            &method,
+            &request(&[
+                ("sec-fetch-site", site),
+                ("sec-fetch-mode", mode),
+                ("sec-fetch-dest", dest),
+            ]),
+        )
+    }
+
+    #[test]
+    fn test_no_metadata() {
+        let policy = IsolationPolicy::new();
+        assert_eq!(
+            policy.evaluate(&Method::POST, &HeaderMap::new()),
+            Decision::Allow(Reason::NoMetadata)
+        );
+        let headers = request(&[("sec-fetch-site", "same-party"), ("sec-fetch-mode", "cors")]);
+        assert_eq!(
+            policy.evaluate(&Method::POST, &headers),
+            Decision::Allow(Reason::NoMetadata)
+        );
+    }
+
+    #[test]
+    fn test_allowed_sites() {
+        assert_eq!(
+            evaluate(Method::POST, "same-origin", "cors", "empty"),
+            Decision::Allow(Reason::SameOrigin)
+        );
+        assert_eq!(
+            evaluate(Method::POST, "same-site", "no-cors", "image"),
+            Decision::Allow(Reason::SameSite)
+        );
+        assert_eq!(
+            evaluate(Method::GET, "none", "navigate", "document"),
+            Decision::Allow(Reason::UserInitiated)
+        );
+    }
+
+    #[test]
+    fn test_same_origin_only() {
+        let policy = IsolationPolicy::new().allow_same_site(false);
+        let headers = request(&[("sec-fetch-site", "same-site"), ("sec-fetch-mode", "cors")]);
+        assert_eq!(
+            policy.evaluate(&Method::GET, &headers),
+            Decision::Deny(Reason::CrossSite)
+        );
+        let headers = request(&[
+            ("sec-fetch-site", "same-site"),
+            ("sec-fetch-mode", "navigate"),
+        ]);
+        assert_eq!(
+            policy.evaluate(&Method::GET, &headers),
+            Decision::Allow(Reason::Navigation)
+        );
+    }
+
+    #[test]
+    fn test_cross_site() {
+        assert_eq!(
+            evaluate(Method::GET, "cross-site", "no-cors", "script"),
+            Decision::Deny(Reason::CrossSite)
+        );
+        assert_eq!(
+            evaluate(Method::POST, "cross-site", "cors", "empty"),
+            Decision::Deny(Reason::CrossSite)
+        );
+        assert_eq!(
+            evaluate(Method::GET, "cross-site", "navigate", "iframe"),
+            Decision::Allow(Reason::Navigation)
+        );
+        assert_eq!(
+            evaluate(Method::POST, "cross-site", "navigate", "document"),
+            Decision::Deny(Reason::UnsafeNavigation)
+        );
+        assert_eq!(
+            evaluate(Method::GET, "cross-site", "navigate", "embed"),
+            Decision::Deny(Reason::UnsafeNavigation)
+        );
+        assert_eq!(
+            evaluate(Method::GET, "cross-site", "navigate", "object"),
+            Decision::Deny(Reason::UnsafeNavigation)
+        );
+        // A missing mode is not a navigation
+        let headers = request(&[("sec-fetch-site", "cross-site")]);
+        assert_eq!(
+            IsolationPolicy::new().evaluate(&Method::GET, &headers),
+            Decision::Deny(Reason::CrossSite)
+        );
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            evaluate(Method::GET, "cross-site", "no-cors", "image").to_string(),
+            "denied: cross-site subresource request"
+        );
+        assert_eq!(
+            Decision::Allow(Reason::SameOrigin).to_string(),
+            "allowed: same-origin request"
+        );
+    }
+}
@@ -0,0 +1,733 @@
+//! Forwarding chains of proxied requests, per [RFC 7239].
+//!
+//! The path of a request through proxies is recorded either in the
+//! standard `Forwarded` header, or in the de facto `X-Forwarded-For`,
+//! `X-Forwarded-Proto` and `X-Forwarded-Host` headers. A
+//! [`Chain`](struct.Chain.html) is the normalized form of either, as a list
+//! of [`Hop`](struct.Hop.html)s from the client to the closest proxy. It is
+//! parsed from the headers of a request, extended by a proxy with its own
+//! hop, and encoded in either or both representations when forwarding.
+//!
+//! The headers are set by clients and proxies alike, so only the hops added
+//! by trusted proxies are reliable. [`Chain::client`](struct.Chain.html#method.client)
+//! selects the effective client address under a
+//! [`TrustedProxies`](enum.TrustedProxies.html) policy, given the address of
+//! the immediate peer.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::forwarded::{Chain, Hop, Node, Representation, TrustedProxies};
+//!
+//! # fn run() -> hyperx::Result<()> {
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("x-forwarded-for", "203.0.113.7, 10.0.0.2".parse().unwrap());
+//! headers.insert("x-forwarded-proto", "https".parse().unwrap());
+//!
+//! let mut chain = Chain::from_headers(&headers)?;
+//! let peer = "10.0.0.3".parse().unwrap();
+//! let trusted = TrustedProxies::Cidrs(vec!["10.0.0.0/8".parse()?]);
+//! assert_eq!(chain.client(peer, &trusted), Node::Ip("203.0.113.7".parse().unwrap()));
+//!
+//! // Forward with the standard header only
+//! chain.push(Hop::new(Node::Ip(peer)).with_proto("http"));
+//! chain.encode(&mut headers, Representation::Forwarded);
+//! assert_eq!(
+//!     headers["forwarded"],
+//!     "for=203.0.113.7;proto=https, for=10.0.0.2, for=10.0.0.3;proto=http"
+//! );
+//! assert!(!headers.contains_key("x-forwarded-for"));
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+//!
+//! If a request has both representations, as when passing through proxies
+//! of different generations, the `Forwarded` header takes precedence, as
+//! the order of the hops across headers can not be determined.
+//!
+//! [RFC 7239]: https://www.rfc-editor.org/rfc/rfc7239
+
+use std::fmt;
+use std::net::{IpAddr, SocketAddr};
+use std::str::FromStr;
+
+use http::header::{self as h, HeaderMap, HeaderName, HeaderValue};
+
+use header::parsing;
+use {Error, HeaderErrorKind, Result};
+
+static X_FORWARDED_FOR: &str = "x-forwarded-for";
+static X_FORWARDED_PROTO: &str = "x-forwarded-proto";
+static X_FORWARDED_HOST: &str = "x-forwarded-host";
+
+/// A node of a forwarding chain: a client or proxy.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum Node {
+    /// An IP address, without a port.
+    Ip(IpAddr),
+    /// An IP address and port.
+    Socket(SocketAddr),
+    /// The `unknown` node, e.g. of a hop without a `for` parameter.
+    Unknown,
+    /// An obfuscated identifier, such as `_hidden`.
+    Obfuscated(String),
+}
+
+impl Node {
+    /// The IP address of the node, if known.
+    pub fn ip(&self) -> Option<IpAddr> {
+        match *self {
+            Node::Ip(ip) => Some(ip),
+            Node::Socket(addr) => Some(addr.ip()),
+            _ => None,
+        }
+    }
+
+    // The node as an `X-Forwarded-For` item, without brackets around a
+    // bare IPv6 address.
+    fn fmt_x_forwarded(&self) -> String {
+        match *self {
+            Node::Ip(ip) => ip.to_string(),
+            _ => self.to_string(),
+        }
+    }
+}
+
+/// Formats the node as in the `Forwarded` header, without quotes, e.g.
+/// `[2001:db8::1]:4711`.
+impl fmt::Display for Node {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Node::Ip(IpAddr::V4(ip)) => fmt::Display::fmt(&ip, f),
+            Node::Ip(IpAddr::V6(ip)) => write!(f, "[{}]", ip),
+            Node::Socket(addr) => fmt::Display::fmt(&addr, f),
+            Node::Unknown => f.write_str("unknown"),
+            Node::Obfuscated(ref s) => f.write_str(s),
+        }
+    }
+}
+
+/// Parses a node as in the `Forwarded` or `X-Forwarded-For` headers, where
+/// IPv6 addresses without a port need not be bracketed. An obfuscated port,
+/// such as in `192.0.2.43:_hidden`, is dropped.
+impl FromStr for Node {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Node> {
+        if s.eq_ignore_ascii_case("unknown") {
+            return Ok(Node::Unknown);
+        }
+        if s.starts_with('_') && is_obfuscated(s) {
+            return Ok(Node::Obfuscated(s.to_owned()));
+        }
+        if let Ok(ip) = s.parse() {
+            return Ok(Node::Ip(ip));
+        }
+        if let Ok(addr) = s.parse() {
+            return Ok(Node::Socket(addr));
+        }
+        let (host, port) = match s.rfind(':') {
+            Some(i) if s.starts_with('[') == s[..i].ends_with(']') => (&s[..i], &s[i + 1..]),
+            _ => (s, ""),
+        };
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        match host.parse() {
+            Ok(ip) if port.starts_with('_') && is_obfuscated(port) => Ok(Node::Ip(ip)),
+            Ok(ip) if port.is_empty() && s.starts_with('[') => Ok(Node::Ip(ip)),
+            _ => Err(Error::header(HeaderErrorKind::Syntax)),
+        }
+    }
+}
+
+fn is_obfuscated(s: &str) -> bool {
+    s.bytes()
+        .all(|b| b.is_ascii_alphanumeric() || b == b'.' || b == b'_' || b == b'-')
+}
+
+/// A hop of a forwarding chain, as added by a proxy: the node which made
+/// the request to the proxy, and the request as received by the proxy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Hop {
+    /// The node making the request to the proxy (the `for` parameter).
+    pub for_: Option<Node>,
+    /// The interface of the proxy receiving the request (`by`).
+    pub by: Option<Node>,
+    /// The `Host` of the request received by the proxy (`host`).
+    pub host: Option<String>,
+    /// The scheme of the request received by the proxy, such as `https`
+    /// (`proto`).
+    pub proto: Option<String>,
+}
+
+impl Hop {
+    /// Create a hop of a request made by the given node.
+    pub fn new(for_: Node) -> Hop {
+        Hop {
+            for_: Some(for_),
+            ..Hop::default()
+        }
+    }
+
+    /// Set the interface of the proxy receiving the request.
+    pub fn with_by(mut self, by: Node) -> Hop {
+        self.by = Some(by);
+        self
+    }
+
+    /// Set the `Host` of the request received by the proxy.
+    pub fn with_host<S: Into<String>>(mut self, host: S) -> Hop {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set the scheme of the request received by the proxy.
+    pub fn with_proto<S: Into<String>>(mut self, proto: S) -> Hop {
+        self.proto = Some(proto.into());
+        self
+    }
+}
+
+/// Formats the hop as an element of the `Forwarded` header, such as
+/// `for="[2001:db8::1]";proto=https`.
+impl fmt::Display for Hop {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut sep = "";
+        let nodes = [("for", &self.for_), ("by", &self.by)];
+        for &(name, node) in &nodes {
+            if let Some(ref node) = *node {
+                write!(f, "{}{}=", sep, name)?;
+                fmt_value(f, &node.to_string())?;
+                sep = ";";
+            }
+        }
+        let values = [("host", &self.host), ("proto", &self.proto)];
+        for &(name, value) in &values {
+            if let Some(ref value) = *value {
+                write!(f, "{}{}=", sep, name)?;
+                fmt_value(f, value)?;
+                sep = ";";
+            }
+        }
+        Ok(())
+    }
+}
+
+fn fmt_value(f: &mut fmt::Formatter, value: &str) -> fmt::Result {
+    if parsing::is_token(value) {
+        f.write_str(value)
+    } else {
+        parsing::fmt_quoted(f, value)
+    }
+}
+
+/// Parses an element of the `Forwarded` header. Unknown parameters are
+/// ignored.
+impl FromStr for Hop {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Hop> {
+        let mut hop = Hop::default();
+        for pair in parsing::split_delimited(s, ';') {
+            let (name, value) = parsing::parse_parameter(pair)?;
+            let value = value.ok_or_else(|| Error::header(HeaderErrorKind::Syntax))?;
+            if name.eq_ignore_ascii_case("for") {
+                hop.for_ = Some(value.parse()?);
+            } else if name.eq_ignore_ascii_case("by") {
+                hop.by = Some(value.parse()?);
+            } else if name.eq_ignore_ascii_case("host") {
+                hop.host = Some(value.into_owned());
+            } else if name.eq_ignore_ascii_case("proto") {
+                hop.proto = Some(value.to_ascii_lowercase());
+            }
+        }
+        Ok(hop)
+    }
+}
+
+/// The representation of a forwarding chain in headers.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Representation {
+    /// The standard `Forwarded` header.
+    Forwarded,
+    /// The `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host`
+    /// headers. Only the `proto` and `host` of the first hop, from the
+    /// client, are represented, and `by` not at all.
+    XForwarded,
+    /// Both of the above.
+    Both,
+}
+
+/// The policy for trusting the hops of a forwarding chain, to select the
+/// effective client address.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum TrustedProxies {
+    /// The given number of proxies closest to the server, including the
+    /// immediate peer, are trusted. `Count(0)` trusts no hops at all.
+    Count(usize),
+    /// Proxies with an address in any of the given ranges are trusted.
+    Cidrs(Vec<Cidr>),
+}
+
+impl TrustedProxies {
+    fn is_trusted(&self, ip: IpAddr) -> bool {
+        match *self {
+            TrustedProxies::Count(n) => n > 0,
+            TrustedProxies::Cidrs(ref cidrs) => cidrs.iter().any(|c| c.contains(ip)),
+        }
+    }
+}
+
+/// A range of IP addresses, such as `10.0.0.0/8` or `2001:db8::/32`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Cidr {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl Cidr {
+    /// Create a range from an address and a prefix length, returning `None`
+    /// if the prefix is longer than the address.
+    pub fn new(addr: IpAddr, prefix: u8) -> Option<Cidr> {
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        if prefix > max {
+            None
+        } else {
+            Some(Cidr { addr, prefix })
+        }
+    }
+
+    /// Returns true if the address is in the range. IPv4-mapped IPv6
+    /// addresses match the IPv4 ranges.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Cidr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+/// Parses a range such as `10.0.0.0/8`, or a single address.
+impl FromStr for Cidr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Cidr> {
+        let (addr, prefix) = match s.split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s, None),
+        };
+        let addr: IpAddr = addr
+            .parse()
+            .map_err(|e| Error::header_with_source(HeaderErrorKind::Syntax, e))?;
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse()
+                .map_err(|e| Error::header_with_source(HeaderErrorKind::Number, e))?,
+            None if addr.is_ipv4() => 32,
+            None => 128,
+        };
+        Cidr::new(addr, prefix).ok_or_else(|| Error::header(HeaderErrorKind::Number))
+    }
+}
+
+/// A forwarding chain, as a list of hops from the client to the closest
+/// proxy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Chain {
+    hops: Vec<Hop>,
+}
+
+impl Chain {
+    /// Create a new, empty chain.
+    pub fn new() -> Chain {
+        Chain::default()
+    }
+
+    /// Parse the chain of a request from the `Forwarded` header, or if
+    /// absent, the `X-Forwarded-*` headers.
+    ///
+    /// A `X-Forwarded-Proto` or `X-Forwarded-Host` list with an item per
+    /// `X-Forwarded-For` item applies to the respective hops; otherwise the
+    /// first item applies to the first hop, from the client.
+    pub fn from_headers(headers: &HeaderMap) -> Result<Chain> {
+        if headers.contains_key(h::FORWARDED) {
+            let mut hops = Vec::new();
+            for value in items(headers, h::FORWARDED.as_str())? {
+                hops.push(value.parse()?);
+            }
+            return Ok(Chain { hops });
+        }
+
+        let mut hops = Vec::new();
+        for value in items(headers, X_FORWARDED_FOR)? {
+            hops.push(Hop::new(value.parse()?));
+        }
+        let protos = items(headers, X_FORWARDED_PROTO)?;
+        let hosts = items(headers, X_FORWARDED_HOST)?;
+        if hops.is_empty() && (!protos.is_empty() || !hosts.is_empty()) {
+            hops.push(Hop::default());
+        }
+        for (i, proto) in spread(&protos, hops.len()) {
+            hops[i].proto = Some(proto.to_ascii_lowercase());
+        }
+        for (i, host) in spread(&hosts, hops.len()) {
+            hops[i].host = Some(host.to_owned());
+        }
+        Ok(Chain { hops })
+    }
+
+    /// Append a hop, e.g. for the request received by this proxy.
+    pub fn push(&mut self, hop: Hop) {
+        self.hops.push(hop);
+    }
+
+    /// The hops, from the client to the closest proxy.
+    pub fn hops(&self) -> &[Hop] {
+        &self.hops
+    }
+
+    /// Returns true if the chain has no hops.
+    pub fn is_empty(&self) -> bool {
+        self.hops.is_empty()
+    }
+
+    /// Select the effective client of a request received from the given
+    /// peer.
+    ///
+    /// The hops are walked from the closest proxy towards the client for
+    /// as long as they were added by trusted proxies, and the first
+    /// untrusted node is returned. If the peer itself is not trusted, it is
+    /// the client. If all nodes are trusted, the first node of the chain is
+    /// returned. A hop without a `for` node yields `Node::Unknown`.
+    pub fn client(&self, peer: IpAddr, trusted: &TrustedProxies) -> Node {
+        if !trusted.is_trusted(peer) {
+            return Node::Ip(peer);
+        }
+        let mut client = Node::Ip(peer);
+        for (i, hop) in self.hops.iter().rev().enumerate() {
+            client = hop.for_.clone().unwrap_or(Node::Unknown);
+            let next_trusted = match *trusted {
+                TrustedProxies::Count(n) => i + 1 < n,
+                TrustedProxies::Cidrs(_) => client.ip().is_some_and(|ip| trusted.is_trusted(ip)),
+            };
+            if !next_trusted {
+                break;
+            }
+        }
+        client
+    }
+
+    /// Replace the forwarding headers of a request with this chain, in the
+    /// given representation. The headers of the other representation, if
+    /// any, are removed.
+    ///
+    /// # Panics
+    ///
+    /// If a `host` or `proto` contains characters invalid in a header
+    /// value.
+    pub fn encode(&self, headers: &mut HeaderMap, representation: Representation) {
+        headers.remove(h::FORWARDED);
+        headers.remove(X_FORWARDED_FOR);
+        headers.remove(X_FORWARDED_PROTO);
+        headers.remove(X_FORWARDED_HOST);
+        if self.hops.is_empty() {
+            return;
+        }
+        if representation != Representation::XForwarded {
+            let hops: Vec<String> = self.hops.iter().map(|hop| hop.to_string()).collect();
+            headers.insert(h::FORWARDED, value(hops.join(", ")));
+        }
+        if representation != Representation::Forwarded {
+            let nodes: Vec<String> = self
+                .hops
+                .iter()
+                .map(|hop| match hop.for_ {
+                    Some(ref node) => node.fmt_x_forwarded(),
+                    None => Node::Unknown.to_string(),
+                })
+                .collect();
+            headers.insert(
+                HeaderName::from_static(X_FORWARDED_FOR),
+                value(nodes.join(", ")),
+            );
+            if let Some(ref proto) = self.hops[0].proto {
+                headers.insert(
+                    HeaderName::from_static(X_FORWARDED_PROTO),
+                    value(proto.clone()),
+                );
+            }
+            if let Some(ref host) = self.hops[0].host {
+                headers.insert(
+                    HeaderName::from_static(X_FORWARDED_HOST),
+                    value(host.clone()),
+                );
+            }
+        }
+    }
+}
+
+// The comma separated items of all values of a header.
+fn items<'a>(headers: &'a HeaderMap, name: &str) -> Result<Vec<&'a str>> {
+    let mut items = Vec::new();
+    for value in headers.get_all(name) {
+        let value = value
+            .to_str()
+            .map_err(|e| Error::header_with_source(HeaderErrorKind::Utf8, e))?;
+        items.extend(parsing::split_comma_delimited(value));
+    }
+    Ok(items)
+}
+
+// Pairs the items with hop indexes: one per hop if the counts match,
+// otherwise the first item with the first hop.
+fn spread<'a>(items: &[&'a str], hops: usize) -> Vec<(usize, &'a str)> {
+    if items.len() == hops {
+        items.iter().cloned().enumerate().collect()
+    } else {
+        items.first().map(|item| (0, *item)).into_iter().collect()
+    }
+}
+
+fn value(s: String) -> HeaderValue {
+    HeaderValue::from_str(&s).expect("valid header value")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn ip(s: &str) -> IpAddr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn test_node() {
+        assert_eq!(
+            "192.0.2.43".parse::<Node>().unwrap(),
+            Node::Ip(ip("192.0.2.43"))
+        );
+        assert_eq!(
+            "[2001:db8:cafe::17]:4711".parse::<Node>().unwrap(),
+            Node::Socket("[2001:db8:cafe::17]:4711".parse().unwrap())
+        );
+        assert_eq!(
+            "[2001:db8::1]".parse::<Node>().unwrap(),
+            Node::Ip(ip("2001:db8::1"))
+        );
+        assert_eq!(
+            "2001:db8::1".parse::<Node>().unwrap(),
+            Node::Ip(ip("2001:db8::1"))
+        );
+        assert_eq!(
+            "192.0.2.43:_p".parse::<Node>().unwrap(),
+            Node::Ip(ip("192.0.2.43"))
+        );
+        assert_eq!("UNKNOWN".parse::<Node>().unwrap(), Node::Unknown);
+        assert_eq!(
+            "_hidden".parse::<Node>().unwrap(),
+            Node::Obfuscated("_hidden".to_owned())
+        );
+        assert!("example.com".parse::<Node>().is_err());
+        assert!("192.0.2.43:port".parse::<Node>().is_err());
+        assert!("_a b".parse::<Node>().is_err());
+
+        assert_eq!(Node::Ip(ip("2001:db8::1")).to_string(), "[2001:db8::1]");
+        assert_eq!(Node::Ip(ip("2001:db8::1")).fmt_x_forwarded(), "2001:db8::1");
+    }
+
+    #[test]
+    fn test_forwarded() {
+        let headers = request(&[
+            ("forwarded", "for=192.0.2.60;proto=HTTP;by=203.0.113.43"),
+            (
+                "forwarded",
+                r#"For="[2001:db8:cafe::17]:4711";host="example.com:8080", for=unknown;ext=1"#,
+            ),
+            ("x-forwarded-for", "198.51.100.1"),
+        ]);
+        let chain = Chain::from_headers(&headers).unwrap();
+        assert_eq!(chain.hops().len(), 3);
+        assert_eq!(
+            chain.hops()[0],
+            Hop::new(Node::Ip(ip("192.0.2.60")))
+                .with_by(Node::Ip(ip("203.0.113.43")))
+                .with_proto("http")
+        );
+        assert_eq!(chain.hops()[1].host.as_ref().unwrap(), "example.com:8080");
+        assert_eq!(chain.hops()[2], Hop::new(Node::Unknown));
+
+        let mut encoded = HeaderMap::new();
+        chain.encode(&mut encoded, Representation::Forwarded);
+        assert_eq!(
+            encoded["forwarded"],
+            r#"for=192.0.2.60;by=203.0.113.43;proto=http, for="[2001:db8:cafe::17]:4711";host="example.com:8080", for=unknown"#
+        );
+        assert_eq!(Chain::from_headers(&encoded).unwrap(), chain);
+
+        assert!(Chain::from_headers(&request(&[("forwarded", "for")])).is_err());
+        assert!(Chain::from_headers(&request(&[("forwarded", "for=\"a")])).is_err());
+    }
+
+    #[test]
+    fn test_x_forwarded() {
+        let headers = request(&[
+            ("x-forwarded-for", "203.0.113.7, 2001:db8::1"),
+            ("x-forwarded-for", "10.0.0.2"),
+            ("x-forwarded-proto", "HTTPS"),
+            ("x-forwarded-host", "example.com"),
+        ]);
+        let chain = Chain::from_headers(&headers).unwrap();
+        assert_eq!(
+            chain.hops(),
+            &[
+                Hop::new(Node::Ip(ip("203.0.113.7")))
+                    .with_proto("https")
+                    .with_host("example.com"),
+                Hop::new(Node::Ip(ip("2001:db8::1"))),
+                Hop::new(Node::Ip(ip("10.0.0.2"))),
+            ]
+        );
+
+        let mut encoded = HeaderMap::new();
+        chain.encode(&mut encoded, Representation::Both);
+        assert_eq!(
+            encoded["x-forwarded-for"],
+            "203.0.113.7, 2001:db8::1, 10.0.0.2"
+        );
+        assert_eq!(encoded["x-forwarded-proto"], "https");
+        assert_eq!(encoded["x-forwarded-host"], "example.com");
+        assert_eq!(
+            encoded["forwarded"],
+            r#"for=203.0.113.7;host=example.com;proto=https, for="[2001:db8::1]", for=10.0.0.2"#
+        );
+        encoded.remove("forwarded");
+        assert_eq!(Chain::from_headers(&encoded).unwrap(), chain);
+
+        let paired = request(&[
+            ("x-forwarded-for", "192.0.2.1, 192.0.2.2"),
+            ("x-forwarded-proto", "https, http"),
+        ]);
+        let chain = Chain::from_headers(&paired).unwrap();
+        assert_eq!(chain.hops()[1].proto.as_ref().unwrap(), "http");
+
+        let proto_only = Chain::from_headers(&request(&[("x-forwarded-proto", "https")])).unwrap();
+        assert_eq!(proto_only.hops(), &[Hop::default().with_proto("https")]);
+        assert!(Chain::from_headers(&HeaderMap::new()).unwrap().is_empty());
+        assert!(Chain::from_headers(&request(&[("x-forwarded-for", "a.example")])).is_err());
+    }
+
+    #[test]
+    fn test_encode_replaces() {
+        let mut headers = request(&[
+            ("forwarded", "for=192.0.2.1"),
+            ("x-forwarded-for", "192.0.2.1"),
+            ("x-forwarded-proto", "https"),
+        ]);
+        let mut chain = Chain::new();
+        chain.encode(&mut headers, Representation::Both);
+        assert!(headers.is_empty());
+
+        chain.push(Hop::new(Node::Obfuscated("_a".to_owned())));
+        chain.encode(&mut headers, Representation::XForwarded);
+        assert_eq!(headers.len(), 1);
+        assert_eq!(headers["x-forwarded-for"], "_a");
+    }
+
+    #[test]
+    fn test_cidr() {
+        let cidr: Cidr = "10.0.0.0/8".parse().unwrap();
+        assert!(cidr.contains(ip("10.1.2.3")));
+        assert!(cidr.contains(ip("::ffff:10.1.2.3")));
+        assert!(!cidr.contains(ip("11.0.0.0")));
+        assert!(!cidr.contains(ip("::1")));
+        assert_eq!(cidr.to_string(), "10.0.0.0/8");
+
+        let cidr: Cidr = "2001:db8::/32".parse().unwrap();
+        assert!(cidr.contains(ip("2001:db8:1::1")));
+        assert!(!cidr.contains(ip("2001:db9::1")));
+
+        assert!("0.0.0.0/0".parse::<Cidr>().unwrap().contains(ip("1.2.3.4")));
+        assert!("192.0.2.1"
+            .parse::<Cidr>()
+            .unwrap()
+            .contains(ip("192.0.2.1")));
+        assert!(!"192.0.2.1"
+            .parse::<Cidr>()
+            .unwrap()
+            .contains(ip("192.0.2.2")));
+        assert!("10.0.0.0/33".parse::<Cidr>().is_err());
+        assert!("10.0.0/8".parse::<Cidr>().is_err());
+        assert!("10.0.0.0/x".parse::<Cidr>().is_err());
+    }
+
+    #[test]
+    fn test_client() {
+        let headers = request(&[("x-forwarded-for", "198.51.100.9, 203.0.113.7, 10.0.0.2")]);
+        let chain = Chain::from_headers(&headers).unwrap();
+        let peer = ip("10.0.0.3");
+
+        assert_eq!(
+            chain.client(peer, &TrustedProxies::Count(0)),
+            Node::Ip(peer)
+        );
+        assert_eq!(
+            chain.client(peer, &TrustedProxies::Count(1)),
+            Node::Ip(ip("10.0.0.2"))
+        );
+        assert_eq!(
+            chain.client(peer, &TrustedProxies::Count(2)),
+            Node::Ip(ip("203.0.113.7"))
+        );
+        assert_eq!(
+            chain.client(peer, &TrustedProxies::Count(10)),
+            Node::Ip(ip("198.51.100.9"))
+        );
+
+        let private = TrustedProxies::Cidrs(vec!["10.0.0.0/8".parse().unwrap()]);
+        assert_eq!(chain.client(peer, &private), Node::Ip(ip("203.0.113.7")));
+        assert_eq!(
+            chain.client(ip("192.0.2.1"), &private),
+            Node::Ip(ip("192.0.2.1"))
+        );
+        assert_eq!(Chain::new().client(peer, &private), Node::Ip(peer));
+
+        let all = TrustedProxies::Cidrs(vec!["0.0.0.0/0".parse().unwrap()]);
+        assert_eq!(chain.client(peer, &all), Node::Ip(ip("198.51.100.9")));
+
+        let mut hidden = Chain::new();
+        hidden.push(Hop::new(Node::Obfuscated("_x".to_owned())));
+        hidden.push(Hop::default());
+        assert_eq!(hidden.client(peer, &private), Node::Unknown);
+    }
+}
@@ -0,0 +1,190 @@
+//! `proptest::arbitrary::Arbitrary` implementations for typed headers, with
+//! the _proptest_ feature.
+//!
+//! Values are generated within the canonical forms of each header, such
+//! that formatting and re-parsing yields an equal value. Where a header
+//! normalizes on formatting (e.g. the default ports of `Host`), such values
+//! are not generated.
+
+use std::time::{Duration, UNIX_EPOCH};
+
+use proptest::collection::vec;
+use proptest::prelude::*;
+use unicase::Ascii;
+
+use method::Method;
+
+use super::{
+    q, AcceptEncoding, AccessControlMaxAge, Age, Allow, Connection, ConnectionOption,
+    ContentEncoding, ContentLength, Date, ETag, Encoding, EntityTag, Expires, Host, HttpDate,
+    IfMatch, IfModifiedSince, IfNoneMatch, IfUnmodifiedSince, LastModified, Quality, QualityItem,
+    Server, Te, TransferEncoding, UserAgent, Vary,
+};
+
+// A lowercase token, which is not one of the given reserved values.
+fn token(reserved: &'static [&'static str]) -> impl Strategy<Value = String> {
+    "[a-z][a-z0-9-]{0,11}".prop_filter("reserved token", move |s| !reserved.contains(&&s[..]))
+}
+
+// Visible ASCII text with inner spaces, such as of a product.
+fn text() -> impl Strategy<Value = String> {
+    "[!-~]([ !-~]{0,30}[!-~])?"
+}
+
+macro_rules! arbitrary {
+    ($ty:ty, $strategy:expr) => {
+        impl Arbitrary for $ty {
+            type Parameters = ();
+            type Strategy = BoxedStrategy<$ty>;
+
+            fn arbitrary_with(_: ()) -> BoxedStrategy<$ty> {
+                $strategy.boxed()
+            }
+        }
+    };
+}
+
+arbitrary!(
+    Encoding,
+    prop_oneof![
+        Just(Encoding::Chunked),
+        Just(Encoding::Brotli),
+        Just(Encoding::Gzip),
+        Just(Encoding::Deflate),
+        Just(Encoding::Compress),
+        Just(Encoding::Identity),
+        Just(Encoding::Trailers),
+        token(&["chunked", "br", "gzip", "deflate", "compress", "identity", "trailers"])
+            .prop_map(|s| Encoding::EncodingExt(Box::new(s))),
+    ]
+);
+
+// List splitting treats `\` as the escape of a quoted-string, so it is not
+// generated within the otherwise opaque tags
+arbitrary!(
+    EntityTag,
+    (any::<bool>(), "[!#-\\[\\]-~]{0,16}").prop_map(|(weak, tag)| EntityTag::new(weak, tag))
+);
+
+// Whole seconds, up to the year 9999
+arbitrary!(
+    HttpDate,
+    (0u64..253_402_300_800).prop_map(|s| HttpDate::from(UNIX_EPOCH + Duration::from_secs(s)))
+);
+
+arbitrary!(Quality, (0u16..=1000).prop_map(q));
+
+impl<T: Arbitrary + 'static> Arbitrary for QualityItem<T> {
+    type Parameters = ();
+    type Strategy = BoxedStrategy<QualityItem<T>>;
+
+    fn arbitrary_with(_: ()) -> BoxedStrategy<QualityItem<T>> {
+        (any::<T>(), any::<Quality>())
+            .prop_map(|(item, quality)| QualityItem::new(item, quality))
+            .boxed()
+    }
+}
+
+arbitrary!(
+    Method,
+    prop_oneof![
+        Just(Method::Options),
+        Just(Method::Get),
+        Just(Method::Post),
+        Just(Method::Put),
+        Just(Method::Delete),
+        Just(Method::Head),
+        Just(Method::Trace),
+        Just(Method::Connect),
+        Just(Method::Patch),
+        Just(Method::PropFind),
+        Just(Method::Query),
+        "[A-Z]{1,12}"
+            .prop_filter("standard method", |m| m
+                .parse::<Method>()
+                .is_ok_and(|m| matches!(m, Method::Extension(_))))
+            .prop_map(|m| Method::Extension(m.into())),
+    ]
+);
+
+arbitrary!(
+    ConnectionOption,
+    prop_oneof![
+        Just(ConnectionOption::KeepAlive),
+        Just(ConnectionOption::Close),
+        token(&["keep-alive", "close"])
+            .prop_map(|t| ConnectionOption::ConnectionHeader(Ascii::new(t))),
+    ]
+);
+
+arbitrary!(
+    AccessControlMaxAge,
+    any::<u32>().prop_map(AccessControlMaxAge)
+);
+arbitrary!(
+    AcceptEncoding,
+    vec(any::<QualityItem<Encoding>>(), 0..4).prop_map(|v| AcceptEncoding(v.into()))
+);
+arbitrary!(Age, any::<u32>().prop_map(Age));
+arbitrary!(
+    Allow,
+    vec(any::<Method>(), 0..4).prop_map(|v| Allow(v.into()))
+);
+arbitrary!(
+    Connection,
+    vec(any::<ConnectionOption>(), 1..4).prop_map(|v| Connection(v.into()))
+);
+arbitrary!(
+    ContentEncoding,
+    vec(any::<Encoding>(), 1..4).prop_map(|v| ContentEncoding(v.into()))
+);
+arbitrary!(ContentLength, any::<u64>().prop_map(ContentLength));
+arbitrary!(Date, any::<HttpDate>().prop_map(Date));
+arbitrary!(ETag, any::<EntityTag>().prop_map(ETag));
+arbitrary!(Expires, any::<HttpDate>().prop_map(Expires));
+// The default ports 80 and 443 are not formatted
+arbitrary!(
+    Host,
+    (
+        "[a-z0-9]([a-z0-9.-]{0,20}[a-z0-9])?",
+        proptest::option::of(any::<u16>().prop_filter("default port", |p| *p != 80 && *p != 443)),
+    )
+        .prop_map(|(hostname, port)| Host::new(hostname, port))
+);
+arbitrary!(
+    IfMatch,
+    prop_oneof![
+        Just(IfMatch::Any),
+        vec(any::<EntityTag>(), 1..4).prop_map(|v| IfMatch::Items(v.into())),
+    ]
+);
+arbitrary!(IfModifiedSince, any::<HttpDate>().prop_map(IfModifiedSince));
+arbitrary!(
+    IfNoneMatch,
+    prop_oneof![
+        Just(IfNoneMatch::Any),
+        vec(any::<EntityTag>(), 1..4).prop_map(|v| IfNoneMatch::Items(v.into())),
+    ]
+);
+arbitrary!(
+    IfUnmodifiedSince,
+    any::<HttpDate>().prop_map(IfUnmodifiedSince)
+);
+arbitrary!(LastModified, any::<HttpDate>().prop_map(LastModified));
+arbitrary!(Server, text().prop_map(Server::new));
+arbitrary!(
+    Te,
+    vec(any::<QualityItem<Encoding>>(), 0..4).prop_map(|v| Te(v.into()))
+);
+arbitrary!(
+    TransferEncoding,
+    vec(any::<Encoding>(), 1..4).prop_map(|v| TransferEncoding(v.into()))
+);
+arbitrary!(UserAgent, text().prop_map(UserAgent::new));
+arbitrary!(
+    Vary,
+    prop_oneof![
+        Just(Vary::Any),
+        vec(token(&[]).prop_map(Ascii::new), 1..4).prop_map(|v| Vary::Items(v.into())),
+    ]
+);
@@ -0,0 +1,262 @@
+//! Implementation module for integration with the _axum_ web framework, via
+//! the _axum-core_ crate.
+
+use std::convert::Infallible;
+use std::error::Error as StdError;
+use std::fmt;
+use std::future::{ready, Future};
+use std::ops::{Deref, DerefMut};
+
+use axum_core::extract::{FromRequestParts, OptionalFromRequestParts};
+use axum_core::response::{IntoResponse, IntoResponseParts, Response, ResponseParts};
+use http::request::Parts;
+use http::{HeaderMap, StatusCode};
+
+use super::compat::with_context;
+use super::{interned, Formatter, Header, Multi};
+
+/// A typed header, as an _axum_ extractor of the request headers or as
+/// (part of) a response.
+///
+/// As an extractor, a missing or invalid header is rejected with a
+/// `TypedHeaderRejection`. Extract an `Option<Typed<H>>` to instead accept
+/// a missing header as `None`, while still rejecting an invalid one.
+///
+/// As a response part, any existing values of the header are replaced.
+/// Encoding panics if the header formats a byte not allowed in a header
+/// value, as with `TypedHeaders::encode`.
+///
+/// Requires the `axum` feature.
+///
+/// # Example
+///
+/// ```
+/// # extern crate axum_core;
+/// # extern crate hyperx;
+/// use axum_core::response::IntoResponse;
+/// use hyperx::header::{Age, CacheControl, CacheDirective, Typed};
+///
+/// // As the return value of a handler, such as:
+/// // `async fn handler(Typed(age): Typed<Age>) -> impl IntoResponse`
+/// # fn main() {
+/// let res = (
+///     Typed(Age(60)),
+///     Typed(CacheControl(vec![CacheDirective::MaxAge(3600)])),
+///     "body",
+/// ).into_response();
+/// assert_eq!(res.headers()["age"], "60");
+/// assert_eq!(res.headers()["cache-control"], "max-age=3600");
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Typed<H>(pub H);
+
+impl<H> Deref for Typed<H> {
+    type Target = H;
+
+    fn deref(&self) -> &H {
+        &self.0
+    }
+}
+
+impl<H> DerefMut for Typed<H> {
+    fn deref_mut(&mut self) -> &mut H {
+        &mut self.0
+    }
+}
+
+impl<H: Header> Typed<H> {
+    // Decode from the headers, or `None` if there are no values.
+    fn decode(headers: &HeaderMap) -> Result<Option<Typed<H>>, TypedHeaderRejection> {
+        let vals = headers.get_all(H::http_name());
+        if vals.iter().next().is_none() {
+            return Ok(None);
+        }
+        match interned::parse_header(&vals) {
+            Ok(h) => Ok(Some(Typed(h))),
+            Err(e) => Err(TypedHeaderRejection {
+                name: H::header_name(),
+                error: Some(with_context::<H, _>(e, &vals)),
+            }),
+        }
+    }
+}
+
+impl<H, S> FromRequestParts<S> for Typed<H>
+where
+    H: Header,
+    S: Send + Sync,
+{
+    type Rejection = TypedHeaderRejection;
+
+    fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> impl Future<Output = Result<Self, Self::Rejection>> + Send {
+        ready(Typed::decode(&parts.headers).and_then(|h| {
+            h.ok_or(TypedHeaderRejection {
+                name: H::header_name(),
+                error: None,
+            })
+        }))
+    }
+}
+
+impl<H, S> OptionalFromRequestParts<S> for Typed<H>
+where
+    H: Header,
+    S: Send + Sync,
+{
+    type Rejection = TypedHeaderRejection;
+
+    fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> impl Future<Output = Result<Option<Self>, Self::Rejection>> + Send {
+        ready(Typed::decode(&parts.headers))
+    }
+}
+
+impl<H: Header> IntoResponseParts for Typed<H> {
+    type Error = Infallible;
+
+    fn into_response_parts(self, mut res: ResponseParts) -> Result<ResponseParts, Infallible> {
+        let name = H::http_name();
+        let headers = res.headers_mut();
+        headers.remove(&name);
+        self.0
+            .fmt_header(&mut Formatter(Multi::Append(&name, headers)))
+            .expect("header value");
+        Ok(res)
+    }
+}
+
+impl<H: Header> IntoResponse for Typed<H> {
+    fn into_response(self) -> Response {
+        (self, ()).into_response()
+    }
+}
+
+/// Rejection of the `Typed` extractor, for a missing or invalid header.
+///
+/// Responds with `400 Bad Request`.
+#[derive(Debug)]
+pub struct TypedHeaderRejection {
+    name: &'static str,
+    error: Option<::Error>,
+}
+
+impl TypedHeaderRejection {
+    /// The name of the header.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Return true if the header was missing, rather than invalid.
+    pub fn is_missing(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// The error parsing the header, if invalid.
+    pub fn error(&self) -> Option<&::Error> {
+        self.error.as_ref()
+    }
+}
+
+impl fmt::Display for TypedHeaderRejection {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.error {
+            Some(ref e) => write!(f, "Invalid request header {}: {}", self.name, e),
+            None => write!(f, "Missing request header {}", self.name),
+        }
+    }
+}
+
+impl StdError for TypedHeaderRejection {
+    fn source(&self) -> Option<&(dyn StdError + 'static)> {
+        self.error.as_ref().map(|e| e as &(dyn StdError + 'static))
+    }
+}
+
+impl IntoResponse for TypedHeaderRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::{Age, ContentLength, ETag, EntityTag};
+    use http::header::{AGE, CONTENT_LENGTH, ETAG};
+    use http::Request;
+    use std::future::Future;
+    use std::pin::pin;
+    use std::task::{Context, Poll, Waker};
+
+    // The futures are always ready.
+    fn now<F: Future>(f: F) -> F::Output {
+        let mut cx = Context::from_waker(Waker::noop());
+        match pin!(f).poll(&mut cx) {
+            Poll::Ready(out) => out,
+            Poll::Pending => panic!("pending"),
+        }
+    }
+
+    fn parts(headers: &[(&str, &str)]) -> Parts {
+        let mut req = Request::builder();
+        for &(name, value) in headers {
+            req = req.header(name, value);
+        }
+        req.body(()).unwrap().into_parts().0
+    }
+
+    #[test]
+    fn test_extract() {
+        let mut p = parts(&[("content-length", "42")]);
+        let len =
+            now(<Typed<ContentLength> as FromRequestParts<()>>::from_request_parts(&mut p, &()));
+        assert_eq!(len.unwrap(), Typed(ContentLength(42)));
+
+        let age = now(<Typed<Age> as FromRequestParts<()>>::from_request_parts(
+            &mut p,
+            &(),
+        ));
+        let rej = age.unwrap_err();
+        assert!(rej.is_missing());
+        assert_eq!(rej.to_string(), "Missing request header Age");
+        assert_eq!(rej.into_response().status(), StatusCode::BAD_REQUEST);
+
+        let age =
+            now(<Typed<Age> as OptionalFromRequestParts<()>>::from_request_parts(&mut p, &()));
+        assert_eq!(age.unwrap(), None);
+    }
+
+    #[test]
+    fn test_extract_invalid() {
+        let mut p = parts(&[("content-length", "4x")]);
+        let len = now(
+            <Typed<ContentLength> as OptionalFromRequestParts<()>>::from_request_parts(&mut p, &()),
+        );
+        let rej = len.unwrap_err();
+        assert!(!rej.is_missing());
+        assert_eq!(rej.name(), "Content-Length");
+        assert_eq!(
+            rej.error().unwrap().header_error().unwrap().name(),
+            Some("Content-Length")
+        );
+        assert!(rej.source().is_some());
+    }
+
+    #[test]
+    fn test_response() {
+        let etag = ETag(EntityTag::strong("xyzzy".to_owned()));
+        let res = ([(AGE, "1"), (AGE, "2")], Typed(Age(3)), Typed(etag), "body").into_response();
+        let ages: Vec<_> = res.headers().get_all(AGE).iter().collect();
+        assert_eq!(ages, ["3"]);
+        assert_eq!(res.headers()[ETAG], "\"xyzzy\"");
+
+        let res = Typed(ContentLength(0)).into_response();
+        assert_eq!(res.headers()[CONTENT_LENGTH], "0");
+    }
+}
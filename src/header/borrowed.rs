@@ -0,0 +1,52 @@
+use http::header::HeaderValue;
+use http::HeaderMap;
+
+use super::RawLike;
+
+/// A header parsed by borrowing from its raw values (lines), rather than
+/// copying them into an owned `Header` type.
+///
+/// Borrowed variants are provided for string-heavy headers, such as
+/// `CookieRef` of `Cookie`, for when the parsed header is only inspected
+/// while the raw headers are still at hand. Values with obsolete line
+/// folding, which cannot be unfolded without allocating, are rejected.
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::{CookieRef, HeaderRef};
+///
+/// # fn main() {
+/// let mut headers = http::HeaderMap::new();
+/// headers.insert("cookie", "SID=31d4d96e407aad42; lang=en-US".parse().unwrap());
+///
+/// let cookie = CookieRef::decode_ref(&headers).unwrap().unwrap();
+/// assert_eq!(cookie.get("lang"), Some("en-US"));
+/// # }
+/// ```
+pub trait HeaderRef<'a>: Sized {
+    /// Returns the name of the header field this belongs to.
+    fn header_name() -> &'static str;
+
+    /// Parse the header from its values (lines), borrowing from them.
+    fn parse_lines<I>(lines: I) -> ::Result<Self>
+    where
+        I: IntoIterator<Item = &'a [u8]>;
+
+    /// Parse the header from a raw header, borrowing from it.
+    fn parse_header_ref<T>(raw: &'a T) -> ::Result<Self>
+    where
+        T: RawLike<'a>,
+    {
+        Self::parse_lines(raw.iter())
+    }
+
+    /// Decode the header from a `http::HeaderMap`, borrowing from it, or
+    /// return `None` if not found.
+    fn decode_ref(headers: &'a HeaderMap) -> Option<::Result<Self>> {
+        let values = headers.get_all(Self::header_name());
+        let mut lines = values.iter().map(HeaderValue::as_bytes).peekable();
+        lines.peek()?;
+        Some(Self::parse_lines(lines))
+    }
+}
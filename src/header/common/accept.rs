@@ -1,4 +1,4 @@
-use header::{qitem, QualityItem};
+use header::{q, qitem, Quality, QualityItem};
 use mime::{self, Mime};
 
 header! {
@@ -38,7 +38,7 @@ header! {
     /// headers.encode(
     ///     &Accept(vec![
     ///         qitem(mime::TEXT_HTML),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     ///
@@ -51,7 +51,7 @@ header! {
     /// headers.encode(
     ///     &Accept(vec![
     ///         qitem(mime::APPLICATION_JSON),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     ///
@@ -75,7 +75,7 @@ header! {
     ///             mime::STAR_STAR,
     ///             q(800)
     ///         ),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     (Accept, "Accept") => (QualityItem<Mime>)+
@@ -85,14 +85,14 @@ header! {
          test_header!(
             test1,
             vec![b"audio/*; q=0.2, audio/basic"],
-            Some(HeaderField(vec![
+            Some(HeaderField(smallvec![
                 QualityItem::new("audio/*".parse().unwrap(), q(200)),
                 qitem("audio/basic".parse().unwrap()),
                 ])));
         test_header!(
             test2,
             vec![b"text/plain; q=0.5, text/html, text/x-dvi; q=0.8, text/x-c"],
-            Some(HeaderField(vec![
+            Some(HeaderField(smallvec![
                 QualityItem::new(TEXT_PLAIN, q(500)),
                 qitem(TEXT_HTML),
                 QualityItem::new(
@@ -104,13 +104,13 @@ header! {
         test_header!(
             test3,
             vec![b"text/plain; charset=utf-8"],
-            Some(Accept(vec![
+            Some(Accept(smallvec![
                 qitem(TEXT_PLAIN_UTF_8),
                 ])));
         test_header!(
             test4,
             vec![b"text/plain; charset=utf-8; q=0.5"],
-            Some(Accept(vec![
+            Some(Accept(smallvec![
                 QualityItem::new(TEXT_PLAIN_UTF_8,
                     q(500)),
             ])));
@@ -121,28 +121,99 @@ header! {
             let header = Accept::parse_header(&raw);
             assert!(header.is_ok());
         }
+
+        #[test]
+        fn test_size() {
+            use std::mem::size_of;
+
+            // Four items inline, plus the length and discriminant
+            let item = size_of::<QualityItem<Mime>>();
+            assert!(size_of::<Accept>() <= 4 * item + 2 * size_of::<usize>());
+            assert!(size_of::<HeaderVec<u64>>() <= 6 * size_of::<u64>());
+
+            let raw: Raw = "text/html, application/xml;q=0.9, image/webp, */*;q=0.8".into();
+            let accept = Accept::parse_header(&raw).unwrap();
+            assert_eq!(accept.len(), 4);
+            assert!(!accept.spilled());
+        }
     }
 }
 
 impl Accept {
     /// A constructor to easily create `Accept: */*`.
     pub fn star() -> Accept {
-        Accept(vec![qitem(mime::STAR_STAR)])
+        Accept(smallvec![qitem(mime::STAR_STAR)])
     }
 
     /// A constructor to easily create `Accept: application/json`.
     pub fn json() -> Accept {
-        Accept(vec![qitem(mime::APPLICATION_JSON)])
+        Accept(smallvec![qitem(mime::APPLICATION_JSON)])
     }
 
     /// A constructor to easily create `Accept: text/*`.
     pub fn text() -> Accept {
-        Accept(vec![qitem(mime::TEXT_STAR)])
+        Accept(smallvec![qitem(mime::TEXT_STAR)])
     }
 
     /// A constructor to easily create `Accept: image/*`.
     pub fn image() -> Accept {
-        Accept(vec![qitem(mime::IMAGE_STAR)])
+        Accept(smallvec![qitem(mime::IMAGE_STAR)])
+    }
+
+    /// Return the quality of a media type, from the most specific of the
+    /// media ranges matching it, or `None` if none match.
+    ///
+    /// As per [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-12.5.1),
+    /// `text/html;level=1` is more specific than `text/html`, which is more
+    /// specific than `text/*`, and in turn `*/*`. A range with parameters
+    /// only matches a media type with all the same parameters.
+    ///
+    /// ```
+    /// use hyperx::header::{q, Accept};
+    ///
+    /// let accept: Accept = "text/*;q=0.3, text/plain;q=0.7, text/plain;format=flowed, */*;q=0.5"
+    ///     .parse()
+    ///     .unwrap();
+    /// assert_eq!(accept.quality(&"text/plain;format=flowed".parse().unwrap()), Some(q(1000)));
+    /// assert_eq!(accept.quality(&"text/plain".parse().unwrap()), Some(q(700)));
+    /// assert_eq!(accept.quality(&"text/html".parse().unwrap()), Some(q(300)));
+    /// assert_eq!(accept.quality(&"image/jpeg".parse().unwrap()), Some(q(500)));
+    /// ```
+    pub fn quality(&self, media_type: &Mime) -> Option<Quality> {
+        ::negotiate::media_type_quality(self, media_type, None).map(|t| q(t as u16))
+    }
+
+    /// Select the most preferred of the available media types, or `None`
+    /// if none are acceptable (i.e. `406 Not Acceptable`).
+    ///
+    /// Ties are broken in favor of the earliest media type, so they should
+    /// be listed in order of server preference. An empty header accepts the
+    /// first. See the `negotiate` module to also negotiate the encoding,
+    /// language and charset.
+    ///
+    /// ```
+    /// use hyperx::header::Accept;
+    /// use hyperx::mime;
+    ///
+    /// let available = [mime::APPLICATION_JSON, mime::TEXT_HTML];
+    /// let accept: Accept = "text/html, application/*;q=0.9".parse().unwrap();
+    /// assert_eq!(accept.negotiate(&available), Some(&mime::TEXT_HTML));
+    ///
+    /// let accept: Accept = "image/*".parse().unwrap();
+    /// assert_eq!(accept.negotiate(&available), None);
+    /// ```
+    pub fn negotiate<'a>(&self, available: &'a [Mime]) -> Option<&'a Mime> {
+        if self.is_empty() {
+            return available.first();
+        }
+        let mut best: Option<(&Mime, u16)> = None;
+        for media_type in available {
+            let quality = self.quality(media_type).map_or(0, Quality::thousandths);
+            if quality > 0 && best.map_or(true, |(_, b)| quality > b) {
+                best = Some((media_type, quality));
+            }
+        }
+        best.map(|(media_type, _)| media_type)
     }
 }
 
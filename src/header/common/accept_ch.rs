@@ -0,0 +1,40 @@
+use unicase::Ascii;
+
+header! {
+    /// `Accept-CH` header, defined in
+    /// [RFC 8942](https://www.rfc-editor.org/rfc/rfc8942#section-3.1)
+    ///
+    /// The `Accept-CH` response header lists the client hints a server
+    /// would like the client to send on subsequent requests, such as
+    /// `Sec-CH-UA-Platform` or `Sec-CH-Prefers-Color-Scheme`.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Accept-CH = sf-list
+    /// ```
+    ///
+    /// # Example values
+    /// * `Sec-CH-UA-Platform, Sec-CH-UA-Model`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # extern crate unicase;
+    /// # fn main() {
+    /// use hyperx::header::{AcceptCh, TypedHeaders};
+    /// use unicase::Ascii;
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let hints = AcceptCh(vec![Ascii::new("Sec-CH-UA-Platform".to_owned())].into());
+    /// headers.encode(&hints);
+    /// # }
+    /// ```
+    (AcceptCh, "Accept-CH") => (Ascii<String>)*
+
+    test_accept_ch {
+        test_header!(test1, [b"Sec-CH-UA-Platform, Sec-CH-UA-Model"]);
+    }
+}
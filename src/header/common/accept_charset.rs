@@ -27,7 +27,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &AcceptCharset(vec![qitem(Charset::Us_Ascii)])
+    ///     &AcceptCharset(vec![qitem(Charset::Us_Ascii)].into())
     /// );
     /// ```
     /// ```
@@ -39,7 +39,7 @@ header! {
     ///     &AcceptCharset(vec![
     ///         QualityItem::new(Charset::Us_Ascii, q(900)),
     ///         QualityItem::new(Charset::Iso_8859_10, q(200)),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     /// ```
@@ -48,7 +48,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &AcceptCharset(vec![qitem(Charset::Ext("utf-8".to_owned()))])
+    ///     &AcceptCharset(vec![qitem(Charset::Ext("utf-8".to_owned()))].into())
     /// );
     /// ```
     (AcceptCharset, "Accept-Charset") => (QualityItem<Charset>)+
@@ -31,7 +31,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &AcceptEncoding(vec![qitem(Encoding::Chunked)])
+    ///     &AcceptEncoding(vec![qitem(Encoding::Chunked)].into())
     /// );
     /// ```
     /// ```
@@ -44,7 +44,7 @@ header! {
     ///         qitem(Encoding::Chunked),
     ///         qitem(Encoding::Gzip),
     ///         qitem(Encoding::Deflate),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     /// ```
@@ -56,8 +56,8 @@ header! {
     ///     &AcceptEncoding(vec![
     ///         qitem(Encoding::Chunked),
     ///         QualityItem::new(Encoding::Gzip, q(600)),
-    ///         QualityItem::new(Encoding::EncodingExt("*".to_owned()), q(0)),
-    ///     ])
+    ///         QualityItem::new(Encoding::EncodingExt(Box::new("*".to_owned())), q(0)),
+    ///     ].into())
     /// );
     /// ```
     (AcceptEncoding, "Accept-Encoding") => (QualityItem<Encoding>)*
@@ -65,7 +65,7 @@ header! {
     test_accept_encoding {
         // From the RFC
         test_header!(test1, [b"compress, gzip"]);
-        test_header!(test2, vec![b""], Some(AcceptEncoding(vec![])));
+        test_header!(test2, vec![b""], Some(AcceptEncoding(smallvec![])));
         test_header!(test3, [b"*"]);
         // Note: Removed quality 1 from gzip
         test_header!(test4, [b"compress;q=0.5, gzip"]);
@@ -74,4 +74,8 @@ header! {
     }
 }
 
+bench_header!(bench, AcceptEncoding, {
+    vec![b"gzip, deflate, br;q=0.9, zstd;q=0.5".to_vec()]
+});
+
 standard_header!(AcceptEncoding, ACCEPT_ENCODING);
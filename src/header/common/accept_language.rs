@@ -1,4 +1,4 @@
-use header::QualityItem;
+use header::{q, LanguageRange, Quality, QualityItem};
 use language_tags::LanguageTag;
 
 header! {
@@ -31,7 +31,7 @@ header! {
     /// headers.encode(
     ///     &AcceptLanguage(vec![
     ///         qitem(langtag),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     ///
@@ -48,11 +48,11 @@ header! {
     ///         qitem("da".parse().unwrap()),
     ///         QualityItem::new("en-US".parse().unwrap(), q(800)),
     ///         QualityItem::new("en".parse().unwrap(), q(700)),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
-    (AcceptLanguage, "Accept-Language") => (QualityItem<LanguageTag>)+
+    (AcceptLanguage, "Accept-Language") => (QualityItem<LanguageRange>)+
 
     test_accept_language {
         // From the RFC
@@ -60,11 +60,79 @@ header! {
         // Own test
         test_header!(
             test2, vec![b"en-US, en; q=0.5, fr"],
-            Some(AcceptLanguage(vec![
+            Some(AcceptLanguage(smallvec![
                 qitem("en-US".parse().unwrap()),
                 QualityItem::new("en".parse().unwrap(), q(500)),
                 qitem("fr".parse().unwrap()),
         ])));
+
+        #[test]
+        fn test_quality() {
+            let accept: AcceptLanguage = "de-CH, de;q=0.9, en-x-y;q=0.1".parse().unwrap();
+            let tag = |s: &str| -> LanguageTag { s.parse().unwrap() };
+            assert_eq!(accept.quality(&tag("de-CH-1996")), Some(q(1000)));
+            assert_eq!(accept.quality(&tag("de-AT")), Some(q(900)));
+            assert_eq!(accept.quality(&tag("en-x-y")), None);
+
+            let accept: AcceptLanguage = "*;q=0.1, en, fr;q=0".parse().unwrap();
+            assert_eq!(accept.quality(&tag("en-GB")), Some(q(1000)));
+            assert_eq!(accept.quality(&tag("de")), Some(q(100)));
+            assert_eq!(accept.quality(&tag("fr")), Some(q(0)));
+        }
+
+        #[test]
+        fn test_canonicalize() {
+            let mut accept: AcceptLanguage = "iw, en-Latn-US;q=0.5".parse().unwrap();
+            accept.canonicalize();
+            assert_eq!(accept.to_string(), "he, en-US; q=0.5");
+        }
+    }
+}
+
+impl AcceptLanguage {
+    /// Return the quality of a language tag, from the most specific of the
+    /// language ranges matching it, or `None` if none match.
+    ///
+    /// A range matches as per `LanguageTag::matches`, for example `en`
+    /// matches `en` and `en-GB`, but `en-GB` does not match `en`. Ranges
+    /// with extension or private use subtags never match. The `*` range
+    /// matches any tag, as the least specific range.
+    ///
+    /// ```
+    /// use hyperx::header::{AcceptLanguage, LanguageTag, q};
+    ///
+    /// let accept: AcceptLanguage = "en-GB;q=0.8, en;q=0.5".parse().unwrap();
+    /// let tag = |s| LanguageTag::parse(s).unwrap();
+    /// assert_eq!(accept.quality(&tag("en-gb")), Some(q(800)));
+    /// assert_eq!(accept.quality(&tag("en-US")), Some(q(500)));
+    /// assert_eq!(accept.quality(&tag("de")), None);
+    ///
+    /// let accept: AcceptLanguage = "en, *;q=0.5".parse().unwrap();
+    /// assert_eq!(accept.quality(&tag("de")), Some(q(500)));
+    /// ```
+    pub fn quality(&self, tag: &LanguageTag) -> Option<Quality> {
+        ::negotiate::language_quality(self, tag).map(|t| q(t as u16))
+    }
+
+    /// Replace each language tag with its canonical form, as per
+    /// `LanguageTag::canonicalize`, where it has a unique one.
+    pub fn canonicalize(&mut self) {
+        canonicalize(self.0.iter_mut().filter_map(|range| match range.item {
+            LanguageRange::Any => None,
+            LanguageRange::Tag(ref mut tag) => Some(tag),
+        }));
+    }
+}
+
+// Shared with `ContentLanguage`.
+pub(super) fn canonicalize<'a, I>(tags: I)
+where
+    I: IntoIterator<Item = &'a mut LanguageTag>,
+{
+    for tag in tags {
+        if let Ok(canonical) = tag.canonicalize() {
+            *tag = canonical;
+        }
     }
 }
 
@@ -1,3 +1,4 @@
+use header::parsing::is_token;
 use std::fmt::{self, Display};
 use std::str::FromStr;
 
@@ -26,7 +27,7 @@ header! {
     /// use hyperx::header::{AcceptRanges, RangeUnit, TypedHeaders};
     ///
     /// let mut headers = http::HeaderMap::new();
-    /// headers.encode(&AcceptRanges(vec![RangeUnit::Bytes]));
+    /// headers.encode(&AcceptRanges(vec![RangeUnit::Bytes].into()));
     /// ```
     ///
     /// ```
@@ -34,7 +35,7 @@ header! {
     /// use hyperx::header::{AcceptRanges, RangeUnit, TypedHeaders};
     ///
     /// let mut headers = http::HeaderMap::new();
-    /// headers.encode(&AcceptRanges(vec![RangeUnit::None]));
+    /// headers.encode(&AcceptRanges(vec![RangeUnit::None].into()));
     /// ```
     ///
     /// ```
@@ -44,11 +45,11 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
     ///     &AcceptRanges(vec![
-    ///         RangeUnit::Unregistered("nibbles".to_owned()),
+    ///         RangeUnit::Unregistered(Box::new("nibbles".to_owned())),
     ///         RangeUnit::Bytes,
-    ///         RangeUnit::Unregistered("doublets".to_owned()),
-    ///         RangeUnit::Unregistered("quadlets".to_owned()),
-    ///     ])
+    ///         RangeUnit::Unregistered(Box::new("doublets".to_owned())),
+    ///         RangeUnit::Unregistered(Box::new("quadlets".to_owned())),
+    ///     ].into())
     /// );
     /// ```
     (AcceptRanges, "Accept-Ranges") => (RangeUnit)+
@@ -58,6 +59,19 @@ header! {
         test_header!(test2, [b"none"]);
         test_header!(test3, [b"unknown-unit"]);
         test_header!(test4, [b"bytes, unknown-unit"]);
+
+        #[test]
+        fn test_invalid_unit() {
+            assert!("bad/unit".parse::<RangeUnit>().is_err());
+            let raw: Raw = "bytes, bad/unit".into();
+            let r: AcceptRanges = Header::parse_header(&raw).unwrap();
+            assert_eq!(r, AcceptRanges(smallvec![RangeUnit::Bytes]));
+        }
+
+        #[test]
+        fn test_unit_size() {
+            assert!(::std::mem::size_of::<RangeUnit>() <= 2 * ::std::mem::size_of::<usize>());
+        }
     }
 }
 
@@ -81,7 +95,10 @@ pub enum RangeUnit {
     /// Reserved as keyword, indicating no ranges are supported.
     None,
     /// The given range unit is not registered at IANA.
-    Unregistered(String),
+    ///
+    /// Boxed, so that lists of the registered units don't reserve the size
+    /// of a `String` per item.
+    Unregistered(Box<String>),
 }
 
 impl FromStr for RangeUnit {
@@ -90,8 +107,8 @@ impl FromStr for RangeUnit {
         match s {
             "bytes" => Ok(RangeUnit::Bytes),
             "none" => Ok(RangeUnit::None),
-            // FIXME: Check if s is really a Token
-            _ => Ok(RangeUnit::Unregistered(s.to_owned())),
+            _ if is_token(s) => Ok(RangeUnit::Unregistered(Box::new(s.to_owned()))),
+            _ => Err(::Error::header(::HeaderErrorKind::Token)),
         }
     }
 }
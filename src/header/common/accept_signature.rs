@@ -0,0 +1,286 @@
+use std::fmt;
+
+use std::convert::TryFrom;
+
+use header::sfv::{
+    insert_member, BareItem, Dictionary, InnerList, Item, ListEntry, Parameters, SfString,
+};
+use header::{Header, RawLike};
+use signature::{Component, Param, SignatureParams};
+
+/// `Accept-Signature` header, defined in
+/// [RFC9421](https://www.rfc-editor.org/rfc/rfc9421#section-5.1)
+///
+/// The `Accept-Signature` header requests, for each signature by label,
+/// that the recipient sign its response (or later request) over the given
+/// components, and with the given signature parameters, such as `keyid`
+/// or `alg`.
+///
+/// # ABNF
+///
+/// ```text
+/// Accept-Signature = sf-dictionary
+/// ```
+///
+/// # Example values
+/// * `sig1=("@method" "@target-uri" "content-digest");keyid="test-key";created`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate hyperx;
+/// # fn main() {
+/// use hyperx::header::{AcceptSignature, Header, Raw};
+/// use hyperx::signature::{Component, Param};
+///
+/// let raw = Raw::from("sig1=(\"@method\" \"@authority\");keyid=\"k1\";created");
+/// let accept = AcceptSignature::parse_header(&raw).unwrap();
+/// let requested = accept.get("sig1").unwrap();
+/// assert_eq!(requested.components, [Component::Method, Component::Authority]);
+/// assert!(requested.wants_created());
+///
+/// // The parameters to sign with, given the time of signing
+/// let params = requested.signature_params().with(Param::Created(1618884473));
+/// assert_eq!(
+///     params.to_string(),
+///     "(\"@method\" \"@authority\");keyid=\"k1\";created=1618884473"
+/// );
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AcceptSignature(pub Vec<(String, RequestedSignature)>);
+
+impl AcceptSignature {
+    /// Create an empty header, without requested signatures.
+    pub fn new() -> AcceptSignature {
+        AcceptSignature::default()
+    }
+
+    /// The requested signature with the given label.
+    pub fn get(&self, label: &str) -> Option<&RequestedSignature> {
+        self.0.iter().find(|m| m.0 == label).map(|m| &m.1)
+    }
+
+    /// Set a requested signature, replacing any with the same label in its
+    /// place.
+    ///
+    /// Fails with `HeaderErrorKind::Token` if the label is not a valid
+    /// structured field key, such as `Sig1`.
+    pub fn insert<L>(&mut self, label: L, requested: RequestedSignature) -> ::Result<()>
+    where
+        L: Into<String>,
+    {
+        insert_member(&mut self.0, label.into(), requested)
+    }
+}
+
+impl Header for AcceptSignature {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Accept-Signature";
+        NAME
+    }
+
+    __hyper__http_name!("Accept-Signature");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AcceptSignature>
+    where
+        T: RawLike<'a>,
+    {
+        let dict = Dictionary::from_raw(raw)?;
+        if dict.is_empty() {
+            return Err(::Error::header(::HeaderErrorKind::Empty));
+        }
+        dict.iter()
+            .map(|(label, member)| match *member {
+                ListEntry::InnerList(ref list) => {
+                    RequestedSignature::from_inner_list(list).map(|r| (label.to_owned(), r))
+                }
+                ListEntry::Item(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
+            })
+            .collect::<::Result<_>>()
+            .map(AcceptSignature)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for AcceptSignature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let mut dict = Dictionary::new();
+        for (label, requested) in &self.0 {
+            dict.insert(label.as_str(), requested.to_inner_list())
+                .map_err(|_| fmt::Error)?;
+        }
+        fmt::Display::fmt(&dict, f)
+    }
+}
+
+/// A member of the `Accept-Signature` header: the components and
+/// parameters of a requested signature.
+///
+/// The parameters are as requested, where `created` and `expires` may be
+/// `true` rather than a time, to request that the signer include them.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RequestedSignature {
+    /// The components to cover, in order.
+    pub components: Vec<Component>,
+    /// The requested signature parameters, in order.
+    pub params: Parameters,
+}
+
+impl RequestedSignature {
+    /// Create a request covering the given components, in order, without
+    /// parameters.
+    pub fn new<I>(components: I) -> RequestedSignature
+    where
+        I: IntoIterator<Item = Component>,
+    {
+        RequestedSignature {
+            components: components.into_iter().collect(),
+            params: Parameters::new(),
+        }
+    }
+
+    /// Set a parameter, failing as `sfv::Parameters::insert`.
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> ::Result<RequestedSignature>
+    where
+        K: Into<String>,
+        V: Into<BareItem>,
+    {
+        self.params.insert(key, value)?;
+        Ok(self)
+    }
+
+    /// Return true if the `created` parameter is requested.
+    pub fn wants_created(&self) -> bool {
+        self.params.get("created").is_some()
+    }
+
+    /// Return true if the `expires` parameter is requested.
+    pub fn wants_expires(&self) -> bool {
+        self.params.get("expires").is_some()
+    }
+
+    /// The requested `keyid` parameter, if any.
+    pub fn key_id(&self) -> Option<&str> {
+        self.params.get("keyid").and_then(BareItem::as_string)
+    }
+
+    /// The requested `alg` parameter, if any.
+    pub fn alg(&self) -> Option<&str> {
+        self.params.get("alg").and_then(BareItem::as_string)
+    }
+
+    /// The parameters to sign with: the requested components, and the
+    /// `nonce`, `alg`, `keyid` and `tag` parameters, as well as `created`
+    /// and `expires` if given as times. Other parameters are dropped.
+    pub fn signature_params(&self) -> SignatureParams {
+        let mut params = SignatureParams::new(self.components.iter().cloned());
+        for (key, value) in self.params.iter() {
+            let param = match (key, value) {
+                ("created", BareItem::Integer(t)) if t.get() >= 0 => Param::Created(t.get() as u64),
+                ("expires", BareItem::Integer(t)) if t.get() >= 0 => Param::Expires(t.get() as u64),
+                ("nonce", BareItem::String(s)) => Param::Nonce(s.as_str().to_owned()),
+                ("alg", BareItem::String(s)) => Param::Alg(s.as_str().to_owned()),
+                ("keyid", BareItem::String(s)) => Param::KeyId(s.as_str().to_owned()),
+                ("tag", BareItem::String(s)) => Param::Tag(s.as_str().to_owned()),
+                _ => continue,
+            };
+            params = params.with(param);
+        }
+        params
+    }
+
+    fn from_inner_list(list: &InnerList) -> ::Result<RequestedSignature> {
+        let components = list
+            .items
+            .iter()
+            .map(|item| match item.bare_item {
+                // Component parameters are not supported, as for
+                // `SignatureParams`
+                BareItem::String(ref name) if item.params.is_empty() => name.as_str().parse(),
+                _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+            })
+            .collect::<::Result<_>>()?;
+        Ok(RequestedSignature {
+            components,
+            params: list.params.clone(),
+        })
+    }
+
+    fn to_inner_list(&self) -> InnerList {
+        InnerList {
+            items: self
+                .components
+                .iter()
+                .map(|c| Item::new(SfString::try_from(c.name()).expect("ASCII component name")))
+                .collect(),
+            params: self.params.clone(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+
+    #[test]
+    fn test_parse() {
+        let raw = Raw::from(vec![
+            b"sig1=(\"@method\" \"content-digest\");created;expires=1700000000".to_vec(),
+            b"sig2=(\"@status\");alg=\"ed25519\";x=1".to_vec(),
+        ]);
+        let accept = AcceptSignature::parse_header(&raw).unwrap();
+        let sig1 = accept.get("sig1").unwrap();
+        assert_eq!(sig1.components[1], "content-digest".parse().unwrap());
+        assert!(sig1.wants_created() && sig1.wants_expires());
+        assert_eq!(
+            sig1.signature_params().params(),
+            [Param::Expires(1700000000)]
+        );
+        let sig2 = accept.get("sig2").unwrap();
+        assert_eq!(sig2.alg(), Some("ed25519"));
+        assert_eq!(sig2.key_id(), None);
+        assert_eq!(
+            sig2.signature_params().to_string(),
+            "(\"@status\");alg=\"ed25519\""
+        );
+        assert_eq!(
+            accept.to_string(),
+            "sig1=(\"@method\" \"content-digest\");created;expires=1700000000, \
+             sig2=(\"@status\");alg=\"ed25519\";x=1"
+        );
+
+        for bad in &[
+            "",
+            "sig1=\"@method\"",
+            "sig1=(\"@nope\")",
+            "sig1=(\"@method\";req)",
+        ] {
+            let raw = Raw::from(*bad);
+            assert!(AcceptSignature::parse_header(&raw).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_fmt() {
+        let mut accept = AcceptSignature::new();
+        let requested = RequestedSignature::new(vec![Component::Method, Component::Path])
+            .with_param("keyid", SfString::try_from("k1").unwrap())
+            .and_then(|r| r.with_param("created", true))
+            .unwrap();
+        accept.insert("sig1", requested.clone()).unwrap();
+        assert!(accept.insert("sig 2", requested).is_err());
+        assert_eq!(
+            accept.to_string(),
+            "sig1=(\"@method\" \"@path\");keyid=\"k1\";created"
+        );
+    }
+}
+
+__hyper__try_from!(AcceptSignature);
+__hyper__from_str!(AcceptSignature);
+__hyper__serde!(AcceptSignature);
@@ -49,6 +49,8 @@ impl Header for AccessControlAllowCredentials {
         NAME
     }
 
+    __hyper__http_name!("Access-Control-Allow-Credentials");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<AccessControlAllowCredentials>
     where
         T: RawLike<'a>,
@@ -66,7 +68,7 @@ impl Header for AccessControlAllowCredentials {
                 return Ok(AccessControlAllowCredentials);
             }
         }
-        Err(::Error::Header)
+        Err(::Error::header(::HeaderErrorKind::Token))
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -100,3 +102,7 @@ standard_header!(
     AccessControlAllowCredentials,
     ACCESS_CONTROL_ALLOW_CREDENTIALS
 );
+
+__hyper__try_from!(AccessControlAllowCredentials);
+__hyper__from_str!(AccessControlAllowCredentials);
+__hyper__serde!(AccessControlAllowCredentials);
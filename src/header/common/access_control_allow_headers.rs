@@ -31,7 +31,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &AccessControlAllowHeaders(vec![Ascii::new("date".to_owned())])
+    ///     &AccessControlAllowHeaders(vec![Ascii::new("date".to_owned())].into())
     /// );
     /// # }
     /// ```
@@ -51,7 +51,7 @@ header! {
     ///     &AccessControlAllowHeaders(vec![
     ///         Ascii::new("accept-language".to_owned()),
     ///         Ascii::new("date".to_owned()),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
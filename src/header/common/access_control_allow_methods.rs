@@ -26,7 +26,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &AccessControlAllowMethods(vec![Method::Get])
+    ///     &AccessControlAllowMethods(vec![Method::Get].into())
     /// );
     /// ```
     ///
@@ -41,8 +41,8 @@ header! {
     ///         Method::Get,
     ///         Method::Post,
     ///         Method::Patch,
-    ///         Method::Extension("COPY".to_owned()),
-    ///     ])
+    ///         Method::Copy,
+    ///     ].into())
     /// );
     /// ```
     (AccessControlAllowMethods, "Access-Control-Allow-Methods") => (Method)*
@@ -1,4 +1,5 @@
 use header::{Header, RawLike};
+use std::borrow::Cow;
 use std::fmt::{self, Display};
 use std::str;
 
@@ -45,7 +46,7 @@ use std::str;
 ///
 /// let mut headers = http::HeaderMap::new();
 /// headers.encode(
-///     &AccessControlAllowOrigin::Value("http://hyper.rs".to_owned())
+///     &AccessControlAllowOrigin::Value("http://hyper.rs".into())
 /// );
 /// ```
 #[derive(Clone, PartialEq, Debug)]
@@ -55,7 +56,7 @@ pub enum AccessControlAllowOrigin {
     /// A hidden origin
     Null,
     /// Allow one particular origin
-    Value(String),
+    Value(Cow<'static, str>),
 }
 
 impl Header for AccessControlAllowOrigin {
@@ -71,10 +72,10 @@ impl Header for AccessControlAllowOrigin {
             Ok(match line {
                 b"*" => AccessControlAllowOrigin::Any,
                 b"null" => AccessControlAllowOrigin::Null,
-                _ => AccessControlAllowOrigin::Value(str::from_utf8(line)?.into()),
+                _ => AccessControlAllowOrigin::Value(str::from_utf8(line)?.to_owned().into()),
             })
         } else {
-            Err(::Error::Header)
+            Err(::header::parsing::not_one(raw))
         }
     }
 
@@ -103,3 +104,7 @@ mod test_access_control_allow_origin {
 }
 
 standard_header!(AccessControlAllowOrigin, ACCESS_CONTROL_ALLOW_ORIGIN);
+
+__hyper__try_from!(AccessControlAllowOrigin);
+__hyper__from_str!(AccessControlAllowOrigin);
+__hyper__serde!(AccessControlAllowOrigin);
@@ -0,0 +1,100 @@
+use header::{Header, RawLike};
+use std::fmt::{self, Display};
+use std::str;
+use unicase;
+
+/// `Access-Control-Allow-Private-Network` header, part of
+/// [Private Network Access](https://wicg.github.io/private-network-access/#headers)
+///
+/// > The Access-Control-Allow-Private-Network HTTP response header indicates
+/// > whether the response to a preflight request allows the actual request
+/// > to be made from a less private network.
+///
+/// # ABNF
+///
+/// ```text
+/// Access-Control-Allow-Private-Network: "Access-Control-Allow-Private-Network" ":" "true"
+/// ```
+///
+/// Like `AccessControlAllowCredentials`, the header struct does not accept
+/// any values, since only `true` is acceptable.
+///
+/// # Example values
+/// * "true"
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// # fn main() {
+///
+/// use hyperx::header::{AccessControlAllowPrivateNetwork, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&AccessControlAllowPrivateNetwork);
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AccessControlAllowPrivateNetwork;
+
+const ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK_TRUE: &str = "true";
+
+impl Header for AccessControlAllowPrivateNetwork {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Access-Control-Allow-Private-Network";
+        NAME
+    }
+
+    __hyper__http_name!("Access-Control-Allow-Private-Network");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AccessControlAllowPrivateNetwork>
+    where
+        T: RawLike<'a>,
+    {
+        if let Some(line) = raw.one() {
+            let text = unsafe {
+                // safe because:
+                // 1. we don't actually care if it's utf8, we just want to
+                //    compare the bytes with the "case" normalized. If it's not
+                //    utf8, then the byte comparison will fail, and we'll return
+                //    None. No big deal.
+                str::from_utf8_unchecked(line)
+            };
+            if unicase::eq_ascii(text, ACCESS_CONTROL_ALLOW_PRIVATE_NETWORK_TRUE) {
+                return Ok(AccessControlAllowPrivateNetwork);
+            }
+        }
+        Err(::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl Display for AccessControlAllowPrivateNetwork {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str("true")
+    }
+}
+
+#[cfg(test)]
+mod test_access_control_allow_private_network {
+    use super::AccessControlAllowPrivateNetwork as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(works, vec![b"true"], Some(HeaderField));
+    test_header!(ignores_case, [b"True"]);
+    test_header!(not_bool, vec![b"false"], None);
+    test_header!(only_single, vec![b"true", b"true"], None);
+    test_header!(
+        no_gibberish,
+        vec!["\u{645}\u{631}\u{62d}\u{628}\u{627}".as_bytes()],
+        None
+    );
+}
+
+__hyper__try_from!(AccessControlAllowPrivateNetwork);
+__hyper__from_str!(AccessControlAllowPrivateNetwork);
+__hyper__serde!(AccessControlAllowPrivateNetwork);
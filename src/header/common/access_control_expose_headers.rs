@@ -33,7 +33,7 @@ header! {
     ///     &AccessControlExposeHeaders(vec![
     ///         Ascii::new("etag".to_owned()),
     ///         Ascii::new("content-length".to_owned())
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
@@ -53,7 +53,7 @@ header! {
     ///     &AccessControlExposeHeaders(vec![
     ///         Ascii::new("etag".to_owned()),
     ///         Ascii::new("content-length".to_owned())
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
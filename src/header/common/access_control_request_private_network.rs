@@ -0,0 +1,97 @@
+use header::{Header, RawLike};
+use std::fmt::{self, Display};
+use std::str;
+use unicase;
+
+/// `Access-Control-Request-Private-Network` header, part of
+/// [Private Network Access](https://wicg.github.io/private-network-access/#headers)
+///
+/// > The Access-Control-Request-Private-Network HTTP request header is sent
+/// > in a preflight request when the actual request targets a more private
+/// > network than its initiator.
+///
+/// # ABNF
+///
+/// ```text
+/// Access-Control-Request-Private-Network: "Access-Control-Request-Private-Network" ":" "true"
+/// ```
+///
+/// # Example values
+/// * "true"
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// # fn main() {
+///
+/// use hyperx::header::{AccessControlRequestPrivateNetwork, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&AccessControlRequestPrivateNetwork);
+/// # }
+/// ```
+#[derive(Clone, PartialEq, Debug)]
+pub struct AccessControlRequestPrivateNetwork;
+
+const ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK_TRUE: &str = "true";
+
+impl Header for AccessControlRequestPrivateNetwork {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Access-Control-Request-Private-Network";
+        NAME
+    }
+
+    __hyper__http_name!("Access-Control-Request-Private-Network");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AccessControlRequestPrivateNetwork>
+    where
+        T: RawLike<'a>,
+    {
+        if let Some(line) = raw.one() {
+            let text = unsafe {
+                // safe because:
+                // 1. we don't actually care if it's utf8, we just want to
+                //    compare the bytes with the "case" normalized. If it's not
+                //    utf8, then the byte comparison will fail, and we'll return
+                //    None. No big deal.
+                str::from_utf8_unchecked(line)
+            };
+            if unicase::eq_ascii(text, ACCESS_CONTROL_REQUEST_PRIVATE_NETWORK_TRUE) {
+                return Ok(AccessControlRequestPrivateNetwork);
+            }
+        }
+        Err(::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl Display for AccessControlRequestPrivateNetwork {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter) -> ::std::fmt::Result {
+        f.write_str("true")
+    }
+}
+
+#[cfg(test)]
+mod test_access_control_request_private_network {
+    use super::AccessControlRequestPrivateNetwork as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(works, vec![b"true"], Some(HeaderField));
+    test_header!(ignores_case, [b"True"]);
+    test_header!(not_bool, vec![b"false"], None);
+    test_header!(only_single, vec![b"true", b"true"], None);
+    test_header!(
+        no_gibberish,
+        vec!["\u{645}\u{631}\u{62d}\u{628}\u{627}".as_bytes()],
+        None
+    );
+}
+
+__hyper__try_from!(AccessControlRequestPrivateNetwork);
+__hyper__from_str!(AccessControlRequestPrivateNetwork);
+__hyper__serde!(AccessControlRequestPrivateNetwork);
@@ -28,7 +28,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &Allow(vec![Method::Get])
+    ///     &Allow(vec![Method::Get].into())
     /// );
     /// ```
     ///
@@ -43,8 +43,8 @@ header! {
     ///         Method::Get,
     ///         Method::Post,
     ///         Method::Patch,
-    ///         Method::Extension("COPY".to_owned()),
-    ///     ])
+    ///         Method::Copy,
+    ///     ].into())
     /// );
     /// ```
     (Allow, "Allow") => (Method)*
@@ -54,12 +54,12 @@ header! {
         test_header!(
             test1,
             vec![b"GET, HEAD, PUT"],
-            Some(HeaderField(vec![Method::Get, Method::Head, Method::Put])));
+            Some(HeaderField(smallvec![Method::Get, Method::Head, Method::Put])));
         // Own tests
         test_header!(
             test2,
             vec![b"OPTIONS, GET, PUT, POST, DELETE, HEAD, TRACE, CONNECT, PATCH, fOObAr"],
-            Some(HeaderField(vec![
+            Some(HeaderField(smallvec![
                 Method::Options,
                 Method::Get,
                 Method::Put,
@@ -69,11 +69,11 @@ header! {
                 Method::Trace,
                 Method::Connect,
                 Method::Patch,
-                Method::Extension("fOObAr".to_owned())])));
+                Method::Extension("fOObAr".into())])));
         test_header!(
             test3,
             vec![b""],
-            Some(HeaderField(Vec::<Method>::new())));
+            Some(HeaderField(::header::HeaderVec::<Method>::new())));
     }
 }
 
@@ -0,0 +1,250 @@
+use std::fmt;
+use std::str::{self, FromStr};
+
+use header::parsing::{self, fmt_quoted, from_comma_delimited, parse_parameter, split_delimited};
+use header::{Header, RawLike};
+
+use super::ratelimit::parse_integer;
+
+/// `Alt-Svc` header, defined in
+/// [RFC7838](https://www.rfc-editor.org/rfc/rfc7838#section-3)
+///
+/// The `Alt-Svc` response header advertises alternative services: other
+/// protocols, hosts or ports through which the same origin can be
+/// reached, such as HTTP/3 over QUIC. The value `clear` invalidates all
+/// alternative services previously advertised for the origin.
+///
+/// # ABNF
+///
+/// ```text
+/// Alt-Svc       = clear / 1#alt-value
+/// clear         = %s"clear"
+/// alt-value     = alternative *( OWS ";" OWS parameter )
+/// alternative   = protocol-id "=" alt-authority
+/// protocol-id   = token
+/// alt-authority = quoted-string
+/// ```
+///
+/// # Example values
+/// * `h3=":443"; ma=86400`
+/// * `h2="alt.example.com:8443"; ma=3600; persist=1, h3=":443"`
+/// * `clear`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::{AltService, AltSvc, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&AltSvc::Services(vec![
+///     AltService::new("h3", 443).max_age(86400),
+///     AltService::new("h2", 8443).host("alt.example.com").persist(),
+/// ]));
+/// assert_eq!(
+///     headers["alt-svc"],
+///     "h3=\":443\"; ma=86400, h2=\"alt.example.com:8443\"; persist=1"
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum AltSvc {
+    /// `clear`, invalidating all alternative services of the origin.
+    Clear,
+    /// The advertised alternative services, in order of preference.
+    Services(Vec<AltService>),
+}
+
+impl Header for AltSvc {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Alt-Svc";
+        NAME
+    }
+
+    __hyper__http_name!("Alt-Svc");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<AltSvc>
+    where
+        T: RawLike<'a>,
+    {
+        if let Some(line) = raw.one() {
+            if str::from_utf8(line).map(str::trim) == Ok("clear") {
+                return Ok(AltSvc::Clear);
+            }
+        }
+        let services = from_comma_delimited(raw)?;
+        if services.is_empty() {
+            return Err(::Error::header(::HeaderErrorKind::Syntax));
+        }
+        Ok(AltSvc::Services(services))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for AltSvc {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            AltSvc::Clear => f.write_str("clear"),
+            AltSvc::Services(ref services) => parsing::fmt_comma_delimited(f, services),
+        }
+    }
+}
+
+/// An alternative service of the `Alt-Svc` header.
+///
+/// Parameters other than `ma` and `persist` are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AltService {
+    /// The ALPN protocol identifier, such as `h3` or `h2`.
+    pub protocol: String,
+    /// The host of the alternative service, or `None` for the host of the
+    /// origin.
+    pub host: Option<String>,
+    /// The port of the alternative service.
+    pub port: u16,
+    /// The number of seconds the alternative service is fresh for, `ma`,
+    /// if given. Defaults to 24 hours.
+    pub max_age: Option<u64>,
+    /// Whether the alternative service survives network changes,
+    /// `persist=1`.
+    pub persist: bool,
+}
+
+impl AltService {
+    /// Create a new alternative service, on the host of the origin.
+    pub fn new<S: Into<String>>(protocol: S, port: u16) -> AltService {
+        AltService {
+            protocol: protocol.into(),
+            host: None,
+            port,
+            max_age: None,
+            persist: false,
+        }
+    }
+
+    /// Set the host of the alternative service.
+    pub fn host<S: Into<String>>(mut self, host: S) -> AltService {
+        self.host = Some(host.into());
+        self
+    }
+
+    /// Set the number of seconds the alternative service is fresh for.
+    pub fn max_age(mut self, seconds: u64) -> AltService {
+        self.max_age = Some(seconds);
+        self
+    }
+
+    /// Set the `persist` parameter.
+    pub fn persist(mut self) -> AltService {
+        self.persist = true;
+        self
+    }
+}
+
+impl fmt::Display for AltService {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}=", self.protocol)?;
+        let authority = format!("{}:{}", self.host.as_deref().unwrap_or(""), self.port);
+        fmt_quoted(f, &authority)?;
+        if let Some(max_age) = self.max_age {
+            write!(f, "; ma={}", max_age)?;
+        }
+        if self.persist {
+            f.write_str("; persist=1")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for AltService {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<AltService> {
+        let mut parts = split_delimited(s, ';');
+        let (protocol, authority) = match parse_parameter(parts.next().unwrap_or(""))? {
+            (protocol, Some(authority)) => (protocol, authority),
+            _ => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+        };
+        let (host, port) = authority
+            .rsplit_once(':')
+            .ok_or_else(|| ::Error::header(::HeaderErrorKind::Syntax))?;
+        if port.is_empty() || port.len() > 5 || !port.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(::Error::header(::HeaderErrorKind::Syntax));
+        }
+        let port = port
+            .parse()
+            .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Syntax, e))?;
+        let mut service = AltService::new(protocol, port);
+        if !host.is_empty() {
+            service.host = Some(host.to_owned());
+        }
+        for part in parts {
+            match parse_parameter(part)? {
+                ("ma", Some(value)) => service.max_age = Some(parse_integer(&value)?),
+                ("persist", Some(value)) => service.persist = value == "1",
+                _ => {}
+            }
+        }
+        Ok(service)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+
+    fn parse(s: &str) -> ::Result<AltSvc> {
+        let r: Raw = s.into();
+        AltSvc::parse_header(&r)
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("clear").unwrap(), AltSvc::Clear);
+        assert_eq!(
+            parse("h3=\":443\" ; ma=86400, h2=\"alt.example.com:8443\";persist=1;x=y").unwrap(),
+            AltSvc::Services(vec![
+                AltService::new("h3", 443).max_age(86400),
+                AltService::new("h2", 8443)
+                    .host("alt.example.com")
+                    .persist(),
+            ])
+        );
+        assert_eq!(
+            parse("h2=\"[::1]:443\"; persist=0").unwrap(),
+            AltSvc::Services(vec![AltService::new("h2", 443).host("[::1]")])
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("h3").is_err());
+        assert!("h3=\"443\"".parse::<AltService>().is_err());
+        assert!("h3=\":\"".parse::<AltService>().is_err());
+        assert!("h3=\":65536\"".parse::<AltService>().is_err());
+        assert!("h3=\":443\"; ma=-1".parse::<AltService>().is_err());
+        assert!("=\":443\"".parse::<AltService>().is_err());
+    }
+
+    #[test]
+    fn test_fmt() {
+        assert_eq!(AltSvc::Clear.to_string(), "clear");
+        let alt_svc = AltSvc::Services(vec![
+            AltService::new("h3", 443),
+            AltService::new("h2", 80).host("a\"b").max_age(0).persist(),
+        ]);
+        let s = alt_svc.to_string();
+        assert_eq!(s, "h3=\":443\", h2=\"a\\\"b:80\"; ma=0; persist=1");
+        assert_eq!(parse(&s).unwrap(), alt_svc);
+    }
+}
+
+standard_header!(AltSvc, ALT_SVC);
+
+__hyper__try_from!(AltSvc);
+__hyper__serde!(AltSvc);
@@ -1,6 +1,6 @@
-use base64::{decode, encode};
-use header::{Header, RawLike};
+use header::{parsing, Base64, Header, RawLike};
 use std::any::Any;
+use std::borrow::Cow;
 use std::fmt::{self, Display};
 use std::ops::{Deref, DerefMut};
 use std::str::{from_utf8, FromStr};
@@ -40,8 +40,8 @@ use std::str::{from_utf8, FromStr};
 /// headers.encode(
 ///    &Authorization(
 ///        Basic {
-///            username: "Aladdin".to_owned(),
-///            password: Some("open sesame".to_owned())
+///            username: "Aladdin".into(),
+///            password: Some("open sesame".into())
 ///        }
 ///    )
 /// );
@@ -54,7 +54,7 @@ use std::str::{from_utf8, FromStr};
 /// headers.encode(
 ///    &Authorization(
 ///        Bearer {
-///            token: "QWxhZGRpbjpvcGVuIHNlc2FtZQ".to_owned()
+///            token: "QWxhZGRpbjpvcGVuIHNlc2FtZQ".into()
 ///        }
 ///    )
 /// );
@@ -85,6 +85,8 @@ where
         NAME
     }
 
+    __hyper__http_name!("Authorization");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Authorization<S>>
     where
         T: RawLike<'a>,
@@ -95,19 +97,19 @@ where
                 if header.starts_with(scheme) && header.len() > scheme.len() + 1 {
                     match header[scheme.len() + 1..].parse::<S>().map(Authorization) {
                         Ok(h) => Ok(h),
-                        Err(_) => Err(::Error::Header),
+                        Err(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
                     }
                 } else {
-                    Err(::Error::Header)
+                    Err(::Error::header(::HeaderErrorKind::Token))
                 }
             } else {
                 match header.parse::<S>().map(Authorization) {
                     Ok(h) => Ok(h),
-                    Err(_) => Err(::Error::Header),
+                    Err(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
                 }
             }
         } else {
-            Err(::Error::Header)
+            Err(::header::parsing::not_one(raw))
         }
     }
 
@@ -149,29 +151,50 @@ impl Scheme for String {
 #[derive(Clone, PartialEq, Debug)]
 pub struct Basic {
     /// The username as a possibly empty string
-    pub username: String,
+    pub username: Cow<'static, str>,
     /// The password. `None` if the `:` delimiter character was not
     /// part of the parsed input. Note: A compliant client MUST
     /// always send a password (which may be the empty string).
-    pub password: Option<String>,
+    pub password: Option<Cow<'static, str>>,
 }
 
-impl Scheme for Basic {
-    fn scheme() -> Option<&'static str> {
-        Some("Basic")
+impl Basic {
+    /// Decode credentials encoded with the given base64 alphabet and
+    /// padding, for schemes which differ from the standard alphabet with
+    /// padding of `FromStr`.
+    pub fn decode_with(s: &str, base64: Base64) -> ::Result<Basic> {
+        let text = String::from_utf8(base64.decode(s)?)
+            .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Utf8, e))?;
+        let parts = &mut text.split(':');
+        let user = match parts.next() {
+            Some(part) => part.to_owned().into(),
+            None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+        };
+        let password = parts.next().map(|part| part.to_owned().into());
+        Ok(Basic {
+            username: user,
+            password,
+        })
     }
 
-    fn fmt_scheme(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        //FIXME: serialize::base64 could use some Debug implementation, so
-        //that we don't have to allocate a new string here just to write it
-        //to the formatter.
-        let mut text = self.username.clone();
+    /// Encode the credentials with the given base64 alphabet and padding.
+    pub fn encode_with(&self, base64: Base64) -> String {
+        let mut text = self.username.to_string();
         text.push(':');
         if let Some(ref pass) = self.password {
             text.push_str(&pass[..]);
         }
+        base64.encode(&text)
+    }
+}
+
+impl Scheme for Basic {
+    fn scheme() -> Option<&'static str> {
+        Some("Basic")
+    }
 
-        f.write_str(&encode(&text))
+    fn fmt_scheme(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.encode_with(Base64::STANDARD))
     }
 }
 
@@ -179,32 +202,43 @@ impl Scheme for Basic {
 impl FromStr for Basic {
     type Err = ::Error;
     fn from_str(s: &str) -> ::Result<Basic> {
-        match decode(s) {
-            Ok(decoded) => match String::from_utf8(decoded) {
-                Ok(text) => {
-                    let parts = &mut text.split(':');
-                    let user = match parts.next() {
-                        Some(part) => part.to_owned(),
-                        None => return Err(::Error::Header),
-                    };
-                    let password = parts.next().map(|part| part.to_owned());
-                    Ok(Basic {
-                        username: user,
-                        password,
-                    })
-                }
-                Err(_) => Err(::Error::Header),
-            },
-            Err(_) => Err(::Error::Header),
-        }
+        Basic::decode_with(s, Base64::STANDARD)
     }
 }
 
 #[derive(Clone, PartialEq, Debug)]
 ///Token holder for Bearer Authentication, most often seen with oauth
+///
+/// When parsing strictly, the token must be a `b64token` as defined in
+/// [RFC6750](https://tools.ietf.org/html/rfc6750#section-2.1).
 pub struct Bearer {
     ///Actual bearer token as a string
-    pub token: String,
+    pub token: Cow<'static, str>,
+}
+
+impl Bearer {
+    /// Create a token of bytes encoded with the given base64 alphabet and
+    /// padding.
+    pub fn encode_with(bytes: &[u8], base64: Base64) -> Bearer {
+        Bearer {
+            token: base64.encode(bytes).into(),
+        }
+    }
+
+    /// Decode the token as bytes, with the given base64 alphabet and
+    /// padding.
+    pub fn decode_with(&self, base64: Base64) -> ::Result<Vec<u8>> {
+        base64.decode(self.token.as_bytes())
+    }
+
+    /// Return true if the token is a valid `b64token`.
+    pub fn is_valid(&self) -> bool {
+        let token = self.token.trim_end_matches('=');
+        !token.is_empty()
+            && token.bytes().all(|b| {
+                b.is_ascii_alphanumeric() || matches!(b, b'-' | b'.' | b'_' | b'~' | b'+' | b'/')
+            })
+    }
 }
 
 impl Scheme for Bearer {
@@ -220,9 +254,141 @@ impl Scheme for Bearer {
 impl FromStr for Bearer {
     type Err = ::Error;
     fn from_str(s: &str) -> ::Result<Bearer> {
-        Ok(Bearer {
-            token: s.to_owned(),
-        })
+        let bearer = Bearer {
+            token: s.to_owned().into(),
+        };
+        if parsing::is_strict() && !bearer.is_valid() {
+            return Err(::Error::header(::HeaderErrorKind::Token));
+        }
+        Ok(bearer)
+    }
+}
+
+/// Credentials for Digest Access Authentication, defined in
+/// [RFC 7616](https://www.rfc-editor.org/rfc/rfc7616#section-3.4)
+///
+/// Values are kept as sent; see the [`digest`](../digest/index.html) module
+/// for computing and verifying the `response`.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Digest {
+    /// The user name, or its hash if `userhash` is true.
+    pub username: String,
+    /// The protection space, as of the challenge.
+    pub realm: String,
+    /// The server nonce, as of the challenge.
+    pub nonce: String,
+    /// The request-target of the request.
+    pub uri: String,
+    /// The hex-encoded response digest.
+    pub response: String,
+    /// The algorithm name, e.g. `SHA-256`. `None` implies `MD5`.
+    pub algorithm: Option<String>,
+    /// The client nonce, sent if `qop` is.
+    pub cnonce: Option<String>,
+    /// The opaque value of the challenge, returned unchanged.
+    pub opaque: Option<String>,
+    /// The quality of protection, `auth` or `auth-int`.
+    pub qop: Option<String>,
+    /// The nonce count, sent if `qop` is.
+    pub nc: Option<u32>,
+    /// Whether `username` is hashed.
+    pub userhash: bool,
+}
+
+impl Scheme for Digest {
+    fn scheme() -> Option<&'static str> {
+        Some("Digest")
+    }
+
+    fn fmt_scheme(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("username=")?;
+        parsing::fmt_quoted(f, &self.username)?;
+        f.write_str(", realm=")?;
+        parsing::fmt_quoted(f, &self.realm)?;
+        f.write_str(", uri=")?;
+        parsing::fmt_quoted(f, &self.uri)?;
+        if let Some(ref algorithm) = self.algorithm {
+            write!(f, ", algorithm={}", algorithm)?;
+        }
+        f.write_str(", nonce=")?;
+        parsing::fmt_quoted(f, &self.nonce)?;
+        if let Some(nc) = self.nc {
+            write!(f, ", nc={:08x}", nc)?;
+        }
+        if let Some(ref cnonce) = self.cnonce {
+            f.write_str(", cnonce=")?;
+            parsing::fmt_quoted(f, cnonce)?;
+        }
+        if let Some(ref qop) = self.qop {
+            write!(f, ", qop={}", qop)?;
+        }
+        f.write_str(", response=")?;
+        parsing::fmt_quoted(f, &self.response)?;
+        if let Some(ref opaque) = self.opaque {
+            f.write_str(", opaque=")?;
+            parsing::fmt_quoted(f, opaque)?;
+        }
+        if self.userhash {
+            f.write_str(", userhash=true")?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the comma separated auth-params of Digest credentials; unknown
+/// parameters are ignored
+impl FromStr for Digest {
+    type Err = ::Error;
+    fn from_str(s: &str) -> ::Result<Digest> {
+        let syntax = || ::Error::header(::HeaderErrorKind::Syntax);
+        let mut digest = Digest::default();
+        let (mut username, mut realm, mut nonce, mut uri, mut response) =
+            (false, false, false, false, false);
+        for param in parsing::split_comma_delimited(s) {
+            let (name, value) = match parsing::parse_parameter(param)? {
+                (name, Some(value)) => (name, value.into_owned()),
+                _ => return Err(syntax()),
+            };
+            match name {
+                n if n.eq_ignore_ascii_case("username") => {
+                    username = true;
+                    digest.username = value;
+                }
+                n if n.eq_ignore_ascii_case("realm") => {
+                    realm = true;
+                    digest.realm = value;
+                }
+                n if n.eq_ignore_ascii_case("nonce") => {
+                    nonce = true;
+                    digest.nonce = value;
+                }
+                n if n.eq_ignore_ascii_case("uri") => {
+                    uri = true;
+                    digest.uri = value;
+                }
+                n if n.eq_ignore_ascii_case("response") => {
+                    response = true;
+                    digest.response = value;
+                }
+                n if n.eq_ignore_ascii_case("algorithm") => digest.algorithm = Some(value),
+                n if n.eq_ignore_ascii_case("cnonce") => digest.cnonce = Some(value),
+                n if n.eq_ignore_ascii_case("opaque") => digest.opaque = Some(value),
+                n if n.eq_ignore_ascii_case("qop") => digest.qop = Some(value),
+                n if n.eq_ignore_ascii_case("nc") => {
+                    let nc = u32::from_str_radix(&value, 16).map_err(|_| syntax())?;
+                    digest.nc = Some(nc);
+                }
+                n if n.eq_ignore_ascii_case("userhash") => {
+                    digest.userhash = value.eq_ignore_ascii_case("true")
+                }
+                _ => {}
+            }
+        }
+        if username && realm && nonce && uri && response {
+            Ok(digest)
+        } else {
+            Err(syntax())
+        }
     }
 }
 
@@ -246,10 +412,86 @@ where
     }
 }
 
+impl<'v, S> ::std::convert::TryFrom<&'v ::http::header::HeaderValue> for Authorization<S>
+where
+    S: Scheme + Any,
+{
+    type Error = ::Error;
+
+    #[inline]
+    fn try_from(value: &'v ::http::header::HeaderValue) -> ::Result<Self> {
+        Self::parse_header(&::header::Raw::from(value))
+    }
+}
+
+impl<S> ::std::str::FromStr for Authorization<S>
+where
+    S: Scheme + Any,
+{
+    type Err = ::Error;
+
+    #[inline]
+    fn from_str(s: &str) -> ::Result<Self> {
+        Self::parse_header(&::header::Raw::from(s))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S> ::serde::Serialize for Authorization<S>
+where
+    S: Scheme + Any,
+{
+    #[inline]
+    fn serialize<Se>(&self, serializer: Se) -> ::std::result::Result<Se::Ok, Se::Error>
+    where
+        Se: ::serde::Serializer,
+    {
+        ::header::__private::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S> ::serde::Deserialize<'de> for Authorization<S>
+where
+    S: Scheme + Any,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::header::__private::deserialize(deserializer)
+    }
+}
+
+impl<'h, S> ::std::convert::TryFrom<&'h Authorization<S>> for ::http::header::HeaderValue
+where
+    S: Scheme + Any,
+{
+    type Error = ::http::header::InvalidHeaderValue;
+
+    #[inline]
+    fn try_from(header: &'h Authorization<S>) -> ::std::result::Result<Self, Self::Error> {
+        ::header::__private::to_value(header)
+    }
+}
+
+impl<S> ::std::convert::TryFrom<Authorization<S>> for ::http::header::HeaderValue
+where
+    S: Scheme + Any,
+{
+    type Error = ::http::header::InvalidHeaderValue;
+
+    #[inline]
+    fn try_from(header: Authorization<S>) -> ::std::result::Result<Self, Self::Error> {
+        ::header::__private::to_value(&header)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::super::{Header, Raw};
-    use super::{Authorization, Basic, Bearer};
+    use super::{Authorization, Base64, Basic, Bearer, Digest};
 
     #[cfg(feature = "headers")]
     use super::super::super::Headers;
@@ -277,8 +519,8 @@ mod tests {
     fn test_basic_auth() {
         let mut headers = Headers::new();
         headers.set(Authorization(Basic {
-            username: "Aladdin".to_owned(),
-            password: Some("open sesame".to_owned()),
+            username: "Aladdin".into(),
+            password: Some("open sesame".into()),
         }));
         assert_eq!(
             headers.to_string(),
@@ -291,7 +533,7 @@ mod tests {
     fn test_basic_auth_no_password() {
         let mut headers = Headers::new();
         headers.set(Authorization(Basic {
-            username: "Aladdin".to_owned(),
+            username: "Aladdin".into(),
             password: None,
         }));
         assert_eq!(
@@ -305,7 +547,7 @@ mod tests {
         let r: Raw = b"Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".as_ref().into();
         let auth: Authorization<Basic> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.username, "Aladdin");
-        assert_eq!(auth.0.password, Some("open sesame".to_owned()));
+        assert_eq!(auth.0.password.as_deref(), Some("open sesame"));
     }
 
     #[test]
@@ -313,7 +555,7 @@ mod tests {
         let r: Raw = b"Basic QWxhZGRpbjo=".as_ref().into();
         let auth: Authorization<Basic> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.username, "Aladdin");
-        assert_eq!(auth.0.password, Some("".to_owned()));
+        assert_eq!(auth.0.password.as_deref(), Some(""));
     }
 
     #[cfg(feature = "headers")]
@@ -321,7 +563,7 @@ mod tests {
     fn test_bearer_auth() {
         let mut headers = Headers::new();
         headers.set(Authorization(Bearer {
-            token: "fpKL54jvWmEGVoRdCNjG".to_owned(),
+            token: "fpKL54jvWmEGVoRdCNjG".into(),
         }));
         assert_eq!(
             headers.to_string(),
@@ -335,4 +577,73 @@ mod tests {
         let auth: Authorization<Bearer> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.token, "fpKL54jvWmEGVoRdCNjG");
     }
+
+    #[test]
+    fn test_base64_with() {
+        let basic = Basic {
+            username: "\u{fb}".into(),
+            password: Some("?".into()),
+        };
+        assert_eq!(basic.encode_with(Base64::STANDARD), "w7s6Pw==");
+        assert_eq!(basic.encode_with(Base64::URL_SAFE_NO_PAD), "w7s6Pw");
+        assert_eq!(
+            Basic::decode_with("w7s6Pw", Base64::URL_SAFE_NO_PAD).unwrap(),
+            basic
+        );
+        assert!("w7s6Pw".parse::<Basic>().is_err());
+
+        let bearer = Bearer::encode_with(b"\xfb\xff", Base64::URL_SAFE);
+        assert_eq!(bearer.token, "-_8=");
+        assert!(bearer.is_valid());
+        assert_eq!(bearer.decode_with(Base64::URL_SAFE).unwrap(), b"\xfb\xff");
+        assert!(bearer.decode_with(Base64::STANDARD).is_err());
+    }
+
+    #[test]
+    fn test_bearer_strict() {
+        use header::parsing::ParseConfig;
+
+        let strict = ParseConfig::new().strict(true);
+        let r: Raw = b"Bearer mF_9.B5f-4.1JqM".as_ref().into();
+        assert!(strict.parse::<Authorization<Bearer>, _>(&r).is_ok());
+        let r: Raw = b"Bearer a=b".as_ref().into();
+        assert!(strict.parse::<Authorization<Bearer>, _>(&r).is_err());
+        assert!(ParseConfig::new()
+            .parse::<Authorization<Bearer>, _>(&r)
+            .is_ok());
+    }
+
+    #[test]
+    fn test_digest_auth() {
+        let r: Raw = b"Digest username=\"Mufasa\", realm=\"http-auth@example.org\", \
+            uri=\"/dir/index.html\", algorithm=SHA-256, nonce=\"7ypf\", nc=0000000a, \
+            cnonce=\"f2/wE4q7\", qop=auth, response=\"7539\", opaque=\"FQhe\""
+            .as_ref()
+            .into();
+        let auth: Authorization<Digest> = Header::parse_header(&r).unwrap();
+        assert_eq!(auth.0.username, "Mufasa");
+        assert_eq!(auth.0.algorithm, Some("SHA-256".to_owned()));
+        assert_eq!(auth.0.nc, Some(10));
+        assert_eq!(auth.0.qop, Some("auth".to_owned()));
+        assert!(!auth.0.userhash);
+        assert_eq!(
+            auth.to_string(),
+            "Digest username=\"Mufasa\", realm=\"http-auth@example.org\", \
+             uri=\"/dir/index.html\", algorithm=SHA-256, nonce=\"7ypf\", nc=0000000a, \
+             cnonce=\"f2/wE4q7\", qop=auth, response=\"7539\", opaque=\"FQhe\""
+        );
+    }
+
+    #[test]
+    fn test_digest_auth_parse_invalid() {
+        let r: Raw = b"Digest username=\"Mufasa\", realm=\"r\", nonce=\"n\""
+            .as_ref()
+            .into();
+        assert!(<Authorization<Digest> as Header>::parse_header(&r).is_err());
+        let r: Raw = b"Digest username=\"a\", realm=\"r\", nonce=\"n\", uri=\"/\", \
+            response=\"x\", nc=zz"
+            .as_ref()
+            .into();
+        assert!(<Authorization<Digest> as Header>::parse_header(&r).is_err());
+    }
 }
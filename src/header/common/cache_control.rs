@@ -1,4 +1,7 @@
-use header::parsing::{fmt_comma_delimited, from_comma_delimited};
+use header::parsing::{
+    check_limit, find_ignore_case, fmt_comma_delimited, fmt_quoted, from_comma_delimited, is_token,
+    unquote, Limit,
+};
 use header::{Header, RawLike};
 use std::fmt;
 use std::str::FromStr;
@@ -44,12 +47,12 @@ use std::str::FromStr;
 ///         CacheDirective::NoCache,
 ///         CacheDirective::Private,
 ///         CacheDirective::MaxAge(360u32),
-///         CacheDirective::Extension("foo".to_owned(),
-///                                   Some("bar".to_owned())),
+///         CacheDirective::Extension(Box::new("foo".to_owned()),
+///                                   Some(Box::new("bar".to_owned()))),
 ///     ])
 /// );
 /// ```
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub struct CacheControl(pub Vec<CacheDirective>);
 
 __hyper__deref!(CacheControl => Vec<CacheDirective>);
@@ -61,15 +64,18 @@ impl Header for CacheControl {
         NAME
     }
 
+    __hyper__http_name!("Cache-Control");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<CacheControl>
     where
         T: RawLike<'a>,
     {
-        let directives = from_comma_delimited(raw)?;
+        let directives: Vec<CacheDirective> = from_comma_delimited(raw)?;
+        check_limit(Limit::Directives, directives.len())?;
         if !directives.is_empty() {
             Ok(CacheControl(directives))
         } else {
-            Err(::Error::Header)
+            Err(::Error::header(::HeaderErrorKind::Empty))
         }
     }
 
@@ -85,7 +91,7 @@ impl fmt::Display for CacheControl {
 }
 
 /// `CacheControl` contains a list of these directives.
-#[derive(PartialEq, Clone, Debug)]
+#[derive(PartialEq, Eq, Hash, Clone, Debug)]
 pub enum CacheDirective {
     /// "no-cache"
     NoCache,
@@ -117,7 +123,10 @@ pub enum CacheDirective {
     SMaxAge(u32),
 
     /// Extension directives. Optionally include an argument.
-    Extension(String, Option<String>),
+    ///
+    /// The name and argument are boxed, so that lists of the standard
+    /// directives don't reserve the size of two `String`s per item.
+    Extension(Box<String>, Option<Box<String>>),
 }
 
 impl fmt::Display for CacheDirective {
@@ -141,7 +150,13 @@ impl fmt::Display for CacheDirective {
                 SMaxAge(secs) => return write!(f, "s-maxage={}", secs),
 
                 Extension(ref name, None) => &name[..],
-                Extension(ref name, Some(ref arg)) => return write!(f, "{}={}", name, arg),
+                Extension(ref name, Some(ref arg)) if is_token(arg) => {
+                    return write!(f, "{}={}", name, arg)
+                }
+                Extension(ref name, Some(ref arg)) => {
+                    write!(f, "{}=", name)?;
+                    return fmt_quoted(f, arg);
+                }
             },
             f,
         )
@@ -152,28 +167,40 @@ impl FromStr for CacheDirective {
     type Err = Option<<u32 as FromStr>::Err>;
     fn from_str(s: &str) -> Result<CacheDirective, Option<<u32 as FromStr>::Err>> {
         use self::CacheDirective::*;
-        match s {
-            "no-cache" => Ok(NoCache),
-            "no-store" => Ok(NoStore),
-            "no-transform" => Ok(NoTransform),
-            "only-if-cached" => Ok(OnlyIfCached),
-            "must-revalidate" => Ok(MustRevalidate),
-            "public" => Ok(Public),
-            "private" => Ok(Private),
-            "proxy-revalidate" => Ok(ProxyRevalidate),
-            "" => Err(None),
-            _ => match s.find('=') {
-                Some(idx) if idx + 1 < s.len() => match (&s[..idx], s[idx + 1..].trim_matches('"'))
-                {
-                    ("max-age", secs) => secs.parse().map(MaxAge).map_err(Some),
-                    ("max-stale", secs) => secs.parse().map(MaxStale).map_err(Some),
-                    ("min-fresh", secs) => secs.parse().map(MinFresh).map_err(Some),
-                    ("s-maxage", secs) => secs.parse().map(SMaxAge).map_err(Some),
-                    (left, right) => Ok(Extension(left.to_owned(), Some(right.to_owned()))),
-                },
-                Some(_) => Err(None),
-                None => Ok(Extension(s.to_owned(), None)),
-            },
+        // Directive names are case-insensitive
+        let flags = [
+            ("no-cache", NoCache),
+            ("no-store", NoStore),
+            ("no-transform", NoTransform),
+            ("only-if-cached", OnlyIfCached),
+            ("must-revalidate", MustRevalidate),
+            ("public", Public),
+            ("private", Private),
+            ("proxy-revalidate", ProxyRevalidate),
+        ];
+        if let Some(directive) = find_ignore_case(s, &flags) {
+            return Ok(directive);
+        }
+        match s.find('=') {
+            Some(idx) if is_token(&s[..idx]) && !s[idx + 1..].trim_matches('"').is_empty() => {
+                let (name, secs) = (&s[..idx], s[idx + 1..].trim_matches('"'));
+                if name.eq_ignore_ascii_case("max-age") {
+                    secs.parse().map(MaxAge).map_err(Some)
+                } else if name.eq_ignore_ascii_case("max-stale") {
+                    secs.parse().map(MaxStale).map_err(Some)
+                } else if name.eq_ignore_ascii_case("min-fresh") {
+                    secs.parse().map(MinFresh).map_err(Some)
+                } else if name.eq_ignore_ascii_case("s-maxage") {
+                    secs.parse().map(SMaxAge).map_err(Some)
+                } else {
+                    let arg =
+                        unquote(&s[idx + 1..]).map_or_else(|_| secs.to_owned(), |a| a.into_owned());
+                    Ok(Extension(Box::new(name.to_owned()), Some(Box::new(arg))))
+                }
+            }
+            Some(_) => Err(None),
+            None if is_token(s) => Ok(Extension(Box::new(s.to_owned()), None)),
+            None => Err(None),
         }
     }
 }
@@ -183,6 +210,19 @@ mod tests {
     use super::*;
     use header::{Header, Raw};
 
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert("no-cache, max-age=60".parse::<CacheControl>().unwrap());
+        assert!(set.contains(&CacheControl(vec![
+            CacheDirective::NoCache,
+            CacheDirective::MaxAge(60)
+        ])));
+        assert!(!set.contains(&CacheControl(vec![CacheDirective::NoCache])));
+    }
+
     #[test]
     fn test_parse_multiple_headers() {
         let r: Raw = vec![b"no-cache".to_vec(), b"private".to_vec()].into();
@@ -209,6 +249,20 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_parse_case_insensitive() {
+        let r: Raw = vec![b"No-Cache, MAX-AGE=100, X-Ext".to_vec()].into();
+        let cache = Header::parse_header(&r);
+        assert_eq!(
+            cache.ok(),
+            Some(CacheControl(vec![
+                CacheDirective::NoCache,
+                CacheDirective::MaxAge(100),
+                CacheDirective::Extension(Box::new("X-Ext".to_owned()), None)
+            ]))
+        )
+    }
+
     #[test]
     fn test_parse_quote_form() {
         let r: Raw = vec![b"max-age=\"200\"".to_vec()].into();
@@ -219,6 +273,11 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_directive_size() {
+        assert!(::std::mem::size_of::<CacheDirective>() <= 3 * ::std::mem::size_of::<usize>());
+    }
+
     #[test]
     fn test_parse_extension() {
         let r: Raw = vec![b"foo, bar=baz".to_vec()].into();
@@ -226,17 +285,46 @@ mod tests {
         assert_eq!(
             cache.ok(),
             Some(CacheControl(vec![
-                CacheDirective::Extension("foo".to_owned(), None),
-                CacheDirective::Extension("bar".to_owned(), Some("baz".to_owned()))
+                CacheDirective::Extension(Box::new("foo".to_owned()), None),
+                CacheDirective::Extension(
+                    Box::new("bar".to_owned()),
+                    Some(Box::new("baz".to_owned()))
+                )
             ]))
         )
     }
 
+    #[test]
+    fn test_parse_quoted_extension() {
+        let r: Raw = vec![br#"foo="a, \"b\"", bar="baz""#.to_vec()].into();
+        let cache = CacheControl::parse_header(&r).unwrap();
+        assert_eq!(
+            cache,
+            CacheControl(vec![
+                CacheDirective::Extension(
+                    Box::new("foo".to_owned()),
+                    Some(Box::new(r#"a, "b""#.to_owned()))
+                ),
+                CacheDirective::Extension(
+                    Box::new("bar".to_owned()),
+                    Some(Box::new("baz".to_owned()))
+                ),
+            ])
+        );
+        assert_eq!(cache.to_string(), r#"foo="a, \"b\"", bar=baz"#);
+    }
+
     #[test]
     fn test_parse_bad_syntax() {
         let r: Raw = vec![b"foo=".to_vec()].into();
         let cache: ::Result<CacheControl> = Header::parse_header(&r);
-        assert_eq!(cache.ok(), None)
+        assert_eq!(cache.ok(), None);
+
+        for bad in &[&b"foo=\"\""[..], b"=bar", b"=\""] {
+            let r: Raw = vec![bad.to_vec()].into();
+            let cache: ::Result<CacheControl> = Header::parse_header(&r);
+            assert_eq!(cache.ok(), None)
+        }
     }
 }
 
@@ -245,3 +333,7 @@ bench_header!(normal, CacheControl, {
 });
 
 standard_header!(CacheControl, CACHE_CONTROL);
+
+__hyper__try_from!(CacheControl);
+__hyper__from_str!(CacheControl);
+__hyper__serde!(CacheControl);
@@ -8,7 +8,7 @@ static KEEP_ALIVE: &str = "keep-alive";
 static CLOSE: &str = "close";
 
 /// Values that can be in the `Connection` header.
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum ConnectionOption {
     /// The `keep-alive` connection value.
     KeepAlive,
@@ -94,10 +94,11 @@ header! {
     /// headers.encode(
     ///     &Connection(vec![
     ///         ConnectionOption::ConnectionHeader(Ascii::new("upgrade".to_owned())),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
+    #[derive(Eq, Hash)]
     (Connection, "Connection") => (ConnectionOption)+
 
     test_connection {
@@ -111,13 +112,13 @@ impl Connection {
     /// A constructor to easily create a `Connection: close` header.
     #[inline]
     pub fn close() -> Connection {
-        Connection(vec![ConnectionOption::Close])
+        Connection(smallvec![ConnectionOption::Close])
     }
 
     /// A constructor to easily create a `Connection: keep-alive` header.
     #[inline]
     pub fn keep_alive() -> Connection {
-        Connection(vec![ConnectionOption::KeepAlive])
+        Connection(smallvec![ConnectionOption::KeepAlive])
     }
 }
 
@@ -149,7 +150,9 @@ mod tests {
             parse_option(b"Keep-Alive".to_vec())
         );
         assert_eq!(
-            Connection(vec![ConnectionHeader(Ascii::new("upgrade".to_owned()))]),
+            Connection(smallvec![ConnectionHeader(Ascii::new(
+                "upgrade".to_owned()
+            ))]),
             parse_option(b"upgrade".to_vec())
         );
     }
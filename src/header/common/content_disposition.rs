@@ -8,9 +8,11 @@
 
 use header::parsing::{http_percent_encode, parse_extended_value};
 use header::shared::Charset;
-use header::{parsing, Header, RawLike};
+use header::{parsing, Header, HeaderRef, RawLike};
 use language_tags::LanguageTag;
+use std::borrow::Cow;
 use std::fmt;
+use std::str;
 use unicase;
 
 /// The implied disposition of the content of the HTTP body.
@@ -44,7 +46,7 @@ pub enum DispositionParam {
 /// to use when saving the response payload locally.
 ///
 /// # ABNF
-
+///
 /// ```text
 /// content-disposition = "Content-Disposition" ":"
 ///                       disposition-type *( ";" disposition-parm )
@@ -95,62 +97,14 @@ impl Header for ContentDisposition {
         NAME
     }
 
+    __hyper__http_name!("Content-Disposition");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentDisposition>
     where
         T: RawLike<'a>,
     {
         parsing::from_one_raw_str(raw).and_then(|s: String| {
-            let mut sections = s.split(';');
-            let disposition = match sections.next() {
-                Some(s) => s.trim(),
-                None => return Err(::Error::Header),
-            };
-
-            let mut cd = ContentDisposition {
-                disposition: if unicase::eq_ascii(disposition, "inline") {
-                    DispositionType::Inline
-                } else if unicase::eq_ascii(disposition, "attachment") {
-                    DispositionType::Attachment
-                } else {
-                    DispositionType::Ext(disposition.to_owned())
-                },
-                parameters: Vec::new(),
-            };
-
-            for section in sections {
-                let mut parts = section.splitn(2, '=');
-
-                let key = if let Some(key) = parts.next() {
-                    key.trim()
-                } else {
-                    return Err(::Error::Header);
-                };
-
-                let val = if let Some(val) = parts.next() {
-                    val.trim()
-                } else {
-                    return Err(::Error::Header);
-                };
-
-                cd.parameters.push(if unicase::eq_ascii(key, "filename") {
-                    DispositionParam::Filename(
-                        Charset::Ext("UTF-8".to_owned()),
-                        None,
-                        val.trim_matches('"').as_bytes().to_owned(),
-                    )
-                } else if unicase::eq_ascii(key, "filename*") {
-                    let extended_value = parse_extended_value(val)?;
-                    DispositionParam::Filename(
-                        extended_value.charset,
-                        extended_value.language_tag,
-                        extended_value.value,
-                    )
-                } else {
-                    DispositionParam::Ext(key.to_owned(), val.trim_matches('"').to_owned())
-                });
-            }
-
-            Ok(cd)
+            ContentDispositionRef::parse(&s).map(ContentDispositionRef::into_owned)
         })
     }
 
@@ -170,19 +124,9 @@ impl fmt::Display for ContentDisposition {
         for param in &self.parameters {
             match *param {
                 DispositionParam::Filename(ref charset, ref opt_lang, ref bytes) => {
-                    let use_simple_format = String::from_utf8(bytes.clone())
-                        .map(|s| s.is_ascii())
-                        .unwrap_or(false);
-
-                    if use_simple_format {
-                        write!(
-                            f,
-                            "; filename=\"{}\"",
-                            match String::from_utf8(bytes.clone()) {
-                                Ok(s) => s,
-                                Err(_) => return Err(fmt::Error),
-                            }
-                        )?;
+                    if bytes.is_ascii() {
+                        let name = str::from_utf8(bytes).map_err(|_| fmt::Error)?;
+                        write!(f, "; filename=\"{}\"", name)?;
                     } else {
                         write!(f, "; filename*={}'", charset)?;
                         if let Some(ref lang) = *opt_lang {
@@ -199,11 +143,171 @@ impl fmt::Display for ContentDisposition {
     }
 }
 
+/// The `Content-Disposition` header, borrowing its disposition type and
+/// parameters from the raw header rather than copying them. See
+/// `HeaderRef`.
+///
+/// Parameter values are unquoted, except for extended values such as
+/// `filename*`, which are kept encoded.
+///
+/// ```
+/// use hyperx::header::{ContentDispositionRef, HeaderRef, Raw};
+///
+/// let raw = Raw::from("attachment; filename=\"report.pdf\"; size=1024");
+/// let cd = ContentDispositionRef::parse_header_ref(&raw).unwrap();
+/// assert!(cd.is_attachment());
+/// assert_eq!(cd.filename().unwrap(), "report.pdf");
+/// assert_eq!(cd.param("size"), Some("1024"));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContentDispositionRef<'a> {
+    disposition: &'a str,
+    params: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> ContentDispositionRef<'a> {
+    /// The disposition type, as given.
+    pub fn disposition(&self) -> &'a str {
+        self.disposition
+    }
+
+    /// Return `true` if the disposition type is `inline`.
+    pub fn is_inline(&self) -> bool {
+        unicase::eq_ascii(self.disposition, "inline")
+    }
+
+    /// Return `true` if the disposition type is `attachment`.
+    pub fn is_attachment(&self) -> bool {
+        unicase::eq_ascii(self.disposition, "attachment")
+    }
+
+    /// The parameters (name, value), in order.
+    pub fn params(&self) -> &[(&'a str, &'a str)] {
+        &self.params
+    }
+
+    /// Get the value of the first parameter of the name, case-insensitive,
+    /// if it exists.
+    pub fn param(&self, name: &str) -> Option<&'a str> {
+        self.params
+            .iter()
+            .find(|p| unicase::eq_ascii(p.0, name))
+            .map(|p| p.1)
+    }
+
+    /// The file name, preferring a UTF-8 encoded `filename*` over
+    /// `filename`. Only a decoded `filename*` is copied.
+    pub fn filename(&self) -> Option<Cow<'a, str>> {
+        let decoded = self
+            .param("filename*")
+            .and_then(|val| parse_extended_value(val).ok())
+            .and_then(|ext| match ext.charset {
+                Charset::Ext(ref cs) if cs.eq_ignore_ascii_case("UTF-8") => {
+                    String::from_utf8(ext.value).ok()
+                }
+                _ => None,
+            });
+        match decoded {
+            Some(name) => Some(Cow::Owned(name)),
+            None => self.param("filename").map(Cow::Borrowed),
+        }
+    }
+
+    /// Copy into an owned `ContentDisposition`.
+    pub fn into_owned(self) -> ContentDisposition {
+        let disposition = if self.is_inline() {
+            DispositionType::Inline
+        } else if self.is_attachment() {
+            DispositionType::Attachment
+        } else {
+            DispositionType::Ext(self.disposition.to_owned())
+        };
+        let parameters = self
+            .params
+            .into_iter()
+            .map(|(key, val)| {
+                if unicase::eq_ascii(key, "filename") {
+                    DispositionParam::Filename(
+                        Charset::Ext("UTF-8".to_owned()),
+                        None,
+                        val.as_bytes().to_owned(),
+                    )
+                } else if unicase::eq_ascii(key, "filename*") {
+                    let extended_value =
+                        parse_extended_value(val).expect("extended value checked when parsed");
+                    DispositionParam::Filename(
+                        extended_value.charset,
+                        extended_value.language_tag,
+                        extended_value.value,
+                    )
+                } else {
+                    DispositionParam::Ext(key.to_owned(), val.to_owned())
+                }
+            })
+            .collect();
+        ContentDisposition {
+            disposition,
+            parameters,
+        }
+    }
+
+    fn parse(s: &'a str) -> ::Result<ContentDispositionRef<'a>> {
+        let mut sections = s.split(';');
+        let disposition = match sections.next() {
+            Some(s) => s.trim(),
+            None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+        };
+
+        let mut cd = ContentDispositionRef {
+            disposition,
+            params: Vec::new(),
+        };
+
+        for section in sections {
+            let mut parts = section.splitn(2, '=');
+
+            let key = if let Some(key) = parts.next() {
+                key.trim()
+            } else {
+                return Err(::Error::header(::HeaderErrorKind::Syntax));
+            };
+
+            let val = if let Some(val) = parts.next() {
+                val.trim()
+            } else {
+                return Err(::Error::header(::HeaderErrorKind::Syntax));
+            };
+
+            if unicase::eq_ascii(key, "filename*") {
+                parse_extended_value(val)?;
+                cd.params.push((key, val));
+            } else {
+                cd.params.push((key, val.trim_matches('"')));
+            }
+        }
+
+        Ok(cd)
+    }
+}
+
+impl<'a> HeaderRef<'a> for ContentDispositionRef<'a> {
+    fn header_name() -> &'static str {
+        ContentDisposition::header_name()
+    }
+
+    fn parse_lines<I>(lines: I) -> ::Result<ContentDispositionRef<'a>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        parsing::from_one_raw_str_ref(lines).and_then(ContentDispositionRef::parse)
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{ContentDisposition, DispositionParam, DispositionType};
+    use super::{ContentDisposition, ContentDispositionRef, DispositionParam, DispositionType};
     use header::shared::Charset;
-    use header::{Header, Raw};
+    use header::{Header, HeaderRef, Raw};
 
     #[test]
     fn test_parse_header() {
@@ -254,6 +358,33 @@ mod tests {
         assert_eq!(a, b);
     }
 
+    #[test]
+    fn test_parse_ref() {
+        let a: Raw =
+            "Attachment; name=upload; filename=\"a b.txt\"; filename*=UTF-8''%e2%82%ac.txt".into();
+        let cd = ContentDispositionRef::parse_header_ref(&a).unwrap();
+        assert_eq!(cd.disposition(), "Attachment");
+        assert!(cd.is_attachment() && !cd.is_inline());
+        assert_eq!(cd.param("NAME"), Some("upload"));
+        assert_eq!(cd.param("filename"), Some("a b.txt"));
+        assert_eq!(cd.filename().unwrap(), "\u{20ac}.txt");
+        assert_eq!(cd.params().len(), 3);
+        assert_eq!(
+            cd.into_owned(),
+            ContentDisposition::parse_header(&a).unwrap()
+        );
+
+        let a: Raw = "inline; filename=plain.txt".into();
+        let cd = ContentDispositionRef::parse_header_ref(&a).unwrap();
+        assert!(cd.is_inline());
+        assert_eq!(cd.filename().unwrap(), "plain.txt");
+
+        let a: Raw = "attachment; filename".into();
+        assert!(ContentDispositionRef::parse_header_ref(&a).is_err());
+        let a: Raw = "attachment; filename*=bogus".into();
+        assert!(ContentDispositionRef::parse_header_ref(&a).is_err());
+    }
+
     #[test]
     fn test_display() {
         let as_string = "attachment; filename*=UTF-8'en'%C2%A3%20and%20%E2%82%AC%20rates";
@@ -296,3 +427,7 @@ mod tests {
 }
 
 standard_header!(ContentDisposition, CONTENT_DISPOSITION);
+
+__hyper__try_from!(ContentDisposition);
+__hyper__from_str!(ContentDisposition);
+__hyper__serde!(ContentDisposition);
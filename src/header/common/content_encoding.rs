@@ -29,7 +29,7 @@ header! {
     /// use hyperx::header::{ContentEncoding, Encoding, TypedHeaders};
     ///
     /// let mut headers = http::HeaderMap::new();
-    /// headers.encode(&ContentEncoding(vec![Encoding::Chunked]));
+    /// headers.encode(&ContentEncoding(vec![Encoding::Chunked].into()));
     /// ```
     ///
     /// ```
@@ -41,14 +41,15 @@ header! {
     ///     &ContentEncoding(vec![
     ///         Encoding::Gzip,
     ///         Encoding::Chunked,
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
+    #[derive(Eq, Hash)]
     (ContentEncoding, "Content-Encoding") => (Encoding)+
 
     test_content_encoding {
         // Testcase from the RFC
-        test_header!(test1, vec![b"gzip"], Some(ContentEncoding(vec![Encoding::Gzip])));
+        test_header!(test1, vec![b"gzip"], Some(ContentEncoding(smallvec![Encoding::Gzip])));
     }
 }
 
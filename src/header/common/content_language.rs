@@ -1,3 +1,4 @@
+use header::common::accept_language::canonicalize;
 use header::QualityItem;
 use language_tags::LanguageTag;
 
@@ -34,7 +35,7 @@ header! {
     /// headers.encode(
     ///     &ContentLanguage(vec![
     ///         qitem("en".parse().unwrap()),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
@@ -51,7 +52,7 @@ header! {
     ///     &ContentLanguage(vec![
     ///         qitem("da".parse().unwrap()),
     ///         qitem("en-GB".parse().unwrap()),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
@@ -60,6 +61,35 @@ header! {
     test_content_language {
         test_header!(test1, [b"da"]);
         test_header!(test2, [b"mi, en"]);
+
+        #[test]
+        fn test_matches() {
+            let mut cl: ContentLanguage = "mi, iw-IL".parse().unwrap();
+            let he: LanguageTag = "he".parse().unwrap();
+            assert!(!cl.matches(&he));
+            cl.canonicalize();
+            assert!(cl.matches(&he));
+            assert!(!cl.matches(&"de".parse().unwrap()));
+            assert_eq!(cl.to_string(), "mi, he-IL");
+        }
+    }
+}
+
+impl ContentLanguage {
+    /// Return true if any of the language tags matches a language range, as
+    /// per `LanguageTag::matches`.
+    ///
+    /// # Panics
+    ///
+    /// If the range has extension or private use subtags.
+    pub fn matches(&self, range: &LanguageTag) -> bool {
+        self.iter().any(|tag| range.matches(&tag.item))
+    }
+
+    /// Replace each language tag with its canonical form, as per
+    /// `LanguageTag::canonicalize`, where it has a unique one.
+    pub fn canonicalize(&mut self) {
+        canonicalize(self.0.iter_mut().map(|tag| &mut tag.item));
     }
 }
 
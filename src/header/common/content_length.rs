@@ -1,4 +1,5 @@
-use header::{parsing, Header, RawLike};
+use header::parsing::{self, Limit};
+use header::{Header, RawLike};
 use std::fmt;
 
 /// `Content-Length` header, defined in
@@ -39,7 +40,7 @@ use std::fmt;
 /// let mut headers = http::HeaderMap::new();
 /// headers.encode(&ContentLength(1024u64));
 /// ```
-#[derive(Clone, Copy, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
 pub struct ContentLength(pub u64);
 
 impl Header for ContentLength {
@@ -49,22 +50,30 @@ impl Header for ContentLength {
         NAME
     }
 
+    __hyper__http_name!("Content-Length");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentLength>
     where
         T: RawLike<'a>,
     {
         // If multiple Content-Length headers were sent, everything can still
         // be alright if they all contain the same value, and all parse
-        // correctly. If not, then it's an error.
+        // correctly. If not, or if parsing strictly, then it's an error.
+        if let Some(line) = raw.one() {
+            return parse_line(line).map(ContentLength);
+        }
+        if raw.len() > 1 && parsing::is_strict() {
+            return Err(::Error::header(::HeaderErrorKind::Multiple));
+        }
         raw.iter()
-            .map(parsing::from_raw_str)
+            .map(parse_line)
             .fold(None, |prev, x| match (prev, x) {
                 (None, x) => Some(x),
                 (e @ Some(Err(_)), _) => e,
                 (Some(Ok(prev)), Ok(x)) if prev == x => Some(Ok(prev)),
-                _ => Some(Err(::Error::Header)),
+                _ => Some(Err(::Error::header(::HeaderErrorKind::Multiple))),
             })
-            .unwrap_or(Err(::Error::Header))
+            .unwrap_or(Err(::Error::header(::HeaderErrorKind::Empty)))
             .map(ContentLength)
     }
 
@@ -74,8 +83,30 @@ impl Header for ContentLength {
     }
 }
 
+// Parse a line of only decimal digits, by far the most common form,
+// directly from the bytes, or otherwise trimmed via the generic
+// `from_raw_str`, failing with `Number` if not a valid length.
+fn parse_line(line: &[u8]) -> ::Result<u64> {
+    // Any 19 digits fit in a u64
+    if !line.is_empty() && line.len() < 20 && line.iter().all(u8::is_ascii_digit) {
+        parsing::check_limit(Limit::ValueLength, line.len())?;
+        return Ok(line.iter().fold(0, |n, b| n * 10 + u64::from(b - b'0')));
+    }
+    let s: String = parsing::from_raw_str(line)?;
+    if s.is_empty() {
+        return Err(::Error::header(::HeaderErrorKind::Empty));
+    }
+    s.parse().map_err(|e| {
+        ::Error::header_with_source(::HeaderErrorKind::Number, e).with_header_value(line)
+    })
+}
+
 standard_header!(ContentLength, CONTENT_LENGTH);
 
+__hyper__try_from!(ContentLength);
+__hyper__from_str!(ContentLength);
+__hyper__serde!(ContentLength);
+
 impl fmt::Display for ContentLength {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -90,6 +121,14 @@ __hyper__tm!(ContentLength, tests {
     test_header!(test1, vec![b"3495"], Some(HeaderField(3495)));
 
     test_header!(test_invalid, vec![b"34v95"], None);
+    test_header!(test_max, vec![b"18446744073709551615"], Some(HeaderField(u64::MAX)));
+    test_header!(test_overflow, vec![b"18446744073709551616"], None);
+
+    #[test]
+    fn test_whitespace() {
+        let r: Raw = " 42\t".into();
+        assert_eq!(HeaderField::parse_header(&r).unwrap(), HeaderField(42));
+    }
 
     // Can't use the test_header macro because "5, 5" gets cleaned to "5".
     #[test]
@@ -101,6 +140,18 @@ __hyper__tm!(ContentLength, tests {
     }
 
     test_header!(test_duplicates_vary, vec![b"5", b"6", b"5"], None);
+
+    #[test]
+    fn test_error_kind() {
+        let kind = |s: &str| {
+            let r: Raw = s.into();
+            HeaderField::parse_header(&r).unwrap_err().header_error().unwrap().kind()
+        };
+        assert_eq!(kind("12a"), ::HeaderErrorKind::Number);
+        assert_eq!(kind("-1"), ::HeaderErrorKind::Number);
+        assert_eq!(kind("18446744073709551616"), ::HeaderErrorKind::Number);
+        assert_eq!(kind(" "), ::HeaderErrorKind::Empty);
+    }
 });
 
 bench_header!(bench, ContentLength, { vec![b"42349984".to_vec()] });
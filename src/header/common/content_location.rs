@@ -38,7 +38,6 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&ContentLocation("http://www.example.org/hypertext/Overview.html".to_owned()));
     /// ```
-    // TODO: use URL
     (ContentLocation, "Content-Location") => [String]
 
     test_content_location {
@@ -48,4 +47,6 @@ header! {
     }
 }
 
+uri_reference!(ContentLocation);
+
 standard_header!(ContentLocation, CONTENT_LOCATION);
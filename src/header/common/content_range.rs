@@ -122,23 +122,32 @@ impl FromStr for ContentRangeSpec {
     fn from_str(s: &str) -> ::Result<Self> {
         let res = match split_in_two(s, ' ') {
             Some(("bytes", resp)) => {
-                let (range, instance_length) = split_in_two(resp, '/').ok_or(::Error::Header)?;
+                let (range, instance_length) =
+                    split_in_two(resp, '/').ok_or(::Error::header(::HeaderErrorKind::Syntax))?;
 
                 let instance_length = if instance_length == "*" {
                     None
                 } else {
-                    Some(instance_length.parse().map_err(|_| ::Error::Header)?)
+                    Some(
+                        instance_length
+                            .parse()
+                            .map_err(|_| ::Error::header(::HeaderErrorKind::Number))?,
+                    )
                 };
 
                 let range = if range == "*" {
                     None
                 } else {
-                    let (first_byte, last_byte) =
-                        split_in_two(range, '-').ok_or(::Error::Header)?;
-                    let first_byte = first_byte.parse().map_err(|_| ::Error::Header)?;
-                    let last_byte = last_byte.parse().map_err(|_| ::Error::Header)?;
+                    let (first_byte, last_byte) = split_in_two(range, '-')
+                        .ok_or(::Error::header(::HeaderErrorKind::Syntax))?;
+                    let first_byte = first_byte
+                        .parse()
+                        .map_err(|_| ::Error::header(::HeaderErrorKind::Number))?;
+                    let last_byte = last_byte
+                        .parse()
+                        .map_err(|_| ::Error::header(::HeaderErrorKind::Number))?;
                     if last_byte < first_byte {
-                        return Err(::Error::Header);
+                        return Err(::Error::header(::HeaderErrorKind::Syntax));
                     }
                     Some((first_byte, last_byte))
                 };
@@ -152,7 +161,7 @@ impl FromStr for ContentRangeSpec {
                 unit: unit.to_owned(),
                 resp: resp.to_owned(),
             },
-            _ => return Err(::Error::Header),
+            _ => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         };
         Ok(res)
     }
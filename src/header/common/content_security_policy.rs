@@ -0,0 +1,194 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+use std::str;
+
+/// `Content-Security-Policy` header, defined in
+/// [CSP3](https://www.w3.org/TR/CSP3/#csp-header)
+///
+/// The `Content-Security-Policy` header delivers a policy of directives,
+/// each with a list of source expressions, which restrict the resources a
+/// document may load or the contexts it may be embedded in.
+///
+/// A response may carry multiple policies as separate header lines, each of
+/// which is enforced; this type represents a single policy. Directive names
+/// are lowercased, and as per the specification only the first occurrence
+/// of a directive is retained when parsing.
+///
+/// # ABNF
+///
+/// ```text
+/// Content-Security-Policy = 1#serialized-policy
+/// serialized-policy = serialized-directive *( OWS ";" [ OWS serialized-directive ] )
+/// serialized-directive = directive-name [ RWS directive-value ]
+/// ```
+///
+/// # Example values
+///
+/// * `default-src 'self'; frame-ancestors 'none'`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{ContentSecurityPolicy, TypedHeaders};
+///
+/// let csp = ContentSecurityPolicy::new()
+///     .directive("default-src", vec!["'self'"])
+///     .directive("frame-ancestors", vec!["'none'"]);
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&csp);
+/// assert_eq!(
+///     headers["content-security-policy"],
+///     "default-src 'self'; frame-ancestors 'none'"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct ContentSecurityPolicy(Vec<(String, Vec<String>)>);
+
+impl ContentSecurityPolicy {
+    /// Create a new, empty policy.
+    pub fn new() -> ContentSecurityPolicy {
+        ContentSecurityPolicy::default()
+    }
+
+    /// Set a directive and its values, replacing any existing directive of
+    /// the same name.
+    pub fn directive<N, I, V>(mut self, name: N, values: I) -> ContentSecurityPolicy
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = V>,
+        V: Into<String>,
+    {
+        let name = name.into().to_ascii_lowercase();
+        let values = values.into_iter().map(Into::into).collect();
+        match self.0.iter_mut().find(|d| d.0 == name) {
+            Some(d) => d.1 = values,
+            None => self.0.push((name, values)),
+        }
+        self
+    }
+
+    /// Remove a directive, if present.
+    pub fn remove(mut self, name: &str) -> ContentSecurityPolicy {
+        self.0.retain(|d| !d.0.eq_ignore_ascii_case(name));
+        self
+    }
+
+    /// Returns the values of a directive, if present.
+    pub fn get(&self, name: &str) -> Option<&[String]> {
+        self.0
+            .iter()
+            .find(|d| d.0.eq_ignore_ascii_case(name))
+            .map(|d| &d.1[..])
+    }
+
+    /// Returns true if the policy has no directives.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns an iterator over the directive names and values.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0.iter().map(|d| (&d.0[..], &d.1[..]))
+    }
+
+    fn parse(s: &str) -> ::Result<ContentSecurityPolicy> {
+        let mut directives: Vec<(String, Vec<String>)> = Vec::new();
+        for directive in s.split(';') {
+            let mut tokens = directive.split_ascii_whitespace();
+            let name = match tokens.next() {
+                Some(name) => name.to_ascii_lowercase(),
+                None => continue,
+            };
+            if directives.iter().any(|d| d.0 == name) {
+                continue;
+            }
+            directives.push((name, tokens.map(ToOwned::to_owned).collect()));
+        }
+        if directives.is_empty() {
+            return Err(::Error::header(::HeaderErrorKind::Empty));
+        }
+        Ok(ContentSecurityPolicy(directives))
+    }
+}
+
+impl Header for ContentSecurityPolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Content-Security-Policy";
+        NAME
+    }
+
+    __hyper__http_name!("Content-Security-Policy");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentSecurityPolicy>
+    where
+        T: RawLike<'a>,
+    {
+        let line = raw.one().ok_or_else(|| parsing::not_one(raw))?;
+        ContentSecurityPolicy::parse(str::from_utf8(line)?)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for ContentSecurityPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (name, values)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            f.write_str(name)?;
+            for v in values {
+                write!(f, " {}", v)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_content_security_policy {
+    use super::ContentSecurityPolicy as HeaderField;
+    use header::*;
+    use std::str;
+
+    test_header!(
+        test_basic,
+        vec![b"default-src 'self'; img-src *; upgrade-insecure-requests"],
+        Some(
+            HeaderField::new()
+                .directive("default-src", vec!["'self'"])
+                .directive("img-src", vec!["*"])
+                .directive("upgrade-insecure-requests", Vec::<String>::new())
+        )
+    );
+    test_header!(test_empty, vec![b" ; "], None);
+    test_header!(
+        test_multiple_lines,
+        vec![&b"default-src 'self'"[..], &b"img-src *"[..]],
+        None
+    );
+
+    #[test]
+    fn test_duplicate_and_case() {
+        let raw: Raw =
+            "Script-Src a.example;  ; script-src b.example;frame-ancestors 'none'".into();
+        let csp = HeaderField::parse_header(&raw).unwrap();
+        assert_eq!(csp.get("script-src"), Some(&["a.example".to_owned()][..]));
+        assert_eq!(
+            csp.to_string(),
+            "script-src a.example; frame-ancestors 'none'"
+        );
+        let csp = csp.remove("Script-Src");
+        assert_eq!(csp.iter().count(), 1);
+    }
+}
+
+standard_header!(ContentSecurityPolicy, CONTENT_SECURITY_POLICY);
+
+__hyper__try_from!(ContentSecurityPolicy);
+__hyper__from_str!(ContentSecurityPolicy);
+__hyper__serde!(ContentSecurityPolicy);
@@ -1,66 +1,166 @@
+use header::parsing::{self, Limit};
+use header::{Header, RawLike};
 use mime::{self, Mime};
+use std::fmt;
+use std::hash::{Hash, Hasher};
 
-header! {
-    /// `Content-Type` header, defined in
-    /// [RFC7231](http://tools.ietf.org/html/rfc7231#section-3.1.1.5)
-    ///
-    /// The `Content-Type` header field indicates the media type of the
-    /// associated representation: either the representation enclosed in the
-    /// message payload or the selected representation, as determined by the
-    /// message semantics.  The indicated media type defines both the data
-    /// format and how that data is intended to be processed by a recipient,
-    /// within the scope of the received message semantics, after any content
-    /// codings indicated by Content-Encoding are decoded.
-    ///
-    /// Although the `mime` crate allows the mime options to be any slice, this crate
-    /// forces the use of Vec. This is to make sure the same header can't have more than 1 type. If
-    /// this is an issue, it's possible to implement `Header` on a custom struct.
-    ///
-    /// # ABNF
-    ///
-    /// ```text
-    /// Content-Type = media-type
-    /// ```
-    ///
-    /// # Example values
-    ///
-    /// * `text/html; charset=utf-8`
-    /// * `application/json`
-    ///
-    /// # Examples
-    ///
-    /// ```
-    /// # extern crate http;
-    /// use hyperx::header::{ContentType, TypedHeaders};
-    ///
-    /// let mut headers = http::HeaderMap::new();
-    ///
-    /// headers.encode(
-    ///     &ContentType::json()
-    /// );
-    /// ```
-    ///
-    /// ```
-    /// # extern crate http;
-    /// use hyperx::header::{ContentType, TypedHeaders};
-    /// use hyperx::mime;
-    ///
-    /// let mut headers = http::HeaderMap::new();
-    ///
-    /// headers.encode(
-    ///     &ContentType(mime::TEXT_HTML)
-    /// );
-    /// ```
-    (ContentType, "Content-Type") => danger [Mime]
-
-    test_content_type {
-        test_header!(
-            test1,
-            vec![b"text/html"],
-            Some(HeaderField(TEXT_HTML)));
+/// `Content-Type` header, defined in
+/// [RFC7231](http://tools.ietf.org/html/rfc7231#section-3.1.1.5)
+///
+/// The `Content-Type` header field indicates the media type of the
+/// associated representation: either the representation enclosed in the
+/// message payload or the selected representation, as determined by the
+/// message semantics.  The indicated media type defines both the data
+/// format and how that data is intended to be processed by a recipient,
+/// within the scope of the received message semantics, after any content
+/// codings indicated by Content-Encoding are decoded.
+///
+/// Although the `mime` crate allows the mime options to be any slice, this crate
+/// forces the use of Vec. This is to make sure the same header can't have more than 1 type. If
+/// this is an issue, it's possible to implement `Header` on a custom struct.
+///
+/// # ABNF
+///
+/// ```text
+/// Content-Type = media-type
+/// ```
+///
+/// # Example values
+///
+/// * `text/html; charset=utf-8`
+/// * `application/json`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{ContentType, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+///
+/// headers.encode(
+///     &ContentType::json()
+/// );
+/// ```
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{ContentType, TypedHeaders};
+/// use hyperx::mime;
+///
+/// let mut headers = http::HeaderMap::new();
+///
+/// headers.encode(
+///     &ContentType(mime::TEXT_HTML)
+/// );
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct ContentType(pub Mime);
+
+__hyper__deref!(ContentType => Mime);
+__hyper__try_from!(ContentType);
+__hyper__from_str!(ContentType);
+__hyper__serde!(ContentType);
+
+impl Header for ContentType {
+    #[inline]
+    fn header_name() -> &'static str {
+        static NAME: &str = "Content-Type";
+        NAME
+    }
+
+    __hyper__http_name!("Content-Type");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<ContentType>
+    where
+        T: RawLike<'a>,
+    {
+        if let Some(line) = raw.one() {
+            if let Some(mime) = common_mime(line) {
+                return parsing::check_limit(Limit::ValueLength, line.len())
+                    .map(|()| ContentType(mime))
+                    .map_err(|e| e.with_header_name(Self::header_name()));
+            }
+        }
+        parsing::from_one_raw_str(raw)
+            .map(ContentType)
+            .map_err(|e| e.with_header_name(Self::header_name()))
+    }
+
+    #[inline]
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.danger_fmt_line_without_newline_replacer(self)
+    }
+}
+
+impl fmt::Display for ContentType {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
     }
 }
 
+// The `Mime` constant for the most common raw values, which would otherwise
+// be parsed and allocated. Only an exact match is used, so that the value is
+// formatted as received.
+fn common_mime(line: &[u8]) -> Option<Mime> {
+    Some(match line {
+        b"application/json" => mime::APPLICATION_JSON,
+        b"application/javascript" => mime::APPLICATION_JAVASCRIPT,
+        b"application/octet-stream" => mime::APPLICATION_OCTET_STREAM,
+        b"application/x-www-form-urlencoded" => mime::APPLICATION_WWW_FORM_URLENCODED,
+        b"image/jpeg" => mime::IMAGE_JPEG,
+        b"image/png" => mime::IMAGE_PNG,
+        b"text/css" => mime::TEXT_CSS,
+        b"text/html" => mime::TEXT_HTML,
+        b"text/html; charset=utf-8" => mime::TEXT_HTML_UTF_8,
+        b"text/plain" => mime::TEXT_PLAIN,
+        b"text/plain; charset=utf-8" => mime::TEXT_PLAIN_UTF_8,
+        _ => return None,
+    })
+}
+
+__hyper__tm!(ContentType, tests {
+    test_header!(
+        test1,
+        vec![b"text/html"],
+        Some(HeaderField(TEXT_HTML)));
+
+    #[test]
+    fn test_hash() {
+        use std::collections::HashSet;
+
+        let mut set = HashSet::new();
+        set.insert("text/plain; charset=utf-8".parse::<HeaderField>().unwrap());
+        assert!(set.contains(&HeaderField(TEXT_PLAIN_UTF_8)));
+        assert!(set.contains(&"Text/Plain; Charset=UTF-8".parse().unwrap()));
+        assert!(!set.contains(&HeaderField(TEXT_PLAIN)));
+    }
+
+    #[test]
+    fn test_common_mime() {
+        for value in &[
+            "application/json",
+            "application/javascript",
+            "application/octet-stream",
+            "application/x-www-form-urlencoded",
+            "image/jpeg",
+            "image/png",
+            "text/css",
+            "text/html",
+            "text/html; charset=utf-8",
+            "text/plain",
+            "text/plain; charset=utf-8",
+        ] {
+            let common = super::common_mime(value.as_bytes()).expect(value);
+            assert_eq!(common, value.parse::<Mime>().unwrap());
+            assert_eq!(common.to_string(), *value);
+        }
+        assert_eq!(super::common_mime(b"Application/JSON"), None);
+        assert_eq!(super::common_mime(b"text/html;charset=utf-8"), None);
+    }
+});
+
 impl ContentType {
     /// A constructor  to easily create a `Content-Type: application/json` header.
     #[inline]
@@ -126,6 +226,17 @@ impl ContentType {
 
 impl Eq for ContentType {}
 
+// `Mime` equality ignores the case of some parameter values (e.g. `charset`),
+// so only hash the case-normalized essence (type, subtype and suffix), which
+// is always the same for equal values.
+impl Hash for ContentType {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        for b in self.0.essence_str().bytes() {
+            state.write_u8(b.to_ascii_lowercase());
+        }
+    }
+}
+
 bench_header!(bench, ContentType, { vec![b"application/json".to_vec()] });
 
 standard_header!(ContentType, CONTENT_TYPE);
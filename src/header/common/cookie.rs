@@ -1,8 +1,11 @@
 use header::internals::VecMap;
-use header::{Header, RawLike};
+use header::parsing::{Limit, ParseConfig};
+use header::{Header, HeaderRef, RawLike};
 use std::borrow::Cow;
 use std::fmt;
+use std::ops::Range;
 use std::str::from_utf8;
+use std::sync::Arc;
 
 /// `Cookie` header, defined in [RFC6265](http://tools.ietf.org/html/rfc6265#section-5.4)
 ///
@@ -18,7 +21,11 @@ use std::str::from_utf8;
 /// * `SID=31d4d96e407aad42; lang=en-US`
 ///
 /// Consider using the _cookie_ crate for parsing/decoding or encoding
-/// cookie values.
+/// cookie values. With the `cookie` feature, `Cookie` can be collected from
+/// `cookie::Cookie` values, and converted to a `Vec` of them.
+///
+/// When parsed, the names and values of all the pairs are copied into a
+/// single shared buffer, rather than allocated individually.
 ///
 /// # Example
 /// ```
@@ -34,7 +41,7 @@ use std::str::from_utf8;
 /// headers.encode(&cookie);
 /// ```
 #[derive(Clone)]
-pub struct Cookie(VecMap<Cow<'static, str>, Cow<'static, str>>);
+pub struct Cookie(VecMap<CookieStr, CookieStr>);
 
 impl Default for Cookie {
     fn default() -> Self {
@@ -59,8 +66,8 @@ impl Cookie {
         K: Into<Cow<'static, str>>,
         V: Into<Cow<'static, str>>,
     {
-        let key = key.into();
-        let value = value.into();
+        let key = CookieStr::Given(key.into());
+        let value = CookieStr::Given(value.into());
         self.0.remove_all(&key);
         self.0.append(key, value);
     }
@@ -83,7 +90,8 @@ impl Cookie {
         K: Into<Cow<'static, str>>,
         V: Into<Cow<'static, str>>,
     {
-        self.0.append(key.into(), value.into());
+        self.0
+            .append(CookieStr::Given(key.into()), CookieStr::Given(value.into()));
     }
 
     /// Get a value for the name, if it exists.
@@ -94,7 +102,7 @@ impl Cookie {
     /// any other values associated with the name, parse
     /// the `str` representation.
     pub fn get(&self, key: &str) -> Option<&str> {
-        self.0.get(key).map(AsRef::as_ref)
+        self.0.get(key).map(CookieStr::as_str)
     }
 
     /// Iterate cookies.
@@ -116,7 +124,7 @@ impl Cookie {
     /// assert_eq!(keys, vec!["foo", "dyn"]);
     /// assert_eq!(values, vec!["bar", "amic"]);
     /// ```
-    pub fn iter(&self) -> CookieIter {
+    pub fn iter(&self) -> CookieIter<'_> {
         CookieIter(self.0.iter())
     }
 }
@@ -127,27 +135,13 @@ impl Header for Cookie {
         NAME
     }
 
+    __hyper__http_name!("Cookie");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Cookie>
     where
         T: RawLike<'a>,
     {
-        let mut vec_map = VecMap::with_capacity(raw.len());
-        for cookies_raw in raw.iter() {
-            let cookies_str = from_utf8(cookies_raw)?;
-            for cookie_str in cookies_str.split(';') {
-                let mut key_val = cookie_str.splitn(2, '=');
-                let key_val = (key_val.next(), key_val.next());
-                if let (Some(key), Some(val)) = key_val {
-                    vec_map.insert(key.trim().to_owned().into(), val.trim().to_owned().into());
-                }
-            }
-        }
-
-        if vec_map.len() != 0 {
-            Ok(Cookie(vec_map))
-        } else {
-            Err(::Error::Header)
-        }
+        CookieRef::parse_header_ref(raw).map(CookieRef::into_owned)
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -158,7 +152,7 @@ impl Header for Cookie {
 impl PartialEq for Cookie {
     fn eq(&self, other: &Cookie) -> bool {
         if self.0.len() == other.0.len() {
-            for (k, v) in self.0.iter() {
+            for (k, v) in self.iter() {
                 if other.get(k) != Some(v) {
                     return false;
                 }
@@ -172,15 +166,13 @@ impl PartialEq for Cookie {
 
 impl fmt::Debug for Cookie {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        f.debug_map()
-            .entries(self.0.iter().map(|(k, v)| (k, v)))
-            .finish()
+        f.debug_map().entries(self.iter()).finish()
     }
 }
 
 impl fmt::Display for Cookie {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        let mut iter = self.0.iter();
+        let mut iter = self.iter();
         if let Some((key, val)) = iter.next() {
             write!(f, "{}={}", key, val)?;
         }
@@ -193,20 +185,161 @@ impl fmt::Display for Cookie {
 
 /// Iterator for cookie.
 #[derive(Debug)]
-pub struct CookieIter<'a>(::std::slice::Iter<'a, (Cow<'static, str>, Cow<'static, str>)>);
+pub struct CookieIter<'a>(::std::slice::Iter<'a, (CookieStr, CookieStr)>);
 
 impl<'a> Iterator for CookieIter<'a> {
     type Item = (&'a str, &'a str);
 
     fn next(&mut self) -> Option<Self::Item> {
-        self.0.next().map(|kv| (kv.0.as_ref(), kv.1.as_ref()))
+        self.0.next().map(|kv| (kv.0.as_str(), kv.1.as_str()))
+    }
+}
+
+// A cookie name or value, either as given, or as a range of the buffer
+// shared by all the pairs of a parsed header, which is allocated once.
+#[derive(Clone)]
+enum CookieStr {
+    Given(Cow<'static, str>),
+    Shared(Arc<str>, Range<usize>),
+}
+
+impl CookieStr {
+    fn as_str(&self) -> &str {
+        match *self {
+            CookieStr::Given(ref s) => s,
+            CookieStr::Shared(ref buf, ref range) => &buf[range.clone()],
+        }
+    }
+}
+
+impl AsRef<str> for CookieStr {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl fmt::Debug for CookieStr {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Debug::fmt(self.as_str(), f)
+    }
+}
+
+impl PartialEq for CookieStr {
+    fn eq(&self, other: &CookieStr) -> bool {
+        self.as_str() == other.as_str()
+    }
+}
+
+impl PartialEq<CookieStr> for str {
+    fn eq(&self, other: &CookieStr) -> bool {
+        self == other.as_str()
+    }
+}
+
+/// The `Cookie` header, borrowing its names and values from the raw header
+/// rather than copying them. See `HeaderRef`.
+///
+/// ```
+/// use hyperx::header::{CookieRef, HeaderRef, Raw};
+///
+/// let raw = Raw::from("SID=31d4d96e407aad42; lang=en-US");
+/// let cookie = CookieRef::parse_header_ref(&raw).unwrap();
+/// assert_eq!(cookie.get("SID"), Some("31d4d96e407aad42"));
+/// assert_eq!(cookie.into_owned().to_string(), "SID=31d4d96e407aad42; lang=en-US");
+/// ```
+#[derive(Clone)]
+pub struct CookieRef<'a>(VecMap<&'a str, &'a str>);
+
+impl<'a> CookieRef<'a> {
+    /// Get a value for the name, if it exists.
+    pub fn get(&self, key: &str) -> Option<&'a str> {
+        self.0.get(&key).cloned()
+    }
+
+    /// Iterate cookie (key, value) in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.0.iter().cloned()
+    }
+
+    /// Return the number of cookies.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return `true` if there are no cookies, which is never the case for
+    /// a parsed header.
+    pub fn is_empty(&self) -> bool {
+        self.0.len() == 0
+    }
+
+    /// Copy into an owned `Cookie`.
+    pub fn into_owned(self) -> Cookie {
+        // Copy all names and values into one buffer, shared by the pairs
+        let mut buf =
+            String::with_capacity(self.0.iter().map(|&(key, val)| key.len() + val.len()).sum());
+        for &(key, val) in self.0.iter() {
+            buf.push_str(key);
+            buf.push_str(val);
+        }
+        let buf: Arc<str> = buf.into();
+        let mut vec_map = VecMap::with_capacity(self.0.len());
+        let mut start = 0;
+        for &(key, val) in self.0.iter() {
+            let mid = start + key.len();
+            let end = mid + val.len();
+            vec_map.append(
+                CookieStr::Shared(buf.clone(), start..mid),
+                CookieStr::Shared(buf.clone(), mid..end),
+            );
+            start = end;
+        }
+        Cookie(vec_map)
+    }
+}
+
+impl<'a> HeaderRef<'a> for CookieRef<'a> {
+    fn header_name() -> &'static str {
+        Cookie::header_name()
+    }
+
+    fn parse_lines<I>(lines: I) -> ::Result<CookieRef<'a>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let config = ParseConfig::current();
+        let lines = lines.into_iter();
+        let mut vec_map = VecMap::with_capacity(lines.size_hint().0);
+        for cookies_raw in lines {
+            config.check(Limit::ValueLength, cookies_raw.len())?;
+            let cookies_str = from_utf8(cookies_raw)?;
+            for cookie_str in cookies_str.split(';') {
+                let mut key_val = cookie_str.splitn(2, '=');
+                let key_val = (key_val.next(), key_val.next());
+                if let (Some(key), Some(val)) = key_val {
+                    config.check(Limit::CookiePairs, vec_map.len() + 1)?;
+                    vec_map.insert(key.trim(), val.trim());
+                }
+            }
+        }
+
+        if vec_map.len() != 0 {
+            Ok(CookieRef(vec_map))
+        } else {
+            Err(::Error::header(::HeaderErrorKind::Empty))
+        }
+    }
+}
+
+impl<'a> fmt::Debug for CookieRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_map().entries(self.iter()).finish()
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::Cookie;
-    use header::{Header, Raw};
+    use super::{Cookie, CookieRef};
+    use header::{Header, HeaderRef, Raw};
 
     #[test]
     fn test_set_and_get() {
@@ -298,8 +431,37 @@ mod tests {
         cookie.append("double", "=2");
         assert_eq!(cookie, parsed);
     }
+
+    #[test]
+    fn test_parse_ref() {
+        let r: Raw = vec![b"foo=bar; dup=1".to_vec(), b" baz = quux; dup=2".to_vec()].into();
+        let cookie = CookieRef::parse_header_ref(&r).unwrap();
+        assert_eq!(cookie.get("foo"), Some("bar"));
+        assert_eq!(cookie.get("dup"), Some("2"));
+        assert_eq!(cookie.get("nope"), None);
+        let pairs: Vec<_> = cookie.iter().collect();
+        assert_eq!(pairs, [("foo", "bar"), ("dup", "2"), ("baz", "quux")]);
+        assert_eq!(cookie.len(), 3);
+        assert_eq!(cookie.into_owned(), Cookie::parse_header(&r).unwrap());
+
+        let owned = Cookie::parse_header(&r).unwrap();
+        let pairs: Vec<_> = owned.iter().collect();
+        assert_eq!(pairs, [("foo", "bar"), ("dup", "2"), ("baz", "quux")]);
+        assert_eq!(owned.to_string(), "foo=bar; dup=2; baz=quux");
+        assert_eq!(
+            format!("{:?}", owned),
+            r#"{"foo": "bar", "dup": "2", "baz": "quux"}"#
+        );
+
+        let r: Raw = b"invalid; ;".to_vec().into();
+        assert!(CookieRef::parse_header_ref(&r).is_err());
+    }
 }
 
 bench_header!(bench, Cookie, { vec![b"foo=bar; baz=quux".to_vec()] });
 
 standard_header!(Cookie, COOKIE);
+
+__hyper__try_from!(Cookie);
+__hyper__from_str!(Cookie);
+__hyper__serde!(Cookie);
@@ -0,0 +1,41 @@
+use unicase::Ascii;
+
+header! {
+    /// `Critical-CH` header, defined in
+    /// [Client Hint Reliability](https://datatracker.ietf.org/doc/html/draft-davidben-http-client-hint-reliability#section-3)
+    ///
+    /// The `Critical-CH` response header lists the client hints, also
+    /// requested via `Accept-CH`, without which the server would have
+    /// produced a different response. A client which did not send a listed
+    /// hint it supports retries the request with the hint.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Critical-CH = sf-list
+    /// ```
+    ///
+    /// # Example values
+    /// * `Sec-CH-Prefers-Reduced-Motion`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # extern crate unicase;
+    /// # fn main() {
+    /// use hyperx::header::{CriticalCh, TypedHeaders};
+    /// use unicase::Ascii;
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let hints = CriticalCh(vec![Ascii::new("Sec-CH-Prefers-Reduced-Motion".to_owned())].into());
+    /// headers.encode(&hints);
+    /// # }
+    /// ```
+    (CriticalCh, "Critical-CH") => (Ascii<String>)*
+
+    test_critical_ch {
+        test_header!(test1, [b"Sec-CH-Prefers-Reduced-Motion"]);
+    }
+}
@@ -0,0 +1,99 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Cross-Origin-Embedder-Policy` header, defined in
+/// [HTML](https://html.spec.whatwg.org/multipage/browsers.html#coep)
+///
+/// The `Cross-Origin-Embedder-Policy` header controls whether a document
+/// may load cross-origin resources which do not explicitly grant permission.
+/// Parameters such as `report-to` are ignored when parsing.
+///
+/// # ABNF
+///
+/// ```text
+/// Cross-Origin-Embedder-Policy = "unsafe-none" / "require-corp" / "credentialless"
+/// ```
+///
+/// # Example values
+///
+/// * `require-corp`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{CrossOriginEmbedderPolicy, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&CrossOriginEmbedderPolicy::RequireCorp);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CrossOriginEmbedderPolicy {
+    /// `unsafe-none`
+    UnsafeNone,
+    /// `require-corp`
+    RequireCorp,
+    /// `credentialless`
+    Credentialless,
+}
+
+impl Header for CrossOriginEmbedderPolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Cross-Origin-Embedder-Policy";
+        NAME
+    }
+
+    __hyper__http_name!("Cross-Origin-Embedder-Policy");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<CrossOriginEmbedderPolicy>
+    where
+        T: RawLike<'a>,
+    {
+        use self::CrossOriginEmbedderPolicy::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        // Parameters such as `report-to` are ignored
+        let value = value.split(';').next().unwrap_or_default();
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("unsafe-none", UnsafeNone),
+                ("require-corp", RequireCorp),
+                ("credentialless", Credentialless),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for CrossOriginEmbedderPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CrossOriginEmbedderPolicy::*;
+        f.write_str(match *self {
+            UnsafeNone => "unsafe-none",
+            RequireCorp => "require-corp",
+            Credentialless => "credentialless",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_cross_origin_embedder_policy {
+    use super::CrossOriginEmbedderPolicy as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(
+        test_require_corp,
+        vec![b"require-corp"],
+        Some(HeaderField::RequireCorp)
+    );
+    test_header!(test_credentialless, [b"credentialless"]);
+    test_header!(test_other, vec![b"same-origin"], None);
+}
+
+__hyper__try_from!(CrossOriginEmbedderPolicy);
+__hyper__from_str!(CrossOriginEmbedderPolicy);
+__hyper__serde!(CrossOriginEmbedderPolicy);
@@ -0,0 +1,113 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Cross-Origin-Opener-Policy` header, defined in
+/// [HTML](https://html.spec.whatwg.org/multipage/browsers.html#cross-origin-opener-policies)
+///
+/// The `Cross-Origin-Opener-Policy` header controls whether a top-level
+/// document shares its browsing context group with cross-origin documents.
+/// Parameters such as `report-to` are ignored when parsing.
+///
+/// # ABNF
+///
+/// ```text
+/// Cross-Origin-Opener-Policy = "unsafe-none" / "same-origin-allow-popups"
+///                              / "same-origin" / "noopener-allow-popups"
+/// ```
+///
+/// # Example values
+///
+/// * `same-origin`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{CrossOriginOpenerPolicy, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&CrossOriginOpenerPolicy::SameOrigin);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CrossOriginOpenerPolicy {
+    /// `unsafe-none`
+    UnsafeNone,
+    /// `same-origin-allow-popups`
+    SameOriginAllowPopups,
+    /// `same-origin`
+    SameOrigin,
+    /// `noopener-allow-popups`
+    NoopenerAllowPopups,
+}
+
+impl Header for CrossOriginOpenerPolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Cross-Origin-Opener-Policy";
+        NAME
+    }
+
+    __hyper__http_name!("Cross-Origin-Opener-Policy");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<CrossOriginOpenerPolicy>
+    where
+        T: RawLike<'a>,
+    {
+        use self::CrossOriginOpenerPolicy::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        // Parameters such as `report-to` are ignored
+        let value = value.split(';').next().unwrap_or_default();
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("unsafe-none", UnsafeNone),
+                ("same-origin-allow-popups", SameOriginAllowPopups),
+                ("same-origin", SameOrigin),
+                ("noopener-allow-popups", NoopenerAllowPopups),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for CrossOriginOpenerPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CrossOriginOpenerPolicy::*;
+        f.write_str(match *self {
+            UnsafeNone => "unsafe-none",
+            SameOriginAllowPopups => "same-origin-allow-popups",
+            SameOrigin => "same-origin",
+            NoopenerAllowPopups => "noopener-allow-popups",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_cross_origin_opener_policy {
+    use super::CrossOriginOpenerPolicy as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(
+        test_same_origin,
+        vec![b"same-origin"],
+        Some(HeaderField::SameOrigin)
+    );
+    test_header!(test_popups, [b"same-origin-allow-popups"]);
+
+    #[test]
+    fn test_report_to() {
+        let raw: Raw = "unsafe-none; report-to=\"coop\"".into();
+        assert_eq!(
+            HeaderField::parse_header(&raw).ok(),
+            Some(HeaderField::UnsafeNone)
+        );
+    }
+    test_header!(test_other, vec![b"cross-origin"], None);
+}
+
+__hyper__try_from!(CrossOriginOpenerPolicy);
+__hyper__from_str!(CrossOriginOpenerPolicy);
+__hyper__serde!(CrossOriginOpenerPolicy);
@@ -0,0 +1,96 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Cross-Origin-Resource-Policy` header, defined in
+/// [Fetch](https://fetch.spec.whatwg.org/#cross-origin-resource-policy-header)
+///
+/// The `Cross-Origin-Resource-Policy` header restricts which origins may
+/// load the resource in `no-cors` requests.
+///
+/// # ABNF
+///
+/// ```text
+/// Cross-Origin-Resource-Policy = "same-origin" / "same-site" / "cross-origin"
+/// ```
+///
+/// # Example values
+///
+/// * `same-site`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{CrossOriginResourcePolicy, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&CrossOriginResourcePolicy::SameSite);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum CrossOriginResourcePolicy {
+    /// `same-origin`
+    SameOrigin,
+    /// `same-site`
+    SameSite,
+    /// `cross-origin`
+    CrossOrigin,
+}
+
+impl Header for CrossOriginResourcePolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Cross-Origin-Resource-Policy";
+        NAME
+    }
+
+    __hyper__http_name!("Cross-Origin-Resource-Policy");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<CrossOriginResourcePolicy>
+    where
+        T: RawLike<'a>,
+    {
+        use self::CrossOriginResourcePolicy::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("same-origin", SameOrigin),
+                ("same-site", SameSite),
+                ("cross-origin", CrossOrigin),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for CrossOriginResourcePolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::CrossOriginResourcePolicy::*;
+        f.write_str(match *self {
+            SameOrigin => "same-origin",
+            SameSite => "same-site",
+            CrossOrigin => "cross-origin",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_cross_origin_resource_policy {
+    use super::CrossOriginResourcePolicy as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(
+        test_same_site,
+        vec![b"same-site"],
+        Some(HeaderField::SameSite)
+    );
+    test_header!(test_cross_origin, [b"cross-origin"]);
+    test_header!(test_other, vec![b"none"], None);
+}
+
+__hyper__try_from!(CrossOriginResourcePolicy);
+__hyper__from_str!(CrossOriginResourcePolicy);
+__hyper__serde!(CrossOriginResourcePolicy);
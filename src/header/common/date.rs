@@ -1,38 +1,94 @@
-use header::HttpDate;
-
-header! {
-    /// `Date` header, defined in [RFC7231](http://tools.ietf.org/html/rfc7231#section-7.1.1.2)
-    ///
-    /// The `Date` header field represents the date and time at which the
-    /// message was originated.
-    ///
-    /// # ABNF
-    ///
-    /// ```text
-    /// Date = HTTP-date
-    /// ```
-    ///
-    /// # Example values
-    ///
-    /// * `Tue, 15 Nov 1994 08:12:31 GMT`
-    ///
-    /// # Example
-    ///
-    /// ```
-    /// # extern crate http;
-    /// use hyperx::header::{Date, TypedHeaders};
-    /// use std::time::SystemTime;
-    ///
-    /// let mut headers = http::HeaderMap::new();
-    /// headers.encode(&Date(SystemTime::now().into()));
-    /// ```
-    (Date, "Date") => [HttpDate]
-
-    test_date {
-        test_header!(test1, [b"Tue, 15 Nov 1994 08:12:31 GMT"]);
+use header::parsing::{self, Limit};
+use header::{Header, HttpDate, RawLike};
+use std::fmt;
+
+/// `Date` header, defined in [RFC7231](http://tools.ietf.org/html/rfc7231#section-7.1.1.2)
+///
+/// The `Date` header field represents the date and time at which the
+/// message was originated.
+///
+/// # ABNF
+///
+/// ```text
+/// Date = HTTP-date
+/// ```
+///
+/// # Example values
+///
+/// * `Tue, 15 Nov 1994 08:12:31 GMT`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{Date, TypedHeaders};
+/// use std::time::SystemTime;
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&Date(SystemTime::now().into()));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Date(pub HttpDate);
+
+__hyper__deref!(Date => HttpDate);
+__hyper__try_from!(Date);
+__hyper__from_str!(Date);
+__hyper__serde!(Date);
+
+impl Header for Date {
+    #[inline]
+    fn header_name() -> &'static str {
+        static NAME: &str = "Date";
+        NAME
+    }
+
+    __hyper__http_name!("Date");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<Date>
+    where
+        T: RawLike<'a>,
+    {
+        if let Some(line) = raw.one() {
+            if let Some(date) = HttpDate::parse_bytes(line) {
+                return parsing::check_limit(Limit::ValueLength, line.len())
+                    .map(|()| Date(date))
+                    .map_err(|e| e.with_header_name(Self::header_name()));
+            }
+        }
+        parsing::from_one_raw_str(raw)
+            .map(Date)
+            .map_err(|e| e.with_header_name(Self::header_name()))
+    }
+
+    #[inline]
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
     }
 }
 
+impl fmt::Display for Date {
+    #[inline]
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt::Display::fmt(&self.0, f)
+    }
+}
+
+__hyper__tm!(Date, tests {
+    test_header!(test1, [b"Tue, 15 Nov 1994 08:12:31 GMT"]);
+
+    #[test]
+    fn test_formats() {
+        let fixdate: Raw = "Sun, 06 Nov 1994 08:49:37 GMT".into();
+        let fixdate = HeaderField::parse_header(&fixdate).unwrap();
+        for value in &["Sunday, 06-Nov-94 08:49:37 GMT", " Sun Nov  6 08:49:37 1994 "] {
+            let raw: Raw = (*value).into();
+            assert_eq!(HeaderField::parse_header(&raw).unwrap(), fixdate);
+        }
+        let raw: Raw = "Mon, 06 Nov 1994 08:49:37 GMT".into();
+        assert!(HeaderField::parse_header(&raw).is_err());
+    }
+});
+
 bench_header!(imf_fixdate, Date, {
     vec![b"Mon, 07 Nov 1994 08:48:37 GMT".to_vec()]
 });
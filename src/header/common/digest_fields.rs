@@ -0,0 +1,416 @@
+use std::fmt;
+
+use header::sfv::{insert_member, BareItem, Dictionary, Item, ListEntry};
+use header::RawLike;
+
+use super::signature::{fmt_byte_sequences, parse_byte_sequences};
+
+// The fields of RFC9530 come in pairs, for the content and for the selected
+// representation of a message: of digests by algorithm, or of preferences
+// for algorithms.
+macro_rules! digest_field {
+    ($(#[$a:meta])*($id:ident, $n:expr) => Digests) => {
+        $(#[$a])*
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $id(pub Vec<(String, Vec<u8>)>);
+
+        impl $id {
+            /// Create an empty header, without digests.
+            pub fn new() -> $id {
+                $id::default()
+            }
+
+            /// The digest of the given algorithm, such as `sha-256`.
+            pub fn get(&self, algorithm: &str) -> Option<&[u8]> {
+                self.0
+                    .iter()
+                    .find(|m| m.0 == algorithm)
+                    .map(|m| m.1.as_slice())
+            }
+
+            /// Set the digest of an algorithm, replacing any existing in its
+            /// place.
+            ///
+            /// Fails with `HeaderErrorKind::Token` if the algorithm is not a
+            /// valid structured field key, such as `SHA-256`.
+            pub fn insert<A>(&mut self, algorithm: A, digest: Vec<u8>) -> $crate::Result<()>
+            where
+                A: Into<String>,
+            {
+                insert_member(&mut self.0, algorithm.into(), digest)
+            }
+        }
+
+        impl $crate::header::Header for $id {
+            fn header_name() -> &'static str {
+                static NAME: &str = $n;
+                NAME
+            }
+
+            $crate::__hyper__http_name!($n);
+
+            fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<$id>
+            where
+                T: RawLike<'a>,
+            {
+                parse_byte_sequences(raw).map($id)
+            }
+
+            fn fmt_header(&self, f: &mut $crate::header::Formatter) -> fmt::Result {
+                f.fmt_line(self)
+            }
+        }
+
+        impl fmt::Display for $id {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_byte_sequences(f, &self.0)
+            }
+        }
+
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
+    };
+
+    ($(#[$a:meta])*($id:ident, $n:expr) => Preferences) => {
+        $(#[$a])*
+        #[derive(Clone, Debug, Default, PartialEq, Eq)]
+        pub struct $id(pub Vec<(String, u8)>);
+
+        impl $id {
+            /// Create an empty header, without algorithms.
+            pub fn new() -> $id {
+                $id::default()
+            }
+
+            /// The preference of the given algorithm, or `None` if not listed.
+            pub fn preference(&self, algorithm: &str) -> Option<u8> {
+                preference(&self.0, algorithm)
+            }
+
+            /// Set the preference of an algorithm, from `0` to `10`, replacing
+            /// any existing in its place.
+            ///
+            /// Fails with `HeaderErrorKind::Token` if the algorithm is not a
+            /// valid structured field key, such as `SHA-256`.
+            pub fn insert<A>(&mut self, algorithm: A, preference: u8) -> $crate::Result<()>
+            where
+                A: Into<String>,
+            {
+                insert_member(&mut self.0, algorithm.into(), preference)
+            }
+
+            /// Return the supported algorithm of the highest non-zero
+            /// preference, with ties going to the earliest in `supported`.
+            pub fn preferred<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+                preferred(&self.0, supported)
+            }
+        }
+
+        impl $crate::header::Header for $id {
+            fn header_name() -> &'static str {
+                static NAME: &str = $n;
+                NAME
+            }
+
+            $crate::__hyper__http_name!($n);
+
+            fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<$id>
+            where
+                T: RawLike<'a>,
+            {
+                parse_preferences(raw).map($id)
+            }
+
+            fn fmt_header(&self, f: &mut $crate::header::Formatter) -> fmt::Result {
+                f.fmt_line(self)
+            }
+        }
+
+        impl fmt::Display for $id {
+            fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                fmt_preferences(f, &self.0)
+            }
+        }
+
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
+    };
+}
+
+digest_field! {
+    /// `Content-Digest` header, defined in
+    /// [RFC9530](https://www.rfc-editor.org/rfc/rfc9530#section-2)
+    ///
+    /// The `Content-Digest` header conveys one or more digests of the content
+    /// of a message, as sent, by hashing algorithm, such as `sha-256` or
+    /// `sha-512`. It obsoletes the `Digest` header of RFC3230, see
+    /// `InstanceDigest`.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Content-Digest = sf-dictionary
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:`
+    /// * `sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:, sha-512=:YMAam51Jz/jOATT6/zvHrLVgOYTGFy1d6GJiOHTohq4yP+pgk4vf2aCsyRZOtw8MjkM7iw7yZ/WkppmM44T3qg==:`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{ContentDigest, TypedHeaders};
+    ///
+    /// let mut digest = ContentDigest::new();
+    /// digest.insert("sha-256", b"\x01\x02\x03".to_vec()).unwrap();
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&digest);
+    /// assert_eq!(headers["content-digest"], "sha-256=:AQID:");
+    ///
+    /// let digest = headers.decode::<ContentDigest>().unwrap();
+    /// assert_eq!(digest.get("sha-256"), Some(&b"\x01\x02\x03"[..]));
+    /// # }
+    /// ```
+    (ContentDigest, "Content-Digest") => Digests
+}
+
+digest_field! {
+    /// `Repr-Digest` header, defined in
+    /// [RFC9530](https://www.rfc-editor.org/rfc/rfc9530#section-3)
+    ///
+    /// The `Repr-Digest` header conveys one or more digests of the selected
+    /// representation of a message, including any content coding, but of the
+    /// whole representation where the content is a range of it, by hashing
+    /// algorithm, such as `sha-256` or `sha-512`.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Repr-Digest = sf-dictionary
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:`
+    /// * `sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:, sha-512=:YMAam51Jz/jOATT6/zvHrLVgOYTGFy1d6GJiOHTohq4yP+pgk4vf2aCsyRZOtw8MjkM7iw7yZ/WkppmM44T3qg==:`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{ReprDigest, TypedHeaders};
+    ///
+    /// let mut digest = ReprDigest::new();
+    /// digest.insert("sha-256", b"\x01\x02\x03".to_vec()).unwrap();
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&digest);
+    /// assert_eq!(headers["repr-digest"], "sha-256=:AQID:");
+    ///
+    /// let digest = headers.decode::<ReprDigest>().unwrap();
+    /// assert_eq!(digest.get("sha-256"), Some(&b"\x01\x02\x03"[..]));
+    /// # }
+    /// ```
+    (ReprDigest, "Repr-Digest") => Digests
+}
+
+digest_field! {
+    /// `Want-Content-Digest` header, defined in
+    /// [RFC9530](https://www.rfc-editor.org/rfc/rfc9530#section-4)
+    ///
+    /// The `Want-Content-Digest` header lists the hashing algorithms, such as
+    /// `sha-256`, which the sender would like in the `Content-Digest` header of
+    /// a message, each with a preference from `1`, least preferred, to `10`,
+    /// most preferred. A preference of `0` excludes an algorithm.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Want-Content-Digest = sf-dictionary
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `sha-256=1`
+    /// * `sha-512=3, sha-256=10, unixsum=0`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{Header, Raw, WantContentDigest};
+    ///
+    /// let raw = Raw::from("sha-512=3, sha-256=10, unixsum=0");
+    /// let want = WantContentDigest::parse_header(&raw).unwrap();
+    /// assert_eq!(want.preference("sha-512"), Some(3));
+    /// assert_eq!(want.preferred(&["sha-512", "sha-256"]), Some("sha-256"));
+    /// assert_eq!(want.preferred(&["unixsum", "md5"]), None);
+    /// # }
+    /// ```
+    (WantContentDigest, "Want-Content-Digest") => Preferences
+}
+
+digest_field! {
+    /// `Want-Repr-Digest` header, defined in
+    /// [RFC9530](https://www.rfc-editor.org/rfc/rfc9530#section-4)
+    ///
+    /// The `Want-Repr-Digest` header lists the hashing algorithms, such as
+    /// `sha-256`, which the sender would like in the `Repr-Digest` header of
+    /// a message, each with a preference from `1`, least preferred, to `10`,
+    /// most preferred. A preference of `0` excludes an algorithm.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Want-Repr-Digest = sf-dictionary
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `sha-256=1`
+    /// * `sha-512=3, sha-256=10, unixsum=0`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{Header, Raw, WantReprDigest};
+    ///
+    /// let raw = Raw::from("sha-512=3, sha-256=10, unixsum=0");
+    /// let want = WantReprDigest::parse_header(&raw).unwrap();
+    /// assert_eq!(want.preference("sha-512"), Some(3));
+    /// assert_eq!(want.preferred(&["sha-512", "sha-256"]), Some("sha-256"));
+    /// assert_eq!(want.preferred(&["unixsum", "md5"]), None);
+    /// # }
+    /// ```
+    (WantReprDigest, "Want-Repr-Digest") => Preferences
+}
+
+// Parse a non-empty dictionary with integer values from 0 to 10.
+fn parse_preferences<'a, T>(raw: &'a T) -> ::Result<Vec<(String, u8)>>
+where
+    T: RawLike<'a>,
+{
+    let dict = Dictionary::from_raw(raw)?;
+    if dict.is_empty() {
+        return Err(::Error::header(::HeaderErrorKind::Empty));
+    }
+    dict.iter()
+        .map(|(key, member)| match *member {
+            ListEntry::Item(Item {
+                bare_item: BareItem::Integer(p),
+                ..
+            }) if (0..=10).contains(&p.get()) => Ok((key.to_owned(), p.get() as u8)),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+        })
+        .collect()
+}
+
+fn fmt_preferences(f: &mut fmt::Formatter, members: &[(String, u8)]) -> fmt::Result {
+    let mut dict = Dictionary::new();
+    for &(ref key, p) in members {
+        dict.insert(key.as_str(), Item::new(p))
+            .map_err(|_| fmt::Error)?;
+    }
+    fmt::Display::fmt(&dict, f)
+}
+
+fn preference(members: &[(String, u8)], algorithm: &str) -> Option<u8> {
+    members.iter().find(|m| m.0 == algorithm).map(|m| m.1)
+}
+
+fn preferred<'a>(members: &[(String, u8)], supported: &[&'a str]) -> Option<&'a str> {
+    let mut best: Option<(&'a str, u8)> = None;
+    for &algorithm in supported {
+        match preference(members, algorithm) {
+            Some(p) if p > 0 && best.map_or(true, |b| p > b.1) => best = Some((algorithm, p)),
+            _ => {}
+        }
+    }
+    best.map(|b| b.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::{Header, Raw};
+
+    #[test]
+    fn test_parse_digests() {
+        let raw = Raw::from(vec![
+            b"sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:".to_vec(),
+            b"unixsum=:AQ==:".to_vec(),
+        ]);
+        let digest = ContentDigest::parse_header(&raw).unwrap();
+        assert_eq!(digest.get("sha-256").unwrap().len(), 32);
+        assert_eq!(digest.get("unixsum"), Some(&[1][..]));
+        assert_eq!(digest.get("SHA-256"), None);
+        assert_eq!(
+            digest.to_string(),
+            "sha-256=:RK/0qy18MlBSVnWgjwz6lZEWjP/lF5HF9bvEF8FabDg=:, unixsum=:AQ==:"
+        );
+        assert_eq!(ReprDigest::parse_header(&raw).unwrap().0, digest.0);
+
+        for bad in &["", "sha-256=AQID", "SHA-256=:AQID:", "sha-256=:AQID"] {
+            let raw = Raw::from(*bad);
+            assert!(ContentDigest::parse_header(&raw).is_err(), "{}", bad);
+            assert!(ReprDigest::parse_header(&raw).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_insert_digests() {
+        let mut digest = ReprDigest::new();
+        digest.insert("sha-256", vec![1]).unwrap();
+        digest.insert("sha-512", vec![2]).unwrap();
+        digest.insert("sha-256", vec![3]).unwrap();
+        assert_eq!(digest.to_string(), "sha-256=:Aw==:, sha-512=:Ag==:");
+
+        let e = digest.insert("SHA-256", vec![4]).unwrap_err();
+        assert_eq!(e.header_error().unwrap().kind(), ::HeaderErrorKind::Token);
+        assert!(ContentDigest::new().insert("", vec![4]).is_err());
+        assert_eq!(digest.0.len(), 2);
+    }
+
+    #[test]
+    fn test_parse_preferences() {
+        let raw = Raw::from(vec![
+            b"sha-256=1".to_vec(),
+            b"sha-512=1, unixsum=0".to_vec(),
+        ]);
+        let want = WantContentDigest::parse_header(&raw).unwrap();
+        assert_eq!(want.preference("sha-256"), Some(1));
+        assert_eq!(want.preference("md5"), None);
+        assert_eq!(want.preferred(&["sha-512", "sha-256"]), Some("sha-512"));
+        assert_eq!(want.preferred(&["unixsum"]), None);
+        assert_eq!(want.to_string(), "sha-256=1, sha-512=1, unixsum=0");
+        assert_eq!(WantReprDigest::parse_header(&raw).unwrap().0, want.0);
+
+        for bad in &["", "sha-256", "sha-256=11", "sha-256=-1", "sha-256=1.0"] {
+            let raw = Raw::from(*bad);
+            assert!(WantContentDigest::parse_header(&raw).is_err(), "{}", bad);
+            assert!(WantReprDigest::parse_header(&raw).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_insert_preferences() {
+        let mut want = WantContentDigest::new();
+        want.insert("sha-256", 5).unwrap();
+        want.insert("sha-512", 10).unwrap();
+        want.insert("sha-256", 0).unwrap();
+        assert_eq!(want.to_string(), "sha-256=0, sha-512=10");
+        assert!(WantReprDigest::new().insert("SHA-256", 1).is_err());
+    }
+}
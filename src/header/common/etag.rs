@@ -41,6 +41,7 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&ETag(EntityTag::new(true, "xyzzy".to_owned())));
     /// ```
+    #[derive(Eq, Hash)]
     (ETag, "ETag") => [EntityTag]
 
     test_etag {
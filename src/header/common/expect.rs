@@ -31,6 +31,8 @@ impl Header for Expect {
         NAME
     }
 
+    __hyper__http_name!("Expect");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Expect>
     where
         T: RawLike<'a>,
@@ -47,10 +49,10 @@ impl Header for Expect {
             if unicase::eq_ascii(text, "100-continue") {
                 Ok(Expect::Continue)
             } else {
-                Err(::Error::Header)
+                Err(::Error::header(::HeaderErrorKind::Token))
             }
         } else {
-            Err(::Error::Header)
+            Err(::header::parsing::not_one(raw))
         }
     }
 
@@ -66,3 +68,7 @@ impl fmt::Display for Expect {
 }
 
 standard_header!(Expect, EXPECT);
+
+__hyper__try_from!(Expect);
+__hyper__from_str!(Expect);
+__hyper__serde!(Expect);
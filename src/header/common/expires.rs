@@ -30,6 +30,7 @@ header! {
     /// let expiration = SystemTime::now() + Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&Expires(expiration.into()));
     /// ```
+    #[derive(Eq, Hash)]
     (Expires, "Expires") => [HttpDate]
 
     test_expires {
@@ -65,6 +65,8 @@ impl Header for Host {
         NAME
     }
 
+    __hyper__http_name!("Host");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Host>
     where
         T: RawLike<'a>,
@@ -136,3 +138,6 @@ mod tests {
 bench_header!(bench, Host, { vec![b"foo.com:3000".to_vec()] });
 
 standard_header!(Host, HOST);
+
+__hyper__try_from!(Host);
+__hyper__serde!(Host);
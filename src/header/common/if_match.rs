@@ -47,9 +47,10 @@ header! {
     ///         EntityTag::new(false, "xyzzy".to_owned()),
     ///         EntityTag::new(false, "foobar".to_owned()),
     ///         EntityTag::new(false, "bazquux".to_owned()),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
+    #[derive(Eq, Hash)]
     (IfMatch, "If-Match") => {Any / (EntityTag)+}
 
     test_if_match {
@@ -57,12 +58,12 @@ header! {
             test1,
             vec![b"\"xyzzy\""],
             Some(HeaderField::Items(
-                vec![EntityTag::new(false, "xyzzy".to_owned())])));
+                smallvec![EntityTag::new(false, "xyzzy".to_owned())])));
         test_header!(
             test2,
             vec![b"\"xyzzy\", \"r2d2xxxx\", \"c3piozzzz\""],
             Some(HeaderField::Items(
-                vec![EntityTag::new(false, "xyzzy".to_owned()),
+                smallvec![EntityTag::new(false, "xyzzy".to_owned()),
                      EntityTag::new(false, "r2d2xxxx".to_owned()),
                      EntityTag::new(false, "c3piozzzz".to_owned())])));
         test_header!(test3, vec![b"*"], Some(IfMatch::Any));
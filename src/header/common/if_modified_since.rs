@@ -30,6 +30,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&IfModifiedSince(modified.into()));
     /// ```
+    #[derive(Eq, Hash)]
     (IfModifiedSince, "If-Modified-Since") => [HttpDate]
 
     test_if_modified_since {
@@ -49,9 +49,10 @@ header! {
     ///         EntityTag::new(false, "xyzzy".to_owned()),
     ///         EntityTag::new(false, "foobar".to_owned()),
     ///         EntityTag::new(false, "bazquux".to_owned()),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
+    #[derive(Eq, Hash)]
     (IfNoneMatch, "If-None-Match") => {Any / (EntityTag)+}
 
     test_if_none_match {
@@ -84,7 +85,10 @@ mod tests {
         let weak_etag = EntityTag::new(true, "weak-etag".to_owned());
         entities.push(foobar_etag);
         entities.push(weak_etag);
-        assert_eq!(if_none_match.ok(), Some(IfNoneMatch::Items(entities)));
+        assert_eq!(
+            if_none_match.ok(),
+            Some(IfNoneMatch::Items(entities.into()))
+        );
     }
 }
 
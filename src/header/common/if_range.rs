@@ -59,6 +59,8 @@ impl Header for IfRange {
         static NAME: &str = "If-Range";
         NAME
     }
+
+    __hyper__http_name!("If-Range");
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<IfRange>
     where
         T: RawLike<'a>,
@@ -71,7 +73,7 @@ impl Header for IfRange {
         if let Ok(date) = date {
             return Ok(IfRange::Date(date));
         }
-        Err(::Error::Header)
+        Err(::Error::header(::HeaderErrorKind::Syntax))
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> ::std::fmt::Result {
@@ -99,3 +101,7 @@ mod test_if_range {
 }
 
 standard_header!(IfRange, IF_RANGE);
+
+__hyper__try_from!(IfRange);
+__hyper__from_str!(IfRange);
+__hyper__serde!(IfRange);
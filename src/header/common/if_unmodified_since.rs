@@ -31,6 +31,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&IfUnmodifiedSince(modified.into()));
     /// ```
+    #[derive(Eq, Hash)]
     (IfUnmodifiedSince, "If-Unmodified-Since") => [HttpDate]
 
     test_if_unmodified_since {
@@ -0,0 +1,128 @@
+use std::fmt;
+use std::str::FromStr;
+
+use header::Base64;
+
+header! {
+    /// `Digest` header, defined in
+    /// [RFC3230](https://www.rfc-editor.org/rfc/rfc3230#section-4.3.2)
+    ///
+    /// The `Digest` header conveys one or more digests of the instance
+    /// (representation) of a message, each by algorithm, such as
+    /// `SHA-256`, for the recipient to verify its integrity. It is
+    /// obsoleted by `Content-Digest` and `Repr-Digest` of RFC9530, but still
+    /// used by older clients. Named `InstanceDigest`, as `Digest` is the
+    /// scheme of `Authorization`.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Digest = "Digest" ":" #(instance-digest)
+    /// instance-digest = digest-algorithm "=" <encoded digest output>
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE=`
+    /// * `MD5=HUXZLQLMuI/KZ5KDcJPcOA==, UNIXsum=30637`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{DigestValue, InstanceDigest, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let digest = InstanceDigest(vec![DigestValue::new("SHA-256", b"\x01\x02\x03")].into());
+    /// headers.encode(&digest);
+    /// assert_eq!(headers["digest"], "SHA-256=AQID");
+    ///
+    /// let digest = headers.decode::<InstanceDigest>().unwrap();
+    /// let sha256 = digest.get("sha-256").unwrap();
+    /// assert_eq!(sha256.bytes().unwrap(), b"\x01\x02\x03");
+    /// # }
+    /// ```
+    (InstanceDigest, "Digest") => (DigestValue)+
+
+    test_instance_digest {
+        test_header!(test1, [b"SHA-256=X48E9qOokqqrvdts8nOJRJN3OWDUoyWxBf7kbu9DBPE="]);
+        test_header!(test2, [b"MD5=HUXZLQLMuI/KZ5KDcJPcOA==, UNIXsum=30637"]);
+
+        #[test]
+        fn test_get() {
+            let raw = Raw::from(vec![b"MD5=HUXZLQLMuI/KZ5KDcJPcOA==".to_vec(), b"unixsum=30637".to_vec()]);
+            let digest = InstanceDigest::parse_header(&raw).unwrap();
+            assert_eq!(digest.get("UNIXsum").unwrap().value, "30637");
+            assert_eq!(digest.get("md5").unwrap().bytes().unwrap().len(), 16);
+            assert!(digest.get("SHA").is_none());
+        }
+
+        #[test]
+        fn test_invalid() {
+            assert!("SHA-256".parse::<DigestValue>().is_err());
+            assert!("=AQID".parse::<DigestValue>().is_err());
+            assert!("SHA-256=".parse::<DigestValue>().is_err());
+            let value: DigestValue = "SHA-256=!".parse().unwrap();
+            assert!(value.bytes().is_err());
+        }
+    }
+}
+
+impl InstanceDigest {
+    /// The digest of the given algorithm, ignoring ASCII case.
+    pub fn get(&self, algorithm: &str) -> Option<&DigestValue> {
+        self.iter()
+            .find(|d| d.algorithm.eq_ignore_ascii_case(algorithm))
+    }
+}
+
+/// An item of the `Digest` header: a digest algorithm and encoded value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DigestValue {
+    /// The algorithm, such as `SHA-256` or `MD5`, compared ignoring ASCII
+    /// case.
+    pub algorithm: String,
+    /// The encoded digest, in base64 for the `SHA`, `SHA-256`, `SHA-512`
+    /// and `MD5` algorithms, or otherwise as defined by the algorithm.
+    pub value: String,
+}
+
+impl DigestValue {
+    /// Create a digest, encoding the bytes in base64.
+    pub fn new<A: Into<String>>(algorithm: A, digest: &[u8]) -> DigestValue {
+        DigestValue {
+            algorithm: algorithm.into(),
+            value: Base64::STANDARD.encode(digest),
+        }
+    }
+
+    /// Decode the digest from base64, failing with
+    /// `HeaderErrorKind::Syntax` for an algorithm with another encoding.
+    pub fn bytes(&self) -> ::Result<Vec<u8>> {
+        Base64::STANDARD.decode(&self.value)
+    }
+}
+
+impl fmt::Display for DigestValue {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.algorithm, self.value)
+    }
+}
+
+impl FromStr for DigestValue {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<DigestValue> {
+        match s.split_once('=') {
+            Some((algorithm, value)) if !algorithm.is_empty() && !value.is_empty() => {
+                Ok(DigestValue {
+                    algorithm: algorithm.to_owned(),
+                    value: value.to_owned(),
+                })
+            }
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+        }
+    }
+}
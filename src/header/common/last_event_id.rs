@@ -1,4 +1,5 @@
 use header::{self, Header, RawLike};
+use std::borrow::Cow;
 use std::fmt::{self, Display};
 
 /// `Last-Event-ID` header, defined in
@@ -22,11 +23,11 @@ use std::fmt::{self, Display};
 /// let mut headers = http::HeaderMap::new();
 /// headers.insert(
 ///     "last-event-id",
-///     LastEventId("1".to_owned()).to_string().parse().unwrap()
+///     LastEventId("1".into()).to_string().parse().unwrap()
 /// );
 /// ```
 #[derive(Clone, Debug, PartialEq)]
-pub struct LastEventId(pub String);
+pub struct LastEventId(pub Cow<'static, str>);
 
 impl Header for LastEventId {
     #[inline]
@@ -35,15 +36,19 @@ impl Header for LastEventId {
         NAME
     }
 
+    __hyper__http_name!("Last-Event-ID");
+
     #[inline]
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Self>
     where
         T: RawLike<'a>,
     {
         match raw.one() {
-            Some(line) if line.is_empty() => Ok(LastEventId("".to_owned())),
-            Some(line) => header::parsing::from_raw_str(line).map(LastEventId),
-            None => Err(::Error::Header),
+            Some(b"") => Ok(LastEventId("".into())),
+            Some(line) => {
+                header::parsing::from_raw_str::<String>(line).map(|s| LastEventId(s.into()))
+            }
+            None => Err(::header::parsing::not_one(raw)),
         }
     }
 
@@ -53,6 +58,10 @@ impl Header for LastEventId {
     }
 }
 
+__hyper__try_from!(LastEventId);
+__hyper__from_str!(LastEventId);
+__hyper__serde!(LastEventId);
+
 impl Display for LastEventId {
     #[inline]
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
@@ -60,11 +69,11 @@ impl Display for LastEventId {
     }
 }
 
-__hyper__deref!(LastEventId => String);
+__hyper__deref!(LastEventId => Cow<'static, str>);
 
 __hyper__tm!(LastEventId, tests {
     // Initial state
     test_header!(test1, [b""]);
     // Own testcase
-    test_header!(test2, vec![b"1"], Some(LastEventId("1".to_owned())));
+    test_header!(test2, vec![b"1"], Some(LastEventId("1".into())));
 });
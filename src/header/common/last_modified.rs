@@ -30,6 +30,7 @@ header! {
     /// let modified = SystemTime::now() - Duration::from_secs(60 * 60 * 24);
     /// headers.encode(&LastModified(modified.into()));
     /// ```
+    #[derive(Eq, Hash)]
     (LastModified, "Last-Modified") => [HttpDate]
 
     test_last_modified {
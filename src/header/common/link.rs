@@ -1,5 +1,5 @@
 use header::parsing;
-use header::{Header, RawLike};
+use header::{Header, HeaderRef, RawLike};
 use language_tags::LanguageTag;
 use mime::Mime;
 use std::borrow::Cow;
@@ -104,6 +104,10 @@ pub struct LinkValue {
     /// Hint on the media type of the result of dereferencing
     /// the link: `type`.
     media_type: Option<Mime>,
+
+    /// Other parameters: `link-extension`, such as the `as` of a preload
+    /// link, in order.
+    params: Vec<(String, Option<String>)>,
 }
 
 /// A Media Descriptors Enum based on:
@@ -261,6 +265,7 @@ impl LinkValue {
             title: None,
             title_star: None,
             media_type: None,
+            params: Vec::new(),
         }
     }
 
@@ -309,6 +314,22 @@ impl LinkValue {
         self.media_type.as_ref()
     }
 
+    /// Get the `LinkValue`'s extension parameters, as name and value if
+    /// any, in order.
+    pub fn params(&self) -> &[(String, Option<String>)] {
+        self.params.as_ref()
+    }
+
+    /// Get the value of the `LinkValue`'s first extension parameter of the
+    /// given name, compared case-insensitively: `Some(None)` if present
+    /// without a value.
+    pub fn param(&self, name: &str) -> Option<Option<&str>> {
+        self.params
+            .iter()
+            .find(|p| p.0.eq_ignore_ascii_case(name))
+            .map(|p| p.1.as_deref())
+    }
+
     /// Add a `RelationType` to the `LinkValue`'s `rel` parameter.
     pub fn push_rel(mut self, rel: RelationType) -> LinkValue {
         let mut v = self.rel.take().unwrap_or_default();
@@ -380,6 +401,24 @@ impl LinkValue {
 
         self
     }
+
+    /// Add an extension parameter with a value to the `LinkValue`.
+    pub fn push_param<N, V>(mut self, name: N, value: V) -> LinkValue
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        self.params.push((name.into(), Some(value.into())));
+
+        self
+    }
+
+    /// Add an extension parameter without a value to the `LinkValue`.
+    pub fn push_flag<N: Into<String>>(mut self, name: N) -> LinkValue {
+        self.params.push((name.into(), None));
+
+        self
+    }
 }
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -392,6 +431,8 @@ impl Header for Link {
         NAME
     }
 
+    __hyper__http_name!("Link");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Link>
     where
         T: RawLike<'a>,
@@ -409,9 +450,9 @@ impl Header for Link {
 
                     Some(Ok(p))
                 }
-                _ => Some(Err(::Error::Header)),
+                _ => Some(Err(::Error::header(::HeaderErrorKind::Syntax))),
             })
-            .unwrap_or(Err(::Error::Header))
+            .unwrap_or(Err(::Error::header(::HeaderErrorKind::Empty)))
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -455,6 +496,17 @@ impl fmt::Display for LinkValue {
         if let Some(ref media_type) = self.media_type {
             write!(f, "; type=\"{}\"", media_type)?;
         }
+        for (name, value) in &self.params {
+            write!(f, "; {}", name)?;
+            match *value {
+                Some(ref v) if parsing::is_token(v) => write!(f, "={}", v)?,
+                Some(ref v) => {
+                    f.write_str("=")?;
+                    parsing::fmt_quoted(f, v)?;
+                }
+                None => {}
+            }
+        }
 
         Ok(())
     }
@@ -476,7 +528,7 @@ impl FromStr for Link {
             // https://tools.ietf.org/html/rfc5988#section-5.1
             if segment.trim().starts_with('<') {
                 link_values.push(match verify_and_trim(segment.trim(), (b'<', b'>')) {
-                    Err(_) => return Err(::Error::Header),
+                    Err(_) => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                     Ok(s) => LinkValue {
                         link: s.to_owned().into(),
                         rel: None,
@@ -487,6 +539,7 @@ impl FromStr for Link {
                         title: None,
                         title_star: None,
                         media_type: None,
+                        params: Vec::new(),
                     },
                 });
             } else {
@@ -494,12 +547,12 @@ impl FromStr for Link {
                 let mut link_param_split = segment.splitn(2, '=');
 
                 let link_param_name = match link_param_split.next() {
-                    None => return Err(::Error::Header),
+                    None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                     Some(p) => p.trim(),
                 };
 
                 let link_header = match link_values.last_mut() {
-                    None => return Err(::Error::Header),
+                    None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                     Some(l) => l,
                 };
 
@@ -508,13 +561,15 @@ impl FromStr for Link {
                     // https://tools.ietf.org/html/rfc5988#section-5.3
                     if link_header.rel.is_none() {
                         link_header.rel = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => s
                                 .trim_matches(|c: char| c == '"' || c.is_whitespace())
                                 .split(' ')
                                 .map(|t| t.trim().parse())
                                 .collect::<Result<Vec<RelationType>, _>>()
-                                .map_err(|_| ::Error::Header)
+                                .map_err(|_| ::Error::header(::HeaderErrorKind::Syntax))
                                 .ok(),
                         };
                     }
@@ -522,9 +577,9 @@ impl FromStr for Link {
                     // Parse the `Context IRI`.
                     // https://tools.ietf.org/html/rfc5988#section-5.2
                     link_header.anchor = match link_param_split.next() {
-                        None | Some("") => return Err(::Error::Header),
+                        None | Some("") => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                         Some(s) => match verify_and_trim(s.trim(), (b'"', b'"')) {
-                            Err(_) => return Err(::Error::Header),
+                            Err(_) => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                             Ok(a) => Some(String::from(a)),
                         },
                     };
@@ -533,13 +588,15 @@ impl FromStr for Link {
                     // https://tools.ietf.org/html/rfc5988#section-5.3
                     if link_header.rev.is_none() {
                         link_header.rev = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => s
                                 .trim_matches(|c: char| c == '"' || c.is_whitespace())
                                 .split(' ')
                                 .map(|t| t.trim().parse())
                                 .collect::<Result<Vec<RelationType>, _>>()
-                                .map_err(|_| ::Error::Header)
+                                .map_err(|_| ::Error::header(::HeaderErrorKind::Syntax))
                                 .ok(),
                         }
                     }
@@ -549,9 +606,14 @@ impl FromStr for Link {
                     let mut v = link_header.href_lang.take().unwrap_or(Vec::new());
 
                     v.push(match link_param_split.next() {
-                        None | Some("") => return Err(::Error::Header),
+                        None | Some("") => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                         Some(s) => match s.trim().parse() {
-                            Err(_) => return Err(::Error::Header),
+                            Err(e) => {
+                                return Err(::Error::header_with_source(
+                                    ::HeaderErrorKind::Syntax,
+                                    e,
+                                ))
+                            }
                             Ok(t) => t,
                         },
                     });
@@ -562,13 +624,15 @@ impl FromStr for Link {
                     // https://tools.ietf.org/html/rfc5988#section-5.4
                     if link_header.media_desc.is_none() {
                         link_header.media_desc = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => s
                                 .trim_matches(|c: char| c == '"' || c.is_whitespace())
                                 .split(',')
                                 .map(|t| t.trim().parse())
                                 .collect::<Result<Vec<MediaDesc>, _>>()
-                                .map_err(|_| ::Error::Header)
+                                .map_err(|_| ::Error::header(::HeaderErrorKind::Syntax))
                                 .ok(),
                         };
                     }
@@ -577,9 +641,11 @@ impl FromStr for Link {
                     // https://tools.ietf.org/html/rfc5988#section-5.4
                     if link_header.title.is_none() {
                         link_header.title = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => match verify_and_trim(s.trim(), (b'"', b'"')) {
-                                Err(_) => return Err(::Error::Header),
+                                Err(_) => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                                 Ok(t) => Some(String::from(t)),
                             },
                         };
@@ -592,7 +658,9 @@ impl FromStr for Link {
                     //       https://tools.ietf.org/html/rfc5987#section-3.2.1
                     if link_header.title_star.is_none() {
                         link_header.title_star = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => Some(String::from(s.trim())),
                         };
                     }
@@ -601,18 +669,31 @@ impl FromStr for Link {
                     // https://tools.ietf.org/html/rfc5988#section-5.4
                     if link_header.media_type.is_none() {
                         link_header.media_type = match link_param_split.next() {
-                            None | Some("") => return Err(::Error::Header),
+                            None | Some("") => {
+                                return Err(::Error::header(::HeaderErrorKind::Syntax))
+                            }
                             Some(s) => match verify_and_trim(s.trim(), (b'"', b'"')) {
-                                Err(_) => return Err(::Error::Header),
+                                Err(_) => return Err(::Error::header(::HeaderErrorKind::Syntax)),
                                 Ok(t) => match t.parse() {
-                                    Err(_) => return Err(::Error::Header),
+                                    Err(_) => {
+                                        return Err(::Error::header(::HeaderErrorKind::Syntax))
+                                    }
                                     Ok(m) => Some(m),
                                 },
                             },
                         };
                     }
+                } else if parsing::is_token(link_param_name) {
+                    // Parse a `link-extension`.
+                    // https://tools.ietf.org/html/rfc5988#section-5
+                    let value = match link_param_split.next().map(str::trim) {
+                        None => None,
+                        Some("") => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+                        Some(s) => Some(parsing::unquote(s)?.into_owned()),
+                    };
+                    link_header.params.push((link_param_name.to_owned(), value));
                 } else {
-                    return Err(::Error::Header);
+                    return Err(::Error::header(::HeaderErrorKind::Syntax));
                 }
             }
         }
@@ -621,6 +702,140 @@ impl FromStr for Link {
     }
 }
 
+/// The `Link` header, borrowing its link-values from the raw header rather
+/// than copying them. See `HeaderRef`.
+///
+/// Unlike `Link`, parameters are not interpreted, but kept as (name, value)
+/// pairs of each link-value. Quoted values are unquoted, which copies only
+/// those with escapes.
+///
+/// ```
+/// use hyperx::header::{HeaderRef, LinkRef, Raw};
+///
+/// let raw = Raw::from(r#"<https://api.example/items?page=2>; rel="next", </items?page=9>; rel=last"#);
+/// let link = LinkRef::parse_header_ref(&raw).unwrap();
+/// let next = link.values().iter().find(|v| v.has_rel("next")).unwrap();
+/// assert_eq!(next.link(), "https://api.example/items?page=2");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkRef<'a>(Vec<LinkValueRef<'a>>);
+
+/// A link-value of the `LinkRef` header.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LinkValueRef<'a> {
+    link: &'a str,
+    params: Vec<(&'a str, Option<Cow<'a, str>>)>,
+}
+
+impl<'a> LinkRef<'a> {
+    /// The link-values, in order.
+    pub fn values(&self) -> &[LinkValueRef<'a>] {
+        &self.0
+    }
+}
+
+impl<'a> LinkValueRef<'a> {
+    /// The target URI reference.
+    pub fn link(&self) -> &'a str {
+        self.link
+    }
+
+    /// The parameters (name, value), in order.
+    pub fn params(&self) -> &[(&'a str, Option<Cow<'a, str>>)] {
+        &self.params
+    }
+
+    /// Get the value of the first parameter of the given name, compared
+    /// case-insensitively: `Some(None)` if present without a value.
+    pub fn param(&self, name: &str) -> Option<Option<&str>> {
+        self.params
+            .iter()
+            .find(|p| p.0.eq_ignore_ascii_case(name))
+            .map(|p| p.1.as_deref())
+    }
+
+    /// Return `true` if the first `rel` parameter includes the relation
+    /// type, compared case-insensitively.
+    pub fn has_rel(&self, rel: &str) -> bool {
+        match self.param("rel") {
+            Some(Some(rels)) => rels.split_whitespace().any(|r| r.eq_ignore_ascii_case(rel)),
+            _ => false,
+        }
+    }
+}
+
+impl<'a> HeaderRef<'a> for LinkRef<'a> {
+    fn header_name() -> &'static str {
+        Link::header_name()
+    }
+
+    fn parse_lines<I>(lines: I) -> ::Result<LinkRef<'a>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        let mut values = Vec::new();
+        let mut found = false;
+        for line in lines {
+            found = true;
+            parse_link_values(parsing::from_raw_str_ref(line)?, &mut values)?;
+        }
+        if !found {
+            return Err(::Error::header(::HeaderErrorKind::Empty));
+        }
+        Ok(LinkRef(values))
+    }
+}
+
+// Parse the comma separated link-values of a line, where the target URI
+// may itself contain commas or semicolons.
+fn parse_link_values<'a>(s: &'a str, values: &mut Vec<LinkValueRef<'a>>) -> ::Result<()> {
+    let syntax = || ::Error::header(::HeaderErrorKind::Syntax);
+    let mut rest = s;
+    loop {
+        rest = rest.trim_start_matches([',', ' ', '\t']);
+        if rest.is_empty() {
+            return Ok(());
+        }
+        if !rest.starts_with('<') {
+            return Err(syntax());
+        }
+        let end = rest.find('>').ok_or_else(syntax)?;
+        let mut value = LinkValueRef {
+            link: &rest[1..end],
+            params: Vec::new(),
+        };
+        rest = rest[end + 1..].trim_start();
+        while rest.starts_with(';') {
+            rest = &rest[1..];
+            let end = param_end(rest);
+            value.params.push(parsing::parse_parameter(&rest[..end])?);
+            rest = rest[end..].trim_start();
+        }
+        if !rest.is_empty() && !rest.starts_with(',') {
+            return Err(syntax());
+        }
+        values.push(value);
+    }
+}
+
+// The end of a link-param: the first `;` or `,` not within a quoted-string.
+fn param_end(s: &str) -> usize {
+    let mut quoted = false;
+    let mut escaped = false;
+    for (i, c) in s.char_indices() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        } else if !quoted && (c == ';' || c == ',') {
+            return i;
+        }
+    }
+    s.len()
+}
+
 impl fmt::Display for MediaDesc {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -825,7 +1040,7 @@ impl<'a> Iterator for SplitAsciiUnquoted<'a> {
 
             let mut in_quotes = false;
 
-            for c in self.src[prev_pos..].as_bytes().iter() {
+            for c in self.src.as_bytes()[prev_pos..].iter() {
                 in_quotes ^= *c == b'"';
 
                 // Ignore `c` if we're `in_quotes`.
@@ -876,7 +1091,7 @@ fn verify_and_trim(s: &str, b: (u8, u8)) -> ::Result<&str> {
     if length > 1 && b.0 == byte_array[0] && b.1 == byte_array[length - 1] {
         Ok(s.trim_matches(|c: char| c == b.0 as char || c == b.1 as char || c.is_whitespace()))
     } else {
-        Err(::Error::Header)
+        Err(::Error::header(::HeaderErrorKind::Syntax))
     }
 }
 
@@ -890,9 +1105,9 @@ mod tests {
     use std::fmt::Write;
 
     use super::{fmt_delimited, verify_and_trim};
-    use super::{Link, LinkValue, MediaDesc, RelationType, SplitAsciiUnquoted};
+    use super::{Link, LinkRef, LinkValue, MediaDesc, RelationType, SplitAsciiUnquoted};
 
-    use header::{Header, Raw};
+    use header::{Header, HeaderRef, Raw};
 
     use mime;
 
@@ -986,6 +1201,34 @@ mod tests {
         assert_eq!(link_header, expected_link_header);
     }
 
+    #[test]
+    fn test_link_extension_params() {
+        let link_header = b"</font.woff2>; rel=preload; as=font; crossorigin; \
+            nopush; x-note=\"a; b\"";
+
+        let expected_link = Link::new(vec![LinkValue::new("/font.woff2")
+            .push_rel(RelationType::ExtRelType("preload".to_owned()))
+            .push_param("as", "font")
+            .push_flag("crossorigin")
+            .push_flag("nopush")
+            .push_param("x-note", "a; b")]);
+
+        let r: Raw = vec![link_header.to_vec()].into();
+        let link: Link = Header::parse_header(&r).unwrap();
+        assert_eq!(link, expected_link);
+
+        let value = &link.values()[0];
+        assert_eq!(value.param("AS"), Some(Some("font")));
+        assert_eq!(value.param("crossorigin"), Some(None));
+        assert_eq!(value.param("media"), None);
+
+        assert_eq!(
+            link.to_string(),
+            "</font.woff2>; rel=\"preload\"; as=font; crossorigin; nopush; \
+             x-note=\"a; b\""
+        );
+    }
+
     #[test]
     fn test_link_parsing_errors() {
         let link_a = b"http://example.com/TheBook/chapter2; \
@@ -1014,7 +1257,7 @@ mod tests {
         assert!(err.is_err());
 
         let link_e = b"<http://example.com/TheBook/chapter2>; \
-            rel=\"previous\"; rev=next; attr=unknown";
+            rel=\"previous\"; rev=next; attr=";
         let r: Raw = vec![link_e.to_vec()].into();
         err = Header::parse_header(&r);
         assert!(err.is_err());
@@ -1064,6 +1307,35 @@ mod tests {
         let err = verify_and_trim(" >  some string   <", (b'>', b'<'));
         assert!(err.is_err());
     }
+
+    #[test]
+    fn test_link_parse_ref() {
+        let raw: Raw = vec![
+            b"<http://example.com/a,b;c>; rel=\"next start\"; title=\"a \\\"b\\\", c\"".to_vec(),
+            b"</d>, </e>;crossorigin".to_vec(),
+        ]
+        .into();
+        let link = LinkRef::parse_header_ref(&raw).unwrap();
+        let values = link.values();
+        assert_eq!(values.len(), 3);
+        assert_eq!(values[0].link(), "http://example.com/a,b;c");
+        assert!(values[0].has_rel("START"));
+        assert!(!values[0].has_rel("prev"));
+        assert_eq!(values[0].param("title"), Some(Some("a \"b\", c")));
+        assert_eq!(values[1].link(), "/d");
+        assert!(values[1].params().is_empty());
+        assert_eq!(values[2].param("crossorigin"), Some(None));
+
+        for bad in &[
+            "http://no-brackets",
+            "<unterminated",
+            "</a> junk",
+            "</a>; =v",
+        ] {
+            let raw = Raw::from(*bad);
+            assert!(LinkRef::parse_header_ref(&raw).is_err(), "{}", bad);
+        }
+    }
 }
 
 bench_header!(bench_link, Link, {
@@ -1071,3 +1343,6 @@ bench_header!(bench_link, Link, {
 });
 
 standard_header!(Link, LINK);
+
+__hyper__try_from!(Link);
+__hyper__serde!(Link);
@@ -34,13 +34,41 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&Location::new("http://www.example.com/index.html"));
     /// ```
-    // TODO: Use URL
+    ///
+    /// A relative reference is resolved against the URI of the request, when
+    /// following a redirect:
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use hyperx::header::Location;
+    ///
+    /// # fn main() {
+    /// let request: http::Uri = "https://www.example.com/a/b?q".parse().unwrap();
+    /// let location = Location::new("../c#top");
+    /// assert_eq!(location.resolve(&request).unwrap(), "https://www.example.com/c");
+    /// assert!(location.uri().is_err());
+    /// # }
+    /// ```
     (Location, "Location") => Cow[str]
 
     test_location {
         // Testcase from RFC
         test_header!(test1, [b"/People.html#tim"]);
         test_header!(test2, [b"http://www.example.net/index.html"]);
+
+        #[test]
+        fn test_uri() {
+            let location = Location::new("/People.html#tim");
+            assert_eq!(location.uri().unwrap(), "/People.html");
+            let base: ::http::Uri = "http://www.example.net/a/b".parse().unwrap();
+            assert_eq!(
+                location.resolve(&base).unwrap(),
+                "http://www.example.net/People.html"
+            );
+            let uri: ::http::Uri = "http://www.example.net/index.html".parse().unwrap();
+            assert_eq!(Location::from(uri.clone()).uri().unwrap(), uri);
+        }
     }
 
 }
@@ -49,4 +77,6 @@ bench_header!(bench, Location, {
     vec![b"http://foo.com/hello:3000".to_vec()]
 });
 
+uri_reference!(Location);
+
 standard_header!(Location, LOCATION);
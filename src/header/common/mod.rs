@@ -7,31 +7,45 @@
 //! is used, such as `ContentType(pub Mime)`.
 
 pub use self::accept::Accept;
+pub use self::accept_ch::AcceptCh;
 pub use self::accept_charset::AcceptCharset;
 pub use self::accept_encoding::AcceptEncoding;
 pub use self::accept_language::AcceptLanguage;
 pub use self::accept_ranges::{AcceptRanges, RangeUnit};
+pub use self::accept_signature::{AcceptSignature, RequestedSignature};
 pub use self::access_control_allow_credentials::AccessControlAllowCredentials;
 pub use self::access_control_allow_headers::AccessControlAllowHeaders;
 pub use self::access_control_allow_methods::AccessControlAllowMethods;
 pub use self::access_control_allow_origin::AccessControlAllowOrigin;
+pub use self::access_control_allow_private_network::AccessControlAllowPrivateNetwork;
 pub use self::access_control_expose_headers::AccessControlExposeHeaders;
 pub use self::access_control_max_age::AccessControlMaxAge;
 pub use self::access_control_request_headers::AccessControlRequestHeaders;
 pub use self::access_control_request_method::AccessControlRequestMethod;
+pub use self::access_control_request_private_network::AccessControlRequestPrivateNetwork;
+pub use self::age::Age;
 pub use self::allow::Allow;
-pub use self::authorization::{Authorization, Basic, Bearer, Scheme};
+pub use self::alt_svc::{AltService, AltSvc};
+pub use self::authorization::{Authorization, Basic, Bearer, Digest, Scheme};
 pub use self::cache_control::{CacheControl, CacheDirective};
 pub use self::connection::{Connection, ConnectionOption};
-pub use self::content_disposition::{ContentDisposition, DispositionParam, DispositionType};
+pub use self::content_disposition::{
+    ContentDisposition, ContentDispositionRef, DispositionParam, DispositionType,
+};
 pub use self::content_encoding::ContentEncoding;
 pub use self::content_language::ContentLanguage;
 pub use self::content_length::ContentLength;
 pub use self::content_location::ContentLocation;
 pub use self::content_range::{ContentRange, ContentRangeSpec};
+pub use self::content_security_policy::ContentSecurityPolicy;
 pub use self::content_type::ContentType;
-pub use self::cookie::{Cookie, CookieIter};
+pub use self::cookie::{Cookie, CookieIter, CookieRef};
+pub use self::critical_ch::CriticalCh;
+pub use self::cross_origin_embedder_policy::CrossOriginEmbedderPolicy;
+pub use self::cross_origin_opener_policy::CrossOriginOpenerPolicy;
+pub use self::cross_origin_resource_policy::CrossOriginResourcePolicy;
 pub use self::date::Date;
+pub use self::digest_fields::{ContentDigest, ReprDigest, WantContentDigest, WantReprDigest};
 pub use self::etag::ETag;
 pub use self::expect::Expect;
 pub use self::expires::Expires;
@@ -42,28 +56,51 @@ pub use self::if_modified_since::IfModifiedSince;
 pub use self::if_none_match::IfNoneMatch;
 pub use self::if_range::IfRange;
 pub use self::if_unmodified_since::IfUnmodifiedSince;
+pub use self::instance_digest::{DigestValue, InstanceDigest};
 pub use self::last_event_id::LastEventId;
 pub use self::last_modified::LastModified;
-pub use self::link::{Link, LinkValue, MediaDesc, RelationType};
+pub use self::link::{Link, LinkRef, LinkValue, LinkValueRef, MediaDesc, RelationType};
 pub use self::location::Location;
 pub use self::origin::Origin;
+pub use self::permissions_policy::PermissionsPolicy;
 pub use self::pragma::Pragma;
 pub use self::prefer::{Prefer, Preference};
 pub use self::preference_applied::PreferenceApplied;
 pub use self::proxy_authorization::ProxyAuthorization;
 pub use self::range::{ByteRangeSpec, Range};
-pub use self::referer::Referer;
+pub use self::ratelimit::{RateLimit, RateLimitItem};
+pub use self::ratelimit_policy::{QuotaPolicy, RateLimitPolicy};
+pub use self::referer::{Referer, RefererRef};
 pub use self::referrer_policy::ReferrerPolicy;
 pub use self::retry_after::RetryAfter;
+pub use self::sec_fetch_dest::SecFetchDest;
+pub use self::sec_fetch_mode::SecFetchMode;
+pub use self::sec_fetch_site::SecFetchSite;
+pub use self::sec_fetch_user::SecFetchUser;
+pub use self::sec_websocket_accept::SecWebSocketAccept;
+pub use self::sec_websocket_key::SecWebSocketKey;
+pub use self::sec_websocket_protocol::SecWebSocketProtocol;
+pub use self::sec_websocket_version::SecWebSocketVersion;
 pub use self::server::Server;
-pub use self::set_cookie::SetCookie;
+pub use self::server_timing::{ServerTiming, TimingMetric};
+pub use self::set_cookie::{ResponseCookie, SameSite, SetCookie};
+pub use self::signature::Signature;
+pub use self::signature_input::SignatureInput;
 pub use self::strict_transport_security::StrictTransportSecurity;
 pub use self::te::Te;
+pub use self::timing_allow_origin::TimingAllowOrigin;
+pub use self::trailer::Trailer;
 pub use self::transfer_encoding::TransferEncoding;
 pub use self::upgrade::{Protocol, ProtocolName, Upgrade};
-pub use self::user_agent::UserAgent;
+pub use self::user_agent::{UserAgent, UserAgentRef};
 pub use self::vary::Vary;
+pub use self::want_digest::WantDigest;
 pub use self::warning::Warning;
+pub use self::x_content_type_options::XContentTypeOptions;
+pub use self::x_forwarded_for::XForwardedFor;
+pub use self::x_forwarded_host::XForwardedHost;
+pub use self::x_forwarded_proto::XForwardedProto;
+pub use self::x_frame_options::XFrameOptions;
 
 #[doc(hidden)]
 #[macro_export]
@@ -119,6 +156,178 @@ macro_rules! __hyper__deref {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__http_name {
+    ($n:expr) => {
+        #[inline]
+        fn http_name() -> $crate::header::__private::HeaderName {
+            // Converted and validated once, at compile time
+            const LOWER: [u8; $n.len()] = $crate::header::__private::lowercase($n);
+            const NAME: $crate::header::__private::HeaderName =
+                $crate::header::__private::HeaderName::from_static(
+                    $crate::header::__private::as_str(&LOWER),
+                );
+            NAME
+        }
+    };
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__try_from {
+    ($id:ty) => {
+        impl<'v> ::std::convert::TryFrom<&'v $crate::header::__private::HeaderValue> for $id {
+            type Error = $crate::Error;
+
+            #[inline]
+            fn try_from(value: &'v $crate::header::__private::HeaderValue) -> $crate::Result<Self> {
+                <$id as $crate::header::Header>::parse_header(&$crate::header::Raw::from(value))
+            }
+        }
+
+        impl<'h> ::std::convert::TryFrom<&'h $id> for $crate::header::__private::HeaderValue {
+            type Error = $crate::header::__private::InvalidHeaderValue;
+
+            #[inline]
+            fn try_from(header: &'h $id) -> ::std::result::Result<Self, Self::Error> {
+                $crate::header::__private::to_value(header)
+            }
+        }
+
+        impl ::std::convert::TryFrom<$id> for $crate::header::__private::HeaderValue {
+            type Error = $crate::header::__private::InvalidHeaderValue;
+
+            #[inline]
+            fn try_from(header: $id) -> ::std::result::Result<Self, Self::Error> {
+                $crate::header::__private::to_value(&header)
+            }
+        }
+
+        $crate::__hyper__try_from_http02!($id);
+    };
+}
+
+#[cfg(feature = "http02")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__try_from_http02 {
+    ($id:ty) => {
+        impl<'v> ::std::convert::TryFrom<&'v $crate::header::__private::HeaderValue02> for $id {
+            type Error = $crate::Error;
+
+            #[inline]
+            fn try_from(
+                value: &'v $crate::header::__private::HeaderValue02,
+            ) -> $crate::Result<Self> {
+                <$id as $crate::header::Header>::parse_header(&value)
+            }
+        }
+
+        impl<'h> ::std::convert::TryFrom<&'h $id> for $crate::header::__private::HeaderValue02 {
+            type Error = $crate::header::__private::InvalidHeaderValue02;
+
+            #[inline]
+            fn try_from(header: &'h $id) -> ::std::result::Result<Self, Self::Error> {
+                $crate::header::__private::to_value02(header)
+            }
+        }
+
+        impl ::std::convert::TryFrom<$id> for $crate::header::__private::HeaderValue02 {
+            type Error = $crate::header::__private::InvalidHeaderValue02;
+
+            #[inline]
+            fn try_from(header: $id) -> ::std::result::Result<Self, Self::Error> {
+                $crate::header::__private::to_value02(&header)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "http02"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__try_from_http02 {
+    ($id:ty) => {};
+}
+
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__from_str {
+    ($id:ty) => {
+        impl ::std::str::FromStr for $id {
+            type Err = $crate::Error;
+
+            #[inline]
+            fn from_str(s: &str) -> $crate::Result<Self> {
+                <$id as $crate::header::Header>::parse_header(&$crate::header::Raw::from(s))
+            }
+        }
+    };
+}
+
+#[cfg(feature = "serde")]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__serde {
+    ($id:ty) => {
+        impl $crate::header::__private::Serialize for $id {
+            #[inline]
+            fn serialize<S>(&self, serializer: S) -> ::std::result::Result<S::Ok, S::Error>
+            where
+                S: $crate::header::__private::Serializer,
+            {
+                $crate::header::__private::serialize(self, serializer)
+            }
+        }
+
+        impl<'de> $crate::header::__private::Deserialize<'de> for $id {
+            #[inline]
+            fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+            where
+                D: $crate::header::__private::Deserializer<'de>,
+            {
+                $crate::header::__private::deserialize(deserializer)
+            }
+        }
+    };
+}
+
+#[cfg(not(feature = "serde"))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! __hyper__serde {
+    ($id:ty) => {};
+}
+
+// Methods of the headers with a URI reference value.
+macro_rules! uri_reference {
+    ($id:ident) => {
+        impl $id {
+            /// Parse the value as an `http::Uri`, if an absolute URI or an
+            /// absolute path. Any fragment is dropped.
+            ///
+            /// Fails for other relative references, such as `../page`, which
+            /// must be resolved via `resolve`.
+            pub fn uri(&self) -> ::Result<::http::Uri> {
+                ::header::parse_uri(&self.0)
+            }
+
+            /// Resolve the value against an absolute base URI, such as that of
+            /// the request, as with `resolve_uri`.
+            pub fn resolve(&self, base: &::http::Uri) -> ::Result<::http::Uri> {
+                ::header::resolve_uri(base, &self.0)
+            }
+        }
+
+        impl From<::http::Uri> for $id {
+            fn from(uri: ::http::Uri) -> $id {
+                $id(uri.to_string().into())
+            }
+        }
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! __hyper__tm {
@@ -130,6 +339,7 @@ macro_rules! __hyper__tm {
             use $crate::header::*;
             use $crate::mime::*;
             use $crate::method::Method;
+            use $crate::test_header;
             use super::$id as HeaderField;
             $($tf)*
         }
@@ -137,7 +347,17 @@ macro_rules! __hyper__tm {
     }
 }
 
-#[doc(hidden)]
+/// Define a test that a header parses from raw values, for use in the test
+/// module of a `header!` definition, where the header type is named
+/// `HeaderField`.
+///
+/// With two arguments, a test name and a `Vec` of raw values, the test
+/// checks that the values parse, and that the header formats to the first
+/// value, ignoring case and spaces.
+///
+/// With a third argument, an `Option` of the expected header, or `None` if
+/// the values are invalid, the test checks the parsed result, and that a
+/// valid header formats to the values joined with `", "`.
 #[macro_export]
 macro_rules! test_header {
     ($id:ident, $raw:expr) => {
@@ -188,6 +408,64 @@ macro_rules! test_header {
 }
 
 /// Create a custom header type.
+///
+/// This is how the included headers are defined, and downstream crates may
+/// use it for their own, such as proprietary `X-` headers. The generated
+/// type implements `Header`, `Display`, `FromStr`, and conversions from and
+/// to `http::header::HeaderValue`, as well as `Serialize` and `Deserialize`
+/// with the _serde_ feature. Leading attributes, such as doc comments or
+/// additional derives like `Eq` and `Hash`, are applied to the generated
+/// type. The forms are:
+///
+/// * `(Id, "Name") => [T]`: a single value, as `Id(pub T)`, where `T`
+///   implements `FromStr` and `Display`.
+/// * `(Id, "Name") => (T)*`: a comma separated list of zero or more items,
+///   as `Id(pub HeaderVec<T>)`.
+/// * `(Id, "Name") => (T)+`: as above, for a header whose grammar requires
+///   one or more items.
+/// * `(Id, "Name") => {Any / (T)+}`: either `*`, as `Id::Any`, or one or
+///   more items, as `Id::Items`.
+/// * `(Id, "Name") => Cow[T]`: a single value, borrowed if `'static`, as
+///   created by `Id::new`.
+///
+/// Any form may be followed by the name and body of a test module, in which
+/// the header type is in scope as `HeaderField`, along with `header::*`.
+/// The `test_header!` macro may be used there to check that raw values
+/// parse, and format back to the same string.
+///
+/// The macro may be invoked by path, or after `#[macro_use]`, as its helper
+/// macros are resolved via `$crate`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::Header;
+///
+/// hyperx::header! {
+///     /// `X-Org-Tenant`, the tenant of a request.
+///     (XOrgTenant, "X-Org-Tenant") => [String]
+///
+///     test_x_org_tenant {
+///         test_header!(test1, vec![b"acme"], Some(HeaderField("acme".to_owned())));
+///     }
+/// }
+///
+/// hyperx::header! {
+///     /// `X-Org-Regions`, the regions which may serve a request.
+///     (XOrgRegions, "X-Org-Regions") => (String)+
+/// }
+///
+/// # fn main() {
+/// let value = http::HeaderValue::from_static("acme");
+/// assert_eq!(*XOrgTenant::decode_value(&value).unwrap(), "acme");
+///
+/// let regions: XOrgRegions = "eu-west, us-east".parse().unwrap();
+/// assert_eq!(regions.len(), 2);
+/// assert_eq!(regions.encode_to_value().unwrap(), "eu-west, us-east");
+/// # }
+/// ```
 #[macro_export]
 macro_rules! header {
     // $a:meta: Attributes associated with the header item (usually docs)
@@ -199,18 +477,29 @@ macro_rules! header {
     ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)*) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
-        pub struct $id(pub Vec<$item>);
-        __hyper__deref!($id => Vec<$item>);
+        pub struct $id(pub $crate::header::HeaderVec<$item>);
+        $crate::__hyper__deref!($id => $crate::header::HeaderVec<$item>);
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                $crate::header::parsing::from_comma_delimited(raw).map($id)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    let mut items = $crate::header::HeaderVec::new();
+                    $crate::header::parsing::extend_comma_delimited(raw, &mut items)
+                        .map(|()| $id(items))
+                        .map_err(|e| e.with_header_name($n))
+                }
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -228,19 +517,30 @@ macro_rules! header {
     ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)+) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
-        pub struct $id(pub Vec<$item>);
-        __hyper__deref!($id => Vec<$item>);
+        pub struct $id(pub $crate::header::HeaderVec<$item>);
+        $crate::__hyper__deref!($id => $crate::header::HeaderVec<$item>);
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             #[inline]
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                $crate::header::parsing::from_comma_delimited(raw).map($id)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    let mut items = $crate::header::HeaderVec::new();
+                    $crate::header::parsing::extend_comma_delimited(raw, &mut items)
+                        .map(|()| $id(items))
+                        .map_err(|e| e.with_header_name($n))
+                }
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -259,18 +559,27 @@ macro_rules! header {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub $value);
-        __hyper__deref!($id => $value);
+        $crate::__hyper__deref!($id => $value);
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             #[inline]
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                $crate::header::parsing::from_one_raw_str(raw).map($id)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    $crate::header::parsing::from_one_raw_str(raw).map($id)
+                        .map_err(|e| e.with_header_name($n))
+                }
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -289,18 +598,27 @@ macro_rules! header {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
         pub struct $id(pub $value);
-        __hyper__deref!($id => $value);
+        $crate::__hyper__deref!($id => $value);
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             #[inline]
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                $crate::header::parsing::from_one_raw_str(raw).map($id)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    $crate::header::parsing::from_one_raw_str(raw).map($id)
+                        .map_err(|e| e.with_header_name($n))
+                }
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -332,17 +650,25 @@ macro_rules! header {
                 &(self.0)
             }
         }
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             #[inline]
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                $crate::header::parsing::from_one_raw_str::<_, <$value as ::std::borrow::ToOwned>::Owned>(raw).map($id::new)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    $crate::header::parsing::from_one_raw_str::<_, <$value as ::std::borrow::ToOwned>::Owned>(raw).map($id::new)
+                }
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -360,29 +686,42 @@ macro_rules! header {
     ($(#[$a:meta])*($id:ident, $n:expr) => {Any / ($item:ty)+}) => {
         $(#[$a])*
         #[derive(Clone, Debug, PartialEq)]
+        // The items are inline by design, to avoid allocating
+        #[allow(clippy::large_enum_variant)]
         pub enum $id {
             /// Any value is a match
             Any,
             /// Only the listed items are a match
-            Items(Vec<$item>),
+            Items($crate::header::HeaderVec<$item>),
         }
+        $crate::__hyper__try_from!($id);
+        $crate::__hyper__from_str!($id);
+        $crate::__hyper__serde!($id);
         impl $crate::header::Header for $id {
             #[inline]
             fn header_name() -> &'static str {
                 static NAME: &'static str = $n;
                 NAME
             }
+            $crate::__hyper__http_name!($n);
             #[inline]
             fn parse_header<'a, T>(raw: &'a T) -> $crate::Result<Self>
             where T: $crate::header::RawLike<'a>
             {
-                // FIXME: Return None if no item is in $id::Only
-                if let Some(l) = raw.one() {
-                    if l == b"*" {
-                        return Ok($id::Any)
+                // Compiled once, rather than once per raw type
+                fn parse(raw: &$crate::header::RawSlice) -> $crate::Result<$id> {
+                    // FIXME: Return None if no item is in $id::Only
+                    if let Some(l) = $crate::header::RawLike::one(raw) {
+                        if l == b"*" {
+                            return Ok($id::Any)
+                        }
                     }
+                    let mut items = $crate::header::HeaderVec::new();
+                    $crate::header::parsing::extend_comma_delimited(raw, &mut items)
+                        .map(|()| $id::Items(items))
+                        .map_err(|e| e.with_header_name($n))
                 }
-                $crate::header::parsing::from_comma_delimited(raw).map($id::Items)
+                $crate::header::RawSlice::with(raw, parse)
             }
             #[inline]
             fn fmt_header(&self, f: &mut $crate::header::Formatter) -> ::std::fmt::Result {
@@ -403,52 +742,52 @@ macro_rules! header {
 
     // optional test module
     ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)* $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => ($item)*
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
     ($(#[$a:meta])*($id:ident, $n:expr) => ($item:ty)+ $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => ($item)+
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
     ($(#[$a:meta])*($id:ident, $n:expr) => [$item:ty] $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => [$item]
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
     ($(#[$a:meta])*($id:ident, $n:expr) => danger [$item:ty] $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => danger [$item]
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
     ($(#[$a:meta])*($id:ident, $n:expr) => Cow[$item:ty] $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => Cow[$item]
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
     ($(#[$a:meta])*($id:ident, $n:expr) => {Any / ($item:ty)+} $tm:ident{$($tf:item)*}) => {
-        header! {
+        $crate::header! {
             $(#[$a])*
             ($id, $n) => {Any / ($item)+}
         }
 
-        __hyper__tm! { $id, $tm { $($tf)* }}
+        $crate::__hyper__tm! { $id, $tm { $($tf)* }}
     };
 }
 
@@ -466,19 +805,25 @@ macro_rules! standard_header {
 }
 
 mod accept;
+mod accept_ch;
 mod accept_charset;
 mod accept_encoding;
 mod accept_language;
 mod accept_ranges;
+mod accept_signature;
 mod access_control_allow_credentials;
 mod access_control_allow_headers;
 mod access_control_allow_methods;
 mod access_control_allow_origin;
+mod access_control_allow_private_network;
 mod access_control_expose_headers;
 mod access_control_max_age;
 mod access_control_request_headers;
 mod access_control_request_method;
+mod access_control_request_private_network;
+mod age;
 mod allow;
+mod alt_svc;
 mod authorization;
 mod cache_control;
 mod connection;
@@ -488,9 +833,15 @@ mod content_language;
 mod content_length;
 mod content_location;
 mod content_range;
+mod content_security_policy;
 mod content_type;
 mod cookie;
+mod critical_ch;
+mod cross_origin_embedder_policy;
+mod cross_origin_opener_policy;
+mod cross_origin_resource_policy;
 mod date;
+mod digest_fields;
 mod etag;
 mod expect;
 mod expires;
@@ -501,25 +852,48 @@ mod if_modified_since;
 mod if_none_match;
 mod if_range;
 mod if_unmodified_since;
+mod instance_digest;
 mod last_event_id;
 mod last_modified;
 mod link;
 mod location;
 mod origin;
+mod permissions_policy;
 mod pragma;
 mod prefer;
 mod preference_applied;
 mod proxy_authorization;
 mod range;
+mod ratelimit;
+mod ratelimit_policy;
 mod referer;
 mod referrer_policy;
 mod retry_after;
+mod sec_fetch_dest;
+mod sec_fetch_mode;
+mod sec_fetch_site;
+mod sec_fetch_user;
+mod sec_websocket_accept;
+mod sec_websocket_key;
+mod sec_websocket_protocol;
+mod sec_websocket_version;
 mod server;
+mod server_timing;
 mod set_cookie;
+mod signature;
+mod signature_input;
 mod strict_transport_security;
 mod te;
+mod timing_allow_origin;
+mod trailer;
 mod transfer_encoding;
 mod upgrade;
 mod user_agent;
 mod vary;
+mod want_digest;
 mod warning;
+mod x_content_type_options;
+mod x_forwarded_for;
+mod x_forwarded_host;
+mod x_forwarded_proto;
+mod x_frame_options;
@@ -69,10 +69,7 @@ impl Origin {
 
     /// Checks if `Origin` is `Null`.
     pub fn is_null(&self) -> bool {
-        match self {
-            &Origin(OriginOrNull::Null) => true,
-            _ => false,
-        }
+        matches!(*self, Origin(OriginOrNull::Null))
     }
 
     /// The scheme, such as http or https.
@@ -110,6 +107,8 @@ impl Header for Origin {
         NAME
     }
 
+    __hyper__http_name!("Origin");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Origin>
     where
         T: RawLike<'a>,
@@ -131,7 +130,7 @@ impl FromStr for Origin {
     fn from_str(s: &str) -> ::Result<Origin> {
         let idx = match s.find("://") {
             Some(idx) => idx,
-            None => return Err(::Error::Header),
+            None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         };
         // idx + 3 because that's how long "://" is
         let (scheme, etc) = (&s[..idx], &s[idx + 3..]);
@@ -194,3 +193,6 @@ mod tests {
 bench_header!(bench, Origin, { vec![b"https://foo.com".to_vec()] });
 
 standard_header!(Origin, ORIGIN);
+
+__hyper__try_from!(Origin);
+__hyper__serde!(Origin);
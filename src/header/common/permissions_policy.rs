@@ -0,0 +1,191 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+use std::str;
+
+/// `Permissions-Policy` header, defined in
+/// [Permissions Policy](https://www.w3.org/TR/permissions-policy/#permissions-policy-http-header-field)
+///
+/// The `Permissions-Policy` header controls which origins may use browser
+/// features, such as `camera` or `geolocation`, in a document and its
+/// embedded frames. Each feature maps to an allowlist of `*`, `self`, `src`
+/// or quoted origins; an empty allowlist disables the feature.
+///
+/// The header is a structured field dictionary; allowlist members are kept
+/// in their serialized form (e.g. `"https://example.com"`, with quotes) and
+/// parameters are not supported.
+///
+/// # ABNF
+///
+/// ```text
+/// Permissions-Policy = sf-dictionary
+/// ```
+///
+/// # Example values
+///
+/// * `camera=(), geolocation=(self "https://maps.example"), fullscreen=*`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{PermissionsPolicy, TypedHeaders};
+///
+/// let policy = PermissionsPolicy::new()
+///     .deny("camera")
+///     .allow("geolocation", vec!["self", "\"https://maps.example\""]);
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&policy);
+/// assert_eq!(
+///     headers["permissions-policy"],
+///     "camera=(), geolocation=(self \"https://maps.example\")"
+/// );
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct PermissionsPolicy(Vec<(String, Vec<String>)>);
+
+impl PermissionsPolicy {
+    /// Create a new, empty policy.
+    pub fn new() -> PermissionsPolicy {
+        PermissionsPolicy::default()
+    }
+
+    /// Allow a feature for the given allowlist members, replacing any
+    /// existing allowlist for the feature.
+    pub fn allow<N, I, V>(mut self, feature: N, allowlist: I) -> PermissionsPolicy
+    where
+        N: Into<String>,
+        I: IntoIterator<Item = V>,
+        V: Into<String>,
+    {
+        let feature = feature.into();
+        let allowlist = allowlist.into_iter().map(Into::into).collect();
+        match self.0.iter_mut().find(|f| f.0 == feature) {
+            Some(f) => f.1 = allowlist,
+            None => self.0.push((feature, allowlist)),
+        }
+        self
+    }
+
+    /// Disable a feature, with an empty allowlist.
+    pub fn deny<N: Into<String>>(self, feature: N) -> PermissionsPolicy {
+        self.allow(feature, Vec::<String>::new())
+    }
+
+    /// Returns the allowlist of a feature, if present.
+    pub fn get(&self, feature: &str) -> Option<&[String]> {
+        self.0.iter().find(|f| f.0 == feature).map(|f| &f.1[..])
+    }
+
+    /// Returns an iterator over the features and their allowlists.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[String])> {
+        self.0.iter().map(|f| (&f.0[..], &f.1[..]))
+    }
+
+    fn parse(s: &str) -> ::Result<PermissionsPolicy> {
+        let mut policy = PermissionsPolicy::new();
+        for member in s.split(',') {
+            let member = member.trim();
+            if member.is_empty() {
+                continue;
+            }
+            let (feature, value) = member
+                .split_once('=')
+                .ok_or_else(|| ::Error::header(::HeaderErrorKind::Syntax))?;
+            let value = value.trim();
+            let allowlist: Vec<&str> = if value.starts_with('(') && value.ends_with(')') {
+                value[1..value.len() - 1].split_ascii_whitespace().collect()
+            } else if !value.is_empty() && !value.contains(char::is_whitespace) {
+                vec![value]
+            } else {
+                return Err(::Error::header(::HeaderErrorKind::Syntax));
+            };
+            // Later dictionary members override earlier ones
+            policy = policy.allow(feature.trim(), allowlist);
+        }
+        if policy.0.is_empty() {
+            return Err(::Error::header(::HeaderErrorKind::Empty));
+        }
+        Ok(policy)
+    }
+}
+
+impl Header for PermissionsPolicy {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Permissions-Policy";
+        NAME
+    }
+
+    __hyper__http_name!("Permissions-Policy");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<PermissionsPolicy>
+    where
+        T: RawLike<'a>,
+    {
+        // Multiple lines combine into one dictionary
+        let lines: Vec<String> = parsing::from_comma_delimited(raw)?;
+        PermissionsPolicy::parse(&lines.join(","))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for PermissionsPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (feature, allowlist)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            if allowlist.len() == 1 && allowlist[0] == "*" {
+                write!(f, "{}=*", feature)?;
+            } else {
+                write!(f, "{}=(", feature)?;
+                for (j, origin) in allowlist.iter().enumerate() {
+                    if j > 0 {
+                        f.write_str(" ")?;
+                    }
+                    f.write_str(origin)?;
+                }
+                f.write_str(")")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test_permissions_policy {
+    use super::PermissionsPolicy as HeaderField;
+    use header::*;
+    use std::str;
+
+    test_header!(
+        test_basic,
+        vec![
+            &b"camera=(), geolocation=(self \"https://a.example\")"[..],
+            &b"fullscreen=*"[..]
+        ],
+        Some(
+            HeaderField::new()
+                .deny("camera")
+                .allow("geolocation", vec!["self", "\"https://a.example\""])
+                .allow("fullscreen", vec!["*"])
+        )
+    );
+    test_header!(test_no_value, vec![b"camera"], None);
+    test_header!(test_bad_value, vec![b"camera=self src"], None);
+
+    #[test]
+    fn test_bare_token_and_override() {
+        let raw: Raw = "camera=self, camera=(src), usb=()".into();
+        let policy = HeaderField::parse_header(&raw).unwrap();
+        assert_eq!(policy.get("camera"), Some(&["src".to_owned()][..]));
+        assert_eq!(policy.to_string(), "camera=(src), usb=()");
+    }
+}
+
+__hyper__try_from!(PermissionsPolicy);
+__hyper__from_str!(PermissionsPolicy);
+__hyper__serde!(PermissionsPolicy);
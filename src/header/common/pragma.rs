@@ -47,15 +47,17 @@ impl Header for Pragma {
         NAME
     }
 
+    __hyper__http_name!("Pragma");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Pragma>
     where
         T: RawLike<'a>,
     {
         parsing::from_one_raw_str(raw).map(|s: String| {
-            let slice = &s.to_ascii_lowercase()[..];
-            match slice {
-                "no-cache" => Pragma::NoCache,
-                _ => Pragma::Ext(s),
+            if s.eq_ignore_ascii_case("no-cache") {
+                Pragma::NoCache
+            } else {
+                Pragma::Ext(s)
             }
         })
     }
@@ -83,6 +85,10 @@ fn test_parse_header() {
     let b = Pragma::NoCache;
     assert_eq!(a, b);
 
+    let r: Raw = "No-Cache".into();
+    let a: Pragma = Header::parse_header(&r).unwrap();
+    assert_eq!(a, Pragma::NoCache);
+
     let r: Raw = "FoObar".into();
     let c: Pragma = Header::parse_header(&r).unwrap();
     let d = Pragma::Ext("FoObar".to_owned());
@@ -94,3 +100,7 @@ fn test_parse_header() {
 }
 
 standard_header!(Pragma, PRAGMA);
+
+__hyper__try_from!(Pragma);
+__hyper__from_str!(Pragma);
+__hyper__serde!(Pragma);
@@ -63,6 +63,8 @@ impl Header for Prefer {
         NAME
     }
 
+    __hyper__http_name!("Prefer");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Prefer>
     where
         T: RawLike<'a>,
@@ -71,7 +73,7 @@ impl Header for Prefer {
         if !preferences.is_empty() {
             Ok(Prefer(preferences))
         } else {
-            Err(::Error::Header)
+            Err(::Error::header(::HeaderErrorKind::Empty))
         }
     }
 
@@ -80,6 +82,10 @@ impl Header for Prefer {
     }
 }
 
+__hyper__try_from!(Prefer);
+__hyper__from_str!(Prefer);
+__hyper__serde!(Prefer);
+
 impl fmt::Display for Prefer {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         fmt_comma_delimited(f, &self[..])
@@ -64,6 +64,8 @@ impl Header for PreferenceApplied {
         NAME
     }
 
+    __hyper__http_name!("Preference-Applied");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<PreferenceApplied>
     where
         T: RawLike<'a>,
@@ -72,7 +74,7 @@ impl Header for PreferenceApplied {
         if !preferences.is_empty() {
             Ok(PreferenceApplied(preferences))
         } else {
-            Err(::Error::Header)
+            Err(::Error::header(::HeaderErrorKind::Empty))
         }
     }
 
@@ -81,21 +83,32 @@ impl Header for PreferenceApplied {
     }
 }
 
+__hyper__try_from!(PreferenceApplied);
+__hyper__from_str!(PreferenceApplied);
+__hyper__serde!(PreferenceApplied);
+
 impl fmt::Display for PreferenceApplied {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        //TODO: format this without allocating a Vec and cloning contents
-        let preferences: Vec<_> = self
-            .0
-            .iter()
-            .map(|pref| match pref {
-                // The spec ignores parameters in `Preferences-Applied`
-                Preference::Extension(name, value, _) => {
-                    Preference::Extension(name.to_owned(), value.to_owned(), vec![])
+        fmt_comma_delimited(f, self.0.iter().map(Applied))
+    }
+}
+
+// A preference as formatted in `Preference-Applied`, where the spec
+// ignores parameters.
+struct Applied<'a>(&'a Preference);
+
+impl<'a> fmt::Display for Applied<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self.0 {
+            Preference::Extension(ref name, ref value, _) => {
+                f.write_str(name)?;
+                if !value.is_empty() {
+                    write!(f, "={}", value)?;
                 }
-                preference => preference.clone(),
-            })
-            .collect();
-        fmt_comma_delimited(f, &preferences)
+                Ok(())
+            }
+            ref preference => fmt::Display::fmt(preference, f),
+        }
     }
 }
 
@@ -40,8 +40,8 @@ use std::str::{from_utf8, FromStr};
 /// headers.encode(
 ///    &ProxyAuthorization(
 ///        Basic {
-///            username: "Aladdin".to_owned(),
-///            password: Some("open sesame".to_owned())
+///            username: "Aladdin".into(),
+///            password: Some("open sesame".into())
 ///        }
 ///    )
 /// );
@@ -55,7 +55,7 @@ use std::str::{from_utf8, FromStr};
 /// headers.encode(
 ///    &ProxyAuthorization(
 ///        Bearer {
-///            token: "QWxhZGRpbjpvcGVuIHNlc2FtZQ".to_owned()
+///            token: "QWxhZGRpbjpvcGVuIHNlc2FtZQ".into()
 ///        }
 ///    )
 /// );
@@ -86,6 +86,8 @@ where
         NAME
     }
 
+    __hyper__http_name!("Proxy-Authorization");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ProxyAuthorization<S>>
     where
         T: RawLike<'a>,
@@ -99,19 +101,19 @@ where
                         .map(ProxyAuthorization)
                     {
                         Ok(h) => Ok(h),
-                        Err(_) => Err(::Error::Header),
+                        Err(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
                     }
                 } else {
-                    Err(::Error::Header)
+                    Err(::Error::header(::HeaderErrorKind::Token))
                 }
             } else {
                 match header.parse::<S>().map(ProxyAuthorization) {
                     Ok(h) => Ok(h),
-                    Err(_) => Err(::Error::Header),
+                    Err(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
                 }
             }
         } else {
-            Err(::Error::Header)
+            Err(::header::parsing::not_one(raw))
         }
     }
 
@@ -156,6 +158,82 @@ where
     }
 }
 
+impl<'v, S> ::std::convert::TryFrom<&'v ::http::header::HeaderValue> for ProxyAuthorization<S>
+where
+    S: Scheme + Any,
+{
+    type Error = ::Error;
+
+    #[inline]
+    fn try_from(value: &'v ::http::header::HeaderValue) -> ::Result<Self> {
+        Self::parse_header(&::header::Raw::from(value))
+    }
+}
+
+impl<S> ::std::str::FromStr for ProxyAuthorization<S>
+where
+    S: Scheme + Any,
+{
+    type Err = ::Error;
+
+    #[inline]
+    fn from_str(s: &str) -> ::Result<Self> {
+        Self::parse_header(&::header::Raw::from(s))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<S> ::serde::Serialize for ProxyAuthorization<S>
+where
+    S: Scheme + Any,
+{
+    #[inline]
+    fn serialize<Se>(&self, serializer: Se) -> ::std::result::Result<Se::Ok, Se::Error>
+    where
+        Se: ::serde::Serializer,
+    {
+        ::header::__private::serialize(self, serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, S> ::serde::Deserialize<'de> for ProxyAuthorization<S>
+where
+    S: Scheme + Any,
+{
+    #[inline]
+    fn deserialize<D>(deserializer: D) -> ::std::result::Result<Self, D::Error>
+    where
+        D: ::serde::Deserializer<'de>,
+    {
+        ::header::__private::deserialize(deserializer)
+    }
+}
+
+impl<'h, S> ::std::convert::TryFrom<&'h ProxyAuthorization<S>> for ::http::header::HeaderValue
+where
+    S: Scheme + Any,
+{
+    type Error = ::http::header::InvalidHeaderValue;
+
+    #[inline]
+    fn try_from(header: &'h ProxyAuthorization<S>) -> ::std::result::Result<Self, Self::Error> {
+        ::header::__private::to_value(header)
+    }
+}
+
+impl<S> ::std::convert::TryFrom<ProxyAuthorization<S>> for ::http::header::HeaderValue
+where
+    S: Scheme + Any,
+{
+    type Error = ::http::header::InvalidHeaderValue;
+
+    #[inline]
+    fn try_from(header: ProxyAuthorization<S>) -> ::std::result::Result<Self, Self::Error> {
+        ::header::__private::to_value(&header)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::super::super::{Basic, Bearer, Header, Raw};
@@ -187,8 +265,8 @@ mod tests {
     fn test_basic_auth() {
         let mut headers = Headers::new();
         headers.set(ProxyAuthorization(Basic {
-            username: "Aladdin".to_owned(),
-            password: Some("open sesame".to_owned()),
+            username: "Aladdin".into(),
+            password: Some("open sesame".into()),
         }));
         assert_eq!(
             headers.to_string(),
@@ -201,7 +279,7 @@ mod tests {
     fn test_basic_auth_no_password() {
         let mut headers = Headers::new();
         headers.set(ProxyAuthorization(Basic {
-            username: "Aladdin".to_owned(),
+            username: "Aladdin".into(),
             password: None,
         }));
         assert_eq!(
@@ -215,7 +293,7 @@ mod tests {
         let r: Raw = b"Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".as_ref().into();
         let auth: ProxyAuthorization<Basic> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.username, "Aladdin");
-        assert_eq!(auth.0.password, Some("open sesame".to_owned()));
+        assert_eq!(auth.0.password.as_deref(), Some("open sesame"));
     }
 
     #[test]
@@ -223,7 +301,7 @@ mod tests {
         let r: Raw = b"Basic QWxhZGRpbjo=".as_ref().into();
         let auth: ProxyAuthorization<Basic> = Header::parse_header(&r).unwrap();
         assert_eq!(auth.0.username, "Aladdin");
-        assert_eq!(auth.0.password, Some("".to_owned()));
+        assert_eq!(auth.0.password.as_deref(), Some(""));
     }
 
     #[cfg(feature = "headers")]
@@ -231,7 +309,7 @@ mod tests {
     fn test_bearer_auth() {
         let mut headers = Headers::new();
         headers.set(ProxyAuthorization(Bearer {
-            token: "fpKL54jvWmEGVoRdCNjG".to_owned(),
+            token: "fpKL54jvWmEGVoRdCNjG".into(),
         }));
         assert_eq!(
             headers.to_string(),
@@ -99,19 +99,19 @@ impl ByteRangeSpec {
     /// conditions:
     ///
     /// > If a valid byte-range-set includes at least one byte-range-spec with
-    /// a first-byte-pos that is less than the current length of the
-    /// representation, or at least one suffix-byte-range-spec with a
-    /// non-zero suffix-length, then the byte-range-set is satisfiable.
-    /// Otherwise, the byte-range-set is unsatisfiable.
+    /// > a first-byte-pos that is less than the current length of the
+    /// > representation, or at least one suffix-byte-range-spec with a
+    /// > non-zero suffix-length, then the byte-range-set is satisfiable.
+    /// > Otherwise, the byte-range-set is unsatisfiable.
     ///
     /// The function also computes remainder ranges based on the RFC:
     ///
     /// > If the last-byte-pos value is
-    /// absent, or if the value is greater than or equal to the current
-    /// length of the representation data, the byte range is interpreted as
-    /// the remainder of the representation (i.e., the server replaces the
-    /// value of last-byte-pos with a value that is one less than the current
-    /// length of the selected representation).
+    /// > absent, or if the value is greater than or equal to the current
+    /// > length of the representation data, the byte range is interpreted as
+    /// > the remainder of the representation (i.e., the server replaces the
+    /// > value of last-byte-pos with a value that is one less than the current
+    /// > length of the selected representation).
     ///
     /// [1]: https://tools.ietf.org/html/rfc7233
     pub fn to_satisfiable_range(&self, full_length: u64) -> Option<(u64, u64)> {
@@ -119,22 +119,22 @@ impl ByteRangeSpec {
         if full_length == 0 {
             return None;
         }
-        match self {
-            &ByteRangeSpec::FromTo(from, to) => {
+        match *self {
+            ByteRangeSpec::FromTo(from, to) => {
                 if from < full_length && from <= to {
                     Some((from, ::std::cmp::min(to, full_length - 1)))
                 } else {
                     None
                 }
             }
-            &ByteRangeSpec::AllFrom(from) => {
+            ByteRangeSpec::AllFrom(from) => {
                 if from < full_length {
                     Some((from, full_length - 1))
                 } else {
                     None
                 }
             }
-            &ByteRangeSpec::Last(last) => {
+            ByteRangeSpec::Last(last) => {
                 if last > 0 {
                     // From the RFC: If the selected representation is shorter
                     // than the specified suffix-length,
@@ -211,14 +211,14 @@ impl FromStr for Range {
             (Some("bytes"), Some(ranges)) => {
                 let ranges = from_comma_delimited(ranges);
                 if ranges.is_empty() {
-                    return Err(::Error::Header);
+                    return Err(::Error::header(::HeaderErrorKind::Empty));
                 }
                 Ok(Range::Bytes(ranges))
             }
             (Some(unit), Some(range_str)) if !unit.is_empty() && !range_str.is_empty() => {
                 Ok(Range::Unregistered(unit.to_owned(), range_str.to_owned()))
             }
-            _ => Err(::Error::Header),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
         }
     }
 }
@@ -232,17 +232,17 @@ impl FromStr for ByteRangeSpec {
         match (parts.next(), parts.next()) {
             (Some(""), Some(end)) => end
                 .parse()
-                .or(Err(::Error::Header))
+                .or(Err(::Error::header(::HeaderErrorKind::Number)))
                 .map(ByteRangeSpec::Last),
             (Some(start), Some("")) => start
                 .parse()
-                .or(Err(::Error::Header))
+                .or(Err(::Error::header(::HeaderErrorKind::Number)))
                 .map(ByteRangeSpec::AllFrom),
             (Some(start), Some(end)) => match (start.parse(), end.parse()) {
                 (Ok(start), Ok(end)) if start <= end => Ok(ByteRangeSpec::FromTo(start, end)),
-                _ => Err(::Error::Header),
+                _ => Err(::Error::header(::HeaderErrorKind::Number)),
             },
-            _ => Err(::Error::Header),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
         }
     }
 }
@@ -263,6 +263,8 @@ impl Header for Range {
         NAME
     }
 
+    __hyper__http_name!("Range");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Range>
     where
         T: RawLike<'a>,
@@ -435,3 +437,6 @@ bench_header!(bytes_multi, Range, {
 bench_header!(custom_unit, Range, { vec![b"other=0-100000".to_vec()] });
 
 standard_header!(Range, RANGE);
+
+__hyper__try_from!(Range);
+__hyper__serde!(Range);
@@ -0,0 +1,133 @@
+use std::fmt;
+use std::str::FromStr;
+
+use header::parsing::{fmt_quoted, parse_parameter, split_delimited, unquote};
+
+header! {
+    /// `RateLimit` header, defined in
+    /// [RateLimit header fields for HTTP](https://datatracker.ietf.org/doc/html/draft-ietf-httpapi-ratelimit-headers#section-4)
+    ///
+    /// The `RateLimit` response header conveys the current service limits
+    /// of the client, as one item per quota policy: the remaining quota
+    /// units, and the number of seconds until the quota resets.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// RateLimit = sf-list
+    /// ```
+    ///
+    /// # Example values
+    /// * `"default";r=50;t=30`
+    /// * `"burst";r=0;t=5, "daily";r=900`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{RateLimit, RateLimitItem, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let limit = RateLimit(vec![RateLimitItem::new("default", 50).reset(30)].into());
+    /// headers.encode(&limit);
+    /// assert_eq!(headers["ratelimit"], "\"default\";r=50;t=30");
+    /// # }
+    /// ```
+    (RateLimit, "RateLimit") => (RateLimitItem)+
+
+    test_ratelimit {
+        test_header!(test1, [b"\"default\";r=50;t=30"]);
+        test_header!(test2, [b"\"burst\";r=0;t=5, \"daily\";r=900"]);
+
+        #[test]
+        fn test_parse() {
+            let limit: RateLimit = "\"a,b\" ; r=1 ; pk=:cHsdsRa894==: ; t=2".parse().unwrap();
+            assert_eq!(limit.0[..], [RateLimitItem::new("a,b", 1).reset(2)]);
+
+            assert!("\"default\";t=30".parse::<RateLimitItem>().is_err());
+            assert!("\"\";r=1".parse::<RateLimitItem>().is_err());
+            assert!("\"default\";r=-1".parse::<RateLimitItem>().is_err());
+            assert!("\"default\";r=1.5".parse::<RateLimitItem>().is_err());
+        }
+    }
+}
+
+/// An item of the `RateLimit` header: the service limit of one quota
+/// policy.
+///
+/// Parameters other than `r` and `t`, such as the partition key `pk`, are
+/// ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RateLimitItem {
+    /// The name of the quota policy, as in the `RateLimit-Policy` header.
+    pub policy: String,
+    /// The remaining quota units, `r`.
+    pub remaining: u64,
+    /// The number of seconds until the quota resets, `t`, if given.
+    pub reset: Option<u64>,
+}
+
+impl RateLimitItem {
+    /// Create a new item, without a reset time.
+    pub fn new<S: Into<String>>(policy: S, remaining: u64) -> RateLimitItem {
+        RateLimitItem {
+            policy: policy.into(),
+            remaining,
+            reset: None,
+        }
+    }
+
+    /// Set the number of seconds until the quota resets.
+    pub fn reset(mut self, seconds: u64) -> RateLimitItem {
+        self.reset = Some(seconds);
+        self
+    }
+}
+
+impl fmt::Display for RateLimitItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_quoted(f, &self.policy)?;
+        write!(f, ";r={}", self.remaining)?;
+        if let Some(reset) = self.reset {
+            write!(f, ";t={}", reset)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for RateLimitItem {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<RateLimitItem> {
+        let mut parts = split_delimited(s, ';');
+        let policy = unquote(parts.next().unwrap_or(""))?;
+        let mut remaining = None;
+        let mut reset = None;
+        for part in parts {
+            match parse_parameter(part)? {
+                ("r", Some(value)) => remaining = Some(parse_integer(&value)?),
+                ("t", Some(value)) => reset = Some(parse_integer(&value)?),
+                _ => {}
+            }
+        }
+        match remaining {
+            Some(remaining) if !policy.is_empty() => Ok(RateLimitItem {
+                policy: policy.into_owned(),
+                remaining,
+                reset,
+            }),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+        }
+    }
+}
+
+// A non-negative sf-integer.
+pub(crate) fn parse_integer(s: &str) -> ::Result<u64> {
+    if s.is_empty() || s.len() > 15 || !s.bytes().all(|b| b.is_ascii_digit()) {
+        return Err(::Error::header(::HeaderErrorKind::Syntax));
+    }
+    s.parse()
+        .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Syntax, e))
+}
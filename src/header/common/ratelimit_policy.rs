@@ -0,0 +1,141 @@
+use std::fmt;
+use std::str::FromStr;
+
+use super::ratelimit::parse_integer;
+use header::parsing::{fmt_quoted, parse_parameter, split_delimited, unquote};
+
+header! {
+    /// `RateLimit-Policy` header, defined in
+    /// [RateLimit header fields for HTTP](https://datatracker.ietf.org/doc/html/draft-ietf-httpapi-ratelimit-headers#section-3)
+    ///
+    /// The `RateLimit-Policy` response header conveys the quota policies of
+    /// a server: for each, the quota units allowed within a time window.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// RateLimit-Policy = sf-list
+    /// ```
+    ///
+    /// # Example values
+    /// * `"default";q=100;w=60`
+    /// * `"burst";q=10;w=5, "bytes";q=1048576;qu="content-bytes";w=3600`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{QuotaPolicy, RateLimitPolicy, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let policy = RateLimitPolicy(vec![QuotaPolicy::new("default", 100).window(60)].into());
+    /// headers.encode(&policy);
+    /// assert_eq!(headers["ratelimit-policy"], "\"default\";q=100;w=60");
+    /// # }
+    /// ```
+    (RateLimitPolicy, "RateLimit-Policy") => (QuotaPolicy)+
+
+    test_ratelimit_policy {
+        test_header!(test1, [b"\"default\";q=100;w=60"]);
+        test_header!(
+            test2,
+            [b"\"burst\";q=10;w=5, \"bytes\";q=1048576;qu=\"content-bytes\";w=3600"]
+        );
+
+        #[test]
+        fn test_parse() {
+            let policy: RateLimitPolicy = "\"default\";w=60;q=100;pk=:cHsdsRa894==:".parse().unwrap();
+            assert_eq!(policy.0[..], [QuotaPolicy::new("default", 100).window(60)]);
+
+            assert!("\"default\";w=60".parse::<QuotaPolicy>().is_err());
+            assert!("\"default\";q=-1".parse::<QuotaPolicy>().is_err());
+        }
+    }
+}
+
+/// An item of the `RateLimit-Policy` header: one quota policy.
+///
+/// Parameters other than `q`, `qu` and `w`, such as the partition key
+/// `pk`, are ignored.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuotaPolicy {
+    /// The name of the policy, as in the `RateLimit` header.
+    pub name: String,
+    /// The quota units allowed within the window, `q`.
+    pub quota: u64,
+    /// The quota unit, `qu`, such as `content-bytes`, if not the default
+    /// `requests`.
+    pub unit: Option<String>,
+    /// The window in seconds, `w`, if given.
+    pub window: Option<u64>,
+}
+
+impl QuotaPolicy {
+    /// Create a new policy, of the `requests` unit and without a window.
+    pub fn new<S: Into<String>>(name: S, quota: u64) -> QuotaPolicy {
+        QuotaPolicy {
+            name: name.into(),
+            quota,
+            unit: None,
+            window: None,
+        }
+    }
+
+    /// Set the quota unit.
+    pub fn unit<S: Into<String>>(mut self, unit: S) -> QuotaPolicy {
+        self.unit = Some(unit.into());
+        self
+    }
+
+    /// Set the window in seconds.
+    pub fn window(mut self, seconds: u64) -> QuotaPolicy {
+        self.window = Some(seconds);
+        self
+    }
+}
+
+impl fmt::Display for QuotaPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_quoted(f, &self.name)?;
+        write!(f, ";q={}", self.quota)?;
+        if let Some(ref unit) = self.unit {
+            f.write_str(";qu=")?;
+            fmt_quoted(f, unit)?;
+        }
+        if let Some(window) = self.window {
+            write!(f, ";w={}", window)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for QuotaPolicy {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<QuotaPolicy> {
+        let mut parts = split_delimited(s, ';');
+        let name = unquote(parts.next().unwrap_or(""))?;
+        let mut quota = None;
+        let mut unit = None;
+        let mut window = None;
+        for part in parts {
+            match parse_parameter(part)? {
+                ("q", Some(value)) => quota = Some(parse_integer(&value)?),
+                ("qu", Some(value)) => unit = Some(value.into_owned()),
+                ("w", Some(value)) => window = Some(parse_integer(&value)?),
+                _ => {}
+            }
+        }
+        match quota {
+            Some(quota) if !name.is_empty() => Ok(QuotaPolicy {
+                name: name.into_owned(),
+                quota,
+                unit,
+                window,
+            }),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+        }
+    }
+}
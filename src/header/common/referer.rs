@@ -35,7 +35,6 @@ header! {
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(&Referer::new("http://www.example.com/index.html"));
     /// ```
-    // TODO Use URL
     (Referer, "Referer") => Cow[str]
 
     test_referer {
@@ -44,8 +43,48 @@ header! {
     }
 }
 
+/// The `Referer` header, borrowing its value from the raw header rather than
+/// copying it. See `HeaderRef`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RefererRef<'a>(&'a str);
+
+impl<'a> RefererRef<'a> {
+    /// The header value.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Copy into an owned `Referer`.
+    pub fn into_owned(self) -> Referer {
+        Referer::new(self.0.to_owned())
+    }
+}
+
+impl<'a> ::std::ops::Deref for RefererRef<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl<'a> ::header::HeaderRef<'a> for RefererRef<'a> {
+    fn header_name() -> &'static str {
+        <Referer as ::header::Header>::header_name()
+    }
+
+    fn parse_lines<I>(lines: I) -> ::Result<RefererRef<'a>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        ::header::parsing::from_one_raw_str_ref(lines).map(RefererRef)
+    }
+}
+
 bench_header!(bench, Referer, {
     vec![b"http://foo.com/hello:3000".to_vec()]
 });
 
+uri_reference!(Referer);
+
 standard_header!(Referer, REFERER);
@@ -58,6 +58,8 @@ impl Header for ReferrerPolicy {
         NAME
     }
 
+    __hyper__http_name!("Referrer-Policy");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<ReferrerPolicy>
     where
         T: RawLike<'a>,
@@ -66,22 +68,27 @@ impl Header for ReferrerPolicy {
         // See https://www.w3.org/TR/referrer-policy/#determine-policy-for-token
         let headers: Vec<String> = parsing::from_comma_delimited(raw)?;
 
-        for h in headers.iter().rev() {
-            let slice = &h.to_ascii_lowercase()[..];
-            match slice {
-                "no-referrer" | "never" => return Ok(NoReferrer),
-                "no-referrer-when-downgrade" | "default" => return Ok(NoReferrerWhenDowngrade),
-                "same-origin" => return Ok(SameOrigin),
-                "origin" => return Ok(Origin),
-                "origin-when-cross-origin" => return Ok(OriginWhenCrossOrigin),
-                "strict-origin" => return Ok(StrictOrigin),
-                "strict-origin-when-cross-origin" => return Ok(StrictOriginWhenCrossOrigin),
-                "unsafe-url" | "always" => return Ok(UnsafeUrl),
-                _ => continue,
-            }
-        }
-
-        Err(::Error::Header)
+        let policies = [
+            ("no-referrer", NoReferrer),
+            ("never", NoReferrer),
+            ("no-referrer-when-downgrade", NoReferrerWhenDowngrade),
+            ("default", NoReferrerWhenDowngrade),
+            ("same-origin", SameOrigin),
+            ("origin", Origin),
+            ("origin-when-cross-origin", OriginWhenCrossOrigin),
+            ("strict-origin", StrictOrigin),
+            (
+                "strict-origin-when-cross-origin",
+                StrictOriginWhenCrossOrigin,
+            ),
+            ("unsafe-url", UnsafeUrl),
+            ("always", UnsafeUrl),
+        ];
+        headers
+            .iter()
+            .rev()
+            .find_map(|h| parsing::find_ignore_case(h, &policies))
+            .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
     }
 
     fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
@@ -128,7 +135,15 @@ mod tests {
         let a: ReferrerPolicy = Header::parse_header(&r).unwrap();
         let b = ReferrerPolicy::Origin;
         assert_eq!(a, b);
+
+        let r: Raw = "Strict-Origin, NEVER".into();
+        let a: ReferrerPolicy = Header::parse_header(&r).unwrap();
+        assert_eq!(a, ReferrerPolicy::NoReferrer);
     }
 }
 
 standard_header!(ReferrerPolicy, REFERRER_POLICY);
+
+__hyper__try_from!(ReferrerPolicy);
+__hyper__from_str!(ReferrerPolicy);
+__hyper__serde!(ReferrerPolicy);
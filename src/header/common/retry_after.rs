@@ -72,7 +72,7 @@ use std::time::Duration;
 ///     &RetryAfter::DateTime(date.into())
 /// );
 /// ```
-
+///
 /// Retry-After header, defined in [RFC7231](http://tools.ietf.org/html/rfc7231#section-7.1.3)
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
 pub enum RetryAfter {
@@ -91,6 +91,8 @@ impl Header for RetryAfter {
         NAME
     }
 
+    __hyper__http_name!("Retry-After");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<RetryAfter>
     where
         T: RawLike<'a>,
@@ -98,7 +100,7 @@ impl Header for RetryAfter {
         if let Some(line) = raw.one() {
             let utf8_str = match ::std::str::from_utf8(line) {
                 Ok(utf8_str) => utf8_str,
-                Err(_) => return Err(::Error::Header),
+                Err(e) => return Err(::Error::header_with_source(::HeaderErrorKind::Utf8, e)),
             };
 
             if let Ok(datetime) = utf8_str.parse::<HttpDate>() {
@@ -109,9 +111,9 @@ impl Header for RetryAfter {
                 return Ok(RetryAfter::Delay(Duration::from_secs(seconds)));
             }
 
-            Err(::Error::Header)
+            Err(::Error::header(::HeaderErrorKind::Date))
         } else {
-            Err(::Error::Header)
+            Err(::header::parsing::not_one(raw))
         }
     }
 
@@ -184,3 +186,7 @@ mod tests {
 }
 
 standard_header!(RetryAfter, RETRY_AFTER);
+
+__hyper__try_from!(RetryAfter);
+__hyper__from_str!(RetryAfter);
+__hyper__serde!(RetryAfter);
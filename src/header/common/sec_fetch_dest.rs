@@ -0,0 +1,170 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Sec-Fetch-Dest` header, defined in
+/// [Fetch Metadata Request Headers](https://w3c.github.io/webappsec-fetch-metadata/#sec-fetch-dest-header)
+///
+/// The `Sec-Fetch-Dest` request header conveys the destination of a
+/// request: how the fetched data will be used, such as a `script` or an
+/// `iframe`.
+///
+/// # ABNF
+///
+/// ```text
+/// Sec-Fetch-Dest = "audio" / "audioworklet" / "document" / ... / "xslt"
+/// ```
+///
+/// # Example values
+///
+/// * `document`
+/// * `script`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{SecFetchDest, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&SecFetchDest::Document);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SecFetchDest {
+    /// `audio`
+    Audio,
+    /// `audioworklet`
+    Audioworklet,
+    /// `document`
+    Document,
+    /// `embed`
+    Embed,
+    /// `empty`: for requests without a destination, such as `fetch()`
+    Empty,
+    /// `font`
+    Font,
+    /// `frame`
+    Frame,
+    /// `iframe`
+    Iframe,
+    /// `image`
+    Image,
+    /// `manifest`
+    Manifest,
+    /// `object`
+    Object,
+    /// `paintworklet`
+    Paintworklet,
+    /// `report`
+    Report,
+    /// `script`
+    Script,
+    /// `serviceworker`
+    Serviceworker,
+    /// `sharedworker`
+    Sharedworker,
+    /// `style`
+    Style,
+    /// `track`
+    Track,
+    /// `video`
+    Video,
+    /// `webidentity`
+    Webidentity,
+    /// `worker`
+    Worker,
+    /// `xslt`
+    Xslt,
+}
+
+impl Header for SecFetchDest {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Sec-Fetch-Dest";
+        NAME
+    }
+
+    __hyper__http_name!("Sec-Fetch-Dest");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<SecFetchDest>
+    where
+        T: RawLike<'a>,
+    {
+        use self::SecFetchDest::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("audio", Audio),
+                ("audioworklet", Audioworklet),
+                ("document", Document),
+                ("embed", Embed),
+                ("empty", Empty),
+                ("font", Font),
+                ("frame", Frame),
+                ("iframe", Iframe),
+                ("image", Image),
+                ("manifest", Manifest),
+                ("object", Object),
+                ("paintworklet", Paintworklet),
+                ("report", Report),
+                ("script", Script),
+                ("serviceworker", Serviceworker),
+                ("sharedworker", Sharedworker),
+                ("style", Style),
+                ("track", Track),
+                ("video", Video),
+                ("webidentity", Webidentity),
+                ("worker", Worker),
+                ("xslt", Xslt),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for SecFetchDest {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::SecFetchDest::*;
+        f.write_str(match *self {
+            Audio => "audio",
+            Audioworklet => "audioworklet",
+            Document => "document",
+            Embed => "embed",
+            Empty => "empty",
+            Font => "font",
+            Frame => "frame",
+            Iframe => "iframe",
+            Image => "image",
+            Manifest => "manifest",
+            Object => "object",
+            Paintworklet => "paintworklet",
+            Report => "report",
+            Script => "script",
+            Serviceworker => "serviceworker",
+            Sharedworker => "sharedworker",
+            Style => "style",
+            Track => "track",
+            Video => "video",
+            Webidentity => "webidentity",
+            Worker => "worker",
+            Xslt => "xslt",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_sec_fetch_dest {
+    use super::SecFetchDest as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(test_iframe, vec![b"iframe"], Some(HeaderField::Iframe));
+    test_header!(test_serviceworker, [b"serviceworker"]);
+    test_header!(test_other, vec![b"unknown"], None);
+}
+
+__hyper__try_from!(SecFetchDest);
+__hyper__from_str!(SecFetchDest);
+__hyper__serde!(SecFetchDest);
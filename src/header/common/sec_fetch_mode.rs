@@ -0,0 +1,105 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Sec-Fetch-Mode` header, defined in
+/// [Fetch Metadata Request Headers](https://w3c.github.io/webappsec-fetch-metadata/#sec-fetch-mode-header)
+///
+/// The `Sec-Fetch-Mode` request header conveys the mode of a request, such
+/// as whether it navigates between documents or is a CORS request.
+///
+/// # ABNF
+///
+/// ```text
+/// Sec-Fetch-Mode = "cors" / "navigate" / "no-cors" / "same-origin" / "websocket"
+/// ```
+///
+/// # Example values
+///
+/// * `navigate`
+/// * `cors`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{SecFetchMode, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&SecFetchMode::Navigate);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SecFetchMode {
+    /// `cors`
+    Cors,
+    /// `navigate`
+    Navigate,
+    /// `no-cors`
+    NoCors,
+    /// `same-origin`
+    SameOrigin,
+    /// `websocket`
+    Websocket,
+}
+
+impl Header for SecFetchMode {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Sec-Fetch-Mode";
+        NAME
+    }
+
+    __hyper__http_name!("Sec-Fetch-Mode");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<SecFetchMode>
+    where
+        T: RawLike<'a>,
+    {
+        use self::SecFetchMode::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("cors", Cors),
+                ("navigate", Navigate),
+                ("no-cors", NoCors),
+                ("same-origin", SameOrigin),
+                ("websocket", Websocket),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for SecFetchMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::SecFetchMode::*;
+        f.write_str(match *self {
+            Cors => "cors",
+            Navigate => "navigate",
+            NoCors => "no-cors",
+            SameOrigin => "same-origin",
+            Websocket => "websocket",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_sec_fetch_mode {
+    use super::SecFetchMode as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(
+        test_navigate,
+        vec![b"navigate"],
+        Some(HeaderField::Navigate)
+    );
+    test_header!(test_no_cors, [b"no-cors"]);
+    test_header!(test_other, vec![b"nested-navigate"], None);
+}
+
+__hyper__try_from!(SecFetchMode);
+__hyper__from_str!(SecFetchMode);
+__hyper__serde!(SecFetchMode);
@@ -0,0 +1,102 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `Sec-Fetch-Site` header, defined in
+/// [Fetch Metadata Request Headers](https://w3c.github.io/webappsec-fetch-metadata/#sec-fetch-site-header)
+///
+/// The `Sec-Fetch-Site` request header conveys the relationship between the
+/// origin which initiated a request and the origin of its target, as
+/// determined by the user agent.
+///
+/// # ABNF
+///
+/// ```text
+/// Sec-Fetch-Site = "cross-site" / "same-origin" / "same-site" / "none"
+/// ```
+///
+/// # Example values
+///
+/// * `same-origin`
+/// * `cross-site`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{SecFetchSite, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&SecFetchSite::SameOrigin);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum SecFetchSite {
+    /// `cross-site`
+    CrossSite,
+    /// `same-origin`
+    SameOrigin,
+    /// `same-site`
+    SameSite,
+    /// `none`: a request initiated by the user, such as by typing a URL
+    None,
+}
+
+impl Header for SecFetchSite {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Sec-Fetch-Site";
+        NAME
+    }
+
+    __hyper__http_name!("Sec-Fetch-Site");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<SecFetchSite>
+    where
+        T: RawLike<'a>,
+    {
+        use self::SecFetchSite::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(
+            value.trim(),
+            &[
+                ("cross-site", CrossSite),
+                ("same-origin", SameOrigin),
+                ("same-site", SameSite),
+                ("none", None),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for SecFetchSite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::SecFetchSite::*;
+        f.write_str(match *self {
+            CrossSite => "cross-site",
+            SameOrigin => "same-origin",
+            SameSite => "same-site",
+            None => "none",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_sec_fetch_site {
+    use super::SecFetchSite as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(
+        test_cross_site,
+        vec![b"cross-site"],
+        Some(HeaderField::CrossSite)
+    );
+    test_header!(test_none, [b"none"]);
+    test_header!(test_other, vec![b"same-party"], None);
+}
+
+__hyper__try_from!(SecFetchSite);
+__hyper__from_str!(SecFetchSite);
+__hyper__serde!(SecFetchSite);
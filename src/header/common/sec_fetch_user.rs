@@ -0,0 +1,73 @@
+use header::{Header, RawLike};
+use std::fmt;
+
+/// `Sec-Fetch-User` header, defined in
+/// [Fetch Metadata Request Headers](https://w3c.github.io/webappsec-fetch-metadata/#sec-fetch-user-header)
+///
+/// The `Sec-Fetch-User` request header is sent with navigation requests
+/// activated by the user, and omitted otherwise.
+///
+/// # ABNF
+///
+/// ```text
+/// Sec-Fetch-User = "?1"
+/// ```
+///
+/// # Example values
+///
+/// * `?1`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{SecFetchUser, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&SecFetchUser);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub struct SecFetchUser;
+
+impl Header for SecFetchUser {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Sec-Fetch-User";
+        NAME
+    }
+
+    __hyper__http_name!("Sec-Fetch-User");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<SecFetchUser>
+    where
+        T: RawLike<'a>,
+    {
+        match raw.one() {
+            Some(line) if line == b"?1" => Ok(SecFetchUser),
+            _ => Err(::Error::header(::HeaderErrorKind::Token)),
+        }
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for SecFetchUser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("?1")
+    }
+}
+
+#[cfg(test)]
+mod test_sec_fetch_user {
+    use super::SecFetchUser as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(test_activated, vec![b"?1"], Some(HeaderField));
+    test_header!(test_false, vec![b"?0"], None);
+    test_header!(test_other, vec![b"true"], None);
+}
+
+__hyper__try_from!(SecFetchUser);
+__hyper__from_str!(SecFetchUser);
+__hyper__serde!(SecFetchUser);
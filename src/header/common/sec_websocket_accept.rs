@@ -0,0 +1,36 @@
+header! {
+    /// `Sec-WebSocket-Accept` header, defined in
+    /// [RFC6455](https://www.rfc-editor.org/rfc/rfc6455#section-11.3.3)
+    ///
+    /// The `Sec-WebSocket-Accept` header is sent by a server in the opening
+    /// handshake of a WebSocket connection, as the base64-encoded SHA-1 hash
+    /// of the client's `Sec-WebSocket-Key` and a fixed GUID; see the
+    /// [`websocket`](../websocket/index.html) module.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Sec-WebSocket-Accept = base64-value-non-empty
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `s3pPLMBiTxaQ9kYGzzhZRbK+xOo=`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{SecWebSocketAccept, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&SecWebSocketAccept("s3pPLMBiTxaQ9kYGzzhZRbK+xOo=".to_owned()));
+    /// ```
+    (SecWebSocketAccept, "Sec-WebSocket-Accept") => [String]
+
+    test_sec_websocket_accept {
+        test_header!(test1, [b"s3pPLMBiTxaQ9kYGzzhZRbK+xOo="]);
+    }
+}
+
+standard_header!(SecWebSocketAccept, SEC_WEBSOCKET_ACCEPT);
@@ -0,0 +1,64 @@
+use header::Base64;
+use std::convert::TryFrom;
+
+header! {
+    /// `Sec-WebSocket-Key` header, defined in
+    /// [RFC6455](https://www.rfc-editor.org/rfc/rfc6455#section-11.3.1)
+    ///
+    /// The `Sec-WebSocket-Key` header is sent by a client in the opening
+    /// handshake of a WebSocket connection, as a base64-encoded random
+    /// 16-byte nonce. The server proves that it received the handshake by
+    /// deriving `Sec-WebSocket-Accept` from it; see the
+    /// [`websocket`](../websocket/index.html) module.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Sec-WebSocket-Key = base64-value-non-empty
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `dGhlIHNhbXBsZSBub25jZQ==`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{SecWebSocketKey, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&SecWebSocketKey("dGhlIHNhbXBsZSBub25jZQ==".to_owned()));
+    /// ```
+    (SecWebSocketKey, "Sec-WebSocket-Key") => [String]
+
+    test_sec_websocket_key {
+        test_header!(test1, [b"dGhlIHNhbXBsZSBub25jZQ=="]);
+
+        #[test]
+        fn test_nonce() {
+            let key = SecWebSocketKey::from_nonce(*b"the sample nonce");
+            assert_eq!(key.0, "dGhlIHNhbXBsZSBub25jZQ==");
+            assert_eq!(key.nonce(), Some(*b"the sample nonce"));
+            assert_eq!(SecWebSocketKey("dGhlIHNhbXBsZSBub25jZQ".to_owned()).nonce(), None);
+            assert_eq!(SecWebSocketKey("c2hvcnQ=".to_owned()).nonce(), None);
+        }
+    }
+}
+
+impl SecWebSocketKey {
+    /// Create a key of a 16-byte nonce, which should be randomly selected
+    /// for each connection.
+    pub fn from_nonce(nonce: [u8; 16]) -> SecWebSocketKey {
+        SecWebSocketKey(Base64::STANDARD.encode(nonce))
+    }
+
+    /// Decode the nonce, or `None` if the key is not 16 bytes encoded with
+    /// the standard base64 alphabet and padding.
+    pub fn nonce(&self) -> Option<[u8; 16]> {
+        let bytes = Base64::STANDARD.decode(self.0.trim()).ok()?;
+        <[u8; 16]>::try_from(&bytes[..]).ok()
+    }
+}
+
+standard_header!(SecWebSocketKey, SEC_WEBSOCKET_KEY);
@@ -0,0 +1,36 @@
+header! {
+    /// `Sec-WebSocket-Protocol` header, defined in
+    /// [RFC6455](https://www.rfc-editor.org/rfc/rfc6455#section-11.3.4)
+    ///
+    /// The `Sec-WebSocket-Protocol` header is sent by a client in the opening
+    /// handshake of a WebSocket connection with the subprotocols it
+    /// supports, in order of preference. The server responds with the single
+    /// subprotocol it selected, if any.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Sec-WebSocket-Protocol = 1#token
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `chat, superchat`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{SecWebSocketProtocol, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&SecWebSocketProtocol(vec!["chat".to_owned()].into()));
+    /// ```
+    (SecWebSocketProtocol, "Sec-WebSocket-Protocol") => (String)+
+
+    test_sec_websocket_protocol {
+        test_header!(test1, [b"chat, superchat"]);
+    }
+}
+
+standard_header!(SecWebSocketProtocol, SEC_WEBSOCKET_PROTOCOL);
@@ -0,0 +1,41 @@
+header! {
+    /// `Sec-WebSocket-Version` header, defined in
+    /// [RFC6455](https://www.rfc-editor.org/rfc/rfc6455#section-11.3.5)
+    ///
+    /// The `Sec-WebSocket-Version` header is sent by a client in the opening
+    /// handshake of a WebSocket connection, with the single protocol version
+    /// it intends to use, `13` as of RFC 6455. A server which does not
+    /// support that version responds with the list of versions it supports.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Sec-WebSocket-Version-Client = version
+    /// Sec-WebSocket-Version-Server = 1#version
+    /// version = DIGIT / (NZDIGIT DIGIT) /
+    ///           ("1" DIGIT DIGIT) / ("2" DIGIT DIGIT)
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `13`
+    /// * `13, 8, 7`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// use hyperx::header::{SecWebSocketVersion, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&SecWebSocketVersion(vec![13].into()));
+    /// ```
+    (SecWebSocketVersion, "Sec-WebSocket-Version") => (u8)+
+
+    test_sec_websocket_version {
+        test_header!(test1, [b"13"]);
+        test_header!(test2, [b"13, 8, 7"]);
+    }
+}
+
+standard_header!(SecWebSocketVersion, SEC_WEBSOCKET_VERSION);
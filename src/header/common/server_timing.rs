@@ -0,0 +1,199 @@
+use std::fmt;
+use std::str::FromStr;
+use std::time::Duration;
+
+use header::parsing::{fmt_quoted, is_token, parse_parameter, split_delimited};
+
+header! {
+    /// `Server-Timing` header, defined in
+    /// [Server Timing](https://w3c.github.io/server-timing/#the-server-timing-header-field)
+    ///
+    /// The `Server-Timing` response header conveys metrics of the request
+    /// processing by the server, such as the durations of a database query
+    /// or a cache lookup, for display in browser developer tools or
+    /// collection via the Performance API.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Server-Timing             = #server-timing-metric
+    /// server-timing-metric      = metric-name *( OWS ";" OWS server-timing-param )
+    /// metric-name               = token
+    /// server-timing-param       = server-timing-param-name OWS "=" OWS server-timing-param-value
+    /// server-timing-param-name  = token
+    /// server-timing-param-value = token / quoted-string
+    /// ```
+    ///
+    /// # Example values
+    /// * `miss, db;dur=53, app;dur=47.2`
+    /// * `cache;desc="Cache Read";dur=23.2`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use std::time::Duration;
+    /// use hyperx::header::{ServerTiming, TimingMetric, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let timing = ServerTiming(vec![
+    ///     TimingMetric::new("db").duration(Duration::from_micros(53_400)),
+    ///     TimingMetric::new("cache").description("Cache Read"),
+    /// ].into());
+    /// headers.encode(&timing);
+    /// assert_eq!(headers["server-timing"], "db;dur=53.4, cache;desc=\"Cache Read\"");
+    /// # }
+    /// ```
+    (ServerTiming, "Server-Timing") => (TimingMetric)*
+
+    test_server_timing {
+        use std::time::Duration;
+
+        test_header!(test1, [b"miss, db;dur=53, app;dur=47.2"]);
+        test_header!(test2, [b"cache;dur=23.2;desc=\"Cache Read\""]);
+
+        #[test]
+        fn test_parse() {
+            let timing: ServerTiming =
+                "cache ; desc=\"Cache Read\" ; dur = 23.2 ; dur=1 ; x=y, total;dur=0.0005"
+                    .parse()
+                    .unwrap();
+            assert_eq!(
+                timing.0[..],
+                [
+                    TimingMetric::new("cache")
+                        .duration(Duration::from_micros(23_200))
+                        .description("Cache Read"),
+                    TimingMetric::new("total").duration(Duration::from_nanos(500)),
+                ]
+            );
+
+            // An invalid duration is ignored
+            let metric: TimingMetric = "db;dur=fast;desc=query".parse().unwrap();
+            assert_eq!(metric, TimingMetric::new("db").description("query"));
+
+            assert!("\"db\";dur=1".parse::<TimingMetric>().is_err());
+            assert!("".parse::<TimingMetric>().is_err());
+        }
+
+        #[test]
+        fn test_fmt_duration() {
+            let metric = TimingMetric::new("a").duration(Duration::from_nanos(1_234_567));
+            assert_eq!(metric.to_string(), "a;dur=1.235");
+            let metric = TimingMetric::new("b").duration(Duration::from_secs(2));
+            assert_eq!(metric.to_string(), "b;dur=2000");
+            let metric = TimingMetric::new("c").description("token");
+            assert_eq!(metric.to_string(), "c;desc=token");
+        }
+    }
+}
+
+/// A metric of the `Server-Timing` header.
+///
+/// Parameters other than `dur` and `desc` are ignored, as are repeated
+/// ones.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TimingMetric {
+    /// The name of the metric.
+    pub name: String,
+    /// The duration, `dur`, if given.
+    pub duration: Option<Duration>,
+    /// The description, `desc`, if given.
+    pub description: Option<String>,
+}
+
+impl TimingMetric {
+    /// Create a new metric, without a duration or description.
+    pub fn new<S: Into<String>>(name: S) -> TimingMetric {
+        TimingMetric {
+            name: name.into(),
+            duration: None,
+            description: None,
+        }
+    }
+
+    /// Set the duration.
+    pub fn duration(mut self, duration: Duration) -> TimingMetric {
+        self.duration = Some(duration);
+        self
+    }
+
+    /// Set the description.
+    pub fn description<S: Into<String>>(mut self, description: S) -> TimingMetric {
+        self.description = Some(description.into());
+        self
+    }
+}
+
+impl fmt::Display for TimingMetric {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.name)?;
+        if let Some(duration) = self.duration {
+            // Milliseconds, to microsecond precision
+            let micros = (duration.as_nanos() + 500) / 1000;
+            write!(f, ";dur={}", micros / 1000)?;
+            let mut fraction = micros % 1000;
+            if fraction != 0 {
+                // Three digits, without trailing zeros
+                let mut digits = 3;
+                while fraction % 10 == 0 {
+                    fraction /= 10;
+                    digits -= 1;
+                }
+                write!(f, ".{:01$}", fraction, digits)?;
+            }
+        }
+        if let Some(ref description) = self.description {
+            f.write_str(";desc=")?;
+            if is_token(description) {
+                f.write_str(description)?;
+            } else {
+                fmt_quoted(f, description)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for TimingMetric {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<TimingMetric> {
+        let mut parts = split_delimited(s, ';');
+        let name = parts.next().unwrap_or("").trim();
+        if !is_token(name) {
+            return Err(::Error::header(::HeaderErrorKind::Token));
+        }
+        let mut metric = TimingMetric::new(name);
+        let mut dur = false;
+        let mut desc = false;
+        for part in parts {
+            match parse_parameter(part)? {
+                (name, Some(value)) if name.eq_ignore_ascii_case("dur") && !dur => {
+                    dur = true;
+                    metric.duration = parse_millis(&value);
+                }
+                (name, Some(value)) if name.eq_ignore_ascii_case("desc") && !desc => {
+                    desc = true;
+                    metric.description = Some(value.into_owned());
+                }
+                _ => {}
+            }
+        }
+        Ok(metric)
+    }
+}
+
+// A non-negative number of milliseconds, to nanosecond precision.
+fn parse_millis(s: &str) -> Option<Duration> {
+    if !s.bytes().all(|b| b.is_ascii_digit() || b == b'.') {
+        return None;
+    }
+    let nanos = (s.parse::<f64>().ok()? * 1e6).round();
+    if nanos >= u64::MAX as f64 {
+        return None;
+    }
+    Some(Duration::from_nanos(nanos as u64))
+}
@@ -1,6 +1,8 @@
-use header::{Header, RawLike};
+use header::parsing::{is_strict, is_token, Limit, ParseConfig};
+use header::{Header, HttpDate, RawLike};
 use std::fmt;
-use std::str::from_utf8;
+use std::str::{from_utf8, FromStr};
+use std::time::Duration;
 
 /// `Set-Cookie` header, defined [RFC6265](http://tools.ietf.org/html/rfc6265#section-4.1)
 ///
@@ -13,11 +15,18 @@ use std::str::from_utf8;
 ///
 /// `SetCookie` _must not_ be encoded as a comma-delimited list. For this
 /// reason, it doesn't implement `fmt::Display` (and `std::string::ToString`).
+/// Each `ResponseCookie` is formatted as its own line.
 ///
-/// Consider using the _cookie_ crate for parsing/decoding or encoding
-/// cookie values. [Usage example].
+/// When parsed leniently, as by default, lines which are not a cookie are
+/// dropped without error, and invalid attribute values are ignored, as a
+/// user agent does: the parsed `SetCookie` may hold fewer cookies than the
+/// field has lines, and fails with `HeaderErrorKind::Empty` only if none is
+/// valid. When parsed strictly, any invalid line or attribute fails the
+/// header.
 ///
-/// [Usage example]: https://github.com/dekellum/hyperx/issues/30#issuecomment-733151305
+/// With the `cookie` feature, `SetCookie` can also be collected from
+/// `cookie::Cookie` values, and a `Vec<cookie::Cookie>` can be converted
+/// via `TryFrom<&SetCookie>`.
 ///
 /// # ABNF
 ///
@@ -66,20 +75,33 @@ use std::str::from_utf8;
 ///
 /// ```
 /// # extern crate http;
-/// use hyperx::header::{SetCookie, TypedHeaders};
+/// use std::time::Duration;
+/// use hyperx::header::{ResponseCookie, SameSite, SetCookie, TypedHeaders};
 ///
 /// let mut headers = http::HeaderMap::new();
+/// headers.encode(&SetCookie(vec![
+///     ResponseCookie::new("session", "31d4d96e407aad42")
+///         .path("/")
+///         .secure(true)
+///         .http_only(true)
+///         .same_site(SameSite::Lax),
+///     ResponseCookie::new("lang", "en-US").max_age(Duration::from_secs(3600)),
+/// ]));
+/// let lines: Vec<_> = headers.get_all(http::header::SET_COOKIE).iter().collect();
+/// assert_eq!(lines, [
+///     "session=31d4d96e407aad42; Path=/; Secure; HttpOnly; SameSite=Lax",
+///     "lang=en-US; Max-Age=3600",
+/// ]);
 ///
-/// headers.append(
-///     http::header::SET_COOKIE,
-///     "foo=bar; Path=/path; Domain=example.com".parse().unwrap()
-/// );
-/// let cookie: SetCookie = headers.decode().unwrap();
+/// let cookies: SetCookie = headers.decode().unwrap();
+/// assert_eq!(cookies[0].name, "session");
+/// assert!(cookies[0].secure);
+/// assert_eq!(cookies[1].max_age, Some(Duration::from_secs(3600)));
 /// ```
 #[derive(Clone, PartialEq, Debug)]
-pub struct SetCookie(pub Vec<String>);
+pub struct SetCookie(pub Vec<ResponseCookie>);
 
-__hyper__deref!(SetCookie => Vec<String>);
+__hyper__deref!(SetCookie => Vec<ResponseCookie>);
 
 impl Header for SetCookie {
     fn header_name() -> &'static str {
@@ -87,21 +109,31 @@ impl Header for SetCookie {
         NAME
     }
 
+    __hyper__http_name!("Set-Cookie");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<SetCookie>
     where
         T: RawLike<'a>,
     {
+        let config = ParseConfig::current();
+        config.check(Limit::CookiePairs, raw.len())?;
         let mut set_cookies = Vec::with_capacity(raw.len());
         for set_cookies_raw in raw.iter() {
-            if let Ok(s) = from_utf8(set_cookies_raw) {
-                set_cookies.push(s.trim().to_owned());
+            config.check(Limit::ValueLength, set_cookies_raw.len())?;
+            let parsed = from_utf8(set_cookies_raw)
+                .map_err(|_| ::Error::header(::HeaderErrorKind::Syntax))
+                .and_then(|s| ResponseCookie::parse(s, config.is_strict()));
+            match parsed {
+                Ok(cookie) => set_cookies.push(cookie),
+                Err(e) if config.is_strict() => return Err(e.with_header_value(set_cookies_raw)),
+                Err(_) => {}
             }
         }
 
         if !set_cookies.is_empty() {
             Ok(SetCookie(set_cookies))
         } else {
-            Err(::Error::Header)
+            Err(::Error::header(::HeaderErrorKind::Empty))
         }
     }
 
@@ -113,12 +145,314 @@ impl Header for SetCookie {
     }
 }
 
+impl From<ResponseCookie> for SetCookie {
+    fn from(cookie: ResponseCookie) -> SetCookie {
+        SetCookie(vec![cookie])
+    }
+}
+
+/// A cookie of the `Set-Cookie` header, with its attributes.
+///
+/// Attributes other than those of RFC6265 and `SameSite`, such as
+/// `Partitioned`, are kept as `extensions`, in order.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResponseCookie {
+    /// The name of the cookie.
+    pub name: String,
+    /// The value of the cookie, including any enclosing quotes.
+    pub value: String,
+    /// The `Expires` attribute, if given.
+    pub expires: Option<HttpDate>,
+    /// The `Max-Age` attribute, if given. A negative value parses as zero.
+    pub max_age: Option<Duration>,
+    /// The `Domain` attribute, if given, without any leading `.`.
+    pub domain: Option<String>,
+    /// The `Path` attribute, if given.
+    pub path: Option<String>,
+    /// True if the `Secure` attribute is given.
+    pub secure: bool,
+    /// True if the `HttpOnly` attribute is given.
+    pub http_only: bool,
+    /// The `SameSite` attribute, if given.
+    pub same_site: Option<SameSite>,
+    /// Any other attributes, such as `Partitioned` or `Priority=High`.
+    pub extensions: Vec<String>,
+}
+
+impl ResponseCookie {
+    /// Create a new cookie, without attributes.
+    pub fn new<N, V>(name: N, value: V) -> ResponseCookie
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        ResponseCookie {
+            name: name.into(),
+            value: value.into(),
+            expires: None,
+            max_age: None,
+            domain: None,
+            path: None,
+            secure: false,
+            http_only: false,
+            same_site: None,
+            extensions: Vec::new(),
+        }
+    }
+
+    /// Set the `Expires` attribute.
+    pub fn expires<D: Into<HttpDate>>(mut self, date: D) -> ResponseCookie {
+        self.expires = Some(date.into());
+        self
+    }
+
+    /// Set the `Max-Age` attribute, to whole seconds.
+    pub fn max_age(mut self, max_age: Duration) -> ResponseCookie {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Set the `Domain` attribute.
+    pub fn domain<S: Into<String>>(mut self, domain: S) -> ResponseCookie {
+        self.domain = Some(domain.into());
+        self
+    }
+
+    /// Set the `Path` attribute.
+    pub fn path<S: Into<String>>(mut self, path: S) -> ResponseCookie {
+        self.path = Some(path.into());
+        self
+    }
+
+    /// Set whether the `Secure` attribute is given.
+    pub fn secure(mut self, secure: bool) -> ResponseCookie {
+        self.secure = secure;
+        self
+    }
+
+    /// Set whether the `HttpOnly` attribute is given.
+    pub fn http_only(mut self, http_only: bool) -> ResponseCookie {
+        self.http_only = http_only;
+        self
+    }
+
+    /// Set the `SameSite` attribute.
+    pub fn same_site(mut self, same_site: SameSite) -> ResponseCookie {
+        self.same_site = Some(same_site);
+        self
+    }
+
+    /// Append another attribute, such as `Partitioned`.
+    pub fn extension<S: Into<String>>(mut self, attribute: S) -> ResponseCookie {
+        self.extensions.push(attribute.into());
+        self
+    }
+
+    // Parse a set-cookie-string. Leniently, as per RFC6265, section 5.2,
+    // invalid attributes are ignored, with the last valid one applying.
+    fn parse(s: &str, strict: bool) -> ::Result<ResponseCookie> {
+        let syntax = || ::Error::header(::HeaderErrorKind::Syntax);
+        let mut parts = s.split(';');
+        let (name, value) = parts
+            .next()
+            .and_then(|pair| pair.split_once('='))
+            .ok_or_else(syntax)?;
+        let (name, value) = (name.trim(), value.trim());
+        if name.is_empty() || (strict && !(is_token(name) && is_cookie_value(value))) {
+            return Err(syntax());
+        }
+        let mut cookie = ResponseCookie::new(name, value);
+        for attr in parts {
+            let (key, val) = match attr.split_once('=') {
+                Some((k, v)) => (k.trim(), Some(v.trim())),
+                None => (attr.trim(), None),
+            };
+            let valid = if key.eq_ignore_ascii_case("expires") {
+                let date = val.and_then(|v| parse_cookie_date(v, strict));
+                cookie.expires = date.or(cookie.expires);
+                date.is_some()
+            } else if key.eq_ignore_ascii_case("max-age") {
+                let max_age = val.and_then(|v| parse_max_age(v, strict));
+                cookie.max_age = max_age.or(cookie.max_age);
+                max_age.is_some()
+            } else if key.eq_ignore_ascii_case("domain") {
+                match val {
+                    Some(v) if strict && (v.starts_with('.') || !is_av_value(v)) => false,
+                    Some(v) if !v.trim_start_matches('.').is_empty() => {
+                        cookie.domain = Some(v.trim_start_matches('.').to_owned());
+                        true
+                    }
+                    _ => false,
+                }
+            } else if key.eq_ignore_ascii_case("path") {
+                match val {
+                    Some(v) if v.starts_with('/') && (!strict || is_av_value(v)) => {
+                        cookie.path = Some(v.to_owned());
+                        true
+                    }
+                    _ => {
+                        cookie.path = None;
+                        false
+                    }
+                }
+            } else if key.eq_ignore_ascii_case("secure") {
+                cookie.secure = true;
+                val.is_none()
+            } else if key.eq_ignore_ascii_case("httponly") {
+                cookie.http_only = true;
+                val.is_none()
+            } else if key.eq_ignore_ascii_case("samesite") {
+                let same_site = val.and_then(|v| v.parse().ok());
+                cookie.same_site = same_site.or(cookie.same_site);
+                same_site.is_some()
+            } else if key.is_empty() {
+                false
+            } else {
+                let attr = attr.trim();
+                cookie.extensions.push(attr.to_owned());
+                is_av_value(attr)
+            };
+            if strict && !valid {
+                return Err(syntax());
+            }
+        }
+        Ok(cookie)
+    }
+}
+
+impl fmt::Display for ResponseCookie {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}={}", self.name, self.value)?;
+        if let Some(expires) = self.expires {
+            write!(f, "; Expires={}", expires)?;
+        }
+        if let Some(max_age) = self.max_age {
+            write!(f, "; Max-Age={}", max_age.as_secs())?;
+        }
+        if let Some(ref domain) = self.domain {
+            write!(f, "; Domain={}", domain)?;
+        }
+        if let Some(ref path) = self.path {
+            write!(f, "; Path={}", path)?;
+        }
+        if self.secure {
+            f.write_str("; Secure")?;
+        }
+        if self.http_only {
+            f.write_str("; HttpOnly")?;
+        }
+        if let Some(same_site) = self.same_site {
+            write!(f, "; SameSite={}", same_site)?;
+        }
+        for attr in &self.extensions {
+            write!(f, "; {}", attr)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ResponseCookie {
+    type Err = ::Error;
+
+    /// Parse a single set-cookie-string, strictly if so configured on the
+    /// current thread.
+    fn from_str(s: &str) -> ::Result<ResponseCookie> {
+        ResponseCookie::parse(s, is_strict())
+    }
+}
+
+/// The `SameSite` attribute of a `ResponseCookie`, defined in
+/// [RFC6265bis](https://datatracker.ietf.org/doc/html/draft-ietf-httpbis-rfc6265bis#section-4.1.2.7)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum SameSite {
+    /// `Strict`: only sent with same-site requests.
+    Strict,
+    /// `Lax`: also sent with cross-site top-level navigations.
+    Lax,
+    /// `None`: sent with all requests; requires `Secure`.
+    None,
+}
+
+impl fmt::Display for SameSite {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            SameSite::Strict => "Strict",
+            SameSite::Lax => "Lax",
+            SameSite::None => "None",
+        })
+    }
+}
+
+impl FromStr for SameSite {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<SameSite> {
+        ::header::parsing::find_ignore_case(
+            s,
+            &[
+                ("strict", SameSite::Strict),
+                ("lax", SameSite::Lax),
+                ("none", SameSite::None),
+            ],
+        )
+        .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+}
+
+// Leniently, also accept the common `Wed, 21-Oct-2015 07:28:00 GMT` format
+fn parse_cookie_date(s: &str, strict: bool) -> Option<HttpDate> {
+    match s.parse::<HttpDate>() {
+        Ok(date) => Some(date),
+        Err(_) if strict => None,
+        Err(_) => s.replace('-', " ").parse().ok(),
+    }
+}
+
+// Seconds; leniently, a negative number is zero, to expire immediately.
+fn parse_max_age(s: &str, strict: bool) -> Option<Duration> {
+    let digits = if strict {
+        s
+    } else {
+        s.strip_prefix('-').unwrap_or(s)
+    };
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    if digits.len() < s.len() {
+        return Some(Duration::from_secs(0));
+    }
+    // Saturate, as values beyond a representable date are not meaningful
+    Some(Duration::from_secs(s.parse().unwrap_or(u64::MAX)))
+}
+
+fn is_cookie_value(s: &str) -> bool {
+    let s = match s.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        Some(quoted) => quoted,
+        None => s,
+    };
+    s.bytes().all(|b| {
+        b == 0x21
+            || (0x23..=0x2B).contains(&b)
+            || (0x2D..=0x3A).contains(&b)
+            || (0x3C..=0x5B).contains(&b)
+            || (0x5D..=0x7E).contains(&b)
+    })
+}
+
+// Any CHAR except CTLs (or ";", already split on).
+fn is_av_value(s: &str) -> bool {
+    s.bytes().all(|b| (0x20..0x7F).contains(&b))
+}
+
 #[cfg(feature = "headers")]
 #[test]
 fn test_set_cookie_fmt() {
     use header::Headers;
     let mut headers = Headers::new();
-    headers.set(SetCookie(vec!["foo=bar".into(), "baz=quux".into()]));
+    headers.set(SetCookie(vec![
+        ResponseCookie::new("foo", "bar"),
+        ResponseCookie::new("baz", "quux"),
+    ]));
     assert_eq!(
         headers.to_string(),
         "Set-Cookie: foo=bar\r\nSet-Cookie: baz=quux\r\n"
@@ -126,3 +460,111 @@ fn test_set_cookie_fmt() {
 }
 
 standard_header!(SetCookie, SET_COOKIE);
+
+__hyper__try_from!(SetCookie);
+__hyper__from_str!(SetCookie);
+__hyper__serde!(SetCookie);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::parsing::ParseConfig;
+    use header::Raw;
+
+    #[test]
+    fn test_parse() {
+        let c: ResponseCookie = "lang=en-US; Expires=Wed, 21 Oct 2099 07:28:00 GMT; \
+                                 Max-Age=60; Domain=.example.com; Path=/docs; secure; \
+                                 HTTPONLY; SameSite=strict; Partitioned; Priority=High"
+            .parse()
+            .unwrap();
+        assert_eq!((c.name.as_str(), c.value.as_str()), ("lang", "en-US"));
+        assert_eq!(
+            c.expires,
+            Some("Wed, 21 Oct 2099 07:28:00 GMT".parse().unwrap())
+        );
+        assert_eq!(c.max_age, Some(Duration::from_secs(60)));
+        assert_eq!(c.domain.as_ref().unwrap(), "example.com");
+        assert_eq!(c.path.as_ref().unwrap(), "/docs");
+        assert!(c.secure && c.http_only);
+        assert_eq!(c.same_site, Some(SameSite::Strict));
+        assert_eq!(c.extensions, ["Partitioned", "Priority=High"]);
+        assert_eq!(
+            c.to_string(),
+            "lang=en-US; Expires=Wed, 21 Oct 2099 07:28:00 GMT; Max-Age=60; \
+             Domain=example.com; Path=/docs; Secure; HttpOnly; SameSite=Strict; \
+             Partitioned; Priority=High"
+        );
+
+        assert!("=nameless".parse::<ResponseCookie>().is_err());
+        assert!("novalue".parse::<ResponseCookie>().is_err());
+    }
+
+    #[test]
+    fn test_lenient() {
+        let c: ResponseCookie = "a=\"b c\"; Expires=Wed, 21-Oct-2099 07:28:00 GMT; \
+                                 Max-Age=soon; Max-Age=-1; Expires=never; Path=rel; \
+                                 SameSite=Sometimes; Domain=; ;"
+            .parse()
+            .unwrap();
+        assert_eq!(c.value, "\"b c\"");
+        assert_eq!(
+            c.expires,
+            Some("Wed, 21 Oct 2099 07:28:00 GMT".parse().unwrap())
+        );
+        assert_eq!(c.max_age, Some(Duration::from_secs(0)));
+        assert_eq!(c.path, None);
+        assert_eq!(c.same_site, None);
+        assert_eq!(c.domain, None);
+        assert!(c.extensions.is_empty());
+    }
+
+    #[test]
+    fn test_strict() {
+        let strict = ParseConfig::new().strict(true);
+        let raw = Raw::from(vec![
+            b"a=1; Path=/; Secure".to_vec(),
+            b"b=2; Max-Age=soon".to_vec(),
+        ]);
+        let lenient = SetCookie::parse_header(&raw).unwrap();
+        assert_eq!(lenient.len(), 2);
+        assert_eq!(lenient[1].max_age, None);
+        assert!(strict.parse::<SetCookie, _>(&raw).is_err());
+
+        for line in &[
+            "a=\"b c\"",
+            "a b=c",
+            "a=1; Expires=Wed, 21-Oct-2099 07:28:00 GMT",
+            "a=1; Max-Age=-1",
+            "a=1; Domain=.example.com",
+            "a=1; Path=rel",
+            "a=1; Secure=yes",
+            "a=1; SameSite=Sometimes",
+            "a=1;",
+        ] {
+            assert!(
+                strict.scope(|| line.parse::<ResponseCookie>()).is_err(),
+                "{}",
+                line
+            );
+            assert!(line.parse::<ResponseCookie>().is_ok(), "{}", line);
+        }
+        assert!(strict
+            .scope(|| "a=\"b\"; Max-Age=0; SameSite=None".parse::<ResponseCookie>())
+            .is_ok());
+    }
+
+    #[test]
+    fn test_skip_invalid() {
+        let raw = Raw::from(vec![
+            b"=nameless".to_vec(),
+            b"a=1".to_vec(),
+            b"\xff".to_vec(),
+        ]);
+        let set_cookie = SetCookie::parse_header(&raw).unwrap();
+        assert_eq!(set_cookie.0, [ResponseCookie::new("a", "1")]);
+
+        let raw = Raw::from("novalue");
+        assert!(SetCookie::parse_header(&raw).is_err());
+    }
+}
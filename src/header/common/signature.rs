@@ -0,0 +1,150 @@
+use std::fmt;
+
+use header::sfv::{insert_member, BareItem, Dictionary, Item, ListEntry};
+use header::{Header, RawLike};
+
+/// `Signature` header, defined in
+/// [RFC9421](https://www.rfc-editor.org/rfc/rfc9421#section-4.2)
+///
+/// The `Signature` header conveys, for each signature of a message by
+/// label, the signature bytes. The covered components and parameters are
+/// in the `Signature-Input` header under the same label.
+///
+/// # ABNF
+///
+/// ```text
+/// Signature = sf-dictionary
+/// ```
+///
+/// # Example values
+/// * `sig1=:P0wLUszWQjoi54udOtydf9IWTfNhy+r53jGFj9XZuP4uKwxyJo1RSHi+oEF1FuX6O29d+lbxwwBao1BAgadijW+7O/PyezlTnqAOVPWx9GlyntiCiHzC87qmSQjvu1CFyFuWSjdGa3qLYYlNm7pVaJFalQiKWnUaqfT4LyttaXyoyZW84jS8gyarxAiWI97mPXU+OVM64+HVBHmnEsS+lTeIsEQo36T3NFf2CujWARPQg53r58RmpZ+J9eKR2CD6IJQvacn5A4Ix5BUAVGqlyp8JYm+S/CWJi31PNUjRRCusCVRj05NrxABNFv3r5S9IXf2fYJK+eyW4AiGVMvMcOg==:`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// # fn main() {
+/// use hyperx::header::{Signature, TypedHeaders};
+///
+/// let mut sig = Signature::new();
+/// sig.insert("sig1", b"\x01\x02\x03".to_vec()).unwrap();
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&sig);
+/// assert_eq!(headers["signature"], "sig1=:AQID:");
+///
+/// let sig = headers.decode::<Signature>().unwrap();
+/// assert_eq!(sig.get("sig1"), Some(&b"\x01\x02\x03"[..]));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Signature(pub Vec<(String, Vec<u8>)>);
+
+impl Signature {
+    /// Create an empty header, without signatures.
+    pub fn new() -> Signature {
+        Signature::default()
+    }
+
+    /// The signature bytes with the given label.
+    pub fn get(&self, label: &str) -> Option<&[u8]> {
+        self.0.iter().find(|m| m.0 == label).map(|m| m.1.as_slice())
+    }
+
+    /// Set the bytes of a signature, replacing any with the same label in
+    /// its place.
+    ///
+    /// Fails with `HeaderErrorKind::Token` if the label is not a valid
+    /// structured field key, such as `Sig1`.
+    pub fn insert<L: Into<String>>(&mut self, label: L, signature: Vec<u8>) -> ::Result<()> {
+        insert_member(&mut self.0, label.into(), signature)
+    }
+}
+
+impl Header for Signature {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Signature";
+        NAME
+    }
+
+    __hyper__http_name!("Signature");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<Signature>
+    where
+        T: RawLike<'a>,
+    {
+        parse_byte_sequences(raw).map(Signature)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for Signature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        fmt_byte_sequences(f, &self.0)
+    }
+}
+
+// Parse a non-empty dictionary with byte sequence values, as of the
+// `Signature` and `Content-Digest` headers.
+pub(super) fn parse_byte_sequences<'a, T>(raw: &'a T) -> ::Result<Vec<(String, Vec<u8>)>>
+where
+    T: RawLike<'a>,
+{
+    let dict = Dictionary::from_raw(raw)?;
+    if dict.is_empty() {
+        return Err(::Error::header(::HeaderErrorKind::Empty));
+    }
+    dict.iter()
+        .map(|(key, member)| match *member {
+            ListEntry::Item(Item {
+                bare_item: BareItem::ByteSequence(ref bytes),
+                ..
+            }) => Ok((key.to_owned(), bytes.clone())),
+            _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
+        })
+        .collect()
+}
+
+pub(super) fn fmt_byte_sequences(
+    f: &mut fmt::Formatter,
+    members: &[(String, Vec<u8>)],
+) -> fmt::Result {
+    let mut dict = Dictionary::new();
+    for (key, bytes) in members {
+        dict.insert(key.as_str(), Item::new(bytes.clone()))
+            .map_err(|_| fmt::Error)?;
+    }
+    fmt::Display::fmt(&dict, f)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+
+    #[test]
+    fn test_parse() {
+        let raw = Raw::from(vec![
+            b"sig1=:AAAA:, sig2=:AQID:".to_vec(),
+            b"sig1=:/w==:".to_vec(),
+        ]);
+        let sig = Signature::parse_header(&raw).unwrap();
+        assert_eq!(sig.get("sig1"), Some(&[0xff][..]));
+        assert_eq!(sig.get("sig2"), Some(&[1, 2, 3][..]));
+        assert_eq!(sig.get("sig3"), None);
+        assert_eq!(sig.to_string(), "sig1=:/w==:, sig2=:AQID:");
+
+        for bad in &["", "sig1=AQID", "sig1=(:AQID:)", "sig1=:AQ!D:"] {
+            let raw = Raw::from(*bad);
+            assert!(Signature::parse_header(&raw).is_err(), "{}", bad);
+        }
+    }
+}
+
+__hyper__try_from!(Signature);
+__hyper__from_str!(Signature);
+__hyper__serde!(Signature);
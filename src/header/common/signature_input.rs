@@ -0,0 +1,180 @@
+use std::fmt;
+
+use header::sfv::{insert_member, Dictionary, ListEntry};
+use header::{Header, RawLike};
+use signature::SignatureParams;
+
+/// `Signature-Input` header, defined in
+/// [RFC9421](https://www.rfc-editor.org/rfc/rfc9421#section-4.1)
+///
+/// The `Signature-Input` header conveys, for each signature of a message
+/// by label, the covered components and the signature parameters, such as
+/// `created` or `keyid`. The signature itself is in the `Signature` header
+/// under the same label.
+///
+/// Each member is parsed as `signature::SignatureParams`, from which the
+/// signature base is constructed via `signature::Message`. Members with
+/// component or signature parameters unsupported by `SignatureParams` fail
+/// to parse.
+///
+/// # ABNF
+///
+/// ```text
+/// Signature-Input = sf-dictionary
+/// ```
+///
+/// # Example values
+/// * `sig1=("@method" "@authority" "content-type");created=1618884473;keyid="test-key"`
+///
+/// # Examples
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// # fn main() {
+/// use hyperx::header::{SignatureInput, TypedHeaders};
+/// use hyperx::signature::{Component, Param, SignatureParams};
+///
+/// let params = SignatureParams::new(vec![Component::Method, Component::Authority])
+///     .with(Param::Created(1618884473));
+/// let mut input = SignatureInput::new();
+/// input.insert("sig1", params).unwrap();
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&input);
+/// assert_eq!(
+///     headers["signature-input"],
+///     "sig1=(\"@method\" \"@authority\");created=1618884473"
+/// );
+///
+/// let input = headers.decode::<SignatureInput>().unwrap();
+/// let params = input.get("sig1").unwrap();
+/// assert_eq!(params.components()[1].name(), "@authority");
+/// assert_eq!(params.created(), Some(1618884473));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SignatureInput(pub Vec<(String, SignatureParams)>);
+
+impl SignatureInput {
+    /// Create an empty header, without signatures.
+    pub fn new() -> SignatureInput {
+        SignatureInput::default()
+    }
+
+    /// The parameters of the signature with the given label.
+    pub fn get(&self, label: &str) -> Option<&SignatureParams> {
+        self.0.iter().find(|m| m.0 == label).map(|m| &m.1)
+    }
+
+    /// Set the parameters of a signature, replacing any with the same label
+    /// in its place.
+    ///
+    /// Fails with `HeaderErrorKind::Token` if the label is not a valid
+    /// structured field key, such as `Sig1`.
+    pub fn insert<L: Into<String>>(&mut self, label: L, params: SignatureParams) -> ::Result<()> {
+        insert_member(&mut self.0, label.into(), params)
+    }
+}
+
+impl Header for SignatureInput {
+    fn header_name() -> &'static str {
+        static NAME: &str = "Signature-Input";
+        NAME
+    }
+
+    __hyper__http_name!("Signature-Input");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<SignatureInput>
+    where
+        T: RawLike<'a>,
+    {
+        let dict = Dictionary::from_raw(raw)?;
+        if dict.is_empty() {
+            return Err(::Error::header(::HeaderErrorKind::Empty));
+        }
+        dict.iter()
+            .map(|(label, member)| match *member {
+                ListEntry::InnerList(ref list) => {
+                    SignatureParams::from_inner_list(list).map(|p| (label.to_owned(), p))
+                }
+                ListEntry::Item(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
+            })
+            .collect::<::Result<_>>()
+            .map(SignatureInput)
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for SignatureInput {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (label, params)) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            write!(f, "{}={}", label, params)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+    use signature::{Component, Param};
+
+    #[test]
+    fn test_parse() {
+        let raw = Raw::from(vec![
+            b"sig1=(\"@method\" \"content-type\");created=1618884473;keyid=\"k1\"".to_vec(),
+            b"sig2=();tag=\"app\", sig1=(\"@path\")".to_vec(),
+        ]);
+        let input = SignatureInput::parse_header(&raw).unwrap();
+        assert_eq!(input.0.len(), 2);
+        assert_eq!(input.get("sig1").unwrap().components(), [Component::Path]);
+        let sig2 = input.get("sig2").unwrap();
+        assert!(sig2.components().is_empty());
+        assert_eq!(sig2.params(), [Param::Tag("app".to_owned())]);
+        assert_eq!(input.to_string(), "sig1=(\"@path\"), sig2=();tag=\"app\"");
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        for bad in &[
+            "",
+            "sig1=\"@method\"",
+            "sig1=(\"@unknown\")",
+            "sig1=(\"@method\";req)",
+            "sig1=(\"@method\");created=-1",
+            "sig1=(\"@method\");other=1",
+            "Sig1=(\"@method\")",
+        ] {
+            let raw = Raw::from(*bad);
+            assert!(SignatureInput::parse_header(&raw).is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_insert() {
+        let mut input = SignatureInput::new();
+        input
+            .insert("a", SignatureParams::new(vec![Component::Method]))
+            .unwrap();
+        input
+            .insert("b", SignatureParams::new(vec![Component::Status]))
+            .unwrap();
+        input
+            .insert("a", SignatureParams::new(vec![Component::Path]))
+            .unwrap();
+        assert!(input.insert("A", SignatureParams::new(vec![])).is_err());
+        assert_eq!(input.to_string(), "a=(\"@path\"), b=(\"@status\")");
+    }
+}
+
+__hyper__try_from!(SignatureInput);
+__hyper__from_str!(SignatureInput);
+__hyper__serde!(SignatureInput);
@@ -103,20 +103,20 @@ impl FromStr for StrictTransportSecurity {
                     }
                 }
             })
-            .fold(Ok((None, None)), |res, dir| match (res, dir) {
-                (Ok((None, sub)), Ok(Directive::MaxAge(age))) => Ok((Some(age), sub)),
-                (Ok((age, None)), Ok(Directive::IncludeSubdomains)) => Ok((age, Some(()))),
-                (Ok((Some(_), _)), Ok(Directive::MaxAge(_)))
-                | (Ok((_, Some(_))), Ok(Directive::IncludeSubdomains))
-                | (_, Err(_)) => Err(::Error::Header),
-                (res, _) => res,
+            .try_fold((None, None), |res, dir| match (res, dir) {
+                ((None, sub), Ok(Directive::MaxAge(age))) => Ok((Some(age), sub)),
+                ((age, None), Ok(Directive::IncludeSubdomains)) => Ok((age, Some(()))),
+                ((Some(_), _), Ok(Directive::MaxAge(_)))
+                | ((_, Some(_)), Ok(Directive::IncludeSubdomains))
+                | (_, Err(_)) => Err(::Error::header(::HeaderErrorKind::Syntax)),
+                (res, _) => Ok(res),
             })
             .and_then(|res| match res {
                 (Some(age), sub) => Ok(StrictTransportSecurity {
                     max_age: age,
                     include_subdomains: sub.is_some(),
                 }),
-                _ => Err(::Error::Header),
+                _ => Err(::Error::header(::HeaderErrorKind::Syntax)),
             })
     }
 }
@@ -127,6 +127,8 @@ impl Header for StrictTransportSecurity {
         NAME
     }
 
+    __hyper__http_name!("Strict-Transport-Security");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<StrictTransportSecurity>
     where
         T: RawLike<'a>,
@@ -239,3 +241,6 @@ bench_header!(bench, StrictTransportSecurity, {
 });
 
 standard_header!(StrictTransportSecurity, STRICT_TRANSPORT_SECURITY);
+
+__hyper__try_from!(StrictTransportSecurity);
+__hyper__serde!(StrictTransportSecurity);
@@ -32,7 +32,7 @@ header! {
     ///
     /// let mut headers = http::HeaderMap::new();
     /// headers.encode(
-    ///     &Te(vec![qitem(Encoding::Trailers)])
+    ///     &Te(vec![qitem(Encoding::Trailers)].into())
     /// );
     /// ```
     ///
@@ -46,7 +46,7 @@ header! {
     ///         qitem(Encoding::Trailers),
     ///         qitem(Encoding::Gzip),
     ///         qitem(Encoding::Deflate),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     ///
@@ -59,8 +59,8 @@ header! {
     ///     &Te(vec![
     ///         qitem(Encoding::Trailers),
     ///         QualityItem::new(Encoding::Gzip, q(600)),
-    ///         QualityItem::new(Encoding::EncodingExt("*".to_owned()), q(0)),
-    ///     ])
+    ///         QualityItem::new(Encoding::EncodingExt(Box::new("*".to_owned())), q(0)),
+    ///     ].into())
     /// );
     /// ```
     (Te, "TE") => (QualityItem<Encoding>)*
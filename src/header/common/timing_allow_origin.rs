@@ -0,0 +1,42 @@
+use header::Origin;
+
+header! {
+    /// `Timing-Allow-Origin` header, defined in
+    /// [Resource Timing](https://w3c.github.io/resource-timing/#sec-timing-allow-origin)
+    ///
+    /// The `Timing-Allow-Origin` response header lists the origins allowed
+    /// to see the detailed timing of the resource, including its
+    /// `Server-Timing` metrics, which are otherwise hidden from cross-origin
+    /// documents.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Timing-Allow-Origin = 1#( origin-or-null / wildcard )
+    /// ```
+    ///
+    /// # Example values
+    /// * `*`
+    /// * `https://app.example, https://admin.example`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{Origin, TimingAllowOrigin, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let tao = TimingAllowOrigin::Items(vec![Origin::new("https", "app.example", None)].into());
+    /// headers.encode(&tao);
+    /// assert_eq!(headers["timing-allow-origin"], "https://app.example");
+    /// # }
+    /// ```
+    (TimingAllowOrigin, "Timing-Allow-Origin") => {Any / (Origin)+}
+
+    test_timing_allow_origin {
+        test_header!(test_any, [b"*"]);
+        test_header!(test_origins, [b"https://app.example, http://localhost:8080"]);
+    }
+}
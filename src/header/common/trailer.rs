@@ -0,0 +1,46 @@
+header! {
+    /// `Trailer` header, defined in
+    /// [RFC9110](https://www.rfc-editor.org/rfc/rfc9110#section-6.6.2)
+    ///
+    /// The `Trailer` header field declares the field names a sender
+    /// anticipates sending in the trailer section of a message, e.g. of a
+    /// chunked body, so that a recipient can prepare for them before
+    /// processing the content. See the [`trailers`](../trailers/index.html)
+    /// module for the fields allowed in trailers.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Trailer = #field-name
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `grpc-status, grpc-message`
+    /// * `Server-Timing`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use hyperx::header::{Trailer, TypedHeaders};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// headers.encode(&Trailer(vec![http::header::HeaderName::from_static("grpc-status")].into()));
+    /// ```
+    (Trailer, "Trailer") => (::http::header::HeaderName)+
+
+    test_trailer {
+        test_header!(test1, [b"grpc-status, grpc-message"]);
+        #[test]
+        fn test_case() {
+            let r: Raw = "Server-Timing, ETag".into();
+            let trailer: Trailer = Header::parse_header(&r).unwrap();
+            assert_eq!(trailer.0[0], "server-timing");
+            assert_eq!(trailer.to_string(), "server-timing, etag");
+        }
+    }
+}
+
+standard_header!(Trailer, TRAILER);
@@ -37,9 +37,10 @@ header! {
     ///     &TransferEncoding(vec![
     ///         Encoding::Gzip,
     ///         Encoding::Chunked,
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
+    #[derive(Eq, Hash)]
     (TransferEncoding, "Transfer-Encoding") => (Encoding)+
 
     transfer_encoding {
@@ -47,14 +48,14 @@ header! {
             test1,
             vec![b"gzip, chunked"],
             Some(HeaderField(
-                vec![Encoding::Gzip, Encoding::Chunked]
+                smallvec![Encoding::Gzip, Encoding::Chunked]
                 )));
         // Issue: #683
         test_header!(
             test2,
             vec![b"chunked", b"chunked"],
             Some(HeaderField(
-                vec![Encoding::Chunked, Encoding::Chunked]
+                smallvec![Encoding::Chunked, Encoding::Chunked]
             )));
 
     }
@@ -63,7 +64,7 @@ header! {
 impl TransferEncoding {
     /// Constructor for the most common Transfer-Encoding, `chunked`.
     pub fn chunked() -> TransferEncoding {
-        TransferEncoding(vec![Encoding::Chunked])
+        TransferEncoding(smallvec![Encoding::Chunked])
     }
 }
 
@@ -36,7 +36,7 @@ header! {
     /// use hyperx::header::{Upgrade, Protocol, ProtocolName, TypedHeaders};
     ///
     /// let mut headers = http::HeaderMap::new();
-    /// headers.encode(&Upgrade(vec![Protocol::new(ProtocolName::WebSocket, None)]));
+    /// headers.encode(&Upgrade(vec![Protocol::new(ProtocolName::WebSocket, None)].into()));
     /// ```
     ///
     /// ```
@@ -51,7 +51,7 @@ header! {
     ///             Some("1.3".to_owned())),
     ///         Protocol::new(ProtocolName::Unregistered("IRC".to_owned()),
     ///             Some("6.9".to_owned())),
-    ///     ])
+    ///     ].into())
     /// );
     /// ```
     (Upgrade, "Upgrade") => (Protocol)+
@@ -61,7 +61,7 @@ header! {
         test_header!(
             test1,
             vec![b"HTTP/2.0, SHTTP/1.3, IRC/6.9, RTA/x11"],
-            Some(Upgrade(vec![
+            Some(Upgrade(smallvec![
                 Protocol::new(ProtocolName::Http, Some("2.0".to_owned())),
                 Protocol::new(ProtocolName::Unregistered("SHTTP".to_owned()),
                     Some("1.3".to_owned())),
@@ -71,12 +71,12 @@ header! {
         // Own tests
         test_header!(
             test2, vec![b"websocket"],
-            Some(Upgrade(vec![Protocol::new(ProtocolName::WebSocket, None)])));
+            Some(Upgrade(smallvec![Protocol::new(ProtocolName::WebSocket, None)])));
         #[test]
         fn test3() {
             let r: Raw = "WEbSOCKet".into();
             let x: ::Result<Upgrade> = Header::parse_header(&r);
-            assert_eq!(x.ok(), Some(Upgrade(vec![Protocol::new(ProtocolName::WebSocket, None)])));
+            assert_eq!(x.ok(), Some(Upgrade(smallvec![Protocol::new(ProtocolName::WebSocket, None)])));
         }
     }
 }
@@ -43,6 +43,62 @@ header! {
         test_header!(test1, [b"CERN-LineMode/2.15 libwww/2.17b3"]);
         // Own testcase
         test_header!(test2, vec![b"Bunnies"], Some(UserAgent::new("Bunnies")));
+
+        #[test]
+        fn test_parse_ref() {
+            use header::{HeaderRef, Raw};
+
+            let raw = Raw::from(" curl/8.5.0 ");
+            let ua = UserAgentRef::parse_header_ref(&raw).unwrap();
+            assert_eq!(ua.as_str(), "curl/8.5.0");
+            assert!(ua.starts_with("curl/"));
+            assert_eq!(ua.into_owned(), UserAgent::new("curl/8.5.0"));
+
+            let raw = Raw::from(vec![b"a".to_vec(), b"b".to_vec()]);
+            assert!(UserAgentRef::parse_header_ref(&raw).is_err());
+            let raw = Raw::from("");
+            assert!(UserAgentRef::parse_header_ref(&raw).is_err());
+            let raw = Raw::from("a\r\n b");
+            assert!(UserAgentRef::parse_header_ref(&raw).is_err());
+        }
+    }
+}
+
+/// The `User-Agent` header, borrowing its value from the raw header rather
+/// than copying it. See `HeaderRef`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UserAgentRef<'a>(&'a str);
+
+impl<'a> UserAgentRef<'a> {
+    /// The header value.
+    pub fn as_str(&self) -> &'a str {
+        self.0
+    }
+
+    /// Copy into an owned `UserAgent`.
+    pub fn into_owned(self) -> UserAgent {
+        UserAgent::new(self.0.to_owned())
+    }
+}
+
+impl<'a> ::std::ops::Deref for UserAgentRef<'a> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.0
+    }
+}
+
+impl<'a> ::header::HeaderRef<'a> for UserAgentRef<'a> {
+    fn header_name() -> &'static str {
+        <UserAgent as ::header::Header>::header_name()
+    }
+
+    fn parse_lines<I>(lines: I) -> ::Result<UserAgentRef<'a>>
+    where
+        I: IntoIterator<Item = &'a [u8]>,
+    {
+        ::header::parsing::from_one_raw_str_ref(lines).map(UserAgentRef)
     }
 }
 
@@ -47,10 +47,11 @@ header! {
     ///     &Vary::Items(vec![
     ///         Ascii::new("accept-encoding".to_owned()),
     ///         Ascii::new("accept-language".to_owned()),
-    ///     ])
+    ///     ].into())
     /// );
     /// # }
     /// ```
+    #[derive(Eq, Hash)]
     (Vary, "Vary") => {Any / (Ascii<String>)+}
 
     test_vary {
@@ -67,12 +68,24 @@ header! {
             vary = Header::parse_header(&r);
             assert_eq!(
                 vary.ok(),
-                Some(Vary::Items(vec![
+                Some(Vary::Items(smallvec![
                     "eTag".parse().unwrap(),
                     "cookIE".parse().unwrap(),
                     "AlLOw".parse().unwrap(),
                 ])));
         }
+
+        #[test]
+        fn test_hash() {
+            use std::collections::HashSet;
+
+            let mut set = HashSet::new();
+            set.insert("Accept-Encoding".parse::<Vary>().unwrap());
+            set.insert(Vary::Any);
+            assert!(set.contains(&"accept-encoding".parse().unwrap()));
+            assert!(set.contains(&"*".parse().unwrap()));
+            assert!(!set.contains(&"cookie".parse().unwrap()));
+        }
     }
 }
 
@@ -0,0 +1,80 @@
+use header::{Quality, QualityItem};
+
+header! {
+    /// `Want-Digest` header, defined in
+    /// [RFC3230](https://www.rfc-editor.org/rfc/rfc3230#section-4.3.1)
+    ///
+    /// The `Want-Digest` header lists the digest algorithms which the
+    /// sender would like in the `Digest` header of a response, with
+    /// optional quality values. A quality of zero excludes an algorithm. It
+    /// is obsoleted by `Want-Content-Digest` and `Want-Repr-Digest` of
+    /// RFC9530, but still used by older clients.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// Want-Digest = "Want-Digest" ":" #(digest-algorithm [ ";" "q" "=" qvalue])
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `SHA-256`
+    /// * `SHA-256;q=0.3, MD5;q=1, UNIXsum;q=0`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{Header, Raw, WantDigest};
+    ///
+    /// let raw = Raw::from("SHA-256;q=0.3, sha-512, MD5;q=0");
+    /// let want = WantDigest::parse_header(&raw).unwrap();
+    /// assert_eq!(want.preferred(&["MD5", "SHA-256"]), Some("SHA-256"));
+    /// assert_eq!(want.preferred(&["SHA-256", "SHA-512"]), Some("SHA-512"));
+    /// assert_eq!(want.preferred(&["MD5"]), None);
+    /// # }
+    /// ```
+    (WantDigest, "Want-Digest") => (QualityItem<String>)+
+
+    test_want_digest {
+        test_header!(test1, [b"SHA-256"]);
+        test_header!(test2, [b"SHA-256;q=0.3, MD5, UNIXsum;q=0"]);
+
+        #[test]
+        fn test_quality() {
+            let raw = Raw::from(vec![b"SHA-256;q=0.3".to_vec(), b"UNIXsum;q=0".to_vec()]);
+            let want = WantDigest::parse_header(&raw).unwrap();
+            assert_eq!(want.quality("sha-256"), Some(q(300)));
+            assert_eq!(want.quality("UNIXSUM"), Some(q(0)));
+            assert_eq!(want.quality("MD5"), None);
+            assert_eq!(want.preferred(&["UNIXsum"]), None);
+        }
+    }
+}
+
+impl WantDigest {
+    /// The quality of the given algorithm, ignoring ASCII case, or `None`
+    /// if not listed.
+    pub fn quality(&self, algorithm: &str) -> Option<Quality> {
+        self.iter()
+            .find(|q| q.item.eq_ignore_ascii_case(algorithm))
+            .map(|q| q.quality)
+    }
+
+    /// Return the supported algorithm of the highest non-zero quality,
+    /// with ties going to the earliest in `supported`.
+    pub fn preferred<'a>(&self, supported: &[&'a str]) -> Option<&'a str> {
+        let mut best: Option<(&'a str, u16)> = None;
+        for &algorithm in supported {
+            let q = match self.quality(algorithm) {
+                Some(q) => q.thousandths(),
+                None => continue,
+            };
+            if q > 0 && best.map_or(true, |b| q > b.1) {
+                best = Some((algorithm, q));
+            }
+        }
+        best.map(|b| b.0)
+    }
+}
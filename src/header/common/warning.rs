@@ -1,5 +1,6 @@
 use header::parsing::from_one_raw_str;
 use header::{Header, HttpDate, RawLike};
+use std::borrow::Cow;
 use std::fmt;
 use std::str::FromStr;
 
@@ -42,8 +43,8 @@ use std::str::FromStr;
 /// headers.encode(
 ///     &Warning {
 ///         code: 299,
-///         agent: "api.hyper.rs".to_owned(),
-///         text: "Deprecated".to_owned(),
+///         agent: "api.hyper.rs".into(),
+///         text: "Deprecated".into(),
 ///         date: None
 ///     }
 /// );
@@ -57,8 +58,8 @@ use std::str::FromStr;
 /// headers.encode(
 ///     &Warning {
 ///         code: 299,
-///         agent: "api.hyper.rs".to_owned(),
-///         text: "Deprecated".to_owned(),
+///         agent: "api.hyper.rs".into(),
+///         text: "Deprecated".into(),
 ///         date: "Tue, 15 Nov 1994 08:12:31 GMT".parse::<HttpDate>().ok()
 ///     }
 /// );
@@ -73,8 +74,8 @@ use std::str::FromStr;
 /// headers.encode(
 ///     &Warning {
 ///         code: 199,
-///         agent: "api.hyper.rs".to_owned(),
-///         text: "Deprecated".to_owned(),
+///         agent: "api.hyper.rs".into(),
+///         text: "Deprecated".into(),
 ///         date: Some(SystemTime::now().into())
 ///     }
 /// );
@@ -84,9 +85,9 @@ pub struct Warning {
     /// The 3 digit warn code.
     pub code: u16,
     /// The name or pseudonym of the server adding this header.
-    pub agent: String,
+    pub agent: Cow<'static, str>,
     /// The warning message describing the error.
-    pub text: String,
+    pub text: Cow<'static, str>,
     /// An optional warning date.
     pub date: Option<HttpDate>,
 }
@@ -97,6 +98,8 @@ impl Header for Warning {
         NAME
     }
 
+    __hyper__http_name!("Warning");
+
     fn parse_header<'a, T>(raw: &'a T) -> ::Result<Warning>
     where
         T: RawLike<'a>,
@@ -130,19 +133,19 @@ impl FromStr for Warning {
         let code = match warning_split.next() {
             Some(c) => match c.parse::<u16>() {
                 Ok(c) => c,
-                Err(..) => return Err(::Error::Header),
+                Err(..) => return Err(::Error::header(::HeaderErrorKind::Number)),
             },
-            None => return Err(::Error::Header),
+            None => return Err(::Error::header(::HeaderErrorKind::Empty)),
         };
         let agent = match warning_split.next() {
-            Some(a) => a.to_string(),
-            None => return Err(::Error::Header),
+            Some(a) => a.to_owned().into(),
+            None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         };
 
         let mut warning_split = s.split('"').skip(1);
         let text = match warning_split.next() {
-            Some(t) => t.to_string(),
-            None => return Err(::Error::Header),
+            Some(t) => t.to_owned().into(),
+            None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         };
         let date = match warning_split.nth(1) {
             Some(d) => d.parse::<HttpDate>().ok(),
@@ -172,8 +175,8 @@ mod tests {
             warning.ok(),
             Some(Warning {
                 code: 112,
-                agent: "-".to_owned(),
-                text: "network down".to_owned(),
+                agent: "-".into(),
+                text: "network down".into(),
                 date: "Sat, 25 Aug 2012 23:34:45 GMT".parse::<HttpDate>().ok()
             })
         );
@@ -187,8 +190,8 @@ mod tests {
             warning.ok(),
             Some(Warning {
                 code: 299,
-                agent: "api.hyper.rs:8080".to_owned(),
-                text: "Deprecated API : use newapi.hyper.rs instead.".to_owned(),
+                agent: "api.hyper.rs:8080".into(),
+                text: "Deprecated API : use newapi.hyper.rs instead.".into(),
                 date: None
             })
         );
@@ -203,8 +206,8 @@ mod tests {
             warning.ok(),
             Some(Warning {
                 code: 299,
-                agent: "api.hyper.rs:8080".to_owned(),
-                text: "Deprecated API : use newapi.hyper.rs instead.".to_owned(),
+                agent: "api.hyper.rs:8080".into(),
+                text: "Deprecated API : use newapi.hyper.rs instead.".into(),
                 date: "Tue, 15 Nov 1994 08:12:31 GMT".parse::<HttpDate>().ok()
             })
         );
@@ -212,3 +215,6 @@ mod tests {
 }
 
 standard_header!(Warning, WARNING);
+
+__hyper__try_from!(Warning);
+__hyper__serde!(Warning);
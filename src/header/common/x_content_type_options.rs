@@ -0,0 +1,81 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `X-Content-Type-Options` header, defined in
+/// [Fetch](https://fetch.spec.whatwg.org/#x-content-type-options-header)
+///
+/// The `X-Content-Type-Options` header indicates that the `Content-Type`
+/// of the response is to be followed, and not sniffed by the user agent.
+///
+/// # ABNF
+///
+/// ```text
+/// X-Content-Type-Options = "nosniff"
+/// ```
+///
+/// # Example values
+///
+/// * `nosniff`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{XContentTypeOptions, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&XContentTypeOptions::NoSniff);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum XContentTypeOptions {
+    /// `nosniff`
+    NoSniff,
+}
+
+impl Header for XContentTypeOptions {
+    fn header_name() -> &'static str {
+        static NAME: &str = "X-Content-Type-Options";
+        NAME
+    }
+
+    __hyper__http_name!("X-Content-Type-Options");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<XContentTypeOptions>
+    where
+        T: RawLike<'a>,
+    {
+        use self::XContentTypeOptions::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(value.trim(), &[("nosniff", NoSniff)])
+            .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for XContentTypeOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::XContentTypeOptions::*;
+        f.write_str(match *self {
+            NoSniff => "nosniff",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_x_content_type_options {
+    use super::XContentTypeOptions as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(test_nosniff, vec![b"nosniff"], Some(HeaderField::NoSniff));
+    test_header!(test_case, [b"NoSniff"]);
+    test_header!(test_other, vec![b"sniff"], None);
+}
+
+standard_header!(XContentTypeOptions, X_CONTENT_TYPE_OPTIONS);
+
+__hyper__try_from!(XContentTypeOptions);
+__hyper__from_str!(XContentTypeOptions);
+__hyper__serde!(XContentTypeOptions);
@@ -0,0 +1,88 @@
+use std::net::IpAddr;
+
+header! {
+    /// `X-Forwarded-For` header, a de-facto standard
+    ///
+    /// The `X-Forwarded-For` request header lists the IP addresses of the
+    /// client and each proxy a request was forwarded through, except the
+    /// last, which is the peer address of the connection. Each proxy appends
+    /// the address of its own peer.
+    ///
+    /// Items which are not IP addresses, such as `unknown` or an address
+    /// with a port, are skipped, or fail the header when parsed strictly.
+    /// The standard `Forwarded` header, and the `forwarded` module for the
+    /// trust of proxies, cover the same information.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// X-Forwarded-For = 1#( IPv4address / IPv6address )
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `203.0.113.195`
+    /// * `203.0.113.195, 2001:db8:85a3:8d3:1319:8a2e:370:7348, 198.51.100.178`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{TypedHeaders, XForwardedFor};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let mut xff: XForwardedFor = "203.0.113.195".parse().unwrap();
+    /// xff.append([198, 51, 100, 178].into());
+    /// headers.encode(&xff);
+    /// assert_eq!(headers["x-forwarded-for"], "203.0.113.195, 198.51.100.178");
+    /// assert_eq!(xff.client(), Some([203, 0, 113, 195].into()));
+    /// # }
+    /// ```
+    (XForwardedFor, "X-Forwarded-For") => (IpAddr)+
+
+    test_x_forwarded_for {
+        use std::net::IpAddr;
+        use header::parsing::ParseConfig;
+
+        test_header!(test1, [b"203.0.113.195"]);
+        test_header!(
+            test2,
+            [b"203.0.113.195, 2001:db8:85a3:8d3:1319:8a2e:370:7348, 198.51.100.178"]
+        );
+
+        #[test]
+        fn test_items() {
+            let raw = Raw::from(vec![b"192.0.2.1, unknown".to_vec(), b"::1".to_vec()]);
+            let xff = XForwardedFor::parse_header(&raw).unwrap();
+            let ips: Vec<IpAddr> = xff.iter().cloned().collect();
+            assert_eq!(ips, [IpAddr::from([192, 0, 2, 1]), "::1".parse().unwrap()]);
+            assert_eq!(xff.to_string(), "192.0.2.1, ::1");
+
+            let strict = ParseConfig::new().strict(true);
+            assert!(strict.parse::<XForwardedFor, _>(&raw).is_err());
+        }
+
+        #[test]
+        fn test_header_map() {
+            let xff: XForwardedFor = "192.0.2.1, ::1".parse().unwrap();
+            let mut hmap = ::http::HeaderMap::new();
+            hmap.encode(&xff);
+            assert_eq!(hmap["x-forwarded-for"], "192.0.2.1, ::1");
+            assert_eq!(hmap.decode::<XForwardedFor>().unwrap(), xff);
+        }
+    }
+}
+
+impl XForwardedFor {
+    /// The address of the originating client, the first item.
+    pub fn client(&self) -> Option<IpAddr> {
+        self.0.first().cloned()
+    }
+
+    /// Append the address of a peer, as a proxy forwarding its request.
+    pub fn append(&mut self, peer: IpAddr) {
+        self.0.push(peer);
+    }
+}
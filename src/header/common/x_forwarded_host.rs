@@ -0,0 +1,68 @@
+use header::Host;
+
+header! {
+    /// `X-Forwarded-Host` header, a de-facto standard
+    ///
+    /// The `X-Forwarded-Host` request header lists the original `Host`
+    /// requested by the client, as forwarded by a reverse proxy, and by any
+    /// further proxies. The first item is the host requested by the client.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// X-Forwarded-Host = 1#( uri-host [ ":" port ] )
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `example.com`
+    /// * `example.com:8443, internal.example.net`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{Host, TypedHeaders, XForwardedHost};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let mut xfh = XForwardedHost(Default::default());
+    /// xfh.append(Host::new("example.com", 8443));
+    /// headers.encode(&xfh);
+    /// assert_eq!(headers["x-forwarded-host"], "example.com:8443");
+    /// # }
+    /// ```
+    (XForwardedHost, "X-Forwarded-Host") => (Host)+
+
+    test_x_forwarded_host {
+        test_header!(test1, [b"example.com"]);
+        test_header!(test2, [b"example.com:8443, [::1]:8080"]);
+
+        #[test]
+        fn test_items() {
+            let raw = Raw::from(vec![b"example.com:8443".to_vec(), b"internal".to_vec()]);
+            let xfh = XForwardedHost::parse_header(&raw).unwrap();
+            assert_eq!(xfh.len(), 2);
+            assert_eq!(xfh[0].hostname(), "example.com");
+            assert_eq!(xfh[0].port(), Some(8443));
+            assert_eq!(xfh[1], Host::new("internal", None));
+        }
+
+        #[test]
+        fn test_header_map() {
+            let xfh = XForwardedHost(vec![Host::new("example.com", Some(8443))].into());
+            let mut hmap = ::http::HeaderMap::new();
+            hmap.encode(&xfh);
+            assert_eq!(hmap["x-forwarded-host"], "example.com:8443");
+            assert_eq!(hmap.decode::<XForwardedHost>().unwrap(), xfh);
+        }
+    }
+}
+
+impl XForwardedHost {
+    /// Append a host, as a proxy forwarding a request.
+    pub fn append(&mut self, host: Host) {
+        self.0.push(host);
+    }
+}
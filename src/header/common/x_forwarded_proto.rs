@@ -0,0 +1,65 @@
+header! {
+    /// `X-Forwarded-Proto` header, a de-facto standard
+    ///
+    /// The `X-Forwarded-Proto` request header lists the protocol, `http` or
+    /// `https`, of the request of the client to a reverse proxy, and of any
+    /// further proxies. The first item is the scheme used by the client.
+    ///
+    /// # ABNF
+    ///
+    /// ```text
+    /// X-Forwarded-Proto = 1#scheme
+    /// ```
+    ///
+    /// # Example values
+    ///
+    /// * `https`
+    /// * `https, http`
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// # fn main() {
+    /// use hyperx::header::{TypedHeaders, XForwardedProto};
+    ///
+    /// let mut headers = http::HeaderMap::new();
+    /// let mut xfp = XForwardedProto(Default::default());
+    /// xfp.append("https");
+    /// headers.encode(&xfp);
+    /// assert_eq!(headers["x-forwarded-proto"], "https");
+    /// # }
+    /// ```
+    (XForwardedProto, "X-Forwarded-Proto") => (String)+
+
+    test_x_forwarded_proto {
+        test_header!(test1, [b"https"]);
+        test_header!(test2, [b"https, http"]);
+
+        #[test]
+        fn test_items() {
+            let raw = Raw::from(vec![b"https".to_vec(), b"http".to_vec()]);
+            let xfp = XForwardedProto::parse_header(&raw).unwrap();
+            let protos: Vec<&str> = xfp.iter().map(String::as_str).collect();
+            assert_eq!(protos, ["https", "http"]);
+        }
+
+        #[test]
+        fn test_header_map() {
+            let mut xfp = XForwardedProto(vec!["https".to_owned()].into());
+            xfp.append("http");
+            let mut hmap = ::http::HeaderMap::new();
+            hmap.encode(&xfp);
+            assert_eq!(hmap["x-forwarded-proto"], "https, http");
+            assert_eq!(hmap.decode::<XForwardedProto>().unwrap(), xfp);
+        }
+    }
+}
+
+impl XForwardedProto {
+    /// Append a protocol, such as `https`, as a proxy forwarding a request.
+    pub fn append<S: Into<String>>(&mut self, proto: S) {
+        self.0.push(proto.into());
+    }
+}
@@ -0,0 +1,94 @@
+use header::{parsing, Header, RawLike};
+use std::fmt;
+
+/// `X-Frame-Options` header, defined in
+/// [RFC7034](https://tools.ietf.org/html/rfc7034)
+///
+/// The `X-Frame-Options` header indicates whether the response may be
+/// rendered in a frame. The obsolete `ALLOW-FROM` option is not supported;
+/// use the `frame-ancestors` directive of `ContentSecurityPolicy` instead.
+///
+/// # ABNF
+///
+/// ```text
+/// X-Frame-Options = "DENY" / "SAMEORIGIN"
+/// ```
+///
+/// # Example values
+///
+/// * `DENY`
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// use hyperx::header::{XFrameOptions, TypedHeaders};
+///
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&XFrameOptions::Deny);
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum XFrameOptions {
+    /// `DENY`
+    Deny,
+    /// `SAMEORIGIN`
+    SameOrigin,
+}
+
+impl Header for XFrameOptions {
+    fn header_name() -> &'static str {
+        static NAME: &str = "X-Frame-Options";
+        NAME
+    }
+
+    __hyper__http_name!("X-Frame-Options");
+
+    fn parse_header<'a, T>(raw: &'a T) -> ::Result<XFrameOptions>
+    where
+        T: RawLike<'a>,
+    {
+        use self::XFrameOptions::*;
+        let value: String = parsing::from_one_raw_str(raw)?;
+        parsing::find_ignore_case(value.trim(), &[("deny", Deny), ("sameorigin", SameOrigin)])
+            .ok_or_else(|| ::Error::header(::HeaderErrorKind::Token))
+    }
+
+    fn fmt_header(&self, f: &mut ::header::Formatter) -> fmt::Result {
+        f.fmt_line(self)
+    }
+}
+
+impl fmt::Display for XFrameOptions {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use self::XFrameOptions::*;
+        f.write_str(match *self {
+            Deny => "DENY",
+            SameOrigin => "SAMEORIGIN",
+        })
+    }
+}
+
+#[cfg(test)]
+mod test_x_frame_options {
+    use super::XFrameOptions as HeaderField;
+    use header::*;
+    use std::str;
+    test_header!(test_deny, vec![b"DENY"], Some(HeaderField::Deny));
+    test_header!(
+        test_same_origin,
+        vec![b"SAMEORIGIN"],
+        Some(HeaderField::SameOrigin)
+    );
+    test_header!(test_case, [b"sameorigin"]);
+    test_header!(
+        test_allow_from,
+        vec![b"ALLOW-FROM https://example.com/"],
+        None
+    );
+}
+
+standard_header!(XFrameOptions, X_FRAME_OPTIONS);
+
+__hyper__try_from!(XFrameOptions);
+__hyper__from_str!(XFrameOptions);
+__hyper__serde!(XFrameOptions);
@@ -1,9 +1,12 @@
 //! Implementation module for various compatibility features with the _http_
 //! crate.
 
-use super::{Header, RawLike};
+use super::internals::TypeMapCell;
+use super::{interned, Formatter, Header, Multi, RawLike};
 use http::header::{GetAll, HeaderMap, HeaderValue, ValueIter};
-use std::fmt::Display;
+use std::any::TypeId;
+use std::fmt;
+use std::slice;
 use Result;
 
 #[cfg(feature = "headers")]
@@ -17,6 +20,9 @@ use super::Headers;
 
 /// A trait for the "standard" headers that have an associated `HeaderName`
 /// constant in the _http_ crate.
+///
+/// Any `Header` may be decoded from or encoded into a `http::HeaderMap` via
+/// `TypedHeaders`; this trait only identifies those known to _http_.
 pub trait StandardHeader: Header + Sized {
     /// The `HeaderName` from the _http_ crate for this header.
     fn http_header_name() -> ::http::header::HeaderName;
@@ -34,7 +40,7 @@ pub trait TypedHeaders {
     /// distinguish the not found case, use `try_decode` instead.
     fn decode<H>(&self) -> Result<H>
     where
-        H: StandardHeader;
+        H: Header;
 
     /// Decode and return `Header` type H or `Error::Header` if found, or
     /// return `None` if not found.
@@ -45,48 +51,95 @@ pub trait TypedHeaders {
     /// `Error::Header`.
     fn try_decode<H>(&self) -> Option<Result<H>>
     where
-        H: StandardHeader;
+        H: Header;
 
     /// Encode and write the specified typed header value in the collection.
     ///
-    /// Uses the `Display` format of the provided header value to write a single
-    /// header. This will overwrite any preexisting values with the same
-    /// key (`HeaderName`). Use `encode_append` instead to avoid this.
+    /// Uses `Header::fmt_header` to write the header value, as one value per
+    /// formatted line (for example, one per cookie of `SetCookie`). This will
+    /// overwrite any preexisting values with the same key (`HeaderName`). Use
+    /// `encode_append` instead to avoid this.
+    ///
+    /// # Panics
+    ///
+    /// If a formatted line contains bytes not allowed in a header value. See
+    /// `Header::encode_to_value` for a fallible alternative.
     fn encode<H>(&mut self, value: &H)
     where
-        H: StandardHeader + Display;
+        H: Header;
 
     /// Encode and append the specified typed header value into the collection.
     ///
-    /// Uses the `Display` format of the provided header value to append a
-    /// single header. If the collection previously had a value for the same
-    /// key, the additional value is appended to the end.
+    /// Uses `Header::fmt_header` to append the header value, as one value per
+    /// formatted line. If the collection previously had a value for the same
+    /// key, the additional values are appended to the end.
+    ///
+    /// # Panics
+    ///
+    /// If a formatted line contains bytes not allowed in a header value. See
+    /// `Header::encode_to_value` for a fallible alternative.
     fn encode_append<H>(&mut self, value: &H)
     where
-        H: StandardHeader + Display;
+        H: Header;
+
+    /// Return `true` if the collection contains any value for `Header` type
+    /// H, without parsing it.
+    fn contains<H>(&self) -> bool
+    where
+        H: Header;
+
+    /// Remove all values for `Header` type H from the collection, returning
+    /// the header if any were found and could be parsed.
+    ///
+    /// Values which fail to parse are left in the collection, so that no
+    /// data is lost; use `try_decode` to find out why. Named `take` as
+    /// `http::HeaderMap` has an inherent `remove` method, which would shadow
+    /// this one.
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use hyperx::header::{ContentLength, TypedHeaders};
+    ///
+    /// # fn main() {
+    /// let mut hmap = http::HeaderMap::new();
+    /// hmap.encode(&ContentLength(11));
+    /// assert!(hmap.contains::<ContentLength>());
+    /// assert_eq!(hmap.take::<ContentLength>(), Some(ContentLength(11)));
+    /// assert!(!hmap.contains::<ContentLength>());
+    /// assert_eq!(hmap.take::<ContentLength>(), None);
+    /// # }
+    /// ```
+    fn take<H>(&mut self) -> Option<H>
+    where
+        H: Header;
 }
 
 /// Iterator adaptor for HeaderValue
 #[derive(Debug)]
 pub struct ValueMapIter<'a>(ValueIter<'a, HeaderValue>);
 
+/// Iterator adaptor for a slice of HeaderValue
+#[derive(Clone, Debug)]
+pub struct ValueSliceIter<'a>(slice::Iter<'a, HeaderValue>);
+
 impl TypedHeaders for HeaderMap {
     fn decode<H>(&self) -> Result<H>
     where
-        H: StandardHeader,
+        H: Header,
     {
-        let vals = self.get_all(H::http_header_name());
-        H::parse_header(&vals)
+        let vals = self.get_all(H::http_name());
+        interned::parse_header(&vals).map_err(|e| with_context::<H, _>(e, &vals))
     }
 
     fn try_decode<H>(&self) -> Option<Result<H>>
     where
-        H: StandardHeader,
+        H: Header,
     {
-        let hname = H::http_header_name();
+        let hname = H::http_name();
         if self.contains_key(&hname) {
             let vals = self.get_all(&hname);
-            Some(H::parse_header(&vals))
+            Some(interned::parse_header(&vals).map_err(|e| with_context::<H, _>(e, &vals)))
         } else {
             None
         }
@@ -94,22 +147,143 @@ impl TypedHeaders for HeaderMap {
 
     fn encode<H>(&mut self, val: &H)
     where
-        H: StandardHeader + Display,
+        H: Header,
     {
-        self.insert(
-            H::http_header_name(),
-            val.to_string().parse().expect("header value"),
-        );
+        self.remove(H::http_name());
+        self.encode_append(val);
     }
 
     fn encode_append<H>(&mut self, val: &H)
     where
-        H: StandardHeader + Display,
+        H: Header,
     {
-        self.append(
-            H::http_header_name(),
-            val.to_string().parse().expect("header value"),
-        );
+        let name = H::http_name();
+        val.fmt_header(&mut Formatter(Multi::Append(&name, self)))
+            .expect("header value");
+    }
+
+    fn contains<H>(&self) -> bool
+    where
+        H: Header,
+    {
+        self.contains_key(H::http_name())
+    }
+
+    fn take<H>(&mut self) -> Option<H>
+    where
+        H: Header,
+    {
+        let hname = H::http_name();
+        if !self.contains_key(&hname) {
+            return None;
+        }
+        let h = interned::parse_header(&self.get_all(&hname)).ok();
+        if h.is_some() {
+            self.remove(&hname);
+        }
+        h
+    }
+}
+
+/// A borrowed `http::HeaderMap` with a cache of the typed headers decoded
+/// from it.
+///
+/// Each header type is parsed at most once, on the first successful
+/// `decode` or `try_decode`, with later calls returning a reference to the
+/// cached value. This suits headers checked by several layers of a request
+/// handler, such as `ContentType`. As the map is borrowed, it can't change
+/// while cached. Failed parses are not cached, and are repeated on each call.
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::{CachedHeaders, ContentLength, TypedHeaders};
+///
+/// # fn main() {
+/// let mut hmap = http::HeaderMap::new();
+/// hmap.encode(&ContentLength(11));
+///
+/// let headers = CachedHeaders::new(&hmap);
+/// let first: &ContentLength = headers.decode().unwrap();
+/// let again: &ContentLength = headers.decode().unwrap();
+/// assert_eq!(*again, ContentLength(11));
+/// assert!(std::ptr::eq(first, again));
+/// # }
+/// ```
+pub struct CachedHeaders<'a> {
+    map: &'a HeaderMap,
+    typed: TypeMapCell<dyn Header + Send + Sync>,
+}
+
+impl<'a> CachedHeaders<'a> {
+    /// Create a cache, initially empty, of typed headers decoded from the
+    /// given map.
+    pub fn new(map: &'a HeaderMap) -> CachedHeaders<'a> {
+        CachedHeaders {
+            map,
+            typed: TypeMapCell::new(),
+        }
+    }
+
+    /// Returns the underlying map.
+    pub fn header_map(&self) -> &'a HeaderMap {
+        self.map
+    }
+
+    /// Decode, or return the cached value of, `Header` type H. See
+    /// `TypedHeaders::decode`.
+    pub fn decode<H>(&self) -> Result<&H>
+    where
+        H: Header,
+    {
+        match self.cached() {
+            Some(h) => Ok(h),
+            None => self.map.decode::<H>().map(|h| self.insert(h)),
+        }
+    }
+
+    /// Decode, or return the cached value of, `Header` type H if found, or
+    /// return `None` if not found. See `TypedHeaders::try_decode`.
+    pub fn try_decode<H>(&self) -> Option<Result<&H>>
+    where
+        H: Header,
+    {
+        // A multi-valued header decoded while absent is cached as empty
+        if !self.map.contains_key(H::http_name()) {
+            return None;
+        }
+        Some(self.decode())
+    }
+
+    fn cached<H: Header>(&self) -> Option<&H> {
+        self.typed
+            .get(TypeId::of::<H>())
+            .and_then(|h| h.as_any().downcast_ref())
+    }
+
+    fn insert<H: Header>(&self, h: H) -> &H {
+        self.typed
+            .insert(TypeId::of::<H>(), Box::new(h))
+            .as_any()
+            .downcast_ref()
+            .expect("cached header type")
+    }
+}
+
+impl<'a> fmt::Debug for CachedHeaders<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("CachedHeaders")
+            .field("map", self.map)
+            .finish()
+    }
+}
+
+// Record the header name, and single value if available, in a parse error.
+pub(super) fn with_context<'a, H: Header, R: RawLike<'a>>(e: ::Error, vals: &'a R) -> ::Error {
+    let e = e.with_header_name(H::header_name());
+    match vals.one() {
+        Some(value) => e.with_header_value(value),
+        None => e,
     }
 }
 
@@ -125,7 +299,7 @@ impl<'a> From<&'a http::HeaderMap> for Headers {
     fn from(header_map: &'a http::HeaderMap) -> Headers {
         let mut headers = Headers::new();
         for (name, value) in header_map.iter() {
-            headers.append_raw_str(name.as_str(), value.as_bytes());
+            headers.append_raw_str(name.as_str(), value);
         }
         headers
     }
@@ -139,34 +313,54 @@ impl From<Headers> for http::HeaderMap {
     }
 }
 
+/// Convert by reference, panicking if any header name or value is rejected by
+/// the _http_ crate. See `Headers::to_header_map` for a fallible alternative.
 #[cfg(feature = "headers")]
 impl<'a> From<&'a Headers> for http::HeaderMap {
     fn from(headers: &'a Headers) -> http::HeaderMap {
-        let mut hmap = http::HeaderMap::new();
-        for header in headers.iter() {
-            let name: http::header::HeaderName =
-                header.name().parse().expect("convert invalid header name");
-            let entry = hmap.entry(name);
-            let mut value_iter = header.raw().iter().map(|line| {
-                http::header::HeaderValue::from_bytes(line).expect("convert invalid header value")
-            });
-            match entry {
-                http::header::Entry::Occupied(mut occupied) => {
-                    for value in value_iter {
-                        occupied.append(value);
-                    }
-                }
-                http::header::Entry::Vacant(vacant) => {
-                    if let Some(first_value) = value_iter.next() {
-                        let mut occupied = vacant.insert_entry(first_value);
-                        for value in value_iter {
-                            occupied.append(value);
-                        }
-                    }
-                }
+        headers
+            .to_header_map()
+            .expect("convert invalid header name or value")
+    }
+}
+
+#[cfg(feature = "headers")]
+impl Headers {
+    /// Convert to an `http::HeaderMap`, or return `Error::Header` if any
+    /// header name or value is rejected by the _http_ crate.
+    ///
+    /// All values (lines) of each header are preserved, in order, as
+    /// separate `HeaderValue`s, including values that are not valid UTF-8.
+    /// Header names are inserted in the same order as they are iterated from
+    /// this `Headers`.  The _http_ crate rejects values containing control
+    /// characters such as CR, LF or NUL, as well as names which are not valid
+    /// tokens, which `set_raw` and `append_raw` otherwise allow.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use hyperx::header::Headers;
+    /// let mut headers = Headers::new();
+    /// headers.append_raw("x-foo", "one");
+    /// headers.append_raw("x-foo", vec![b't', 0xFF, b'o']);
+    /// let hmap = headers.to_header_map().unwrap();
+    /// assert_eq!(hmap.get_all("x-foo").iter().count(), 2);
+    ///
+    /// headers.set_raw("x-bad", "new\nline");
+    /// assert!(headers.to_header_map().is_err());
+    /// ```
+    pub fn to_header_map(&self) -> Result<http::HeaderMap> {
+        let mut hmap = http::HeaderMap::with_capacity(self.len());
+        for header in self.iter() {
+            let name = http::header::HeaderName::from_bytes(header.name().as_bytes())
+                .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Conversion, e))?;
+            for line in header.raw().iter() {
+                let value = http::header::HeaderValue::from_bytes(line)
+                    .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Conversion, e))?;
+                hmap.append(&name, value);
             }
         }
-        hmap
+        Ok(hmap)
     }
 }
 
@@ -198,6 +392,10 @@ impl<'a> RawLike<'a> for GetAll<'a, HeaderValue> {
     fn iter(&'a self) -> ValueMapIter<'a> {
         ValueMapIter(self.iter())
     }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
 }
 
 impl<'a> RawLike<'a> for &'a HeaderValue {
@@ -214,11 +412,82 @@ impl<'a> RawLike<'a> for &'a HeaderValue {
     fn iter(&'a self) -> Self::IntoIter {
         ::std::iter::once(self.as_bytes())
     }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
+}
+
+impl<'a> Iterator for ValueSliceIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(HeaderValue::as_bytes)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.0.size_hint()
+    }
+}
+
+/// Values already collected from a `HeaderMap`, for example via
+/// `get_all(name).iter().cloned().collect()`.
+///
+/// Note that `http::header::ValueIter` itself can not implement `RawLike`,
+/// as it is a single pass iterator. Use the `GetAll` it was obtained from
+/// instead.
+impl<'a> RawLike<'a> for &'a [HeaderValue] {
+    type IntoIter = ValueSliceIter<'a>;
+
+    fn len(&'a self) -> usize {
+        <[HeaderValue]>::len(self)
+    }
+
+    fn one(&'a self) -> Option<&'a [u8]> {
+        match **self {
+            [ref v] => Some(v.as_bytes()),
+            _ => None,
+        }
+    }
+
+    fn iter(&'a self) -> ValueSliceIter<'a> {
+        ValueSliceIter(<[HeaderValue]>::iter(self))
+    }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
+}
+
+impl<'a> RawLike<'a> for Vec<HeaderValue> {
+    type IntoIter = ValueSliceIter<'a>;
+
+    fn len(&'a self) -> usize {
+        Vec::len(self)
+    }
+
+    fn one(&'a self) -> Option<&'a [u8]> {
+        match **self {
+            [ref v] => Some(v.as_bytes()),
+            _ => None,
+        }
+    }
+
+    fn iter(&'a self) -> ValueSliceIter<'a> {
+        ValueSliceIter(<[HeaderValue]>::iter(self))
+    }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
 }
 
 #[cfg(test)]
 mod tests {
-    use header::{ContentEncoding, ContentLength, ETag, Encoding, Header, Te, TypedHeaders};
+    use header::{
+        CachedHeaders, ContentEncoding, ContentLength, ETag, Encoding, Header, ResponseCookie,
+        SetCookie, Te, TypedHeaders,
+    };
     use http;
 
     #[cfg(feature = "headers")]
@@ -237,6 +506,25 @@ mod tests {
         assert!(len.is_err());
     }
 
+    #[test]
+    fn test_cached_decode() {
+        let mut hmap = http::HeaderMap::new();
+        hmap.insert(http::header::CONTENT_LENGTH, "11".parse().unwrap());
+        hmap.insert(http::header::ETAG, "invalid".parse().unwrap());
+        let headers = CachedHeaders::new(&hmap);
+
+        let len = headers.decode::<ContentLength>().unwrap();
+        assert_eq!(*len, ContentLength(11));
+        let again = headers.try_decode::<ContentLength>().unwrap().unwrap();
+        assert!(::std::ptr::eq(len, again));
+
+        assert!(headers.decode::<ETag>().is_err());
+        assert!(headers.try_decode::<ETag>().unwrap().is_err());
+        assert!(headers.try_decode::<Te>().is_none());
+        assert_eq!(headers.decode::<Te>().unwrap(), &Te(smallvec![]));
+        assert!(headers.try_decode::<Te>().is_none());
+    }
+
     #[test]
     fn test_empty_decode_etag() {
         let hmap = http::HeaderMap::new();
@@ -248,14 +536,14 @@ mod tests {
     fn test_empty_decode_te() {
         let hmap = http::HeaderMap::new();
         let te = hmap.decode::<Te>().unwrap();
-        assert_eq!(te, Te(vec![]));
+        assert_eq!(te, Te(smallvec![]));
     }
 
     #[test]
     fn test_empty_decode_content_encoding() {
         let hmap = http::HeaderMap::new();
         let ce = hmap.decode::<ContentEncoding>().unwrap();
-        assert_eq!(ce, ContentEncoding(vec![]));
+        assert_eq!(ce, ContentEncoding(smallvec![]));
     }
 
     #[test]
@@ -291,34 +579,195 @@ mod tests {
     #[test]
     fn test_empty_encode() {
         let mut hmap = http::HeaderMap::new();
-        hmap.encode(&ContentEncoding(vec![]));
+        hmap.encode(&ContentEncoding(smallvec![]));
         assert_eq!(hmap.len(), 1);
         let ce: ContentEncoding = hmap.decode().unwrap();
-        assert_eq!(*ce, vec![]);
+        assert!(ce.is_empty());
     }
 
     #[test]
     fn test_empty_encode_2() {
         let mut hmap = http::HeaderMap::new();
-        hmap.encode(&ContentEncoding(vec![]));
-        hmap.encode_append(&ContentEncoding(vec![]));
+        hmap.encode(&ContentEncoding(smallvec![]));
+        hmap.encode_append(&ContentEncoding(smallvec![]));
         assert_eq!(hmap.len(), 2);
         let ce: ContentEncoding = hmap.decode().unwrap();
-        assert_eq!(*ce, vec![]);
+        assert!(ce.is_empty());
     }
 
     #[test]
     fn test_encode_append() {
         let mut hmap = http::HeaderMap::new();
-        hmap.encode_append(&ContentEncoding(vec![Encoding::Identity]));
-        hmap.encode_append(&ContentEncoding(vec![Encoding::Gzip, Encoding::Chunked]));
+        hmap.encode_append(&ContentEncoding(smallvec![Encoding::Identity]));
+        hmap.encode_append(&ContentEncoding(smallvec![
+            Encoding::Gzip,
+            Encoding::Chunked
+        ]));
         let ce: ContentEncoding = hmap.decode().unwrap();
         assert_eq!(
-            *ce,
-            vec![Encoding::Identity, Encoding::Gzip, Encoding::Chunked]
+            ce[..],
+            [Encoding::Identity, Encoding::Gzip, Encoding::Chunked]
+        );
+    }
+
+    #[test]
+    fn test_take() {
+        let mut hmap = http::HeaderMap::new();
+        assert!(!hmap.contains::<ContentEncoding>());
+        hmap.append("content-encoding", "gzip".parse().unwrap());
+        hmap.append("content-encoding", "chunked".parse().unwrap());
+        hmap.insert("content-length", "x".parse().unwrap());
+        assert!(hmap.contains::<ContentEncoding>());
+        assert!(hmap.contains::<ContentLength>());
+
+        let ce = hmap.take::<ContentEncoding>().unwrap();
+        assert_eq!(ce[..], [Encoding::Gzip, Encoding::Chunked]);
+        assert!(!hmap.contains::<ContentEncoding>());
+
+        // Left in place when invalid
+        assert_eq!(hmap.take::<ContentLength>(), None);
+        assert_eq!(hmap["content-length"], "x");
+        assert!(hmap.try_decode::<ContentLength>().unwrap().is_err());
+        assert_eq!(hmap.len(), 1);
+    }
+
+    #[test]
+    fn test_encode_append_lines() {
+        use header::Link;
+
+        let mut hmap = http::HeaderMap::new();
+        hmap.encode(&SetCookie(vec!["a=1".parse().unwrap()]));
+        hmap.encode_append(&SetCookie(vec!["b=2".parse().unwrap()]));
+        let values: Vec<_> = hmap.get_all("set-cookie").iter().collect();
+        assert_eq!(values, ["a=1", "b=2"]);
+        let cookies: SetCookie = hmap.decode().unwrap();
+        assert_eq!(cookies.len(), 2);
+
+        hmap.encode_append(&"</a>; rel=\"next\"".parse::<Link>().unwrap());
+        hmap.encode_append(&"</b>; rel=\"prev\"".parse::<Link>().unwrap());
+        assert_eq!(hmap.get_all("link").iter().count(), 2);
+        let link: Link = hmap.decode().unwrap();
+        assert_eq!(link.values().len(), 2);
+    }
+
+    #[test]
+    fn test_encode_lines() {
+        use header::Referer;
+
+        let mut hmap = http::HeaderMap::new();
+        hmap.encode(&SetCookie(vec![
+            "a=1".parse().unwrap(),
+            "b=2".parse().unwrap(),
+        ]));
+        let values: Vec<_> = hmap.get_all("set-cookie").iter().collect();
+        assert_eq!(values, ["a=1", "b=2"]);
+
+        hmap.encode(&Referer::new("/a\r\nb"));
+        assert_eq!(hmap["referer"], "/a  b");
+    }
+
+    #[test]
+    #[should_panic(expected = "header value")]
+    fn test_encode_invalid() {
+        use header::Referer;
+
+        http::HeaderMap::new().encode(&Referer::new("/a\u{0}b"));
+    }
+
+    #[test]
+    fn test_try_from_value() {
+        use header::{Authorization, Bearer, LastEventId};
+        use std::convert::TryFrom;
+
+        let value = http::HeaderValue::from_static("11");
+        assert_eq!(ContentLength::try_from(&value).unwrap(), ContentLength(11));
+        assert_eq!(*LastEventId::try_from(&value).unwrap(), "11");
+
+        let value = http::HeaderValue::from_static("eleven");
+        let e = ContentLength::try_from(&value).unwrap_err();
+        assert_eq!(e.kind(), ::ErrorKind::Invalid);
+
+        let value = http::HeaderValue::from_static("Bearer abc");
+        let auth = Authorization::<Bearer>::try_from(&value).unwrap();
+        assert_eq!(auth.token, "abc");
+    }
+
+    #[test]
+    fn test_from_str() {
+        use header::{Authorization, Basic, CacheControl, CacheDirective, IfMatch};
+
+        assert_eq!("11".parse::<ContentLength>().unwrap(), ContentLength(11));
+        assert!("eleven".parse::<ContentLength>().is_err());
+        assert_eq!(
+            "no-cache, max-age=60".parse::<CacheControl>().unwrap(),
+            CacheControl(vec![CacheDirective::NoCache, CacheDirective::MaxAge(60)])
+        );
+        assert_eq!("*".parse::<IfMatch>().unwrap(), IfMatch::Any);
+        let auth: Authorization<Basic> = "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==".parse().unwrap();
+        assert_eq!(auth.username, "Aladdin");
+    }
+
+    #[test]
+    fn test_value_try_from_header() {
+        use header::{Authorization, Basic};
+        use std::convert::TryFrom;
+
+        let value = http::HeaderValue::try_from(ContentLength(11)).unwrap();
+        assert_eq!(value, "11");
+        let value = http::HeaderValue::try_from(&Te(smallvec![])).unwrap();
+        assert_eq!(value, "");
+
+        let auth = Authorization(Basic {
+            username: "Aladdin".into(),
+            password: Some("open sesame".into()),
+        });
+        let value = http::HeaderValue::try_from(&auth).unwrap();
+        assert_eq!(value, "Basic QWxhZGRpbjpvcGVuIHNlc2FtZQ==");
+
+        assert!(
+            http::HeaderValue::try_from(SetCookie(vec![ResponseCookie::new("a", "\0")])).is_err()
         );
     }
 
+    #[test]
+    fn test_encode_to_value() {
+        use std::convert::TryFrom;
+
+        let value = SetCookie(vec!["a=1".parse().unwrap()])
+            .encode_to_value()
+            .unwrap();
+        assert_eq!(value, "a=1");
+
+        // Cookies can not be combined in one value
+        let cookies = SetCookie(vec!["a=1".parse().unwrap(), "b=2".parse().unwrap()]);
+        let e = cookies.encode_to_value().unwrap_err();
+        assert_eq!(e.kind(), ::ErrorKind::Conversion);
+        assert_eq!(e.header_error().unwrap().name(), Some("Set-Cookie"));
+        let mut buf = ::bytes::BytesMut::new();
+        assert!(cookies.write_value(&mut buf).is_err());
+        assert!(buf.is_empty());
+        assert!(http::HeaderValue::try_from(&cookies).is_err());
+
+        let e = SetCookie(vec!["a=\x7f".parse().unwrap()])
+            .encode_to_value()
+            .unwrap_err();
+        assert_eq!(e.kind(), ::ErrorKind::Conversion);
+    }
+
+    #[test]
+    fn test_encode_multi_line() {
+        let mut hmap = http::HeaderMap::new();
+        hmap.encode(&SetCookie(vec![
+            "a=1".parse().unwrap(),
+            "b=2".parse().unwrap(),
+        ]));
+        let values: Vec<_> = hmap.get_all(http::header::SET_COOKIE).iter().collect();
+        assert_eq!(values, ["a=1", "b=2"]);
+
+        hmap.encode(&SetCookie(vec!["c=3".parse().unwrap()]));
+        assert_eq!(hmap.get_all(http::header::SET_COOKIE).iter().count(), 1);
+    }
+
     #[cfg(feature = "headers")]
     fn raw_headers_sample() -> Headers {
         let mut heads = Headers::new();
@@ -394,6 +843,132 @@ mod tests {
         assert_eq!(hmap2.len(), 15);
     }
 
+    #[test]
+    #[cfg(feature = "headers")]
+    fn test_convert_lossless() {
+        let mut headers = Headers::new();
+        headers.append_raw("x-z", "1");
+        headers.append_raw("x-bin", vec![0xC3, 0x28]);
+        headers.append_raw("x-a", "2");
+        headers.append_raw("x-z", "3");
+
+        let hmap = headers.to_header_map().unwrap();
+        let names: Vec<&str> = hmap.keys().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["x-z", "x-bin", "x-a"]);
+        let zs: Vec<&[u8]> = hmap.get_all("x-z").iter().map(|v| v.as_bytes()).collect();
+        assert_eq!(zs, vec![b"1", b"3"]);
+        assert_eq!(hmap.get("x-bin").unwrap().as_bytes(), &[0xC3, 0x28]);
+
+        let headers2 = Headers::from(&hmap);
+        assert_eq!(headers, headers2);
+        let names: Vec<&str> = headers2.iter().map(|h| h.name()).collect();
+        assert_eq!(names, vec!["x-z", "x-bin", "x-a"]);
+    }
+
+    #[test]
+    #[cfg(feature = "headers")]
+    fn test_convert_invalid() {
+        let mut headers = Headers::new();
+        headers.set_raw("x-ok", "fine");
+        headers.set_raw("bad name", "fine");
+        assert!(headers.to_header_map().is_err());
+
+        let mut headers = Headers::new();
+        headers.set_raw("x-nul", vec![b'a', 0, b'b']);
+        let e = headers.to_header_map().unwrap_err();
+        assert_eq!(e.kind(), ::ErrorKind::Conversion);
+        assert!(::std::error::Error::source(&e).is_some());
+    }
+
+    #[test]
+    fn test_decode_error_context() {
+        use header::IfModifiedSince;
+        use HeaderErrorKind;
+
+        let mut hmap = http::HeaderMap::new();
+        hmap.insert(http::header::CONTENT_LENGTH, "12a".parse().unwrap());
+        hmap.insert(
+            http::header::IF_MODIFIED_SINCE,
+            "yesterday".parse().unwrap(),
+        );
+
+        let e = hmap.decode::<ContentLength>().unwrap_err();
+        let he = e.header_error().unwrap();
+        assert_eq!(he.kind(), HeaderErrorKind::Number);
+        assert_eq!(he.name(), Some("Content-Length"));
+        assert_eq!(he.value(), Some(&b"12a"[..]));
+
+        let e = hmap.try_decode::<IfModifiedSince>().unwrap().unwrap_err();
+        assert_eq!(
+            e.to_string(),
+            "invalid If-Modified-Since (invalid syntax): \"yesterday\""
+        );
+
+        let e = http::HeaderMap::new()
+            .decode::<ContentLength>()
+            .unwrap_err();
+        assert_eq!(e.header_error().unwrap().kind(), HeaderErrorKind::Empty);
+    }
+
+    #[test]
+    fn test_http_name() {
+        use header::{Host, StandardHeader, UserAgent};
+
+        assert_eq!(
+            ContentLength::http_name(),
+            ContentLength::http_header_name()
+        );
+        assert_eq!(UserAgent::http_name(), UserAgent::http_header_name());
+        assert_eq!(Host::http_name(), Host::http_header_name());
+        assert_eq!(::header::XForwardedFor::http_name(), "x-forwarded-for");
+        assert_eq!(::header::Signature::http_name(), "signature");
+    }
+
+    #[test]
+    fn test_non_standard_header() {
+        use header::{SecFetchSite, Signature};
+
+        let mut hmap = http::HeaderMap::new();
+        hmap.encode(&SecFetchSite::SameOrigin);
+        assert_eq!(hmap["sec-fetch-site"], "same-origin");
+        assert!(hmap.contains::<SecFetchSite>());
+        assert_eq!(
+            hmap.decode::<SecFetchSite>().unwrap(),
+            SecFetchSite::SameOrigin
+        );
+        assert!(hmap.try_decode::<Signature>().is_none());
+    }
+
+    #[test]
+    fn test_raw_shares_value() {
+        use bytes::Bytes;
+        use header::{Raw, RawLike};
+
+        let value = http::HeaderValue::from_static("chunked, gzip");
+        let raw = Raw::from(&value);
+        assert_eq!(raw, *"chunked, gzip");
+        assert_eq!(raw.one().unwrap().as_ptr(), value.as_bytes().as_ptr());
+
+        let bytes = Bytes::from_static(b"br");
+        let raw = Raw::from(bytes.clone());
+        assert_eq!(raw.one().unwrap().as_ptr(), bytes.as_ptr());
+        assert_eq!(format!("{:?}", raw), "Raw([b\"br\"])");
+    }
+
+    #[cfg(feature = "headers")]
+    #[test]
+    fn test_convert_shares_values() {
+        use header::RawLike;
+
+        let mut hmap = http::HeaderMap::new();
+        hmap.insert(http::header::HOST, "hyper.rs".parse().unwrap());
+        let headers = Headers::from(&hmap);
+        assert_eq!(
+            headers.get_raw("host").unwrap().one().unwrap().as_ptr(),
+            hmap[http::header::HOST].as_bytes().as_ptr()
+        );
+    }
+
     #[test]
     fn test_value_parse() {
         let mut hmap = http::HeaderMap::new();
@@ -403,7 +978,10 @@ mod tests {
         );
         let val = hmap.get(http::header::CONTENT_ENCODING).unwrap();
         let ce = ContentEncoding::parse_header(&val).unwrap();
-        assert_eq!(ce, ContentEncoding(vec![Encoding::Chunked, Encoding::Gzip]))
+        assert_eq!(
+            ce,
+            ContentEncoding(smallvec![Encoding::Chunked, Encoding::Gzip])
+        )
     }
 
     #[test]
@@ -419,10 +997,46 @@ mod tests {
         let ce = ContentEncoding::parse_header(&vals).unwrap();
         assert_eq!(
             ce,
-            ContentEncoding(vec![Encoding::Chunked, Encoding::Gzip, Encoding::Brotli])
+            ContentEncoding(smallvec![
+                Encoding::Chunked,
+                Encoding::Gzip,
+                Encoding::Brotli
+            ])
         )
     }
 
+    #[test]
+    fn test_collected_values_parse() {
+        let mut hmap = http::HeaderMap::new();
+        hmap.insert(
+            http::header::CONTENT_ENCODING,
+            "chunked, gzip".parse().unwrap(),
+        );
+        hmap.append(http::header::CONTENT_ENCODING, "br".parse().unwrap());
+
+        let vals: Vec<http::HeaderValue> = hmap
+            .get_all(http::header::CONTENT_ENCODING)
+            .iter()
+            .cloned()
+            .collect();
+        let expected = ContentEncoding(smallvec![
+            Encoding::Chunked,
+            Encoding::Gzip,
+            Encoding::Brotli
+        ]);
+        assert_eq!(ContentEncoding::parse_header(&vals).unwrap(), expected);
+        assert_eq!(ContentEncoding::parse_header(&&vals[..]).unwrap(), expected);
+
+        // Single valued headers require exactly one value
+        assert!(ContentLength::parse_header(&&vals[..1]).is_err());
+        assert!(ContentLength::parse_header(&Vec::new()).is_err());
+        let vals = vec![http::HeaderValue::from_static("12")];
+        assert_eq!(
+            ContentLength::parse_header(&vals).unwrap(),
+            ContentLength(12)
+        );
+    }
+
     #[cfg(feature = "nightly")]
     #[bench]
     fn bench_0_value_parse(b: &mut Bencher) {
@@ -536,8 +1150,11 @@ mod tests {
     fn bench_4_encode_multi(b: &mut Bencher) {
         b.iter(|| {
             let mut hmap = http::HeaderMap::new();
-            hmap.encode(&ContentEncoding(vec![Encoding::Identity]));
-            hmap.encode_append(&ContentEncoding(vec![Encoding::Gzip, Encoding::Chunked]));
+            hmap.encode(&ContentEncoding(smallvec![Encoding::Identity]));
+            hmap.encode_append(&ContentEncoding(smallvec![
+                Encoding::Gzip,
+                Encoding::Chunked
+            ]));
             hmap.encode(&ContentLength(11));
             hmap.encode(&ETag(EntityTag::strong("pMMV3zmCrXr-n4ZZLR9".to_owned())));
             assert_eq!(hmap.len(), 4);
@@ -0,0 +1,145 @@
+//! Implementation module for conversions with the _cookie_ crate.
+//!
+//! The request `Cookie` header converts to and from the name/value pairs of
+//! `cookie::Cookie` values. The `Set-Cookie` header converts to and from
+//! complete `cookie::Cookie` values, with all attributes, such as `Path`,
+//! `Expires` or `SameSite`, formatted and parsed by the _cookie_ crate.
+
+use std::convert::TryFrom;
+use std::iter::FromIterator;
+use std::str::FromStr;
+
+use cookie;
+
+use super::{Cookie, ResponseCookie, SetCookie};
+
+/// Append the name and value of each cookie. Attributes are ignored, as
+/// they are not sent in a request.
+impl<'c> FromIterator<cookie::Cookie<'c>> for Cookie {
+    fn from_iter<I>(iter: I) -> Cookie
+    where
+        I: IntoIterator<Item = cookie::Cookie<'c>>,
+    {
+        let mut header = Cookie::new();
+        for c in iter {
+            let (name, value) = c.name_value();
+            header.append(name.to_owned(), value.to_owned());
+        }
+        header
+    }
+}
+
+impl<'c> From<cookie::Cookie<'c>> for Cookie {
+    fn from(c: cookie::Cookie<'c>) -> Cookie {
+        Some(c).into_iter().collect()
+    }
+}
+
+/// The name/value pairs, in order, as cookies without attributes.
+impl<'a> From<&'a Cookie> for Vec<cookie::Cookie<'a>> {
+    fn from(header: &'a Cookie) -> Vec<cookie::Cookie<'a>> {
+        header
+            .iter()
+            .map(|(name, value)| cookie::Cookie::new(name, value))
+            .collect()
+    }
+}
+
+/// Convert each cookie, with its attributes. Cookies without a name are
+/// skipped.
+impl<'c> FromIterator<cookie::Cookie<'c>> for SetCookie {
+    fn from_iter<I>(iter: I) -> SetCookie
+    where
+        I: IntoIterator<Item = cookie::Cookie<'c>>,
+    {
+        SetCookie(
+            iter.into_iter()
+                .filter_map(|c| ResponseCookie::from_str(&c.to_string()).ok())
+                .collect(),
+        )
+    }
+}
+
+impl<'c> From<cookie::Cookie<'c>> for SetCookie {
+    fn from(c: cookie::Cookie<'c>) -> SetCookie {
+        Some(c).into_iter().collect()
+    }
+}
+
+/// Convert each cookie of the header, with its attributes.
+///
+/// Fails with `HeaderErrorKind::Syntax` if any is not a valid cookie.
+impl<'a> TryFrom<&'a SetCookie> for Vec<cookie::Cookie<'a>> {
+    type Error = ::Error;
+
+    fn try_from(header: &'a SetCookie) -> ::Result<Vec<cookie::Cookie<'a>>> {
+        header
+            .iter()
+            .map(|c| {
+                cookie::Cookie::parse(c.to_string())
+                    .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Syntax, e))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cookie::SameSite;
+    use header::{Header, Raw};
+
+    #[test]
+    fn test_cookie() {
+        let header: Cookie = vec![
+            cookie::Cookie::new("foo", "bar"),
+            cookie::Cookie::build(("baz", "quux")).path("/").into(),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(header.to_string(), "foo=bar; baz=quux");
+
+        let header = Cookie::parse_header(&Raw::from("a=1; b=2")).unwrap();
+        let cookies = Vec::<cookie::Cookie>::from(&header);
+        assert_eq!(cookies.len(), 2);
+        assert_eq!(cookies[1].name_value(), ("b", "2"));
+        assert_eq!(cookies[1].path(), None);
+
+        let header = Cookie::from(cookie::Cookie::new("one", "1"));
+        assert_eq!(header.get("one"), Some("1"));
+    }
+
+    #[test]
+    fn test_set_cookie() {
+        let c = cookie::Cookie::build(("lang", "en-US"))
+            .path("/")
+            .domain("example.com")
+            .secure(true)
+            .http_only(true)
+            .same_site(SameSite::Lax)
+            .max_age(cookie::time::Duration::hours(1));
+        let header = SetCookie::from(c.build());
+        let expected = "lang=en-US; Max-Age=3600; Domain=example.com; Path=/; Secure; \
+                        HttpOnly; SameSite=Lax";
+        assert_eq!(header[0].to_string(), expected);
+
+        let raw = Raw::from(vec![
+            b"SID=31d4d96e407aad42".to_vec(),
+            b"lang=; Expires=Sun, 06 Nov 1994 08:49:37 GMT; Path=/".to_vec(),
+        ]);
+        let header = SetCookie::parse_header(&raw).unwrap();
+        let cookies = Vec::<cookie::Cookie>::try_from(&header).unwrap();
+        assert_eq!(cookies[0].name_value(), ("SID", "31d4d96e407aad42"));
+        assert_eq!(cookies[1].path(), Some("/"));
+        let expires = cookies[1].expires_datetime().unwrap();
+        assert_eq!(expires.unix_timestamp(), 784_111_777);
+
+        let roundtrip: SetCookie = cookies.into_iter().collect();
+        let cookies = Vec::<cookie::Cookie>::try_from(&roundtrip).unwrap();
+        assert_eq!(cookies[1].expires_datetime(), Some(expires));
+
+        let header = SetCookie(vec![ResponseCookie::new("", "no-name")]);
+        let e = Vec::<cookie::Cookie>::try_from(&header).unwrap_err();
+        assert_eq!(e.header_error().unwrap().kind(), ::HeaderErrorKind::Syntax);
+    }
+}
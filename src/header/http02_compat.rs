@@ -0,0 +1,229 @@
+//! Implementation module for compatibility with the _http_ 0.2 crate, as
+//! used by hyper 0.14, alongside the _http_ 1.x crate.
+
+use http02::header::{GetAll, HeaderMap, HeaderName, HeaderValue, InvalidHeaderValue, ValueIter};
+
+use super::compat::with_context;
+use super::{interned, is_joinable, Header, Joined, Raw, RawLike, TypedHeaders};
+use Result;
+
+/// `TypedHeaders` for the `HeaderMap` of _http_ 0.2, as for that of _http_
+/// 1.x. Requires the `http02` feature.
+///
+/// ```
+/// # extern crate http02;
+/// # extern crate hyperx;
+/// use hyperx::header::{ContentLength, TypedHeaders};
+///
+/// # fn main() {
+/// let mut hmap = http02::HeaderMap::new();
+/// hmap.encode(&ContentLength(11));
+/// assert_eq!(hmap["content-length"], "11");
+/// assert_eq!(hmap.decode::<ContentLength>().unwrap(), ContentLength(11));
+/// # }
+/// ```
+impl TypedHeaders for HeaderMap {
+    fn decode<H>(&self) -> Result<H>
+    where
+        H: Header,
+    {
+        let vals = self.get_all(http_name::<H>());
+        interned::parse_header(&vals).map_err(|e| with_context::<H, _>(e, &vals))
+    }
+
+    fn try_decode<H>(&self) -> Option<Result<H>>
+    where
+        H: Header,
+    {
+        let hname = http_name::<H>();
+        if self.contains_key(&hname) {
+            let vals = self.get_all(&hname);
+            Some(interned::parse_header(&vals).map_err(|e| with_context::<H, _>(e, &vals)))
+        } else {
+            None
+        }
+    }
+
+    fn encode<H>(&mut self, val: &H)
+    where
+        H: Header,
+    {
+        self.remove(http_name::<H>());
+        self.encode_append(val);
+    }
+
+    fn encode_append<H>(&mut self, val: &H)
+    where
+        H: Header,
+    {
+        let name = http_name::<H>();
+        for line in &Raw::from_header(val) {
+            let value = HeaderValue::from_bytes(line).expect("header value");
+            self.append(&name, value);
+        }
+    }
+
+    fn contains<H>(&self) -> bool
+    where
+        H: Header,
+    {
+        self.contains_key(http_name::<H>())
+    }
+
+    fn take<H>(&mut self) -> Option<H>
+    where
+        H: Header,
+    {
+        let hname = http_name::<H>();
+        if !self.contains_key(&hname) {
+            return None;
+        }
+        let h = interned::parse_header(&self.get_all(&hname)).ok();
+        if h.is_some() {
+            self.remove(&hname);
+        }
+        h
+    }
+}
+
+// The _http_ 0.2 `HeaderName` of `H`, converted from `Header::http_name`.
+fn http_name<H: Header>() -> HeaderName {
+    HeaderName::from_bytes(H::http_name().as_str().as_bytes()).expect("valid header name")
+}
+
+/// Format a header as a single _http_ 0.2 value, as `__private::to_value`.
+pub fn to_value<H: Header + ?Sized>(
+    header: &H,
+) -> ::std::result::Result<HeaderValue, InvalidHeaderValue> {
+    use std::fmt::Write;
+    let mut value = String::new();
+    if !is_joinable(header) || write!(value, "{}", Joined(header)).is_err() {
+        // Fails as would any other invalid value
+        return HeaderValue::from_bytes(b"\n");
+    }
+    HeaderValue::from_str(&value)
+}
+
+/// Iterator adaptor for the `HeaderValue` of _http_ 0.2. Requires the
+/// `http02` feature.
+#[derive(Debug)]
+pub struct Http02ValueMapIter<'a>(ValueIter<'a, HeaderValue>);
+
+impl<'a> Iterator for Http02ValueMapIter<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.0.next().map(HeaderValue::as_bytes)
+    }
+}
+
+impl<'a> RawLike<'a> for GetAll<'a, HeaderValue> {
+    type IntoIter = Http02ValueMapIter<'a>;
+
+    fn len(&'a self) -> usize {
+        self.iter().count()
+    }
+
+    fn one(&'a self) -> Option<&'a [u8]> {
+        let mut iter = self.iter();
+        if let Some(v) = iter.next() {
+            if iter.next().is_none() {
+                return Some(v.as_bytes());
+            }
+        }
+        None
+    }
+
+    fn iter(&'a self) -> Http02ValueMapIter<'a> {
+        Http02ValueMapIter(self.iter())
+    }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
+}
+
+impl<'a> RawLike<'a> for &'a HeaderValue {
+    type IntoIter = ::std::iter::Once<&'a [u8]>;
+
+    fn len(&'a self) -> usize {
+        1
+    }
+
+    fn one(&'a self) -> Option<&'a [u8]> {
+        Some(self.as_bytes())
+    }
+
+    fn iter(&'a self) -> Self::IntoIter {
+        ::std::iter::once(self.as_bytes())
+    }
+
+    fn is_validated(&'a self) -> bool {
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::TryFrom;
+
+    use http02::header::{HeaderMap, HeaderValue};
+
+    use header::{
+        ContentEncoding, ContentLength, Encoding, Header, SecFetchSite, SetCookie, TypedHeaders,
+    };
+
+    #[test]
+    fn test_round_trip() {
+        let mut hmap = HeaderMap::new();
+        hmap.encode(&ContentEncoding(
+            vec![Encoding::Gzip, Encoding::Chunked].into(),
+        ));
+        hmap.encode_append(&ContentEncoding(vec![Encoding::Brotli].into()));
+        hmap.encode(&SetCookie(vec![
+            "a=1".parse().unwrap(),
+            "b=2".parse().unwrap(),
+        ]));
+        hmap.encode(&SecFetchSite::SameOrigin);
+        assert_eq!(hmap.get_all("set-cookie").iter().count(), 2);
+        assert_eq!(hmap["sec-fetch-site"], "same-origin");
+
+        let ce: ContentEncoding = hmap.decode().unwrap();
+        assert_eq!(
+            ce[..],
+            [Encoding::Gzip, Encoding::Chunked, Encoding::Brotli]
+        );
+        assert_eq!(hmap.decode::<SetCookie>().unwrap().len(), 2);
+        assert_eq!(
+            hmap.decode::<SecFetchSite>().unwrap(),
+            SecFetchSite::SameOrigin
+        );
+        assert!(hmap.contains::<SetCookie>());
+        assert!(hmap.try_decode::<ContentLength>().is_none());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let mut hmap = HeaderMap::new();
+        hmap.insert("content-length", "x".parse().unwrap());
+        let e = hmap.decode::<ContentLength>().unwrap_err();
+        assert_eq!(e.header_error().unwrap().name(), Some("Content-Length"));
+        assert_eq!(hmap.take::<ContentLength>(), None);
+        assert_eq!(hmap.len(), 1);
+
+        hmap.insert("content-length", "11".parse().unwrap());
+        assert_eq!(hmap.take::<ContentLength>(), Some(ContentLength(11)));
+        assert!(hmap.is_empty());
+    }
+
+    #[test]
+    fn test_try_from() {
+        let value = HeaderValue::try_from(&ContentLength(11)).unwrap();
+        assert_eq!(value, "11");
+        assert_eq!(ContentLength::try_from(&value).unwrap(), ContentLength(11));
+        assert_eq!(
+            ContentLength::parse_header(&&value).unwrap(),
+            ContentLength(11)
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Implementation module for compatibility with the _httparse_ crate.
+
+use std::slice;
+
+use httparse;
+
+use super::RawLike;
+
+/// Raw access to the values (lines) of a single header name, borrowed from
+/// a slice of `httparse::Header` without copying or allocation.
+///
+/// Header names are compared ASCII case-insensitively. Requires the
+/// `httparse` feature.
+///
+/// # Example
+///
+/// ```
+/// # extern crate httparse;
+/// # extern crate hyperx;
+/// use hyperx::header::{ContentLength, Header, HttparseRaw};
+///
+/// # fn main() {
+/// let mut headers = [httparse::EMPTY_HEADER; 8];
+/// let mut req = httparse::Request::new(&mut headers);
+/// req.parse(b"GET / HTTP/1.1\r\nContent-Length: 42\r\n\r\n").unwrap();
+///
+/// let raw = HttparseRaw::new(req.headers, ContentLength::header_name());
+/// assert_eq!(ContentLength::parse_header(&raw).unwrap(), ContentLength(42));
+/// # }
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct HttparseRaw<'h, 'b: 'h> {
+    headers: &'h [httparse::Header<'b>],
+    name: &'h str,
+}
+
+impl<'h, 'b: 'h> HttparseRaw<'h, 'b> {
+    /// Construct over the given parsed headers, for the given header name.
+    pub fn new(headers: &'h [httparse::Header<'b>], name: &'h str) -> HttparseRaw<'h, 'b> {
+        HttparseRaw { headers, name }
+    }
+}
+
+impl<'a, 'h: 'a, 'b: 'h> RawLike<'a> for HttparseRaw<'h, 'b> {
+    type IntoIter = HttparseLines<'a>;
+
+    fn len(&'a self) -> usize {
+        self.iter().count()
+    }
+
+    fn one(&'a self) -> Option<&'a [u8]> {
+        let mut iter = self.iter();
+        if let Some(v) = iter.next() {
+            if iter.next().is_none() {
+                return Some(v);
+            }
+        }
+        None
+    }
+
+    fn iter(&'a self) -> HttparseLines<'a> {
+        HttparseLines {
+            iter: self.headers.iter(),
+            name: self.name,
+        }
+    }
+}
+
+/// Iterator over the values (lines) of a `HttparseRaw`.
+#[derive(Clone, Debug)]
+pub struct HttparseLines<'a> {
+    iter: slice::Iter<'a, httparse::Header<'a>>,
+    name: &'a str,
+}
+
+impl<'a> Iterator for HttparseLines<'a> {
+    type Item = &'a [u8];
+
+    fn next(&mut self) -> Option<&'a [u8]> {
+        let name = self.name;
+        self.iter
+            .by_ref()
+            .find(|h| h.name.eq_ignore_ascii_case(name))
+            .map(|h| h.value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HttparseRaw;
+    use header::{ContentEncoding, ContentLength, Encoding, Header, Host, RawLike};
+    use httparse;
+
+    const REQ: &[u8] = b"GET / HTTP/1.1\r\n\
+        Host: hyper.rs\r\n\
+        content-encoding: chunked, gzip\r\n\
+        Content-Length: 10\r\n\
+        Content-Encoding: br\r\n\
+        Content-Length: 11\r\n\r\n";
+
+    #[test]
+    fn test_parse() {
+        let mut headers = [httparse::EMPTY_HEADER; 16];
+        let mut req = httparse::Request::new(&mut headers);
+        assert!(req.parse(REQ).unwrap().is_complete());
+
+        let raw = HttparseRaw::new(req.headers, "Content-Encoding");
+        assert_eq!(raw.len(), 2);
+        assert_eq!(raw.one(), None);
+        assert_eq!(
+            ContentEncoding::parse_header(&raw).unwrap(),
+            ContentEncoding(smallvec![
+                Encoding::Chunked,
+                Encoding::Gzip,
+                Encoding::Brotli
+            ])
+        );
+
+        let raw = HttparseRaw::new(req.headers, "host");
+        assert_eq!(raw.one(), Some(&b"hyper.rs"[..]));
+        assert_eq!(
+            Host::parse_header(&raw).unwrap(),
+            Host::new("hyper.rs", None)
+        );
+
+        let raw = HttparseRaw::new(req.headers, ContentLength::header_name());
+        assert!(ContentLength::parse_header(&raw).is_err());
+
+        let raw = HttparseRaw::new(req.headers, "x-missing");
+        assert!(raw.is_empty());
+        assert_eq!(raw.iter().next(), None);
+    }
+}
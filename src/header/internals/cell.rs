@@ -1,248 +1,226 @@
-use std::any::{Any, TypeId};
-use std::cell::UnsafeCell;
-use std::collections::HashMap;
-use std::mem;
-use std::ops::Deref;
-
-pub struct OptCell<T>(UnsafeCell<Option<T>>);
-
-impl<T> OptCell<T> {
-    #[inline]
-    pub fn new(val: Option<T>) -> OptCell<T> {
-        OptCell(UnsafeCell::new(val))
-    }
-
-    #[inline]
-    pub fn set(&self, val: T) {
-        unsafe {
-            let opt = self.0.get();
-            debug_assert!((*opt).is_none());
-            *opt = Some(val)
-        }
-    }
-
-    #[inline]
-    pub unsafe fn get_mut(&mut self) -> &mut T {
-        let opt = &mut *self.0.get();
-        opt.as_mut().unwrap()
-    }
+use std::any::TypeId;
+use std::sync::OnceLock;
+
+/// An insert-only map of boxed values by `TypeId`, which may be shared
+/// across threads.
+///
+/// Entries are stored in a list of `OnceLock` cells. Once set, an entry is
+/// never moved or removed while the map is shared, so references to values
+/// remain valid for the duration of the shared borrow.
+pub struct TypeMapCell<V: ?Sized> {
+    head: OnceLock<Box<Node<V>>>,
 }
 
-impl<T> Deref for OptCell<T> {
-    type Target = Option<T>;
-    #[inline]
-    fn deref(&self) -> &Option<T> {
-        unsafe { &*self.0.get() }
-    }
+struct Node<V: ?Sized> {
+    key: TypeId,
+    val: Box<V>,
+    next: OnceLock<Box<Node<V>>>,
 }
 
-impl<T: Clone> Clone for OptCell<T> {
-    #[inline]
-    fn clone(&self) -> OptCell<T> {
-        OptCell::new((**self).clone())
+impl<V: ?Sized> Node<V> {
+    fn new(key: TypeId, val: Box<V>) -> Box<Node<V>> {
+        Box::new(Node {
+            key,
+            val,
+            next: OnceLock::new(),
+        })
     }
 }
 
-pub struct PtrMapCell<V: ?Sized>(UnsafeCell<PtrMap<Box<V>>>);
-
-#[derive(Clone, Debug)]
-enum PtrMap<T> {
-    Empty,
-    One(TypeId, T),
-    Many(HashMap<TypeId, T>),
-}
-
-impl<V: ?Sized + Any + 'static> PtrMapCell<V> {
+impl<V: ?Sized> TypeMapCell<V> {
     #[inline]
-    pub fn new() -> PtrMapCell<V> {
-        PtrMapCell(UnsafeCell::new(PtrMap::Empty))
+    pub fn new() -> TypeMapCell<V> {
+        TypeMapCell {
+            head: OnceLock::new(),
+        }
     }
 
+    #[cfg(feature = "headers")]
     #[inline]
-    pub fn with_one(key: TypeId, val: Box<V>) -> PtrMapCell<V> {
-        PtrMapCell(UnsafeCell::new(PtrMap::One(key, val)))
+    pub fn with_one(key: TypeId, val: Box<V>) -> TypeMapCell<V> {
+        TypeMapCell {
+            head: OnceLock::from(Node::new(key, val)),
+        }
     }
 
-    #[inline]
     pub fn get(&self, key: TypeId) -> Option<&V> {
-        let map = unsafe { &*self.0.get() };
-        match *map {
-            PtrMap::Empty => None,
-            PtrMap::One(id, ref v) => {
-                if id == key {
-                    Some(v)
-                } else {
-                    None
-                }
+        let mut cell = &self.head;
+        while let Some(node) = cell.get() {
+            if node.key == key {
+                return Some(&*node.val);
             }
-            PtrMap::Many(ref hm) => hm.get(&key),
+            cell = &node.next;
         }
-        .map(|val| &**val)
+        None
     }
 
-    #[inline]
+    #[cfg(feature = "headers")]
     pub fn get_mut(&mut self, key: TypeId) -> Option<&mut V> {
-        let map = unsafe { &mut *self.0.get() };
-        match *map {
-            PtrMap::Empty => None,
-            PtrMap::One(id, ref mut v) => {
-                if id == key {
-                    Some(v)
-                } else {
-                    None
-                }
+        let mut cell = &mut self.head;
+        while let Some(node) = cell.get_mut() {
+            if node.key == key {
+                return Some(&mut *node.val);
             }
-            PtrMap::Many(ref mut hm) => hm.get_mut(&key),
+            cell = &mut node.next;
         }
-        .map(|val| &mut **val)
-    }
-
-    #[inline]
-    pub fn into_value(self, key: TypeId) -> Option<Box<V>> {
-        // UnsafeCell::into_inner was unsafe forever, and 1.25 has removed
-        // the unsafe modifier, resulting in a warning. This allow can be
-        // removed when the minimum supported rust version is at least 1.25.
-        #[allow(unused_unsafe)]
-        let map = unsafe { self.0.into_inner() };
-        match map {
-            PtrMap::Empty => None,
-            PtrMap::One(id, v) => {
-                if id == key {
-                    Some(v)
-                } else {
-                    None
-                }
+        None
+    }
+
+    #[cfg(feature = "headers")]
+    pub fn into_value(mut self, key: TypeId) -> Option<Box<V>> {
+        let mut next = self.head.take();
+        while let Some(node) = next {
+            let Node {
+                key: k,
+                val,
+                next: n,
+            } = *node;
+            if k == key {
+                return Some(val);
             }
-            PtrMap::Many(mut hm) => hm.remove(&key),
+            next = n.into_inner();
         }
-    }
-
-    #[inline]
-    pub unsafe fn insert(&self, key: TypeId, val: Box<V>) {
-        let map = &mut *self.0.get();
-        match *map {
-            PtrMap::Empty => *map = PtrMap::One(key, val),
-            PtrMap::One(..) => {
-                let one = mem::replace(map, PtrMap::Empty);
-                match one {
-                    PtrMap::One(id, one) => {
-                        debug_assert_ne!(id, key);
-                        let mut hm = HashMap::with_capacity(2);
-                        hm.insert(id, one);
-                        hm.insert(key, val);
-                        *map = PtrMap::Many(hm);
-                    }
-                    _ => unreachable!(),
+        None
+    }
+
+    /// Insert a value for the given key, unless already present, and return
+    /// the value now stored for the key.
+    ///
+    /// If another thread concurrently inserts a value for the same key, one
+    /// of the values is retained, and the other is dropped.
+    pub fn insert(&self, key: TypeId, val: Box<V>) -> &V {
+        let mut cell = &self.head;
+        let mut node = Some(Node::new(key, val));
+        loop {
+            if let Some(other) = cell.get() {
+                if other.key == key {
+                    return &other.val;
                 }
-            }
-            PtrMap::Many(ref mut hm) => {
-                hm.insert(key, val);
+                cell = &other.next;
+            } else if let Err(n) = cell.set(node.take().expect("node")) {
+                // Set concurrently, so retry on the same cell.
+                node = Some(n);
             }
         }
     }
 
+    #[cfg(feature = "headers")]
+    /// Return the first value inserted, if any.
     #[inline]
-    pub unsafe fn one(&self) -> &V {
-        let map = &*self.0.get();
-        match *map {
-            PtrMap::One(_, ref one) => one,
-            _ => panic!("not PtrMap::One value"),
-        }
+    pub fn first(&self) -> Option<&V> {
+        self.head.get().map(|node| &*node.val)
     }
 }
 
-impl<V: ?Sized + Any + 'static> Clone for PtrMapCell<V>
+impl<V: ?Sized> Clone for TypeMapCell<V>
 where
     Box<V>: Clone,
 {
-    #[inline]
-    fn clone(&self) -> PtrMapCell<V> {
-        let cell = PtrMapCell::new();
-        unsafe { *cell.0.get() = (&*self.0.get()).clone() }
+    fn clone(&self) -> TypeMapCell<V> {
+        let cell = TypeMapCell::new();
+        let mut next = self.head.get();
+        while let Some(node) = next {
+            cell.insert(node.key, node.val.clone());
+            next = node.next.get();
+        }
         cell
     }
 }
 
-#[cfg(test)]
+impl<V: ?Sized> Drop for TypeMapCell<V> {
+    // Drop iteratively, rather than recursively through the list.
+    fn drop(&mut self) {
+        let mut next = self.head.take();
+        while let Some(mut node) = next {
+            next = node.next.take();
+        }
+    }
+}
+
+#[cfg(all(test, feature = "headers"))]
 mod test {
     use super::*;
     use std::any::TypeId;
+    use std::sync::Arc;
+    use std::thread;
 
     #[test]
-    fn test_opt_cell_set() {
-        let one: OptCell<u32> = OptCell::new(None);
-        one.set(1);
-        assert_eq!(*one, Some(1));
-    }
-
-    #[test]
-    fn test_opt_cell_clone() {
-        let one: OptCell<u32> = OptCell::new(Some(3));
-        let stored = *one.clone();
-        assert_eq!(stored, Some(3));
-    }
-
-    #[test]
-    fn test_ptr_map_cell_none() {
+    fn test_type_map_cell_none() {
         let type_id = TypeId::of::<u32>();
-        let pm: PtrMapCell<u32> = PtrMapCell::new();
+        let pm: TypeMapCell<u32> = TypeMapCell::new();
         assert_eq!(pm.get(type_id), None);
+        assert_eq!(pm.first(), None);
     }
 
     #[test]
-    fn test_ptr_map_cell_one() {
+    fn test_type_map_cell_one() {
         let type_id = TypeId::of::<String>();
-        let pm: PtrMapCell<String> = PtrMapCell::new();
-        unsafe {
-            pm.insert(type_id, Box::new("a".to_string()));
-        }
+        let pm: TypeMapCell<String> = TypeMapCell::new();
+        assert_eq!(pm.insert(type_id, Box::new("a".to_string())), "a");
         assert_eq!(pm.get(type_id), Some(&"a".to_string()));
-        assert_eq!(unsafe { pm.one() }, "a");
+        assert_eq!(pm.first().unwrap(), "a");
     }
 
     #[test]
-    fn test_ptr_map_cell_two() {
+    fn test_type_map_cell_existing() {
         let type_id = TypeId::of::<String>();
-        let type_id2 = TypeId::of::<Vec<u8>>();
-        let pm: PtrMapCell<String> = PtrMapCell::new();
-        unsafe {
-            pm.insert(type_id, Box::new("a".to_string()));
-        }
-        unsafe {
-            pm.insert(type_id2, Box::new("b".to_string()));
-        }
+        let pm = TypeMapCell::with_one(type_id, Box::new("a".to_string()));
+        assert_eq!(pm.insert(type_id, Box::new("b".to_string())), "a");
         assert_eq!(pm.get(type_id), Some(&"a".to_string()));
-        assert_eq!(pm.get(type_id2), Some(&"b".to_string()));
     }
 
     #[test]
-    fn test_ptr_map_cell_many() {
+    fn test_type_map_cell_many() {
         let id1 = TypeId::of::<String>();
         let id2 = TypeId::of::<Vec<u8>>();
-        let id3 = TypeId::of::<OptCell<String>>();
-        let pm: PtrMapCell<String> = PtrMapCell::new();
-        unsafe {
-            pm.insert(id1, Box::new("a".to_string()));
-        }
-        unsafe {
-            pm.insert(id2, Box::new("b".to_string()));
-        }
-        unsafe {
-            pm.insert(id3, Box::new("c".to_string()));
-        }
+        let id3 = TypeId::of::<TypeMapCell<String>>();
+        let mut pm: TypeMapCell<String> = TypeMapCell::new();
+        pm.insert(id1, Box::new("a".to_string()));
+        pm.insert(id2, Box::new("b".to_string()));
+        pm.insert(id3, Box::new("c".to_string()));
         assert_eq!(pm.get(id1), Some(&"a".to_string()));
         assert_eq!(pm.get(id2), Some(&"b".to_string()));
         assert_eq!(pm.get(id3), Some(&"c".to_string()));
+        assert_eq!(pm.first().unwrap(), "a");
+
+        pm.get_mut(id2).unwrap().push('!');
+        assert_eq!(pm.get(id2), Some(&"b!".to_string()));
+        assert_eq!(*pm.into_value(id2).unwrap(), "b!");
     }
 
     #[test]
-    fn test_ptr_map_cell_clone() {
+    fn test_type_map_cell_clone() {
         let type_id = TypeId::of::<String>();
-        let pm: PtrMapCell<String> = PtrMapCell::new();
-        unsafe {
-            pm.insert(type_id, Box::new("a".to_string()));
-        }
+        let pm: TypeMapCell<String> = TypeMapCell::new();
+        pm.insert(type_id, Box::new("a".to_string()));
         let cloned = pm.clone();
         assert_eq!(cloned.get(type_id), Some(&"a".to_string()));
     }
+
+    #[test]
+    fn test_type_map_cell_threads() {
+        let pm: Arc<TypeMapCell<usize>> = Arc::new(TypeMapCell::new());
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let pm = pm.clone();
+                thread::spawn(move || {
+                    let id = if i % 2 == 0 {
+                        TypeId::of::<u8>()
+                    } else {
+                        TypeId::of::<u16>()
+                    };
+                    *pm.insert(id, Box::new(i))
+                })
+            })
+            .collect();
+        for t in threads {
+            let v = t.join().unwrap();
+            let id = if v % 2 == 0 {
+                TypeId::of::<u8>()
+            } else {
+                TypeId::of::<u16>()
+            };
+            assert_eq!(pm.get(id), Some(&v));
+        }
+    }
 }
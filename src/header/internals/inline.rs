@@ -0,0 +1,136 @@
+use std::any::Any;
+
+use header::{
+    AccessControlAllowCredentials, AccessControlMaxAge, Age, ContentLength,
+    CrossOriginEmbedderPolicy, CrossOriginOpenerPolicy, CrossOriginResourcePolicy, Date, Expect,
+    Expires, Header, IfModifiedSince, IfUnmodifiedSince, LastModified, ReferrerPolicy,
+    SecFetchDest, SecFetchMode, SecFetchSite, SecFetchUser, XContentTypeOptions, XFrameOptions,
+};
+
+macro_rules! inline {
+    ($($ty:ident),+) => (
+        /// A typed value of one of the small, frequently set headers, stored
+        /// inline rather than boxed.
+        ///
+        /// The variants are limited to headers with at most a pointer-size
+        /// payload, such as a length, a date or a unit enum, so that holding
+        /// one does not grow an `Item` by more than a box would.
+        #[derive(Clone)]
+        pub enum Inline {
+            $($ty($ty)),+
+        }
+
+        impl Inline {
+            /// Wrap a value, if of one of the inline types, or otherwise
+            /// return it unchanged.
+            pub fn new<H: Header>(value: H) -> Result<Inline, H> {
+                let mut value = Some(value);
+                $(
+                if let Some(h) = cast::<H, $ty>(&mut value) {
+                    return Ok(Inline::$ty(h));
+                }
+                )+
+                Err(value.expect("uncast value"))
+            }
+
+            #[inline]
+            pub fn as_header(&self) -> &(dyn Header + Send + Sync) {
+                match *self {
+                    $(Inline::$ty(ref h) => h),+
+                }
+            }
+
+            #[inline]
+            pub fn as_header_mut(&mut self) -> &mut (dyn Header + Send + Sync) {
+                match *self {
+                    $(Inline::$ty(ref mut h) => h),+
+                }
+            }
+
+            pub fn into_value<H: Header>(self) -> Option<H> {
+                match self {
+                    $(Inline::$ty(h) => cast::<$ty, H>(&mut Some(h))),+
+                }
+            }
+        }
+    );
+}
+
+inline! {
+    AccessControlAllowCredentials,
+    AccessControlMaxAge,
+    Age,
+    ContentLength,
+    CrossOriginEmbedderPolicy,
+    CrossOriginOpenerPolicy,
+    CrossOriginResourcePolicy,
+    Date,
+    Expect,
+    Expires,
+    IfModifiedSince,
+    IfUnmodifiedSince,
+    LastModified,
+    ReferrerPolicy,
+    SecFetchDest,
+    SecFetchMode,
+    SecFetchSite,
+    SecFetchUser,
+    XContentTypeOptions,
+    XFrameOptions
+}
+
+impl Inline {
+    #[inline]
+    pub fn downcast_ref<H: Header>(&self) -> Option<&H> {
+        self.as_header().as_any().downcast_ref()
+    }
+
+    #[inline]
+    pub fn downcast_mut<H: Header>(&mut self) -> Option<&mut H> {
+        self.as_header_mut().as_any_mut().downcast_mut()
+    }
+}
+
+// Take the value of a type known to be `T`, by its `TypeId`, leaving `None`.
+#[inline]
+fn cast<F: Any, T: Any>(value: &mut Option<F>) -> Option<T> {
+    (value as &mut dyn Any)
+        .downcast_mut::<Option<T>>()
+        .and_then(Option::take)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use header::{CacheControl, HttpDate};
+    use std::mem;
+    use std::time::SystemTime;
+
+    #[test]
+    fn test_inline_size() {
+        assert!(mem::size_of::<Inline>() <= 2 * mem::size_of::<Box<dyn Header>>());
+    }
+
+    #[test]
+    fn test_inline() {
+        let mut inline = Inline::new(ContentLength(10)).ok().unwrap();
+        assert_eq!(
+            inline.downcast_ref::<ContentLength>(),
+            Some(&ContentLength(10))
+        );
+        assert_eq!(inline.downcast_ref::<Age>(), None);
+        inline.downcast_mut::<ContentLength>().unwrap().0 += 1;
+        assert_eq!(inline.clone().into_value::<Age>(), None);
+        assert_eq!(inline.into_value(), Some(ContentLength(11)));
+
+        let date = Date(HttpDate::from(SystemTime::UNIX_EPOCH));
+        let inline = Inline::new(date.clone()).ok().unwrap();
+        assert_eq!(inline.as_header().as_any().downcast_ref(), Some(&date));
+    }
+
+    #[test]
+    fn test_not_inline() {
+        let cc = CacheControl(vec![]);
+        assert_eq!(Inline::new(cc.clone()).err(), Some(cc));
+    }
+}
@@ -1,97 +1,129 @@
 use std::any::Any;
 use std::any::TypeId;
 use std::fmt;
+use std::mem;
 use std::str::from_utf8;
+use std::sync::OnceLock;
 
-use super::cell::{OptCell, PtrMapCell};
-use header::{raw, Formatter, Header, Multi, Raw, RawLike};
+use super::cell::TypeMapCell;
+use super::inline::Inline;
+use header::{interned, raw, Formatter, Header, Multi, Raw, RawLike};
 
 #[derive(Clone)]
 pub struct Item {
-    raw: OptCell<Raw>,
-    typed: PtrMapCell<dyn Header + Send + Sync>,
+    raw: OnceLock<Raw>,
+    // A small value set or mutated, held without boxing
+    inline: Option<Inline>,
+    typed: TypeMapCell<dyn Header + Send + Sync>,
 }
 
 impl Item {
     #[inline]
     pub fn new_raw(data: Raw) -> Item {
         Item {
-            raw: OptCell::new(Some(data)),
-            typed: PtrMapCell::new(),
+            raw: OnceLock::from(data),
+            inline: None,
+            typed: TypeMapCell::new(),
         }
     }
 
     #[inline]
     pub fn new_typed<H: Header>(val: H) -> Item {
-        Item {
-            raw: OptCell::new(None),
-            typed: PtrMapCell::with_one(TypeId::of::<H>(), Box::new(val)),
+        match Inline::new(val) {
+            Ok(inline) => Item {
+                raw: OnceLock::new(),
+                inline: Some(inline),
+                typed: TypeMapCell::new(),
+            },
+            Err(val) => Item {
+                raw: OnceLock::new(),
+                inline: None,
+                typed: TypeMapCell::with_one(TypeId::of::<H>(), Box::new(val)),
+            },
         }
     }
 
     #[inline]
     pub fn raw_mut(&mut self) -> &mut Raw {
         self.raw();
-        self.typed = PtrMapCell::new();
-        unsafe { self.raw.get_mut() }
+        self.inline = None;
+        self.typed = TypeMapCell::new();
+        self.raw.get_mut().expect("raw value")
     }
 
     pub fn raw(&self) -> &Raw {
-        if let Some(ref raw) = *self.raw {
-            return raw;
-        }
-
-        let mut raw = raw::new();
-        self.write_h1(&mut Formatter(Multi::Raw(&mut raw)))
-            .expect("fmt failed");
-        self.raw.set(raw);
-
-        self.raw.as_ref().unwrap()
+        self.raw.get_or_init(|| {
+            let mut raw = raw::new();
+            self.write_h1(&mut Formatter(Multi::Raw(&mut raw)))
+                .expect("fmt failed");
+            raw
+        })
     }
 
     pub fn typed<H: Header + Any>(&self) -> Option<&H> {
+        if let Some(h) = self.inline.as_ref().and_then(Inline::downcast_ref) {
+            return Some(h);
+        }
         let tid = TypeId::of::<H>();
         match self.typed.get(tid) {
             Some(val) => Some(val),
-            None => parse::<H>(self.raw.as_ref().expect("item.raw must exist")).and_then(|typed| {
-                unsafe {
-                    self.typed.insert(tid, typed);
-                }
-                self.typed.get(tid)
-            }),
+            None => parse::<H>(self.raw()).map(|typed| self.typed.insert(tid, typed)),
         }
-        .map(|typed| unsafe { typed.downcast_ref_unchecked() })
+        .and_then(|typed| typed.as_any().downcast_ref())
     }
 
     pub fn typed_mut<H: Header>(&mut self) -> Option<&mut H> {
         let tid = TypeId::of::<H>();
-        if self.typed.get_mut(tid).is_none() {
-            parse::<H>(self.raw.as_ref().expect("item.raw must exist")).map(|typed| unsafe {
-                self.typed.insert(tid, typed);
-            });
+        // Make sure a raw value exists before any typed values are dropped,
+        // so that a failed parse below doesn't lose the header.
+        self.raw();
+        // Once mutably borrowed, the raw value and any other typed values
+        // may become stale, so only the value being mutated is retained.
+        if self
+            .inline
+            .as_ref()
+            .and_then(Inline::downcast_ref::<H>)
+            .is_some()
+        {
+            self.raw = OnceLock::new();
+            self.typed = TypeMapCell::new();
+        } else {
+            let typed = mem::replace(&mut self.typed, TypeMapCell::new())
+                .into_value(tid)
+                .or_else(|| parse::<H>(self.raw()));
+            if let Some(typed) = typed {
+                self.raw = OnceLock::new();
+                self.inline = None;
+                self.typed = TypeMapCell::with_one(tid, typed);
+            }
         }
-        if self.raw.is_some() && self.typed.get_mut(tid).is_some() {
-            self.raw = OptCell::new(None);
+        match self.inline {
+            Some(ref mut inline) => inline.downcast_mut(),
+            None => self
+                .typed
+                .get_mut(tid)
+                .and_then(|typed| typed.as_any_mut().downcast_mut()),
         }
-        self.typed
-            .get_mut(tid)
-            .map(|typed| unsafe { typed.downcast_mut_unchecked() })
     }
 
     pub fn into_typed<H: Header>(self) -> Option<H> {
         let tid = TypeId::of::<H>();
-        let Item { typed, raw } = self;
+        let Item { typed, inline, raw } = self;
+        if let Some(h) = inline.and_then(Inline::into_value) {
+            return Some(h);
+        }
         typed
             .into_value(tid)
-            .or_else(|| raw.as_ref().and_then(parse::<H>))
-            .map(|typed| unsafe { typed.downcast_unchecked() })
+            .or_else(|| raw.get().and_then(parse::<H>))
+            .and_then(|typed| typed.into_any().downcast().ok())
+            .map(|typed| *typed)
     }
 
     pub fn write_h1(&self, f: &mut Formatter) -> fmt::Result {
-        match *self.raw {
-            Some(ref raw) => {
+        match self.raw.get() {
+            Some(raw) => {
                 for part in raw.iter() {
-                    match from_utf8(&part[..]) {
+                    match from_utf8(part) {
                         Ok(s) => {
                             f.fmt_line(&s)?;
                         }
@@ -102,18 +134,22 @@ impl Item {
                 }
                 Ok(())
             }
-            None => {
-                let typed = unsafe { self.typed.one() };
-                typed.fmt_header(f)
-            }
+            None => match self.inline {
+                Some(ref inline) => inline.as_header().fmt_header(f),
+                None => self
+                    .typed
+                    .first()
+                    .expect("typed or raw value")
+                    .fmt_header(f),
+            },
         }
     }
 }
 
 #[inline]
 fn parse<H: Header>(raw: &Raw) -> Option<Box<dyn Header + Send + Sync>> {
-    H::parse_header(raw)
-        .map(|h| {
+    interned::parse_header(raw)
+        .map(|h: H| {
             let h: Box<dyn Header + Send + Sync> = Box::new(h);
             h
         })
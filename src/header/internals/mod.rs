@@ -1,14 +1,20 @@
+// The caches of parsed values are built on safe primitives only.
+#![forbid(unsafe_code)]
+
 #[cfg(feature = "headers")]
 pub use self::item::Item;
 
+pub use self::cell::TypeMapCell;
 pub use self::vec_map::VecMap;
 
 #[cfg(feature = "headers")]
 pub use self::vec_map::Entry;
 
-#[cfg(feature = "headers")]
 mod cell;
 
+#[cfg(feature = "headers")]
+mod inline;
+
 #[cfg(feature = "headers")]
 mod item;
 
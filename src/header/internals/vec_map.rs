@@ -1,63 +1,114 @@
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+
+// Beyond this many entries, lookups go through a hash index rather than a
+// linear scan of the entries.
+const INDEX_THRESHOLD: usize = 16;
+
+/// A map of string-like keys, in insertion order, which may hold multiple
+/// entries of the same key.
+///
+/// Small maps are scanned linearly. Larger maps, such as those of requests
+/// with many cookies, additionally keep an index of the entry positions by
+/// hash. Keys are hashed with ASCII case folded, which is consistent with
+/// both case-sensitive and case-insensitive key equality.
 #[derive(Clone)]
 pub struct VecMap<K, V> {
     vec: Vec<(K, V)>,
+    index: Option<Index>,
 }
 
-impl<K: PartialEq, V> VecMap<K, V> {
+#[derive(Clone)]
+struct Index {
+    // Randomly keyed per map, so that keys cannot be crafted to collide
+    state: RandomState,
+    // Entry positions by key hash, in ascending order
+    slots: HashMap<u64, Vec<usize>>,
+}
+
+impl Index {
+    fn build<K: AsRef<str>, V>(vec: &[(K, V)]) -> Index {
+        let mut index = Index {
+            state: RandomState::new(),
+            slots: HashMap::with_capacity(vec.len()),
+        };
+        for (pos, entry) in vec.iter().enumerate() {
+            index.add(&entry.0, pos);
+        }
+        index
+    }
+
+    fn hash<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> u64 {
+        let mut hasher = self.state.build_hasher();
+        for b in key.as_ref().bytes() {
+            hasher.write_u8(b.to_ascii_lowercase());
+        }
+        hasher.finish()
+    }
+
+    fn add<Q: AsRef<str> + ?Sized>(&mut self, key: &Q, pos: usize) {
+        let hash = self.hash(key);
+        self.slots.entry(hash).or_default().push(pos);
+    }
+
+    fn positions<Q: AsRef<str> + ?Sized>(&self, key: &Q) -> &[usize] {
+        self.slots
+            .get(&self.hash(key))
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+impl<K: PartialEq + AsRef<str>, V> VecMap<K, V> {
     #[inline]
     pub fn with_capacity(cap: usize) -> VecMap<K, V> {
         VecMap {
             vec: Vec::with_capacity(cap),
+            index: None,
         }
     }
 
     #[inline]
     pub fn insert(&mut self, key: K, value: V) {
-        // not using entry or find_mut because of borrowck
-        for entry in &mut self.vec {
-            if key == entry.0 {
-                *entry = (key, value);
-                return;
-            }
+        match self.pos(&key) {
+            Some(pos) => self.vec[pos] = (key, value),
+            None => self.push(key, value),
         }
-        self.vec.push((key, value));
     }
 
     #[inline]
     pub fn append(&mut self, key: K, value: V) {
-        self.vec.push((key, value));
+        self.push(key, value);
     }
 
     #[cfg(feature = "headers")]
     #[inline]
-    pub fn entry(&mut self, key: K) -> Entry<K, V> {
+    pub fn entry(&mut self, key: K) -> Entry<'_, K, V> {
         match self.pos(&key) {
             Some(pos) => Entry::Occupied(OccupiedEntry {
                 vec: &mut self.vec,
-                pos: pos,
-            }),
-            None => Entry::Vacant(VacantEntry {
-                vec: &mut self.vec,
-                key: key,
+                pos,
             }),
+            None => Entry::Vacant(VacantEntry { map: self, key }),
         }
     }
 
     #[inline]
-    pub fn get<K2: PartialEq<K> + ?Sized>(&self, key: &K2) -> Option<&V> {
-        self.find(key).map(|entry| &entry.1)
+    pub fn get<K2: PartialEq<K> + AsRef<str> + ?Sized>(&self, key: &K2) -> Option<&V> {
+        self.pos(key).map(|pos| &self.vec[pos].1)
     }
 
     #[cfg(feature = "headers")]
     #[inline]
-    pub fn get_mut<K2: PartialEq<K> + ?Sized>(&mut self, key: &K2) -> Option<&mut V> {
-        self.find_mut(key).map(|entry| &mut entry.1)
+    pub fn get_mut<K2: PartialEq<K> + AsRef<str> + ?Sized>(&mut self, key: &K2) -> Option<&mut V> {
+        self.pos(key).map(move |pos| &mut self.vec[pos].1)
     }
 
     #[cfg(feature = "headers")]
     #[inline]
-    pub fn contains_key<K2: PartialEq<K> + ?Sized>(&self, key: &K2) -> bool {
-        self.find(key).is_some()
+    pub fn contains_key<K2: PartialEq<K> + AsRef<str> + ?Sized>(&self, key: &K2) -> bool {
+        self.pos(key).is_some()
     }
 
     #[inline]
@@ -66,25 +117,25 @@ impl<K: PartialEq, V> VecMap<K, V> {
     }
 
     #[inline]
-    pub fn iter(&self) -> ::std::slice::Iter<(K, V)> {
+    pub fn iter(&self) -> ::std::slice::Iter<'_, (K, V)> {
         self.vec.iter()
     }
 
     #[cfg(feature = "headers")]
     #[inline]
-    pub fn remove<K2: PartialEq<K> + ?Sized>(&mut self, key: &K2) -> Option<V> {
-        self.pos(key)
-            .map(|pos| self.vec.remove(pos))
-            .map(|(_, v)| v)
+    pub fn remove<K2: PartialEq<K> + AsRef<str> + ?Sized>(&mut self, key: &K2) -> Option<V> {
+        let pos = self.pos(key)?;
+        let (_, v) = self.vec.remove(pos);
+        self.reindex();
+        Some(v)
     }
 
     #[inline]
-    pub fn remove_all<K2: PartialEq<K> + ?Sized>(&mut self, key: &K2) {
+    pub fn remove_all<K2: PartialEq<K> + AsRef<str> + ?Sized>(&mut self, key: &K2) {
         let len = self.vec.len();
-        for i in (0..len).rev() {
-            if key == &self.vec[i].0 {
-                self.vec.remove(i);
-            }
+        self.vec.retain(|entry| key != &entry.0);
+        if self.vec.len() != len {
+            self.reindex();
         }
     }
 
@@ -92,28 +143,42 @@ impl<K: PartialEq, V> VecMap<K, V> {
     #[inline]
     pub fn clear(&mut self) {
         self.vec.clear();
+        self.index = None;
     }
 
-    #[inline]
-    fn find<K2: PartialEq<K> + ?Sized>(&self, key: &K2) -> Option<&(K, V)> {
-        self.vec.iter().find(|&entry| key == &entry.0)
-    }
-
-    #[cfg(feature = "headers")]
-    #[inline]
-    fn find_mut<K2: PartialEq<K> + ?Sized>(&mut self, key: &K2) -> Option<&mut (K, V)> {
-        for entry in &mut self.vec {
-            if key == &entry.0 {
-                return Some(entry);
+    fn push(&mut self, key: K, value: V) {
+        let pos = self.vec.len();
+        match self.index {
+            Some(ref mut index) => index.add(&key, pos),
+            None if pos >= INDEX_THRESHOLD => {
+                let mut index = Index::build(&self.vec);
+                index.add(&key, pos);
+                self.index = Some(index);
             }
+            None => {}
         }
-        None
+        self.vec.push((key, value));
+    }
+
+    // Rebuild the index after entries are removed, shifting positions.
+    fn reindex(&mut self) {
+        self.index = if self.vec.len() > INDEX_THRESHOLD {
+            Some(Index::build(&self.vec))
+        } else {
+            None
+        };
     }
 
-    #[cfg(feature = "headers")]
     #[inline]
-    fn pos<K2: PartialEq<K> + ?Sized>(&self, key: &K2) -> Option<usize> {
-        self.vec.iter().position(|entry| key == &entry.0)
+    fn pos<K2: PartialEq<K> + AsRef<str> + ?Sized>(&self, key: &K2) -> Option<usize> {
+        match self.index {
+            Some(ref index) => index
+                .positions(key)
+                .iter()
+                .cloned()
+                .find(|&pos| key == &self.vec[pos].0),
+            None => self.vec.iter().position(|entry| key == &entry.0),
+        }
     }
 }
 
@@ -125,16 +190,15 @@ pub enum Entry<'a, K: 'a, V: 'a> {
 
 #[cfg(feature = "headers")]
 pub struct VacantEntry<'a, K: 'a, V: 'a> {
-    vec: &'a mut Vec<(K, V)>,
+    map: &'a mut VecMap<K, V>,
     key: K,
 }
 
 #[cfg(feature = "headers")]
-impl<'a, K, V> VacantEntry<'a, K, V> {
+impl<'a, K: PartialEq + AsRef<str>, V> VacantEntry<'a, K, V> {
     pub fn insert(self, val: V) -> &'a mut V {
-        self.vec.push((self.key, val));
-        let pos = self.vec.len() - 1;
-        &mut self.vec[pos].1
+        self.map.push(self.key, val);
+        &mut self.map.vec.last_mut().expect("pushed entry").1
     }
 }
 
@@ -150,3 +214,96 @@ impl<'a, K, V> OccupiedEntry<'a, K, V> {
         &mut self.vec[self.pos].1
     }
 }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn map(n: usize) -> VecMap<String, usize> {
+        let mut map = VecMap::with_capacity(n);
+        for i in 0..n {
+            map.append(format!("key{}", i), i);
+        }
+        map
+    }
+
+    #[test]
+    fn test_small() {
+        let mut map = map(3);
+        assert!(map.index.is_none());
+        map.insert("key1".to_owned(), 10);
+        map.append("key1".to_owned(), 11);
+        assert_eq!(map.get("key1"), Some(&10));
+        map.remove_all("key1");
+        assert_eq!(map.get("key1"), None);
+        assert_eq!(map.len(), 2);
+    }
+
+    #[test]
+    fn test_large() {
+        let mut map = map(100);
+        assert!(map.index.is_some());
+        for i in 0..100 {
+            assert_eq!(map.get(&format!("key{}", i)[..]), Some(&i));
+        }
+        assert_eq!(map.get("KEY1"), None);
+        assert_eq!(map.get("missing"), None);
+
+        // Replacing keeps the position, appending preserves the first
+        map.insert("key50".to_owned(), 500);
+        map.append("key60".to_owned(), 600);
+        assert_eq!(map.get("key50"), Some(&500));
+        assert_eq!(map.get("key60"), Some(&60));
+        assert_eq!(map.len(), 101);
+        let keys: Vec<_> = map.iter().map(|e| e.1).collect();
+        assert_eq!(keys[49..52], [49, 500, 51]);
+        assert_eq!(keys[100], 600);
+
+        map.remove_all("key60");
+        assert_eq!(map.get("key60"), None);
+        assert_eq!(map.get("key61"), Some(&61));
+        assert_eq!(map.len(), 99);
+
+        map.remove_all("key0");
+        map.remove_all("missing");
+        assert_eq!(map.iter().next().unwrap().1, 1);
+        assert_eq!(map.get("key99"), Some(&99));
+    }
+
+    #[test]
+    fn test_shrink() {
+        let mut map = map(INDEX_THRESHOLD + 1);
+        assert!(map.index.is_some());
+        map.remove_all("key0");
+        assert!(map.index.is_none());
+        assert_eq!(
+            map.get(&format!("key{}", INDEX_THRESHOLD)[..]),
+            Some(&INDEX_THRESHOLD)
+        );
+    }
+
+    #[cfg(feature = "headers")]
+    #[test]
+    fn test_entry() {
+        let mut map = map(INDEX_THRESHOLD);
+        assert!(map.index.is_none());
+        match map.entry("new".to_owned()) {
+            Entry::Vacant(entry) => *entry.insert(1) += 1,
+            Entry::Occupied(_) => panic!("vacant"),
+        }
+        assert!(map.index.is_some());
+        assert_eq!(map.get("new"), Some(&2));
+        match map.entry("key3".to_owned()) {
+            Entry::Occupied(entry) => *entry.into_mut() = 30,
+            Entry::Vacant(_) => panic!("occupied"),
+        }
+        assert_eq!(map.get("key3"), Some(&30));
+        assert_eq!(map.remove("key3"), Some(30));
+        assert!(!map.contains_key("key3"));
+        *map.get_mut("key4").unwrap() = 40;
+        assert_eq!(map.get("key4"), Some(&40));
+        map.clear();
+        assert!(map.index.is_none());
+        assert_eq!(map.len(), 0);
+    }
+}
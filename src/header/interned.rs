@@ -0,0 +1,154 @@
+//! Pre-built values of headers for extremely common raw values, such as
+//! `Accept: */*` or `Connection: keep-alive`.
+//!
+//! A raw value matching one of these byte for byte is decoded by
+//! constructing the typed value directly, skipping UTF-8 validation,
+//! splitting and parsing of its items. Any other value, including the same
+//! value in another case or spacing, goes through the regular parser.
+
+use std::any::{Any, TypeId};
+
+use header::parsing::{Limit, ParseConfig};
+use header::{
+    qitem, Accept, AcceptEncoding, AcceptRanges, CacheControl, CacheDirective, Connection,
+    ConnectionOption, Encoding, Header, Pragma, RangeUnit, RawLike,
+};
+
+// The most items of any interned value, for checking the configured limits.
+const MAX_ITEMS: usize = 3;
+
+/// Parse a header, via a pre-built value if the raw header is a single
+/// common value, or otherwise via `Header::parse_header`.
+pub fn parse_header<'a, H, R>(raw: &'a R) -> ::Result<H>
+where
+    H: Header,
+    R: RawLike<'a>,
+{
+    if let Some(line) = raw.one() {
+        if let Some(h) = lookup::<H>(line) {
+            if within_limits(line) {
+                return Ok(h);
+            }
+        }
+    }
+    H::parse_header(raw)
+}
+
+fn lookup<H: Header>(line: &[u8]) -> Option<H> {
+    let id = TypeId::of::<H>();
+    if id == TypeId::of::<Accept>() {
+        match line {
+            b"*/*" => cast(Accept::star()),
+            _ => None,
+        }
+    } else if id == TypeId::of::<AcceptEncoding>() {
+        let encodings: &[Encoding] = match line {
+            b"gzip, deflate, br" => &[Encoding::Gzip, Encoding::Deflate, Encoding::Brotli],
+            b"gzip, deflate" => &[Encoding::Gzip, Encoding::Deflate],
+            b"gzip" => &[Encoding::Gzip],
+            b"identity" => &[Encoding::Identity],
+            _ => return None,
+        };
+        cast(AcceptEncoding(
+            encodings.iter().cloned().map(qitem).collect(),
+        ))
+    } else if id == TypeId::of::<AcceptRanges>() {
+        match line {
+            b"bytes" => cast(AcceptRanges(smallvec![RangeUnit::Bytes])),
+            b"none" => cast(AcceptRanges(smallvec![RangeUnit::None])),
+            _ => None,
+        }
+    } else if id == TypeId::of::<CacheControl>() {
+        match line {
+            b"no-cache" => cast(CacheControl(vec![CacheDirective::NoCache])),
+            b"no-store" => cast(CacheControl(vec![CacheDirective::NoStore])),
+            b"max-age=0" => cast(CacheControl(vec![CacheDirective::MaxAge(0)])),
+            _ => None,
+        }
+    } else if id == TypeId::of::<Connection>() {
+        match line {
+            b"keep-alive" => cast(Connection(smallvec![ConnectionOption::KeepAlive])),
+            b"close" => cast(Connection(smallvec![ConnectionOption::Close])),
+            _ => None,
+        }
+    } else if id == TypeId::of::<Pragma>() {
+        match line {
+            b"no-cache" => cast(Pragma::NoCache),
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+// Convert a value of a type known to be `H`, by its `TypeId`.
+fn cast<T: Any, H: Any>(value: T) -> Option<H> {
+    let mut value = Some(value);
+    (&mut value as &mut dyn Any)
+        .downcast_mut::<Option<H>>()
+        .and_then(Option::take)
+}
+
+// The parser enforces the limits configured on the current thread, which
+// the values are not exempt from.
+fn within_limits(line: &[u8]) -> bool {
+    let config = ParseConfig::current();
+    config.check(Limit::ValueLength, line.len()).is_ok()
+        && config.check(Limit::Items, MAX_ITEMS).is_ok()
+        && config.check(Limit::Directives, MAX_ITEMS).is_ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+
+    // Every interned value must equal the result of the regular parser.
+    fn check<H: Header + PartialEq + ::std::fmt::Debug>(values: &[&str]) {
+        for value in values {
+            let raw = Raw::from(*value);
+            let interned = lookup::<H>(value.as_bytes()).expect(value);
+            assert_eq!(interned, H::parse_header(&raw).unwrap(), "{}", value);
+            assert_eq!(parse_header::<H, _>(&raw).unwrap(), interned);
+        }
+    }
+
+    #[test]
+    fn test_interned() {
+        check::<Accept>(&["*/*"]);
+        check::<AcceptEncoding>(&["gzip, deflate, br", "gzip, deflate", "gzip", "identity"]);
+        check::<AcceptRanges>(&["bytes", "none"]);
+        check::<CacheControl>(&["no-cache", "no-store", "max-age=0"]);
+        check::<Connection>(&["keep-alive", "close"]);
+        check::<Pragma>(&["no-cache"]);
+    }
+
+    #[test]
+    fn test_not_interned() {
+        assert_eq!(lookup::<Connection>(b"Keep-Alive"), None);
+        assert_eq!(lookup::<AcceptEncoding>(b"gzip,deflate"), None);
+        assert_eq!(lookup::<Pragma>(b"keep-alive"), None);
+        assert_eq!(lookup::<::header::ContentLength>(b"0"), None);
+
+        let raw = Raw::from("Keep-Alive");
+        assert_eq!(
+            parse_header::<Connection, _>(&raw).unwrap(),
+            Connection::keep_alive()
+        );
+        // Multiple lines are parsed as usual
+        let raw = Raw::from(vec![b"gzip".to_vec(), b"br".to_vec()]);
+        let ae: AcceptEncoding = parse_header(&raw).unwrap();
+        assert_eq!(ae.len(), 2);
+    }
+
+    #[test]
+    fn test_limits() {
+        let raw = Raw::from("gzip, deflate, br");
+        let config = ParseConfig::new().limit(Limit::Items, 2);
+        assert!(config.parse::<AcceptEncoding, _>(&raw).is_err());
+        let config = ParseConfig::new().limit(Limit::ValueLength, 8);
+        assert!(config.parse::<AcceptEncoding, _>(&raw).is_err());
+        let config = ParseConfig::new().limit(Limit::Items, 3);
+        assert!(config.parse::<AcceptEncoding, _>(&raw).is_ok());
+    }
+}
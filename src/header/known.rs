@@ -0,0 +1,295 @@
+//! Decoding of all known typed headers from a `http::HeaderMap`.
+
+use http::header::{HeaderMap, HeaderName, Keys};
+
+use super::*;
+
+macro_rules! known_headers {
+    ($($ty:ident),+ $(,)*) => {
+        /// A decoded typed header, for any of the headers of this crate.
+        ///
+        /// Obtain via [`KnownHeader::decode_all`](#method.decode_all), to
+        /// inspect all headers of a `http::HeaderMap` understood by this
+        /// crate in one pass. Generic headers such as `Authorization<S>` are
+        /// not included, and further variants may be added in minor releases.
+        #[derive(Clone, Debug)]
+        #[non_exhaustive]
+        pub enum KnownHeader {
+            $(
+                #[allow(missing_docs)]
+                $ty($ty),
+            )+
+        }
+
+        // The type and field names of all known headers.
+        #[cfg(test)]
+        pub(crate) fn known_types() -> Vec<(&'static str, HeaderName)> {
+            vec![$((stringify!($ty), <$ty as Header>::http_name())),+]
+        }
+
+        // Register all known headers, for `HeaderRegistry::standard`.
+        pub fn register_all(registry: &mut HeaderRegistry) {
+            $(registry.register::<$ty>();)+
+        }
+
+        impl KnownHeader {
+            /// The `HeaderName` from the _http_ crate for this header.
+            pub fn http_name(&self) -> HeaderName {
+                match *self {
+                    $(KnownHeader::$ty(_) => <$ty as Header>::http_name(),)+
+                }
+            }
+
+            /// The decoded header, as a `Header` trait object.
+            pub fn as_header(&self) -> &(dyn Header + Send + Sync) {
+                match *self {
+                    $(KnownHeader::$ty(ref h) => h,)+
+                }
+            }
+
+            fn decode(map: &HeaderMap, name: &HeaderName) -> Option<KnownHeader> {
+                $(
+                    if *name == <$ty as Header>::http_name() {
+                        return map.decode::<$ty>().ok().map(KnownHeader::$ty);
+                    }
+                )+
+                None
+            }
+        }
+    };
+}
+
+known_headers! {
+    Accept,
+    AcceptCh,
+    AcceptCharset,
+    AcceptEncoding,
+    AcceptLanguage,
+    AcceptRanges,
+    AcceptSignature,
+    AccessControlAllowCredentials,
+    AccessControlAllowHeaders,
+    AccessControlAllowMethods,
+    AccessControlAllowOrigin,
+    AccessControlAllowPrivateNetwork,
+    AccessControlExposeHeaders,
+    AccessControlMaxAge,
+    AccessControlRequestHeaders,
+    AccessControlRequestMethod,
+    AccessControlRequestPrivateNetwork,
+    Age,
+    Allow,
+    AltSvc,
+    CacheControl,
+    Connection,
+    ContentDigest,
+    ContentDisposition,
+    ContentEncoding,
+    ContentLanguage,
+    ContentLength,
+    ContentLocation,
+    ContentRange,
+    ContentSecurityPolicy,
+    ContentType,
+    Cookie,
+    CriticalCh,
+    CrossOriginEmbedderPolicy,
+    CrossOriginOpenerPolicy,
+    CrossOriginResourcePolicy,
+    Date,
+    ETag,
+    Expect,
+    Expires,
+    From,
+    Host,
+    IfMatch,
+    IfModifiedSince,
+    IfNoneMatch,
+    IfRange,
+    IfUnmodifiedSince,
+    InstanceDigest,
+    LastEventId,
+    LastModified,
+    Link,
+    Location,
+    Origin,
+    PermissionsPolicy,
+    Pragma,
+    Prefer,
+    PreferenceApplied,
+    Range,
+    RateLimit,
+    RateLimitPolicy,
+    Referer,
+    ReferrerPolicy,
+    ReprDigest,
+    RetryAfter,
+    SecFetchDest,
+    SecFetchMode,
+    SecFetchSite,
+    SecFetchUser,
+    SecWebSocketAccept,
+    SecWebSocketKey,
+    SecWebSocketProtocol,
+    SecWebSocketVersion,
+    Server,
+    ServerTiming,
+    SetCookie,
+    Signature,
+    SignatureInput,
+    StrictTransportSecurity,
+    Te,
+    TimingAllowOrigin,
+    Trailer,
+    TransferEncoding,
+    Upgrade,
+    UserAgent,
+    Vary,
+    WantContentDigest,
+    WantDigest,
+    WantReprDigest,
+    Warning,
+    XContentTypeOptions,
+    XForwardedFor,
+    XForwardedHost,
+    XForwardedProto,
+    XFrameOptions,
+}
+
+impl KnownHeader {
+    /// Return an iterator over the known headers of a `http::HeaderMap`,
+    /// decoding all values of each, in map order.
+    ///
+    /// Headers which are unknown to this crate, or which fail to decode, are
+    /// skipped. Use `TypedHeaders::decode` to obtain the error for a specific
+    /// header.
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use http::header::{HeaderMap, CONTENT_LENGTH, CONTENT_TYPE};
+    /// use hyperx::header::{ContentLength, KnownHeader};
+    ///
+    /// let mut hmap = HeaderMap::new();
+    /// hmap.insert(CONTENT_TYPE, "text/plain".parse().unwrap());
+    /// hmap.insert(CONTENT_LENGTH, "11".parse().unwrap());
+    /// hmap.insert("x-custom", "custom".parse().unwrap());
+    ///
+    /// let known: Vec<KnownHeader> = KnownHeader::decode_all(&hmap).collect();
+    /// assert_eq!(known.len(), 2);
+    /// match known[1] {
+    ///     KnownHeader::ContentLength(ref cl) => assert_eq!(*cl, ContentLength(11)),
+    ///     ref other => panic!("unexpected {:?}", other),
+    /// }
+    /// ```
+    pub fn decode_all(map: &HeaderMap) -> KnownHeaders<'_> {
+        KnownHeaders {
+            map,
+            keys: map.keys(),
+        }
+    }
+}
+
+/// Iterator over the decoded known headers of a `http::HeaderMap`.
+///
+/// Returned by [`KnownHeader::decode_all`](enum.KnownHeader.html#method.decode_all).
+#[derive(Debug)]
+pub struct KnownHeaders<'a> {
+    map: &'a HeaderMap,
+    keys: Keys<'a, ::http::HeaderValue>,
+}
+
+impl<'a> Iterator for KnownHeaders<'a> {
+    type Item = KnownHeader;
+
+    fn next(&mut self) -> Option<KnownHeader> {
+        let map = self.map;
+        self.keys
+            .by_ref()
+            .find_map(|name| KnownHeader::decode(map, name))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{known_types, KnownHeader};
+    use header::{ContentEncoding, Encoding, Raw, SetCookie};
+    use http::header::{HeaderMap, CONTENT_ENCODING, CONTENT_LENGTH, SET_COOKIE};
+    use std::fs;
+
+    #[test]
+    fn test_decode_all() {
+        let mut hmap = HeaderMap::new();
+        hmap.append(CONTENT_ENCODING, "gzip".parse().unwrap());
+        hmap.append(CONTENT_ENCODING, "chunked".parse().unwrap());
+        hmap.append(CONTENT_LENGTH, "eleven".parse().unwrap());
+        hmap.append(SET_COOKIE, "a=1".parse().unwrap());
+        hmap.append(SET_COOKIE, "b=2".parse().unwrap());
+        hmap.append("x-custom", "custom".parse().unwrap());
+
+        let known: Vec<_> = KnownHeader::decode_all(&hmap).collect();
+        assert_eq!(known.len(), 2);
+
+        assert_eq!(known[0].http_name(), CONTENT_ENCODING);
+        match known[0] {
+            KnownHeader::ContentEncoding(ref ce) => assert_eq!(
+                *ce,
+                ContentEncoding(smallvec![Encoding::Gzip, Encoding::Chunked])
+            ),
+            ref other => panic!("unexpected {:?}", other),
+        }
+
+        assert_eq!(known[1].http_name(), SET_COOKIE);
+        match known[1] {
+            KnownHeader::SetCookie(ref sc) => {
+                assert_eq!(
+                    *sc,
+                    SetCookie(vec!["a=1".parse().unwrap(), "b=2".parse().unwrap()])
+                )
+            }
+            ref other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(Raw::from_header(known[1].as_header()), ["a=1", "b=2"][..]);
+    }
+
+    #[test]
+    fn test_decode_all_empty() {
+        assert_eq!(KnownHeader::decode_all(&HeaderMap::new()).count(), 0);
+    }
+
+    #[test]
+    fn test_typed_headers_known() {
+        // Each type given to `header!` or `standard_header!`, and each
+        // non-generic `impl Header`, must be listed above
+        let known: Vec<&str> = known_types().iter().map(|k| k.0).collect();
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/src/header/common");
+        let mut found = (0, 0);
+        for entry in fs::read_dir(dir).unwrap() {
+            let source = fs::read_to_string(entry.unwrap().path()).unwrap();
+            for invocation in source.split("standard_header!(").skip(1) {
+                let ty = invocation.split(',').next().unwrap().trim();
+                assert!(known.contains(&ty), "{} is not known", ty);
+                found.0 += 1;
+            }
+            for line in source.lines().map(str::trim) {
+                // A `header!` arm, such as `(Age, "Age") => [u32]`
+                let arm = line
+                    .strip_prefix('(')
+                    .and_then(|l| l.split_once(", \""))
+                    .filter(|&(ty, rest)| {
+                        ty.chars().all(char::is_alphanumeric) && rest.contains("\") =>")
+                    });
+                let ty = if let Some((ty, _)) = arm {
+                    ty
+                } else if let Some(ty) = line.strip_prefix("impl Header for ") {
+                    ty.trim_end_matches(" {")
+                } else {
+                    continue;
+                };
+                assert!(known.contains(&ty), "{} is not known", ty);
+                found.1 += 1;
+            }
+        }
+        assert!(found.0 > 0 && found.1 > 0);
+    }
+}
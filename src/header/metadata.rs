@@ -0,0 +1,961 @@
+//! Metadata of known header fields, after the IANA HTTP Field Name Registry.
+
+use std::cmp::Ordering;
+
+use self::FieldKind::{List, Multiple, Singleton};
+use self::FieldStatus::{Deprecated, Obsoleted, Permanent, Provisional, Unregistered};
+
+/// The registration status of a header field, as in the IANA HTTP Field
+/// Name Registry.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FieldStatus {
+    /// A permanent registration, e.g. of an RFC or W3C/WHATWG standard.
+    Permanent,
+    /// A provisional registration, or a draft specification.
+    Provisional,
+    /// Deprecated, but still in use.
+    Deprecated,
+    /// Obsoleted by a later specification, and should not be sent.
+    Obsoleted,
+    /// Not registered, but in common use, such as `X-Forwarded-For`.
+    Unregistered,
+}
+
+/// How multiple lines of a header field combine.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FieldKind {
+    /// A single value; multiple lines are an error.
+    Singleton,
+    /// A comma separated list; multiple lines combine as one list.
+    List,
+    /// Multiple lines which can not be combined, such as `Set-Cookie`.
+    Multiple,
+}
+
+/// Metadata of a known header field.
+///
+/// Look up by name with [`FieldInfo::lookup`](#method.lookup), or iterate
+/// all known fields with [`FieldInfo::all`](#method.all):
+///
+/// ```
+/// use hyperx::header::{FieldInfo, FieldKind, FieldStatus};
+///
+/// let info = FieldInfo::lookup("content-length").unwrap();
+/// assert_eq!(info.name, "Content-Length");
+/// assert_eq!(info.kind, FieldKind::Singleton);
+/// assert!(!info.allowed_in_trailers());
+/// assert_eq!(info.type_name, Some("ContentLength"));
+///
+/// let info = FieldInfo::lookup("Warning").unwrap();
+/// assert_eq!(info.status, FieldStatus::Obsoleted);
+/// assert!(FieldInfo::lookup("X-Custom").is_none());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct FieldInfo {
+    /// The field name, in its registered case.
+    pub name: &'static str,
+    /// The registration status.
+    pub status: FieldStatus,
+    /// How multiple lines combine.
+    pub kind: FieldKind,
+    /// Whether the field applies only to the immediate connection, and is
+    /// not forwarded by proxies regardless of the `Connection` header.
+    pub hop_by_hop: bool,
+    /// Whether the field must not be sent in a trailer section, per
+    /// RFC 9110 §6.5.1.
+    pub forbidden_in_trailers: bool,
+    /// The name of the typed header of this crate, if any, such as
+    /// `ContentLength`.
+    pub type_name: Option<&'static str>,
+    /// The specification defining the field, such as
+    /// `RFC 9110, Section 8.6`.
+    pub reference: &'static str,
+}
+
+impl FieldInfo {
+    /// Look up a field by name, case-insensitively.
+    pub fn lookup(name: &str) -> Option<&'static FieldInfo> {
+        FIELDS
+            .binary_search_by(|f| cmp_ignore_case(f.name, name))
+            .ok()
+            .map(|i| &FIELDS[i])
+    }
+
+    /// All known fields, ordered by name case-insensitively.
+    pub fn all() -> &'static [FieldInfo] {
+        FIELDS
+    }
+
+    /// Returns true if the field may be sent in a trailer section.
+    pub fn allowed_in_trailers(&self) -> bool {
+        !self.forbidden_in_trailers
+    }
+
+    const fn hop_by_hop(self) -> FieldInfo {
+        FieldInfo {
+            hop_by_hop: true,
+            ..self
+        }
+    }
+
+    const fn not_in_trailers(self) -> FieldInfo {
+        FieldInfo {
+            forbidden_in_trailers: true,
+            ..self
+        }
+    }
+}
+
+fn cmp_ignore_case(a: &str, b: &str) -> Ordering {
+    let a = a.bytes().map(|b| b.to_ascii_lowercase());
+    a.cmp(b.bytes().map(|b| b.to_ascii_lowercase()))
+}
+
+const fn field(
+    name: &'static str,
+    status: FieldStatus,
+    kind: FieldKind,
+    type_name: Option<&'static str>,
+    reference: &'static str,
+) -> FieldInfo {
+    FieldInfo {
+        name,
+        status,
+        kind,
+        hop_by_hop: false,
+        forbidden_in_trailers: false,
+        type_name,
+        reference,
+    }
+}
+
+// Ordered by name, case-insensitively, for binary search.
+static FIELDS: &[FieldInfo] = &[
+    field(
+        "Accept",
+        Permanent,
+        List,
+        Some("Accept"),
+        "RFC 9110, Section 12.5.1",
+    ),
+    field(
+        "Accept-CH",
+        Permanent,
+        List,
+        Some("AcceptCh"),
+        "RFC 8942, Section 3.1",
+    ),
+    field(
+        "Accept-Charset",
+        Deprecated,
+        List,
+        Some("AcceptCharset"),
+        "RFC 9110, Section 12.5.2",
+    ),
+    field(
+        "Accept-Encoding",
+        Permanent,
+        List,
+        Some("AcceptEncoding"),
+        "RFC 9110, Section 12.5.3",
+    ),
+    field(
+        "Accept-Language",
+        Permanent,
+        List,
+        Some("AcceptLanguage"),
+        "RFC 9110, Section 12.5.4",
+    ),
+    field(
+        "Accept-Patch",
+        Permanent,
+        List,
+        None,
+        "RFC 5789, Section 3.1",
+    ),
+    field(
+        "Accept-Ranges",
+        Permanent,
+        List,
+        Some("AcceptRanges"),
+        "RFC 9110, Section 14.3",
+    ),
+    field(
+        "Accept-Signature",
+        Permanent,
+        List,
+        Some("AcceptSignature"),
+        "RFC 9421, Section 5.1",
+    ),
+    field(
+        "Access-Control-Allow-Credentials",
+        Permanent,
+        Singleton,
+        Some("AccessControlAllowCredentials"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Allow-Headers",
+        Permanent,
+        List,
+        Some("AccessControlAllowHeaders"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Allow-Methods",
+        Permanent,
+        List,
+        Some("AccessControlAllowMethods"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Allow-Origin",
+        Permanent,
+        Singleton,
+        Some("AccessControlAllowOrigin"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Allow-Private-Network",
+        Provisional,
+        Singleton,
+        Some("AccessControlAllowPrivateNetwork"),
+        "Private Network Access",
+    ),
+    field(
+        "Access-Control-Expose-Headers",
+        Permanent,
+        List,
+        Some("AccessControlExposeHeaders"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Max-Age",
+        Permanent,
+        Singleton,
+        Some("AccessControlMaxAge"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Request-Headers",
+        Permanent,
+        List,
+        Some("AccessControlRequestHeaders"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Request-Method",
+        Permanent,
+        Singleton,
+        Some("AccessControlRequestMethod"),
+        "Fetch",
+    ),
+    field(
+        "Access-Control-Request-Private-Network",
+        Provisional,
+        Singleton,
+        Some("AccessControlRequestPrivateNetwork"),
+        "Private Network Access",
+    ),
+    field(
+        "Age",
+        Permanent,
+        Singleton,
+        Some("Age"),
+        "RFC 9111, Section 5.1",
+    )
+    .not_in_trailers(),
+    field(
+        "Allow",
+        Permanent,
+        List,
+        Some("Allow"),
+        "RFC 9110, Section 10.2.1",
+    ),
+    field(
+        "Alt-Svc",
+        Permanent,
+        List,
+        Some("AltSvc"),
+        "RFC 7838, Section 3",
+    ),
+    field(
+        "Authorization",
+        Permanent,
+        Singleton,
+        Some("Authorization"),
+        "RFC 9110, Section 11.6.2",
+    )
+    .not_in_trailers(),
+    field(
+        "Cache-Control",
+        Permanent,
+        List,
+        Some("CacheControl"),
+        "RFC 9111, Section 5.2",
+    )
+    .not_in_trailers(),
+    field("Clear-Site-Data", Permanent, List, None, "Clear Site Data"),
+    field(
+        "Connection",
+        Permanent,
+        List,
+        Some("Connection"),
+        "RFC 9110, Section 7.6.1",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Content-Digest",
+        Permanent,
+        List,
+        Some("ContentDigest"),
+        "RFC 9530, Section 2",
+    ),
+    field(
+        "Content-Disposition",
+        Permanent,
+        Singleton,
+        Some("ContentDisposition"),
+        "RFC 6266",
+    ),
+    field(
+        "Content-Encoding",
+        Permanent,
+        List,
+        Some("ContentEncoding"),
+        "RFC 9110, Section 8.4",
+    )
+    .not_in_trailers(),
+    field(
+        "Content-Language",
+        Permanent,
+        List,
+        Some("ContentLanguage"),
+        "RFC 9110, Section 8.5",
+    ),
+    field(
+        "Content-Length",
+        Permanent,
+        Singleton,
+        Some("ContentLength"),
+        "RFC 9110, Section 8.6",
+    )
+    .not_in_trailers(),
+    field(
+        "Content-Location",
+        Permanent,
+        Singleton,
+        Some("ContentLocation"),
+        "RFC 9110, Section 8.7",
+    ),
+    field(
+        "Content-Range",
+        Permanent,
+        Singleton,
+        Some("ContentRange"),
+        "RFC 9110, Section 14.4",
+    )
+    .not_in_trailers(),
+    field(
+        "Content-Security-Policy",
+        Permanent,
+        List,
+        Some("ContentSecurityPolicy"),
+        "Content Security Policy Level 3",
+    ),
+    field(
+        "Content-Type",
+        Permanent,
+        Singleton,
+        Some("ContentType"),
+        "RFC 9110, Section 8.3",
+    )
+    .not_in_trailers(),
+    field(
+        "Cookie",
+        Permanent,
+        Singleton,
+        Some("Cookie"),
+        "RFC 6265, Section 5.4",
+    )
+    .not_in_trailers(),
+    field(
+        "Critical-CH",
+        Provisional,
+        List,
+        Some("CriticalCh"),
+        "Client Hint Reliability",
+    ),
+    field(
+        "Cross-Origin-Embedder-Policy",
+        Permanent,
+        Singleton,
+        Some("CrossOriginEmbedderPolicy"),
+        "HTML",
+    ),
+    field(
+        "Cross-Origin-Opener-Policy",
+        Permanent,
+        Singleton,
+        Some("CrossOriginOpenerPolicy"),
+        "HTML",
+    ),
+    field(
+        "Cross-Origin-Resource-Policy",
+        Permanent,
+        Singleton,
+        Some("CrossOriginResourcePolicy"),
+        "Fetch",
+    ),
+    field(
+        "Date",
+        Permanent,
+        Singleton,
+        Some("Date"),
+        "RFC 9110, Section 6.6.1",
+    )
+    .not_in_trailers(),
+    field(
+        "Digest",
+        Obsoleted,
+        List,
+        Some("InstanceDigest"),
+        "RFC 3230",
+    ),
+    field(
+        "ETag",
+        Permanent,
+        Singleton,
+        Some("ETag"),
+        "RFC 9110, Section 8.8.3",
+    ),
+    field(
+        "Expect",
+        Permanent,
+        List,
+        Some("Expect"),
+        "RFC 9110, Section 10.1.1",
+    )
+    .not_in_trailers(),
+    field(
+        "Expires",
+        Permanent,
+        Singleton,
+        Some("Expires"),
+        "RFC 9111, Section 5.3",
+    )
+    .not_in_trailers(),
+    field("Forwarded", Permanent, List, None, "RFC 7239, Section 4"),
+    field(
+        "From",
+        Permanent,
+        Singleton,
+        Some("From"),
+        "RFC 9110, Section 10.1.2",
+    ),
+    field(
+        "Host",
+        Permanent,
+        Singleton,
+        Some("Host"),
+        "RFC 9110, Section 7.2",
+    )
+    .not_in_trailers(),
+    field(
+        "If-Match",
+        Permanent,
+        List,
+        Some("IfMatch"),
+        "RFC 9110, Section 13.1.1",
+    )
+    .not_in_trailers(),
+    field(
+        "If-Modified-Since",
+        Permanent,
+        Singleton,
+        Some("IfModifiedSince"),
+        "RFC 9110, Section 13.1.3",
+    )
+    .not_in_trailers(),
+    field(
+        "If-None-Match",
+        Permanent,
+        List,
+        Some("IfNoneMatch"),
+        "RFC 9110, Section 13.1.2",
+    )
+    .not_in_trailers(),
+    field(
+        "If-Range",
+        Permanent,
+        Singleton,
+        Some("IfRange"),
+        "RFC 9110, Section 13.1.5",
+    )
+    .not_in_trailers(),
+    field(
+        "If-Unmodified-Since",
+        Permanent,
+        Singleton,
+        Some("IfUnmodifiedSince"),
+        "RFC 9110, Section 13.1.4",
+    )
+    .not_in_trailers(),
+    field(
+        "Keep-Alive",
+        Permanent,
+        List,
+        None,
+        "RFC 2068, Section 19.7.1.1",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Last-Event-ID",
+        Permanent,
+        Singleton,
+        Some("LastEventId"),
+        "HTML",
+    ),
+    field(
+        "Last-Modified",
+        Permanent,
+        Singleton,
+        Some("LastModified"),
+        "RFC 9110, Section 8.8.2",
+    ),
+    field("Link", Permanent, List, Some("Link"), "RFC 8288, Section 3"),
+    field(
+        "Location",
+        Permanent,
+        Singleton,
+        Some("Location"),
+        "RFC 9110, Section 10.2.2",
+    )
+    .not_in_trailers(),
+    field(
+        "Max-Forwards",
+        Permanent,
+        Singleton,
+        None,
+        "RFC 9110, Section 7.6.2",
+    )
+    .not_in_trailers(),
+    field(
+        "Origin",
+        Permanent,
+        Singleton,
+        Some("Origin"),
+        "RFC 6454, Section 7",
+    ),
+    field(
+        "Permissions-Policy",
+        Provisional,
+        Singleton,
+        Some("PermissionsPolicy"),
+        "Permissions Policy",
+    ),
+    field(
+        "Pragma",
+        Deprecated,
+        List,
+        Some("Pragma"),
+        "RFC 9111, Section 5.4",
+    )
+    .not_in_trailers(),
+    field(
+        "Prefer",
+        Permanent,
+        List,
+        Some("Prefer"),
+        "RFC 7240, Section 2",
+    ),
+    field(
+        "Preference-Applied",
+        Permanent,
+        List,
+        Some("PreferenceApplied"),
+        "RFC 7240, Section 3",
+    ),
+    field(
+        "Proxy-Authenticate",
+        Permanent,
+        List,
+        None,
+        "RFC 9110, Section 11.7.1",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Proxy-Authorization",
+        Permanent,
+        Singleton,
+        Some("ProxyAuthorization"),
+        "RFC 9110, Section 11.7.2",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Proxy-Connection",
+        Obsoleted,
+        List,
+        None,
+        "RFC 9112, Appendix C.2.2",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Range",
+        Permanent,
+        Singleton,
+        Some("Range"),
+        "RFC 9110, Section 14.2",
+    )
+    .not_in_trailers(),
+    field(
+        "RateLimit",
+        Provisional,
+        List,
+        Some("RateLimit"),
+        "RateLimit header fields for HTTP",
+    ),
+    field(
+        "RateLimit-Policy",
+        Provisional,
+        List,
+        Some("RateLimitPolicy"),
+        "RateLimit header fields for HTTP",
+    ),
+    field(
+        "Referer",
+        Permanent,
+        Singleton,
+        Some("Referer"),
+        "RFC 9110, Section 10.1.3",
+    ),
+    field(
+        "Referrer-Policy",
+        Permanent,
+        List,
+        Some("ReferrerPolicy"),
+        "Referrer Policy",
+    ),
+    field(
+        "Repr-Digest",
+        Permanent,
+        List,
+        Some("ReprDigest"),
+        "RFC 9530, Section 3",
+    ),
+    field(
+        "Retry-After",
+        Permanent,
+        Singleton,
+        Some("RetryAfter"),
+        "RFC 9110, Section 10.2.3",
+    )
+    .not_in_trailers(),
+    field(
+        "Sec-Fetch-Dest",
+        Permanent,
+        Singleton,
+        Some("SecFetchDest"),
+        "Fetch Metadata Request Headers",
+    ),
+    field(
+        "Sec-Fetch-Mode",
+        Permanent,
+        Singleton,
+        Some("SecFetchMode"),
+        "Fetch Metadata Request Headers",
+    ),
+    field(
+        "Sec-Fetch-Site",
+        Permanent,
+        Singleton,
+        Some("SecFetchSite"),
+        "Fetch Metadata Request Headers",
+    ),
+    field(
+        "Sec-Fetch-User",
+        Permanent,
+        Singleton,
+        Some("SecFetchUser"),
+        "Fetch Metadata Request Headers",
+    ),
+    field(
+        "Sec-WebSocket-Accept",
+        Permanent,
+        Singleton,
+        Some("SecWebSocketAccept"),
+        "RFC 6455, Section 11.3.3",
+    ),
+    field(
+        "Sec-WebSocket-Extensions",
+        Permanent,
+        List,
+        None,
+        "RFC 6455, Section 11.3.2",
+    ),
+    field(
+        "Sec-WebSocket-Key",
+        Permanent,
+        Singleton,
+        Some("SecWebSocketKey"),
+        "RFC 6455, Section 11.3.1",
+    ),
+    field(
+        "Sec-WebSocket-Protocol",
+        Permanent,
+        List,
+        Some("SecWebSocketProtocol"),
+        "RFC 6455, Section 11.3.4",
+    ),
+    field(
+        "Sec-WebSocket-Version",
+        Permanent,
+        List,
+        Some("SecWebSocketVersion"),
+        "RFC 6455, Section 11.3.5",
+    ),
+    field(
+        "Server",
+        Permanent,
+        Singleton,
+        Some("Server"),
+        "RFC 9110, Section 10.2.4",
+    ),
+    field(
+        "Server-Timing",
+        Permanent,
+        List,
+        Some("ServerTiming"),
+        "Server Timing",
+    ),
+    field(
+        "Set-Cookie",
+        Permanent,
+        Multiple,
+        Some("SetCookie"),
+        "RFC 6265, Section 4.1",
+    )
+    .not_in_trailers(),
+    field(
+        "Signature",
+        Permanent,
+        List,
+        Some("Signature"),
+        "RFC 9421, Section 4.2",
+    ),
+    field(
+        "Signature-Input",
+        Permanent,
+        List,
+        Some("SignatureInput"),
+        "RFC 9421, Section 4.1",
+    ),
+    field(
+        "Strict-Transport-Security",
+        Permanent,
+        Singleton,
+        Some("StrictTransportSecurity"),
+        "RFC 6797, Section 6.1",
+    ),
+    field(
+        "TE",
+        Permanent,
+        List,
+        Some("Te"),
+        "RFC 9110, Section 10.1.4",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Timing-Allow-Origin",
+        Permanent,
+        List,
+        Some("TimingAllowOrigin"),
+        "Resource Timing",
+    ),
+    field(
+        "Trailer",
+        Permanent,
+        List,
+        Some("Trailer"),
+        "RFC 9110, Section 6.6.2",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Transfer-Encoding",
+        Permanent,
+        List,
+        Some("TransferEncoding"),
+        "RFC 9112, Section 6.1",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "Upgrade",
+        Permanent,
+        List,
+        Some("Upgrade"),
+        "RFC 9110, Section 7.8",
+    )
+    .hop_by_hop()
+    .not_in_trailers(),
+    field(
+        "User-Agent",
+        Permanent,
+        Singleton,
+        Some("UserAgent"),
+        "RFC 9110, Section 10.1.5",
+    ),
+    field(
+        "Vary",
+        Permanent,
+        List,
+        Some("Vary"),
+        "RFC 9110, Section 12.5.5",
+    )
+    .not_in_trailers(),
+    field("Via", Permanent, List, None, "RFC 9110, Section 7.6.3"),
+    field(
+        "Want-Content-Digest",
+        Permanent,
+        List,
+        Some("WantContentDigest"),
+        "RFC 9530, Section 4",
+    ),
+    field(
+        "Want-Digest",
+        Obsoleted,
+        List,
+        Some("WantDigest"),
+        "RFC 3230",
+    ),
+    field(
+        "Want-Repr-Digest",
+        Permanent,
+        List,
+        Some("WantReprDigest"),
+        "RFC 9530, Section 4",
+    ),
+    field(
+        "Warning",
+        Obsoleted,
+        List,
+        Some("Warning"),
+        "RFC 9111, Section 5.5",
+    )
+    .not_in_trailers(),
+    field(
+        "WWW-Authenticate",
+        Permanent,
+        List,
+        None,
+        "RFC 9110, Section 11.6.1",
+    )
+    .not_in_trailers(),
+    field(
+        "X-Content-Type-Options",
+        Permanent,
+        Singleton,
+        Some("XContentTypeOptions"),
+        "Fetch",
+    ),
+    field(
+        "X-Forwarded-For",
+        Unregistered,
+        List,
+        Some("XForwardedFor"),
+        "de facto",
+    ),
+    field(
+        "X-Forwarded-Host",
+        Unregistered,
+        List,
+        Some("XForwardedHost"),
+        "de facto",
+    ),
+    field(
+        "X-Forwarded-Proto",
+        Unregistered,
+        List,
+        Some("XForwardedProto"),
+        "de facto",
+    ),
+    field(
+        "X-Frame-Options",
+        Permanent,
+        Singleton,
+        Some("XFrameOptions"),
+        "RFC 7034, Section 2",
+    ),
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::known::known_types;
+
+    #[test]
+    fn test_sorted() {
+        for pair in FIELDS.windows(2) {
+            assert_eq!(
+                cmp_ignore_case(pair[0].name, pair[1].name),
+                Ordering::Less,
+                "{}",
+                pair[1].name
+            );
+        }
+        for info in FieldInfo::all() {
+            assert_eq!(FieldInfo::lookup(&info.name.to_uppercase()), Some(info));
+        }
+    }
+
+    #[test]
+    fn test_consistent() {
+        // RFC 9110 §7.6.1, besides any `Proxy-*` field
+        let hop_by_hop = [
+            "connection",
+            "keep-alive",
+            "te",
+            "trailer",
+            "transfer-encoding",
+            "upgrade",
+        ];
+        for info in FieldInfo::all() {
+            let name = info.name.to_ascii_lowercase();
+            assert_eq!(
+                info.hop_by_hop,
+                hop_by_hop.contains(&&*name) || name.starts_with("proxy-"),
+                "{}",
+                info.name
+            );
+            if info.hop_by_hop {
+                assert!(info.forbidden_in_trailers, "{}", info.name);
+            }
+        }
+    }
+
+    #[test]
+    fn test_type_names() {
+        let known = known_types();
+        for &(ty, ref name) in &known {
+            let info = FieldInfo::lookup(name.as_str());
+            let info = info.unwrap_or_else(|| panic!("{} is not in FIELDS", name));
+            assert_eq!(info.type_name, Some(ty), "{}", info.name);
+        }
+        for info in FieldInfo::all() {
+            if let Some(ty) = info.type_name {
+                // Generic headers are not known
+                let generic = ["Authorization", "ProxyAuthorization"].contains(&ty);
+                assert!(generic || known.iter().any(|k| k.0 == ty), "{}", ty);
+            }
+        }
+    }
+}
@@ -23,7 +23,9 @@
 //!
 //! `HeaderValue`(s) implement the `RawLike` trait which allows for parsing
 //! with less copying than going through `HeaderValue::to_str` or the `Raw`
-//! type. See example usage below:
+//! type: the bytes of the values are borrowed, and not scanned again for
+//! invalid bytes. `Header::decode_value` parses a single value. See example
+//! usage below:
 //!
 //! ```
 //! # extern crate hyperx;
@@ -40,13 +42,13 @@
 //! // Parse the first header value
 //! let first = hmap.get(CONTENT_ENCODING).unwrap();
 //! let ce = ContentEncoding::parse_header(&first)?;
-//! assert_eq!(ce, ContentEncoding(vec![Encoding::Chunked, Encoding::Gzip]));
+//! assert_eq!(ce, ContentEncoding(vec![Encoding::Chunked, Encoding::Gzip].into()));
 //!
 //! // Parse all header values to a single list
 //! let all = hmap.get_all(CONTENT_ENCODING);
 //! let ce = ContentEncoding::parse_header(&all)?;
 //! assert_eq!(ce, ContentEncoding(
-//!     vec![Encoding::Chunked, Encoding::Gzip, Encoding::Identity]
+//!     vec![Encoding::Chunked, Encoding::Gzip, Encoding::Identity].into()
 //! ));
 //! # Ok(())
 //! # }
@@ -70,13 +72,13 @@
 //!
 //! let mut hmap = http::HeaderMap::new();
 //! hmap.encode(
-//!     &ContentEncoding(vec![Encoding::Identity]));
+//!     &ContentEncoding(vec![Encoding::Identity].into()));
 //! hmap.encode_append(
-//!     &ContentEncoding(vec![Encoding::Gzip, Encoding::Chunked]));
+//!     &ContentEncoding(vec![Encoding::Gzip, Encoding::Chunked].into()));
 //! let ce: ContentEncoding = hmap.decode()?;
 //! assert_eq!(
-//!     *ce,
-//!     vec![Encoding::Identity, Encoding::Gzip, Encoding::Chunked]
+//!     ce[..],
+//!     [Encoding::Identity, Encoding::Gzip, Encoding::Chunked]
 //! );
 //! # Ok(())
 //! # }
@@ -85,12 +87,50 @@
 //! # }
 //! ```
 //!
+//! ## Converting single `http::header::HeaderValue`s
+//!
+//! All typed headers implement `TryFrom<&HeaderValue>`, and `HeaderValue`
+//! implements `TryFrom` for typed headers (and references to them), so they
+//! may be passed directly to the _http_ crate builders:
+//!
+//! ```
+//! # extern crate hyperx;
+//! # extern crate http;
+//! # fn run() -> Result<(), Box<std::error::Error>> {
+//! use std::convert::TryFrom;
+//! use http::header::CONTENT_TYPE;
+//! use hyperx::header::ContentType;
+//!
+//! let res = http::Response::builder()
+//!     .header(CONTENT_TYPE, ContentType::json())
+//!     .body(())?;
+//!
+//! let ct = ContentType::try_from(&res.headers()[CONTENT_TYPE])?;
+//! assert_eq!(ct, ContentType::json());
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+//!
+//! ## Serde
+//!
+//! With the `serde` feature, all typed headers (including those defined via
+//! `header!` or the `derive` feature) implement `Serialize` and
+//! `Deserialize` in their string form, as a sequence of strings for headers
+//! formatted to multiple lines, like `SetCookie`. With the `headers` feature
+//! as well, the `Headers` collection is (de)serialized as an ordered map of
+//! header names to sequences of value strings.
+//!
 //! ## Defining Custom Headers
 //!
 //! Hyper*x* provides all commonly used headers in HTTP. If you
 //! need to define a custom header, it's easy to do while still taking
 //! advantage of the type system. Hyper*x* includes a `header!` macro for
-//! defining many wrapper-style headers.
+//! defining many wrapper-style headers. Like the included headers, these
+//! also implement `Display`, `FromStr` (parsing a single header value) and
+//! conversions from and to `http::header::HeaderValue`.
 //!
 //! ```
 //! # extern crate http;
@@ -140,7 +180,7 @@
 //!                 }
 //!             }
 //!         }
-//!         Err(hyperx::Error::Header)
+//!         Err(hyperx::Error::header(hyperx::HeaderErrorKind::Token))
 //!     }
 //!
 //!     fn fmt_header(&self, f: &mut header::Formatter) -> fmt::Result {
@@ -159,15 +199,21 @@ use unicase::Ascii;
 
 use self::sealed::HeaderClone;
 
+pub use self::borrowed::HeaderRef;
 pub use self::common::*;
-pub use self::raw::{Raw, RawLike};
+pub use self::raw::{Raw, RawLike, RawLines, RawSlice};
 pub use self::shared::*;
+pub use self::value_writer::ValueWriter;
+
+/// Proactive content negotiation, re-exported from the crate root.
+pub use negotiate;
 
 #[cfg(feature = "headers")]
 use std::{
     borrow::ToOwned,
+    io,
     iter::{FromIterator, IntoIterator},
-    mem,
+    str,
 };
 
 #[cfg(feature = "headers")]
@@ -176,14 +222,222 @@ use self::internals::{Entry, Item, VecMap};
 #[cfg(feature = "headers")]
 use bytes::Bytes;
 
-pub use self::compat::{StandardHeader, TypedHeaders, ValueMapIter};
+#[cfg(feature = "axum")]
+pub use self::axum_compat::{Typed, TypedHeaderRejection};
+pub use self::compat::{CachedHeaders, StandardHeader, TypedHeaders, ValueMapIter, ValueSliceIter};
+#[cfg(feature = "http02")]
+pub use self::http02_compat::Http02ValueMapIter;
+pub use self::known::{KnownHeader, KnownHeaders};
+pub use self::metadata::{FieldInfo, FieldKind, FieldStatus};
+pub use self::registry::HeaderRegistry;
+
+#[cfg(feature = "httparse")]
+pub use self::httparse_compat::{HttparseLines, HttparseRaw};
+
+/// Derive `Header`, `Display` and `FromStr` for a newtype or enum header.
+///
+/// Requires the `derive` feature. The header name is given via
+/// `#[header(name = "...")]`, and a `Vec<T>` field with the `csv` flag is
+/// parsed and formatted as a comma delimited list:
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::{Header, TypedHeaders};
+/// use std::convert::TryFrom;
+///
+/// #[derive(Clone, Debug, PartialEq, Header)]
+/// #[header(name = "X-Request-Guid")]
+/// struct XRequestGuid(String);
+///
+/// #[derive(Clone, Debug, PartialEq, Header)]
+/// #[header(name = "X-Features", csv)]
+/// struct XFeatures(Vec<String>);
+///
+/// # fn main() {
+/// let mut headers = http::HeaderMap::new();
+/// headers.encode(&XRequestGuid("a proper guid".to_owned()));
+/// headers.append("x-features", "a, b".parse().unwrap());
+/// headers.append("x-features", "c".parse().unwrap());
+///
+/// assert_eq!(
+///     headers.decode::<XRequestGuid>().unwrap(),
+///     XRequestGuid("a proper guid".to_owned())
+/// );
+/// let features = headers.decode::<XFeatures>().unwrap();
+/// assert_eq!(features.0, ["a", "b", "c"]);
+/// assert_eq!(features.to_string(), "a, b, c");
+/// assert_eq!("d, e".parse::<XFeatures>().unwrap().0, ["d", "e"]);
+/// assert_eq!(XRequestGuid::http_name(), "x-request-guid");
+///
+/// let value = http::HeaderValue::from_static("guid");
+/// assert_eq!(XRequestGuid::try_from(&value).unwrap().0, "guid");
+/// let guid = XRequestGuid("guid".to_owned());
+/// assert_eq!(http::HeaderValue::try_from(&guid).unwrap(), value);
+/// # }
+/// ```
+///
+/// An enum of unit variants is parsed from, and formatted as, the value of
+/// each variant, given via `#[header(value = "...")]` or otherwise its name,
+/// ignoring ASCII case when parsing. Other types implementing `FromStr` and
+/// `Display` themselves may be derived with the `from_str` flag:
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::Header;
+/// use std::fmt;
+/// use std::str::FromStr;
+///
+/// #[derive(Clone, Debug, PartialEq, Header)]
+/// #[header(name = "X-Org-Tier")]
+/// enum XOrgTier {
+///     #[header(value = "free")]
+///     Free,
+///     #[header(value = "pro")]
+///     Pro,
+///     Enterprise,
+/// }
+///
+/// #[derive(Clone, Debug, PartialEq, Header)]
+/// #[header(name = "X-Org-Quota", from_str)]
+/// struct XOrgQuota {
+///     used: u32,
+///     limit: u32,
+/// }
+///
+/// impl FromStr for XOrgQuota {
+///     type Err = std::num::ParseIntError;
+///
+///     fn from_str(s: &str) -> Result<XOrgQuota, Self::Err> {
+///         let (used, limit) = s.split_once('/').unwrap_or((s, ""));
+///         Ok(XOrgQuota { used: used.parse()?, limit: limit.parse()? })
+///     }
+/// }
+///
+/// impl fmt::Display for XOrgQuota {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         write!(f, "{}/{}", self.used, self.limit)
+///     }
+/// }
+///
+/// # fn main() {
+/// assert_eq!("PRO".parse::<XOrgTier>().unwrap(), XOrgTier::Pro);
+/// assert_eq!(XOrgTier::Enterprise.encode_to_value().unwrap(), "Enterprise");
+/// assert!("gold".parse::<XOrgTier>().is_err());
+///
+/// let value = http::HeaderValue::from_static("3/10");
+/// let quota = XOrgQuota::decode_value(&value).unwrap();
+/// assert_eq!(quota, XOrgQuota { used: 3, limit: 10 });
+/// assert_eq!(quota.encode_to_value().unwrap(), "3/10");
+/// # }
+/// ```
+#[cfg(feature = "derive")]
+pub use hyperx_derive::Header;
+
+// Paths used by macro and derive generated code.
+#[doc(hidden)]
+pub mod __private {
+    pub use http::header::{HeaderName, HeaderValue, InvalidHeaderValue};
+
+    #[cfg(feature = "http02")]
+    pub use super::http02_compat::to_value as to_value02;
+    #[cfg(feature = "serde")]
+    pub use super::serde_compat::{deserialize, serialize};
+    #[cfg(feature = "http02")]
+    pub use http02::header::{
+        HeaderValue as HeaderValue02, InvalidHeaderValue as InvalidHeaderValue02,
+    };
+    #[cfg(feature = "proptest")]
+    pub mod proptest {
+        pub use proptest::*;
+    }
+    #[cfg(feature = "serde")]
+    pub use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::{Header, Joined, ValueWriter};
+
+    // The ASCII lowercase of a header name, for `HeaderName::from_static`.
+    pub const fn lowercase<const N: usize>(name: &str) -> [u8; N] {
+        let name = name.as_bytes();
+        let mut lower = [0; N];
+        let mut i = 0;
+        while i < N {
+            lower[i] = name[i].to_ascii_lowercase();
+            i += 1;
+        }
+        lower
+    }
+
+    pub const fn as_str(bytes: &[u8]) -> &str {
+        match ::std::str::from_utf8(bytes) {
+            Ok(s) => s,
+            Err(_) => panic!("header name is not UTF-8"),
+        }
+    }
+
+    pub fn to_value<H: Header + ?Sized>(header: &H) -> Result<HeaderValue, InvalidHeaderValue> {
+        use std::fmt::Write;
+        let mut writer = ValueWriter::new();
+        if !super::is_joinable(header) || write!(writer, "{}", Joined(header)).is_err() {
+            return Err(super::value_writer::format_failed());
+        }
+        writer.into_value()
+    }
+}
 
+#[cfg(feature = "proptest")]
+mod arbitrary;
+#[cfg(feature = "axum")]
+mod axum_compat;
+mod borrowed;
 mod common;
 mod compat;
+#[cfg(feature = "cookie")]
+mod cookie_compat;
+#[cfg(feature = "http02")]
+mod http02_compat;
+#[cfg(feature = "httparse")]
+mod httparse_compat;
 mod internals;
+mod interned;
+mod known;
+mod metadata;
 pub mod parsing;
 mod raw;
+mod registry;
+#[cfg(feature = "serde")]
+mod serde_compat;
+pub mod sfv;
 mod shared;
+pub mod testing;
+mod value_writer;
+
+/// The items of a list header, such as `Accept` or `Vary`, as generated by
+/// `header!`.
+///
+/// Up to four items, enough for most real-world values, are stored inline
+/// without allocating. Construct one from a `Vec` via `into()`, by
+/// collecting an iterator, or via the `smallvec!` macro of the re-exported
+/// _smallvec_ crate.
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::{Allow, HeaderVec};
+/// use hyperx::smallvec::smallvec;
+/// use hyperx::Method;
+///
+/// # fn main() {
+/// let allow = Allow(vec![Method::Get, Method::Head].into());
+/// assert_eq!(allow.to_string(), "GET, HEAD");
+/// assert!(!allow.spilled());
+///
+/// let items: HeaderVec<Method> = smallvec![Method::Post];
+/// assert_eq!(Allow(items).to_string(), "POST");
+/// # }
+/// ```
+pub type HeaderVec<T> = ::smallvec::SmallVec<[T; 4]>;
 
 /// A trait for any object that will represent a header field and value.
 ///
@@ -197,6 +451,32 @@ pub trait Header: 'static + HeaderClone + Send + Sync {
     where
         Self: Sized;
 
+    /// Returns the name of the header field as an `http::header::HeaderName`,
+    /// for use as a `http::HeaderMap` key.
+    ///
+    /// The default implementation converts `header_name()` on each call,
+    /// which is free of allocation for the standard names known to the
+    /// _http_ crate, but otherwise copies. The headers of this crate, and
+    /// those of the `header!` macro or derived via the `derive` feature,
+    /// instead return a constant converted once, at compile time.
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use hyperx::header::{ContentLength, Header};
+    ///
+    /// # fn main() {
+    /// assert_eq!(ContentLength::http_name(), http::header::CONTENT_LENGTH);
+    /// # }
+    /// ```
+    fn http_name() -> ::http::header::HeaderName
+    where
+        Self: Sized,
+    {
+        ::http::header::HeaderName::from_bytes(Self::header_name().as_bytes())
+            .expect("valid header name")
+    }
+
     /// Parse a header from a raw stream of bytes.
     ///
     /// It's possible that a request can include a header field more than once,
@@ -223,25 +503,145 @@ pub trait Header: 'static + HeaderClone + Send + Sync {
     /// cookie being set be specified in a separate line. Almost every other
     /// case should only format as 1 single line.
     fn fmt_header(&self, f: &mut Formatter) -> fmt::Result;
+
+    /// Format this header directly into a single `http::header::HeaderValue`.
+    ///
+    /// Multiple lines written by `fmt_header` are joined with `", "`. An
+    /// error of kind `ErrorKind::Conversion` is returned if formatting fails,
+    /// or if the formatted value contains bytes not allowed in a header
+    /// value. It is also returned for multiple lines of a field which can
+    /// not be combined, of `FieldKind::Multiple` such as `Set-Cookie`: encode
+    /// these as separate values via `TypedHeaders::encode`.
+    ///
+    /// ```
+    /// use hyperx::header::{ContentLength, Header, SetCookie};
+    ///
+    /// let value = ContentLength(11).encode_to_value().unwrap();
+    /// assert_eq!(value, "11");
+    ///
+    /// let cookies = SetCookie(vec!["a=1".parse().unwrap(), "b=2".parse().unwrap()]);
+    /// assert!(cookies.encode_to_value().is_err());
+    /// ```
+    fn encode_to_value(&self) -> ::Result<::http::header::HeaderValue> {
+        check_joinable(self)?;
+        value_writer::format_value(format_args!("{}", Joined(self)))
+    }
+
+    /// Format this header as a single value, appended to the end of `buf`.
+    ///
+    /// Multiple lines written by `fmt_header` are joined with `", "`, as for
+    /// `encode_to_value`. The buffer can be reused across headers, with each
+    /// value split off and frozen into a `HeaderValue` without copying. An
+    /// error of kind `ErrorKind::Conversion` is returned, with `buf` left
+    /// unchanged, if formatting fails, if the formatted value contains bytes
+    /// not allowed in a header value, or for multiple lines which can not be
+    /// combined, as for `encode_to_value`.
+    ///
+    /// ```
+    /// # extern crate bytes;
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use bytes::BytesMut;
+    /// use http::header::HeaderValue;
+    /// use hyperx::header::{ContentLength, Header, Host};
+    ///
+    /// # fn main() {
+    /// let mut buf = BytesMut::with_capacity(64);
+    /// ContentLength(11).write_value(&mut buf).unwrap();
+    /// let length = HeaderValue::from_maybe_shared(buf.split().freeze()).unwrap();
+    /// assert_eq!(length, "11");
+    ///
+    /// Host::new("example.com", None).write_value(&mut buf).unwrap();
+    /// let host = HeaderValue::from_maybe_shared(buf.split().freeze()).unwrap();
+    /// assert_eq!(host, "example.com");
+    /// # }
+    /// ```
+    fn write_value(&self, buf: &mut ::bytes::BytesMut) -> ::Result<()> {
+        check_joinable(self)?;
+        value_writer::append_value(buf, format_args!("{}", Joined(self)))
+    }
+
+    /// Parse this header directly from a single `http::header::HeaderValue`,
+    /// the inverse of `encode_to_value`.
+    ///
+    /// The bytes of the value are borrowed rather than copied, and as the
+    /// _http_ crate has already validated them, they are not scanned again
+    /// for invalid bytes. An error has the header name and value as context,
+    /// as with `TypedHeaders::decode`. To parse all values of a header in a
+    /// `http::HeaderMap`, pass its `GetAll` to `parse_header`, or use
+    /// `TypedHeaders::decode`, which equally borrow.
+    ///
+    /// ```
+    /// # extern crate http;
+    /// # extern crate hyperx;
+    /// use http::header::HeaderValue;
+    /// use hyperx::header::{ContentLength, Header};
+    ///
+    /// # fn main() {
+    /// let value = HeaderValue::from_static("11");
+    /// assert_eq!(ContentLength::decode_value(&value).unwrap(), ContentLength(11));
+    ///
+    /// let e = ContentLength::decode_value(&HeaderValue::from_static("x")).unwrap_err();
+    /// assert_eq!(e.header_error().unwrap().name(), Some("Content-Length"));
+    /// # }
+    /// ```
+    fn decode_value(value: &::http::header::HeaderValue) -> ::Result<Self>
+    where
+        Self: Sized,
+    {
+        interned::parse_header(&value).map_err(|e| compat::with_context::<Self, _>(e, &value))
+    }
 }
 
 mod sealed {
     use super::Header;
+    use std::any::Any;
 
     #[doc(hidden)]
     pub trait HeaderClone {
+        fn dyn_name(&self) -> &'static str;
         fn clone_box(&self) -> Box<dyn Header + Send + Sync>;
+        fn as_any(&self) -> &dyn Any;
+        fn as_any_mut(&mut self) -> &mut dyn Any;
+        fn into_any(self: Box<Self>) -> Box<dyn Any>;
     }
 
     impl<T: Header + Clone> HeaderClone for T {
+        #[inline]
+        fn dyn_name(&self) -> &'static str {
+            T::header_name()
+        }
+
         #[inline]
         fn clone_box(&self) -> Box<dyn Header + Send + Sync> {
             Box::new(self.clone())
         }
+
+        #[inline]
+        fn as_any(&self) -> &dyn Any {
+            self
+        }
+
+        #[inline]
+        fn as_any_mut(&mut self) -> &mut dyn Any {
+            self
+        }
+
+        #[inline]
+        fn into_any(self: Box<Self>) -> Box<dyn Any> {
+            self
+        }
     }
 }
 
 /// A formatter used to serialize headers to an output stream.
+///
+/// Depending on the destination, each line written via `fmt_line` becomes a
+/// separate header line (as for HTTP/1 output or values of a
+/// `http::HeaderMap`), or lines are joined with `", "` into a single value
+/// (as for `Display` of a header view). Headers which can not be joined,
+/// such as `Set-Cookie`, should write one line per value and may check
+/// `is_joined`.
 #[allow(missing_debug_implementations)]
 pub struct Formatter<'a, 'b: 'a>(Multi<'a, 'b>);
 
@@ -250,6 +650,7 @@ enum Multi<'a, 'b: 'a> {
     Line(&'a str, &'a mut fmt::Formatter<'b>),
     Join(bool, &'a mut fmt::Formatter<'b>),
     Raw(&'a mut Raw),
+    Append(&'a ::http::header::HeaderName, &'a mut ::http::HeaderMap),
 }
 
 impl<'a, 'b> Formatter<'a, 'b> {
@@ -287,9 +688,35 @@ impl<'a, 'b> Formatter<'a, 'b> {
                 raw.push(s);
                 Ok(())
             }
+            Multi::Append(name, ref mut map) => {
+                // Written in place, and shared rather than copied by the value
+                let mut writer = ValueWriter::new();
+                write!(NewlineReplacer(&mut writer), "{}", line)?;
+                map.append(name, writer.into_value().map_err(|_| fmt::Error)?);
+                Ok(())
+            }
         }
     }
 
+    /// Format each of the given values as one 'line' of a header, via
+    /// `fmt_line`.
+    pub fn fmt_lines<I>(&mut self, lines: I) -> fmt::Result
+    where
+        I: IntoIterator,
+        I::Item: fmt::Display,
+    {
+        for line in lines {
+            self.fmt_line(&line)?;
+        }
+        Ok(())
+    }
+
+    /// Return `true` if lines are being joined with `", "` into a single
+    /// value, rather than written as separate header lines.
+    pub fn is_joined(&self) -> bool {
+        matches!(self.0, Multi::Join(..))
+    }
+
     fn danger_fmt_line_without_newline_replacer<T: fmt::Display>(
         &mut self,
         line: &T,
@@ -316,6 +743,12 @@ impl<'a, 'b> Formatter<'a, 'b> {
                 raw.push(s);
                 Ok(())
             }
+            Multi::Append(name, ref mut map) => {
+                let mut writer = ValueWriter::new();
+                write!(writer, "{}", line)?;
+                map.append(name, writer.into_value().map_err(|_| fmt::Error)?);
+                Ok(())
+            }
         }
     }
 }
@@ -339,6 +772,36 @@ impl<'a> fmt::Display for ValueString<'a> {
     }
 }
 
+// Returns false if the header formats multiple lines which can not be
+// combined into one value, as known by `FieldKind::Multiple`.
+fn is_joinable<H: Header + ?Sized>(header: &H) -> bool {
+    match FieldInfo::lookup(header.dyn_name()) {
+        Some(info) if info.kind == FieldKind::Multiple => {
+            let mut raw = raw::new();
+            // A failure to format is left to be reported when joined
+            let _ = header.fmt_header(&mut Formatter(Multi::Raw(&mut raw)));
+            raw.len() <= 1
+        }
+        _ => true,
+    }
+}
+
+fn check_joinable<H: Header + ?Sized>(header: &H) -> ::Result<()> {
+    if is_joinable(header) {
+        Ok(())
+    } else {
+        Err(::Error::header(::HeaderErrorKind::Conversion).with_header_name(header.dyn_name()))
+    }
+}
+
+struct Joined<'a, H: Header + ?Sized + 'a>(&'a H);
+
+impl<'a, H: Header + ?Sized + 'a> fmt::Display for Joined<'a, H> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.0.fmt_header(&mut Formatter(Multi::Join(true, f)))
+    }
+}
+
 struct NewlineReplacer<'a, F: fmt::Write + 'a>(&'a mut F);
 
 impl<'a, F: fmt::Write + 'a> fmt::Write for NewlineReplacer<'a, F> {
@@ -365,33 +828,37 @@ impl<'a, F: fmt::Write + 'a> fmt::Write for NewlineReplacer<'a, F> {
     }
 }
 
+/// Methods of boxed typed headers, such as parsed by a
+/// [`HeaderRegistry`](struct.HeaderRegistry.html), for use without knowing
+/// their concrete type.
 impl dyn Header + Send + Sync {
-    // A trait object looks like this:
-    //
-    // TraitObject { data: *mut (), vtable: *mut () }
-    //
-    // So, we transmute &Trait into a (*mut (), *mut ()). This depends on the
-    // order the compiler has chosen to represent a TraitObject.
-    //
-    // It has been assured that this order will be stable.
-    #[cfg(feature = "headers")]
+    /// Returns the name of the header, as `Header::header_name`.
     #[inline]
-    unsafe fn downcast_ref_unchecked<T: 'static>(&self) -> &T {
-        &*(mem::transmute::<*const _, (*const (), *const ())>(self).0 as *const T)
+    pub fn name(&self) -> &'static str {
+        self.dyn_name()
     }
 
-    #[cfg(feature = "headers")]
+    /// Format the header as its raw value lines, via `Header::fmt_header`.
+    pub fn to_raw(&self) -> Raw {
+        Raw::from_header(self)
+    }
+
+    /// Returns true if the header is of type `H`.
     #[inline]
-    unsafe fn downcast_mut_unchecked<T: 'static>(&mut self) -> &mut T {
-        &mut *(mem::transmute::<*mut _, (*mut (), *mut ())>(self).0 as *mut T)
+    pub fn is<H: Header>(&self) -> bool {
+        self.as_any().is::<H>()
     }
 
-    #[cfg(feature = "headers")]
+    /// Returns a reference to the header if it is of type `H`.
     #[inline]
-    unsafe fn downcast_unchecked<T: 'static>(self: Box<Self>) -> T {
-        *Box::from_raw(
-            mem::transmute::<*mut _, (*mut (), *mut ())>(Box::into_raw(self)).0 as *mut T,
-        )
+    pub fn downcast_ref<H: Header>(&self) -> Option<&H> {
+        self.as_any().downcast_ref()
+    }
+
+    /// Returns a mutable reference to the header if it is of type `H`.
+    #[inline]
+    pub fn downcast_mut<H: Header>(&mut self) -> Option<&mut H> {
+        self.as_any_mut().downcast_mut()
     }
 }
 
@@ -402,6 +869,12 @@ impl Clone for Box<dyn Header + Send + Sync> {
     }
 }
 
+impl fmt::Debug for dyn Header + Send + Sync {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}: {}", self.name(), Joined(self))
+    }
+}
+
 #[cfg(feature = "headers")]
 #[inline]
 fn header_name<T: Header>() -> &'static str {
@@ -414,6 +887,9 @@ fn header_name<T: Header>() -> &'static str {
 /// enabled. The type is preserved for compatibly, but its use is no longer
 /// required nor recommended. Consider replacing with `http::HeaderMap` and its
 /// [`TypedHeaders`](trait.TypedHeaders.html) extension.
+///
+/// `Headers` is `Send` and `Sync`. Typed values are parsed lazily on first
+/// access, and may be read concurrently through a shared reference.
 #[cfg(feature = "headers")]
 #[derive(Clone)]
 pub struct Headers {
@@ -430,6 +906,7 @@ impl Default for Headers {
 macro_rules! literals {
     ($($len:expr => $($header:path),+;)+) => (
         #[cfg(feature = "headers")]
+        #[allow(clippy::collapsible_match)]
         fn maybe_literal(s: &str) -> Cow<'static, str> {
             match s.len() {
                 $($len => {
@@ -508,6 +985,23 @@ impl Headers {
     }
 
     /// Get a mutable reference to the header field's value, if it exists.
+    ///
+    /// This allows modifying a typed header in place. Any raw value, and any
+    /// other typed interpretation of the same header field, is discarded and
+    /// will be re-formatted from the modified value when next accessed.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use hyperx::header::{CacheControl, CacheDirective, Headers};
+    /// let mut headers = Headers::new();
+    /// headers.set_raw("cache-control", "no-cache");
+    ///
+    /// if let Some(cc) = headers.get_mut::<CacheControl>() {
+    ///     cc.push(CacheDirective::MaxAge(60));
+    /// }
+    /// assert_eq!(headers.get_raw("cache-control").unwrap(), "no-cache, max-age=60");
+    /// ```
     pub fn get_mut<H: Header>(&mut self) -> Option<&mut H> {
         self.data
             .get_mut(&HeaderName(Ascii::new(Cow::Borrowed(header_name::<H>()))))
@@ -542,7 +1036,7 @@ impl Headers {
     }
 
     /// Returns an iterator over the header fields.
-    pub fn iter(&self) -> HeadersItems {
+    pub fn iter(&self) -> HeadersItems<'_> {
         HeadersItems {
             inner: self.data.iter(),
         }
@@ -553,6 +1047,11 @@ impl Headers {
         self.data.len()
     }
 
+    /// Returns `true` if the map contains no headers.
+    pub fn is_empty(&self) -> bool {
+        self.data.len() == 0
+    }
+
     /// Remove all headers from the map.
     pub fn clear(&mut self) {
         self.data.clear()
@@ -575,6 +1074,33 @@ impl Headers {
         self.data.get(name).map(Item::raw)
     }
 
+    /// Iterate over each raw value (line) of a header by name.
+    ///
+    /// Unlike the comma-joined `HeaderView::value_string`, this preserves
+    /// line boundaries, which is required for headers such as `Set-Cookie`.
+    /// If the header is not present, the iterator is empty.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use hyperx::header::{Headers, SetCookie};
+    /// let mut headers = Headers::new();
+    /// headers.set(SetCookie(vec![
+    ///     "foo=bar; Path=/".parse().unwrap(),
+    ///     "baz=quux".parse().unwrap(),
+    /// ]));
+    ///
+    /// let lines: Vec<&[u8]> = headers.get_all("set-cookie").collect();
+    /// assert_eq!(lines, vec![&b"foo=bar; Path=/"[..], &b"baz=quux"[..]]);
+    /// assert_eq!(headers.get_all("x-missing").count(), 0);
+    /// ```
+    pub fn get_all(&self, name: &str) -> RawLines<'_> {
+        match self.data.get(name) {
+            Some(item) => item.raw().iter(),
+            None => self::raw::no_lines(),
+        }
+    }
+
     /// Set the raw value of a header, bypassing any typed headers.
     ///
     /// Example:
@@ -636,6 +1162,129 @@ impl Headers {
     pub fn remove_raw(&mut self, name: &str) {
         self.data.remove(name);
     }
+
+    /// Write all headers in HTTP/1.1 wire format.
+    ///
+    /// Each value (line) is written as `Name: value\r\n`, and the block is
+    /// terminated with an empty `\r\n` line. Raw values are written as-is,
+    /// except that any CR or LF bytes are replaced with a space.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use hyperx::header::{ContentLength, Headers};
+    /// let mut headers = Headers::new();
+    /// headers.set(ContentLength(7));
+    /// headers.append_raw("x-foo", "bar");
+    ///
+    /// let mut buf = Vec::new();
+    /// headers.to_wire(&mut buf).unwrap();
+    /// assert_eq!(buf, b"Content-Length: 7\r\nx-foo: bar\r\n\r\n");
+    /// ```
+    pub fn to_wire<W: io::Write>(&self, w: &mut W) -> io::Result<()> {
+        for header in self.iter() {
+            for line in header.raw().iter() {
+                w.write_all(header.name().as_bytes())?;
+                w.write_all(b": ")?;
+                let mut since = 0;
+                for (i, &byte) in line.iter().enumerate() {
+                    if byte == b'\r' || byte == b'\n' {
+                        w.write_all(&line[since..i])?;
+                        w.write_all(b" ")?;
+                        since = i + 1;
+                    }
+                }
+                w.write_all(&line[since..])?;
+                w.write_all(b"\r\n")?;
+            }
+        }
+        w.write_all(b"\r\n")
+    }
+
+    /// Parse a block of headers in HTTP/1.1 wire format.
+    ///
+    /// Lines may be terminated by CRLF or a bare LF. Parsing stops at the
+    /// first empty line, if any. Obsolete line folding (obs-fold), where a
+    /// line starting with whitespace continues the prior value, is accepted
+    /// and replaced with a single space. Repeated names are appended as
+    /// additional values (lines), in order.
+    ///
+    /// Returns `Error::Header` for a line without a `:` separator, an invalid
+    /// header name, or a fold without a preceding header.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// # use hyperx::header::{ContentLength, Headers};
+    /// let headers = Headers::from_wire(
+    ///     b"Content-Length: 7\r\nx-foo: bar,\r\n baz\r\n\r\n"
+    /// ).unwrap();
+    /// assert_eq!(headers.get(), Some(&ContentLength(7)));
+    /// assert_eq!(headers.get_raw("x-foo").unwrap(), "bar, baz");
+    /// ```
+    pub fn from_wire(buf: &[u8]) -> ::Result<Headers> {
+        let mut fields: Vec<(&str, Vec<u8>)> = Vec::new();
+        for line in buf.split(|&b| b == b'\n') {
+            let line = match line.last() {
+                Some(b'\r') => &line[..line.len() - 1],
+                _ => line,
+            };
+            if line.is_empty() {
+                break;
+            }
+            if line[0] == b' ' || line[0] == b'\t' {
+                let value = match fields.last_mut() {
+                    Some(&mut (_, ref mut value)) => value,
+                    None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+                };
+                let cont = trim_ows(line);
+                if !cont.is_empty() {
+                    if !value.is_empty() {
+                        value.push(b' ');
+                    }
+                    value.extend_from_slice(cont);
+                }
+                continue;
+            }
+            let colon = match line.iter().position(|&b| b == b':') {
+                Some(pos) => pos,
+                None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+            };
+            let name = &line[..colon];
+            if ::http::header::HeaderName::from_bytes(name).is_err() {
+                return Err(::Error::header(::HeaderErrorKind::Token));
+            }
+            let name = str::from_utf8(name)?;
+            fields.push((name, trim_ows(&line[colon + 1..]).to_vec()));
+        }
+
+        let mut headers = Headers::with_capacity(fields.len());
+        headers.extend(
+            fields
+                .into_iter()
+                .map(|(name, value)| (name, Bytes::from(value))),
+        );
+        Ok(headers)
+    }
+}
+
+#[cfg(feature = "headers")]
+fn trim_ows(mut bytes: &[u8]) -> &[u8] {
+    while let Some((&b, rest)) = bytes.split_first() {
+        if b == b' ' || b == b'\t' {
+            bytes = rest;
+        } else {
+            break;
+        }
+    }
+    while let Some((&b, rest)) = bytes.split_last() {
+        if b == b' ' || b == b'\t' {
+            bytes = rest;
+        } else {
+            break;
+        }
+    }
+    bytes
 }
 
 #[cfg(feature = "headers")]
@@ -693,7 +1342,7 @@ impl<'a> Iterator for HeadersItems<'a> {
     type Item = HeaderView<'a>;
 
     fn next(&mut self) -> Option<HeaderView<'a>> {
-        self.inner.next().map(|&(ref k, ref v)| HeaderView(k, v))
+        self.inner.next().map(|(k, v)| HeaderView(k, v))
     }
 }
 
@@ -876,11 +1525,12 @@ mod tests {
             use std::str::FromStr;
 
             if let Some(line) = raw.one() {
-                let s =
-                    from_utf8(line).map(|s| FromStr::from_str(s).map_err(|_| ::Error::Header))?;
+                let s = from_utf8(line).map(|s| {
+                    FromStr::from_str(s).map_err(|_| ::Error::header(::HeaderErrorKind::Number))
+                })?;
                 s.map(|u| CrazyLength(Some(false), u))
             } else {
-                Err(::Error::Header)
+                Err(::header::parsing::not_one(raw))
             }
         }
 
@@ -896,6 +1546,45 @@ mod tests {
         }
     }
 
+    #[derive(Clone, PartialEq, Debug)]
+    struct XLines(Vec<&'static str>, bool);
+
+    impl Header for XLines {
+        fn header_name() -> &'static str {
+            "x-lines"
+        }
+        fn parse_header<'a, T>(_raw: &'a T) -> ::Result<XLines>
+        where
+            T: RawLike<'a>,
+        {
+            unimplemented!()
+        }
+        fn fmt_header(&self, f: &mut super::Formatter) -> fmt::Result {
+            if f.is_joined() && self.1 {
+                f.fmt_line(&self.0.join("; "))
+            } else {
+                f.fmt_lines(&self.0)
+            }
+        }
+    }
+
+    #[test]
+    fn test_fmt_lines() {
+        let header = XLines(vec!["a", "b"], false);
+        assert_eq!(super::Raw::from_header(&header), ["a", "b"][..]);
+
+        let mut headers = Headers::new();
+        headers.set(header);
+        assert_eq!(headers.to_string(), "x-lines: a\r\nx-lines: b\r\n");
+        let view = headers.iter().next().unwrap();
+        assert_eq!(view.value_string(), "a, b");
+
+        headers.set(XLines(vec!["a", "b"], true));
+        assert_eq!(headers.to_string(), "x-lines: a\r\nx-lines: b\r\n");
+        let view = headers.iter().next().unwrap();
+        assert_eq!(view.value_string(), "a; b");
+    }
+
     #[test]
     fn test_different_structs_for_same_header() {
         let headers = make_header!(b"Content-Length: 10");
@@ -936,8 +1625,8 @@ mod tests {
         assert_eq!(headers.get_raw("content-length").unwrap(), "15");
 
         headers.set(SetCookie(vec![
-            "foo=bar".to_string(),
-            "baz=quux; Path=/path".to_string(),
+            "foo=bar".parse().unwrap(),
+            "baz=quux; Path=/path".parse().unwrap(),
         ]));
         assert_eq!(
             headers.get_raw("set-cookie").unwrap(),
@@ -956,6 +1645,76 @@ mod tests {
         assert_eq!(*headers.get::<ContentLength>().unwrap(), ContentLength(20));
     }
 
+    #[test]
+    fn test_get_mutable_other_typed() {
+        let mut headers = make_header!(b"Content-Length: 10");
+        assert_eq!(
+            headers.get::<CrazyLength>(),
+            Some(&CrazyLength(Some(false), 10))
+        );
+        *headers.get_mut::<ContentLength>().unwrap() = ContentLength(20);
+        assert_eq!(headers.to_string(), "Content-Length: 20\r\n");
+        assert_eq!(
+            headers.get::<CrazyLength>(),
+            Some(&CrazyLength(Some(false), 20))
+        );
+    }
+
+    #[test]
+    fn test_get_mutable_after_set() {
+        let mut headers = Headers::new();
+        headers.set(ContentLength(10));
+        headers.get_mut::<CrazyLength>().unwrap().1 = 30;
+        assert_eq!(
+            headers.get_raw("content-length").unwrap(),
+            "Some(false), 30"
+        );
+        assert_eq!(headers.get::<ContentLength>(), None);
+    }
+
+    #[test]
+    fn test_get_mutable_inline() {
+        let mut headers = Headers::new();
+        headers.set(ContentLength(10));
+        assert_eq!(headers.get_raw("content-length").unwrap(), "10");
+        headers.get_mut::<ContentLength>().unwrap().0 = 20;
+        assert_eq!(headers.get_raw("content-length").unwrap(), "20");
+        assert_eq!(
+            headers.get::<CrazyLength>(),
+            Some(&CrazyLength(Some(false), 20))
+        );
+        assert_eq!(headers.clone().remove(), Some(ContentLength(20)));
+        assert_eq!(
+            headers.remove::<CrazyLength>(),
+            Some(CrazyLength(Some(false), 20))
+        );
+    }
+
+    #[test]
+    fn test_get_mutable_invalid() {
+        let mut headers = make_header!(b"Content-Length: ten");
+        assert_eq!(headers.get_mut::<ContentLength>(), None);
+        assert_eq!(headers.get_raw("content-length").unwrap(), "ten");
+    }
+
+    #[test]
+    fn test_many_headers() {
+        let mut headers = Headers::new();
+        for i in 0..64 {
+            headers.set_raw(format!("X-Header-{}", i), i.to_string());
+        }
+        headers.set(ContentLength(10));
+        assert_eq!(headers.len(), 65);
+        assert_eq!(headers.get_raw("x-header-42").unwrap(), "42");
+        assert_eq!(headers.get_raw("X-HEADER-7").unwrap(), "7");
+        assert_eq!(headers.get(), Some(&ContentLength(10)));
+        headers.remove::<ContentLength>();
+        headers.remove_raw("X-Header-0");
+        assert_eq!(headers.len(), 63);
+        assert_eq!(headers.get_raw("x-header-63").unwrap(), "63");
+        assert_eq!(headers.iter().next().unwrap().name(), "X-Header-1");
+    }
+
     #[test]
     fn test_headers_to_string() {
         let mut headers = Headers::new();
@@ -1000,6 +1759,70 @@ mod tests {
         assert_eq!(headers.get_raw("x-foo").unwrap(), &[b"bar".to_vec()][..]);
     }
 
+    #[test]
+    fn test_get_all() {
+        let mut headers = Headers::new();
+        headers.append_raw("x-foo", "one, two");
+        headers.append_raw("X-Foo", "three");
+        let lines: Vec<&[u8]> = headers.get_all("x-FOO").collect();
+        assert_eq!(lines, vec![&b"one, two"[..], &b"three"[..]]);
+        assert_eq!(headers.get_all("x-bar").next(), None);
+    }
+
+    #[test]
+    fn test_wire_round_trip() {
+        let mut headers = Headers::new();
+        headers.set(ContentLength(10));
+        headers.set(SetCookie(vec![
+            "a=1".parse().unwrap(),
+            "b=2".parse().unwrap(),
+        ]));
+        headers.append_raw("x-bin", vec![b'o', 0xFF, b'k']);
+
+        let mut buf = Vec::new();
+        headers.to_wire(&mut buf).unwrap();
+        assert_eq!(
+            &buf[..],
+            &b"Content-Length: 10\r\nSet-Cookie: a=1\r\nSet-Cookie: b=2\r\n\
+               x-bin: o\xFFk\r\n\r\n"[..]
+        );
+        let parsed = Headers::from_wire(&buf).unwrap();
+        assert_eq!(parsed, headers);
+        assert_eq!(parsed.get_all("set-cookie").count(), 2);
+    }
+
+    #[test]
+    fn test_wire_newlines_replaced() {
+        let mut headers = Headers::new();
+        headers.set_raw("x-foo", "a\r\nb");
+        let mut buf = Vec::new();
+        headers.to_wire(&mut buf).unwrap();
+        assert_eq!(&buf[..], &b"x-foo: a  b\r\n\r\n"[..]);
+    }
+
+    #[test]
+    fn test_from_wire() {
+        let headers = Headers::from_wire(
+            b"Host: foo.bar\nX-Multi: one\r\nx-multi:   two  \r\n\
+              X-Fold: a\r\n \t b\r\n\tc\r\n\r\nignored: body",
+        )
+        .unwrap();
+        assert_eq!(headers.len(), 3);
+        assert_eq!(headers.get(), Some(&Host::new("foo.bar", None)));
+        assert_eq!(headers.get_raw("x-multi").unwrap(), &["one", "two"][..]);
+        assert_eq!(headers.get_raw("x-fold").unwrap(), "a b c");
+        assert!(headers.get_raw("ignored").is_none());
+    }
+
+    #[test]
+    fn test_from_wire_invalid() {
+        assert!(Headers::from_wire(b" leading: fold\r\n").is_err());
+        assert!(Headers::from_wire(b"no colon\r\n").is_err());
+        assert!(Headers::from_wire(b"bad name: x\r\n").is_err());
+        assert!(Headers::from_wire(b": empty name\r\n").is_err());
+        assert!(Headers::from_wire(b"").unwrap().is_empty());
+    }
+
     #[test]
     fn test_remove_raw() {
         let mut headers = Headers::new();
@@ -1076,6 +1899,28 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_send_sync() {
+        use std::sync::Arc;
+        use std::thread;
+
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Headers>();
+
+        let mut headers = Headers::new();
+        headers.set_raw("Content-Length", "10");
+        let headers = Arc::new(headers);
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let headers = headers.clone();
+                thread::spawn(move || headers.get::<ContentLength>().cloned())
+            })
+            .collect();
+        for t in threads {
+            assert_eq!(t.join().unwrap(), Some(ContentLength(10)));
+        }
+    }
+
     #[test]
     fn test_eq() {
         let mut headers1 = Headers::new();
@@ -1,13 +1,188 @@
 //! Utility functions for Header implementations.
+//!
+//! These include parsing of delimited lists, parameters, quoted-strings and
+//! quality values, which are generally useful for implementing custom
+//! headers.
 
-use header::shared::Charset;
-use header::RawLike;
+use header::shared::{q, Charset, Quality};
+use header::{interned, Header, RawLike};
 use language_tags::LanguageTag;
 use percent_encoding;
+use std::borrow::Cow;
+use std::cell::Cell;
 use std::fmt::{self, Display};
 use std::str;
 use std::str::FromStr;
 
+/// Configuration of header parsing.
+///
+/// Parsing is lenient by default, tolerating some invalid input as commonly
+/// sent by broken clients and servers. With `strict` enabled, parsing
+/// additionally rejects:
+///
+/// * values which are not a valid `field-value`, e.g. containing control
+///   characters or obsolete line folding (see `unfold`),
+/// * items of comma delimited lists which fail to parse (otherwise
+///   skipped), or lists with an unterminated quoted-string,
+/// * multiple values of singleton headers (like `Content-Length`) which are
+///   otherwise accepted if identical,
+/// * extension methods which are not a valid token.
+///
+/// Limits may also be configured on the resources used by parsers, see
+/// `ParseConfig::limit`. Exceeding a limit results in an error of kind
+/// `HeaderErrorKind::TooLarge`.
+///
+/// A configuration applies to all parsing on the current thread within
+/// `ParseConfig::scope` or `ParseConfig::parse`:
+///
+/// ```
+/// use hyperx::header::{ContentLength, Raw};
+/// use hyperx::header::parsing::ParseConfig;
+///
+/// let raw: Raw = vec![b"3".to_vec(), b"3".to_vec()].into();
+/// assert!(ParseConfig::new().parse::<ContentLength, _>(&raw).is_ok());
+///
+/// let strict = ParseConfig::new().strict(true);
+/// assert!(strict.parse::<ContentLength, _>(&raw).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ParseConfig {
+    strict: bool,
+    value_length: Option<usize>,
+    items: Option<usize>,
+    cookie_pairs: Option<usize>,
+    directives: Option<usize>,
+}
+
+/// A parser resource limit, configured via `ParseConfig::limit`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Limit {
+    /// The length in bytes of each value (line).
+    ValueLength,
+    /// The number of items in a comma delimited list, over all lines.
+    Items,
+    /// The number of `Cookie` pairs or `Set-Cookie` values.
+    CookiePairs,
+    /// The number of `Cache-Control` directives.
+    Directives,
+}
+
+thread_local!(static CONFIG: Cell<ParseConfig> = Cell::new(ParseConfig::new()));
+
+impl ParseConfig {
+    /// Return the default, lenient configuration.
+    pub fn new() -> ParseConfig {
+        ParseConfig {
+            strict: false,
+            value_length: None,
+            items: None,
+            cookie_pairs: None,
+            directives: None,
+        }
+    }
+
+    /// Return the configuration currently applied on this thread.
+    pub fn current() -> ParseConfig {
+        CONFIG.with(Cell::get)
+    }
+
+    /// Set whether to parse strictly.
+    pub fn strict(mut self, strict: bool) -> ParseConfig {
+        self.strict = strict;
+        self
+    }
+
+    /// Return `true` if parsing strictly.
+    pub fn is_strict(&self) -> bool {
+        self.strict
+    }
+
+    /// Set a maximum for the given limit. No limits are set by default.
+    ///
+    /// ```
+    /// use hyperx::header::{Raw, Vary};
+    /// use hyperx::header::parsing::{Limit, ParseConfig};
+    /// use hyperx::ErrorKind;
+    ///
+    /// let config = ParseConfig::new().limit(Limit::Items, 2);
+    /// let raw: Raw = "accept, accept-encoding, accept-language".into();
+    /// let e = config.parse::<Vary, _>(&raw).unwrap_err();
+    /// assert_eq!(e.kind(), ErrorKind::TooLarge);
+    /// ```
+    pub fn limit(mut self, limit: Limit, max: usize) -> ParseConfig {
+        *self.limit_mut(limit) = Some(max);
+        self
+    }
+
+    /// Return the maximum for the given limit, if set.
+    pub fn get_limit(&self, limit: Limit) -> Option<usize> {
+        match limit {
+            Limit::ValueLength => self.value_length,
+            Limit::Items => self.items,
+            Limit::CookiePairs => self.cookie_pairs,
+            Limit::Directives => self.directives,
+        }
+    }
+
+    fn limit_mut(&mut self, limit: Limit) -> &mut Option<usize> {
+        match limit {
+            Limit::ValueLength => &mut self.value_length,
+            Limit::Items => &mut self.items,
+            Limit::CookiePairs => &mut self.cookie_pairs,
+            Limit::Directives => &mut self.directives,
+        }
+    }
+
+    /// Return an error of kind `HeaderErrorKind::TooLarge` if the given
+    /// count exceeds the maximum for the given limit.
+    pub fn check(&self, limit: Limit, count: usize) -> ::Result<()> {
+        match self.get_limit(limit) {
+            Some(max) if count > max => Err(::Error::header(::HeaderErrorKind::TooLarge)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Run the given function with this configuration applied on the current
+    /// thread, restoring the prior configuration on return.
+    pub fn scope<T, F: FnOnce() -> T>(&self, f: F) -> T {
+        struct Restore(ParseConfig);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                CONFIG.with(|c| c.set(self.0));
+            }
+        }
+
+        let _restore = Restore(CONFIG.with(|c| c.replace(*self)));
+        f()
+    }
+
+    /// Parse a header with this configuration.
+    ///
+    /// The `Limit::ValueLength` is checked for all values (lines) before
+    /// parsing, for any header type.
+    pub fn parse<'a, H: Header, R: RawLike<'a>>(&self, raw: &'a R) -> ::Result<H> {
+        for line in raw.iter() {
+            self.check(Limit::ValueLength, line.len())
+                .map_err(|e| e.with_header_name(H::header_name()))?;
+        }
+        self.scope(|| interned::parse_header(raw))
+    }
+}
+
+/// Return `true` if strict parsing is configured on the current thread. See
+/// `ParseConfig`.
+pub fn is_strict() -> bool {
+    ParseConfig::current().is_strict()
+}
+
+/// Check a count against the limit configured on the current thread. See
+/// `ParseConfig::check`.
+pub fn check_limit(limit: Limit, count: usize) -> ::Result<()> {
+    ParseConfig::current().check(limit, count)
+}
+
 /// Reads a single raw string when parsing a header.
 pub fn from_one_raw_str<'a, R, T>(raw: &'a R) -> ::Result<T>
 where
@@ -16,16 +191,158 @@ where
 {
     if let Some(line) = raw.one() {
         if !line.is_empty() {
-            return from_raw_str(line);
+            return parse_line(line, raw.is_validated());
         }
+        return Err(::Error::header(::HeaderErrorKind::Empty));
     }
-    Err(::Error::Header)
+    Err(not_one(raw))
 }
 
 /// Reads a raw string into a value.
 pub fn from_raw_str<T: str::FromStr>(raw: &[u8]) -> ::Result<T> {
-    let s = str::from_utf8(raw)?.trim();
-    T::from_str(s).or(Err(::Error::Header))
+    parse_line(raw, false)
+}
+
+fn parse_line<T: str::FromStr>(raw: &[u8], validated: bool) -> ::Result<T> {
+    let config = ParseConfig::current();
+    let line = Line::scan(raw, validated, &config)?;
+    if config.is_strict() && line.invalid {
+        return Err(::Error::header(::HeaderErrorKind::Syntax).with_header_value(raw));
+    }
+    T::from_str(&line.unfold())
+        .map_err(|_| ::Error::header(::HeaderErrorKind::Syntax).with_header_value(raw))
+}
+
+/// Reads a single raw string, from the values (lines) of a header, for
+/// parsing into a type which borrows from it. See `from_raw_str_ref`.
+pub fn from_one_raw_str_ref<'a, I>(lines: I) -> ::Result<&'a str>
+where
+    I: IntoIterator<Item = &'a [u8]>,
+{
+    let mut lines = lines.into_iter();
+    match (lines.next(), lines.next()) {
+        (Some(line), None) if !line.is_empty() => from_raw_str_ref(line),
+        (Some(_), Some(_)) => Err(::Error::header(::HeaderErrorKind::Multiple)),
+        _ => Err(::Error::header(::HeaderErrorKind::Empty)),
+    }
+}
+
+/// Reads a raw string, trimmed, for parsing into a type which borrows from
+/// it. As unfolding would allocate, a value with obsolete line folding is
+/// rejected with an error of kind `Syntax`.
+///
+/// ```
+/// use hyperx::header::parsing::from_raw_str_ref;
+///
+/// assert_eq!(from_raw_str_ref(b" text/html ").unwrap(), "text/html");
+/// assert!(from_raw_str_ref(b"a,\r\n b").is_err());
+/// ```
+pub fn from_raw_str_ref(raw: &[u8]) -> ::Result<&str> {
+    let config = ParseConfig::current();
+    let line = Line::scan(raw, false, &config)?;
+    if line.folded || (config.is_strict() && line.invalid) {
+        return Err(::Error::header(::HeaderErrorKind::Syntax).with_header_value(raw));
+    }
+    Ok(line.s)
+}
+
+// A raw value (line) as a trimmed string, with the properties checked by
+// the parsers, found in a single pass over its bytes.
+struct Line<'a> {
+    s: &'a str,
+    // Not a valid `field-value`, containing control characters
+    invalid: bool,
+    // Possibly containing obsolete line folding
+    folded: bool,
+    // All quoted-strings are terminated, if known
+    balanced: Option<bool>,
+}
+
+impl<'a> Line<'a> {
+    fn scan(raw: &'a [u8], validated: bool, config: &ParseConfig) -> ::Result<Line<'a>> {
+        config.check(Limit::ValueLength, raw.len())?;
+        let utf8_error =
+            |e| ::Error::header_with_source(::HeaderErrorKind::Utf8, e).with_header_value(raw);
+        if validated {
+            // A `HeaderValue` contains no control characters other than
+            // horizontal tab, and so no line folding either.
+            let s = str::from_utf8(raw).map_err(utf8_error)?.trim();
+            return Ok(Line {
+                s,
+                invalid: false,
+                folded: false,
+                balanced: None,
+            });
+        }
+
+        let trimmed = trim_ascii(raw);
+        let (mut ascii, mut invalid, mut folded) = (true, false, false);
+        let (mut quoted, mut escaped) = (false, false);
+        let mut prev = 0;
+        for &b in trimmed {
+            ascii &= b.is_ascii();
+            invalid |= (b < 0x20 && b != b'\t') || b == 0x7f;
+            folded |= prev == b'\n' && (b == b' ' || b == b'\t');
+            if escaped {
+                escaped = false;
+            } else if quoted && b == b'\\' {
+                escaped = true;
+            } else if b == b'"' {
+                quoted = !quoted;
+            }
+            prev = b;
+        }
+        let s = if ascii {
+            unsafe {
+                // safe because all bytes were just checked to be ASCII,
+                // which is valid UTF-8.
+                str::from_utf8_unchecked(trimmed)
+            }
+        } else {
+            // `str::trim` also trims non-ASCII whitespace, possibly along
+            // with control characters found above, so check again.
+            let s = str::from_utf8(trimmed).map_err(utf8_error)?.trim();
+            invalid = invalid && !is_field_value(s.as_bytes());
+            s
+        };
+        Ok(Line {
+            s,
+            invalid,
+            folded,
+            balanced: Some(!quoted),
+        })
+    }
+
+    fn unfold(&self) -> Cow<'a, str> {
+        if self.folded {
+            unfold(self.s)
+        } else {
+            Cow::Borrowed(self.s)
+        }
+    }
+
+    fn is_quote_balanced(&self) -> bool {
+        self.balanced.unwrap_or_else(|| is_quote_balanced(self.s))
+    }
+}
+
+// Trim the whitespace which `str::trim` would trim, of ASCII.
+fn trim_ascii(b: &[u8]) -> &[u8] {
+    let ws = |c: &u8| matches!(*c, b' ' | b'\t' | b'\n' | b'\x0b' | b'\x0c' | b'\r');
+    let start = b.iter().position(|c| !ws(c)).unwrap_or(b.len());
+    let end = b.iter().rposition(|c| !ws(c)).map_or(start, |i| i + 1);
+    &b[start..end]
+}
+
+/// Returns the error for a raw header without exactly one value (line), as
+/// required for single-valued headers: of kind `Empty` if there are no
+/// values, otherwise `Multiple`.
+pub fn not_one<'a, R: RawLike<'a>>(raw: &'a R) -> ::Error {
+    if raw.is_empty() {
+        ::Error::header(::HeaderErrorKind::Empty)
+    } else {
+        ::Error::header(::HeaderErrorKind::Multiple)
+    }
 }
 
 /// Reads a comma-delimited raw header into a Vec.
@@ -36,35 +353,476 @@ where
     T: str::FromStr,
 {
     let mut result = Vec::new();
-    for s in raw.iter() {
-        let s = str::from_utf8(s)?;
-        result.extend(
-            s.split(',')
-                .filter_map(|x| match x.trim() {
-                    "" => None,
-                    y => Some(y),
-                })
-                .filter_map(|x| x.trim().parse().ok()),
-        )
-    }
+    extend_comma_delimited(raw, &mut result)?;
     Ok(result)
 }
 
-/// Format an array into a comma-delimited string.
-pub fn fmt_comma_delimited<T: Display>(f: &mut fmt::Formatter, parts: &[T]) -> fmt::Result {
-    let mut iter = parts.iter();
+/// Reads a comma-delimited raw header into a collection, such as the
+/// `HeaderVec` of list headers, extending it with the items.
+pub fn extend_comma_delimited<'a, R, T, C>(raw: &'a R, result: &mut C) -> ::Result<()>
+where
+    R: RawLike<'a>,
+    T: str::FromStr,
+    C: Extend<T>,
+{
+    let config = ParseConfig::current();
+    let validated = raw.is_validated();
+    let mut count = 0;
+    for line in raw.iter() {
+        let scanned = Line::scan(line, validated, &config)?;
+        if config.is_strict() {
+            let invalid = || ::Error::header(::HeaderErrorKind::Syntax).with_header_value(line);
+            if scanned.invalid || !scanned.is_quote_balanced() {
+                return Err(invalid());
+            }
+            for item in split_comma_delimited(scanned.s) {
+                count += 1;
+                config.check(Limit::Items, count)?;
+                result.extend(Some(item.parse().map_err(|_| invalid())?));
+            }
+            continue;
+        }
+        let s = scanned.unfold();
+        // Commas within quoted-strings (e.g. of entity-tags) do not delimit
+        // items, unless the quotes are unbalanced.
+        let items: Box<dyn Iterator<Item = &str>> = if scanned.is_quote_balanced() {
+            Box::new(split_comma_delimited(&s))
+        } else {
+            Box::new(s.split(',').map(str::trim).filter(|x| !x.is_empty()))
+        };
+        for item in items.filter_map(|x| x.parse().ok()) {
+            count += 1;
+            config.check(Limit::Items, count)?;
+            result.extend(Some(item));
+        }
+    }
+    Ok(())
+}
+
+/// Format the parts, such as those of a slice or an iterator adapter, into
+/// a comma-delimited string.
+///
+/// ```
+/// use std::fmt;
+/// use hyperx::header::parsing::fmt_comma_delimited;
+///
+/// struct Squares(Vec<u32>);
+///
+/// impl fmt::Display for Squares {
+///     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+///         fmt_comma_delimited(f, self.0.iter().map(|n| n * n))
+///     }
+/// }
+///
+/// assert_eq!(Squares(vec![1, 2, 3]).to_string(), "1, 4, 9");
+/// ```
+pub fn fmt_comma_delimited<I>(f: &mut fmt::Formatter, parts: I) -> fmt::Result
+where
+    I: IntoIterator,
+    I::Item: Display,
+{
+    let mut iter = parts.into_iter();
     if let Some(part) = iter.next() {
-        Display::fmt(part, f)?;
+        Display::fmt(&part, f)?;
     }
     for part in iter {
         f.write_str(", ")?;
-        Display::fmt(part, f)?;
+        Display::fmt(&part, f)?;
     }
     Ok(())
 }
 
+/// Returns `true` if the value is a valid (non-empty) `token`, as defined in
+/// [RFC 7230](https://tools.ietf.org/html/rfc7230#section-3.2.6).
+///
+/// ```
+/// use hyperx::header::parsing::is_token;
+///
+/// assert!(is_token("x-custom_1.0"));
+/// assert!(!is_token("a b"));
+/// assert!(!is_token(""));
+/// ```
+pub fn is_token(s: &str) -> bool {
+    !s.is_empty() && s.bytes().all(is_tchar)
+}
+
+/// Finds the value for a token in a table of names, compared ASCII
+/// case-insensitively, without allocating a lowercase copy of the token.
+///
+/// ```
+/// use hyperx::header::parsing::find_ignore_case;
+///
+/// let table = [("deny", 1), ("sameorigin", 2)];
+/// assert_eq!(find_ignore_case("SameOrigin", &table), Some(2));
+/// assert_eq!(find_ignore_case("allow", &table), None);
+/// ```
+pub fn find_ignore_case<T: Clone>(token: &str, table: &[(&str, T)]) -> Option<T> {
+    table
+        .iter()
+        .find(|entry| entry.0.eq_ignore_ascii_case(token))
+        .map(|entry| entry.1.clone())
+}
+
+#[rustfmt::skip]
+fn is_tchar(b: u8) -> bool {
+    matches!(b,
+        b'a'..=b'z' | b'A'..=b'Z' | b'0'..=b'9' |
+        b'!' | b'#' | b'$' | b'%' | b'&' | b'\'' | b'*' | b'+' | b'-' | b'.' |
+        b'^' | b'_' | b'`' | b'|' | b'~')
+}
+
+/// Returns `true` if the value is a valid `quoted-string`, including the
+/// surrounding quotes, as defined in
+/// [RFC 7230](https://tools.ietf.org/html/rfc7230#section-3.2.6).
+///
+/// ```
+/// use hyperx::header::parsing::is_quoted_string;
+///
+/// assert!(is_quoted_string(r#""a \"b\"""#));
+/// assert!(!is_quoted_string(r#""a "b"""#));
+/// assert!(!is_quoted_string("token"));
+/// ```
+pub fn is_quoted_string(s: &str) -> bool {
+    let b = s.as_bytes();
+    if b.len() < 2 || b[0] != b'"' || b[b.len() - 1] != b'"' {
+        return false;
+    }
+    let mut escaped = false;
+    for &c in &b[1..b.len() - 1] {
+        if escaped {
+            if !is_field_vchar(c) && c != b' ' && c != b'\t' {
+                return false;
+            }
+            escaped = false;
+        } else if c == b'\\' {
+            escaped = true;
+        } else if c == b'"' || !(is_field_vchar(c) || c == b' ' || c == b'\t') {
+            return false;
+        }
+    }
+    !escaped
+}
+
+/// Returns `true` if the bytes are a valid `field-value`, as defined in
+/// [RFC 7230](https://tools.ietf.org/html/rfc7230#section-3.2): visible
+/// ASCII, `obs-text` (bytes 0x80 and above), space or horizontal tab, but
+/// no other control characters (e.g. CR or LF) and no leading or trailing
+/// whitespace.
+///
+/// ```
+/// use hyperx::header::parsing::is_field_value;
+///
+/// assert!(is_field_value(b"text/html; charset=utf-8"));
+/// assert!(is_field_value(b""));
+/// assert!(!is_field_value(b"new\r\nline"));
+/// assert!(!is_field_value(b" padded"));
+/// ```
+pub fn is_field_value(b: &[u8]) -> bool {
+    let ws = |c: &u8| *c == b' ' || *c == b'\t';
+    !b.first().is_some_and(ws)
+        && !b.last().is_some_and(ws)
+        && b.iter()
+            .all(|&c| is_field_vchar(c) || c == b' ' || c == b'\t')
+}
+
+// VCHAR or obs-text
+fn is_field_vchar(c: u8) -> bool {
+    (0x21..=0x7e).contains(&c) || c >= 0x80
+}
+
+/// Returns `true` if the value contains an obsolete line folding
+/// (`obs-fold`): a CRLF (or bare LF) followed by at least one space or
+/// horizontal tab, as defined in
+/// [RFC 7230](https://tools.ietf.org/html/rfc7230#section-3.2.4).
+///
+/// Folded values are rejected when parsing strictly (see `ParseConfig`).
+pub fn is_folded(b: &[u8]) -> bool {
+    b.windows(2)
+        .any(|w| w[0] == b'\n' && (w[1] == b' ' || w[1] == b'\t'))
+}
+
+/// Replaces each obsolete line folding (`obs-fold`, see `is_folded`) in the
+/// value with a single space, as a recipient should before interpreting a
+/// value. Otherwise returns the value without copying.
+///
+/// This is applied by `from_raw_str` and `from_comma_delimited` when not
+/// parsing strictly, which accepts values from archived traffic or legacy
+/// servers (e.g. via the `httparse` option to allow multi-line headers).
+///
+/// ```
+/// use hyperx::header::parsing::unfold;
+///
+/// assert_eq!(unfold("a,\r\n  b,\n\tc"), "a, b, c");
+/// assert_eq!(unfold("a\r\nb"), "a\r\nb");
+/// ```
+pub fn unfold(s: &str) -> Cow<'_, str> {
+    if !is_folded(s.as_bytes()) {
+        return Cow::Borrowed(s);
+    }
+    let b = s.as_bytes();
+    let mut out = String::with_capacity(s.len());
+    let mut since = 0;
+    let mut i = 0;
+    while i < b.len() {
+        let start = i;
+        if b[i] == b'\r' && b.get(i + 1) == Some(&b'\n') {
+            i += 1;
+        }
+        if b[i] == b'\n' && matches!(b.get(i + 1), Some(b' ') | Some(b'\t')) {
+            i += 1;
+            while i < b.len() && (b[i] == b' ' || b[i] == b'\t') {
+                i += 1;
+            }
+            out.push_str(&s[since..start]);
+            out.push(' ');
+            since = i;
+        } else {
+            i = start + 1;
+        }
+    }
+    out.push_str(&s[since..]);
+    Cow::Owned(out)
+}
+
+// Return `true` if all quoted-strings are terminated.
+fn is_quote_balanced(s: &str) -> bool {
+    let mut quoted = false;
+    let mut escaped = false;
+    for c in s.chars() {
+        if escaped {
+            escaped = false;
+        } else if quoted && c == '\\' {
+            escaped = true;
+        } else if c == '"' {
+            quoted = !quoted;
+        }
+    }
+    !quoted
+}
+
+/// Splits a header value on the given delimiter, such as `,` or `;`,
+/// ignoring any delimiters within quoted-strings.
+///
+/// Items are trimmed of surrounding whitespace, and empty items are skipped.
+///
+/// ```
+/// use hyperx::header::parsing::split_delimited;
+///
+/// let items: Vec<&str> = split_delimited(r#"a, "b, c" ,, d"#, ',').collect();
+/// assert_eq!(items, ["a", r#""b, c""#, "d"]);
+/// ```
+pub fn split_delimited(s: &str, delim: char) -> Delimited<'_> {
+    Delimited { rest: s, delim }
+}
+
+/// Splits a comma delimited header value, ignoring commas within
+/// quoted-strings. See `split_delimited`.
+pub fn split_comma_delimited(s: &str) -> Delimited<'_> {
+    split_delimited(s, ',')
+}
+
+/// Iterator over the items of a delimited header value, returned by
+/// `split_delimited`.
+#[derive(Clone, Debug)]
+pub struct Delimited<'a> {
+    rest: &'a str,
+    delim: char,
+}
+
+impl<'a> Iterator for Delimited<'a> {
+    type Item = &'a str;
+
+    fn next(&mut self) -> Option<&'a str> {
+        while !self.rest.is_empty() {
+            let mut quoted = false;
+            let mut escaped = false;
+            let mut end = self.rest.len();
+            for (i, c) in self.rest.char_indices() {
+                if escaped {
+                    escaped = false;
+                } else if quoted && c == '\\' {
+                    escaped = true;
+                } else if c == '"' {
+                    quoted = !quoted;
+                } else if !quoted && c == self.delim {
+                    end = i;
+                    break;
+                }
+            }
+            let item = self.rest[..end].trim();
+            self.rest = match self.rest[end..].chars().next() {
+                Some(c) => &self.rest[end + c.len_utf8()..],
+                None => "",
+            };
+            if !item.is_empty() {
+                return Some(item);
+            }
+        }
+        None
+    }
+}
+
+/// Parses a `name=value` parameter, as found after `;` in many headers.
+///
+/// Returns the trimmed name, and the value if any `=` is found. A
+/// quoted-string value is unquoted (see `unquote`).
+///
+/// ```
+/// use hyperx::header::parsing::parse_parameter;
+///
+/// let (name, value) = parse_parameter(r#" filename = "a \"b\".txt""#).unwrap();
+/// assert_eq!(name, "filename");
+/// assert_eq!(value.unwrap(), r#"a "b".txt"#);
+///
+/// assert_eq!(parse_parameter("HttpOnly").unwrap(), ("HttpOnly", None));
+/// assert!(parse_parameter("=value").is_err());
+/// ```
+pub fn parse_parameter(s: &str) -> ::Result<(&str, Option<Cow<'_, str>>)> {
+    let (name, value) = match s.find('=') {
+        Some(i) => (s[..i].trim(), Some(&s[i + 1..])),
+        None => (s.trim(), None),
+    };
+    if !is_token(name) {
+        return Err(::Error::header(::HeaderErrorKind::Token));
+    }
+    match value {
+        Some(value) => Ok((name, Some(unquote(value.trim())?))),
+        None => Ok((name, None)),
+    }
+}
+
+/// Unquotes and unescapes a quoted-string, or returns the value as is if
+/// it is not quoted.
+///
+/// Returns an error for an unterminated quoted-string, or one with an
+/// unescaped `"` within.
+///
+/// ```
+/// use hyperx::header::parsing::unquote;
+///
+/// assert_eq!(unquote(r#""a \"quoted\" \\ string""#).unwrap(), r#"a "quoted" \ string"#);
+/// assert_eq!(unquote("token").unwrap(), "token");
+/// assert!(unquote(r#""unterminated"#).is_err());
+/// ```
+pub fn unquote(s: &str) -> ::Result<Cow<'_, str>> {
+    if !s.starts_with('"') {
+        return Ok(Cow::Borrowed(s));
+    }
+    if s.len() < 2 || !s.ends_with('"') {
+        return Err(::Error::header(::HeaderErrorKind::Syntax));
+    }
+    let inner = &s[1..s.len() - 1];
+    if !inner.contains(['"', '\\']) {
+        return Ok(Cow::Borrowed(inner));
+    }
+    let mut out = String::with_capacity(inner.len());
+    let mut chars = inner.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => match chars.next() {
+                Some(c) => out.push(c),
+                None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+            },
+            '"' => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+            c => out.push(c),
+        }
+    }
+    Ok(Cow::Owned(out))
+}
+
+/// Formats a value as a quoted-string, escaping any `"` or `\` within.
+pub fn fmt_quoted(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+    f.write_str("\"")?;
+    let mut rest = s;
+    while let Some(i) = rest.find(['"', '\\']) {
+        f.write_str(&rest[..i])?;
+        f.write_str("\\")?;
+        f.write_str(&rest[i..=i])?;
+        rest = &rest[i + 1..];
+    }
+    f.write_str(rest)?;
+    f.write_str("\"")
+}
+
+/// Returns the value as a quoted-string, escaping any `"` or `\` within.
+///
+/// ```
+/// use hyperx::header::parsing::{quote, unquote};
+///
+/// let quoted = quote(r#"a "b" \ c"#);
+/// assert_eq!(quoted, r#""a \"b\" \\ c""#);
+/// assert_eq!(unquote(&quoted).unwrap(), r#"a "b" \ c"#);
+/// ```
+pub fn quote(s: &str) -> String {
+    struct Quoted<'a>(&'a str);
+
+    impl<'a> Display for Quoted<'a> {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            fmt_quoted(f, self.0)
+        }
+    }
+
+    Quoted(s).to_string()
+}
+
+/// Parses a quality value (`qvalue`), as defined in
+/// [RFC 7231](https://tools.ietf.org/html/rfc7231#section-5.3.1), such as
+/// the `0.5` of a `q=0.5` parameter.
+///
+/// ```
+/// use hyperx::header::parsing::parse_quality;
+/// use hyperx::header::q;
+///
+/// assert_eq!(parse_quality("0.5").unwrap(), q(500));
+/// assert_eq!(parse_quality("1").unwrap(), q(1000));
+/// assert!(parse_quality("1.5").is_err());
+/// assert!(parse_quality("0.1234").is_err());
+/// ```
+pub fn parse_quality(s: &str) -> ::Result<Quality> {
+    let bytes = s.as_bytes();
+    let (whole, frac) = match bytes.split_first() {
+        Some((&b'0', rest)) => (0, rest),
+        Some((&b'1', rest)) => (1000, rest),
+        _ => return Err(::Error::header(::HeaderErrorKind::Number)),
+    };
+    let digits = match frac.split_first() {
+        None => frac,
+        Some((&b'.', digits)) if digits.len() <= 3 => digits,
+        _ => return Err(::Error::header(::HeaderErrorKind::Number)),
+    };
+    let mut value = 0u16;
+    let mut scale = 100;
+    for &d in digits {
+        if !d.is_ascii_digit() {
+            return Err(::Error::header(::HeaderErrorKind::Number));
+        }
+        value += u16::from(d - b'0') * scale;
+        scale /= 10;
+    }
+    if whole == 1000 && value != 0 {
+        return Err(::Error::header(::HeaderErrorKind::Number));
+    }
+    Ok(q(whole + value))
+}
+
 /// An extended header parameter value (i.e., tagged with a character set and optionally,
-/// a language), as defined in [RFC 5987](https://tools.ietf.org/html/rfc5987#section-3.2).
+/// a language), as defined in [RFC 5987](https://tools.ietf.org/html/rfc5987#section-3.2)
+/// and [RFC 8187](https://tools.ietf.org/html/rfc8187#section-3.2).
+///
+/// Such values are used for parameters with names ending in `*`, for example
+/// `filename*` of `Content-Disposition` or `title*` of `Link`. Parse with
+/// `parse_extended_value` (or `FromStr`), and encode with `Display`:
+///
+/// ```
+/// use hyperx::header::parsing::ExtendedValue;
+///
+/// let ev = ExtendedValue::utf8("£ rates.txt");
+/// assert_eq!(ev.to_string(), "UTF-8''%C2%A3%20rates.txt");
+///
+/// let ev: ExtendedValue = "UTF-8'en'%C2%A3%20rates.txt".parse().unwrap();
+/// assert_eq!(ev.language_tag.as_ref().unwrap().as_str(), "en");
+/// assert_eq!(ev.to_text().unwrap(), "£ rates.txt");
+/// ```
 #[derive(Clone, Debug, PartialEq)]
 pub struct ExtendedValue {
     /// The character set that is used to encode the `value` to a string.
@@ -75,6 +833,44 @@ pub struct ExtendedValue {
     pub value: Vec<u8>,
 }
 
+impl ExtendedValue {
+    /// Construct a UTF-8 value without a language tag, as recommended by
+    /// RFC 8187.
+    pub fn utf8(text: &str) -> ExtendedValue {
+        ExtendedValue {
+            charset: Charset::Ext("UTF-8".to_owned()),
+            language_tag: None,
+            value: text.as_bytes().to_vec(),
+        }
+    }
+
+    /// Decode the value to text, if the charset is UTF-8, ISO-8859-1 or
+    /// US-ASCII and the value is valid in that charset. Otherwise return
+    /// `None`.
+    pub fn to_text(&self) -> Option<Cow<'_, str>> {
+        match self.charset {
+            Charset::Ext(ref cs) if cs.eq_ignore_ascii_case("UTF-8") => {
+                str::from_utf8(&self.value).ok().map(Cow::Borrowed)
+            }
+            Charset::Us_Ascii if self.value.is_ascii() => {
+                str::from_utf8(&self.value).ok().map(Cow::Borrowed)
+            }
+            Charset::Iso_8859_1 => Some(Cow::Owned(
+                self.value.iter().map(|&b| char::from(b)).collect(),
+            )),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for ExtendedValue {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<ExtendedValue> {
+        parse_extended_value(s)
+    }
+}
+
 /// Parses extended header parameter values (`ext-value`), as defined in
 /// [RFC 5987](https://tools.ietf.org/html/rfc5987#section-3.2).
 ///
@@ -116,23 +912,23 @@ pub fn parse_extended_value(val: &str) -> ::Result<ExtendedValue> {
 
     // Interpret the first piece as a Charset
     let charset: Charset = match parts.next() {
-        None => return Err(::Error::Header),
+        None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         Some(n) => FromStr::from_str(n)?,
     };
 
     // Interpret the second piece as a language tag
     let lang: Option<LanguageTag> = match parts.next() {
-        None => return Err(::Error::Header),
+        None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         Some("") => None,
         Some(s) => match s.parse() {
             Ok(lt) => Some(lt),
-            Err(_) => return Err(::Error::Header),
+            Err(e) => return Err(::Error::header_with_source(::HeaderErrorKind::Syntax, e)),
         },
     };
 
     // Interpret the third piece as a sequence of value characters
     let value: Vec<u8> = match parts.next() {
-        None => return Err(::Error::Header),
+        None => return Err(::Error::header(::HeaderErrorKind::Syntax)),
         Some(v) => percent_encoding::percent_decode(v.as_bytes()).collect(),
     };
 
@@ -185,8 +981,10 @@ mod percent_encoding_http {
         .add(b';')
         .add(b'<')
         .add(b'-')
+        .add(b'=')
         .add(b'>')
         .add(b'?')
+        .add(b'@')
         .add(b'[')
         .add(b'\\')
         .add(b']')
@@ -196,10 +994,33 @@ mod percent_encoding_http {
 
 #[cfg(test)]
 mod tests {
-    use super::{parse_extended_value, ExtendedValue};
+    use super::{
+        from_comma_delimited, from_one_raw_str, is_folded, is_strict, parse_extended_value,
+        parse_parameter, split_delimited, unfold, ExtendedValue, Limit, ParseConfig,
+    };
     use header::shared::Charset;
+    use header::{Encoding, Raw};
     use language_tags::LanguageTag;
 
+    #[test]
+    fn test_split_delimited() {
+        let items: Vec<&str> =
+            split_delimited(r#"form-data; name="a;\"b"; filename="é.txt";"#, ';').collect();
+        assert_eq!(
+            items,
+            ["form-data", r#"name="a;\"b""#, r#"filename="é.txt""#]
+        );
+        let (name, value) = parse_parameter(items[1]).unwrap();
+        assert_eq!(name, "name");
+        assert_eq!(value.unwrap(), "a;\"b");
+
+        assert_eq!(split_delimited(" , ,", ',').next(), None);
+        assert_eq!(split_delimited("", ',').next(), None);
+        // An unterminated quote extends to the end
+        let items: Vec<&str> = split_delimited(r#"a, "b, c"#, ',').collect();
+        assert_eq!(items, ["a", r#""b, c"#]);
+    }
+
     #[test]
     fn test_parse_extended_value_with_encoding_and_language_tag() {
         let expected_language_tag = "en".parse::<LanguageTag>().unwrap();
@@ -265,6 +1086,24 @@ mod tests {
         assert_eq!("ISO-8859-1'en'%A3%20rates", format!("{}", extended_value));
     }
 
+    #[test]
+    fn test_extended_value_round_trip() {
+        let ev = ExtendedValue::utf8("a=b@c 'd' 统计.txt");
+        let encoded = ev.to_string();
+        assert_eq!(
+            encoded,
+            "UTF-8''a%3Db%40c%20%27d%27%20%E7%BB%9F%E8%AE%A1.txt"
+        );
+        let decoded: ExtendedValue = encoded.parse().unwrap();
+        assert_eq!(decoded, ev);
+        assert_eq!(decoded.to_text().unwrap(), "a=b@c 'd' 统计.txt");
+
+        let latin1 = parse_extended_value("iso-8859-1''%A3").unwrap();
+        assert_eq!(latin1.to_text().unwrap(), "£");
+        let other = parse_extended_value("koi8-r''%A3").unwrap();
+        assert_eq!(other.to_text(), None);
+    }
+
     #[test]
     fn test_fmt_extended_value_with_encoding() {
         let extended_value = ExtendedValue {
@@ -280,4 +1119,136 @@ mod tests {
             format!("{}", extended_value)
         );
     }
+
+    #[test]
+    fn test_strict() {
+        let strict = ParseConfig::new().strict(true);
+        assert!(!is_strict());
+        assert!(strict.scope(is_strict));
+        assert!(!is_strict());
+
+        let raw: Raw = "1, x, 2".into();
+        let lenient: Vec<u32> = from_comma_delimited(&raw).unwrap();
+        assert_eq!(lenient, [1, 2]);
+        assert!(strict
+            .scope(|| from_comma_delimited::<_, u32>(&raw))
+            .is_err());
+
+        let raw: Raw = "gzip, \"br".into();
+        assert!(from_comma_delimited::<_, Encoding>(&raw).is_ok());
+        assert!(strict
+            .scope(|| from_comma_delimited::<_, Encoding>(&raw))
+            .is_err());
+
+        let raw: Raw = "a\x01b".into();
+        assert!(from_one_raw_str::<_, String>(&raw).is_ok());
+        assert!(strict
+            .scope(|| from_one_raw_str::<_, String>(&raw))
+            .is_err());
+    }
+
+    #[test]
+    fn test_scan() {
+        use header::{RawLike, RawSlice};
+        use http::header::HeaderValue;
+
+        let strict = ParseConfig::new().strict(true);
+        // Control characters trimmed along with non-ASCII whitespace
+        let raw: Raw = "\u{a0}\r\nb\r\n".into();
+        let s: String = strict.scope(|| from_one_raw_str(&raw)).unwrap();
+        assert_eq!(s, "b");
+        let raw: Raw = "\u{a0}b\r\nc\u{a0}".into();
+        assert!(strict
+            .scope(|| from_one_raw_str::<_, String>(&raw))
+            .is_err());
+
+        let value = HeaderValue::from_static(" 1, \"2\" ");
+        assert!((&value).is_validated());
+        assert!(RawSlice::with(&&value, |raw| raw.is_validated()));
+        assert!(!RawSlice::with(&raw, |raw| raw.is_validated()));
+        let items: Vec<String> = strict.scope(|| from_comma_delimited(&&value)).unwrap();
+        assert_eq!(items, ["1", "\"2\""]);
+        let value = HeaderValue::from_static("\"1, 2");
+        assert!(strict
+            .scope(|| from_comma_delimited::<_, String>(&&value))
+            .is_err());
+    }
+
+    #[test]
+    fn test_limits() {
+        use header::{CacheControl, Cookie, Header, SetCookie};
+        use {ErrorKind, HeaderErrorKind};
+
+        let config = ParseConfig::new()
+            .limit(Limit::ValueLength, 16)
+            .limit(Limit::Items, 3)
+            .limit(Limit::CookiePairs, 2)
+            .limit(Limit::Directives, 2);
+        assert_eq!(config.get_limit(Limit::Items), Some(3));
+        assert_eq!(ParseConfig::new().get_limit(Limit::Items), None);
+
+        let raw: Raw = vec![b"1, 2".to_vec(), b"3".to_vec()].into();
+        assert_eq!(
+            config
+                .scope(|| from_comma_delimited::<_, u32>(&raw))
+                .unwrap(),
+            [1, 2, 3]
+        );
+        let raw: Raw = "1, 2, 3, 4".into();
+        let e = config
+            .scope(|| from_comma_delimited::<_, u32>(&raw))
+            .unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TooLarge);
+        assert_eq!(
+            e.header_error().map(|e| e.kind()),
+            Some(HeaderErrorKind::TooLarge)
+        );
+
+        let raw: Raw = "a value which is too long".into();
+        assert!(config
+            .scope(|| from_one_raw_str::<_, String>(&raw))
+            .is_err());
+        let e = config.parse::<SetCookie, _>(&raw).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::TooLarge);
+        assert_eq!(e.header_error().and_then(|e| e.name()), Some("Set-Cookie"));
+
+        let raw: Raw = "a=1; b=2; c=3".into();
+        assert!(config.parse::<Cookie, _>(&raw).is_err());
+        assert!(Cookie::parse_header(&raw).is_ok());
+
+        let raw: Raw = vec![b"a=1".to_vec(), b"b=2".to_vec(), b"c=3".to_vec()].into();
+        assert!(config.parse::<SetCookie, _>(&raw).is_err());
+
+        let raw: Raw = "no-cache, no-store, private".into();
+        assert!(config.parse::<CacheControl, _>(&raw).is_err());
+    }
+
+    #[test]
+    fn test_obs_fold() {
+        use header::{ContentType, Header, IfModifiedSince};
+
+        assert!(is_folded(b"a,\r\n b"));
+        assert!(!is_folded(b"a, b"));
+        assert!(!is_folded(b"a\r\n"));
+        assert_eq!(unfold("a\r\n \t b"), "a b");
+        assert_eq!(unfold("a\r\n"), "a\r\n");
+        assert_eq!(unfold("\n b"), " b");
+
+        let raw: Raw = "text/plain;\r\n charset=utf-8".into();
+        assert_eq!(
+            ContentType::parse_header(&raw).unwrap(),
+            ContentType::text_utf8()
+        );
+        let raw: Raw = "Sun, 06 Nov 1994\r\n 08:49:37 GMT".into();
+        assert!(IfModifiedSince::parse_header(&raw).is_ok());
+
+        let raw: Raw = "1,\r\n 2,\n\t3".into();
+        assert_eq!(from_comma_delimited::<_, u32>(&raw).unwrap(), [1, 2, 3]);
+
+        let strict = ParseConfig::new().strict(true);
+        assert!(strict.parse::<ContentType, _>(&raw).is_err());
+        assert!(strict
+            .scope(|| from_comma_delimited::<_, u32>(&raw))
+            .is_err());
+    }
 }
@@ -1,6 +1,11 @@
 use bytes::Bytes;
+use http::header::HeaderValue;
 use std::borrow::Cow;
 use std::fmt;
+use std::iter;
+use std::slice;
+
+use super::{Formatter, Header, Multi};
 
 /// Trait for raw bytes parsing access to header values (aka lines) for a single
 /// header name.
@@ -11,19 +16,55 @@ pub trait RawLike<'a> {
     /// Return the number of values (lines) in the headers.
     fn len(&'a self) -> usize;
 
+    /// Return `true` if there are no values (lines) in the headers.
+    fn is_empty(&'a self) -> bool {
+        self.len() == 0
+    }
+
     /// Return the single value (line), if and only if there is exactly
     /// one. Otherwise return `None`.
     fn one(&'a self) -> Option<&'a [u8]>;
 
     /// Iterate the values (lines) as raw bytes.
     fn iter(&'a self) -> Self::IntoIter;
+
+    /// Return `true` if the values are known to contain no control
+    /// characters other than horizontal tab, as guaranteed by
+    /// `http::HeaderValue`, so that parsers need not check for them, nor for
+    /// obsolete line folding.
+    ///
+    /// The default implementation returns `false`.
+    fn is_validated(&'a self) -> bool {
+        false
+    }
 }
 
 /// A raw header value.
+///
+/// Values (lines) are stored as reference-counted byte buffers, so cloning a
+/// `Raw` or constructing one from `bytes::Bytes` or `http::HeaderValue` does
+/// not copy the underlying bytes.
 #[derive(Clone, Debug)]
 pub struct Raw(Lines);
 
 impl Raw {
+    /// Format a header into its lines, one per `Formatter::fmt_line` call,
+    /// as they would be written to an HTTP/1 message or a `http::HeaderMap`.
+    ///
+    /// ```
+    /// use hyperx::header::{Raw, SetCookie};
+    ///
+    /// let cookies = SetCookie(vec!["a=1".parse().unwrap(), "b=2".parse().unwrap()]);
+    /// assert_eq!(Raw::from_header(&cookies), ["a=1", "b=2"][..]);
+    /// ```
+    pub fn from_header<H: Header + ?Sized>(header: &H) -> Raw {
+        let mut raw = new();
+        header
+            .fmt_header(&mut Formatter(Multi::Raw(&mut raw)))
+            .expect("fmt failed");
+        raw
+    }
+
     /// Append a line to this `Raw` header value.
     pub fn push<V: Into<Raw>>(&mut self, val: V) {
         let raw = val.into();
@@ -38,7 +79,7 @@ impl Raw {
         }
     }
 
-    fn push_line(&mut self, line: Bytes) {
+    fn push_line(&mut self, line: Line) {
         let lines = ::std::mem::replace(&mut self.0, Lines::Empty);
         match lines {
             Lines::Empty => {
@@ -88,8 +129,43 @@ impl<'a> RawLike<'a> for Raw {
 #[derive(Clone)]
 enum Lines {
     Empty,
-    One(Bytes),
-    Many(Vec<Bytes>),
+    One(Line),
+    Many(Vec<Line>),
+}
+
+// A single value, either shared bytes or a `HeaderValue` which is itself a
+// reference-counted buffer, but does not expose it.
+#[derive(Clone)]
+enum Line {
+    Shared(Bytes),
+    Value(HeaderValue),
+}
+
+impl AsRef<[u8]> for Line {
+    #[inline]
+    fn as_ref(&self) -> &[u8] {
+        match *self {
+            Line::Shared(ref bytes) => bytes.as_ref(),
+            Line::Value(ref value) => value.as_bytes(),
+        }
+    }
+}
+
+impl fmt::Debug for Line {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("b\"")?;
+        for &b in self.as_ref() {
+            match b {
+                b'\n' => f.write_str("\\n")?,
+                b'\r' => f.write_str("\\r")?,
+                b'\t' => f.write_str("\\t")?,
+                b'\\' | b'"' => write!(f, "\\{}", b as char)?,
+                0x20..=0x7e => write!(f, "{}", b as char)?,
+                _ => write!(f, "\\x{:02x}", b)?,
+            }
+        }
+        f.write_str("\"")
+    }
 }
 
 fn eq_many<A: AsRef<[u8]>, B: AsRef<[u8]>>(a: &[A], b: &[B]) -> bool {
@@ -131,7 +207,7 @@ impl PartialEq<[Vec<u8>]> for Raw {
     }
 }
 
-impl<'a> PartialEq<[&'a [u8]]> for Raw {
+impl PartialEq<[&[u8]]> for Raw {
     fn eq(&self, bytes: &[&[u8]]) -> bool {
         eq(self, bytes)
     }
@@ -170,7 +246,7 @@ impl From<Vec<Vec<u8>>> for Raw {
     fn from(val: Vec<Vec<u8>>) -> Raw {
         Raw(Lines::Many(
             val.into_iter()
-                .map(|vec| maybe_literal(vec.into()))
+                .map(|vec| Line::Shared(maybe_literal(vec.into())))
                 .collect(),
         ))
     }
@@ -186,7 +262,7 @@ impl From<String> for Raw {
 impl From<Vec<u8>> for Raw {
     #[inline]
     fn from(val: Vec<u8>) -> Raw {
-        Raw(Lines::One(maybe_literal(val.into())))
+        Raw(Lines::One(Line::Shared(maybe_literal(val.into()))))
     }
 }
 
@@ -198,32 +274,59 @@ impl<'a> From<&'a str> for Raw {
 
 impl<'a> From<&'a [u8]> for Raw {
     fn from(val: &'a [u8]) -> Raw {
-        Raw(Lines::One(maybe_literal(val.into())))
+        Raw(Lines::One(Line::Shared(maybe_literal(val.into()))))
     }
 }
 
+/// Constructs a single value `Raw` sharing (without copying) the given bytes.
 impl From<Bytes> for Raw {
     #[inline]
     fn from(val: Bytes) -> Raw {
-        Raw(Lines::One(val))
+        Raw(Lines::One(Line::Shared(val)))
+    }
+}
+
+/// Constructs a single value `Raw` sharing (without copying) the bytes of
+/// the given `HeaderValue`.
+impl From<HeaderValue> for Raw {
+    #[inline]
+    fn from(val: HeaderValue) -> Raw {
+        Raw(Lines::One(Line::Value(val)))
+    }
+}
+
+/// Constructs a single value `Raw` sharing (without copying) the bytes of
+/// the given `HeaderValue`, via its cheap, reference-counted `clone`.
+impl<'a> From<&'a HeaderValue> for Raw {
+    #[inline]
+    fn from(val: &'a HeaderValue) -> Raw {
+        Raw(Lines::One(Line::Value(val.clone())))
     }
 }
 
 #[cfg(feature = "headers")]
 pub fn parsed(val: Bytes) -> Raw {
-    Raw(Lines::One(From::from(val)))
+    Raw(Lines::One(Line::Shared(val)))
 }
 
 #[cfg(feature = "headers")]
 pub fn push(raw: &mut Raw, val: Bytes) {
-    raw.push_line(val);
+    raw.push_line(Line::Shared(val));
 }
 
-#[cfg(feature = "headers")]
 pub fn new() -> Raw {
     Raw(Lines::Empty)
 }
 
+#[cfg(feature = "headers")]
+pub fn no_lines() -> RawLines<'static> {
+    static EMPTY: Lines = Lines::Empty;
+    RawLines {
+        inner: &EMPTY,
+        pos: 0,
+    }
+}
+
 impl fmt::Debug for Lines {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match *self {
@@ -254,6 +357,7 @@ impl ::std::ops::Index<usize> for Raw {
 
 macro_rules! literals {
     ($($len:expr => $($value:expr),+;)+) => (
+        #[allow(clippy::collapsible_match)]
         fn maybe_literal(s: Cow<[u8]>) -> Bytes {
             match s.len() {
                 $($len => {
@@ -300,6 +404,82 @@ impl<'a> IntoIterator for &'a Raw {
     }
 }
 
+/// The values (lines) of any `RawLike` header, as a borrowed slice.
+///
+/// `Header::parse_header` is generic over the raw type, so is compiled once
+/// per header for each raw type used. A parser may instead delegate to a
+/// non-generic function of a `RawSlice`, as `header!` does, so that it is
+/// compiled only once.
+///
+/// ```
+/// use hyperx::header::{parsing, Raw, RawLike, RawSlice};
+///
+/// fn parse(raw: &RawSlice) -> hyperx::Result<u32> {
+///     parsing::from_one_raw_str(raw)
+/// }
+///
+/// let raw = Raw::from("42");
+/// assert_eq!(RawSlice::with(&raw, parse).unwrap(), 42);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct RawSlice<'a> {
+    lines: &'a [&'a [u8]],
+    validated: bool,
+}
+
+impl<'a> RawSlice<'a> {
+    /// Create a view of the given values (lines).
+    pub fn new(lines: &'a [&'a [u8]]) -> RawSlice<'a> {
+        RawSlice {
+            lines,
+            validated: false,
+        }
+    }
+
+    /// Call the given function with a view of the values of a raw header.
+    /// Up to four values are collected without allocating.
+    pub fn with<'r, R, F, T>(raw: &'r R, f: F) -> T
+    where
+        R: RawLike<'r>,
+        F: FnOnce(&RawSlice<'_>) -> T,
+    {
+        let lines: ::smallvec::SmallVec<[&[u8]; 4]> = raw.iter().collect();
+        f(&RawSlice {
+            lines: &lines,
+            validated: raw.is_validated(),
+        })
+    }
+}
+
+impl<'a, 'b: 'a> RawLike<'a> for RawSlice<'b> {
+    type IntoIter = iter::Copied<slice::Iter<'a, &'a [u8]>>;
+
+    #[inline]
+    fn len(&'a self) -> usize {
+        self.lines.len()
+    }
+
+    #[inline]
+    fn one(&'a self) -> Option<&'a [u8]> {
+        match *self.lines {
+            [line] => Some(line),
+            _ => None,
+        }
+    }
+
+    #[inline]
+    fn iter(&'a self) -> Self::IntoIter {
+        let lines: &'a [&'a [u8]] = self.lines;
+        lines.iter().copied()
+    }
+
+    #[inline]
+    fn is_validated(&'a self) -> bool {
+        self.validated
+    }
+}
+
+/// An `Iterator` over the values (lines) of a `Raw` header value.
 pub struct RawLines<'a> {
     inner: &'a Lines,
     pos: usize,
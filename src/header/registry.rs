@@ -0,0 +1,166 @@
+//! Runtime dispatch of header parsing and formatting by header name.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use http::header::HeaderName;
+
+use super::{interned, known, Header, Raw};
+
+type ParseFn = fn(&Raw) -> ::Result<Box<dyn Header + Send + Sync>>;
+
+fn parse_boxed<H: Header + Clone>(raw: &Raw) -> ::Result<Box<dyn Header + Send + Sync>> {
+    interned::parse_header(raw)
+        .map(|h: H| Box::new(h) as Box<dyn Header + Send + Sync>)
+        .map_err(|e| e.with_header_name(H::header_name()))
+}
+
+/// A registry of header parsers by (case-insensitive) header name.
+///
+/// Allows parsing a header by a runtime name into a boxed typed value, for
+/// generic header processing that isn't monomorphized per header type. The
+/// boxed `dyn Header` can be named, formatted, cloned and downcast to its
+/// concrete type.
+///
+/// ```
+/// use hyperx::header::{ContentLength, HeaderRegistry, Raw};
+///
+/// let registry = HeaderRegistry::standard();
+/// let raw = Raw::from("42");
+/// let header = registry.parse("content-length", &raw).unwrap().unwrap();
+/// assert_eq!(header.name(), "Content-Length");
+/// assert_eq!(header.downcast_ref::<ContentLength>(), Some(&ContentLength(42)));
+/// assert_eq!(header.to_raw(), *"42");
+///
+/// assert!(registry.parse("x-unknown", &raw).is_none());
+/// ```
+#[derive(Clone, Default)]
+pub struct HeaderRegistry {
+    parsers: HashMap<HeaderName, ParseFn>,
+}
+
+impl HeaderRegistry {
+    /// Create a new, empty registry.
+    pub fn new() -> HeaderRegistry {
+        HeaderRegistry::default()
+    }
+
+    /// Create a registry with all non-generic typed headers of this crate,
+    /// as decoded by [`KnownHeader`](enum.KnownHeader.html).
+    pub fn standard() -> HeaderRegistry {
+        let mut registry = HeaderRegistry::new();
+        known::register_all(&mut registry);
+        registry
+    }
+
+    /// Register header type `H` by its name, replacing any existing parser
+    /// for the same name.
+    ///
+    /// # Panics
+    ///
+    /// If the name of `H` is not a valid header name.
+    pub fn register<H: Header + Clone>(&mut self) -> &mut HeaderRegistry {
+        self.parsers.insert(H::http_name(), parse_boxed::<H>);
+        self
+    }
+
+    /// Returns true if a parser is registered for the header name.
+    pub fn contains(&self, name: &str) -> bool {
+        self.parser(name).is_some()
+    }
+
+    /// Parse the raw value of a header by name, if a parser is registered for
+    /// the name, or return `None` otherwise.
+    pub fn parse(&self, name: &str, raw: &Raw) -> Option<::Result<Box<dyn Header + Send + Sync>>> {
+        self.parser(name).map(|parse| parse(raw))
+    }
+
+    fn parser(&self, name: &str) -> Option<&ParseFn> {
+        HeaderName::from_bytes(name.as_bytes())
+            .ok()
+            .and_then(|name| self.parsers.get(&name))
+    }
+}
+
+impl fmt::Debug for HeaderRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_set().entries(self.parsers.keys()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::HeaderRegistry;
+    use header::{CacheControl, CacheDirective, ContentType, Header, Raw, SetCookie};
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct XCount(u32);
+
+    impl Header for XCount {
+        fn header_name() -> &'static str {
+            "X-Count"
+        }
+
+        fn parse_header<'a, T>(raw: &'a T) -> ::Result<XCount>
+        where
+            T: ::header::RawLike<'a>,
+        {
+            ::header::parsing::from_one_raw_str(raw).map(XCount)
+        }
+
+        fn fmt_header(&self, f: &mut ::header::Formatter) -> ::std::fmt::Result {
+            f.fmt_line(&self.0)
+        }
+    }
+
+    #[test]
+    fn test_standard() {
+        let registry = HeaderRegistry::standard();
+        assert!(registry.contains("Content-Type"));
+        assert!(registry.contains("set-cookie"));
+        assert!(!registry.contains("X-Count"));
+        assert!(!registry.contains("bad name"));
+
+        let ct = registry
+            .parse("Content-Type", &Raw::from("application/json"))
+            .unwrap()
+            .unwrap();
+        assert!(ct.is::<ContentType>());
+        assert_eq!(ct.downcast_ref(), Some(&ContentType::json()));
+        assert!(ct.downcast_ref::<SetCookie>().is_none());
+
+        let raw: Raw = vec![b"a=1".to_vec(), b"b=2".to_vec()].into();
+        let sc = registry.parse("set-cookie", &raw).unwrap().unwrap();
+        assert_eq!(sc.to_raw(), raw);
+
+        let e = registry
+            .parse("Cache-Control", &Raw::from(""))
+            .unwrap()
+            .unwrap_err();
+        assert_eq!(
+            e.header_error().and_then(|e| e.name()),
+            Some("Cache-Control")
+        );
+    }
+
+    #[test]
+    fn test_register() {
+        let mut registry = HeaderRegistry::new();
+        registry.register::<XCount>();
+        assert!(registry.contains("x-count"));
+
+        let mut headers: Vec<Box<dyn Header + Send + Sync>> = vec![
+            registry.parse("x-count", &Raw::from("1")).unwrap().unwrap(),
+            Box::new(CacheControl(vec![CacheDirective::NoCache])),
+        ];
+        for h in headers.iter_mut() {
+            if let Some(count) = h.downcast_mut::<XCount>() {
+                count.0 += 1;
+            }
+        }
+        let cloned = headers.clone();
+        assert_eq!(cloned[0].downcast_ref(), Some(&XCount(2)));
+        assert_eq!(cloned[1].name(), "Cache-Control");
+        assert_eq!(format!("{:?}", cloned[0]), "X-Count: 2");
+    }
+}
@@ -0,0 +1,220 @@
+//! Implementation module for the _serde_ feature: serialization of typed
+//! headers in their string form.
+
+use std::fmt;
+use std::str;
+
+use serde::de::{self, Deserializer, SeqAccess, Visitor};
+use serde::ser::{self, SerializeSeq, Serializer};
+
+#[cfg(feature = "headers")]
+use serde::de::MapAccess;
+#[cfg(feature = "headers")]
+use serde::ser::SerializeMap;
+#[cfg(feature = "headers")]
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "headers")]
+use super::Headers;
+use super::{raw, Header, Raw, RawLike};
+
+/// Serialize a header as its formatted value string, or as a sequence of
+/// strings if it formats to multiple lines (e.g. `SetCookie`).
+pub fn serialize<H, S>(header: &H, serializer: S) -> Result<S::Ok, S::Error>
+where
+    H: Header + ?Sized,
+    S: Serializer,
+{
+    let raw = Raw::from_header(header);
+    if let Some(line) = raw.one() {
+        return serializer.serialize_str(str::from_utf8(line).map_err(ser::Error::custom)?);
+    }
+    let mut seq = serializer.serialize_seq(Some(raw.len()))?;
+    for line in raw.iter() {
+        seq.serialize_element(str::from_utf8(line).map_err(ser::Error::custom)?)?;
+    }
+    seq.end()
+}
+
+/// Deserialize a header by parsing a value string, or each string of a
+/// sequence as one line of the header.
+pub fn deserialize<'de, H, D>(deserializer: D) -> Result<H, D::Error>
+where
+    H: Header,
+    D: Deserializer<'de>,
+{
+    let raw = deserializer.deserialize_any(RawVisitor)?;
+    H::parse_header(&raw).map_err(de::Error::custom)
+}
+
+struct RawVisitor;
+
+impl<'de> Visitor<'de> for RawVisitor {
+    type Value = Raw;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a header value string or sequence of strings")
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Raw, E> {
+        Ok(Raw::from(v.to_owned()))
+    }
+
+    fn visit_string<E: de::Error>(self, v: String) -> Result<Raw, E> {
+        Ok(Raw::from(v))
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Raw, A::Error> {
+        let mut raw = raw::new();
+        while let Some(line) = seq.next_element::<String>()? {
+            raw.push(line);
+        }
+        Ok(raw)
+    }
+}
+
+/// Serialized as an ordered map of header names to sequences of value
+/// strings, with one string per line. Requires the `headers` and `serde`
+/// features.
+#[cfg(feature = "headers")]
+impl Serialize for Headers {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut map = serializer.serialize_map(Some(self.len()))?;
+        for header in self.iter() {
+            map.serialize_entry(header.name(), &Lines(header.raw()))?;
+        }
+        map.end()
+    }
+}
+
+/// Deserialized from a map of header names to value strings or sequences of
+/// value strings. Values for repeated names are appended in order.
+#[cfg(feature = "headers")]
+impl<'de> Deserialize<'de> for Headers {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Headers, D::Error> {
+        deserializer.deserialize_map(HeadersVisitor)
+    }
+}
+
+#[cfg(feature = "headers")]
+struct Lines<'a>(&'a Raw);
+
+#[cfg(feature = "headers")]
+impl<'a> Serialize for Lines<'a> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.0.len()))?;
+        for line in self.0.iter() {
+            seq.serialize_element(str::from_utf8(line).map_err(ser::Error::custom)?)?;
+        }
+        seq.end()
+    }
+}
+
+#[cfg(feature = "headers")]
+struct RawValue(Raw);
+
+#[cfg(feature = "headers")]
+impl<'de> Deserialize<'de> for RawValue {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<RawValue, D::Error> {
+        deserializer.deserialize_any(RawVisitor).map(RawValue)
+    }
+}
+
+#[cfg(feature = "headers")]
+struct HeadersVisitor;
+
+#[cfg(feature = "headers")]
+impl<'de> Visitor<'de> for HeadersVisitor {
+    type Value = Headers;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a map of header names to values")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Headers, A::Error> {
+        let mut headers = Headers::with_capacity(map.size_hint().unwrap_or(0));
+        while let Some((name, RawValue(raw))) = map.next_entry::<String, RawValue>()? {
+            if raw.len() > 0 {
+                headers.append_raw(name, raw);
+            }
+        }
+        Ok(headers)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use header::{
+        CacheControl, CacheDirective, ContentType, IfMatch, SetCookie, StrictTransportSecurity,
+    };
+    use serde_json;
+
+    #[test]
+    fn test_string_form() {
+        let cc = CacheControl(vec![CacheDirective::NoCache, CacheDirective::MaxAge(60)]);
+        let json = serde_json::to_string(&cc).unwrap();
+        assert_eq!(json, r#""no-cache, max-age=60""#);
+        assert_eq!(serde_json::from_str::<CacheControl>(&json).unwrap(), cc);
+
+        let ct: ContentType = serde_json::from_str(r#""application/json""#).unwrap();
+        assert_eq!(ct, ContentType::json());
+
+        let im: IfMatch = serde_json::from_str(r#""*""#).unwrap();
+        assert_eq!(im, IfMatch::Any);
+
+        let sts: StrictTransportSecurity = serde_json::from_str(r#""max-age=60""#).unwrap();
+        assert_eq!(sts, StrictTransportSecurity::excluding_subdomains(60));
+    }
+
+    #[test]
+    fn test_multi_line() {
+        let cookies = SetCookie(vec![
+            "a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT"
+                .parse()
+                .unwrap(),
+            "b=2".parse().unwrap(),
+        ]);
+        let json = serde_json::to_string(&cookies).unwrap();
+        assert_eq!(
+            json,
+            r#"["a=1; Expires=Wed, 21 Oct 2015 07:28:00 GMT","b=2"]"#
+        );
+        assert_eq!(serde_json::from_str::<SetCookie>(&json).unwrap(), cookies);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let e = serde_json::from_str::<CacheControl>("42").unwrap_err();
+        assert!(e.to_string().contains("header value string"));
+        assert!(serde_json::from_str::<ContentType>(r#""""#).is_err());
+    }
+
+    #[cfg(feature = "headers")]
+    #[test]
+    fn test_headers() {
+        use header::{ContentLength, Headers};
+
+        let mut headers = Headers::new();
+        headers.set(ContentLength(11));
+        headers.set(SetCookie(vec![
+            "a=1".parse().unwrap(),
+            "b=2".parse().unwrap(),
+        ]));
+        headers.set_raw("X-Custom", "x");
+
+        let json = serde_json::to_string(&headers).unwrap();
+        assert_eq!(
+            json,
+            r#"{"Content-Length":["11"],"Set-Cookie":["a=1","b=2"],"X-Custom":["x"]}"#
+        );
+        let back: Headers = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, headers);
+        assert_eq!(back.get(), Some(&ContentLength(11)));
+
+        let headers: Headers =
+            serde_json::from_str(r#"{"x-a":"1","X-B":["2"],"x-a":["3"],"x-c":[]}"#).unwrap();
+        assert_eq!(*headers.get_raw("x-a").unwrap(), ["1", "3"][..]);
+        assert_eq!(headers.get_raw("x-b").unwrap(), "2");
+        assert_eq!(headers.len(), 2);
+    }
+}
@@ -0,0 +1,1315 @@
+//! Structured Field Values for HTTP, defined in
+//! [RFC9651](https://www.rfc-editor.org/rfc/rfc9651), which obsoletes
+//! [RFC8941](https://www.rfc-editor.org/rfc/rfc8941).
+//!
+//! Newer fields, such as `Priority` or `Cache-Status`, are defined as one of
+//! three structured types: an [`Item`](struct.Item.html), a
+//! [`List`](struct.List.html) or a [`Dictionary`](struct.Dictionary.html).
+//! Each parses from a field value via `FromStr`, or from all values (lines)
+//! of a field via `from_raw`, and formats to its serialization via
+//! `Display`.
+//!
+//! Values are validated on construction, such that formatting can not
+//! fail: an `Integer` is of at most 15 digits, a `Token` or key of valid
+//! characters only, and an `SfString` of printable ASCII characters (use
+//! `BareItem::DisplayString` for others).
+//!
+//! # Example
+//!
+//! A custom header on top of a `Dictionary`:
+//!
+//! ```
+//! use std::fmt;
+//! use hyperx::header::sfv::{BareItem, Dictionary, Item};
+//! use hyperx::header::{Formatter, Header, Raw, RawLike};
+//!
+//! #[derive(Clone, Debug)]
+//! struct Priority(Dictionary);
+//!
+//! impl Header for Priority {
+//!     fn header_name() -> &'static str {
+//!         "Priority"
+//!     }
+//!
+//!     fn parse_header<'a, T: RawLike<'a>>(raw: &'a T) -> hyperx::Result<Priority> {
+//!         Dictionary::from_raw(raw).map(Priority)
+//!     }
+//!
+//!     fn fmt_header(&self, f: &mut Formatter) -> fmt::Result {
+//!         f.fmt_line(&self.0)
+//!     }
+//! }
+//!
+//! let p = Priority::parse_header(&Raw::from("u=1, i")).unwrap();
+//! let urgency = p.0.get("u").and_then(|m| m.as_item());
+//! assert_eq!(urgency.map(|i| &i.bare_item), Some(&BareItem::from(1)));
+//! assert_eq!(p.0.get("i").and_then(|m| m.as_item()), Some(&Item::new(true)));
+//!
+//! let mut dict = Dictionary::new();
+//! dict.insert("u", Item::new(5)).unwrap();
+//! dict.insert("i", Item::new(false)).unwrap();
+//! assert_eq!(dict.to_string(), "u=5, i=?0");
+//! assert!(dict.insert("U", Item::new(5)).is_err());
+//! ```
+
+use std::convert::TryFrom;
+use std::fmt::{self, Display, Write};
+use std::ops::{Deref, DerefMut};
+use std::str::{self, FromStr};
+
+use header::{Base64, RawLike};
+
+const MAX_INTEGER: i64 = 999_999_999_999_999;
+
+/// A bare item, the value of an `Item` or of a parameter.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum BareItem {
+    /// An integer, of at most 15 digits.
+    Integer(Integer),
+    /// A decimal, of at most 12 integer and 3 fractional digits.
+    Decimal(Decimal),
+    /// A string, of printable ASCII characters.
+    String(SfString),
+    /// A token, such as `text/html` or `*`.
+    Token(Token),
+    /// A byte sequence, serialized in base64.
+    ByteSequence(Vec<u8>),
+    /// A boolean, serialized as `?1` or `?0`.
+    Boolean(bool),
+    /// A date, in seconds since the Unix epoch.
+    Date(Integer),
+    /// A string of Unicode characters, serialized percent-encoded.
+    DisplayString(String),
+}
+
+impl BareItem {
+    /// The value of an `Integer`.
+    pub fn as_integer(&self) -> Option<i64> {
+        match *self {
+            BareItem::Integer(i) => Some(i.get()),
+            _ => None,
+        }
+    }
+
+    /// The value of a `Decimal`.
+    pub fn as_decimal(&self) -> Option<Decimal> {
+        match *self {
+            BareItem::Decimal(d) => Some(d),
+            _ => None,
+        }
+    }
+
+    /// The value of a `String`.
+    pub fn as_string(&self) -> Option<&str> {
+        match *self {
+            BareItem::String(ref s) => Some(s.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The value of a `Token`.
+    pub fn as_token(&self) -> Option<&str> {
+        match *self {
+            BareItem::Token(ref t) => Some(t.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The value of a `ByteSequence`.
+    pub fn as_byte_sequence(&self) -> Option<&[u8]> {
+        match *self {
+            BareItem::ByteSequence(ref b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// The value of a `Boolean`.
+    pub fn as_boolean(&self) -> Option<bool> {
+        match *self {
+            BareItem::Boolean(b) => Some(b),
+            _ => None,
+        }
+    }
+
+    /// The value of a `Date`.
+    pub fn as_date(&self) -> Option<i64> {
+        match *self {
+            BareItem::Date(d) => Some(d.get()),
+            _ => None,
+        }
+    }
+
+    /// The value of a `DisplayString`.
+    pub fn as_display_string(&self) -> Option<&str> {
+        match *self {
+            BareItem::DisplayString(ref s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+impl From<Integer> for BareItem {
+    fn from(i: Integer) -> BareItem {
+        BareItem::Integer(i)
+    }
+}
+
+impl From<Decimal> for BareItem {
+    fn from(d: Decimal) -> BareItem {
+        BareItem::Decimal(d)
+    }
+}
+
+impl From<SfString> for BareItem {
+    fn from(s: SfString) -> BareItem {
+        BareItem::String(s)
+    }
+}
+
+impl From<Token> for BareItem {
+    fn from(t: Token) -> BareItem {
+        BareItem::Token(t)
+    }
+}
+
+impl From<bool> for BareItem {
+    fn from(b: bool) -> BareItem {
+        BareItem::Boolean(b)
+    }
+}
+
+impl From<Vec<u8>> for BareItem {
+    fn from(b: Vec<u8>) -> BareItem {
+        BareItem::ByteSequence(b)
+    }
+}
+
+impl Display for BareItem {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            BareItem::Integer(i) => Display::fmt(&i, f),
+            BareItem::Decimal(d) => Display::fmt(&d, f),
+            BareItem::String(ref s) => {
+                f.write_char('"')?;
+                for c in s.as_str().chars() {
+                    if c == '"' || c == '\\' {
+                        f.write_char('\\')?;
+                    }
+                    f.write_char(c)?;
+                }
+                f.write_char('"')
+            }
+            BareItem::Token(ref t) => f.write_str(t.as_str()),
+            BareItem::ByteSequence(ref b) => write!(f, ":{}:", Base64::STANDARD.encode(b)),
+            BareItem::Boolean(b) => f.write_str(if b { "?1" } else { "?0" }),
+            BareItem::Date(d) => write!(f, "@{}", d),
+            BareItem::DisplayString(ref s) => {
+                f.write_str("%\"")?;
+                for b in s.bytes() {
+                    match b {
+                        b'%' | b'"' => write!(f, "%{:02x}", b)?,
+                        b' '..=b'~' => f.write_char(b as char)?,
+                        _ => write!(f, "%{:02x}", b)?,
+                    }
+                }
+                f.write_char('"')
+            }
+        }
+    }
+}
+
+/// An integer, of at most 15 digits, the value of `BareItem::Integer` or
+/// `BareItem::Date`.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use hyperx::header::sfv::Integer;
+///
+/// assert_eq!(Integer::try_from(-42i64).unwrap().get(), -42);
+/// assert_eq!(Integer::from(7u8).to_string(), "7");
+/// assert!(Integer::try_from(1_000_000_000_000_000i64).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Integer(i64);
+
+impl Integer {
+    /// The value.
+    pub fn get(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<Integer> for i64 {
+    fn from(i: Integer) -> i64 {
+        i.0
+    }
+}
+
+/// Fails with `HeaderErrorKind::Number` if of more than 15 digits.
+impl TryFrom<i64> for Integer {
+    type Error = ::Error;
+
+    fn try_from(i: i64) -> ::Result<Integer> {
+        if (-MAX_INTEGER..=MAX_INTEGER).contains(&i) {
+            Ok(Integer(i))
+        } else {
+            Err(number())
+        }
+    }
+}
+
+macro_rules! integer_from {
+    ($($t:ty),*) => {$(
+        impl From<$t> for Integer {
+            fn from(i: $t) -> Integer {
+                Integer(i64::from(i))
+            }
+        }
+
+        impl From<$t> for BareItem {
+            fn from(i: $t) -> BareItem {
+                BareItem::Integer(Integer::from(i))
+            }
+        }
+    )*};
+}
+
+integer_from!(i8, u8, i16, u16, i32, u32);
+
+impl Display for Integer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// A string of printable ASCII characters, the value of
+/// `BareItem::String`.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use hyperx::header::sfv::{Item, SfString};
+///
+/// let s = SfString::try_from("a \"b\"").unwrap();
+/// assert_eq!(s.as_str(), "a \"b\"");
+/// assert_eq!(Item::new(s).to_string(), "\"a \\\"b\\\"\"");
+/// assert!(SfString::try_from("é").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SfString(String);
+
+impl SfString {
+    /// The string.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Fails with `HeaderErrorKind::Syntax` if of a character other than
+/// printable ASCII.
+impl TryFrom<String> for SfString {
+    type Error = ::Error;
+
+    fn try_from(s: String) -> ::Result<SfString> {
+        if s.bytes().all(|b| (b' '..=b'~').contains(&b)) {
+            Ok(SfString(s))
+        } else {
+            Err(syntax())
+        }
+    }
+}
+
+/// Fails with `HeaderErrorKind::Syntax` if of a character other than
+/// printable ASCII.
+impl<'a> TryFrom<&'a str> for SfString {
+    type Error = ::Error;
+
+    fn try_from(s: &'a str) -> ::Result<SfString> {
+        SfString::try_from(s.to_owned())
+    }
+}
+
+/// A token, such as `text/html` or `*`, the value of `BareItem::Token`.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use hyperx::header::sfv::Token;
+///
+/// assert_eq!(Token::try_from("text/html").unwrap().as_str(), "text/html");
+/// assert!(Token::try_from("1a").is_err());
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Token(String);
+
+impl Token {
+    /// The token.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Fails with `HeaderErrorKind::Token` if not starting with a letter or
+/// `*`, or of a character other than a `tchar`, `:` or `/`.
+impl TryFrom<String> for Token {
+    type Error = ::Error;
+
+    fn try_from(t: String) -> ::Result<Token> {
+        let valid = t.starts_with(|c: char| c.is_ascii_alphabetic() || c == '*')
+            && t.bytes().all(is_token_char);
+        if valid {
+            Ok(Token(t))
+        } else {
+            Err(::Error::header(::HeaderErrorKind::Token))
+        }
+    }
+}
+
+/// Fails with `HeaderErrorKind::Token` if not starting with a letter or
+/// `*`, or of a character other than a `tchar`, `:` or `/`.
+impl<'a> TryFrom<&'a str> for Token {
+    type Error = ::Error;
+
+    fn try_from(t: &'a str) -> ::Result<Token> {
+        Token::try_from(t.to_owned())
+    }
+}
+
+/// A decimal number, of at most 12 integer and 3 fractional digits, held
+/// exactly as thousandths.
+///
+/// ```
+/// use std::convert::TryFrom;
+/// use hyperx::header::sfv::Decimal;
+///
+/// let d = Decimal::try_from(-1.5).unwrap();
+/// assert_eq!(d.thousandths(), -1500);
+/// assert_eq!(d.to_string(), "-1.5");
+/// assert_eq!(f64::from(d), -1.5);
+/// assert!(Decimal::try_from(1e12).is_err());
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Decimal(i64);
+
+impl Decimal {
+    /// Create from a number of thousandths, or `None` if out of range.
+    pub fn from_thousandths(thousandths: i64) -> Option<Decimal> {
+        if (-MAX_INTEGER..=MAX_INTEGER).contains(&thousandths) {
+            Some(Decimal(thousandths))
+        } else {
+            None
+        }
+    }
+
+    /// The number of thousandths.
+    pub fn thousandths(&self) -> i64 {
+        self.0
+    }
+}
+
+impl From<Decimal> for f64 {
+    fn from(d: Decimal) -> f64 {
+        d.0 as f64 / 1000.0
+    }
+}
+
+/// Round to the nearest thousandth, with ties to even.
+///
+/// Fails with `HeaderErrorKind::Number` if not finite, or out of range.
+impl TryFrom<f64> for Decimal {
+    type Error = ::Error;
+
+    fn try_from(f: f64) -> ::Result<Decimal> {
+        let x = f * 1000.0;
+        let mut r = x.round();
+        if (r - x).abs() == 0.5 && r % 2.0 != 0.0 {
+            r -= x.signum();
+        }
+        if !r.is_finite() || r.abs() > MAX_INTEGER as f64 {
+            return Err(::Error::header(::HeaderErrorKind::Number));
+        }
+        Ok(Decimal(r as i64))
+    }
+}
+
+impl Display for Decimal {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        if self.0 < 0 {
+            f.write_char('-')?;
+        }
+        let abs = self.0.unsigned_abs();
+        write!(f, "{}.", abs / 1000)?;
+        let frac = abs % 1000;
+        if frac == 0 {
+            f.write_char('0')
+        } else {
+            let digits = format!("{:03}", frac);
+            f.write_str(digits.trim_end_matches('0'))
+        }
+    }
+}
+
+/// The parameters of an `Item` or `InnerList`, as ordered key/value pairs
+/// with unique keys.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Parameters(Vec<(String, BareItem)>);
+
+impl Parameters {
+    /// Create empty parameters.
+    pub fn new() -> Parameters {
+        Parameters::default()
+    }
+
+    /// The value of the parameter with the given key.
+    pub fn get(&self, key: &str) -> Option<&BareItem> {
+        self.0.iter().find(|p| p.0 == key).map(|p| &p.1)
+    }
+
+    /// Set a parameter, replacing the value of any with the same key in
+    /// its place.
+    ///
+    /// Fails with `HeaderErrorKind::Token` if the key is not a valid key:
+    /// a lowercase letter or `*`, followed by lowercase letters, digits,
+    /// `_`, `-`, `.` or `*`.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> ::Result<()>
+    where
+        K: Into<String>,
+        V: Into<BareItem>,
+    {
+        insert_member(&mut self.0, key.into(), value.into())
+    }
+
+    /// Return an iterator over the keys and values, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &BareItem)> {
+        self.0.iter().map(|p| (p.0.as_str(), &p.1))
+    }
+
+    /// The number of parameters.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return true if there are no parameters.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (key, value) in self.iter() {
+            write!(f, ";{}", key)?;
+            if *value != BareItem::Boolean(true) {
+                write!(f, "={}", value)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// An item: a bare item, with parameters.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Item {
+    /// The value.
+    pub bare_item: BareItem,
+    /// The parameters.
+    pub params: Parameters,
+}
+
+impl Item {
+    /// Create an item without parameters.
+    pub fn new<V: Into<BareItem>>(value: V) -> Item {
+        Item {
+            bare_item: value.into(),
+            params: Parameters::new(),
+        }
+    }
+
+    /// Set a parameter, failing as `Parameters::insert`.
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> ::Result<Item>
+    where
+        K: Into<String>,
+        V: Into<BareItem>,
+    {
+        self.params.insert(key, value)?;
+        Ok(self)
+    }
+
+    /// Parse all values of a field as an item.
+    pub fn from_raw<'a, R: RawLike<'a>>(raw: &'a R) -> ::Result<Item> {
+        join_lines(raw)?.parse()
+    }
+}
+
+impl Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}{}", self.bare_item, self.params)
+    }
+}
+
+impl FromStr for Item {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Item> {
+        Parser::parse(s, Parser::item)
+    }
+}
+
+/// An inner list: a parenthesized list of items, with parameters.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct InnerList {
+    /// The items.
+    pub items: Vec<Item>,
+    /// The parameters.
+    pub params: Parameters,
+}
+
+impl InnerList {
+    /// Create an inner list without parameters.
+    pub fn new(items: Vec<Item>) -> InnerList {
+        InnerList {
+            items,
+            params: Parameters::new(),
+        }
+    }
+
+    /// Set a parameter, failing as `Parameters::insert`.
+    pub fn with_param<K, V>(mut self, key: K, value: V) -> ::Result<InnerList>
+    where
+        K: Into<String>,
+        V: Into<BareItem>,
+    {
+        self.params.insert(key, value)?;
+        Ok(self)
+    }
+}
+
+impl Display for InnerList {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('(')?;
+        for (i, item) in self.items.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            Display::fmt(item, f)?;
+        }
+        write!(f, "){}", self.params)
+    }
+}
+
+/// A member of a `List`, or value of a `Dictionary`.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ListEntry {
+    /// An item.
+    Item(Item),
+    /// An inner list.
+    InnerList(InnerList),
+}
+
+impl ListEntry {
+    /// The item, if not an inner list.
+    pub fn as_item(&self) -> Option<&Item> {
+        match *self {
+            ListEntry::Item(ref item) => Some(item),
+            ListEntry::InnerList(_) => None,
+        }
+    }
+
+    /// The inner list, if not an item.
+    pub fn as_inner_list(&self) -> Option<&InnerList> {
+        match *self {
+            ListEntry::Item(_) => None,
+            ListEntry::InnerList(ref list) => Some(list),
+        }
+    }
+}
+
+impl From<Item> for ListEntry {
+    fn from(item: Item) -> ListEntry {
+        ListEntry::Item(item)
+    }
+}
+
+impl From<InnerList> for ListEntry {
+    fn from(list: InnerList) -> ListEntry {
+        ListEntry::InnerList(list)
+    }
+}
+
+impl Display for ListEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ListEntry::Item(ref item) => Display::fmt(item, f),
+            ListEntry::InnerList(ref list) => Display::fmt(list, f),
+        }
+    }
+}
+
+/// A list of items and inner lists.
+///
+/// ```
+/// use hyperx::header::sfv::{BareItem, List};
+///
+/// let list: List = "sugar, tea, (rum brandy);q=0.5".parse().unwrap();
+/// assert_eq!(list.len(), 3);
+/// assert_eq!(list[0].as_item().unwrap().bare_item.as_token(), Some("sugar"));
+/// let inner = list[2].as_inner_list().unwrap();
+/// assert_eq!(inner.items.len(), 2);
+/// assert_eq!(inner.params.get("q").and_then(BareItem::as_decimal).unwrap().thousandths(), 500);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct List(pub Vec<ListEntry>);
+
+impl Deref for List {
+    type Target = Vec<ListEntry>;
+
+    fn deref(&self) -> &Vec<ListEntry> {
+        &self.0
+    }
+}
+
+impl DerefMut for List {
+    fn deref_mut(&mut self) -> &mut Vec<ListEntry> {
+        &mut self.0
+    }
+}
+
+impl List {
+    /// Parse all values of a field as a single list.
+    pub fn from_raw<'a, R: RawLike<'a>>(raw: &'a R) -> ::Result<List> {
+        join_lines(raw)?.parse()
+    }
+}
+
+impl Display for List {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, entry) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            Display::fmt(entry, f)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for List {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<List> {
+        Parser::parse(s, Parser::list).map(List)
+    }
+}
+
+/// A dictionary, as ordered keys with unique keys and values of items or
+/// inner lists.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Dictionary(Vec<(String, ListEntry)>);
+
+impl Dictionary {
+    /// Create an empty dictionary.
+    pub fn new() -> Dictionary {
+        Dictionary::default()
+    }
+
+    /// Parse all values of a field as a single dictionary.
+    pub fn from_raw<'a, R: RawLike<'a>>(raw: &'a R) -> ::Result<Dictionary> {
+        join_lines(raw)?.parse()
+    }
+
+    /// The value of the member with the given key.
+    pub fn get(&self, key: &str) -> Option<&ListEntry> {
+        self.0.iter().find(|m| m.0 == key).map(|m| &m.1)
+    }
+
+    /// Set a member, replacing the value of any with the same key in its
+    /// place.
+    ///
+    /// Fails with `HeaderErrorKind::Token` if the key is not a valid key,
+    /// as of `Parameters::insert`.
+    pub fn insert<K, V>(&mut self, key: K, value: V) -> ::Result<()>
+    where
+        K: Into<String>,
+        V: Into<ListEntry>,
+    {
+        insert_member(&mut self.0, key.into(), value.into())
+    }
+
+    /// Remove and return the member with the given key.
+    pub fn remove(&mut self, key: &str) -> Option<ListEntry> {
+        let i = self.0.iter().position(|m| m.0 == key)?;
+        Some(self.0.remove(i).1)
+    }
+
+    /// Return an iterator over the keys and values, in order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &ListEntry)> {
+        self.0.iter().map(|m| (m.0.as_str(), &m.1))
+    }
+
+    /// The number of members.
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Return true if there are no members.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl Display for Dictionary {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, (key, value)) in self.iter().enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+            f.write_str(key)?;
+            match *value {
+                ListEntry::Item(ref item) if item.bare_item == BareItem::Boolean(true) => {
+                    Display::fmt(&item.params, f)?;
+                }
+                _ => write!(f, "={}", value)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Dictionary {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<Dictionary> {
+        Parser::parse(s, Parser::dictionary).map(Dictionary)
+    }
+}
+
+// Set the value of a member by key, failing if not a valid key.
+pub(crate) fn insert_member<V>(
+    members: &mut Vec<(String, V)>,
+    key: String,
+    value: V,
+) -> ::Result<()> {
+    if !is_key(&key) {
+        return Err(::Error::header(::HeaderErrorKind::Token));
+    }
+    insert(members, key, value);
+    Ok(())
+}
+
+// Set the value of a member by key, in its place if present.
+fn insert<V>(members: &mut Vec<(String, V)>, key: String, value: V) {
+    match members.iter_mut().find(|m| m.0 == key) {
+        Some(m) => m.1 = value,
+        None => members.push((key, value)),
+    }
+}
+
+// Combine the values of a field, as per section 4.2.
+fn join_lines<'a, R: RawLike<'a>>(raw: &'a R) -> ::Result<String> {
+    let mut joined = String::new();
+    for line in raw.iter() {
+        let line = str::from_utf8(line).map_err(|_| ::Error::header(::HeaderErrorKind::Utf8))?;
+        let line = line.trim_matches(|c| c == ' ' || c == '\t');
+        if line.is_empty() {
+            continue;
+        }
+        if !joined.is_empty() {
+            joined.push_str(", ");
+        }
+        joined.push_str(line);
+    }
+    Ok(joined)
+}
+
+// Structured field key: lcalpha or "*", followed by lcalpha, DIGIT, "_",
+// "-", "." or "*".
+pub(crate) fn is_key(key: &str) -> bool {
+    key.starts_with(|c: char| c.is_ascii_lowercase() || c == '*') && key.bytes().all(is_key_char)
+}
+
+fn is_key_char(b: u8) -> bool {
+    b.is_ascii_lowercase() || b.is_ascii_digit() || b"_-.*".contains(&b)
+}
+
+fn is_token_char(b: u8) -> bool {
+    b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~:/".contains(&b)
+}
+
+fn syntax() -> ::Error {
+    ::Error::header(::HeaderErrorKind::Syntax)
+}
+
+fn number() -> ::Error {
+    ::Error::header(::HeaderErrorKind::Number)
+}
+
+// The parsing algorithms of section 4.2.
+struct Parser<'a> {
+    s: &'a str,
+    pos: usize,
+}
+
+impl<'a> Parser<'a> {
+    fn parse<T, F>(s: &'a str, f: F) -> ::Result<T>
+    where
+        F: FnOnce(&mut Parser<'a>) -> ::Result<T>,
+    {
+        let mut p = Parser { s, pos: 0 };
+        p.skip(b" ");
+        let value = f(&mut p)?;
+        p.skip(b" ");
+        if p.peek().is_some() {
+            return Err(syntax());
+        }
+        Ok(value)
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.s.as_bytes().get(self.pos).cloned()
+    }
+
+    fn next(&mut self) -> Option<u8> {
+        let b = self.peek()?;
+        self.pos += 1;
+        Some(b)
+    }
+
+    fn eat(&mut self, b: u8) -> bool {
+        if self.peek() == Some(b) {
+            self.pos += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expect(&mut self, b: u8) -> ::Result<()> {
+        if self.eat(b) {
+            Ok(())
+        } else {
+            Err(syntax())
+        }
+    }
+
+    fn skip(&mut self, chars: &[u8]) {
+        while self.peek().is_some_and(|b| chars.contains(&b)) {
+            self.pos += 1;
+        }
+    }
+
+    fn take_while<F: Fn(u8) -> bool>(&mut self, f: F) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&f) {
+            self.pos += 1;
+        }
+        &self.s[start..self.pos]
+    }
+
+    // Members separated by commas, for both lists and dictionaries.
+    fn members<T, F>(&mut self, mut member: F) -> ::Result<Vec<T>>
+    where
+        F: FnMut(&mut Parser<'a>) -> ::Result<T>,
+    {
+        let mut members = Vec::new();
+        while self.peek().is_some() {
+            members.push(member(self)?);
+            self.skip(b" \t");
+            if self.peek().is_none() {
+                break;
+            }
+            self.expect(b',')?;
+            self.skip(b" \t");
+            if self.peek().is_none() {
+                return Err(syntax());
+            }
+        }
+        Ok(members)
+    }
+
+    fn list(&mut self) -> ::Result<Vec<ListEntry>> {
+        self.members(Parser::list_entry)
+    }
+
+    fn dictionary(&mut self) -> ::Result<Vec<(String, ListEntry)>> {
+        let members = self.members(|p| {
+            let key = p.key()?;
+            let value = if p.eat(b'=') {
+                p.list_entry()?
+            } else {
+                ListEntry::Item(Item {
+                    bare_item: BareItem::Boolean(true),
+                    params: p.params()?,
+                })
+            };
+            Ok((key, value))
+        })?;
+        let mut dict = Vec::with_capacity(members.len());
+        for (key, value) in members {
+            insert(&mut dict, key, value);
+        }
+        Ok(dict)
+    }
+
+    fn list_entry(&mut self) -> ::Result<ListEntry> {
+        if self.peek() == Some(b'(') {
+            self.inner_list().map(ListEntry::InnerList)
+        } else {
+            self.item().map(ListEntry::Item)
+        }
+    }
+
+    fn inner_list(&mut self) -> ::Result<InnerList> {
+        self.expect(b'(')?;
+        let mut items = Vec::new();
+        loop {
+            self.skip(b" ");
+            if self.eat(b')') {
+                let params = self.params()?;
+                return Ok(InnerList { items, params });
+            }
+            items.push(self.item()?);
+            match self.peek() {
+                Some(b' ') | Some(b')') => {}
+                _ => return Err(syntax()),
+            }
+        }
+    }
+
+    fn item(&mut self) -> ::Result<Item> {
+        let bare_item = self.bare_item()?;
+        let params = self.params()?;
+        Ok(Item { bare_item, params })
+    }
+
+    fn params(&mut self) -> ::Result<Parameters> {
+        let mut params = Vec::new();
+        while self.eat(b';') {
+            self.skip(b" ");
+            let key = self.key()?;
+            let value = if self.eat(b'=') {
+                self.bare_item()?
+            } else {
+                BareItem::Boolean(true)
+            };
+            insert(&mut params, key, value);
+        }
+        Ok(Parameters(params))
+    }
+
+    fn key(&mut self) -> ::Result<String> {
+        match self.peek() {
+            Some(b) if b.is_ascii_lowercase() || b == b'*' => {}
+            _ => return Err(syntax()),
+        }
+        Ok(self.take_while(is_key_char).to_owned())
+    }
+
+    fn bare_item(&mut self) -> ::Result<BareItem> {
+        match self.peek() {
+            Some(b'-') | Some(b'0'..=b'9') => self.number(),
+            Some(b'"') => self.string().map(|s| BareItem::String(SfString(s))),
+            Some(b':') => self.byte_sequence().map(BareItem::ByteSequence),
+            Some(b'?') => self.boolean().map(BareItem::Boolean),
+            Some(b'@') => {
+                self.pos += 1;
+                match self.number()? {
+                    BareItem::Integer(i) => Ok(BareItem::Date(i)),
+                    _ => Err(number()),
+                }
+            }
+            Some(b'%') => self.display_string().map(BareItem::DisplayString),
+            Some(b) if b.is_ascii_alphabetic() || b == b'*' => {
+                let token = self.take_while(is_token_char);
+                Ok(BareItem::Token(Token(token.to_owned())))
+            }
+            _ => Err(syntax()),
+        }
+    }
+
+    fn number(&mut self) -> ::Result<BareItem> {
+        let negative = self.eat(b'-');
+        let integer = self.take_while(|b| b.is_ascii_digit());
+        if integer.is_empty() {
+            return Err(number());
+        }
+        if !self.eat(b'.') {
+            if integer.len() > 15 {
+                return Err(number());
+            }
+            let i: i64 = integer.parse().map_err(|_| number())?;
+            return Ok(BareItem::Integer(Integer(if negative { -i } else { i })));
+        }
+        let fraction = self.take_while(|b| b.is_ascii_digit());
+        if integer.len() > 12 || fraction.is_empty() || fraction.len() > 3 {
+            return Err(number());
+        }
+        let i: i64 = integer.parse().map_err(|_| number())?;
+        let mut f: i64 = fraction.parse().map_err(|_| number())?;
+        for _ in fraction.len()..3 {
+            f *= 10;
+        }
+        let thousandths = i * 1000 + f;
+        Ok(BareItem::Decimal(Decimal(if negative {
+            -thousandths
+        } else {
+            thousandths
+        })))
+    }
+
+    fn string(&mut self) -> ::Result<String> {
+        self.expect(b'"')?;
+        let mut s = String::new();
+        loop {
+            match self.next() {
+                Some(b'\\') => match self.next() {
+                    Some(c @ b'"') | Some(c @ b'\\') => s.push(c as char),
+                    _ => return Err(syntax()),
+                },
+                Some(b'"') => return Ok(s),
+                Some(c @ b' '..=b'~') => s.push(c as char),
+                _ => return Err(syntax()),
+            }
+        }
+    }
+
+    fn byte_sequence(&mut self) -> ::Result<Vec<u8>> {
+        self.expect(b':')?;
+        let encoded = self.take_while(|b| b.is_ascii_alphanumeric() || b"+/=".contains(&b));
+        self.expect(b':')?;
+        // Parsers should not fail on missing padding
+        Base64::STANDARD.lenient().decode(encoded)
+    }
+
+    fn boolean(&mut self) -> ::Result<bool> {
+        self.expect(b'?')?;
+        match self.next() {
+            Some(b'1') => Ok(true),
+            Some(b'0') => Ok(false),
+            _ => Err(syntax()),
+        }
+    }
+
+    fn display_string(&mut self) -> ::Result<String> {
+        self.expect(b'%')?;
+        self.expect(b'"')?;
+        let mut bytes = Vec::new();
+        loop {
+            match self.next() {
+                Some(b'%') => {
+                    let hex = self.s.get(self.pos..self.pos + 2).ok_or_else(syntax)?;
+                    if !hex
+                        .bytes()
+                        .all(|b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+                    {
+                        return Err(syntax());
+                    }
+                    bytes.push(u8::from_str_radix(hex, 16).map_err(|_| syntax())?);
+                    self.pos += 2;
+                }
+                Some(b'"') => {
+                    return String::from_utf8(bytes)
+                        .map_err(|_| ::Error::header(::HeaderErrorKind::Utf8));
+                }
+                Some(c @ b' '..=b'~') => bytes.push(c),
+                _ => return Err(syntax()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::Raw;
+
+    fn round_trip<T>(s: &str, expected: &str)
+    where
+        T: FromStr<Err = ::Error> + Display,
+    {
+        let value: T = s.parse().unwrap_or_else(|e| panic!("{}: {}", s, e));
+        assert_eq!(value.to_string(), expected, "{}", s);
+    }
+
+    #[test]
+    fn test_bare_items() {
+        for &(s, ref expected) in &[
+            ("42", BareItem::Integer(Integer(42))),
+            ("-0", BareItem::Integer(Integer(0))),
+            ("999999999999999", BareItem::Integer(Integer(MAX_INTEGER))),
+            ("4.5", BareItem::Decimal(Decimal(4500))),
+            ("-0.05", BareItem::Decimal(Decimal(-50))),
+            (
+                "\"a \\\"b\\\\\"",
+                BareItem::String(SfString("a \"b\\".to_owned())),
+            ),
+            (
+                "*foo/bar:1",
+                BareItem::Token(Token("*foo/bar:1".to_owned())),
+            ),
+            (
+                ":cHJldGVuZA==:",
+                BareItem::ByteSequence(b"pretend".to_vec()),
+            ),
+            (":cHJldGVuZA:", BareItem::ByteSequence(b"pretend".to_vec())),
+            ("?0", BareItem::Boolean(false)),
+            ("@1659578233", BareItem::Date(Integer(1_659_578_233))),
+            (
+                "%\"f%c3%bc%c3%bc%22\"",
+                BareItem::DisplayString("füü\"".to_owned()),
+            ),
+        ] {
+            let item: Item = s.parse().unwrap_or_else(|e| panic!("{}: {}", s, e));
+            assert_eq!(item.bare_item, *expected, "{}", s);
+            assert!(item.params.is_empty());
+        }
+        round_trip::<Item>(":cHJldGVuZA:", ":cHJldGVuZA==:");
+        round_trip::<Item>("-0", "0");
+        round_trip::<Item>("1.50", "1.5");
+        round_trip::<Item>("2.0", "2.0");
+        round_trip::<Item>("%\"f%c3%bc%c3%bc%22\"", "%\"f%c3%bc%c3%bc%22\"");
+    }
+
+    #[test]
+    fn test_invalid_items() {
+        for s in &[
+            "",
+            "1000000000000000",
+            "1234567890123.0",
+            "1.2345",
+            "1.",
+            "-",
+            "\"unterminated",
+            "\"bad \\escape\"",
+            "\"é\"",
+            ":not base64!:",
+            "?2",
+            "@1.5",
+            "%\"%C3%BC\"",
+            "%\"%c3\"",
+            "a b",
+            "1;A=2",
+        ] {
+            let err = s.parse::<Item>().unwrap_err();
+            assert!(err.header_error().is_some(), "{}", s);
+        }
+        // Tokens may start with an uppercase letter; keys not
+        assert!("Token;a".parse::<Item>().is_ok());
+        assert_eq!(
+            "1.2345"
+                .parse::<Item>()
+                .unwrap_err()
+                .header_error()
+                .unwrap()
+                .kind(),
+            ::HeaderErrorKind::Number
+        );
+    }
+
+    #[test]
+    fn test_params() {
+        let item: Item = "abc;a=1;b=2; cde_456;a=3".parse().unwrap();
+        assert_eq!(item.bare_item, BareItem::Token(Token("abc".to_owned())));
+        let params: Vec<_> = item.params.iter().collect();
+        assert_eq!(
+            params,
+            [
+                ("a", &BareItem::Integer(Integer(3))),
+                ("b", &BareItem::Integer(Integer(2))),
+                ("cde_456", &BareItem::Boolean(true)),
+            ]
+        );
+        assert_eq!(item.to_string(), "abc;a=3;b=2;cde_456");
+    }
+
+    #[test]
+    fn test_list() {
+        round_trip::<List>("sugar,  tea,rum", "sugar, tea, rum");
+        round_trip::<List>(
+            "(\"foo\" \"bar\"), (\"baz\"), (\"bat\" \"one\"), ()",
+            "(\"foo\" \"bar\"), (\"baz\"), (\"bat\" \"one\"), ()",
+        );
+        round_trip::<List>(
+            "(  \"foo\"   \"bar\" );lvl=5, (\"bar\" \"baz\");lvl=1",
+            "(\"foo\" \"bar\");lvl=5, (\"bar\" \"baz\");lvl=1",
+        );
+        assert!("".parse::<List>().unwrap().is_empty());
+        for s in &["a,", ",a", "a,,b", "(a b", "(a)b", "(a,b)", "a b"] {
+            assert!(s.parse::<List>().is_err(), "{}", s);
+        }
+
+        let raw = Raw::from(vec![b"a, b".to_vec(), b"".to_vec(), b"c".to_vec()]);
+        assert_eq!(List::from_raw(&raw).unwrap().to_string(), "a, b, c");
+
+        let list = List(vec![
+            Item::new(Token("a".to_owned())).into(),
+            InnerList::new(vec![Item::new(1), Item::new(SfString("x".to_owned()))])
+                .with_param("q", Decimal::from_thousandths(500).unwrap())
+                .unwrap()
+                .into(),
+        ]);
+        assert_eq!(list.to_string(), "a, (1 \"x\");q=0.5");
+    }
+
+    #[test]
+    fn test_dictionary() {
+        let dict: Dictionary = "en=\"Applepie\", da=:w4ZibGV0w6ZydGU=:, a=1, a=2;x, b"
+            .parse()
+            .unwrap();
+        let keys: Vec<_> = dict.iter().map(|(k, _)| k).collect();
+        assert_eq!(keys, ["en", "da", "a", "b"]);
+        assert_eq!(
+            dict.get("da").and_then(|m| m.as_item()).unwrap().bare_item,
+            BareItem::ByteSequence("Æbletærte".as_bytes().to_vec())
+        );
+        assert_eq!(
+            dict.to_string(),
+            "en=\"Applepie\", da=:w4ZibGV0w6ZydGU=:, a=2;x, b"
+        );
+        round_trip::<Dictionary>("a=?0, b, c;foo=bar", "a=?0, b, c;foo=bar");
+        round_trip::<Dictionary>(
+            "rating=1.5, feelings=(joy sadness)",
+            "rating=1.5, feelings=(joy sadness)",
+        );
+        assert!("A=1".parse::<Dictionary>().is_err());
+        assert!("a=1,".parse::<Dictionary>().is_err());
+
+        let raw = Raw::from(vec![b"a=1".to_vec(), b"b=2, a=3".to_vec()]);
+        let mut dict = Dictionary::from_raw(&raw).unwrap();
+        assert_eq!(dict.to_string(), "a=3, b=2");
+        assert!(dict.remove("a").is_some());
+        assert_eq!(dict.len(), 1);
+    }
+
+    #[test]
+    fn test_invalid_values() {
+        assert!(Integer::try_from(MAX_INTEGER + 1).is_err());
+        assert!(Integer::try_from(-MAX_INTEGER - 1).is_err());
+        assert!(SfString::try_from("é").is_err());
+        assert!(SfString::try_from("a\tb").is_err());
+        assert!(Token::try_from("1a").is_err());
+        assert!(Token::try_from("").is_err());
+        assert!(Item::new(1).with_param("Key", 1).is_err());
+        assert!(Dictionary::new().insert("a b", Item::new(1)).is_err());
+        assert_eq!(
+            Token::try_from("a,b")
+                .unwrap_err()
+                .header_error()
+                .unwrap()
+                .kind(),
+            ::HeaderErrorKind::Token
+        );
+        assert_eq!(
+            Item::new(BareItem::DisplayString("é %".to_owned())).to_string(),
+            "%\"%c3%a9 %25\""
+        );
+    }
+
+    #[test]
+    fn test_decimal() {
+        assert_eq!(Decimal::try_from(0.0025).unwrap(), Decimal(2));
+        assert_eq!(Decimal::try_from(0.0035).unwrap(), Decimal(4));
+        assert_eq!(Decimal::try_from(-1.2345).unwrap(), Decimal(-1234));
+        assert!(Decimal::try_from(f64::NAN).is_err());
+        assert!(Decimal::from_thousandths(MAX_INTEGER + 1).is_none());
+        assert_eq!(Decimal(-50).to_string(), "-0.05");
+        assert_eq!(Decimal(123_000).to_string(), "123.0");
+    }
+}
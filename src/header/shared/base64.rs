@@ -0,0 +1,140 @@
+use base64::alphabet::{Alphabet, STANDARD, URL_SAFE};
+use base64::engine::{DecodePaddingMode, GeneralPurpose, GeneralPurposeConfig};
+use base64::Engine;
+
+/// A base64 alphabet and padding, for the values of headers which encode
+/// bytes, such as `Basic` credentials or a `Sec-WebSocket-Key`.
+///
+/// The headers use the standard alphabet with padding by default, as their
+/// specifications require, but accept another where a scheme requires it.
+///
+/// # Example
+///
+/// ```
+/// use hyperx::header::Base64;
+///
+/// assert_eq!(Base64::STANDARD.encode(b"\xfb\xff"), "+/8=");
+/// assert_eq!(Base64::URL_SAFE_NO_PAD.encode(b"\xfb\xff"), "-_8");
+///
+/// assert!(Base64::STANDARD.decode("-_8").is_err());
+/// assert!(Base64::URL_SAFE.decode("-_8").is_err());
+/// assert_eq!(Base64::URL_SAFE.lenient().decode("-_8").unwrap(), b"\xfb\xff");
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Base64 {
+    url_safe: bool,
+    pad: bool,
+    lenient: bool,
+}
+
+impl Base64 {
+    /// The standard alphabet, with padding, of
+    /// [RFC4648](https://tools.ietf.org/html/rfc4648#section-4).
+    pub const STANDARD: Base64 = Base64::new(false, true);
+
+    /// The standard alphabet, without padding.
+    pub const STANDARD_NO_PAD: Base64 = Base64::new(false, false);
+
+    /// The URL and filename safe alphabet, with padding, of
+    /// [RFC4648](https://tools.ietf.org/html/rfc4648#section-5).
+    pub const URL_SAFE: Base64 = Base64::new(true, true);
+
+    /// The URL and filename safe alphabet, without padding.
+    pub const URL_SAFE_NO_PAD: Base64 = Base64::new(true, false);
+
+    const fn new(url_safe: bool, pad: bool) -> Base64 {
+        Base64 {
+            url_safe,
+            pad,
+            lenient: false,
+        }
+    }
+
+    /// Accept input with or without padding when decoding, rather than
+    /// only as encoded.
+    pub const fn lenient(self) -> Base64 {
+        Base64 {
+            lenient: true,
+            ..self
+        }
+    }
+
+    /// Return true if the URL and filename safe alphabet is used.
+    pub fn is_url_safe(&self) -> bool {
+        self.url_safe
+    }
+
+    /// Return true if padding is written when encoding.
+    pub fn is_padded(&self) -> bool {
+        self.pad
+    }
+
+    /// Encode bytes.
+    pub fn encode<T: AsRef<[u8]>>(&self, input: T) -> String {
+        self.engine().encode(input)
+    }
+
+    /// Decode bytes, failing with `HeaderErrorKind::Syntax` if the input is
+    /// not valid for the alphabet and padding.
+    pub fn decode<T: AsRef<[u8]>>(&self, input: T) -> ::Result<Vec<u8>> {
+        self.engine()
+            .decode(input)
+            .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Syntax, e))
+    }
+
+    fn engine(&self) -> GeneralPurpose {
+        let alphabet: &Alphabet = if self.url_safe { &URL_SAFE } else { &STANDARD };
+        let padding = match (self.lenient, self.pad) {
+            (true, _) => DecodePaddingMode::Indifferent,
+            (false, true) => DecodePaddingMode::RequireCanonical,
+            (false, false) => DecodePaddingMode::RequireNone,
+        };
+        let config = GeneralPurposeConfig::new()
+            .with_encode_padding(self.pad)
+            .with_decode_padding_mode(padding);
+        GeneralPurpose::new(alphabet, config)
+    }
+}
+
+impl Default for Base64 {
+    fn default() -> Base64 {
+        Base64::STANDARD
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Base64;
+
+    #[test]
+    fn test_round_trip() {
+        let bytes: Vec<u8> = (0..=255).collect();
+        for &b64 in &[
+            Base64::STANDARD,
+            Base64::STANDARD_NO_PAD,
+            Base64::URL_SAFE,
+            Base64::URL_SAFE_NO_PAD,
+        ] {
+            let encoded = b64.encode(&bytes[..]);
+            assert_eq!(encoded.ends_with("=="), b64.is_padded());
+            assert_eq!(encoded.contains('-'), b64.is_url_safe());
+            assert_eq!(b64.decode(&encoded).unwrap(), bytes);
+            assert_eq!(
+                b64.lenient().decode(encoded.trim_end_matches('=')).unwrap(),
+                bytes
+            );
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        assert!(Base64::STANDARD.decode("YQ").is_err());
+        assert!(Base64::STANDARD_NO_PAD.decode("YQ==").is_err());
+        assert_eq!(Base64::STANDARD.lenient().decode("YQ").unwrap(), b"a");
+        assert_eq!(
+            Base64::STANDARD_NO_PAD.lenient().decode("YQ==").unwrap(),
+            b"a"
+        );
+        assert_eq!(Base64::default(), Base64::STANDARD);
+    }
+}
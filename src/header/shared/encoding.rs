@@ -7,7 +7,7 @@ pub use self::Encoding::{
 
 /// A value to represent an encoding used in `Transfer-Encoding`
 /// or `Accept-Encoding` header.
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub enum Encoding {
     /// The `chunked` encoding.
     Chunked,
@@ -24,7 +24,10 @@ pub enum Encoding {
     /// The `trailers` encoding.
     Trailers,
     /// Some other encoding that is less common, can be any String.
-    EncodingExt(String),
+    ///
+    /// Boxed, so that lists of the common encodings don't reserve the size
+    /// of a `String` per item.
+    EncodingExt(Box<String>),
 }
 
 impl fmt::Display for Encoding {
@@ -37,7 +40,7 @@ impl fmt::Display for Encoding {
             Compress => "compress",
             Identity => "identity",
             Trailers => "trailers",
-            EncodingExt(ref s) => s.as_ref(),
+            EncodingExt(ref s) => s.as_str(),
         })
     }
 }
@@ -53,7 +56,7 @@ impl str::FromStr for Encoding {
             "compress" => Ok(Compress),
             "identity" => Ok(Identity),
             "trailers" => Ok(Trailers),
-            _ => Ok(EncodingExt(s.to_owned())),
+            _ => Ok(EncodingExt(Box::new(s.to_owned()))),
         }
     }
 }
@@ -41,7 +41,7 @@ fn check_slice_validity(slice: &str) -> bool {
 /// | `W/"1"` | `W/"2"` | no match          | no match        |
 /// | `W/"1"` | `"1"`   | no match          | match           |
 /// | `"1"`   | `"1"`   | match             | match           |
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Eq, Hash, PartialEq)]
 pub struct EntityTag {
     /// Weakness indicator for the tag
     pub weak: bool,
@@ -126,7 +126,7 @@ impl FromStr for EntityTag {
         let slice = s;
         // Early exits if it doesn't terminate in a DQUOTE.
         if !slice.ends_with('"') || slice.len() < 2 {
-            return Err(::Error::Header);
+            return Err(::Error::header(::HeaderErrorKind::Syntax));
         }
         // The etag is weak if its first char is not a DQUOTE.
         if slice.len() >= 2 && slice.starts_with('"') && check_slice_validity(&slice[1..length - 1])
@@ -146,7 +146,7 @@ impl FromStr for EntityTag {
                 tag: slice[3..length - 1].to_owned(),
             });
         }
-        Err(::Error::Header)
+        Err(::Error::header(::HeaderErrorKind::Syntax))
     }
 }
 
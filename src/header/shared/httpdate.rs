@@ -1,9 +1,18 @@
-use httpdate::HttpDate as InnerDate;
 use std::fmt::{self, Display};
-use std::str::FromStr;
-use std::time::SystemTime;
+use std::str::{self, FromStr};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone, Utc};
+#[cfg(any(feature = "chrono", feature = "time"))]
+use std::convert::TryFrom;
+#[cfg(feature = "time")]
+use time::OffsetDateTime;
 
 /// A timestamp with HTTP formatting and parsing
+///
+/// Converts to and from `SystemTime`, and with the `chrono` or `time`
+/// features, to and from `chrono::DateTime` or `time::OffsetDateTime`.
 //   Prior to 1995, there were three different formats commonly used by
 //   servers to communicate timestamps.  For compatibility with old
 //   implementations, all three are defined here.  The preferred format is
@@ -26,36 +35,341 @@ use std::time::SystemTime;
 //   header field that contains one or more timestamps defined as
 //   HTTP-date, the sender MUST generate those timestamps in the
 //   IMF-fixdate format.
-#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
-pub struct HttpDate(InnerDate);
+//
+// Stored as whole seconds since the Unix epoch, from 1970 up to but not
+// including the year 10000, the range which the four digit year of the
+// IMF-fixdate can represent.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct HttpDate(u64);
+
+// The first second of the year 10000.
+const MAX_SECS: u64 = 253_402_300_800;
+
+const WEEKDAYS: [&[u8; 3]; 7] = [b"Mon", b"Tue", b"Wed", b"Thu", b"Fri", b"Sat", b"Sun"];
+const WEEKDAYS_LONG: [&[u8]; 7] = [
+    b"Monday",
+    b"Tuesday",
+    b"Wednesday",
+    b"Thursday",
+    b"Friday",
+    b"Saturday",
+    b"Sunday",
+];
+const MONTHS: [&[u8; 3]; 12] = [
+    b"Jan", b"Feb", b"Mar", b"Apr", b"May", b"Jun", b"Jul", b"Aug", b"Sep", b"Oct", b"Nov", b"Dec",
+];
+
+impl HttpDate {
+    // Parse any of the three formats, dispatching on the length of the
+    // (trimmed) value, which differs between them.
+    pub(crate) fn parse_bytes(s: &[u8]) -> Option<HttpDate> {
+        let s = trim(s);
+        let fields = match s.len() {
+            29 => parse_imf_fixdate(s)?,
+            24 => parse_asctime(s)?,
+            30..=33 => parse_rfc850(s)?,
+            _ => return None,
+        };
+        fields.to_date()
+    }
+}
 
 impl FromStr for HttpDate {
     type Err = ::Error;
     fn from_str(s: &str) -> ::Result<HttpDate> {
-        InnerDate::from_str(s)
-            .map(HttpDate)
-            .map_err(|_| ::Error::Header)
+        HttpDate::parse_bytes(s.as_bytes()).ok_or_else(|| ::Error::header(::HeaderErrorKind::Date))
     }
 }
 
 impl Display for HttpDate {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        fmt::Display::fmt(&self.0, f)
+        let days = self.0 / 86_400;
+        let secs = self.0 % 86_400;
+        let (year, month, day) = civil_from_days(days);
+        let (hour, min, sec) = (secs / 3600, secs / 60 % 60, secs % 60);
+
+        // Sun, 06 Nov 1994 08:49:37 GMT
+        let mut buf = *b"   , 00     0000 00:00:00 GMT";
+        buf[..3].copy_from_slice(WEEKDAYS[weekday(days)]);
+        put_digits(&mut buf[5..7], day);
+        buf[8..11].copy_from_slice(MONTHS[month as usize - 1]);
+        put_digits(&mut buf[12..16], year);
+        put_digits(&mut buf[17..19], hour);
+        put_digits(&mut buf[20..22], min);
+        put_digits(&mut buf[23..25], sec);
+        f.write_str(str::from_utf8(&buf).map_err(|_| fmt::Error)?)
     }
 }
 
 impl From<SystemTime> for HttpDate {
+    /// Convert a `SystemTime`, truncated to whole seconds.
+    ///
+    /// # Panics
+    ///
+    /// If the time is before the Unix epoch or after the year 9999.
     fn from(sys: SystemTime) -> HttpDate {
-        HttpDate(sys.into())
+        let secs = sys
+            .duration_since(UNIX_EPOCH)
+            .expect("all times should be after the epoch")
+            .as_secs();
+        assert!(secs < MAX_SECS, "date must be before year 10000");
+        HttpDate(secs)
     }
 }
 
 impl From<HttpDate> for SystemTime {
     fn from(date: HttpDate) -> SystemTime {
-        date.0.into()
+        UNIX_EPOCH + Duration::from_secs(date.0)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<HttpDate> for DateTime<Utc> {
+    fn from(date: HttpDate) -> DateTime<Utc> {
+        DateTime::from_timestamp(date.0 as i64, 0).expect("date in range")
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl<Tz: TimeZone> TryFrom<DateTime<Tz>> for HttpDate {
+    type Error = ::Error;
+
+    /// Convert a date and time of any time zone, truncated to whole seconds.
+    ///
+    /// Fails with `HeaderErrorKind::Date` if the time is before the Unix
+    /// epoch or after the year 9999.
+    fn try_from(date: DateTime<Tz>) -> ::Result<HttpDate> {
+        match u64::try_from(date.timestamp()) {
+            Ok(secs) if secs < MAX_SECS => Ok(HttpDate(secs)),
+            _ => Err(::Error::header(::HeaderErrorKind::Date)),
+        }
+    }
+}
+
+/// With the `time` feature, which also enables its `formatting` feature, a
+/// date may be formatted in the well-known formats of the _time_ crate.
+///
+/// ```
+/// # extern crate hyperx;
+/// # extern crate time;
+/// use hyperx::header::HttpDate;
+/// use time::format_description::well_known::{Iso8601, Rfc2822};
+/// use time::OffsetDateTime;
+///
+/// # fn main() {
+/// let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+/// let dt = OffsetDateTime::from(date);
+/// assert_eq!(dt.format(&Rfc2822).unwrap(), "Sun, 06 Nov 1994 08:49:37 +0000");
+/// assert_eq!(
+///     dt.format(&Iso8601::DEFAULT).unwrap(),
+///     "1994-11-06T08:49:37.000000000Z"
+/// );
+/// # }
+/// ```
+#[cfg(feature = "time")]
+impl From<HttpDate> for OffsetDateTime {
+    fn from(date: HttpDate) -> OffsetDateTime {
+        OffsetDateTime::from_unix_timestamp(date.0 as i64).expect("date in range")
+    }
+}
+
+#[cfg(feature = "time")]
+impl TryFrom<OffsetDateTime> for HttpDate {
+    type Error = ::Error;
+
+    /// Convert a date and time of any offset, truncated to whole seconds.
+    ///
+    /// Fails with `HeaderErrorKind::Date` if the time is before the Unix
+    /// epoch or after the year 9999.
+    fn try_from(date: OffsetDateTime) -> ::Result<HttpDate> {
+        match u64::try_from(date.unix_timestamp()) {
+            Ok(secs) if secs < MAX_SECS => Ok(HttpDate(secs)),
+            _ => Err(::Error::header(::HeaderErrorKind::Date)),
+        }
+    }
+}
+
+// The fields of a parsed date, yet to be validated.
+struct Fields {
+    year: u64,
+    month: u64,
+    day: u64,
+    hour: u64,
+    min: u64,
+    sec: u64,
+    // 0 (Monday) to 6 (Sunday)
+    weekday: usize,
+}
+
+impl Fields {
+    fn to_date(&self) -> Option<HttpDate> {
+        if self.year < 1970
+            || self.year > 9999
+            || self.month < 1
+            || self.month > 12
+            || self.day < 1
+            || self.day > days_in_month(self.year, self.month)
+            || self.hour > 23
+            || self.min > 59
+            || self.sec > 59
+        {
+            return None;
+        }
+        let days = days_from_civil(self.year, self.month, self.day);
+        if weekday(days) != self.weekday {
+            return None;
+        }
+        Some(HttpDate(
+            days * 86_400 + self.hour * 3600 + self.min * 60 + self.sec,
+        ))
+    }
+}
+
+// Sun, 06 Nov 1994 08:49:37 GMT
+fn parse_imf_fixdate(s: &[u8]) -> Option<Fields> {
+    if &s[3..5] != b", " || s[7] != b' ' || s[11] != b' ' || &s[25..] != b" GMT" {
+        return None;
+    }
+    let weekday = find(&WEEKDAYS, &s[..3])?;
+    let day = digits(&s[5..7])?;
+    let month = find(&MONTHS, &s[8..11])? as u64 + 1;
+    let year = digits(&s[12..16])?;
+    let (hour, min, sec) = parse_time(&s[16..25])?;
+    Some(Fields {
+        year,
+        month,
+        day,
+        hour,
+        min,
+        sec,
+        weekday,
+    })
+}
+
+// Sunday, 06-Nov-94 08:49:37 GMT
+fn parse_rfc850(s: &[u8]) -> Option<Fields> {
+    let (name, s) = s.split_at(s.len() - 24);
+    if &s[..2] != b", " || s[4] != b'-' || s[8] != b'-' || &s[20..] != b" GMT" {
+        return None;
+    }
+    let weekday = WEEKDAYS_LONG.iter().position(|&long| long == name)?;
+    let day = digits(&s[2..4])?;
+    let month = find(&MONTHS, &s[5..8])? as u64 + 1;
+    // Two digit years are taken to be within 1970 to 2069
+    let year = match digits(&s[9..11])? {
+        y if y < 70 => 2000 + y,
+        y => 1900 + y,
+    };
+    let (hour, min, sec) = parse_time(&s[11..20])?;
+    Some(Fields {
+        year,
+        month,
+        day,
+        hour,
+        min,
+        sec,
+        weekday,
+    })
+}
+
+// Sun Nov  6 08:49:37 1994
+fn parse_asctime(s: &[u8]) -> Option<Fields> {
+    if s[3] != b' ' || s[7] != b' ' || s[19] != b' ' {
+        return None;
+    }
+    let weekday = find(&WEEKDAYS, &s[..3])?;
+    let month = find(&MONTHS, &s[4..7])? as u64 + 1;
+    let day = match s[8] {
+        b' ' => digits(&s[9..10])?,
+        _ => digits(&s[8..10])?,
+    };
+    let (hour, min, sec) = parse_time(&s[10..19])?;
+    let year = digits(&s[20..24])?;
+    Some(Fields {
+        year,
+        month,
+        day,
+        hour,
+        min,
+        sec,
+        weekday,
+    })
+}
+
+// Parse ` HH:MM:SS`.
+fn parse_time(s: &[u8]) -> Option<(u64, u64, u64)> {
+    if s[0] != b' ' || s[3] != b':' || s[6] != b':' {
+        return None;
+    }
+    Some((digits(&s[1..3])?, digits(&s[4..6])?, digits(&s[7..9])?))
+}
+
+fn digits(s: &[u8]) -> Option<u64> {
+    s.iter().try_fold(0, |n, &b| match b {
+        b'0'..=b'9' => Some(n * 10 + u64::from(b - b'0')),
+        _ => None,
+    })
+}
+
+fn put_digits(buf: &mut [u8], mut n: u64) {
+    for b in buf.iter_mut().rev() {
+        *b = b'0' + (n % 10) as u8;
+        n /= 10;
     }
 }
 
+fn find(names: &[&[u8; 3]], name: &[u8]) -> Option<usize> {
+    names.iter().position(|&n| n == name)
+}
+
+fn trim(s: &[u8]) -> &[u8] {
+    let start = s.iter().position(|b| !b.is_ascii_whitespace());
+    let end = s.iter().rposition(|b| !b.is_ascii_whitespace());
+    match (start, end) {
+        (Some(start), Some(end)) => &s[start..=end],
+        _ => &[],
+    }
+}
+
+// 0 (Monday) to 6 (Sunday), where day 0, 1970-01-01, was a Thursday.
+fn weekday(days: u64) -> usize {
+    ((days + 3) % 7) as usize
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+// Days since the epoch of a date from 1970 on, and the inverse, counting
+// years from March so that the leap day falls at the end of each; see
+// http://howardhinnant.github.io/date_algorithms.html
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year / 400;
+    let yoe = year - era * 400;
+    let doy = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    era * 146_097 + doe - 719_468
+}
+
+fn civil_from_days(days: u64) -> (u64, u64, u64) {
+    let days = days + 719_468;
+    let era = days / 146_097;
+    let doe = days - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+    (year, month, day)
+}
+
 #[cfg(test)]
 mod tests {
     use std::time::{Duration, SystemTime};
@@ -66,8 +380,7 @@ mod tests {
         ($function: ident, $date: expr) => {
             #[test]
             fn $function() {
-                let nov_07 =
-                    HttpDate((SystemTime::UNIX_EPOCH + Duration::new(784198117, 0)).into());
+                let nov_07 = HttpDate::from(SystemTime::UNIX_EPOCH + Duration::new(784198117, 0));
 
                 assert_eq!($date.parse::<HttpDate>().unwrap(), nov_07);
             }
@@ -77,9 +390,115 @@ mod tests {
     test_parse!(test_imf_fixdate, "Mon, 07 Nov 1994 08:48:37 GMT");
     test_parse!(test_rfc_850, "Monday, 07-Nov-94 08:48:37 GMT");
     test_parse!(test_asctime, "Mon Nov  7 08:48:37 1994");
+    test_parse!(test_trimmed, " Mon, 07 Nov 1994 08:48:37 GMT\t");
 
     #[test]
     fn test_no_date() {
         assert!("this-is-no-date".parse::<HttpDate>().is_err());
     }
+
+    #[test]
+    fn test_invalid() {
+        for s in &[
+            // Wrong weekday
+            "Sun, 07 Nov 1994 08:48:37 GMT",
+            // Not a leap year
+            "Sun, 29 Feb 2100 00:00:00 GMT",
+            "Mon, 31 Apr 1995 00:00:00 GMT",
+            "Mon, 07 Nov 1994 24:00:00 GMT",
+            "Mon, 07 Nov 1994 08:60:37 GMT",
+            "Mon, 07 Nov 1994 08:48:60 GMT",
+            "Wed, 31 Dec 1969 23:59:59 GMT",
+            "Mon, 07 Nov 1994 08:48:37 UTC",
+            "Mon, 7 Nov 1994 08:48:37 GMT",
+            "Mon, 07 nov 1994 08:48:37 GMT",
+            "Mon, 07-Nov-94 08:48:37 GMT",
+            "Monday, 07 Nov 94 08:48:37 GMT",
+            "Mon Nov  7 08:48:37 94",
+            "Mon Nov 7 08:48:37 1994",
+            "Mon, 07 Nov 1994 08:48:37 GM\u{e9}",
+        ] {
+            assert!(s.parse::<HttpDate>().is_err(), "{}", s);
+        }
+    }
+
+    #[test]
+    fn test_fmt() {
+        let date: HttpDate = "Sun Nov  6 08:49:37 1994".parse().unwrap();
+        assert_eq!(date.to_string(), "Sun, 06 Nov 1994 08:49:37 GMT");
+        let leap: HttpDate = "Tuesday, 29-Feb-00 00:00:00 GMT".parse().unwrap();
+        assert_eq!(leap.to_string(), "Tue, 29 Feb 2000 00:00:00 GMT");
+
+        let epoch = HttpDate::from(SystemTime::UNIX_EPOCH);
+        assert_eq!(epoch.to_string(), "Thu, 01 Jan 1970 00:00:00 GMT");
+        let last = "Fri, 31 Dec 9999 23:59:59 GMT";
+        assert_eq!(last.parse::<HttpDate>().unwrap().to_string(), last);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        // Every day of a few leap cycles, at an increasing time of day
+        for day in 0..(4 * 365 * 3) {
+            let secs = day * 86_400 + day * 61 % 86_400;
+            let date = HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(secs));
+            assert_eq!(date.to_string().parse::<HttpDate>().unwrap(), date);
+            assert_eq!(
+                SystemTime::from(date),
+                SystemTime::UNIX_EPOCH + Duration::from_secs(secs)
+            );
+        }
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_chrono() {
+        use chrono::{DateTime, FixedOffset, Utc};
+        use std::convert::TryFrom;
+
+        let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+        let dt = DateTime::<Utc>::from(date);
+        assert_eq!(dt.to_rfc3339(), "1994-11-06T08:49:37+00:00");
+        assert_eq!(HttpDate::try_from(dt).unwrap(), date);
+
+        // Any time zone, truncated to whole seconds
+        let dt = DateTime::<FixedOffset>::parse_from_rfc3339("1994-11-06T09:49:37.9+01:00");
+        assert_eq!(HttpDate::try_from(dt.unwrap()).unwrap(), date);
+
+        let last =
+            DateTime::<Utc>::from("Fri, 31 Dec 9999 23:59:59 GMT".parse::<HttpDate>().unwrap());
+        assert!(HttpDate::try_from(last).is_ok());
+        assert!(HttpDate::try_from(last + chrono::Duration::seconds(1)).is_err());
+        let before = DateTime::<Utc>::from_timestamp(-1, 0).unwrap();
+        assert!(HttpDate::try_from(before).is_err());
+    }
+
+    #[cfg(feature = "time")]
+    #[test]
+    fn test_time() {
+        use std::convert::TryFrom;
+        use time::{Duration, OffsetDateTime, UtcOffset};
+
+        let date: HttpDate = "Sun, 06 Nov 1994 08:49:37 GMT".parse().unwrap();
+        let dt = OffsetDateTime::from(date);
+        assert_eq!(dt.unix_timestamp(), 784_111_777);
+        assert_eq!(dt.offset(), UtcOffset::UTC);
+        assert_eq!(HttpDate::try_from(dt).unwrap(), date);
+
+        // Any offset, truncated to whole seconds
+        let offset = UtcOffset::from_hms(1, 0, 0).unwrap();
+        let dt = (dt + Duration::milliseconds(900)).to_offset(offset);
+        assert_eq!(HttpDate::try_from(dt).unwrap(), date);
+
+        // The year 10000 is out of the default range of `OffsetDateTime`
+        let last =
+            OffsetDateTime::from("Fri, 31 Dec 9999 23:59:59 GMT".parse::<HttpDate>().unwrap());
+        assert!(HttpDate::try_from(last).is_ok());
+        assert!(HttpDate::try_from(OffsetDateTime::UNIX_EPOCH - Duration::seconds(1)).is_err());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_from_year_10000() {
+        let _ = HttpDate::from(SystemTime::UNIX_EPOCH + Duration::from_secs(253_402_300_800));
+    }
 }
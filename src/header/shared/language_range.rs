@@ -0,0 +1,82 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use language_tags::LanguageTag;
+
+/// A language range of the `Accept-Language` header, defined in
+/// [RFC4647](https://www.rfc-editor.org/rfc/rfc4647#section-2.1): a
+/// language tag, or `*` for any language.
+///
+/// ```
+/// use hyperx::header::{LanguageRange, LanguageTag};
+///
+/// let de = LanguageTag::parse("de-CH").unwrap();
+/// assert!("de".parse::<LanguageRange>().unwrap().matches(&de));
+/// assert!(!"de-AT".parse::<LanguageRange>().unwrap().matches(&de));
+/// assert!(LanguageRange::Any.matches(&de));
+/// assert_eq!("*".parse::<LanguageRange>().unwrap(), LanguageRange::Any);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum LanguageRange {
+    /// `*`, matching any language tag.
+    Any,
+    /// A language tag, matching itself and the tags of which it is a
+    /// prefix, such as `en` of `en-GB`.
+    Tag(LanguageTag),
+}
+
+impl LanguageRange {
+    /// Return true if this range matches the language tag, as per
+    /// `LanguageTag::matches` for a tag. Tags with extension or private use
+    /// subtags never match.
+    pub fn matches(&self, tag: &LanguageTag) -> bool {
+        match *self {
+            LanguageRange::Any => true,
+            LanguageRange::Tag(ref range) => range.is_language_range() && range.matches(tag),
+        }
+    }
+
+    /// The range as a string, `*` for `Any`.
+    pub fn as_str(&self) -> &str {
+        match *self {
+            LanguageRange::Any => "*",
+            LanguageRange::Tag(ref tag) => tag.as_str(),
+        }
+    }
+}
+
+impl From<LanguageTag> for LanguageRange {
+    fn from(tag: LanguageTag) -> LanguageRange {
+        LanguageRange::Tag(tag)
+    }
+}
+
+impl Display for LanguageRange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LanguageRange {
+    type Err = ::Error;
+
+    fn from_str(s: &str) -> ::Result<LanguageRange> {
+        if s == "*" {
+            return Ok(LanguageRange::Any);
+        }
+        LanguageTag::parse(s)
+            .map(LanguageRange::Tag)
+            .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Token, e))
+    }
+}
+
+#[test]
+fn test_parse() {
+    assert_eq!("*".parse::<LanguageRange>().unwrap(), LanguageRange::Any);
+    assert_eq!(
+        "en-gb".parse::<LanguageRange>().unwrap().to_string(),
+        "en-GB"
+    );
+    assert!("".parse::<LanguageRange>().is_err());
+    assert!("**".parse::<LanguageRange>().is_err());
+}
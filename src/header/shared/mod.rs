@@ -1,12 +1,19 @@
+pub use self::base64::Base64;
 pub use self::charset::Charset;
 pub use self::encoding::Encoding;
 pub use self::entity::EntityTag;
 pub use self::httpdate::HttpDate;
+pub use self::language_range::LanguageRange;
 pub use self::quality_item::{q, qitem, Quality, QualityItem};
+pub(crate) use self::uri_reference::parse_uri;
+pub use self::uri_reference::resolve_uri;
 pub use language_tags::LanguageTag;
 
+mod base64;
 mod charset;
 mod encoding;
 mod entity;
 mod httpdate;
+mod language_range;
 mod quality_item;
+mod uri_reference;
@@ -4,6 +4,7 @@ use std::fmt;
 use std::str;
 
 use self::internal::IntoQuality;
+use header::parsing;
 
 /// Represents a quality used in quality values.
 ///
@@ -19,9 +20,17 @@ use self::internal::IntoQuality;
 ///
 /// [RFC7231 Section 5.3.1](https://tools.ietf.org/html/rfc7231#section-5.3.1)
 /// gives more information on quality values in HTTP header fields.
-#[derive(Copy, Clone, Debug, Eq, Ord, PartialEq, PartialOrd)]
+#[derive(Copy, Clone, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
 pub struct Quality(u16);
 
+impl Quality {
+    /// The quality value in thousandths, from 0 (not acceptable) to 1000.
+    #[inline]
+    pub fn thousandths(self) -> u16 {
+        self.0
+    }
+}
+
 impl Default for Quality {
     fn default() -> Quality {
         Quality(1000)
@@ -30,7 +39,7 @@ impl Default for Quality {
 
 /// Represents an item with a quality value as defined in
 /// [RFC7231](https://tools.ietf.org/html/rfc7231#section-5.3.1).
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
 pub struct QualityItem<T> {
     /// The actual contents of the field.
     pub item: T,
@@ -59,7 +68,16 @@ impl<T: fmt::Display> fmt::Display for QualityItem<T> {
         match self.quality.0 {
             1000 => Ok(()),
             0 => f.write_str("; q=0"),
-            x => write!(f, "; q=0.{}", format!("{:03}", x).trim_end_matches('0')),
+            x => {
+                // Three digits, without trailing zeros
+                let mut digits = 3;
+                let mut x = x;
+                while x % 10 == 0 {
+                    x /= 10;
+                    digits -= 1;
+                }
+                write!(f, "; q=0.{:01$}", x, digits)
+            }
         }
     }
 }
@@ -68,40 +86,27 @@ impl<T: str::FromStr> str::FromStr for QualityItem<T> {
     type Err = ::Error;
     fn from_str(s: &str) -> ::Result<QualityItem<T>> {
         if !s.is_ascii() {
-            return Err(::Error::Header);
+            return Err(::Error::header(::HeaderErrorKind::Syntax));
         }
         // Set defaults used if parsing fails.
         let mut raw_item = s;
-        let mut quality = 1f32;
+        let mut quality = Quality::default();
 
-        let parts: Vec<&str> = s.rsplitn(2, ';').map(|x| x.trim()).collect();
-        if parts.len() == 2 {
-            if parts[0].len() < 2 {
-                return Err(::Error::Header);
+        if let Some((item, param)) = s.rsplit_once(';') {
+            let param = param.trim();
+            if param.len() < 2 {
+                return Err(::Error::header(::HeaderErrorKind::Syntax));
             }
-            let start = &parts[0][0..2];
+            let start = &param[0..2];
             if start == "q=" || start == "Q=" {
-                let q_part = &parts[0][2..parts[0].len()];
-                if q_part.len() > 5 {
-                    return Err(::Error::Header);
-                }
-                match q_part.parse::<f32>() {
-                    Ok(q_value) => {
-                        if (0f32..=1f32).contains(&q_value) {
-                            quality = q_value;
-                            raw_item = parts[1];
-                        } else {
-                            return Err(::Error::Header);
-                        }
-                    }
-                    Err(_) => return Err(::Error::Header),
-                }
+                quality = parsing::parse_quality(&param[2..])?;
+                raw_item = item.trim();
             }
         }
         match raw_item.parse::<T>() {
             // we already checked above that the quality is within range
-            Ok(item) => Ok(QualityItem::new(item, from_f32(quality))),
-            Err(_) => Err(::Error::Header),
+            Ok(item) => Ok(QualityItem::new(item, quality)),
+            Err(_) => Err(::Error::header(::HeaderErrorKind::Syntax)),
         }
     }
 }
@@ -172,6 +177,13 @@ mod tests {
     use super::super::encoding::*;
     use super::*;
 
+    #[test]
+    fn test_quality_item_size() {
+        let word = ::std::mem::size_of::<usize>();
+        assert!(::std::mem::size_of::<Encoding>() <= 2 * word);
+        assert!(::std::mem::size_of::<QualityItem<Encoding>>() <= 3 * word);
+    }
+
     #[test]
     fn test_quality_item_fmt_q_1() {
         let x = qitem(Chunked);
@@ -183,10 +195,17 @@ mod tests {
         assert_eq!(format!("{}", x), "chunked; q=0.001");
     }
     #[test]
+    fn test_quality_item_fmt_q_025() {
+        let x = QualityItem::new(Chunked, Quality(250));
+        assert_eq!(format!("{}", x), "chunked; q=0.25");
+        let x = QualityItem::new(Chunked, Quality(99));
+        assert_eq!(format!("{}", x), "chunked; q=0.099");
+    }
+    #[test]
     fn test_quality_item_fmt_q_05() {
         // Custom value
         let x = QualityItem {
-            item: EncodingExt("identity".to_owned()),
+            item: EncodingExt(Box::new("identity".to_owned())),
             quality: Quality(500),
         };
         assert_eq!(format!("{}", x), "identity; q=0.5");
@@ -196,7 +215,7 @@ mod tests {
     fn test_quality_item_fmt_q_0() {
         // Custom value
         let x = QualityItem {
-            item: EncodingExt("identity".to_owned()),
+            item: EncodingExt(Box::new("identity".to_owned())),
             quality: Quality(0),
         };
         assert_eq!(x.to_string(), "identity; q=0");
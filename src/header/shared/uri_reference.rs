@@ -0,0 +1,257 @@
+use std::convert::TryFrom;
+
+use http::Uri;
+
+/// Resolve a URI reference, as the value of a `Location`, `Referer` or
+/// `Content-Location` header, against a base URI, as per
+/// [RFC3986](https://tools.ietf.org/html/rfc3986#section-5.2).
+///
+/// The base URI must be absolute, for example the URI of the request that a
+/// redirect `Location` responds to. The reference may be absolute, or a
+/// relative reference such as `../page`, `?query` or `//host/path`. Dot
+/// segments are removed, and any fragment is dropped, as `http::Uri` has
+/// none.
+///
+/// Fails with `HeaderErrorKind::Syntax` if the base is not absolute, or
+/// the result is not a valid `http::Uri`.
+///
+/// # Example
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use hyperx::header::resolve_uri;
+///
+/// # fn main() {
+/// let base: http::Uri = "http://a/b/c/d;p?q".parse().unwrap();
+/// assert_eq!(resolve_uri(&base, "../g").unwrap(), "http://a/b/g");
+/// assert_eq!(resolve_uri(&base, "//g/h#s").unwrap(), "http://g/h");
+/// assert_eq!(resolve_uri(&base, "https://b/").unwrap(), "https://b/");
+/// # }
+/// ```
+pub fn resolve_uri(base: &Uri, reference: &str) -> ::Result<Uri> {
+    let (scheme, authority) = match (base.scheme_str(), base.authority()) {
+        (Some(scheme), Some(authority)) => (scheme, authority.as_str()),
+        _ => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+    };
+    let r = Reference::split(reference.trim());
+    let mut target = String::with_capacity(reference.len() + authority.len() + 16);
+    let (scheme, authority) = match (r.scheme, r.authority) {
+        (Some(s), Some(a)) => (s, a),
+        (Some(_), None) => return Err(::Error::header(::HeaderErrorKind::Syntax)),
+        (None, Some(a)) => (scheme, a),
+        (None, None) => (scheme, authority),
+    };
+    target.push_str(scheme);
+    target.push_str("://");
+    target.push_str(authority);
+
+    let mut query = r.query;
+    if r.authority.is_some() || r.path.starts_with('/') {
+        remove_dot_segments(&mut target, r.path);
+    } else if r.path.is_empty() {
+        target.push_str(base.path());
+        query = query.or_else(|| base.query());
+    } else {
+        // Merge with all but the last segment of the base path
+        let path = base.path();
+        let merged = match path.rfind('/') {
+            Some(i) => format!("{}{}", &path[..=i], r.path),
+            None => format!("/{}", r.path),
+        };
+        remove_dot_segments(&mut target, &merged);
+    }
+    if let Some(query) = query {
+        target.push('?');
+        target.push_str(query);
+    }
+    to_uri(target)
+}
+
+/// Parse a URI reference as an `http::Uri`, if absolute or an absolute
+/// path. Other relative references must be resolved via `resolve_uri`.
+pub(crate) fn parse_uri(reference: &str) -> ::Result<Uri> {
+    let reference = reference.trim();
+    let r = Reference::split(reference);
+    let absolute = match (r.scheme, r.authority) {
+        (Some(_), Some(_)) => true,
+        (None, None) => r.path.starts_with('/'),
+        _ => false,
+    };
+    if !absolute {
+        return Err(::Error::header(::HeaderErrorKind::Syntax));
+    }
+    let end = reference.find('#').unwrap_or(reference.len());
+    to_uri(reference[..end].to_owned())
+}
+
+fn to_uri(s: String) -> ::Result<Uri> {
+    Uri::try_from(s).map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Syntax, e))
+}
+
+// The components of a URI reference, as split by the regular expression of
+// RFC3986, appendix B. The fragment is dropped.
+#[derive(Debug, PartialEq)]
+struct Reference<'a> {
+    scheme: Option<&'a str>,
+    authority: Option<&'a str>,
+    path: &'a str,
+    query: Option<&'a str>,
+}
+
+impl<'a> Reference<'a> {
+    fn split(s: &'a str) -> Reference<'a> {
+        let s = s.split('#').next().unwrap_or("");
+        let (s, query) = match s.find('?') {
+            Some(i) => (&s[..i], Some(&s[i + 1..])),
+            None => (s, None),
+        };
+        let (scheme, s) = match s.find([':', '/']) {
+            Some(i) if i > 0 && s.as_bytes()[i] == b':' && is_scheme(&s[..i]) => {
+                (Some(&s[..i]), &s[i + 1..])
+            }
+            _ => (None, s),
+        };
+        let (authority, path) = if let Some(rest) = s.strip_prefix("//") {
+            let i = rest.find('/').unwrap_or(rest.len());
+            (Some(&rest[..i]), &rest[i..])
+        } else {
+            (None, s)
+        };
+        Reference {
+            scheme,
+            authority,
+            path,
+            query,
+        }
+    }
+}
+
+fn is_scheme(s: &str) -> bool {
+    s.bytes().next().is_some_and(|b| b.is_ascii_alphabetic())
+        && s.bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'+' || b == b'-' || b == b'.')
+}
+
+// Append the path to the output, without "." and ".." segments, as per
+// RFC3986, section 5.2.4.
+fn remove_dot_segments(out: &mut String, path: &str) {
+    let start = out.len();
+    let mut input = path;
+    while !input.is_empty() {
+        if let Some(rest) = input.strip_prefix("../") {
+            input = rest;
+        } else if let Some(rest) = input.strip_prefix("./") {
+            input = rest;
+        } else if input.starts_with("/./") {
+            input = &input[2..];
+        } else if input == "/." {
+            input = "/";
+        } else if input.starts_with("/../") || input == "/.." {
+            input = if input.len() == 3 { "/" } else { &input[3..] };
+            let last = out[start..].rfind('/').map_or(start, |i| start + i);
+            out.truncate(last);
+        } else if input == "." || input == ".." {
+            input = "";
+        } else {
+            let i = input[1..].find('/').map_or(input.len(), |i| i + 1);
+            out.push_str(&input[..i]);
+            input = &input[i..];
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        assert_eq!(
+            Reference::split("http://a/b?c#d"),
+            Reference {
+                scheme: Some("http"),
+                authority: Some("a"),
+                path: "/b",
+                query: Some("c"),
+            }
+        );
+        assert_eq!(
+            Reference::split("g:h/i"),
+            Reference {
+                scheme: Some("g"),
+                authority: None,
+                path: "h/i",
+                query: None,
+            }
+        );
+        let r = Reference::split("./g:h");
+        assert_eq!((r.scheme, r.path), (None, "./g:h"));
+        let r = Reference::split("//g");
+        assert_eq!((r.authority, r.path), (Some("g"), ""));
+    }
+
+    // The examples of RFC3986, section 5.4, except those with a scheme
+    // and no authority, which `http::Uri` can not represent.
+    #[test]
+    fn test_resolve() {
+        let base: Uri = "http://a/b/c/d;p?q".parse().unwrap();
+        for &(reference, expected) in &[
+            ("g", "http://a/b/c/g"),
+            ("./g", "http://a/b/c/g"),
+            ("g/", "http://a/b/c/g/"),
+            ("/g", "http://a/g"),
+            ("//g", "http://g/"),
+            ("?y", "http://a/b/c/d;p?y"),
+            ("g?y", "http://a/b/c/g?y"),
+            ("#s", "http://a/b/c/d;p?q"),
+            ("g#s", "http://a/b/c/g"),
+            (";x", "http://a/b/c/;x"),
+            ("", "http://a/b/c/d;p?q"),
+            (".", "http://a/b/c/"),
+            ("./", "http://a/b/c/"),
+            ("..", "http://a/b/"),
+            ("../", "http://a/b/"),
+            ("../g", "http://a/b/g"),
+            ("../..", "http://a/"),
+            ("../../g", "http://a/g"),
+            ("../../../g", "http://a/g"),
+            ("../../../../g", "http://a/g"),
+            ("/./g", "http://a/g"),
+            ("/../g", "http://a/g"),
+            ("g.", "http://a/b/c/g."),
+            (".g", "http://a/b/c/.g"),
+            ("g..", "http://a/b/c/g.."),
+            ("..g", "http://a/b/c/..g"),
+            ("./../g", "http://a/b/g"),
+            ("./g/.", "http://a/b/c/g/"),
+            ("g/./h", "http://a/b/c/g/h"),
+            ("g/../h", "http://a/b/c/h"),
+            ("g;x=1/./y", "http://a/b/c/g;x=1/y"),
+            ("g;x=1/../y", "http://a/b/c/y"),
+            ("g?y/./x", "http://a/b/c/g?y/./x"),
+            ("http://b/c/../d", "http://b/d"),
+        ] {
+            assert_eq!(
+                resolve_uri(&base, reference).unwrap(),
+                expected,
+                "{}",
+                reference
+            );
+        }
+        assert!(resolve_uri(&base, "g:h").is_err());
+        assert!(resolve_uri(&base, "a b").is_err());
+
+        let relative: Uri = "/b/c".parse().unwrap();
+        assert!(resolve_uri(&relative, "g").is_err());
+    }
+
+    #[test]
+    fn test_parse_uri() {
+        assert_eq!(parse_uri("http://a/b?c#d").unwrap(), "http://a/b?c");
+        assert_eq!(parse_uri("/People.html#tim").unwrap(), "/People.html");
+        assert!(parse_uri("People.html").is_err());
+        assert!(parse_uri("//a/b").is_err());
+        assert!(parse_uri("mailto:a@b").is_err());
+    }
+}
@@ -0,0 +1,271 @@
+//! Conformance test utilities for `Header` implementations.
+//!
+//! These are used by the hyper*x* tests of its own headers, and may equally
+//! be used in the tests of custom headers, directly or via the
+//! `test_round_trip!` macro:
+//!
+//! ```
+//! # #[macro_use] extern crate hyperx;
+//! header! { (XRequestGuid, "X-Request-Guid") => [String] }
+//!
+//! #[cfg(test)]
+//! mod tests {
+//!     use super::XRequestGuid;
+//!
+//!     test_round_trip!(test_guid, XRequestGuid, [b"a proper guid"]);
+//!     test_round_trip!(
+//!         test_guid_value,
+//!         XRequestGuid,
+//!         [b"guid"],
+//!         XRequestGuid("guid".to_owned())
+//!     );
+//! }
+//!
+//! # fn main() {
+//! use hyperx::header::testing::{assert_invalid, round_trip};
+//!
+//! let guid = round_trip::<XRequestGuid, _>(&["a proper guid"]);
+//! assert_eq!(*guid, "a proper guid");
+//! assert_invalid::<XRequestGuid, _>(&["one", "two"]);
+//! # }
+//! ```
+//!
+//! With the _proptest_ feature, [`check_round_trip`](fn.check_round_trip.html)
+//! and [`check_parse`](fn.check_parse.html) check the same invariants as
+//! `proptest` properties, over generated typed values or raw values
+//! respectively. The typed headers of this crate implement
+//! `proptest::arbitrary::Arbitrary`, and the `proptest_round_trip!` macro
+//! generates a property test of a type, for any `Arbitrary` header or a
+//! given strategy:
+//!
+//! ```ignore
+//! proptest_round_trip!(prop_content_length, ContentLength);
+//! proptest_round_trip!(prop_guid, XRequestGuid, "[a-z]{1,8}".prop_map(XRequestGuid));
+//! ```
+
+use std::fmt;
+
+#[cfg(feature = "proptest")]
+use proptest::test_runner::TestCaseError;
+
+use super::{Header, Raw};
+
+/// Parse the raw values (lines) as header `H`, format it back to raw
+/// values, re-parse those and assert that the two parsed values are equal.
+///
+/// Returns the parsed value.
+///
+/// # Panics
+///
+/// If any step fails, with a message including the offending value.
+pub fn round_trip<H, R>(raw: &[R]) -> H
+where
+    H: Header + PartialEq + fmt::Debug,
+    R: AsRef<[u8]>,
+{
+    let raw = to_raw(raw);
+    let parsed = match H::parse_header(&raw) {
+        Ok(h) => h,
+        Err(e) => panic!("failed to parse {:?}: {}", raw, e),
+    };
+    let formatted = format(&parsed);
+    match H::parse_header(&formatted) {
+        Ok(reparsed) => assert_eq!(
+            parsed, reparsed,
+            "re-parse of formatted {:?} differs",
+            formatted
+        ),
+        Err(e) => panic!("failed to re-parse formatted {:?}: {}", formatted, e),
+    }
+    parsed
+}
+
+/// Assert that the raw values (lines) fail to parse as header `H`.
+pub fn assert_invalid<H, R>(raw: &[R])
+where
+    H: Header + fmt::Debug,
+    R: AsRef<[u8]>,
+{
+    let raw = to_raw(raw);
+    if let Ok(h) = H::parse_header(&raw) {
+        panic!("unexpected parse of {:?} as {:?}", raw, h);
+    }
+}
+
+/// Format the header to raw values (lines), as it would be written to a
+/// HTTP/1 message.
+pub fn format<H: Header>(header: &H) -> Raw {
+    Raw::from_header(header)
+}
+
+/// Format the header, parse the formatted raw values, and check that the
+/// parsed value equals the original, and formats the same.
+#[cfg(feature = "proptest")]
+pub fn check_round_trip<H>(header: &H) -> Result<(), TestCaseError>
+where
+    H: Header + PartialEq + fmt::Debug,
+{
+    let formatted = format(header);
+    let parsed = H::parse_header(&formatted).map_err(|e| {
+        TestCaseError::fail(format!("failed to parse formatted {:?}: {}", formatted, e))
+    })?;
+    prop_assert_eq!(
+        header,
+        &parsed,
+        "parse of formatted {:?} differs",
+        formatted
+    );
+    prop_assert_eq!(format(&parsed), formatted);
+    Ok(())
+}
+
+/// Parse the raw values (lines), and if they are valid for header `H`,
+/// check the round trip of the parsed value, as `check_round_trip`.
+///
+/// Suited to arbitrary raw values, which are mostly invalid.
+#[cfg(feature = "proptest")]
+pub fn check_parse<H, R>(raw: &[R]) -> Result<(), TestCaseError>
+where
+    H: Header + PartialEq + fmt::Debug,
+    R: AsRef<[u8]>,
+{
+    match H::parse_header(&to_raw(raw)) {
+        Ok(parsed) => check_round_trip(&parsed),
+        Err(_) => Ok(()),
+    }
+}
+
+fn to_raw<R: AsRef<[u8]>>(raw: &[R]) -> Raw {
+    raw.iter()
+        .map(|line| line.as_ref().to_vec())
+        .collect::<Vec<Vec<u8>>>()
+        .into()
+}
+
+/// Generate a test function asserting the round trip (parse, format,
+/// re-parse, equality) of a `Header` type over raw values (lines), and
+/// optionally the expected parsed value. See `header::testing::round_trip`.
+#[macro_export]
+macro_rules! test_round_trip {
+    ($id:ident, $ty:ty, $raw:expr) => {
+        #[test]
+        fn $id() {
+            let raw: &[&[u8]] = &$raw;
+            $crate::header::testing::round_trip::<$ty, _>(raw);
+        }
+    };
+    ($id:ident, $ty:ty, $raw:expr, $typed:expr) => {
+        #[test]
+        fn $id() {
+            let raw: &[&[u8]] = &$raw;
+            let parsed = $crate::header::testing::round_trip::<$ty, _>(raw);
+            assert_eq!(parsed, $typed);
+        }
+    };
+}
+
+/// Generate a `proptest` property test of the round trip (format, parse,
+/// equality) of a `Header` type, over values of its `Arbitrary`
+/// implementation or the given strategy. See
+/// `header::testing::check_round_trip`.
+#[cfg(feature = "proptest")]
+#[macro_export]
+macro_rules! proptest_round_trip {
+    ($id:ident, $ty:ty) => {
+        proptest_round_trip!(
+            $id,
+            $ty,
+            $crate::header::__private::proptest::arbitrary::any::<$ty>()
+        );
+    };
+    ($id:ident, $ty:ty, $strategy:expr) => {
+        $crate::header::__private::proptest::proptest! {
+            #[test]
+            fn $id(header in $strategy) {
+                let header: $ty = header;
+                $crate::header::testing::check_round_trip(&header)?;
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{assert_invalid, format, round_trip};
+    use header::{ContentLength, Encoding, SetCookie, TransferEncoding};
+
+    test_round_trip!(test_length, ContentLength, [b"42"], ContentLength(42));
+    test_round_trip!(
+        test_encodings,
+        TransferEncoding,
+        [b"gzip, chunked", b"br"],
+        TransferEncoding(smallvec![
+            Encoding::Gzip,
+            Encoding::Chunked,
+            Encoding::Brotli
+        ])
+    );
+
+    #[test]
+    fn test_multi_line() {
+        let cookies = round_trip::<SetCookie, _>(&["a=1; Path=/", "b=2"]);
+        assert_eq!(format(&cookies), ["a=1; Path=/", "b=2"][..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "failed to parse")]
+    fn test_round_trip_invalid() {
+        round_trip::<ContentLength, _>(&["nope"]);
+    }
+
+    #[test]
+    fn test_invalid() {
+        assert_invalid::<ContentLength, _>(&["nope"]);
+        assert_invalid::<ContentLength, _>(&["1", "2"]);
+    }
+
+    #[cfg(feature = "proptest")]
+    mod props {
+        use header::testing::check_parse;
+        use header::*;
+        use proptest::prelude::*;
+
+        proptest_round_trip!(prop_accept_encoding, AcceptEncoding);
+        proptest_round_trip!(prop_access_control_max_age, AccessControlMaxAge);
+        proptest_round_trip!(prop_age, Age);
+        proptest_round_trip!(prop_allow, Allow);
+        proptest_round_trip!(prop_connection, Connection);
+        proptest_round_trip!(prop_content_encoding, ContentEncoding);
+        proptest_round_trip!(prop_content_length, ContentLength);
+        proptest_round_trip!(prop_date, Date);
+        proptest_round_trip!(prop_etag, ETag);
+        proptest_round_trip!(prop_expires, Expires);
+        proptest_round_trip!(prop_host, Host);
+        proptest_round_trip!(prop_if_match, IfMatch);
+        proptest_round_trip!(prop_if_modified_since, IfModifiedSince);
+        proptest_round_trip!(prop_if_none_match, IfNoneMatch);
+        proptest_round_trip!(prop_if_unmodified_since, IfUnmodifiedSince);
+        proptest_round_trip!(prop_last_modified, LastModified);
+        proptest_round_trip!(prop_server, Server);
+        proptest_round_trip!(prop_te, Te);
+        proptest_round_trip!(prop_transfer_encoding, TransferEncoding);
+        proptest_round_trip!(prop_user_agent, UserAgent);
+        proptest_round_trip!(prop_vary, Vary);
+        proptest_round_trip!(
+            prop_strategy,
+            ContentLength,
+            (0u64..100).prop_map(ContentLength)
+        );
+
+        proptest! {
+            #[test]
+            fn prop_parse_raw(raw in "[ -~]{0,40}") {
+                check_parse::<CacheControl, _>(&[&raw])?;
+                check_parse::<ContentType, _>(&[&raw])?;
+                check_parse::<IfNoneMatch, _>(&[&raw])?;
+                check_parse::<AcceptEncoding, _>(&[&raw])?;
+                check_parse::<Connection, _>(&[&raw])?;
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+use bytes::BytesMut;
+use http::header::{HeaderValue, InvalidHeaderValue};
+use std::fmt;
+
+/// A `fmt::Write` adapter building a `http::HeaderValue` in a single growing
+/// buffer.
+///
+/// Each byte written is checked as it is appended, so the finished value is
+/// not scanned again, and no intermediate `String` is formatted. This is
+/// used when encoding typed headers, including via
+/// `Header::encode_to_value` and `TypedHeaders::encode`.
+///
+/// ```
+/// # extern crate http;
+/// # extern crate hyperx;
+/// use std::fmt::Write;
+/// use hyperx::header::ValueWriter;
+///
+/// # fn main() {
+/// let mut writer = ValueWriter::new();
+/// write!(writer, "max-age={}", 3600).unwrap();
+/// assert_eq!(writer.into_value().unwrap(), "max-age=3600");
+///
+/// let mut writer = ValueWriter::new();
+/// writer.write_str("new\nline").unwrap();
+/// assert!(writer.into_value().is_err());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ValueWriter {
+    buf: BytesMut,
+    valid: bool,
+}
+
+impl ValueWriter {
+    /// Create an empty writer.
+    pub fn new() -> ValueWriter {
+        ValueWriter::with_capacity(0)
+    }
+
+    /// Create an empty writer, with space for `capacity` bytes.
+    pub fn with_capacity(capacity: usize) -> ValueWriter {
+        ValueWriter {
+            buf: BytesMut::with_capacity(capacity),
+            valid: true,
+        }
+    }
+
+    /// Return the bytes written so far.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf
+    }
+
+    /// Convert the bytes written into a `HeaderValue`, without copying.
+    ///
+    /// Fails if any byte written is not allowed in a header value, such as
+    /// a control character other than horizontal tab.
+    pub fn into_value(self) -> Result<HeaderValue, InvalidHeaderValue> {
+        if self.valid {
+            unsafe {
+                // safe because every byte was checked when written, as
+                // `HeaderValue::from_maybe_shared` would check them.
+                Ok(HeaderValue::from_maybe_shared_unchecked(self.buf.freeze()))
+            }
+        } else {
+            // Checked again, only for the error
+            HeaderValue::from_maybe_shared(self.buf.freeze())
+        }
+    }
+}
+
+impl Default for ValueWriter {
+    fn default() -> ValueWriter {
+        ValueWriter::new()
+    }
+}
+
+impl fmt::Write for ValueWriter {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        // Invalid bytes are kept, to fail on conversion rather than abort
+        // the formatting of the header.
+        self.valid = self.valid && s.bytes().all(is_valid);
+        self.buf.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+/// Format a value, as for `Header::encode_to_value`.
+///
+/// Fails with `HeaderErrorKind::Conversion` if the formatting fails, or if
+/// any byte written is not allowed in a header value.
+pub(super) fn format_value(args: fmt::Arguments) -> ::Result<HeaderValue> {
+    let mut writer = ValueWriter::new();
+    fmt::write(&mut writer, args).map_err(|_| ::Error::header(::HeaderErrorKind::Conversion))?;
+    writer
+        .into_value()
+        .map_err(|e| ::Error::header_with_source(::HeaderErrorKind::Conversion, e))
+}
+
+/// Append a formatted value to the end of `buf`, as for
+/// `Header::write_value`.
+///
+/// Fails as `format_value`, with `buf` truncated to its prior length.
+pub(super) fn append_value(buf: &mut BytesMut, args: fmt::Arguments) -> ::Result<()> {
+    let start = buf.len();
+    let mut appender = Appender { buf, valid: true };
+    if fmt::write(&mut appender, args).is_err() {
+        buf.truncate(start);
+        return Err(::Error::header(::HeaderErrorKind::Conversion));
+    }
+    if appender.valid {
+        return Ok(());
+    }
+    // Checked again, only for the error
+    let e = HeaderValue::from_bytes(&buf[start..]).unwrap_err();
+    buf.truncate(start);
+    Err(::Error::header_with_source(
+        ::HeaderErrorKind::Conversion,
+        e,
+    ))
+}
+
+/// An `InvalidHeaderValue`, for a value which failed to format, as the
+/// _http_ crate offers no other way to construct one.
+pub(super) fn format_failed() -> InvalidHeaderValue {
+    HeaderValue::from_bytes(b"\n").unwrap_err()
+}
+
+struct Appender<'a> {
+    buf: &'a mut BytesMut,
+    valid: bool,
+}
+
+impl<'a> fmt::Write for Appender<'a> {
+    #[inline]
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.valid = self.valid && s.bytes().all(is_valid);
+        self.buf.extend_from_slice(s.as_bytes());
+        Ok(())
+    }
+}
+
+// As checked by `HeaderValue::from_bytes`.
+#[inline]
+fn is_valid(b: u8) -> bool {
+    (b >= 0x20 && b != 0x7f) || b == b'\t'
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{append_value, ValueWriter};
+    use bytes::BytesMut;
+    use header::{Formatter, Header, RawLike};
+    use std::fmt::{self, Write};
+    use HeaderErrorKind;
+
+    // A header with a `Display` implementation which fails.
+    #[derive(Clone, Debug)]
+    struct Unformattable;
+
+    impl Header for Unformattable {
+        fn header_name() -> &'static str {
+            "X-Unformattable"
+        }
+
+        fn parse_header<'a, T>(_raw: &'a T) -> ::Result<Unformattable>
+        where
+            T: RawLike<'a>,
+        {
+            Ok(Unformattable)
+        }
+
+        fn fmt_header(&self, f: &mut Formatter) -> fmt::Result {
+            f.fmt_line(&"partial")?;
+            Err(fmt::Error)
+        }
+    }
+
+    #[test]
+    fn test_format_failed() {
+        let e = Unformattable.encode_to_value().unwrap_err();
+        assert_eq!(
+            e.header_error().unwrap().kind(),
+            HeaderErrorKind::Conversion
+        );
+
+        let mut buf = BytesMut::from("a, ");
+        let e = Unformattable.write_value(&mut buf).unwrap_err();
+        assert_eq!(
+            e.header_error().unwrap().kind(),
+            HeaderErrorKind::Conversion
+        );
+        assert_eq!(&buf[..], b"a, ");
+
+        assert!(::header::__private::to_value(&Unformattable).is_err());
+    }
+
+    #[test]
+    fn test_value_writer() {
+        let mut writer = ValueWriter::with_capacity(4);
+        for i in 0..1000 {
+            write!(writer, "{}, ", i).unwrap();
+        }
+        writer.write_str("caf\u{e9}\t").unwrap();
+        assert!(writer.as_bytes().starts_with(b"0, 1, 2"));
+        let value = writer.into_value().unwrap();
+        assert!(value.as_bytes().ends_with("999, caf\u{e9}\t".as_bytes()));
+
+        for invalid in &["\r", "\0", "\x7f"] {
+            let mut writer = ValueWriter::new();
+            writer.write_str("a").unwrap();
+            writer.write_str(invalid).unwrap();
+            writer.write_str("b").unwrap();
+            assert!(writer.into_value().is_err(), "{:?}", invalid);
+        }
+        assert_eq!(ValueWriter::new().into_value().unwrap(), "");
+    }
+
+    #[test]
+    fn test_append_value() {
+        let mut buf = BytesMut::from("a, ");
+        append_value(&mut buf, format_args!("{}", 1)).unwrap();
+        assert_eq!(&buf[..], b"a, 1");
+        assert!(append_value(&mut buf, format_args!("b{}c", '\n')).is_err());
+        assert_eq!(&buf[..], b"a, 1");
+    }
+}
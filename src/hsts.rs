@@ -0,0 +1,354 @@
+//! A client HTTP Strict Transport Security (HSTS) policy store, per
+//! [RFC 6797].
+//!
+//! An [`HstsStore`](struct.HstsStore.html) records the typed
+//! `StrictTransportSecurity` headers received over secure connections, as
+//! policies of known HSTS hosts, and answers whether a host must be
+//! accessed over HTTPS: either it is a known HSTS host itself, or a
+//! superdomain of it is one whose policy includes subdomains.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, SystemTime};
+//! use http::Uri;
+//! use hyperx::header::StrictTransportSecurity;
+//! use hyperx::hsts::HstsStore;
+//!
+//! let now = SystemTime::now();
+//! let mut store = HstsStore::new();
+//! let url: Uri = "https://example.com/".parse().unwrap();
+//! store.store(&StrictTransportSecurity::including_subdomains(31536000), &url, now);
+//!
+//! assert!(store.is_https_only("www.example.com", now));
+//! assert!(!store.is_https_only("example.org", now));
+//!
+//! let upgraded = store.upgrade(&"http://www.example.com/a?b".parse().unwrap(), now);
+//! assert_eq!(upgraded.unwrap(), "https://www.example.com/a?b");
+//!
+//! let later = now + Duration::from_secs(31536000);
+//! assert!(!store.is_https_only("example.com", later));
+//! ```
+//!
+//! [RFC 6797]: https://www.rfc-editor.org/rfc/rfc6797
+
+use std::collections::hash_map::{self, HashMap};
+use std::net::IpAddr;
+use std::time::{Duration, SystemTime};
+
+use http::header::STRICT_TRANSPORT_SECURITY;
+use http::uri::{Authority, Scheme, Uri};
+use http::HeaderMap;
+
+use header::{Header, StrictTransportSecurity};
+
+/// The HSTS policy of a known HSTS host.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HstsPolicy {
+    host: String,
+    include_subdomains: bool,
+    expires: SystemTime,
+}
+
+impl HstsPolicy {
+    /// The host, in lowercase.
+    pub fn host(&self) -> &str {
+        &self.host
+    }
+
+    /// Whether the policy also applies to subdomains of the host.
+    pub fn include_subdomains(&self) -> bool {
+        self.include_subdomains
+    }
+
+    /// The time at which the policy expires.
+    pub fn expires(&self) -> SystemTime {
+        self.expires
+    }
+
+    fn is_expired(&self, now: SystemTime) -> bool {
+        self.expires <= now
+    }
+}
+
+/// A store of HSTS policies received from servers.
+#[derive(Clone, Debug, Default)]
+pub struct HstsStore {
+    policies: HashMap<String, HstsPolicy>,
+}
+
+impl HstsStore {
+    /// Create a new, empty store.
+    pub fn new() -> HstsStore {
+        HstsStore::default()
+    }
+
+    /// Store the policy of a `StrictTransportSecurity` header, received at
+    /// `now` in response to a request for `url`.
+    ///
+    /// The header is ignored unless received over HTTPS, and for hosts
+    /// which are IP addresses. A `max-age` of zero removes any policy of
+    /// the host; otherwise the policy replaces any existing one.
+    pub fn store(&mut self, sts: &StrictTransportSecurity, url: &Uri, now: SystemTime) {
+        if url.scheme() != Some(&Scheme::HTTPS) {
+            return;
+        }
+        let host = match url.host().and_then(normalize) {
+            Some(host) => host,
+            None => return,
+        };
+        let expires = now.checked_add(Duration::from_secs(sts.max_age));
+        match expires {
+            Some(expires) if sts.max_age > 0 => {
+                self.policies.insert(
+                    host.clone(),
+                    HstsPolicy {
+                        host,
+                        include_subdomains: sts.include_subdomains,
+                        expires,
+                    },
+                );
+            }
+            _ => {
+                self.policies.remove(&host);
+            }
+        }
+    }
+
+    /// Store the policy of any `Strict-Transport-Security` header of a
+    /// response.
+    ///
+    /// Only the first header is processed, and an invalid header is
+    /// ignored.
+    pub fn store_response(&mut self, headers: &HeaderMap, url: &Uri, now: SystemTime) {
+        let value = match headers.get(STRICT_TRANSPORT_SECURITY) {
+            Some(value) => value,
+            None => return,
+        };
+        if let Ok(sts) = StrictTransportSecurity::parse_header(&value) {
+            self.store(&sts, url, now);
+        }
+    }
+
+    /// Returns the policy applying to a host at `now`, if any: that of the
+    /// host itself, or else that of the nearest superdomain which includes
+    /// subdomains.
+    pub fn policy(&self, host: &str, now: SystemTime) -> Option<&HstsPolicy> {
+        let host = normalize(host)?;
+        let mut domain = &host[..];
+        let mut congruent = true;
+        loop {
+            if let Some(p) = self.policies.get(domain) {
+                if !p.is_expired(now) && (congruent || p.include_subdomains) {
+                    return Some(p);
+                }
+            }
+            domain = &domain[domain.find('.')? + 1..];
+            congruent = false;
+        }
+    }
+
+    /// Returns true if the host must be accessed over HTTPS at `now`.
+    pub fn is_https_only(&self, host: &str, now: SystemTime) -> bool {
+        self.policy(host, now).is_some()
+    }
+
+    /// Returns the `url` rewritten to HTTPS, if it is an `http` URL of a
+    /// host which must be accessed over HTTPS at `now`.
+    ///
+    /// An explicit port 80 becomes 443; any other port is kept.
+    pub fn upgrade(&self, url: &Uri, now: SystemTime) -> Option<Uri> {
+        if url.scheme() != Some(&Scheme::HTTP) || !self.is_https_only(url.host()?, now) {
+            return None;
+        }
+        let authority = url.authority()?;
+        let authority = match authority.port_u16() {
+            Some(80) => {
+                let host = authority.as_str().rsplit_once(':')?.0;
+                format!("{}:443", host).parse::<Authority>().ok()?
+            }
+            _ => authority.clone(),
+        };
+        let mut parts = url.clone().into_parts();
+        parts.scheme = Some(Scheme::HTTPS);
+        parts.authority = Some(authority);
+        Uri::from_parts(parts).ok()
+    }
+
+    /// Remove the policy of a host, if any.
+    pub fn remove(&mut self, host: &str) -> Option<HstsPolicy> {
+        self.policies.remove(&normalize(host)?)
+    }
+
+    /// Remove all policies which have expired at `now`.
+    pub fn remove_expired(&mut self, now: SystemTime) {
+        self.policies.retain(|_, p| !p.is_expired(now));
+    }
+
+    /// Remove all policies.
+    pub fn clear(&mut self) {
+        self.policies.clear();
+    }
+
+    /// Returns an iterator over the stored policies, in arbitrary order,
+    /// including any which have expired but not yet been removed.
+    pub fn iter(&self) -> hash_map::Values<'_, String, HstsPolicy> {
+        self.policies.values()
+    }
+
+    /// The number of stored policies.
+    pub fn len(&self) -> usize {
+        self.policies.len()
+    }
+
+    /// Returns true if no policies are stored.
+    pub fn is_empty(&self) -> bool {
+        self.policies.is_empty()
+    }
+}
+
+// Lowercase, without any trailing dot, or `None` for an IP address.
+fn normalize(host: &str) -> Option<String> {
+    let host = host.trim_end_matches('.').to_ascii_lowercase();
+    if host.is_empty() || host.starts_with('[') || host.parse::<IpAddr>().is_ok() {
+        return None;
+    }
+    Some(host)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn uri(s: &str) -> Uri {
+        s.parse().unwrap()
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000_000)
+    }
+
+    #[test]
+    fn test_store() {
+        let mut store = HstsStore::new();
+        let sts = StrictTransportSecurity::excluding_subdomains(60);
+        store.store(&sts, &uri("https://Example.COM./"), now());
+        assert_eq!(store.len(), 1);
+
+        let policy = store.policy("example.com", now()).unwrap();
+        assert_eq!(policy.host(), "example.com");
+        assert!(!policy.include_subdomains());
+        assert_eq!(policy.expires(), now() + Duration::from_secs(60));
+
+        assert!(store.is_https_only("EXAMPLE.com.", now()));
+        assert!(!store.is_https_only("www.example.com", now()));
+        assert!(!store.is_https_only("example.com", now() + Duration::from_secs(60)));
+    }
+
+    #[test]
+    fn test_store_ignored() {
+        let mut store = HstsStore::new();
+        let sts = StrictTransportSecurity::including_subdomains(60);
+        store.store(&sts, &uri("http://example.com/"), now());
+        store.store(&sts, &uri("https://127.0.0.1/"), now());
+        store.store(&sts, &uri("https://[::1]:8443/"), now());
+        store.store(&sts, &uri("/relative"), now());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_max_age_zero() {
+        let mut store = HstsStore::new();
+        let url = uri("https://example.com/");
+        store.store(
+            &StrictTransportSecurity::including_subdomains(60),
+            &url,
+            now(),
+        );
+        store.store(
+            &StrictTransportSecurity::including_subdomains(0),
+            &url,
+            now(),
+        );
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_superdomain() {
+        let mut store = HstsStore::new();
+        store.store(
+            &StrictTransportSecurity::including_subdomains(60),
+            &uri("https://example.com/"),
+            now(),
+        );
+        store.store(
+            &StrictTransportSecurity::excluding_subdomains(120),
+            &uri("https://a.example.com/"),
+            now(),
+        );
+        assert!(store.is_https_only("b.a.example.com", now()));
+        assert_eq!(
+            store.policy("a.example.com", now()).unwrap().host(),
+            "a.example.com"
+        );
+        assert_eq!(
+            store.policy("b.a.example.com", now()).unwrap().host(),
+            "example.com"
+        );
+        assert!(!store.is_https_only("badexample.com", now()));
+        assert!(!store.is_https_only("com", now()));
+
+        // The congruent match outlives the superdomain policy
+        let later = now() + Duration::from_secs(90);
+        assert!(store.is_https_only("a.example.com", later));
+        assert!(!store.is_https_only("b.a.example.com", later));
+
+        store.remove_expired(later);
+        assert_eq!(store.len(), 1);
+        assert!(store.remove("A.example.com").is_some());
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn test_store_response() {
+        let mut store = HstsStore::new();
+        let mut headers = HeaderMap::new();
+        headers.append(
+            "strict-transport-security",
+            "max-age=60; includeSubDomains".parse().unwrap(),
+        );
+        headers.append("strict-transport-security", "max-age=0".parse().unwrap());
+        store.store_response(&headers, &uri("https://example.com/"), now());
+        assert!(store.is_https_only("www.example.com", now()));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("strict-transport-security", "bogus".parse().unwrap());
+        store.store_response(&headers, &uri("https://example.org/"), now());
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn test_upgrade() {
+        let mut store = HstsStore::new();
+        store.store(
+            &StrictTransportSecurity::including_subdomains(60),
+            &uri("https://example.com/"),
+            now(),
+        );
+        assert_eq!(
+            store
+                .upgrade(&uri("http://example.com:80/x"), now())
+                .unwrap(),
+            "https://example.com:443/x"
+        );
+        assert_eq!(
+            store
+                .upgrade(&uri("http://www.example.com:8080/"), now())
+                .unwrap(),
+            "https://www.example.com:8080/"
+        );
+        assert_eq!(store.upgrade(&uri("https://example.com/"), now()), None);
+        assert_eq!(store.upgrade(&uri("http://example.org/"), now()), None);
+    }
+}
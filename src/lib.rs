@@ -13,25 +13,77 @@
 //!
 //! See the [*header*](header/index.html) module for more details.
 
+#[cfg(feature = "axum")]
+extern crate axum_core;
 extern crate base64;
 extern crate bytes;
+#[cfg(feature = "chrono")]
+extern crate chrono;
+#[cfg(feature = "cookie")]
+extern crate cookie;
 extern crate http;
-extern crate httpdate;
-extern crate language_tags;
+#[cfg(feature = "http02")]
+extern crate http02;
+#[cfg(feature = "httparse")]
+extern crate httparse;
+#[cfg(feature = "derive")]
+extern crate hyperx_derive;
+pub extern crate language_tags;
+#[cfg(test)]
+extern crate md5;
 pub extern crate mime;
 extern crate percent_encoding;
+#[cfg(feature = "proptest")]
+#[macro_use]
+extern crate proptest;
+#[cfg(feature = "serde")]
+extern crate serde;
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+#[cfg(test)]
+extern crate sha2;
+#[macro_use]
+pub extern crate smallvec;
+#[cfg(feature = "time")]
+extern crate time;
 extern crate unicase;
 
 #[cfg(all(test, feature = "nightly"))]
 extern crate test;
 
-pub use error::{Error, Result};
+pub use error::{Error, ErrorKind, HeaderError, HeaderErrorKind, Result};
 
 #[cfg(feature = "headers")]
 pub use header::Headers;
 
 pub use method::Method;
 
+pub mod alt_svc;
+pub mod audit;
+pub mod cache;
+pub mod canonical;
+pub mod client_hints;
+pub mod coding;
+pub mod conditional;
+pub mod cookie_jar;
+pub mod cors;
+pub mod digest;
+pub mod early_hints;
 mod error;
+pub mod etag;
+pub mod fetch_metadata;
+pub mod forwarded;
 pub mod header;
+pub mod hsts;
 mod method;
+pub mod multipart;
+pub mod negotiate;
+pub mod proxy;
+pub mod range;
+pub mod ratelimit;
+pub mod security;
+pub mod server_timing;
+pub mod signature;
+pub mod smuggling;
+pub mod trailers;
+pub mod websocket;
@@ -1,25 +1,38 @@
 //! The HTTP request method
 use error::Error;
 use http;
+use std::borrow::Cow;
 use std::convert::{AsRef, TryFrom};
 use std::fmt;
 use std::str::FromStr;
 
-use self::Method::{Connect, Delete, Extension, Get, Head, Options, Patch, Post, Put, Trace};
+use self::Method::{
+    Acl, Connect, Delete, Extension, Get, Head, Lock, MkCalendar, MkCol, Move, Options, Patch,
+    Post, PropFind, PropPatch, Put, Query, Report, Search, Trace, Unlock,
+};
 
 /// The Request Method (VERB)
 ///
-/// Currently includes 8 variants representing the 8 methods defined in
-/// [RFC 7230](https://tools.ietf.org/html/rfc7231#section-4.1), plus PATCH,
-/// and an Extension variant for all extensions.
+/// Includes the 8 methods defined in
+/// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-9), PATCH,
+/// and the registered methods of WebDAV and its extensions, such as
+/// PROPFIND or REPORT, and QUERY. All other methods are represented by the
+/// Extension variant.
 ///
-/// It may make sense to grow this to include all variants currently
-/// registered with IANA, if they are at all common to use.
-#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+/// Converts from `http::Method` without loss, and into `http::Method` via
+/// `TryFrom`, failing only for an Extension method which is not a valid
+/// token.
+///
+/// The enum is non-exhaustive, as further registered methods may be added,
+/// which would otherwise be parsed as an Extension. Match on `as_ref()` to
+/// handle a method by name regardless.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+#[non_exhaustive]
 pub enum Method {
     /// OPTIONS
     Options,
     /// GET
+    #[default]
     Get,
     /// POST
     Post,
@@ -35,8 +48,34 @@ pub enum Method {
     Connect,
     /// PATCH
     Patch,
-    /// Method extensions. An example would be `let m = Extension("FOO".to_string())`.
-    Extension(String),
+    /// ACL, of [RFC 3744](https://www.rfc-editor.org/rfc/rfc3744#section-8.1)
+    Acl,
+    /// COPY, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.8)
+    Copy,
+    /// LOCK, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.10)
+    Lock,
+    /// MKCALENDAR, of [RFC 4791](https://www.rfc-editor.org/rfc/rfc4791#section-5.3.1)
+    MkCalendar,
+    /// MKCOL, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.3)
+    MkCol,
+    /// MOVE, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.9)
+    Move,
+    /// PROPFIND, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.1)
+    PropFind,
+    /// PROPPATCH, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.2)
+    PropPatch,
+    /// QUERY, of [draft-ietf-httpbis-safe-method-w-body](https://datatracker.ietf.org/doc/draft-ietf-httpbis-safe-method-w-body/)
+    Query,
+    /// REPORT, of [RFC 3253](https://www.rfc-editor.org/rfc/rfc3253#section-3.6)
+    Report,
+    /// SEARCH, of [RFC 5323](https://www.rfc-editor.org/rfc/rfc5323#section-2)
+    Search,
+    /// UNLOCK, of [RFC 4918](https://www.rfc-editor.org/rfc/rfc4918#section-9.11)
+    Unlock,
+    /// Method extensions. An example would be `let m = Extension("FOO".into())`.
+    ///
+    /// A static name is borrowed rather than copied, see `Method::extension`.
+    Extension(Cow<'static, str>),
 }
 
 impl AsRef<str> for Method {
@@ -51,43 +90,86 @@ impl AsRef<str> for Method {
             Trace => "TRACE",
             Connect => "CONNECT",
             Patch => "PATCH",
+            Acl => "ACL",
+            Method::Copy => "COPY",
+            Lock => "LOCK",
+            MkCalendar => "MKCALENDAR",
+            MkCol => "MKCOL",
+            Move => "MOVE",
+            PropFind => "PROPFIND",
+            PropPatch => "PROPPATCH",
+            Query => "QUERY",
+            Report => "REPORT",
+            Search => "SEARCH",
+            Unlock => "UNLOCK",
             Extension(ref s) => s.as_ref(),
         }
     }
 }
 
 impl Method {
-    /// Whether a method is considered "safe", meaning the request is
-    /// essentially read-only.
+    /// Create an extension method of a static name, without allocation.
     ///
-    /// See [the spec](https://tools.ietf.org/html/rfc7231#section-4.2.1)
-    /// for more words.
-    pub fn safe(&self) -> bool {
-        match *self {
-            Get | Head | Options | Trace => true,
-            _ => false,
-        }
+    /// The name should be a token, and not one of the methods with a
+    /// variant, which would not compare equal to the parsed method.
+    ///
+    /// ```
+    /// use hyperx::Method;
+    ///
+    /// const PURGE: Method = Method::extension("PURGE");
+    /// assert_eq!("PURGE".parse::<Method>().unwrap(), PURGE);
+    /// ```
+    pub const fn extension(name: &'static str) -> Method {
+        Extension(Cow::Borrowed(name))
     }
 
-    /// Whether a method is considered "idempotent", meaning the request has
-    /// the same result if executed multiple times.
+    /// Whether a method is "safe", meaning the request is essentially
+    /// read-only, as defined in
+    /// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.1), or
+    /// registered as such. Extension methods are not considered safe.
+    pub fn is_safe(&self) -> bool {
+        matches!(
+            *self,
+            Get | Head | Options | Trace | PropFind | Query | Report | Search
+        )
+    }
+
+    /// Whether a method is "idempotent", meaning the request has the same
+    /// result if executed multiple times, as defined in
+    /// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.2), or
+    /// registered as such. All safe methods are idempotent.
+    pub fn is_idempotent(&self) -> bool {
+        self.is_safe()
+            || matches!(
+                *self,
+                Put | Delete | Acl | Method::Copy | MkCalendar | MkCol | Move | PropPatch | Unlock
+            )
+    }
+
+    /// Whether responses to a method may be stored by a cache, as defined in
+    /// [RFC 9110](https://www.rfc-editor.org/rfc/rfc9110#section-9.2.3):
+    /// GET, HEAD, POST and QUERY.
     ///
-    /// See [the spec](https://tools.ietf.org/html/rfc7231#section-4.2.2) for
-    /// more words.
+    /// A response to POST is only reusable for a later GET or HEAD, and
+    /// only with explicit freshness information and a matching
+    /// `Content-Location`, which caches rarely implement.
+    pub fn is_cacheable(&self) -> bool {
+        matches!(*self, Get | Head | Post | Query)
+    }
+
+    /// Equivalent to `is_safe`.
+    pub fn safe(&self) -> bool {
+        self.is_safe()
+    }
+
+    /// Equivalent to `is_idempotent`.
     pub fn idempotent(&self) -> bool {
-        if self.safe() {
-            true
-        } else {
-            match *self {
-                Put | Delete => true,
-                _ => false,
-            }
-        }
+        self.is_idempotent()
     }
 }
 
 macro_rules! from_str {
-    ($s:ident, { $($n:pat => { $($text:pat => $var:ident,)* },)* }) => ({
+    ($s:ident, { $($n:pat => { $($text:pat => $var:path,)* },)* }) => ({
         let s = $s;
         match s.len() {
             $(
@@ -101,7 +183,10 @@ macro_rules! from_str {
             0 => return Err(::Error::Method),
             _ => {},
         }
-        Ok(Extension(s.to_owned()))
+        if ::header::parsing::is_strict() && !::header::parsing::is_token(s) {
+            return Err(::Error::Method);
+        }
+        Ok(Extension(Cow::Owned(s.to_owned())))
     })
 }
 
@@ -112,85 +197,101 @@ impl FromStr for Method {
             3 => {
                 "GET" => Get,
                 "PUT" => Put,
+                "ACL" => Acl,
             },
             4 => {
                 "HEAD" => Head,
                 "POST" => Post,
+                "COPY" => Method::Copy,
+                "LOCK" => Lock,
+                "MOVE" => Move,
             },
             5 => {
                 "PATCH" => Patch,
                 "TRACE" => Trace,
+                "MKCOL" => MkCol,
+                "QUERY" => Query,
             },
             6 => {
                 "DELETE" => Delete,
+                "REPORT" => Report,
+                "SEARCH" => Search,
+                "UNLOCK" => Unlock,
             },
             7 => {
                 "OPTIONS" => Options,
                 "CONNECT" => Connect,
             },
+            8 => {
+                "PROPFIND" => PropFind,
+            },
+            9 => {
+                "PROPPATCH" => PropPatch,
+            },
+            10 => {
+                "MKCALENDAR" => MkCalendar,
+            },
         })
     }
 }
 
 impl fmt::Display for Method {
     fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        fmt.write_str(match *self {
-            Options => "OPTIONS",
-            Get => "GET",
-            Post => "POST",
-            Put => "PUT",
-            Delete => "DELETE",
-            Head => "HEAD",
-            Trace => "TRACE",
-            Connect => "CONNECT",
-            Patch => "PATCH",
-            Extension(ref s) => s.as_ref(),
-        })
+        fmt.write_str(self.as_ref())
     }
 }
 
-impl Default for Method {
-    fn default() -> Method {
-        Method::Get
+impl<'a> From<&'a http::Method> for Method {
+    fn from(method: &'a http::Method) -> Method {
+        match *method {
+            http::Method::GET => Get,
+            http::Method::POST => Post,
+            http::Method::PUT => Put,
+            http::Method::DELETE => Delete,
+            http::Method::HEAD => Head,
+            http::Method::OPTIONS => Options,
+            http::Method::CONNECT => Connect,
+            http::Method::PATCH => Patch,
+            http::Method::TRACE => Trace,
+            // Always a valid token, so not subject to strict parsing
+            ref m => match m.as_str().parse() {
+                Ok(method) => method,
+                Err(_) => Extension(Cow::Owned(m.as_str().to_owned())),
+            },
+        }
     }
 }
 
 impl From<http::Method> for Method {
     fn from(method: http::Method) -> Method {
-        match method {
-            http::Method::GET => Method::Get,
-            http::Method::POST => Method::Post,
-            http::Method::PUT => Method::Put,
-            http::Method::DELETE => Method::Delete,
-            http::Method::HEAD => Method::Head,
-            http::Method::OPTIONS => Method::Options,
-            http::Method::CONNECT => Method::Connect,
-            http::Method::PATCH => Method::Patch,
-            http::Method::TRACE => Method::Trace,
-            _ => method
-                .as_ref()
-                .parse()
-                .expect("attempted to convert invalid method"),
-        }
+        Method::from(&method)
     }
 }
 
-impl From<Method> for http::Method {
-    fn from(method: Method) -> http::Method {
-        match method {
-            Method::Get => http::Method::GET,
-            Method::Post => http::Method::POST,
-            Method::Put => http::Method::PUT,
-            Method::Delete => http::Method::DELETE,
-            Method::Head => http::Method::HEAD,
-            Method::Options => http::Method::OPTIONS,
-            Method::Connect => http::Method::CONNECT,
-            Method::Patch => http::Method::PATCH,
-            Method::Trace => http::Method::TRACE,
-            Method::Extension(s) => {
-                http::Method::try_from(s.as_str()).expect("attempted to convert invalid method")
-            }
-        }
+impl<'a> TryFrom<&'a Method> for http::Method {
+    type Error = http::method::InvalidMethod;
+
+    fn try_from(method: &'a Method) -> Result<http::Method, Self::Error> {
+        Ok(match *method {
+            Get => http::Method::GET,
+            Post => http::Method::POST,
+            Put => http::Method::PUT,
+            Delete => http::Method::DELETE,
+            Head => http::Method::HEAD,
+            Options => http::Method::OPTIONS,
+            Connect => http::Method::CONNECT,
+            Patch => http::Method::PATCH,
+            Trace => http::Method::TRACE,
+            ref m => http::Method::from_bytes(m.as_ref().as_bytes())?,
+        })
+    }
+}
+
+impl TryFrom<Method> for http::Method {
+    type Error = http::method::InvalidMethod;
+
+    fn try_from(method: Method) -> Result<http::Method, Self::Error> {
+        http::Method::try_from(&method)
     }
 }
 
@@ -199,6 +300,7 @@ mod tests {
     use super::Method;
     use super::Method::{Extension, Get, Post, Put};
     use error::Error;
+    use std::borrow::Cow;
     use std::collections::HashMap;
     use std::convert::TryFrom;
     use std::str::FromStr;
@@ -216,12 +318,31 @@ mod tests {
         assert!(!Post.idempotent());
     }
 
+    #[test]
+    fn test_cacheable() {
+        assert!(Get.is_cacheable());
+        assert!(Post.is_cacheable());
+        assert!(!Put.is_cacheable());
+        assert!(!Method::extension("PURGE").is_cacheable());
+    }
+
+    #[test]
+    fn test_extension() {
+        let purge = Method::extension("PURGE");
+        match purge.clone() {
+            Extension(Cow::Borrowed(s)) => assert_eq!(s, "PURGE"),
+            _ => panic!("not borrowed"),
+        }
+        assert_eq!(purge, "PURGE".parse().unwrap());
+        assert!(!purge.is_safe() && !purge.is_idempotent());
+    }
+
     #[test]
     fn test_from_str() {
         assert_eq!(Get, FromStr::from_str("GET").unwrap());
         assert_eq!(
-            Extension("MOVE".to_owned()),
-            FromStr::from_str("MOVE").unwrap()
+            Extension("XMODIFY".into()),
+            FromStr::from_str("XMODIFY").unwrap()
         );
         let x: Result<Method, _> = FromStr::from_str("");
         if let Err(Error::Method) = x {
@@ -230,12 +351,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_from_str_strict() {
+        use header::parsing::ParseConfig;
+
+        assert!("BAD METHOD".parse::<Method>().is_ok());
+        let strict = ParseConfig::new().strict(true);
+        assert!(strict.scope(|| "BAD METHOD".parse::<Method>()).is_err());
+        assert!(strict.scope(|| "XMODIFY".parse::<Method>()).is_ok());
+    }
+
     #[test]
     fn test_fmt() {
         assert_eq!("GET".to_owned(), format!("{}", Get));
         assert_eq!(
-            "MOVE".to_owned(),
-            format!("{}", Extension("MOVE".to_owned()))
+            "XMODIFY".to_owned(),
+            format!("{}", Extension("XMODIFY".into()))
         );
     }
 
@@ -251,19 +382,52 @@ mod tests {
         assert_eq!(Get.as_ref(), "GET");
         assert_eq!(Post.as_ref(), "POST");
         assert_eq!(Put.as_ref(), "PUT");
-        assert_eq!(Extension("MOVE".to_owned()).as_ref(), "MOVE");
+        assert_eq!(Extension("XMODIFY".into()).as_ref(), "XMODIFY");
     }
 
     #[test]
     fn test_compat() {
-        let methods = vec!["GET", "POST", "PUT", "MOVE"];
+        let methods = vec!["GET", "POST", "PUT", "MOVE", "PROPFIND", "XMODIFY"];
         for method in methods {
             let orig_hyper_method = Method::from_str(method).unwrap();
             let orig_http_method = http::Method::try_from(method).unwrap();
             let conv_hyper_method: Method = orig_http_method.clone().into();
-            let conv_http_method: http::Method = orig_hyper_method.clone().into();
+            let conv_http_method = http::Method::try_from(orig_hyper_method.clone()).unwrap();
             assert_eq!(orig_hyper_method, conv_hyper_method);
             assert_eq!(orig_http_method, conv_http_method);
         }
+        assert!(http::Method::try_from(Extension("BAD METHOD".into())).is_err());
+    }
+
+    #[test]
+    fn test_registered() {
+        for &(s, ref m) in &[
+            ("ACL", Method::Acl),
+            ("COPY", Method::Copy),
+            ("LOCK", Method::Lock),
+            ("MKCALENDAR", Method::MkCalendar),
+            ("MKCOL", Method::MkCol),
+            ("MOVE", Method::Move),
+            ("PROPFIND", Method::PropFind),
+            ("PROPPATCH", Method::PropPatch),
+            ("QUERY", Method::Query),
+            ("REPORT", Method::Report),
+            ("SEARCH", Method::Search),
+            ("UNLOCK", Method::Unlock),
+        ] {
+            assert_eq!(s.parse::<Method>().unwrap(), *m);
+            assert_eq!(m.to_string(), s);
+            let http = http::Method::try_from(m).unwrap();
+            assert_eq!(http.as_str(), s);
+            assert_eq!(Method::from(&http), *m);
+        }
+        // Methods are case-sensitive
+        assert_eq!(
+            "propfind".parse::<Method>().unwrap(),
+            Extension("propfind".into())
+        );
+        assert!(Method::PropFind.safe());
+        assert!(!Method::Lock.idempotent());
+        assert!(Method::Move.idempotent() && !Method::Move.safe());
     }
 }
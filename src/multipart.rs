@@ -0,0 +1,334 @@
+//! Headers of `multipart/form-data` parts, per [RFC 7578].
+//!
+//! Each part of a `multipart/form-data` body starts with a header block,
+//! naming the form field in a `Content-Disposition: form-data` header, with
+//! an optional `Content-Type` and (deprecated) `Content-Transfer-Encoding`.
+//! [`FormDataPart::parse`](struct.FormDataPart.html#method.parse) parses the
+//! header block of a part into a typed [`FormDataPart`](struct.FormDataPart.html),
+//! leaving the splitting of the body on boundaries, and the decoding of the
+//! part body, to the caller. The `Display` form of a `FormDataPart` is its
+//! header block, for writing a part.
+//!
+//! ```
+//! # extern crate hyperx;
+//! use hyperx::mime;
+//! use hyperx::multipart::FormDataPart;
+//!
+//! # fn run() -> hyperx::Result<()> {
+//! let part = b"Content-Disposition: form-data; name=\"file\"; filename=\"a b.txt\"\r\n\
+//!              Content-Type: text/plain\r\n\
+//!              \r\n\
+//!              content";
+//! let (headers, body) = FormDataPart::parse(part)?;
+//! assert_eq!(headers.name, "file");
+//! assert_eq!(headers.filename.as_ref().unwrap(), "a b.txt");
+//! assert_eq!(headers.media_type(), mime::TEXT_PLAIN);
+//! assert_eq!(body, b"content");
+//!
+//! let field = FormDataPart::new("title");
+//! assert_eq!(field.to_string(), "Content-Disposition: form-data; name=\"title\"\r\n\r\n");
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+//!
+//! [RFC 7578]: https://www.rfc-editor.org/rfc/rfc7578
+
+use std::fmt;
+use std::str;
+
+use http::header::{self as h, HeaderMap, HeaderName, HeaderValue};
+use mime::{self, Mime};
+
+use header::parsing;
+use {Error, HeaderErrorKind, Result};
+
+static CONTENT_TRANSFER_ENCODING: &str = "content-transfer-encoding";
+
+/// The typed header block of a `multipart/form-data` part.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FormDataPart {
+    /// The form field name, the `name` parameter of `Content-Disposition`.
+    pub name: String,
+    /// The original file name, from the `filename*` or `filename` parameter
+    /// of `Content-Disposition`.
+    pub filename: Option<String>,
+    /// The media type of the part, from `Content-Type`.
+    pub content_type: Option<Mime>,
+    /// The lowercased `Content-Transfer-Encoding` of the part, such as
+    /// `base64` or `quoted-printable`. Deprecated by RFC 7578, but still
+    /// sent by some clients.
+    pub transfer_encoding: Option<String>,
+    /// Any other headers of the part.
+    pub headers: HeaderMap,
+}
+
+impl FormDataPart {
+    /// Create the headers of a part for the given field name.
+    pub fn new<S: Into<String>>(name: S) -> FormDataPart {
+        FormDataPart {
+            name: name.into(),
+            filename: None,
+            content_type: None,
+            transfer_encoding: None,
+            headers: HeaderMap::new(),
+        }
+    }
+
+    /// Set the file name.
+    pub fn with_filename<S: Into<String>>(mut self, filename: S) -> FormDataPart {
+        self.filename = Some(filename.into());
+        self
+    }
+
+    /// Set the media type.
+    pub fn with_content_type(mut self, content_type: Mime) -> FormDataPart {
+        self.content_type = Some(content_type);
+        self
+    }
+
+    /// The media type of the part, defaulting to `text/plain`.
+    pub fn media_type(&self) -> Mime {
+        self.content_type.clone().unwrap_or(mime::TEXT_PLAIN)
+    }
+
+    /// Parse the header block at the start of a part, as between the
+    /// boundary delimiter line and the empty line, returning the headers
+    /// and the remaining part body.
+    ///
+    /// Lines may end with CRLF or a bare LF, and obsolete line folding is
+    /// unfolded. Fails if the empty line ending the header block is
+    /// missing, if a line is malformed, or if there is no
+    /// `Content-Disposition: form-data` header with a `name`.
+    pub fn parse(part: &[u8]) -> Result<(FormDataPart, &[u8])> {
+        let (block, body) = split_block(part)?;
+        let mut headers = HeaderMap::new();
+        let mut lines: Vec<Vec<u8>> = Vec::new();
+        for line in block.split(|&b| b == b'\n') {
+            let line = line.strip_suffix(b"\r").unwrap_or(line);
+            match (line.first(), lines.last_mut()) {
+                (Some(&b' '), Some(last)) | (Some(&b'\t'), Some(last)) => {
+                    last.push(b' ');
+                    last.extend_from_slice(trim(line));
+                }
+                _ => lines.push(line.to_vec()),
+            }
+        }
+        for line in lines {
+            let colon = line
+                .iter()
+                .position(|&b| b == b':')
+                .ok_or_else(|| Error::header(HeaderErrorKind::Syntax))?;
+            let name = HeaderName::from_bytes(&line[..colon])
+                .map_err(|e| Error::header_with_source(HeaderErrorKind::Token, e))?;
+            let value = HeaderValue::from_bytes(trim(&line[colon + 1..]))
+                .map_err(|e| Error::header_with_source(HeaderErrorKind::Syntax, e))?;
+            headers.append(name, value);
+        }
+
+        let mut part = FormDataPart::new("");
+        parse_disposition(&mut part, one(&mut headers, h::CONTENT_DISPOSITION)?)?;
+        if let Some(value) = one_opt(&mut headers, h::CONTENT_TYPE)? {
+            part.content_type = Some(
+                utf8(&value)?
+                    .parse()
+                    .map_err(|e| Error::header_with_source(HeaderErrorKind::Syntax, e))?,
+            );
+        }
+        let cte = HeaderName::from_static(CONTENT_TRANSFER_ENCODING);
+        if let Some(value) = one_opt(&mut headers, cte)? {
+            let value = utf8(&value)?;
+            if !parsing::is_token(value) {
+                return Err(Error::header(HeaderErrorKind::Token));
+            }
+            part.transfer_encoding = Some(value.to_ascii_lowercase());
+        }
+        part.headers = headers;
+        Ok((part, body))
+    }
+}
+
+/// Formats the header block, with each line and the final empty line
+/// terminated by CRLF.
+///
+/// The `filename` is sent as a quoted-string of the UTF-8 name, as by
+/// browsers, rather than as a `filename*` extended value, per RFC 7578
+/// §4.2.
+impl fmt::Display for FormDataPart {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("Content-Disposition: form-data; name=")?;
+        parsing::fmt_quoted(f, &self.name)?;
+        if let Some(ref filename) = self.filename {
+            f.write_str("; filename=")?;
+            parsing::fmt_quoted(f, filename)?;
+        }
+        f.write_str("\r\n")?;
+        if let Some(ref content_type) = self.content_type {
+            write!(f, "Content-Type: {}\r\n", content_type)?;
+        }
+        if let Some(ref encoding) = self.transfer_encoding {
+            write!(f, "Content-Transfer-Encoding: {}\r\n", encoding)?;
+        }
+        for (name, value) in &self.headers {
+            f.write_str(name.as_str())?;
+            f.write_str(": ")?;
+            f.write_str(&String::from_utf8_lossy(value.as_bytes()))?;
+            f.write_str("\r\n")?;
+        }
+        f.write_str("\r\n")
+    }
+}
+
+// Split the part at the empty line ending the header block, which may also
+// be the first line.
+fn split_block(part: &[u8]) -> Result<(&[u8], &[u8])> {
+    let mut start = 0;
+    while let Some(i) = part[start..].iter().position(|&b| b == b'\n') {
+        let end = start + i;
+        let line = &part[start..end];
+        if line.is_empty() || line == b"\r" {
+            let block = part[..start].strip_suffix(b"\n").unwrap_or(&part[..start]);
+            return Ok((block.strip_suffix(b"\r").unwrap_or(block), &part[end + 1..]));
+        }
+        start = end + 1;
+    }
+    Err(Error::header(HeaderErrorKind::Syntax))
+}
+
+fn parse_disposition(part: &mut FormDataPart, value: HeaderValue) -> Result<()> {
+    let value = utf8(&value)?;
+    let mut params = parsing::split_delimited(value, ';');
+    match params.next() {
+        Some(t) if t.eq_ignore_ascii_case("form-data") => (),
+        _ => return Err(Error::header(HeaderErrorKind::Token)),
+    }
+    let mut name = None;
+    let mut filename = None;
+    let mut filename_ext = None;
+    for param in params {
+        let (key, value) = parsing::parse_parameter(param)?;
+        let value = value.ok_or_else(|| Error::header(HeaderErrorKind::Syntax))?;
+        if key.eq_ignore_ascii_case("name") {
+            name = Some(value.into_owned());
+        } else if key.eq_ignore_ascii_case("filename") {
+            filename = Some(value.into_owned());
+        } else if key.eq_ignore_ascii_case("filename*") {
+            let ext = parsing::parse_extended_value(&value)?;
+            filename_ext = ext.to_text().map(|t| t.into_owned());
+        }
+    }
+    part.name = name.ok_or_else(|| Error::header(HeaderErrorKind::Empty))?;
+    part.filename = filename_ext.or(filename);
+    Ok(())
+}
+
+// Remove the single value of a required header.
+fn one(headers: &mut HeaderMap, name: HeaderName) -> Result<HeaderValue> {
+    one_opt(headers, name)?.ok_or_else(|| Error::header(HeaderErrorKind::Empty))
+}
+
+// Remove the single value of an optional header.
+fn one_opt(headers: &mut HeaderMap, name: HeaderName) -> Result<Option<HeaderValue>> {
+    if headers.get_all(&name).iter().nth(1).is_some() {
+        return Err(Error::header(HeaderErrorKind::Multiple));
+    }
+    Ok(headers.remove(name))
+}
+
+fn trim(mut s: &[u8]) -> &[u8] {
+    while let Some((&b' ', rest)) | Some((&b'\t', rest)) = s.split_first() {
+        s = rest;
+    }
+    while let Some((&b' ', rest)) | Some((&b'\t', rest)) = s.split_last() {
+        s = rest;
+    }
+    s
+}
+
+// Header values as UTF-8, as sent by browsers for non-ASCII names.
+fn utf8(value: &HeaderValue) -> Result<&str> {
+    str::from_utf8(value.as_bytes())
+        .map_err(|e| Error::header_with_source(HeaderErrorKind::Utf8, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let part =
+            b"content-disposition: Form-Data; name=\"a \\\"b\\\"\"; filename=\"x.bin\";\r\n \
+                     filename*=UTF-8''%C2%A3.bin\r\n\
+                     Content-Type: application/octet-stream\r\n\
+                     Content-Transfer-Encoding: BASE64\r\n\
+                     X-Extra: 1\r\n\
+                     \r\n\
+                     AAEC\r\n";
+        let (headers, body) = FormDataPart::parse(part).unwrap();
+        assert_eq!(headers.name, "a \"b\"");
+        assert_eq!(headers.filename.as_ref().unwrap(), "£.bin");
+        assert_eq!(headers.content_type, Some(mime::APPLICATION_OCTET_STREAM));
+        assert_eq!(headers.transfer_encoding.as_ref().unwrap(), "base64");
+        assert_eq!(headers.headers.len(), 1);
+        assert_eq!(headers.headers["x-extra"], "1");
+        assert_eq!(body, b"AAEC\r\n");
+    }
+
+    #[test]
+    fn test_parse_lf() {
+        let (headers, body) =
+            FormDataPart::parse(b"Content-Disposition: form-data; name=x\n\n").unwrap();
+        assert_eq!(headers, FormDataPart::new("x"));
+        assert_eq!(headers.media_type(), mime::TEXT_PLAIN);
+        assert!(body.is_empty());
+    }
+
+    #[test]
+    fn test_parse_invalid() {
+        let cases: &[&[u8]] = &[
+            b"",
+            b"\r\n",
+            b"Content-Disposition: form-data; name=x\r\n",
+            b"Content-Disposition: attachment; name=x\r\n\r\n",
+            b"Content-Disposition: form-data\r\n\r\n",
+            b"Content-Disposition: form-data; name\r\n\r\n",
+            b"Content-Disposition: form-data; name=\"x\r\n\r\n",
+            b"Content-Disposition: form-data; name=x\r\nno colon\r\n\r\n",
+            b"Content-Disposition: form-data; name=x\r\nContent-Type: text\r\n\r\n",
+            b"Content-Disposition: form-data; name=x\r\nContent-Type: text/plain\r\nContent-Type: text/html\r\n\r\n",
+            b"Content-Disposition: form-data; name=x\r\nContent-Transfer-Encoding: a b\r\n\r\n",
+        ];
+        for case in cases {
+            assert!(
+                FormDataPart::parse(case).is_err(),
+                "{}",
+                String::from_utf8_lossy(case)
+            );
+        }
+    }
+
+    #[test]
+    fn test_display() {
+        let mut part = FormDataPart::new("upload")
+            .with_filename("résumé \"v2\".pdf")
+            .with_content_type(mime::APPLICATION_PDF);
+        part.transfer_encoding = Some("binary".to_owned());
+        part.headers
+            .insert("x-extra", HeaderValue::from_static("1"));
+        let block = part.to_string();
+        assert_eq!(
+            block,
+            "Content-Disposition: form-data; name=\"upload\"; filename=\"résumé \\\"v2\\\".pdf\"\r\n\
+             Content-Type: application/pdf\r\n\
+             Content-Transfer-Encoding: binary\r\n\
+             x-extra: 1\r\n\
+             \r\n"
+        );
+        let (parsed, body) = FormDataPart::parse(block.as_bytes()).unwrap();
+        assert_eq!(parsed, part);
+        assert!(body.is_empty());
+    }
+}
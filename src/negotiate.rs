@@ -0,0 +1,568 @@
+//! Proactive content negotiation, per
+//! [RFC 7231](https://tools.ietf.org/html/rfc7231#section-3.4.1).
+//!
+//! Given the `Accept`, `Accept-Encoding`, `Accept-Language` and
+//! `Accept-Charset` headers of a request, and the representations
+//! ([`Variant`](struct.Variant.html)s) available from a server,
+//! [`negotiate`](fn.negotiate.html) selects the preferred variant, along with
+//! the `Content-Type`, `Content-Language`, `Content-Encoding` and `Vary`
+//! headers to include in the response. To select among media types by the
+//! `Accept` header alone, use [`media_type`](fn.media_type.html).
+//!
+//! This module is also available as `hyperx::header::negotiate`.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::header::{ContentType, Encoding, TypedHeaders};
+//! use hyperx::mime;
+//! use hyperx::negotiate::{negotiate, Preferences, Variant};
+//!
+//! let mut req = http::HeaderMap::new();
+//! req.insert("accept", "text/html;q=0.9, application/json".parse().unwrap());
+//! req.insert("accept-encoding", "gzip".parse().unwrap());
+//!
+//! let variants = [
+//!     Variant::new(mime::TEXT_HTML),
+//!     Variant::new(mime::APPLICATION_JSON),
+//!     Variant::new(mime::APPLICATION_JSON).encoding(Encoding::Gzip),
+//! ];
+//!
+//! let chosen = negotiate(&Preferences::from_headers(&req), &variants).unwrap();
+//! assert_eq!(chosen.index, 2);
+//!
+//! let mut res = http::HeaderMap::new();
+//! chosen.encode(&mut res);
+//! assert_eq!(res.decode::<ContentType>().unwrap(), ContentType::json());
+//! assert_eq!(res["content-encoding"], "gzip");
+//! assert_eq!(res["vary"], "Accept, Accept-Encoding");
+//! ```
+
+use http::HeaderMap;
+use language_tags::LanguageTag;
+use mime::{self, Mime};
+use unicase::Ascii;
+
+use header::{
+    qitem, Accept, AcceptCharset, AcceptEncoding, AcceptLanguage, Charset, ContentEncoding,
+    ContentLanguage, ContentType, Encoding, LanguageRange, QualityItem, TypedHeaders, Vary,
+};
+
+/// A representation available from the server, as a candidate for
+/// negotiation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Variant {
+    /// The media type of the representation.
+    pub media_type: Mime,
+    /// The language of the representation, if any.
+    pub language: Option<LanguageTag>,
+    /// The content coding of the representation, or `None` for identity.
+    pub encoding: Option<Encoding>,
+    /// The charset of a textual representation, if not already given as a
+    /// parameter of the media type.
+    pub charset: Option<Charset>,
+}
+
+impl Variant {
+    /// Create a new variant of the given media type, without language,
+    /// content coding or charset.
+    pub fn new(media_type: Mime) -> Variant {
+        Variant {
+            media_type,
+            language: None,
+            encoding: None,
+            charset: None,
+        }
+    }
+
+    /// Set the language of the variant.
+    pub fn language(mut self, language: LanguageTag) -> Variant {
+        self.language = Some(language);
+        self
+    }
+
+    /// Set the content coding of the variant.
+    pub fn encoding(mut self, encoding: Encoding) -> Variant {
+        self.encoding = Some(encoding);
+        self
+    }
+
+    /// Set the charset of the variant.
+    pub fn charset(mut self, charset: Charset) -> Variant {
+        self.charset = Some(charset);
+        self
+    }
+
+    fn charset_param(&self) -> Option<String> {
+        match self.charset {
+            Some(ref c) => Some(c.to_string()),
+            None => self
+                .media_type
+                .get_param(mime::CHARSET)
+                .map(|c| c.as_str().to_owned()),
+        }
+    }
+}
+
+/// The content negotiation headers of a request. A `None` header places no
+/// constraint on the selected variant.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Preferences {
+    /// The `Accept` header.
+    pub accept: Option<Accept>,
+    /// The `Accept-Encoding` header.
+    pub accept_encoding: Option<AcceptEncoding>,
+    /// The `Accept-Language` header.
+    pub accept_language: Option<AcceptLanguage>,
+    /// The `Accept-Charset` header.
+    pub accept_charset: Option<AcceptCharset>,
+}
+
+impl Preferences {
+    /// Decode the preferences from the headers of a request. Headers which
+    /// fail to parse are ignored, as if not sent.
+    pub fn from_headers(headers: &HeaderMap) -> Preferences {
+        Preferences {
+            accept: headers.try_decode().and_then(|r| r.ok()),
+            accept_encoding: headers.try_decode().and_then(|r| r.ok()),
+            accept_language: headers.try_decode().and_then(|r| r.ok()),
+            accept_charset: headers.try_decode().and_then(|r| r.ok()),
+        }
+    }
+}
+
+/// The outcome of a successful negotiation.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Negotiated<'a> {
+    /// The selected variant.
+    pub variant: &'a Variant,
+    /// The index of the selected variant.
+    pub index: usize,
+    /// The `Content-Type` of the selected variant, including any charset.
+    pub content_type: ContentType,
+    /// The `Content-Language` of the selected variant, if any.
+    pub content_language: Option<ContentLanguage>,
+    /// The `Content-Encoding` of the selected variant, if not identity.
+    pub content_encoding: Option<ContentEncoding>,
+    /// The request headers by which the available variants differ, if any.
+    pub vary: Option<Vary>,
+}
+
+impl<'a> Negotiated<'a> {
+    /// Encode the selected headers into the headers of a response,
+    /// replacing any existing values.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        headers.encode(&self.content_type);
+        if let Some(ref cl) = self.content_language {
+            headers.encode(cl);
+        }
+        if let Some(ref ce) = self.content_encoding {
+            headers.encode(ce);
+        }
+        if let Some(ref vary) = self.vary {
+            headers.encode(vary);
+        }
+    }
+}
+
+/// Select the variant most preferred by the request, or return `None` if no
+/// variant is acceptable (i.e. `406 Not Acceptable`).
+///
+/// The preference for a variant is the product of the quality values of the
+/// most specific matching entries of each header. An identity (unencoded)
+/// variant is acceptable even if not listed in `Accept-Encoding`, but then
+/// least preferred. Ties are broken in favor of the earliest variant, so
+/// variants should be listed in order of server preference.
+pub fn negotiate<'a>(prefs: &Preferences, variants: &'a [Variant]) -> Option<Negotiated<'a>> {
+    let mut best: Option<(usize, u64)> = None;
+    for (i, v) in variants.iter().enumerate() {
+        let score = quality_of_type(prefs, v)
+            * quality_of_encoding(prefs, v)
+            * quality_of_language(prefs, v)
+            * quality_of_charset(prefs, v);
+        if score > 0 && best.map_or(true, |(_, s)| score > s) {
+            best = Some((i, score));
+        }
+    }
+    let (index, _) = best?;
+    let variant = &variants[index];
+
+    let content_type = match variant.charset {
+        Some(ref c) if variant.media_type.get_param(mime::CHARSET).is_none() => {
+            format!("{}; charset={}", variant.media_type, c)
+                .parse()
+                .map(ContentType)
+                .unwrap_or_else(|_| ContentType(variant.media_type.clone()))
+        }
+        _ => ContentType(variant.media_type.clone()),
+    };
+
+    let content_encoding = match variant.encoding {
+        Some(Encoding::Identity) | None => None,
+        Some(ref e) => Some(ContentEncoding(smallvec![e.clone()])),
+    };
+
+    Some(Negotiated {
+        variant,
+        index,
+        content_type,
+        content_language: variant
+            .language
+            .as_ref()
+            .map(|l| ContentLanguage(smallvec![qitem(l.clone())])),
+        content_encoding,
+        vary: vary(variants),
+    })
+}
+
+/// Select the media type most preferred by a parsed `Accept` header, or
+/// return `None` if none is acceptable (i.e. `406 Not Acceptable`).
+///
+/// Media ranges match as per `Accept::quality`: by wildcards, parameters and
+/// specificity, weighted by quality values. Ties are broken in favor of the
+/// earliest media type. Equivalent to `Accept::negotiate`.
+///
+/// ```
+/// use hyperx::header::negotiate;
+/// use hyperx::header::Accept;
+/// use hyperx::mime;
+///
+/// let accept: Accept = "text/*;q=0.5, application/json".parse().unwrap();
+/// let available = [mime::TEXT_PLAIN, mime::APPLICATION_JSON];
+/// assert_eq!(negotiate::media_type(&accept, &available), Some(&mime::APPLICATION_JSON));
+/// assert_eq!(negotiate::media_type(&accept, &available[..1]), Some(&mime::TEXT_PLAIN));
+/// assert_eq!(negotiate::media_type(&accept, &[mime::IMAGE_PNG]), None);
+/// ```
+pub fn media_type<'a>(accept: &Accept, available: &'a [Mime]) -> Option<&'a Mime> {
+    accept.negotiate(available)
+}
+
+// The request headers for each dimension in which the variants differ.
+fn vary(variants: &[Variant]) -> Option<Vary> {
+    let differs =
+        |f: &dyn Fn(&Variant) -> String| variants.windows(2).any(|w| f(&w[0]) != f(&w[1]));
+    let mut names = Vec::new();
+    if differs(&|v| v.media_type.essence_str().to_ascii_lowercase()) {
+        names.push("Accept");
+    }
+    if differs(&|v| encoding_name(v.encoding.as_ref())) {
+        names.push("Accept-Encoding");
+    }
+    if differs(&|v| {
+        v.language
+            .as_ref()
+            .map_or(String::new(), |l| l.as_str().to_ascii_lowercase())
+    }) {
+        names.push("Accept-Language");
+    }
+    if differs(&|v| v.charset_param().unwrap_or_default().to_ascii_lowercase()) {
+        names.push("Accept-Charset");
+    }
+    if names.is_empty() {
+        None
+    } else {
+        Some(Vary::Items(
+            names
+                .into_iter()
+                .map(|n| Ascii::new(n.to_owned()))
+                .collect(),
+        ))
+    }
+}
+
+// Find the quality of the best (most specific, then first) matching item,
+// where `specificity` returns `None` for items which do not match.
+fn best_match<T, F>(items: &[QualityItem<T>], specificity: F) -> Option<u64>
+where
+    F: Fn(&T) -> Option<usize>,
+{
+    let mut best: Option<(usize, u64)> = None;
+    for qi in items {
+        if let Some(s) = specificity(&qi.item) {
+            if best.map_or(true, |(bs, _)| s > bs) {
+                best = Some((s, u64::from(qi.quality.thousandths())));
+            }
+        }
+    }
+    best.map(|(_, q)| q)
+}
+
+const FULL: u64 = 1000;
+
+fn quality_of_type(prefs: &Preferences, v: &Variant) -> u64 {
+    match prefs.accept {
+        Some(ref a) if !a.is_empty() => {
+            media_type_quality(a, &v.media_type, v.charset.as_ref()).unwrap_or(0)
+        }
+        _ => FULL,
+    }
+}
+
+// The quality (in thousandths) of a media type, with any charset not given
+// as a parameter, from the most specific of the matching media ranges, or
+// `None` if none match.
+pub(crate) fn media_type_quality(
+    accept: &[QualityItem<Mime>],
+    mt: &Mime,
+    charset: Option<&Charset>,
+) -> Option<u64> {
+    best_match(accept, |range| {
+        if range.type_() == mime::STAR {
+            return Some(0);
+        }
+        if range.type_() != mt.type_() {
+            return None;
+        }
+        if range.subtype() == mime::STAR {
+            return Some(1);
+        }
+        if range.subtype() != mt.subtype() || range.suffix() != mt.suffix() {
+            return None;
+        }
+        let mut params = range.params().peekable();
+        if params.peek().is_none() {
+            return Some(2);
+        }
+        let matches = params.all(|(name, value)| match mt.get_param(name) {
+            Some(v) => v.as_str().eq_ignore_ascii_case(value.as_str()),
+            None if name == mime::CHARSET => {
+                charset.is_some_and(|c| c.to_string().eq_ignore_ascii_case(value.as_str()))
+            }
+            None => false,
+        });
+        if matches {
+            Some(3)
+        } else {
+            None
+        }
+    })
+}
+
+fn encoding_name(encoding: Option<&Encoding>) -> String {
+    encoding.map_or("identity".to_owned(), |e| {
+        e.to_string().to_ascii_lowercase()
+    })
+}
+
+fn quality_of_encoding(prefs: &Preferences, v: &Variant) -> u64 {
+    encoding_quality(prefs.accept_encoding.as_ref(), v.encoding.as_ref())
+}
+
+// The quality (in thousandths) of a content coding, or `None` for identity,
+// per an `Accept-Encoding` header if any.
+pub(crate) fn encoding_quality(
+    accept: Option<&AcceptEncoding>,
+    encoding: Option<&Encoding>,
+) -> u64 {
+    let accept = match accept {
+        Some(a) => a,
+        None => return FULL,
+    };
+    let name = encoding_name(encoding);
+    match best_match(accept, |e| match encoding_name(Some(e)) {
+        ref n if *n == name => Some(1),
+        ref n if n == "*" => Some(0),
+        _ => None,
+    }) {
+        Some(q) => q,
+        // Identity is acceptable unless excluded, but least preferred
+        None if name == "identity" => 1,
+        None => 0,
+    }
+}
+
+fn quality_of_language(prefs: &Preferences, v: &Variant) -> u64 {
+    let (accept, tag) = match (prefs.accept_language.as_ref(), v.language.as_ref()) {
+        (Some(a), Some(t)) if !a.is_empty() => (a, t),
+        _ => return FULL,
+    };
+    language_quality(accept, tag).unwrap_or(0)
+}
+
+// The quality (in thousandths) of a language tag, from the longest of the
+// matching language ranges, with `*` the least specific, or `None` if none
+// match. Shared with `AcceptLanguage::quality`.
+pub(crate) fn language_quality(
+    accept: &[QualityItem<LanguageRange>],
+    tag: &LanguageTag,
+) -> Option<u64> {
+    best_match(accept, |range| match *range {
+        LanguageRange::Any => Some(0),
+        ref range if range.matches(tag) => Some(range.as_str().len()),
+        _ => None,
+    })
+}
+
+fn quality_of_charset(prefs: &Preferences, v: &Variant) -> u64 {
+    let (accept, charset) = match (prefs.accept_charset.as_ref(), v.charset_param()) {
+        (Some(a), Some(c)) if !a.is_empty() => (a, c),
+        _ => return FULL,
+    };
+    best_match(accept, |c| match *c {
+        Charset::Ext(ref s) if s == "*" => Some(0),
+        ref c if c.to_string().eq_ignore_ascii_case(&charset) => Some(1),
+        _ => None,
+    })
+    .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::q;
+
+    fn prefs(accept: &str, encoding: &str, language: &str, charset: &str) -> Preferences {
+        let mut headers = HeaderMap::new();
+        for &(name, value) in &[
+            ("accept", accept),
+            ("accept-encoding", encoding),
+            ("accept-language", language),
+            ("accept-charset", charset),
+        ] {
+            if !value.is_empty() {
+                headers.insert(name, value.parse().unwrap());
+            }
+        }
+        Preferences::from_headers(&headers)
+    }
+
+    #[test]
+    fn test_no_preferences() {
+        let variants = [
+            Variant::new(mime::TEXT_HTML),
+            Variant::new(mime::APPLICATION_JSON),
+        ];
+        let n = negotiate(&Preferences::default(), &variants).unwrap();
+        assert_eq!(n.index, 0);
+        assert_eq!(n.content_type, ContentType::html());
+        assert_eq!(n.content_language, None);
+        assert_eq!(n.content_encoding, None);
+        assert_eq!(n.vary, Some("Accept".parse().unwrap()));
+
+        assert!(negotiate(&Preferences::default(), &[]).is_none());
+    }
+
+    #[test]
+    fn test_media_type() {
+        let variants = [
+            Variant::new(mime::TEXT_HTML),
+            Variant::new(mime::TEXT_PLAIN),
+            Variant::new(mime::IMAGE_PNG),
+        ];
+        let chosen = |accept| negotiate(&prefs(accept, "", "", ""), &variants).map(|n| n.index);
+        assert_eq!(chosen("text/plain, text/html;q=0.5"), Some(1));
+        assert_eq!(chosen("text/*;q=0.5, image/png"), Some(2));
+        assert_eq!(chosen("text/*, text/html;q=0"), Some(1));
+        assert_eq!(chosen("*/*;q=0.1, image/*;q=0.2"), Some(2));
+        assert_eq!(chosen("application/json"), None);
+        assert_eq!(chosen("text/plain;format=flowed"), None);
+    }
+
+    #[test]
+    fn test_encoding() {
+        let variants = [
+            Variant::new(mime::TEXT_HTML),
+            Variant::new(mime::TEXT_HTML).encoding(Encoding::Brotli),
+            Variant::new(mime::TEXT_HTML).encoding(Encoding::Gzip),
+        ];
+        let chosen = |encoding| negotiate(&prefs("", encoding, "", ""), &variants).map(|n| n.index);
+        assert_eq!(chosen(""), Some(0));
+        assert_eq!(chosen("gzip"), Some(2));
+        assert_eq!(chosen("br;q=0.8, gzip;q=0.9"), Some(2));
+        assert_eq!(chosen("*"), Some(0));
+        assert_eq!(chosen("identity;q=0.1, *"), Some(1));
+        assert_eq!(chosen("compress"), Some(0));
+        assert_eq!(chosen("identity;q=0, compress"), None);
+
+        let n = negotiate(&prefs("", "gzip", "", ""), &variants).unwrap();
+        assert_eq!(
+            n.content_encoding,
+            Some(ContentEncoding(smallvec![Encoding::Gzip]))
+        );
+        assert_eq!(n.vary, Some("Accept-Encoding".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_language() {
+        let variants = [
+            Variant::new(mime::TEXT_HTML).language("en".parse().unwrap()),
+            Variant::new(mime::TEXT_HTML).language("de-CH".parse().unwrap()),
+            Variant::new(mime::TEXT_HTML).language("fr".parse().unwrap()),
+        ];
+        let chosen = |language| negotiate(&prefs("", "", language, ""), &variants).map(|n| n.index);
+        assert_eq!(chosen(""), Some(0));
+        assert_eq!(chosen("de"), Some(1));
+        assert_eq!(chosen("de, de-CH;q=0.1, fr;q=0.5"), Some(2));
+        assert_eq!(chosen("en-US"), None);
+        assert_eq!(chosen("en-US, *;q=0.5"), Some(0));
+        assert_eq!(chosen("*;q=0.5, fr"), Some(2));
+
+        let de = Variant::new(mime::TEXT_HTML).language("de".parse().unwrap());
+        let p = prefs("", "", "en, *;q=0.5", "");
+        assert_eq!(quality_of_language(&p, &de), 500);
+        assert_eq!(negotiate(&p, &[de]).map(|n| n.index), Some(0));
+        let p = prefs("", "", "*;q=0.5, de;q=0.2", "");
+        assert_eq!(quality_of_language(&p, &variants[1]), 200);
+
+        let n = negotiate(&prefs("", "", "fr", ""), &variants).unwrap();
+        assert_eq!(
+            n.content_language,
+            Some(ContentLanguage(smallvec![qitem("fr".parse().unwrap())]))
+        );
+    }
+
+    #[test]
+    fn test_charset() {
+        let variants = [
+            Variant::new(mime::TEXT_PLAIN_UTF_8),
+            Variant::new(mime::TEXT_PLAIN).charset(Charset::Iso_8859_1),
+        ];
+        let chosen = |charset| negotiate(&prefs("", "", "", charset), &variants).map(|n| n.index);
+        assert_eq!(chosen("utf-8"), Some(0));
+        assert_eq!(chosen("iso-8859-1"), Some(1));
+        assert_eq!(chosen("*;q=0.5, iso-8859-1;q=0.4"), Some(0));
+        assert_eq!(chosen("us-ascii"), None);
+
+        let n = negotiate(
+            &prefs("text/plain;charset=iso-8859-1", "", "", ""),
+            &variants,
+        )
+        .unwrap();
+        assert_eq!(n.index, 1);
+        assert_eq!(
+            n.content_type,
+            ContentType("text/plain; charset=ISO-8859-1".parse().unwrap())
+        );
+        assert_eq!(n.vary, Some("Accept-Charset".parse().unwrap()));
+    }
+
+    #[test]
+    fn test_combined() {
+        let variants = [
+            Variant::new(mime::TEXT_HTML).language("en".parse().unwrap()),
+            Variant::new(mime::APPLICATION_JSON).language("en".parse().unwrap()),
+            Variant::new(mime::APPLICATION_JSON).language("de".parse().unwrap()),
+        ];
+        let p = Preferences {
+            accept: Some(Accept(smallvec![
+                QualityItem::new(mime::TEXT_HTML, q(0.5)),
+                qitem(mime::APPLICATION_JSON),
+            ])),
+            accept_language: Some(AcceptLanguage(smallvec![
+                QualityItem::new("en".parse().unwrap(), q(0.4)),
+                qitem("de".parse().unwrap()),
+            ])),
+            ..Preferences::default()
+        };
+        let n = negotiate(&p, &variants).unwrap();
+        assert_eq!(n.index, 2);
+        assert_eq!(n.vary, Some("Accept, Accept-Language".parse().unwrap()));
+
+        let mut headers = HeaderMap::new();
+        n.encode(&mut headers);
+        assert_eq!(headers["content-type"], "application/json");
+        assert_eq!(headers["content-language"], "de");
+        assert_eq!(headers["vary"], "Accept, Accept-Language");
+        assert!(!headers.contains_key("content-encoding"));
+    }
+}
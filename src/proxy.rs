@@ -0,0 +1,136 @@
+//! Utilities for proxies and gateways forwarding messages.
+//!
+//! Hop-by-hop header fields apply only to a single connection, and must not
+//! be forwarded per [RFC 9110 §7.6.1]. [`strip_hop_by_hop`](fn.strip_hop_by_hop.html)
+//! removes them from a message before it is forwarded: the fields nominated
+//! by the typed [`Connection`](../header/struct.Connection.html) header, and
+//! the standard hop-by-hop fields, whether nominated or not.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::proxy::strip_hop_by_hop;
+//!
+//! let mut headers = http::HeaderMap::new();
+//! headers.insert("connection", "keep-alive, x-trace".parse().unwrap());
+//! headers.insert("keep-alive", "timeout=5".parse().unwrap());
+//! headers.insert("x-trace", "1".parse().unwrap());
+//! headers.insert("proxy-authorization", "Basic Zm9vOmJhcg==".parse().unwrap());
+//! headers.insert("accept", "*/*".parse().unwrap());
+//!
+//! strip_hop_by_hop(&mut headers);
+//! assert_eq!(headers.len(), 1);
+//! assert_eq!(headers["accept"], "*/*");
+//! ```
+//!
+//! [RFC 9110 §7.6.1]: https://www.rfc-editor.org/rfc/rfc9110#section-7.6.1
+
+use http::header::{HeaderMap, HeaderName};
+
+use header::{Connection, ConnectionOption, FieldInfo, TypedHeaders};
+
+/// Returns true if the field is hop-by-hop regardless of the `Connection`
+/// header: `Connection`, `Keep-Alive`, `TE`, `Trailer`, `Transfer-Encoding`,
+/// `Upgrade` and any `Proxy-*` field, such as `Proxy-Authorization` or the
+/// obsolete `Proxy-Connection`.
+///
+/// ```
+/// use hyperx::proxy::is_hop_by_hop;
+///
+/// assert!(is_hop_by_hop(&http::header::TRANSFER_ENCODING));
+/// assert!(is_hop_by_hop(&http::header::PROXY_AUTHENTICATE));
+/// assert!(!is_hop_by_hop(&http::header::CONTENT_LENGTH));
+/// ```
+pub fn is_hop_by_hop(name: &HeaderName) -> bool {
+    FieldInfo::lookup(name.as_str()).is_some_and(|f| f.hop_by_hop)
+        || name.as_str().starts_with("proxy-")
+}
+
+/// Remove the hop-by-hop fields of a message, before forwarding it.
+///
+/// Removes the fields nominated by the `Connection` header, then all fields
+/// for which [`is_hop_by_hop`](fn.is_hop_by_hop.html) is true. Nominated
+/// names which are not valid field names are ignored, as is an invalid
+/// `Connection` header, which is removed regardless.
+pub fn strip_hop_by_hop(headers: &mut HeaderMap) {
+    if let Some(Ok(Connection(options))) = headers.try_decode::<Connection>() {
+        for option in options {
+            let name = match option {
+                ConnectionOption::KeepAlive => continue,
+                ConnectionOption::Close => continue,
+                ConnectionOption::ConnectionHeader(name) => name,
+            };
+            if let Ok(name) = HeaderName::from_bytes(name.as_bytes()) {
+                headers.remove(name);
+            }
+        }
+    }
+    let hop_by_hop: Vec<HeaderName> = headers
+        .keys()
+        .filter(|name| is_hop_by_hop(name))
+        .cloned()
+        .collect();
+    for name in hop_by_hop {
+        headers.remove(name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    #[test]
+    fn test_standard() {
+        let mut headers = request(&[
+            ("te", "trailers"),
+            ("transfer-encoding", "chunked"),
+            ("trailer", "x-checksum"),
+            ("upgrade", "websocket"),
+            ("keep-alive", "timeout=5"),
+            ("proxy-connection", "keep-alive"),
+            ("proxy-authorization", "Basic Zm9vOmJhcg=="),
+            ("host", "example.com"),
+            ("x-checksum", "abc"),
+        ]);
+        strip_hop_by_hop(&mut headers);
+        let mut names: Vec<&str> = headers.keys().map(|n| n.as_str()).collect();
+        names.sort();
+        assert_eq!(names, vec!["host", "x-checksum"]);
+    }
+
+    #[test]
+    fn test_nominated() {
+        let mut headers = request(&[
+            ("connection", "close, X-Foo"),
+            ("connection", "x-bar, upgrade"),
+            ("x-foo", "1"),
+            ("x-bar", "2"),
+            ("x-bar", "3"),
+            ("x-baz", "4"),
+            ("upgrade", "h2c"),
+        ]);
+        strip_hop_by_hop(&mut headers);
+        let names: Vec<&str> = headers.keys().map(|n| n.as_str()).collect();
+        assert_eq!(names, vec!["x-baz"]);
+    }
+
+    #[test]
+    fn test_invalid_connection() {
+        let mut headers = request(&[("connection", ""), ("x-foo", "1")]);
+        strip_hop_by_hop(&mut headers);
+        assert_eq!(headers.len(), 1);
+        assert!(headers.contains_key("x-foo"));
+
+        let mut headers = request(&[("connection", "bad name, x-foo"), ("x-foo", "1")]);
+        strip_hop_by_hop(&mut headers);
+        assert!(headers.is_empty());
+    }
+}
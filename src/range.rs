@@ -0,0 +1,399 @@
+//! Resolution of byte range requests, per [RFC 9110 §14].
+//!
+//! [`resolve`](fn.resolve.html) validates and coalesces the ranges of a
+//! typed `Range` header against the length of the selected representation.
+//! For a multiple range request,
+//! [`MultipartByteranges`](struct.MultipartByteranges.html) provides the
+//! `multipart/byteranges` boundary, per-part headers and total body length.
+//!
+//! ```
+//! use hyperx::header::{ByteRangeSpec, ContentType, Range};
+//! use hyperx::range::{resolve, ByteRange, MultipartByteranges, Resolution};
+//!
+//! let range = Range::Bytes(vec![ByteRangeSpec::FromTo(0, 9), ByteRangeSpec::Last(5)]);
+//! let ranges = match resolve(&range, 100) {
+//!     Resolution::Multiple(ranges) => ranges,
+//!     other => panic!("unexpected {:?}", other),
+//! };
+//! assert_eq!(ranges, vec![ByteRange::new(0, 9), ByteRange::new(95, 99)]);
+//!
+//! let multipart = MultipartByteranges::new(ranges, 100, Some(ContentType::plaintext()))
+//!     .with_boundary("THIS_STRING_SEPARATES");
+//! assert_eq!(
+//!     multipart.content_type().to_string(),
+//!     "multipart/byteranges; boundary=THIS_STRING_SEPARATES"
+//! );
+//! let body = multipart.body(&[b'x'; 100]);
+//! assert_eq!(body.len() as u64, multipart.body_length());
+//! ```
+//!
+//! [RFC 9110 §14]: https://www.rfc-editor.org/rfc/rfc9110#section-14
+
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use header::{ContentRange, ContentRangeSpec, ContentType, Range};
+
+/// A satisfiable, end-inclusive range of bytes of a representation.
+///
+/// The first byte position is never greater than the last.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ByteRange {
+    first: u64,
+    last: u64,
+}
+
+impl ByteRange {
+    /// Create a new range from the first to last (inclusive) byte position.
+    ///
+    /// # Panics
+    ///
+    /// If `last` is less than `first`.
+    pub fn new(first: u64, last: u64) -> ByteRange {
+        assert!(first <= last, "invalid byte range {}-{}", first, last);
+        ByteRange { first, last }
+    }
+
+    /// The position of the first byte.
+    pub fn first(&self) -> u64 {
+        self.first
+    }
+
+    /// The position of the last byte, inclusive.
+    pub fn last(&self) -> u64 {
+        self.last
+    }
+
+    /// The number of bytes in the range.
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> u64 {
+        self.last - self.first + 1
+    }
+
+    /// The `Content-Range` header for this range of a representation of the
+    /// given complete length.
+    pub fn content_range(&self, complete_length: u64) -> ContentRange {
+        ContentRange(ContentRangeSpec::Bytes {
+            range: Some((self.first, self.last)),
+            instance_length: Some(complete_length),
+        })
+    }
+}
+
+/// The resolution of a `Range` header against a representation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The range unit is not supported: respond with `200 OK` and the full
+    /// representation.
+    Full,
+    /// Respond with `206 Partial Content` and the single range.
+    Single(ByteRange),
+    /// Respond with `206 Partial Content` and a `multipart/byteranges` body
+    /// of the (coalesced, ascending) ranges.
+    Multiple(Vec<ByteRange>),
+    /// No range is satisfiable: respond with `416 Range Not Satisfiable`.
+    /// See [`unsatisfied`](fn.unsatisfied.html).
+    Unsatisfiable,
+}
+
+/// Resolve a `Range` header against a representation of the given complete
+/// length.
+///
+/// Unsatisfiable byte-range-specs are dropped, and the remaining ranges are
+/// sorted and coalesced where they overlap or are adjacent, as permitted by
+/// RFC 9110 §14.1.1. This limits the number of parts to the number of
+/// disjoint ranges, and never yields a multipart response larger than the
+/// representation plus part headers.
+pub fn resolve(range: &Range, complete_length: u64) -> Resolution {
+    let specs = match *range {
+        Range::Bytes(ref specs) => specs,
+        Range::Unregistered(..) => return Resolution::Full,
+    };
+    let mut ranges: Vec<ByteRange> = specs
+        .iter()
+        .filter_map(|s| s.to_satisfiable_range(complete_length))
+        .map(|(first, last)| ByteRange { first, last })
+        .collect();
+    ranges.sort();
+
+    let mut coalesced: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+    for r in ranges {
+        match coalesced.last_mut() {
+            Some(prev) if r.first <= prev.last.saturating_add(1) => {
+                prev.last = prev.last.max(r.last);
+            }
+            _ => coalesced.push(r),
+        }
+    }
+
+    match coalesced.len() {
+        0 => Resolution::Unsatisfiable,
+        1 => Resolution::Single(coalesced[0]),
+        _ => Resolution::Multiple(coalesced),
+    }
+}
+
+/// The `Content-Range` header of a `416 Range Not Satisfiable` response, for
+/// a representation of the given complete length.
+pub fn unsatisfied(complete_length: u64) -> ContentRange {
+    ContentRange(ContentRangeSpec::Bytes {
+        range: None,
+        instance_length: Some(complete_length),
+    })
+}
+
+/// The layout of a `multipart/byteranges` response body.
+///
+/// The body consists of, for each range, a part header (delimiter,
+/// `Content-Type` and `Content-Range`) followed by the bytes of the range,
+/// and finally a closing delimiter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MultipartByteranges {
+    ranges: Vec<ByteRange>,
+    complete_length: u64,
+    content_type: Option<ContentType>,
+    boundary: String,
+}
+
+impl MultipartByteranges {
+    /// Create a new multipart body of the ranges of a representation of the
+    /// given complete length and (optional) content type, with a generated
+    /// boundary.
+    ///
+    /// The boundary is randomly generated, but not cryptographically so. If
+    /// the representation may contain arbitrary client-controlled content,
+    /// consider supplying a boundary via `with_boundary` which is known not
+    /// to occur within it.
+    pub fn new(
+        ranges: Vec<ByteRange>,
+        complete_length: u64,
+        content_type: Option<ContentType>,
+    ) -> MultipartByteranges {
+        MultipartByteranges {
+            ranges,
+            complete_length,
+            content_type,
+            boundary: generate_boundary(),
+        }
+    }
+
+    /// Replace the boundary.
+    ///
+    /// # Panics
+    ///
+    /// If the boundary is empty, longer than 70 characters, or contains
+    /// characters other than ASCII alphanumerics, `'`, `+`, `_`, `-` and
+    /// `.`, which are valid both as boundary and unquoted parameter value.
+    pub fn with_boundary<S: Into<String>>(mut self, boundary: S) -> MultipartByteranges {
+        let boundary = boundary.into();
+        assert!(
+            !boundary.is_empty()
+                && boundary.len() <= 70
+                && boundary
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b"'+_-.".contains(&b)),
+            "invalid multipart boundary {:?}",
+            boundary
+        );
+        self.boundary = boundary;
+        self
+    }
+
+    /// The boundary delimiting the parts.
+    pub fn boundary(&self) -> &str {
+        &self.boundary
+    }
+
+    /// The ranges of the parts.
+    pub fn ranges(&self) -> &[ByteRange] {
+        &self.ranges
+    }
+
+    /// The `Content-Type` header of the response:
+    /// `multipart/byteranges; boundary=...`.
+    pub fn content_type(&self) -> ContentType {
+        let mime = format!("multipart/byteranges; boundary={}", self.boundary)
+            .parse()
+            .expect("valid multipart mime");
+        ContentType(mime)
+    }
+
+    /// The part header preceding the bytes of the part at `index`, including
+    /// the delimiter and trailing blank line.
+    ///
+    /// # Panics
+    ///
+    /// If `index` is out of bounds.
+    pub fn part_header(&self, index: usize) -> String {
+        let range = self.ranges[index];
+        let mut header = String::new();
+        if index > 0 {
+            header.push_str("\r\n");
+        }
+        header.push_str("--");
+        header.push_str(&self.boundary);
+        header.push_str("\r\n");
+        if let Some(ref ct) = self.content_type {
+            header.push_str(&format!("Content-Type: {}\r\n", ct));
+        }
+        header.push_str(&format!(
+            "Content-Range: {}\r\n\r\n",
+            range.content_range(self.complete_length).0
+        ));
+        header
+    }
+
+    /// The closing delimiter, following the bytes of the last part.
+    pub fn closing(&self) -> String {
+        format!("\r\n--{}--\r\n", self.boundary)
+    }
+
+    /// The total length of the body, for the `Content-Length` header.
+    pub fn body_length(&self) -> u64 {
+        let parts: u64 = (0..self.ranges.len())
+            .map(|i| self.part_header(i).len() as u64 + self.ranges[i].len())
+            .sum();
+        parts + self.closing().len() as u64
+    }
+
+    /// Assemble the complete body from an in-memory representation.
+    ///
+    /// # Panics
+    ///
+    /// If the length of `representation` is not the complete length.
+    pub fn body(&self, representation: &[u8]) -> Vec<u8> {
+        assert_eq!(representation.len() as u64, self.complete_length);
+        let mut body = Vec::with_capacity(self.body_length() as usize);
+        for (i, range) in self.ranges.iter().enumerate() {
+            body.extend_from_slice(self.part_header(i).as_bytes());
+            body.extend_from_slice(&representation[range.first as usize..=range.last as usize]);
+        }
+        body.extend_from_slice(self.closing().as_bytes());
+        body
+    }
+}
+
+fn generate_boundary() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos() as u64)
+        .unwrap_or(0);
+    let state = RandomState::new();
+    let mut first = state.build_hasher();
+    first.write_u64(nanos);
+    let mut second = state.build_hasher();
+    second.write_u64(first.finish());
+    format!("{:016x}{:016x}", first.finish(), second.finish())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::ByteRangeSpec;
+
+    fn bytes(specs: Vec<ByteRangeSpec>) -> Range {
+        Range::Bytes(specs)
+    }
+
+    #[test]
+    fn test_resolve() {
+        use self::ByteRangeSpec::*;
+
+        assert_eq!(
+            resolve(&Range::bytes(0, 499), 1000),
+            Resolution::Single(ByteRange::new(0, 499))
+        );
+        assert_eq!(
+            resolve(&bytes(vec![AllFrom(900), Last(2000)]), 1000),
+            Resolution::Single(ByteRange::new(0, 999))
+        );
+        assert_eq!(
+            resolve(
+                &bytes(vec![FromTo(500, 599), FromTo(0, 99), FromTo(100, 149)]),
+                1000
+            ),
+            Resolution::Multiple(vec![ByteRange::new(0, 149), ByteRange::new(500, 599)])
+        );
+        assert_eq!(
+            resolve(&bytes(vec![FromTo(0, 10), FromTo(5, 20), Last(1)]), 1000),
+            Resolution::Multiple(vec![ByteRange::new(0, 20), ByteRange::new(999, 999)])
+        );
+        assert_eq!(
+            resolve(&bytes(vec![FromTo(1000, 1010), Last(0)]), 1000),
+            Resolution::Unsatisfiable
+        );
+        assert_eq!(resolve(&Range::bytes(0, 0), 0), Resolution::Unsatisfiable);
+        assert_eq!(
+            resolve(&Range::Unregistered("pages".into(), "1-2".into()), 1000),
+            Resolution::Full
+        );
+        assert_eq!(unsatisfied(1000).to_string(), "bytes */1000");
+        assert_eq!(
+            ByteRange::new(0, 499).content_range(1000).to_string(),
+            "bytes 0-499/1000"
+        );
+    }
+
+    #[test]
+    fn test_multipart() {
+        let ranges = vec![ByteRange::new(0, 3), ByteRange::new(8, 9)];
+        let multipart = MultipartByteranges::new(ranges, 10, Some(ContentType::plaintext()))
+            .with_boundary("BOUNDARY");
+        let body = multipart.body(b"0123456789");
+        assert_eq!(
+            String::from_utf8(body).unwrap(),
+            "--BOUNDARY\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Range: bytes 0-3/10\r\n\
+             \r\n\
+             0123\r\n\
+             --BOUNDARY\r\n\
+             Content-Type: text/plain; charset=utf-8\r\n\
+             Content-Range: bytes 8-9/10\r\n\
+             \r\n\
+             89\r\n\
+             --BOUNDARY--\r\n"
+        );
+        assert_eq!(
+            multipart.body_length(),
+            multipart.body(b"0123456789").len() as u64
+        );
+
+        let untyped =
+            MultipartByteranges::new(multipart.ranges().to_vec(), 10, None).with_boundary("B");
+        assert_eq!(
+            untyped.part_header(1),
+            "\r\n--B\r\nContent-Range: bytes 8-9/10\r\n\r\n"
+        );
+    }
+
+    #[test]
+    fn test_generated_boundary() {
+        let a = MultipartByteranges::new(vec![ByteRange::new(0, 0)], 1, None);
+        let b = MultipartByteranges::new(vec![ByteRange::new(0, 0)], 1, None);
+        assert_eq!(a.boundary().len(), 32);
+        assert_ne!(a.boundary(), b.boundary());
+        let b = b.clone().with_boundary(a.boundary());
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_byte_range() {
+        let range = ByteRange::new(5, 9);
+        assert_eq!((range.first(), range.last(), range.len()), (5, 9, 5));
+        assert_eq!(ByteRange::new(7, 7).len(), 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_byte_range() {
+        ByteRange::new(9, 5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_invalid_boundary() {
+        MultipartByteranges::new(vec![], 0, None).with_boundary("has space");
+    }
+}
@@ -0,0 +1,272 @@
+//! Rate limit evaluation, per [RateLimit header fields for HTTP].
+//!
+//! Servers render their quota policies and the current state of each window
+//! with [`RateLimitHeaders::render`](struct.RateLimitHeaders.html#method.render),
+//! producing the `RateLimit-Policy`, `RateLimit` and, once a quota is
+//! exhausted, `Retry-After` headers. Clients compute the earliest time at
+//! which a further request may be sent from those same headers, with
+//! [`retry_at`](fn.retry_at.html).
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::{Duration, UNIX_EPOCH};
+//! use hyperx::header::QuotaPolicy;
+//! use hyperx::ratelimit::{retry_at, RateLimitHeaders, WindowState};
+//!
+//! let policy = QuotaPolicy::new("default", 100).window(60);
+//! let state = WindowState::new(100, Duration::from_millis(12_500));
+//!
+//! let mut response = http::HeaderMap::new();
+//! RateLimitHeaders::render(&[(policy, state)]).encode(&mut response);
+//! assert_eq!(response["ratelimit-policy"], "\"default\";q=100;w=60");
+//! assert_eq!(response["ratelimit"], "\"default\";r=0;t=13");
+//! assert_eq!(response["retry-after"], "13");
+//!
+//! let now = UNIX_EPOCH + Duration::from_secs(1_000_000);
+//! assert_eq!(retry_at(&response, now), now + Duration::from_secs(13));
+//! ```
+//!
+//! [RateLimit header fields for HTTP]: https://datatracker.ietf.org/doc/html/draft-ietf-httpapi-ratelimit-headers
+
+use std::time::{Duration, SystemTime};
+
+use http::HeaderMap;
+
+use header::{QuotaPolicy, RateLimit, RateLimitItem, RateLimitPolicy, RetryAfter, TypedHeaders};
+
+/// The current state of the window of a quota policy, as tracked by a
+/// server.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct WindowState {
+    /// The quota units consumed within the current window.
+    pub used: u64,
+    /// The time until the window resets.
+    pub reset: Duration,
+}
+
+impl WindowState {
+    /// Create a new window state.
+    pub fn new(used: u64, reset: Duration) -> WindowState {
+        WindowState { used, reset }
+    }
+}
+
+/// The rate limit headers of a response.
+#[derive(Clone, Debug, PartialEq)]
+pub struct RateLimitHeaders {
+    /// The `RateLimit-Policy` header.
+    pub policy: RateLimitPolicy,
+    /// The `RateLimit` header.
+    pub limit: RateLimit,
+    /// The `Retry-After` header, if any quota is exhausted.
+    pub retry_after: Option<RetryAfter>,
+}
+
+impl RateLimitHeaders {
+    /// Render quota policies and the current state of their windows.
+    ///
+    /// The remaining quota units saturate at zero, and the reset time is
+    /// rounded up to whole seconds, so that a client never retries early.
+    /// Once any quota is exhausted, `Retry-After` is set to the latest reset
+    /// of the exhausted quotas, for use with a `429 Too Many Requests`
+    /// response.
+    pub fn render(quotas: &[(QuotaPolicy, WindowState)]) -> RateLimitHeaders {
+        let mut retry_after = None;
+        let items = quotas
+            .iter()
+            .map(|&(ref policy, state)| {
+                let remaining = policy.quota.saturating_sub(state.used);
+                let reset = ceil_secs(state.reset);
+                if remaining == 0 {
+                    retry_after = Some(retry_after.map_or(reset, |r: u64| r.max(reset)));
+                }
+                RateLimitItem::new(policy.name.clone(), remaining).reset(reset)
+            })
+            .collect();
+        RateLimitHeaders {
+            policy: RateLimitPolicy(quotas.iter().map(|q| q.0.clone()).collect()),
+            limit: RateLimit(items),
+            retry_after: retry_after.map(|s| RetryAfter::Delay(Duration::from_secs(s))),
+        }
+    }
+
+    /// Returns true if any quota is exhausted.
+    pub fn is_limited(&self) -> bool {
+        self.retry_after.is_some()
+    }
+
+    /// Encode the headers into a response `http::HeaderMap`, replacing any
+    /// existing values. Headers without any items are not encoded.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if !self.policy.is_empty() {
+            headers.encode(&self.policy);
+        }
+        if !self.limit.is_empty() {
+            headers.encode(&self.limit);
+        }
+        if let Some(ref h) = self.retry_after {
+            headers.encode(h);
+        }
+    }
+}
+
+fn ceil_secs(d: Duration) -> u64 {
+    d.as_secs() + u64::from(d.subsec_nanos() > 0)
+}
+
+/// Returns the earliest time at which a further request may be sent, given
+/// the headers of a response received at `now`.
+///
+/// This is the latest of `now`, the `Retry-After` header, and the reset of
+/// each exhausted quota of the `RateLimit` header. An exhausted quota
+/// without a reset time `t` falls back to the window `w` of the same policy
+/// in `RateLimit-Policy`, if any. Invalid headers are ignored.
+pub fn retry_at(headers: &HeaderMap, now: SystemTime) -> SystemTime {
+    let mut earliest = now;
+    match headers.try_decode::<RetryAfter>() {
+        Some(Ok(RetryAfter::Delay(delay))) => earliest = earliest.max(now + delay),
+        Some(Ok(RetryAfter::DateTime(date))) => earliest = earliest.max(date.into()),
+        _ => {}
+    }
+    let limit = match headers.try_decode::<RateLimit>().and_then(Result::ok) {
+        Some(limit) => limit,
+        None => return earliest,
+    };
+    let policy = headers.try_decode::<RateLimitPolicy>().and_then(Result::ok);
+    for item in limit.iter().filter(|item| item.remaining == 0) {
+        let reset = item.reset.or_else(|| {
+            policy
+                .as_ref()
+                .and_then(|p| p.iter().find(|q| q.name == item.policy))
+                .and_then(|q| q.window)
+        });
+        if let Some(reset) = reset {
+            earliest = earliest.max(now + Duration::from_secs(reset));
+        }
+    }
+    earliest
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::UNIX_EPOCH;
+
+    fn response(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn now() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(1_000_000)
+    }
+
+    fn secs(s: u64) -> SystemTime {
+        now() + Duration::from_secs(s)
+    }
+
+    #[test]
+    fn test_render() {
+        let quotas = [
+            (
+                QuotaPolicy::new("burst", 10).window(1),
+                WindowState::new(3, Duration::from_millis(400)),
+            ),
+            (
+                QuotaPolicy::new("daily", 1000)
+                    .unit("content-bytes")
+                    .window(86400),
+                WindowState::new(1200, Duration::from_secs(3600)),
+            ),
+        ];
+        let rendered = RateLimitHeaders::render(&quotas);
+        assert!(rendered.is_limited());
+        assert_eq!(
+            rendered.limit.0[..],
+            [
+                RateLimitItem::new("burst", 7).reset(1),
+                RateLimitItem::new("daily", 0).reset(3600),
+            ]
+        );
+        assert_eq!(
+            rendered.retry_after,
+            Some(RetryAfter::Delay(Duration::from_secs(3600)))
+        );
+
+        let mut headers = HeaderMap::new();
+        rendered.encode(&mut headers);
+        assert_eq!(
+            headers["ratelimit-policy"],
+            "\"burst\";q=10;w=1, \"daily\";q=1000;qu=\"content-bytes\";w=86400"
+        );
+        assert_eq!(
+            headers["ratelimit"],
+            "\"burst\";r=7;t=1, \"daily\";r=0;t=3600"
+        );
+        assert_eq!(retry_at(&headers, now()), secs(3600));
+    }
+
+    #[test]
+    fn test_render_not_limited() {
+        let rendered = RateLimitHeaders::render(&[(
+            QuotaPolicy::new("default", 100),
+            WindowState::new(0, Duration::from_secs(60)),
+        )]);
+        assert!(!rendered.is_limited());
+        let mut headers = HeaderMap::new();
+        rendered.encode(&mut headers);
+        assert!(!headers.contains_key("retry-after"));
+        assert_eq!(retry_at(&headers, now()), now());
+
+        let mut headers = HeaderMap::new();
+        RateLimitHeaders::render(&[]).encode(&mut headers);
+        assert!(headers.is_empty());
+    }
+
+    #[test]
+    fn test_retry_at() {
+        assert_eq!(retry_at(&HeaderMap::new(), now()), now());
+
+        let headers = response(&[("retry-after", "30"), ("ratelimit", "\"a\";r=0;t=10")]);
+        assert_eq!(retry_at(&headers, now()), secs(30));
+
+        let headers = response(&[("retry-after", "5"), ("ratelimit", "\"a\";r=0;t=10")]);
+        assert_eq!(retry_at(&headers, now()), secs(10));
+
+        let date = "Mon, 12 Jan 1970 13:47:00 GMT";
+        let headers = response(&[("retry-after", date)]);
+        assert_eq!(
+            retry_at(&headers, now()),
+            UNIX_EPOCH + Duration::from_secs(1_000_020)
+        );
+
+        // A past date does not precede now
+        let headers = response(&[("retry-after", "Thu, 01 Jan 1970 00:00:00 GMT")]);
+        assert_eq!(retry_at(&headers, now()), now());
+
+        let headers = response(&[("ratelimit", "\"a\";r=1;t=10")]);
+        assert_eq!(retry_at(&headers, now()), now());
+    }
+
+    #[test]
+    fn test_retry_at_policy_window() {
+        let headers = response(&[
+            ("ratelimit-policy", "\"a\";q=10;w=60, \"b\";q=5"),
+            ("ratelimit", "\"a\";r=0, \"b\";r=0"),
+        ]);
+        assert_eq!(retry_at(&headers, now()), secs(60));
+
+        let headers = response(&[("ratelimit", "\"a\";r=0")]);
+        assert_eq!(retry_at(&headers, now()), now());
+    }
+
+    #[test]
+    fn test_retry_at_invalid() {
+        let headers = response(&[("retry-after", "soon"), ("ratelimit", "r=0;t=10")]);
+        assert_eq!(retry_at(&headers, now()), now());
+    }
+}
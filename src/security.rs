@@ -0,0 +1,334 @@
+//! Preset sets of security response headers.
+//!
+//! [`SecurityHeaders`](struct.SecurityHeaders.html) holds a coherent set of
+//! typed security headers, starting from one of the profiles
+//! [`strict`](struct.SecurityHeaders.html#method.strict),
+//! [`api`](struct.SecurityHeaders.html#method.api) or
+//! [`relaxed`](struct.SecurityHeaders.html#method.relaxed), with any header
+//! overridden or removed, and encodes them into a `http::HeaderMap` in one
+//! call.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::header::{ReferrerPolicy, XFrameOptions};
+//! use hyperx::security::SecurityHeaders;
+//!
+//! let security = SecurityHeaders::strict()
+//!     .frame_options(Some(XFrameOptions::SameOrigin))
+//!     .referrer_policy(Some(ReferrerPolicy::StrictOriginWhenCrossOrigin))
+//!     .cross_origin_embedder_policy(None);
+//!
+//! let mut headers = http::HeaderMap::new();
+//! security.encode(&mut headers);
+//! assert_eq!(headers["x-frame-options"], "SAMEORIGIN");
+//! assert!(headers["content-security-policy"]
+//!     .to_str()
+//!     .unwrap()
+//!     .contains("frame-ancestors 'self'"));
+//! assert!(!headers.contains_key("cross-origin-embedder-policy"));
+//! ```
+
+use http::HeaderMap;
+
+use header::{
+    ContentSecurityPolicy, CrossOriginEmbedderPolicy, CrossOriginOpenerPolicy,
+    CrossOriginResourcePolicy, Header, PermissionsPolicy, ReferrerPolicy, StrictTransportSecurity,
+    TypedHeaders, XContentTypeOptions, XFrameOptions,
+};
+
+const TWO_YEARS: u64 = 2 * 365 * 24 * 60 * 60;
+const ONE_YEAR: u64 = 365 * 24 * 60 * 60;
+
+/// A set of security response headers.
+///
+/// Each header is optional; `None` omits it when encoding.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SecurityHeaders {
+    hsts: Option<StrictTransportSecurity>,
+    content_type_options: Option<XContentTypeOptions>,
+    frame_options: Option<XFrameOptions>,
+    content_security_policy: Option<ContentSecurityPolicy>,
+    referrer_policy: Option<ReferrerPolicy>,
+    opener_policy: Option<CrossOriginOpenerPolicy>,
+    embedder_policy: Option<CrossOriginEmbedderPolicy>,
+    resource_policy: Option<CrossOriginResourcePolicy>,
+    permissions_policy: Option<PermissionsPolicy>,
+}
+
+impl SecurityHeaders {
+    /// An empty set, with no headers.
+    pub fn none() -> SecurityHeaders {
+        SecurityHeaders::default()
+    }
+
+    /// A strict profile for HTML applications which load only same-origin
+    /// content and are never framed:
+    ///
+    /// * `Strict-Transport-Security: max-age=63072000; includeSubDomains`
+    /// * `X-Content-Type-Options: nosniff`
+    /// * `X-Frame-Options: DENY`
+    /// * `Content-Security-Policy: default-src 'self'; base-uri 'self';
+    ///   form-action 'self'; object-src 'none'; frame-ancestors 'none'`
+    /// * `Referrer-Policy: no-referrer`
+    /// * `Cross-Origin-Opener-Policy: same-origin`
+    /// * `Cross-Origin-Embedder-Policy: require-corp`
+    /// * `Cross-Origin-Resource-Policy: same-origin`
+    /// * `Permissions-Policy` disabling camera, microphone, geolocation,
+    ///   payment and usb.
+    pub fn strict() -> SecurityHeaders {
+        SecurityHeaders {
+            hsts: Some(StrictTransportSecurity::including_subdomains(TWO_YEARS)),
+            content_type_options: Some(XContentTypeOptions::NoSniff),
+            frame_options: None,
+            content_security_policy: Some(
+                ContentSecurityPolicy::new()
+                    .directive("default-src", vec!["'self'"])
+                    .directive("base-uri", vec!["'self'"])
+                    .directive("form-action", vec!["'self'"])
+                    .directive("object-src", vec!["'none'"]),
+            ),
+            referrer_policy: Some(ReferrerPolicy::NoReferrer),
+            opener_policy: Some(CrossOriginOpenerPolicy::SameOrigin),
+            embedder_policy: Some(CrossOriginEmbedderPolicy::RequireCorp),
+            resource_policy: Some(CrossOriginResourcePolicy::SameOrigin),
+            permissions_policy: Some(
+                ["camera", "microphone", "geolocation", "payment", "usb"]
+                    .iter()
+                    .fold(PermissionsPolicy::new(), |p, f| p.deny(*f)),
+            ),
+        }
+        .frame_options(Some(XFrameOptions::Deny))
+    }
+
+    /// A profile for APIs returning data rather than documents:
+    ///
+    /// * `Strict-Transport-Security: max-age=63072000; includeSubDomains`
+    /// * `X-Content-Type-Options: nosniff`
+    /// * `X-Frame-Options: DENY`
+    /// * `Content-Security-Policy: default-src 'none'; frame-ancestors 'none'`
+    /// * `Referrer-Policy: no-referrer`
+    /// * `Cross-Origin-Resource-Policy: same-site`
+    pub fn api() -> SecurityHeaders {
+        SecurityHeaders {
+            hsts: Some(StrictTransportSecurity::including_subdomains(TWO_YEARS)),
+            content_type_options: Some(XContentTypeOptions::NoSniff),
+            content_security_policy: Some(
+                ContentSecurityPolicy::new().directive("default-src", vec!["'none'"]),
+            ),
+            referrer_policy: Some(ReferrerPolicy::NoReferrer),
+            resource_policy: Some(CrossOriginResourcePolicy::SameSite),
+            ..SecurityHeaders::default()
+        }
+        .frame_options(Some(XFrameOptions::Deny))
+    }
+
+    /// A relaxed profile for applications which embed third-party content,
+    /// or are framed by the same origin:
+    ///
+    /// * `Strict-Transport-Security: max-age=31536000`
+    /// * `X-Content-Type-Options: nosniff`
+    /// * `X-Frame-Options: SAMEORIGIN`
+    /// * `Content-Security-Policy: frame-ancestors 'self'`
+    /// * `Referrer-Policy: strict-origin-when-cross-origin`
+    /// * `Cross-Origin-Opener-Policy: same-origin-allow-popups`
+    pub fn relaxed() -> SecurityHeaders {
+        SecurityHeaders {
+            hsts: Some(StrictTransportSecurity::excluding_subdomains(ONE_YEAR)),
+            content_type_options: Some(XContentTypeOptions::NoSniff),
+            content_security_policy: Some(ContentSecurityPolicy::new()),
+            referrer_policy: Some(ReferrerPolicy::StrictOriginWhenCrossOrigin),
+            opener_policy: Some(CrossOriginOpenerPolicy::SameOriginAllowPopups),
+            ..SecurityHeaders::default()
+        }
+        .frame_options(Some(XFrameOptions::SameOrigin))
+    }
+
+    /// Override the `Strict-Transport-Security` header.
+    pub fn hsts(mut self, value: Option<StrictTransportSecurity>) -> SecurityHeaders {
+        self.hsts = value;
+        self
+    }
+
+    /// Override the `X-Content-Type-Options` header.
+    pub fn content_type_options(mut self, value: Option<XContentTypeOptions>) -> SecurityHeaders {
+        self.content_type_options = value;
+        self
+    }
+
+    /// Override the `X-Frame-Options` header.
+    ///
+    /// For coherence, the `frame-ancestors` directive of any
+    /// `Content-Security-Policy` is updated to match: `'none'` for `DENY`,
+    /// `'self'` for `SAMEORIGIN`, or removed for `None`.
+    pub fn frame_options(mut self, value: Option<XFrameOptions>) -> SecurityHeaders {
+        self.frame_options = value;
+        if let Some(csp) = self.content_security_policy.take() {
+            self.content_security_policy = Some(match value {
+                Some(XFrameOptions::Deny) => csp.directive("frame-ancestors", vec!["'none'"]),
+                Some(XFrameOptions::SameOrigin) => csp.directive("frame-ancestors", vec!["'self'"]),
+                None => csp.remove("frame-ancestors"),
+            });
+        }
+        self
+    }
+
+    /// Override the `Content-Security-Policy` header.
+    ///
+    /// The policy is used as given; its `frame-ancestors` directive is not
+    /// synchronized with `X-Frame-Options`.
+    pub fn content_security_policy(
+        mut self,
+        value: Option<ContentSecurityPolicy>,
+    ) -> SecurityHeaders {
+        self.content_security_policy = value;
+        self
+    }
+
+    /// Override the `Referrer-Policy` header.
+    pub fn referrer_policy(mut self, value: Option<ReferrerPolicy>) -> SecurityHeaders {
+        self.referrer_policy = value;
+        self
+    }
+
+    /// Override the `Cross-Origin-Opener-Policy` header.
+    pub fn cross_origin_opener_policy(
+        mut self,
+        value: Option<CrossOriginOpenerPolicy>,
+    ) -> SecurityHeaders {
+        self.opener_policy = value;
+        self
+    }
+
+    /// Override the `Cross-Origin-Embedder-Policy` header.
+    pub fn cross_origin_embedder_policy(
+        mut self,
+        value: Option<CrossOriginEmbedderPolicy>,
+    ) -> SecurityHeaders {
+        self.embedder_policy = value;
+        self
+    }
+
+    /// Override the `Cross-Origin-Resource-Policy` header.
+    pub fn cross_origin_resource_policy(
+        mut self,
+        value: Option<CrossOriginResourcePolicy>,
+    ) -> SecurityHeaders {
+        self.resource_policy = value;
+        self
+    }
+
+    /// Override the `Permissions-Policy` header.
+    pub fn permissions_policy(mut self, value: Option<PermissionsPolicy>) -> SecurityHeaders {
+        self.permissions_policy = value;
+        self
+    }
+
+    /// Encode the headers into a response `http::HeaderMap`, replacing any
+    /// existing values of the same headers. A `Content-Security-Policy`
+    /// without directives is omitted.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if let Some(ref h) = self.hsts {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.content_type_options {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.frame_options {
+            headers.encode(h);
+        }
+        if let Some(h) = self
+            .content_security_policy
+            .as_ref()
+            .filter(|h| !h.is_empty())
+        {
+            headers.encode(h);
+        }
+        if let Some(ref h) = self.referrer_policy {
+            headers.encode(h);
+        }
+        insert(headers, self.opener_policy.as_ref());
+        insert(headers, self.embedder_policy.as_ref());
+        insert(headers, self.resource_policy.as_ref());
+        insert(headers, self.permissions_policy.as_ref());
+    }
+}
+
+fn insert<H: Header>(headers: &mut HeaderMap, value: Option<&H>) {
+    if let Some(h) = value {
+        headers.encode(h);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn encoded(security: &SecurityHeaders) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        security.encode(&mut headers);
+        headers
+    }
+
+    #[test]
+    fn test_strict() {
+        let h = encoded(&SecurityHeaders::strict());
+        assert_eq!(h.len(), 9);
+        assert_eq!(
+            h["strict-transport-security"],
+            "max-age=63072000; includeSubdomains"
+        );
+        assert_eq!(h["x-content-type-options"], "nosniff");
+        assert_eq!(h["x-frame-options"], "DENY");
+        assert_eq!(
+            h["content-security-policy"],
+            "default-src 'self'; base-uri 'self'; form-action 'self'; \
+             object-src 'none'; frame-ancestors 'none'"
+        );
+        assert_eq!(h["referrer-policy"], "no-referrer");
+        assert_eq!(h["cross-origin-opener-policy"], "same-origin");
+        assert_eq!(h["cross-origin-embedder-policy"], "require-corp");
+        assert_eq!(h["cross-origin-resource-policy"], "same-origin");
+        assert_eq!(
+            h["permissions-policy"],
+            "camera=(), microphone=(), geolocation=(), payment=(), usb=()"
+        );
+    }
+
+    #[test]
+    fn test_api() {
+        let h = encoded(&SecurityHeaders::api());
+        assert_eq!(h.len(), 6);
+        assert_eq!(
+            h["content-security-policy"],
+            "default-src 'none'; frame-ancestors 'none'"
+        );
+        assert_eq!(h["cross-origin-resource-policy"], "same-site");
+    }
+
+    #[test]
+    fn test_relaxed() {
+        let h = encoded(&SecurityHeaders::relaxed());
+        assert_eq!(h.len(), 6);
+        assert_eq!(h["strict-transport-security"], "max-age=31536000");
+        assert_eq!(h["x-frame-options"], "SAMEORIGIN");
+        assert_eq!(h["content-security-policy"], "frame-ancestors 'self'");
+        assert_eq!(h["referrer-policy"], "strict-origin-when-cross-origin");
+    }
+
+    #[test]
+    fn test_overrides() {
+        let security = SecurityHeaders::relaxed()
+            .frame_options(None)
+            .hsts(None)
+            .permissions_policy(Some(PermissionsPolicy::new().deny("usb")));
+        let h = encoded(&security);
+        assert!(!h.contains_key("x-frame-options"));
+        assert!(!h.contains_key("strict-transport-security"));
+        // Empty policy after removing frame-ancestors, so not encoded
+        assert!(!h.contains_key("content-security-policy"));
+        assert_eq!(h["permissions-policy"], "usb=()");
+
+        assert!(encoded(&SecurityHeaders::none()).is_empty());
+    }
+}
@@ -0,0 +1,238 @@
+//! Recording of `Server-Timing` metrics during request handling, per
+//! [Server Timing].
+//!
+//! A [`TimingRecorder`](struct.TimingRecorder.html) accumulates metrics
+//! while a request is handled, either by timing a
+//! [`Span`](struct.Span.html) or by recording a measured duration, and
+//! renders them into the typed `ServerTiming` header. Browsers hide the
+//! metrics from cross-origin documents, unless allowed by the
+//! `Timing-Allow-Origin` header which the recorder encodes alongside.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use std::time::Duration;
+//! use hyperx::header::Origin;
+//! use hyperx::server_timing::TimingRecorder;
+//!
+//! let mut timings = TimingRecorder::new()
+//!     .allow_origin(Origin::new("https", "app.example", None));
+//!
+//! let span = timings.start("db");
+//! // ... query the database
+//! timings.finish(span);
+//! timings.describe("db", "Primary DB");
+//! timings.record("render", Duration::from_micros(4_500));
+//! timings.describe("cache", "miss");
+//!
+//! let mut response = http::HeaderMap::new();
+//! timings.encode(&mut response);
+//! assert!(response["server-timing"]
+//!     .to_str()
+//!     .unwrap()
+//!     .ends_with("render;dur=4.5, cache;desc=miss"));
+//! assert_eq!(response["timing-allow-origin"], "https://app.example");
+//! ```
+//!
+//! [Server Timing]: https://w3c.github.io/server-timing/
+
+use std::time::{Duration, Instant};
+
+use http::HeaderMap;
+
+use header::{Origin, ServerTiming, TimingAllowOrigin, TimingMetric, TypedHeaders};
+
+/// A metric being timed, started by
+/// [`TimingRecorder::start`](struct.TimingRecorder.html#method.start).
+#[derive(Debug)]
+#[must_use = "a span is recorded only once finished"]
+pub struct Span {
+    name: String,
+    start: Instant,
+}
+
+impl Span {
+    /// The name of the metric.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// The time elapsed since the span started.
+    pub fn elapsed(&self) -> Duration {
+        self.start.elapsed()
+    }
+}
+
+/// A recorder of `Server-Timing` metrics, with its `Timing-Allow-Origin`
+/// configured via builder methods.
+///
+/// Metrics are kept in the order first recorded. Recording a metric of the
+/// same name again adds to its duration, e.g. for the total time of
+/// repeated database queries.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TimingRecorder {
+    metrics: Vec<TimingMetric>,
+    allow_origin: Option<TimingAllowOrigin>,
+}
+
+impl TimingRecorder {
+    /// Create a new recorder, without metrics, whose metrics are hidden
+    /// from cross-origin documents.
+    pub fn new() -> TimingRecorder {
+        TimingRecorder::default()
+    }
+
+    /// Allow an origin to see the metrics.
+    pub fn allow_origin(mut self, origin: Origin) -> TimingRecorder {
+        match self.allow_origin {
+            Some(TimingAllowOrigin::Any) => {}
+            Some(TimingAllowOrigin::Items(ref mut origins)) => {
+                if !origins.contains(&origin) {
+                    origins.push(origin);
+                }
+            }
+            None => self.allow_origin = Some(TimingAllowOrigin::Items(smallvec![origin])),
+        }
+        self
+    }
+
+    /// Allow any origin to see the metrics.
+    pub fn allow_any_origin(mut self) -> TimingRecorder {
+        self.allow_origin = Some(TimingAllowOrigin::Any);
+        self
+    }
+
+    /// Start timing a metric.
+    pub fn start<S: Into<String>>(&self, name: S) -> Span {
+        Span {
+            name: name.into(),
+            start: Instant::now(),
+        }
+    }
+
+    /// Finish timing a metric, recording and returning its duration.
+    pub fn finish(&mut self, span: Span) -> Duration {
+        let duration = span.elapsed();
+        self.record(span.name, duration);
+        duration
+    }
+
+    /// Record the duration of a metric.
+    pub fn record<S: Into<String>>(&mut self, name: S, duration: Duration) {
+        let metric = self.metric(name.into());
+        metric.duration = Some(metric.duration.unwrap_or_default() + duration);
+    }
+
+    /// Set the description of a metric, recording the metric without a
+    /// duration if it is not yet recorded.
+    pub fn describe<S, D>(&mut self, name: S, description: D)
+    where
+        S: Into<String>,
+        D: Into<String>,
+    {
+        self.metric(name.into()).description = Some(description.into());
+    }
+
+    /// The recorded metrics, in order.
+    pub fn metrics(&self) -> &[TimingMetric] {
+        &self.metrics
+    }
+
+    /// The `Server-Timing` header of the recorded metrics.
+    pub fn server_timing(&self) -> ServerTiming {
+        ServerTiming(self.metrics.iter().cloned().collect())
+    }
+
+    /// The configured `Timing-Allow-Origin` header, if any.
+    pub fn timing_allow_origin(&self) -> Option<&TimingAllowOrigin> {
+        self.allow_origin.as_ref()
+    }
+
+    /// Encode the headers into a response `http::HeaderMap`. `Server-Timing`
+    /// is appended to any existing value, unless no metrics are recorded,
+    /// and `Timing-Allow-Origin`, if configured, replaces any existing
+    /// value.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if !self.metrics.is_empty() {
+            headers.encode_append(&self.server_timing());
+        }
+        if let Some(ref h) = self.allow_origin {
+            headers.encode(h);
+        }
+    }
+
+    fn metric(&mut self, name: String) -> &mut TimingMetric {
+        let i = match self.metrics.iter().position(|m| m.name == name) {
+            Some(i) => i,
+            None => {
+                self.metrics.push(TimingMetric::new(name));
+                self.metrics.len() - 1
+            }
+        };
+        &mut self.metrics[i]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record() {
+        let mut timings = TimingRecorder::new();
+        timings.record("db", Duration::from_millis(3));
+        timings.describe("cache", "Cache Read");
+        timings.record("db", Duration::from_micros(1_500));
+        timings.describe("db", "queries");
+        assert_eq!(
+            timings.metrics(),
+            &[
+                TimingMetric::new("db")
+                    .duration(Duration::from_micros(4_500))
+                    .description("queries"),
+                TimingMetric::new("cache").description("Cache Read"),
+            ]
+        );
+        assert_eq!(
+            timings.server_timing().to_string(),
+            "db;dur=4.5;desc=queries, cache;desc=\"Cache Read\""
+        );
+    }
+
+    #[test]
+    fn test_span() {
+        let mut timings = TimingRecorder::new();
+        let span = timings.start("app");
+        assert_eq!(span.name(), "app");
+        let duration = timings.finish(span);
+        assert_eq!(timings.metrics()[0].duration, Some(duration));
+    }
+
+    #[test]
+    fn test_encode() {
+        let mut headers = HeaderMap::new();
+        TimingRecorder::new().encode(&mut headers);
+        assert!(headers.is_empty());
+
+        let mut timings = TimingRecorder::new()
+            .allow_origin(Origin::new("https", "a.example", None))
+            .allow_origin(Origin::new("https", "b.example", None))
+            .allow_origin(Origin::new("https", "a.example", None));
+        timings.record("total", Duration::from_millis(12));
+
+        let mut headers = HeaderMap::new();
+        headers.insert("server-timing", "edge;dur=1".parse().unwrap());
+        timings.encode(&mut headers);
+        let values: Vec<_> = headers.get_all("server-timing").iter().collect();
+        assert_eq!(values, vec!["edge;dur=1", "total;dur=12"]);
+        assert_eq!(
+            headers["timing-allow-origin"],
+            "https://a.example, https://b.example"
+        );
+
+        let timings = timings.allow_any_origin();
+        assert_eq!(timings.timing_allow_origin(), Some(&TimingAllowOrigin::Any));
+        let timings = timings.allow_origin(Origin::new("https", "c.example", None));
+        assert_eq!(timings.timing_allow_origin(), Some(&TimingAllowOrigin::Any));
+    }
+}
@@ -0,0 +1,727 @@
+//! HTTP Message Signatures, per [RFC 9421].
+//!
+//! This module constructs the _signature base_ of a request or response from
+//! the covered components of a signature, including derived components such
+//! as `@method` and `@target-uri`, and signs or verifies it via pluggable
+//! [`Signer`](trait.Signer.html) and [`Verifier`](trait.Verifier.html)
+//! implementations. Cryptographic algorithms are left to those
+//! implementations.
+//!
+//! The `Signature-Input` and `Signature` header values are produced by
+//! [`sign`](fn.sign.html) and consumed by [`verify`](fn.verify.html), for a
+//! single signature by label. To read or write all signatures of a message,
+//! or to request signatures via `Accept-Signature`, use the typed
+//! `header::SignatureInput`, `header::Signature` and
+//! `header::AcceptSignature` headers.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::signature::{self, Component, Param, SignatureParams, Signer, Verifier};
+//!
+//! // A toy "algorithm", for demonstration only.
+//! struct Reverse;
+//!
+//! impl Signer for Reverse {
+//!     fn sign(&self, base: &[u8]) -> hyperx::Result<Vec<u8>> {
+//!         Ok(base.iter().rev().cloned().collect())
+//!     }
+//! }
+//!
+//! impl Verifier for Reverse {
+//!     fn verify(&self, _: &SignatureParams, base: &[u8], signature: &[u8]) -> bool {
+//!         base.iter().rev().eq(signature.iter())
+//!     }
+//! }
+//!
+//! # fn run() -> Result<(), Box<std::error::Error>> {
+//! let mut req = http::Request::post("https://example.com/foo")
+//!     .header("content-type", "application/json")
+//!     .body(())?;
+//!
+//! let params = SignatureParams::new(vec![
+//!     Component::Method,
+//!     Component::TargetUri,
+//!     "content-type".parse()?,
+//! ])
+//! .with(Param::Created(1618884473))
+//! .with(Param::KeyId("test-key".to_owned()));
+//!
+//! let (input, sig) = signature::sign(&req, "sig1", &params, &Reverse)?;
+//! assert_eq!(
+//!     input,
+//!     r#"sig1=("@method" "@target-uri" "content-type");created=1618884473;keyid="test-key""#
+//! );
+//! req.headers_mut().append("signature-input", input);
+//! req.headers_mut().append("signature", sig);
+//!
+//! let verified = signature::verify(&req, "sig1", &Reverse)?;
+//! assert_eq!(verified.key_id(), Some("test-key"));
+//! # Ok(())
+//! # }
+//! # fn main() {
+//! #     run().unwrap();
+//! # }
+//! ```
+//!
+//! Component parameters (e.g. `;sf` or `;req`), the `@query-param` derived
+//! component, and signature parameters other than those of `Param` are not
+//! currently supported, and are rejected when parsing `Signature-Input`.
+//!
+//! [RFC 9421]: https://www.rfc-editor.org/rfc/rfc9421
+
+use std::convert::TryFrom;
+use std::fmt::{self, Write};
+use std::str::FromStr;
+
+use http::header::{HeaderMap, HeaderName, HeaderValue};
+use http::{Method, Request, Response, StatusCode, Uri};
+
+use header::sfv::{
+    self, BareItem, Dictionary, InnerList, Integer, Item, List, ListEntry, SfString,
+};
+use {Error, HeaderErrorKind, Result};
+
+const SIGNATURE_INPUT: &str = "Signature-Input";
+const SIGNATURE: &str = "Signature";
+
+/// A component of a message covered by a signature.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Component {
+    /// `@method`: the request method.
+    Method,
+    /// `@target-uri`: the full (absolute) request target URI.
+    TargetUri,
+    /// `@authority`: the authority (host and port) of the target URI.
+    Authority,
+    /// `@scheme`: the scheme of the target URI.
+    Scheme,
+    /// `@request-target`: the request target (path and query).
+    RequestTarget,
+    /// `@path`: the absolute path of the target URI.
+    Path,
+    /// `@query`: the query of the target URI, including the leading `?`.
+    Query,
+    /// `@status`: the response status code.
+    Status,
+    /// A header field, by name.
+    Field(HeaderName),
+}
+
+impl Component {
+    /// The component name, as used in its identifier.
+    pub fn name(&self) -> &str {
+        match *self {
+            Component::Method => "@method",
+            Component::TargetUri => "@target-uri",
+            Component::Authority => "@authority",
+            Component::Scheme => "@scheme",
+            Component::RequestTarget => "@request-target",
+            Component::Path => "@path",
+            Component::Query => "@query",
+            Component::Status => "@status",
+            Component::Field(ref name) => name.as_str(),
+        }
+    }
+}
+
+/// Formats the component identifier, as a quoted string.
+impl fmt::Display for Component {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "\"{}\"", self.name())
+    }
+}
+
+/// Parses a component name, without quotes, such as `@method` or
+/// `content-type`.
+impl FromStr for Component {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Component> {
+        Ok(match s {
+            "@method" => Component::Method,
+            "@target-uri" => Component::TargetUri,
+            "@authority" => Component::Authority,
+            "@scheme" => Component::Scheme,
+            "@request-target" => Component::RequestTarget,
+            "@path" => Component::Path,
+            "@query" => Component::Query,
+            "@status" => Component::Status,
+            _ if s.starts_with('@') => return Err(Error::header(HeaderErrorKind::Token)),
+            _ => Component::Field(
+                HeaderName::from_bytes(s.as_bytes())
+                    .map_err(|_| Error::header(HeaderErrorKind::Token))?,
+            ),
+        })
+    }
+}
+
+/// A parameter of a signature.
+///
+/// Strings must be of printable ASCII characters, and times of at most 15
+/// digits, to be serialized: formatting fails otherwise, as do `sign` and
+/// `Message::signature_base`, with `HeaderErrorKind::Conversion`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Param {
+    /// `created`: creation time, as a UNIX timestamp.
+    Created(u64),
+    /// `expires`: expiration time, as a UNIX timestamp.
+    Expires(u64),
+    /// `nonce`: a random unique value.
+    Nonce(String),
+    /// `alg`: the signature algorithm.
+    Alg(String),
+    /// `keyid`: the identifier of the key.
+    KeyId(String),
+    /// `tag`: an application-specific tag.
+    Tag(String),
+}
+
+impl Param {
+    fn key(&self) -> &'static str {
+        match *self {
+            Param::Created(_) => "created",
+            Param::Expires(_) => "expires",
+            Param::Nonce(_) => "nonce",
+            Param::Alg(_) => "alg",
+            Param::KeyId(_) => "keyid",
+            Param::Tag(_) => "tag",
+        }
+    }
+}
+
+impl fmt::Display for Param {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, ";{}=", self.key())?;
+        match *self {
+            Param::Created(t) | Param::Expires(t) => {
+                let t = i64::try_from(t)
+                    .ok()
+                    .and_then(|t| Integer::try_from(t).ok())
+                    .ok_or(fmt::Error)?;
+                fmt::Display::fmt(&BareItem::Integer(t), f)
+            }
+            Param::Nonce(ref s) | Param::Alg(ref s) | Param::KeyId(ref s) | Param::Tag(ref s) => {
+                let s = SfString::try_from(s.as_str()).map_err(|_| fmt::Error)?;
+                fmt::Display::fmt(&BareItem::String(s), f)
+            }
+        }
+    }
+}
+
+/// The covered components and parameters of a signature, as the value of a
+/// `Signature-Input` member, and the last line of the signature base.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignatureParams {
+    components: Vec<Component>,
+    params: Vec<Param>,
+}
+
+impl SignatureParams {
+    /// Create new parameters covering the given components, in order.
+    pub fn new<I>(components: I) -> SignatureParams
+    where
+        I: IntoIterator<Item = Component>,
+    {
+        SignatureParams {
+            components: components.into_iter().collect(),
+            params: Vec::new(),
+        }
+    }
+
+    /// Set a parameter, replacing any existing parameter of the same kind in
+    /// place, or otherwise appending it.
+    pub fn with(mut self, param: Param) -> SignatureParams {
+        match self.params.iter().position(|p| p.key() == param.key()) {
+            Some(i) => self.params[i] = param,
+            None => self.params.push(param),
+        }
+        self
+    }
+
+    /// The covered components, in order.
+    pub fn components(&self) -> &[Component] {
+        &self.components
+    }
+
+    /// The parameters, in order.
+    pub fn params(&self) -> &[Param] {
+        &self.params
+    }
+
+    /// The `created` parameter, if set.
+    pub fn created(&self) -> Option<u64> {
+        self.params.iter().find_map(|p| match *p {
+            Param::Created(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// The `expires` parameter, if set.
+    pub fn expires(&self) -> Option<u64> {
+        self.params.iter().find_map(|p| match *p {
+            Param::Expires(t) => Some(t),
+            _ => None,
+        })
+    }
+
+    /// The `nonce` parameter, if set.
+    pub fn nonce(&self) -> Option<&str> {
+        self.params.iter().find_map(|p| match *p {
+            Param::Nonce(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The `alg` parameter, if set.
+    pub fn alg(&self) -> Option<&str> {
+        self.params.iter().find_map(|p| match *p {
+            Param::Alg(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The `keyid` parameter, if set.
+    pub fn key_id(&self) -> Option<&str> {
+        self.params.iter().find_map(|p| match *p {
+            Param::KeyId(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    /// The `tag` parameter, if set.
+    pub fn tag(&self) -> Option<&str> {
+        self.params.iter().find_map(|p| match *p {
+            Param::Tag(ref s) => Some(s.as_str()),
+            _ => None,
+        })
+    }
+
+    // Convert from the inner list of a `Signature-Input` member.
+    pub(crate) fn from_inner_list(list: &InnerList) -> Result<SignatureParams> {
+        let components = list
+            .items
+            .iter()
+            .map(|item| match item.bare_item {
+                // Component parameters are not supported
+                BareItem::String(ref name) if item.params.is_empty() => name.as_str().parse(),
+                _ => Err(Error::header(HeaderErrorKind::Syntax)),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let mut params = SignatureParams::new(components);
+        for (key, value) in list.params.iter() {
+            let param = match (key, value) {
+                ("created", BareItem::Integer(t)) if t.get() >= 0 => Param::Created(t.get() as u64),
+                ("expires", BareItem::Integer(t)) if t.get() >= 0 => Param::Expires(t.get() as u64),
+                ("created", _) | ("expires", _) => {
+                    return Err(Error::header(HeaderErrorKind::Number))
+                }
+                ("nonce", BareItem::String(s)) => Param::Nonce(s.as_str().to_owned()),
+                ("alg", BareItem::String(s)) => Param::Alg(s.as_str().to_owned()),
+                ("keyid", BareItem::String(s)) => Param::KeyId(s.as_str().to_owned()),
+                ("tag", BareItem::String(s)) => Param::Tag(s.as_str().to_owned()),
+                ("nonce", _) | ("alg", _) | ("keyid", _) | ("tag", _) => {
+                    return Err(Error::header(HeaderErrorKind::Syntax))
+                }
+                _ => return Err(Error::header(HeaderErrorKind::Token)),
+            };
+            params = params.with(param);
+        }
+        Ok(params)
+    }
+}
+
+impl fmt::Display for SignatureParams {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_char('(')?;
+        for (i, c) in self.components.iter().enumerate() {
+            if i > 0 {
+                f.write_char(' ')?;
+            }
+            fmt::Display::fmt(c, f)?;
+        }
+        f.write_char(')')?;
+        for p in &self.params {
+            fmt::Display::fmt(p, f)?;
+        }
+        Ok(())
+    }
+}
+
+/// Parses the inner list form of a `Signature-Input` member value.
+impl FromStr for SignatureParams {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SignatureParams> {
+        let list: List = s.parse()?;
+        match list.as_slice() {
+            [ListEntry::InnerList(inner)] => SignatureParams::from_inner_list(inner),
+            _ => Err(Error::header(HeaderErrorKind::Syntax)),
+        }
+    }
+}
+
+/// The parts of a request or response from which a signature base is
+/// constructed.
+#[derive(Clone, Copy, Debug)]
+pub struct Message<'a> {
+    method: Option<&'a Method>,
+    uri: Option<&'a Uri>,
+    status: Option<StatusCode>,
+    headers: &'a HeaderMap,
+}
+
+impl<'a, B> From<&'a Request<B>> for Message<'a> {
+    fn from(req: &'a Request<B>) -> Message<'a> {
+        Message {
+            method: Some(req.method()),
+            uri: Some(req.uri()),
+            status: None,
+            headers: req.headers(),
+        }
+    }
+}
+
+impl<'a, B> From<&'a Response<B>> for Message<'a> {
+    fn from(res: &'a Response<B>) -> Message<'a> {
+        Message {
+            method: None,
+            uri: None,
+            status: Some(res.status()),
+            headers: res.headers(),
+        }
+    }
+}
+
+impl<'a> Message<'a> {
+    /// Construct the signature base for the given parameters.
+    ///
+    /// Returns `Error::Header` if a covered component is not available from
+    /// this message, or is covered more than once, or of kind
+    /// `HeaderErrorKind::Conversion` if a parameter can not be serialized.
+    pub fn signature_base(&self, params: &SignatureParams) -> Result<String> {
+        let mut base = String::new();
+        for (i, c) in params.components.iter().enumerate() {
+            if params.components[..i].contains(c) {
+                return Err(Error::header(HeaderErrorKind::Multiple));
+            }
+            let value = self.component_value(c)?;
+            base.push_str(&format!("{}: {}\n", c, value));
+        }
+        base.push_str("\"@signature-params\": ");
+        base.push_str(&serialize(params)?);
+        Ok(base)
+    }
+
+    fn component_value(&self, component: &Component) -> Result<String> {
+        let missing = || Error::header(HeaderErrorKind::Empty).with_header_name(SIGNATURE_INPUT);
+        let uri = self.uri.ok_or_else(missing);
+        Ok(match *component {
+            Component::Method => self.method.ok_or_else(missing)?.as_str().to_owned(),
+            Component::TargetUri => {
+                let uri = uri?;
+                if uri.scheme().is_none() || uri.authority().is_none() {
+                    return Err(missing());
+                }
+                uri.to_string()
+            }
+            Component::Authority => {
+                let authority = match uri?.authority() {
+                    Some(a) => a.as_str().to_owned(),
+                    None => self
+                        .headers
+                        .get(::http::header::HOST)
+                        .and_then(|h| h.to_str().ok())
+                        .ok_or_else(missing)?
+                        .to_owned(),
+                };
+                authority.to_ascii_lowercase()
+            }
+            Component::Scheme => uri?.scheme_str().ok_or_else(missing)?.to_ascii_lowercase(),
+            Component::RequestTarget => match uri?.path_and_query() {
+                Some(pq) => pq.as_str().to_owned(),
+                None => "/".to_owned(),
+            },
+            Component::Path => uri?.path().to_owned(),
+            Component::Query => format!("?{}", uri?.query().unwrap_or("")),
+            Component::Status => self.status.ok_or_else(missing)?.as_str().to_owned(),
+            Component::Field(ref name) => {
+                let mut value = String::new();
+                let mut found = false;
+                for v in self.headers.get_all(name) {
+                    let v = v
+                        .to_str()
+                        .map_err(|e| Error::header_with_source(HeaderErrorKind::Utf8, e))?;
+                    if found {
+                        value.push_str(", ");
+                    }
+                    value.push_str(v.trim_matches(|c| c == ' ' || c == '\t'));
+                    found = true;
+                }
+                if !found {
+                    return Err(missing());
+                }
+                value
+            }
+        })
+    }
+}
+
+/// A signing algorithm and key.
+pub trait Signer {
+    /// Sign the signature base, returning the signature bytes.
+    fn sign(&self, base: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// A verification algorithm and key (or key lookup).
+pub trait Verifier {
+    /// Verify the signature of the signature base. The parameters may be
+    /// used to select the key, e.g. by `keyid`, or to reject a signature,
+    /// e.g. by `expires`.
+    fn verify(&self, params: &SignatureParams, base: &[u8], signature: &[u8]) -> bool;
+}
+
+/// Sign a message, returning the `Signature-Input` and `Signature` header
+/// values for the given label, to be appended to the message.
+///
+/// The label must be a valid structured field key (e.g. `sig1`), and the
+/// parameters serializable, see `Param`.
+pub fn sign<'a, M, S>(
+    message: M,
+    label: &str,
+    params: &SignatureParams,
+    signer: &S,
+) -> Result<(HeaderValue, HeaderValue)>
+where
+    M: Into<Message<'a>>,
+    S: Signer + ?Sized,
+{
+    if !sfv::is_key(label) {
+        return Err(Error::header(HeaderErrorKind::Token).with_header_name(SIGNATURE_INPUT));
+    }
+    let base = message.into().signature_base(params)?;
+    let signature = signer.sign(base.as_bytes())?;
+    let to_value = |s: String| {
+        HeaderValue::try_from(s)
+            .map_err(|e| Error::header_with_source(HeaderErrorKind::Conversion, e))
+    };
+    Ok((
+        to_value(format!("{}={}", label, serialize(params)?))?,
+        to_value(format!("{}={}", label, Item::new(signature)))?,
+    ))
+}
+
+/// Verify the signature of a message with the given label, from its
+/// `Signature-Input` and `Signature` headers, returning the verified
+/// parameters.
+///
+/// Returns `Error::Header` if either header or the label is missing or
+/// malformed, or of kind `HeaderErrorKind::Signature` if the verifier
+/// rejects the signature.
+pub fn verify<'a, M, V>(message: M, label: &str, verifier: &V) -> Result<SignatureParams>
+where
+    M: Into<Message<'a>>,
+    V: Verifier + ?Sized,
+{
+    let message = message.into();
+    let params = match find_member(message.headers, SIGNATURE_INPUT, label)? {
+        ListEntry::InnerList(ref list) => SignatureParams::from_inner_list(list),
+        ListEntry::Item(_) => Err(Error::header(HeaderErrorKind::Syntax)),
+    }
+    .map_err(|e| e.with_header_name(SIGNATURE_INPUT))?;
+
+    let signature = match find_member(message.headers, SIGNATURE, label)? {
+        ListEntry::Item(Item {
+            bare_item: BareItem::ByteSequence(bytes),
+            ..
+        }) => bytes,
+        _ => return Err(Error::header(HeaderErrorKind::Syntax).with_header_name(SIGNATURE)),
+    };
+
+    let base = message.signature_base(&params)?;
+    if verifier.verify(&params, base.as_bytes(), &signature) {
+        Ok(params)
+    } else {
+        Err(Error::header(HeaderErrorKind::Signature).with_header_name(SIGNATURE))
+    }
+}
+
+// Serialize the parameters, which fails as of `Param`.
+fn serialize(params: &SignatureParams) -> Result<String> {
+    let mut s = String::new();
+    write!(s, "{}", params).map_err(|_| {
+        Error::header(HeaderErrorKind::Conversion).with_header_name(SIGNATURE_INPUT)
+    })?;
+    Ok(s)
+}
+
+// Find the member with the given key of the named dictionary header, across
+// all of its values, where the last of any duplicate keys wins.
+fn find_member(headers: &HeaderMap, name: &'static str, key: &str) -> Result<ListEntry> {
+    Dictionary::from_raw(&headers.get_all(name))
+        .map_err(|e| e.with_header_name(name))?
+        .remove(key)
+        .ok_or_else(|| Error::header(HeaderErrorKind::Empty).with_header_name(name))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Reverse;
+
+    impl Signer for Reverse {
+        fn sign(&self, base: &[u8]) -> Result<Vec<u8>> {
+            Ok(base.iter().rev().cloned().collect())
+        }
+    }
+
+    impl Verifier for Reverse {
+        fn verify(&self, _: &SignatureParams, base: &[u8], signature: &[u8]) -> bool {
+            base.iter().rev().eq(signature.iter())
+        }
+    }
+
+    fn request() -> Request<()> {
+        Request::post("https://example.com/foo?param=Value&Pet=dog")
+            .header("date", "Tue, 20 Apr 2021 02:07:55 GMT")
+            .header("content-type", "application/json")
+            .header("content-length", " 18 ")
+            .body(())
+            .unwrap()
+    }
+
+    #[test]
+    fn test_signature_base() {
+        let params: SignatureParams = r#"("date" "@method" "@path" "@query" "@authority" "content-type" "content-length");created=1618884473;keyid="test-key-rsa-pss""#.parse().unwrap();
+        let base = Message::from(&request()).signature_base(&params).unwrap();
+        assert_eq!(
+            base,
+            "\"date\": Tue, 20 Apr 2021 02:07:55 GMT\n\
+             \"@method\": POST\n\
+             \"@path\": /foo\n\
+             \"@query\": ?param=Value&Pet=dog\n\
+             \"@authority\": example.com\n\
+             \"content-type\": application/json\n\
+             \"content-length\": 18\n\
+             \"@signature-params\": (\"date\" \"@method\" \"@path\" \"@query\" \
+             \"@authority\" \"content-type\" \"content-length\");created=1618884473;\
+             keyid=\"test-key-rsa-pss\""
+        );
+    }
+
+    #[test]
+    fn test_response_base() {
+        let res = Response::builder()
+            .status(StatusCode::OK)
+            .header("content-type", "text/plain")
+            .body(())
+            .unwrap();
+        let params = SignatureParams::new(vec![Component::Status, "content-type".parse().unwrap()]);
+        let base = Message::from(&res).signature_base(&params).unwrap();
+        assert_eq!(
+            base,
+            "\"@status\": 200\n\
+             \"content-type\": text/plain\n\
+             \"@signature-params\": (\"@status\" \"content-type\")"
+        );
+
+        let params = SignatureParams::new(vec![Component::Method]);
+        assert!(Message::from(&res).signature_base(&params).is_err());
+    }
+
+    #[test]
+    fn test_params() {
+        let params = SignatureParams::new(vec![Component::Method])
+            .with(Param::KeyId("k\"1".to_owned()))
+            .with(Param::Created(1))
+            .with(Param::KeyId("k2".to_owned()));
+        assert_eq!(params.to_string(), r#"("@method");keyid="k2";created=1"#);
+        assert_eq!(
+            params.to_string().parse::<SignatureParams>().unwrap(),
+            params
+        );
+        assert_eq!(params.key_id(), Some("k2"));
+        assert_eq!(params.created(), Some(1));
+        assert_eq!(params.expires(), None);
+        assert_eq!(params.nonce(), None);
+
+        let params: SignatureParams = r#"( "@path"  "x-a" );tag="a\\b""#.parse().unwrap();
+        assert_eq!(params.to_string(), r#"("@path" "x-a");tag="a\\b""#);
+        assert_eq!(params.tag(), Some("a\\b"));
+
+        for bad in &[
+            r#""@method""#,
+            r#"("@method""#,
+            r#"("@unknown")"#,
+            r#"("@method";sf)"#,
+            r#"("@method");created=now"#,
+            r#"("@method");other="x""#,
+            r#"("@method") x"#,
+        ] {
+            assert!(bad.parse::<SignatureParams>().is_err(), "{}", bad);
+        }
+    }
+
+    #[test]
+    fn test_sign_verify() {
+        let mut req = request();
+        let params = SignatureParams::new(vec![
+            Component::Method,
+            Component::TargetUri,
+            "content-type".parse().unwrap(),
+        ])
+        .with(Param::Created(1618884473));
+
+        let (input, sig) = sign(&req, "sig1", &params, &Reverse).unwrap();
+        assert_eq!(
+            input,
+            r#"sig1=("@method" "@target-uri" "content-type");created=1618884473"#
+        );
+        assert!(sig.to_str().unwrap().starts_with("sig1=:"));
+        assert!(sign(&req, "Sig1", &params, &Reverse).is_err());
+
+        for param in &[
+            Param::KeyId("ключ".to_owned()),
+            Param::Nonce("a\nb".to_owned()),
+            Param::Expires(1_000_000_000_000_000),
+        ] {
+            let bad = params.clone().with(param.clone());
+            let e = sign(&req, "sig1", &bad, &Reverse).unwrap_err();
+            assert_eq!(
+                e.header_error().unwrap().kind(),
+                HeaderErrorKind::Conversion
+            );
+            let e = Message::from(&req).signature_base(&bad).unwrap_err();
+            assert_eq!(
+                e.header_error().unwrap().kind(),
+                HeaderErrorKind::Conversion
+            );
+        }
+
+        let headers = req.headers_mut();
+        // Of duplicate labels, the last wins
+        headers.append(
+            "signature-input",
+            r#"other=("@method"), sig1=("@path")"#.parse().unwrap(),
+        );
+        headers.append("signature-input", input);
+        headers.append(
+            "signature",
+            r#"other=:AAAA:, sig1=:AAAA:, x=("a,b")"#.parse().unwrap(),
+        );
+        headers.append("signature", sig);
+        assert_eq!(verify(&req, "sig1", &Reverse).unwrap(), params);
+
+        let e = verify(&req, "other", &Reverse).unwrap_err();
+        assert_eq!(e.header_error().unwrap().kind(), HeaderErrorKind::Signature);
+        assert!(verify(&req, "sig2", &Reverse).is_err());
+
+        req.headers_mut()
+            .insert("content-type", "text/plain".parse().unwrap());
+        let e = verify(&req, "sig1", &Reverse).unwrap_err();
+        assert_eq!(e.header_error().unwrap().kind(), HeaderErrorKind::Signature);
+    }
+}
@@ -0,0 +1,394 @@
+//! Detection of request smuggling prone message framing.
+//!
+//! When a frontend and a backend disagree on where a request ends, an
+//! attacker can smuggle a second request inside the body of the first, per
+//! [RFC 9112 §11.2]. [`check_fields`](fn.check_fields.html) inspects the
+//! header fields of a request, as received, and reports each ambiguity as
+//! a [`Finding`](struct.Finding.html), so that a frontend can reject the
+//! request with `400 Bad Request` instead of forwarding it. The checks are:
+//!
+//! * multiple `Content-Length` values, whether repeated or conflicting, and
+//!   invalid ones, such as `+42` or `0x2a`;
+//! * `Content-Length` together with `Transfer-Encoding`;
+//! * obfuscated `Transfer-Encoding` values, such as `"chunked"`,
+//!   `chunked;x=y`, `xchunked` or a vertical tab within, and `chunked`
+//!   other than as the final coding;
+//! * whitespace within field names, such as `Transfer-Encoding :`, which
+//!   some recipients strip and others do not;
+//! * CR, LF or NUL characters within field values.
+//!
+//! ```
+//! use hyperx::smuggling::{check_fields, Risk};
+//!
+//! let fields: &[(&[u8], &[u8])] = &[
+//!     (b"Host", b"example.com"),
+//!     (b"Content-Length", b"42"),
+//!     (b"Transfer-Encoding ", b"chunked"),
+//! ];
+//! let findings = check_fields(fields.iter().cloned());
+//! assert_eq!(findings.len(), 2);
+//! assert_eq!(findings[0].risk, Risk::WhitespaceInName);
+//! assert_eq!(findings[1].risk, Risk::ContentLengthWithTransferEncoding);
+//! ```
+//!
+//! [RFC 9112 §11.2]: https://www.rfc-editor.org/rfc/rfc9112#section-11.2
+
+use std::fmt;
+
+use http::HeaderMap;
+
+use header::parsing::is_token;
+
+/// The kind of ambiguity reported by a [`Finding`](struct.Finding.html).
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Risk {
+    /// `Content-Length` is given more than once, with the same value.
+    MultipleContentLength,
+    /// `Content-Length` is given more than once, with differing values.
+    ConflictingContentLength,
+    /// A `Content-Length` value is not a decimal number.
+    InvalidContentLength,
+    /// `Content-Length` is given together with `Transfer-Encoding`.
+    ContentLengthWithTransferEncoding,
+    /// A `Transfer-Encoding` value which recipients may or may not
+    /// recognize as `chunked`.
+    ObfuscatedTransferEncoding,
+    /// `chunked` is not the final transfer coding, or is applied more than
+    /// once.
+    ChunkedNotFinal,
+    /// A field name contains whitespace.
+    WhitespaceInName,
+    /// A field value contains a CR, LF or NUL character.
+    InvalidValueCharacter,
+}
+
+impl Risk {
+    fn description(self) -> &'static str {
+        match self {
+            Risk::MultipleContentLength => "Content-Length is repeated",
+            Risk::ConflictingContentLength => "Content-Length values differ",
+            Risk::InvalidContentLength => "Content-Length is not a decimal number",
+            Risk::ContentLengthWithTransferEncoding => "Content-Length with Transfer-Encoding",
+            Risk::ObfuscatedTransferEncoding => "obfuscated Transfer-Encoding",
+            Risk::ChunkedNotFinal => "chunked is not the final transfer coding",
+            Risk::WhitespaceInName => "whitespace in field name",
+            Risk::InvalidValueCharacter => "CR, LF or NUL in field value",
+        }
+    }
+}
+
+impl fmt::Display for Risk {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.description())
+    }
+}
+
+/// An ambiguity found by [`check_fields`](fn.check_fields.html).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Finding {
+    /// The kind of ambiguity.
+    pub risk: Risk,
+    /// The name of the offending field, as received (lossily converted to
+    /// UTF-8).
+    pub name: String,
+}
+
+impl Finding {
+    fn new(risk: Risk, name: &[u8]) -> Finding {
+        Finding {
+            risk,
+            name: String::from_utf8_lossy(name).into_owned(),
+        }
+    }
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:?}: {}", self.name, self.risk)
+    }
+}
+
+/// Check the header fields of a request, as received, in order, returning
+/// all findings. An empty result means the framing of the request is
+/// unambiguous.
+///
+/// Fields are given as raw name and value pairs, e.g. those of a request
+/// parsed by _httparse_:
+/// `req.headers.iter().map(|h| (h.name.as_bytes(), h.value))`. Field names
+/// are compared case-insensitively, after stripping surrounding whitespace,
+/// so that a field is checked however a recipient interprets its name.
+pub fn check_fields<'a, I>(fields: I) -> Vec<Finding>
+where
+    I: IntoIterator<Item = (&'a [u8], &'a [u8])>,
+{
+    let mut findings = Vec::new();
+    let mut lengths: Vec<&'a [u8]> = Vec::new();
+    let mut length_name = None;
+    let mut encoding_name = None;
+    let mut chunked = false;
+    for (name, value) in fields {
+        if name.iter().any(u8::is_ascii_whitespace) {
+            findings.push(Finding::new(Risk::WhitespaceInName, name));
+        }
+        if value.iter().any(|&b| b == b'\r' || b == b'\n' || b == 0) {
+            findings.push(Finding::new(Risk::InvalidValueCharacter, name));
+        }
+        let trimmed = trim_whitespace(name);
+        if trimmed.eq_ignore_ascii_case(b"content-length") {
+            length_name.get_or_insert(name);
+            for part in value.split(|&b| b == b',') {
+                let part = trim_ows(part);
+                if part.is_empty() || !part.iter().all(u8::is_ascii_digit) {
+                    findings.push(Finding::new(Risk::InvalidContentLength, name));
+                }
+                lengths.push(part);
+            }
+        } else if trimmed.eq_ignore_ascii_case(b"transfer-encoding") {
+            encoding_name.get_or_insert(name);
+            check_codings(name, value, &mut chunked, &mut findings);
+        }
+    }
+    if let Some(name) = length_name {
+        if lengths.iter().any(|l| *l != lengths[0]) {
+            findings.push(Finding::new(Risk::ConflictingContentLength, name));
+        } else if lengths.len() > 1 {
+            findings.push(Finding::new(Risk::MultipleContentLength, name));
+        }
+    }
+    if let (Some(_), Some(name)) = (length_name, encoding_name) {
+        findings.push(Finding::new(Risk::ContentLengthWithTransferEncoding, name));
+    }
+    findings
+}
+
+/// Check the header fields of a request already collected into a
+/// `http::HeaderMap`.
+///
+/// Field names of a `HeaderMap` are valid and lowercase, so this finds
+/// ambiguities of values only, and fields are checked in the order of the
+/// map rather than as received. Prefer
+/// [`check_fields`](fn.check_fields.html) when the raw fields are
+/// available.
+pub fn check_headers(headers: &HeaderMap) -> Vec<Finding> {
+    check_fields(
+        headers
+            .iter()
+            .map(|(name, value)| (name.as_str().as_bytes(), value.as_bytes())),
+    )
+}
+
+// Check the codings of one Transfer-Encoding field value, given whether
+// chunked was applied by a previous value.
+fn check_codings(name: &[u8], value: &[u8], chunked: &mut bool, findings: &mut Vec<Finding>) {
+    let mut obfuscated = false;
+    let mut not_final = false;
+    for coding in value.split(|&b| b == b',') {
+        let coding = trim_ows(coding);
+        if coding.is_empty() {
+            continue;
+        }
+        if *chunked {
+            not_final = true;
+        }
+        let coding_name = match coding.iter().position(|&b| b == b';') {
+            Some(i) => trim_ows(&coding[..i]),
+            None => coding,
+        };
+        let token = std::str::from_utf8(coding_name).is_ok_and(is_token);
+        if coding_name.eq_ignore_ascii_case(b"chunked") {
+            // chunked takes no parameters
+            obfuscated |= coding_name.len() != coding.len();
+            *chunked = true;
+        } else if !token || contains_chunked(coding_name) {
+            obfuscated = true;
+        }
+    }
+    if obfuscated {
+        findings.push(Finding::new(Risk::ObfuscatedTransferEncoding, name));
+    }
+    if not_final {
+        findings.push(Finding::new(Risk::ChunkedNotFinal, name));
+    }
+}
+
+fn contains_chunked(s: &[u8]) -> bool {
+    s.windows(7).any(|w| w.eq_ignore_ascii_case(b"chunked"))
+}
+
+fn trim_whitespace(mut s: &[u8]) -> &[u8] {
+    while let [b, rest @ ..] = s {
+        if !b.is_ascii_whitespace() {
+            break;
+        }
+        s = rest;
+    }
+    while let [rest @ .., b] = s {
+        if !b.is_ascii_whitespace() {
+            break;
+        }
+        s = rest;
+    }
+    s
+}
+
+// Trim optional whitespace: spaces and horizontal tabs only.
+fn trim_ows(mut s: &[u8]) -> &[u8] {
+    while let [b' ' | b'\t', rest @ ..] = s {
+        s = rest;
+    }
+    while let [rest @ .., b' ' | b'\t'] = s {
+        s = rest;
+    }
+    s
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(fields: &[(&str, &str)]) -> Vec<(Risk, String)> {
+        check_fields(
+            fields
+                .iter()
+                .map(|&(name, value)| (name.as_bytes(), value.as_bytes())),
+        )
+        .into_iter()
+        .map(|f| (f.risk, f.name))
+        .collect()
+    }
+
+    fn risks(fields: &[(&str, &str)]) -> Vec<Risk> {
+        check(fields).into_iter().map(|f| f.0).collect()
+    }
+
+    #[test]
+    fn test_clean() {
+        assert!(check(&[("Host", "a"), ("Content-Length", "42")]).is_empty());
+        assert!(check(&[("Transfer-Encoding", "gzip, Chunked")]).is_empty());
+        assert!(check(&[
+            ("transfer-encoding", "gzip"),
+            ("transfer-encoding", "chunked")
+        ])
+        .is_empty());
+        assert!(check(&[("Transfer-Encoding", "\tchunked ")]).is_empty());
+        assert!(check(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_content_length() {
+        assert_eq!(
+            risks(&[("Content-Length", "42"), ("content-length", "42")]),
+            vec![Risk::MultipleContentLength]
+        );
+        assert_eq!(
+            risks(&[("Content-Length", "42, 42")]),
+            vec![Risk::MultipleContentLength]
+        );
+        assert_eq!(
+            check(&[("Content-Length", "42"), ("CONTENT-LENGTH", "7")]),
+            vec![(Risk::ConflictingContentLength, "Content-Length".to_owned())]
+        );
+        assert_eq!(
+            risks(&[("Content-Length", "+42")]),
+            vec![Risk::InvalidContentLength]
+        );
+        assert_eq!(
+            risks(&[("Content-Length", "")]),
+            vec![Risk::InvalidContentLength]
+        );
+        assert_eq!(
+            risks(&[("Content-Length", "0x2a"), ("Content-Length", "42")]),
+            vec![Risk::InvalidContentLength, Risk::ConflictingContentLength]
+        );
+    }
+
+    #[test]
+    fn test_content_length_with_transfer_encoding() {
+        assert_eq!(
+            check(&[("Transfer-Encoding", "chunked"), ("Content-Length", "3")]),
+            vec![(
+                Risk::ContentLengthWithTransferEncoding,
+                "Transfer-Encoding".to_owned()
+            )]
+        );
+    }
+
+    #[test]
+    fn test_obfuscated_transfer_encoding() {
+        let obfuscated = [
+            "\"chunked\"",
+            "chunked;x=y",
+            "xchunked",
+            "chunked-false",
+            "chunked\x0b",
+            "\x0cchunked",
+            "chu nked",
+            "chunk\u{212a}d",
+        ];
+        for value in &obfuscated {
+            assert_eq!(
+                risks(&[("Transfer-Encoding", value)]),
+                vec![Risk::ObfuscatedTransferEncoding],
+                "{:?}",
+                value
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunked_not_final() {
+        assert_eq!(
+            risks(&[("Transfer-Encoding", "chunked, gzip")]),
+            vec![Risk::ChunkedNotFinal]
+        );
+        assert_eq!(
+            risks(&[("Transfer-Encoding", "chunked, chunked")]),
+            vec![Risk::ChunkedNotFinal]
+        );
+        assert_eq!(
+            risks(&[
+                ("Transfer-Encoding", "chunked"),
+                ("Transfer-Encoding", "identity")
+            ]),
+            vec![Risk::ChunkedNotFinal]
+        );
+    }
+
+    #[test]
+    fn test_names_and_values() {
+        assert_eq!(
+            check(&[(" Transfer-Encoding", "chunked"), ("Content-Length", "3")]),
+            vec![
+                (Risk::WhitespaceInName, " Transfer-Encoding".to_owned()),
+                (
+                    Risk::ContentLengthWithTransferEncoding,
+                    " Transfer-Encoding".to_owned()
+                ),
+            ]
+        );
+        assert_eq!(
+            risks(&[("Content\tLength", "3")]),
+            vec![Risk::WhitespaceInName]
+        );
+        assert_eq!(
+            risks(&[("X-Foo", "a\r\nContent-Length: 3")]),
+            vec![Risk::InvalidValueCharacter]
+        );
+    }
+
+    #[test]
+    fn test_check_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append("content-length", "3".parse().unwrap());
+        headers.append("transfer-encoding", "chunked".parse().unwrap());
+        headers.append("content-length", "4".parse().unwrap());
+        let findings = check_headers(&headers);
+        assert_eq!(findings.len(), 2);
+        assert_eq!(findings[0].risk, Risk::ConflictingContentLength);
+        assert_eq!(
+            findings[1].to_string(),
+            "\"transfer-encoding\": Content-Length with Transfer-Encoding"
+        );
+    }
+}
@@ -0,0 +1,301 @@
+//! Trailer sections, per [RFC 9110 §6.5].
+//!
+//! [`Trailers`](struct.Trailers.html) is a small map of typed headers, like
+//! `http::HeaderMap` with the [`TypedHeaders`](../header/trait.TypedHeaders.html)
+//! extension, restricted to the fields allowed in a trailer section. Fields
+//! needed to frame, route, authenticate or process a message, such as
+//! `Content-Length` or `Host`, are rejected as a
+//! [`ForbiddenTrailer`](struct.ForbiddenTrailer.html). The typed
+//! [`Trailer`](../header/struct.Trailer.html) header declaring the fields
+//! is produced by [`Trailers::declaration`](struct.Trailers.html#method.declaration).
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use hyperx::header::{ContentLength, ETag, EntityTag, TypedHeaders};
+//! use hyperx::trailers::Trailers;
+//!
+//! let mut trailers = Trailers::new();
+//! trailers.set(&ETag(EntityTag::strong("xyzzy".to_owned()))).unwrap();
+//! trailers
+//!     .insert(
+//!         http::header::HeaderName::from_static("grpc-status"),
+//!         http::HeaderValue::from_static("0"),
+//!     )
+//!     .unwrap();
+//! assert!(trailers.set(&ContentLength(42)).is_err());
+//!
+//! // Declared in the header section, ahead of the content
+//! let mut headers = http::HeaderMap::new();
+//! headers.encode(&trailers.declaration().unwrap());
+//! assert_eq!(headers["trailer"], "etag, grpc-status");
+//!
+//! assert_eq!(trailers.get::<ETag>().unwrap().unwrap().tag(), "xyzzy");
+//! ```
+//!
+//! [RFC 9110 §6.5]: https://www.rfc-editor.org/rfc/rfc9110#section-6.5
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use http::header::{self as h, HeaderMap, HeaderName, HeaderValue};
+
+use header::{FieldInfo, Header, Trailer, TypedHeaders};
+
+/// Returns true if the field may be sent in a trailer section.
+///
+/// Fields needed to frame, route or process a message (RFC 9110 §6.5.1)
+/// are forbidden, per [`FieldInfo::allowed_in_trailers`](../header/struct.FieldInfo.html#method.allowed_in_trailers).
+/// Unknown fields are allowed.
+///
+/// ```
+/// use hyperx::trailers::is_allowed;
+///
+/// assert!(is_allowed(&http::header::ETAG));
+/// assert!(!is_allowed(&http::header::CONTENT_LENGTH));
+/// assert!(!is_allowed(&http::header::HeaderName::from_static("keep-alive")));
+/// ```
+pub fn is_allowed(name: &HeaderName) -> bool {
+    FieldInfo::lookup(name.as_str()).map_or(true, FieldInfo::allowed_in_trailers)
+}
+
+/// The fields of a trailer section.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Trailers {
+    map: HeaderMap,
+}
+
+impl Trailers {
+    /// Create a new, empty trailer section.
+    pub fn new() -> Trailers {
+        Trailers::default()
+    }
+
+    /// Create a trailer section from the fields of a `http::HeaderMap`,
+    /// e.g. as received, rejecting any forbidden field.
+    pub fn from_map(map: HeaderMap) -> Result<Trailers, ForbiddenTrailer> {
+        if let Some(name) = map.keys().find(|n| !is_allowed(n)) {
+            return Err(ForbiddenTrailer(name.clone()));
+        }
+        Ok(Trailers { map })
+    }
+
+    /// Set a typed header, replacing any existing values.
+    ///
+    /// An error of kind `ErrorKind::Conversion` is returned if the header
+    /// fails to encode as a `HeaderValue`, or is not allowed in trailers, in
+    /// which case its source is a `ForbiddenTrailer`.
+    pub fn set<H: Header>(&mut self, header: &H) -> ::Result<()> {
+        let value = header.encode_to_value()?;
+        self.insert(H::http_name(), value)?;
+        Ok(())
+    }
+
+    /// Decode a typed header, returning `None` if not present.
+    pub fn get<H: Header>(&self) -> Option<::Result<H>> {
+        self.map.try_decode()
+    }
+
+    /// Insert a raw field, replacing any existing values.
+    pub fn insert(&mut self, name: HeaderName, value: HeaderValue) -> Result<(), ForbiddenTrailer> {
+        if !is_allowed(&name) {
+            return Err(ForbiddenTrailer(name));
+        }
+        self.map.insert(name, value);
+        Ok(())
+    }
+
+    /// Append a raw field value.
+    pub fn append(&mut self, name: HeaderName, value: HeaderValue) -> Result<(), ForbiddenTrailer> {
+        if !is_allowed(&name) {
+            return Err(ForbiddenTrailer(name));
+        }
+        self.map.append(name, value);
+        Ok(())
+    }
+
+    /// Remove a field, returning its first value, if present.
+    pub fn remove(&mut self, name: &HeaderName) -> Option<HeaderValue> {
+        self.map.remove(name)
+    }
+
+    /// The `Trailer` header declaring the field names of the section, or
+    /// `None` if it is empty.
+    pub fn declaration(&self) -> Option<Trailer> {
+        if self.map.is_empty() {
+            None
+        } else {
+            Some(Trailer(self.map.keys().cloned().collect()))
+        }
+    }
+
+    /// Returns the first field name of the section not declared by a
+    /// `Trailer` header, if any.
+    pub fn undeclared(&self, declared: &Trailer) -> Option<&HeaderName> {
+        self.map.keys().find(|n| !declared.0.contains(n))
+    }
+
+    /// Returns the number of field values.
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    /// Returns true if the section has no fields.
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    /// Returns an iterator over the field names and values.
+    pub fn iter(&self) -> h::Iter<'_, HeaderValue> {
+        self.map.iter()
+    }
+
+    /// The fields as a `http::HeaderMap`.
+    pub fn as_map(&self) -> &HeaderMap {
+        &self.map
+    }
+
+    /// Convert into a `http::HeaderMap`, e.g. for the trailers of a body.
+    pub fn into_map(self) -> HeaderMap {
+        self.map
+    }
+}
+
+/// A field which is not allowed in a trailer section.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ForbiddenTrailer(pub HeaderName);
+
+impl fmt::Display for ForbiddenTrailer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "field {} is not allowed in trailers", self.0)
+    }
+}
+
+impl StdError for ForbiddenTrailer {}
+
+impl From<ForbiddenTrailer> for ::Error {
+    fn from(err: ForbiddenTrailer) -> ::Error {
+        ::Error::header_with_source(::HeaderErrorKind::Conversion, err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use header::{ContentType, ETag, EntityTag, Server, TypedHeaders};
+    use ErrorKind;
+
+    #[test]
+    fn test_allowed() {
+        assert!(is_allowed(&h::SERVER));
+        assert!(is_allowed(&HeaderName::from_static("grpc-status")));
+        let forbidden = [
+            h::AGE,
+            h::AUTHORIZATION,
+            h::CACHE_CONTROL,
+            h::CONNECTION,
+            h::CONTENT_ENCODING,
+            h::CONTENT_LENGTH,
+            h::CONTENT_RANGE,
+            h::CONTENT_TYPE,
+            h::COOKIE,
+            h::DATE,
+            h::EXPECT,
+            h::EXPIRES,
+            h::HOST,
+            h::IF_MATCH,
+            h::IF_MODIFIED_SINCE,
+            h::IF_NONE_MATCH,
+            h::IF_RANGE,
+            h::IF_UNMODIFIED_SINCE,
+            h::LOCATION,
+            h::MAX_FORWARDS,
+            h::PRAGMA,
+            h::PROXY_AUTHENTICATE,
+            h::PROXY_AUTHORIZATION,
+            h::RANGE,
+            h::RETRY_AFTER,
+            h::SET_COOKIE,
+            h::TE,
+            h::TRAILER,
+            h::TRANSFER_ENCODING,
+            h::UPGRADE,
+            h::VARY,
+            h::WARNING,
+            h::WWW_AUTHENTICATE,
+            HeaderName::from_static("keep-alive"),
+            HeaderName::from_static("proxy-connection"),
+        ];
+        for name in &forbidden {
+            assert!(!is_allowed(name), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_typed() {
+        let mut trailers = Trailers::new();
+        assert_eq!(trailers.declaration(), None);
+        assert!(trailers.get::<ETag>().is_none());
+
+        let etag = ETag(EntityTag::weak("1".to_owned()));
+        trailers.set(&etag).unwrap();
+        assert_eq!(trailers.get::<ETag>().unwrap().unwrap(), etag);
+        let e = trailers.set(&ContentType::json()).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::Conversion);
+        assert_eq!(
+            e.source().unwrap().downcast_ref(),
+            Some(&ForbiddenTrailer(h::CONTENT_TYPE))
+        );
+        assert_eq!(
+            e.source().unwrap().to_string(),
+            "field content-type is not allowed in trailers"
+        );
+
+        let e = trailers.set(&Server::new("a\0b")).unwrap_err();
+        assert_eq!(e.kind(), ErrorKind::Conversion);
+        assert!(trailers.get::<Server>().is_none());
+
+        trailers
+            .insert(h::ETAG, HeaderValue::from_static("invalid"))
+            .unwrap();
+        assert!(trailers.get::<ETag>().unwrap().is_err());
+        assert_eq!(trailers.remove(&h::ETAG).unwrap(), "invalid");
+        assert!(trailers.is_empty());
+    }
+
+    #[test]
+    fn test_map_and_declaration() {
+        let mut map = HeaderMap::new();
+        map.append("grpc-status", HeaderValue::from_static("0"));
+        map.append("x-checksum", HeaderValue::from_static("a"));
+        map.append("x-checksum", HeaderValue::from_static("b"));
+        let trailers = Trailers::from_map(map.clone()).unwrap();
+        assert_eq!(trailers.len(), 3);
+        assert_eq!(trailers.iter().count(), 3);
+
+        let declaration = trailers.declaration().unwrap();
+        assert_eq!(declaration.to_string(), "grpc-status, x-checksum");
+        assert_eq!(trailers.undeclared(&declaration), None);
+        let partial = Trailer(smallvec![HeaderName::from_static("grpc-status")]);
+        assert_eq!(
+            trailers.undeclared(&partial),
+            Some(&HeaderName::from_static("x-checksum"))
+        );
+        assert_eq!(trailers.as_map(), &map);
+        assert_eq!(trailers.into_map(), map);
+
+        let mut headers = HeaderMap::new();
+        headers.encode(&declaration);
+        assert_eq!(headers.decode::<Trailer>().unwrap(), declaration);
+
+        map.append("transfer-encoding", HeaderValue::from_static("chunked"));
+        assert_eq!(
+            Trailers::from_map(map),
+            Err(ForbiddenTrailer(h::TRANSFER_ENCODING))
+        );
+        let mut trailers = Trailers::new();
+        assert!(trailers
+            .append(h::HOST, HeaderValue::from_static("a"))
+            .is_err());
+    }
+}
@@ -0,0 +1,401 @@
+//! The WebSocket opening handshake, per [RFC 6455].
+//!
+//! [`Handshake::from_request`](struct.Handshake.html#method.from_request)
+//! validates the typed `Upgrade`, `Connection`, `Sec-WebSocket-Key` and
+//! `Sec-WebSocket-Version` headers of an upgrade request, and
+//! [`Handshake::accept`](struct.Handshake.html#method.accept) produces the
+//! typed headers of the `101 Switching Protocols` response, including the
+//! `Sec-WebSocket-Accept` derived from the key.
+//!
+//! ```
+//! # extern crate http;
+//! # extern crate hyperx;
+//! use http::{HeaderMap, Method};
+//! use hyperx::websocket::Handshake;
+//!
+//! let mut request = HeaderMap::new();
+//! request.insert("upgrade", "websocket".parse().unwrap());
+//! request.insert("connection", "keep-alive, Upgrade".parse().unwrap());
+//! request.insert("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ==".parse().unwrap());
+//! request.insert("sec-websocket-version", "13".parse().unwrap());
+//! request.insert("sec-websocket-protocol", "chat, superchat".parse().unwrap());
+//!
+//! let handshake = Handshake::from_request(&Method::GET, &request).unwrap();
+//! assert_eq!(handshake.protocols(), ["chat", "superchat"]);
+//!
+//! let mut response = HeaderMap::new();
+//! handshake.accept(Some("chat")).encode(&mut response);
+//! assert_eq!(response["upgrade"], "websocket");
+//! assert_eq!(response["connection"], "Upgrade");
+//! assert_eq!(response["sec-websocket-accept"], "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+//! assert_eq!(response["sec-websocket-protocol"], "chat");
+//! ```
+//!
+//! Checking the HTTP version, `Host` and `Origin` of the request, and
+//! negotiating `Sec-WebSocket-Extensions`, is left to the caller.
+//!
+//! [RFC 6455]: https://www.rfc-editor.org/rfc/rfc6455
+
+use std::error::Error as StdError;
+use std::fmt;
+
+use http::{HeaderMap, Method, StatusCode};
+use unicase::Ascii;
+
+use header::{
+    Base64, Connection, ConnectionOption, Protocol, ProtocolName, SecWebSocketAccept,
+    SecWebSocketKey, SecWebSocketProtocol, SecWebSocketVersion, TypedHeaders, Upgrade,
+};
+
+/// The WebSocket protocol version of RFC 6455.
+pub const VERSION: u8 = 13;
+
+const GUID: &str = "258EAFA5-E914-47DA-95CA-C5AB0DC85B11";
+
+/// Compute the `Sec-WebSocket-Accept` value for a `Sec-WebSocket-Key`
+/// value, which a client also uses to verify the response.
+///
+/// ```
+/// use hyperx::websocket::accept_key;
+///
+/// assert_eq!(accept_key("dGhlIHNhbXBsZSBub25jZQ=="), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+/// ```
+pub fn accept_key(key: &str) -> String {
+    let mut input = String::with_capacity(key.len() + GUID.len());
+    input.push_str(key);
+    input.push_str(GUID);
+    Base64::STANDARD.encode(sha1(input.as_bytes()))
+}
+
+/// A validated WebSocket upgrade request.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Handshake {
+    key: String,
+    protocols: Vec<String>,
+}
+
+impl Handshake {
+    /// Validate the method and headers of an upgrade request.
+    pub fn from_request(method: &Method, headers: &HeaderMap) -> Result<Handshake, HandshakeError> {
+        if *method != Method::GET {
+            return Err(HandshakeError::Method);
+        }
+        let upgrade = headers
+            .decode::<Upgrade>()
+            .map_err(|_| HandshakeError::Upgrade)?;
+        if !upgrade.iter().any(|p| p.name == ProtocolName::WebSocket) {
+            return Err(HandshakeError::Upgrade);
+        }
+        let connection = headers
+            .decode::<Connection>()
+            .map_err(|_| HandshakeError::Connection)?;
+        let upgrade = Ascii::new("upgrade");
+        let upgrading = connection.iter().any(|o| match *o {
+            ConnectionOption::ConnectionHeader(ref h) => *h == upgrade,
+            _ => false,
+        });
+        if !upgrading {
+            return Err(HandshakeError::Connection);
+        }
+        let version = headers
+            .decode::<SecWebSocketVersion>()
+            .map_err(|_| HandshakeError::Version)?;
+        if version[..] != [VERSION] {
+            return Err(HandshakeError::Version);
+        }
+        let key = headers
+            .decode::<SecWebSocketKey>()
+            .map_err(|_| HandshakeError::Key)?;
+        if key.nonce().is_none() {
+            return Err(HandshakeError::Key);
+        }
+        let protocols = headers
+            .decode::<SecWebSocketProtocol>()
+            .map(|p| p.0.into_vec())
+            .unwrap_or_default();
+        Ok(Handshake {
+            key: key.0.trim().to_owned(),
+            protocols,
+        })
+    }
+
+    /// The `Sec-WebSocket-Key` of the request.
+    pub fn key(&self) -> &str {
+        &self.key
+    }
+
+    /// The subprotocols requested by the client, in order of preference.
+    pub fn protocols(&self) -> &[String] {
+        &self.protocols
+    }
+
+    /// Accept the handshake, with the subprotocol selected by the server,
+    /// if any, which should be one of the requested `protocols`.
+    pub fn accept(&self, protocol: Option<&str>) -> HandshakeResponse {
+        HandshakeResponse {
+            upgrade: Upgrade(smallvec![Protocol::new(ProtocolName::WebSocket, None)]),
+            connection: Connection(smallvec![ConnectionOption::ConnectionHeader(Ascii::new(
+                "Upgrade".to_owned(),
+            ))]),
+            accept: SecWebSocketAccept(accept_key(&self.key)),
+            protocol: protocol.map(|p| SecWebSocketProtocol(smallvec![p.to_owned()])),
+        }
+    }
+}
+
+/// The headers of a `101 Switching Protocols` response, accepting a
+/// WebSocket handshake.
+#[derive(Clone, Debug, PartialEq)]
+pub struct HandshakeResponse {
+    /// The `Upgrade: websocket` header.
+    pub upgrade: Upgrade,
+    /// The `Connection: Upgrade` header.
+    pub connection: Connection,
+    /// The `Sec-WebSocket-Accept` header.
+    pub accept: SecWebSocketAccept,
+    /// The `Sec-WebSocket-Protocol` header, with the selected subprotocol.
+    pub protocol: Option<SecWebSocketProtocol>,
+}
+
+impl HandshakeResponse {
+    /// The status of the response, `101 Switching Protocols`.
+    pub fn status(&self) -> StatusCode {
+        StatusCode::SWITCHING_PROTOCOLS
+    }
+
+    /// Encode the headers into a response `http::HeaderMap`.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        headers.encode(&self.upgrade);
+        headers.encode(&self.connection);
+        headers.encode(&self.accept);
+        if let Some(ref h) = self.protocol {
+            headers.encode(h);
+        }
+    }
+}
+
+/// The reason a WebSocket upgrade request is invalid.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum HandshakeError {
+    /// The method is not `GET`.
+    Method,
+    /// `Upgrade` does not include `websocket`.
+    Upgrade,
+    /// `Connection` does not include `Upgrade`.
+    Connection,
+    /// `Sec-WebSocket-Key` is missing or not a base64-encoded 16-byte
+    /// nonce.
+    Key,
+    /// `Sec-WebSocket-Version` is missing or not supported.
+    Version,
+}
+
+impl HandshakeError {
+    /// The status of the error response: `426 Upgrade Required` for a
+    /// missing `Upgrade` or unsupported version, otherwise
+    /// `400 Bad Request`.
+    pub fn status(&self) -> StatusCode {
+        match *self {
+            HandshakeError::Upgrade | HandshakeError::Version => StatusCode::UPGRADE_REQUIRED,
+            _ => StatusCode::BAD_REQUEST,
+        }
+    }
+
+    /// Encode the headers of the error response into a `http::HeaderMap`:
+    /// `Upgrade: websocket` and `Sec-WebSocket-Version: 13`, advertising
+    /// the supported protocol and version, for a `426` status.
+    pub fn encode(&self, headers: &mut HeaderMap) {
+        if self.status() == StatusCode::UPGRADE_REQUIRED {
+            headers.encode(&Upgrade(smallvec![Protocol::new(
+                ProtocolName::WebSocket,
+                None
+            )]));
+            headers.encode(&SecWebSocketVersion(smallvec![VERSION]));
+        }
+    }
+}
+
+impl fmt::Display for HandshakeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match *self {
+            HandshakeError::Method => "WebSocket upgrade method is not GET",
+            HandshakeError::Upgrade => "Upgrade header does not include websocket",
+            HandshakeError::Connection => "Connection header does not include Upgrade",
+            HandshakeError::Key => "invalid Sec-WebSocket-Key",
+            HandshakeError::Version => "unsupported Sec-WebSocket-Version",
+        })
+    }
+}
+
+impl StdError for HandshakeError {}
+
+// SHA-1 (RFC 3174), as required by the handshake; not for other uses.
+fn sha1(data: &[u8]) -> [u8; 20] {
+    let mut h: [u32; 5] = [
+        0x6745_2301,
+        0xEFCD_AB89,
+        0x98BA_DCFE,
+        0x1032_5476,
+        0xC3D2_E1F0,
+    ];
+    let mut message = data.to_vec();
+    message.push(0x80);
+    while message.len() % 64 != 56 {
+        message.push(0);
+    }
+    message.extend_from_slice(&((data.len() as u64) * 8).to_be_bytes());
+
+    for block in message.chunks(64) {
+        let mut w = [0u32; 80];
+        for (i, word) in block.chunks(4).enumerate() {
+            w[i] = u32::from_be_bytes([word[0], word[1], word[2], word[3]]);
+        }
+        for i in 16..80 {
+            w[i] = (w[i - 3] ^ w[i - 8] ^ w[i - 14] ^ w[i - 16]).rotate_left(1);
+        }
+        let [mut a, mut b, mut c, mut d, mut e] = h;
+        for (i, &wi) in w.iter().enumerate() {
+            let (f, k) = match i {
+                0..=19 => ((b & c) | (!b & d), 0x5A82_7999),
+                20..=39 => (b ^ c ^ d, 0x6ED9_EBA1),
+                40..=59 => ((b & c) | (b & d) | (c & d), 0x8F1B_BCDC),
+                _ => (b ^ c ^ d, 0xCA62_C1D6),
+            };
+            let temp = a
+                .rotate_left(5)
+                .wrapping_add(f)
+                .wrapping_add(e)
+                .wrapping_add(k)
+                .wrapping_add(wi);
+            e = d;
+            d = c;
+            c = b.rotate_left(30);
+            b = a;
+            a = temp;
+        }
+        for (h, v) in h.iter_mut().zip(&[a, b, c, d, e]) {
+            *h = h.wrapping_add(*v);
+        }
+    }
+
+    let mut out = [0u8; 20];
+    for (chunk, v) in out.chunks_mut(4).zip(&h) {
+        chunk.copy_from_slice(&v.to_be_bytes());
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(headers: &[(&'static str, &str)]) -> HeaderMap {
+        let mut map = HeaderMap::new();
+        for &(name, value) in headers {
+            map.append(name, value.parse().unwrap());
+        }
+        map
+    }
+
+    fn valid() -> Vec<(&'static str, &'static str)> {
+        vec![
+            ("upgrade", "websocket"),
+            ("connection", "Upgrade"),
+            ("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+            ("sec-websocket-version", "13"),
+        ]
+    }
+
+    fn without(name: &str) -> HeaderMap {
+        let headers: Vec<_> = valid().into_iter().filter(|h| h.0 != name).collect();
+        request(&headers)
+    }
+
+    #[test]
+    fn test_sha1() {
+        let hex = |b: [u8; 20]| -> String { b.iter().map(|b| format!("{:02x}", b)).collect() };
+        assert_eq!(hex(sha1(b"")), "da39a3ee5e6b4b0d3255bfef95601890afd80709");
+        assert_eq!(
+            hex(sha1(b"abc")),
+            "a9993e364706816aba3e25717850c26c9cd0d89d"
+        );
+        assert_eq!(
+            hex(sha1(&[b'a'; 1000][..])),
+            "291e9a6c66994949b57ba5e650361e98fc36b1ba"
+        );
+    }
+
+    #[test]
+    fn test_handshake() {
+        let headers = request(&valid());
+        let handshake = Handshake::from_request(&Method::GET, &headers).unwrap();
+        assert_eq!(handshake.key(), "dGhlIHNhbXBsZSBub25jZQ==");
+        assert!(handshake.protocols().is_empty());
+
+        let accepted = handshake.accept(None);
+        assert_eq!(accepted.status(), StatusCode::SWITCHING_PROTOCOLS);
+        let mut response = HeaderMap::new();
+        accepted.encode(&mut response);
+        assert_eq!(response.len(), 3);
+        assert_eq!(
+            response["sec-websocket-accept"],
+            "s3pPLMBiTxaQ9kYGzzhZRbK+xOo="
+        );
+    }
+
+    #[test]
+    fn test_case_insensitive() {
+        let headers = request(&[
+            ("upgrade", "WebSocket"),
+            ("connection", "keep-alive"),
+            ("connection", "UPGRADE"),
+            ("sec-websocket-key", "dGhlIHNhbXBsZSBub25jZQ=="),
+            ("sec-websocket-version", "13"),
+        ]);
+        assert!(Handshake::from_request(&Method::GET, &headers).is_ok());
+    }
+
+    #[test]
+    fn test_invalid() {
+        let headers = request(&valid());
+        assert_eq!(
+            Handshake::from_request(&Method::POST, &headers),
+            Err(HandshakeError::Method)
+        );
+        let missing = [
+            ("upgrade", HandshakeError::Upgrade),
+            ("connection", HandshakeError::Connection),
+            ("sec-websocket-key", HandshakeError::Key),
+            ("sec-websocket-version", HandshakeError::Version),
+        ];
+        for (name, error) in missing.iter() {
+            assert_eq!(
+                Handshake::from_request(&Method::GET, &without(name)),
+                Err(error.clone())
+            );
+        }
+
+        let mut headers = request(&valid());
+        headers.insert("sec-websocket-key", "c2hvcnQ=".parse().unwrap());
+        assert_eq!(
+            Handshake::from_request(&Method::GET, &headers),
+            Err(HandshakeError::Key)
+        );
+
+        let mut headers = request(&valid());
+        headers.insert("sec-websocket-version", "8".parse().unwrap());
+        let error = Handshake::from_request(&Method::GET, &headers).unwrap_err();
+        assert_eq!(error, HandshakeError::Version);
+        assert_eq!(error.status(), StatusCode::UPGRADE_REQUIRED);
+        let mut response = HeaderMap::new();
+        error.encode(&mut response);
+        assert_eq!(response["sec-websocket-version"], "13");
+        assert_eq!(response["upgrade"], "websocket");
+
+        let mut response = HeaderMap::new();
+        HandshakeError::Key.encode(&mut response);
+        assert_eq!(HandshakeError::Key.status(), StatusCode::BAD_REQUEST);
+        assert!(response.is_empty());
+    }
+}